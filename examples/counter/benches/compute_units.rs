@@ -0,0 +1,66 @@
+//! Compute units of the counter instructions under mollusk
+//!
+//! Needs the program built first; compare builds by rebuilding with the feature:
+//!
+//! ```bash
+//! cargo build-sbf --manifest-path examples/counter/Cargo.toml
+//! SBF_OUT_DIR=$PWD/target/deploy cargo bench -p counter --bench compute_units
+//! cargo build-sbf --manifest-path examples/counter/Cargo.toml --features safe-borrows
+//! SBF_OUT_DIR=$PWD/target/deploy cargo bench -p counter --bench compute_units
+//! ```
+//!
+//! The bencher writes `target/benches/compute_units.md` with the delta to the previous run.
+
+use counter::{state::COUNTER_SEED, ProgramInstructions, ID};
+use mollusk_svm::Mollusk;
+use mollusk_svm_bencher::MolluskComputeUnitBencher;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+};
+
+fn main() {
+    let program_id = Pubkey::new_from_array(ID);
+    let mollusk = Mollusk::new(&program_id, "counter");
+
+    let owner = Pubkey::new_unique();
+    let (counter, bump) =
+        Pubkey::find_program_address(&[COUNTER_SEED, owner.as_ref()], &program_id);
+
+    // owner, count, bump, cooldown_slots, last_op_slot, then the headroom
+    let mut data = vec![0; counter::state::COUNTER_SPACE];
+    data[..32].copy_from_slice(owner.as_ref());
+    data[32..40].copy_from_slice(&41u64.to_le_bytes());
+    data[40] = bump;
+    let counter_account = Account {
+        lamports: mollusk.sysvars.rent.minimum_balance(data.len()),
+        data,
+        owner: program_id,
+        executable: false,
+        rent_epoch: 0,
+    };
+
+    let instruction = |ix: ProgramInstructions, metas: Vec<AccountMeta>| {
+        let (data, len) = ix.pack();
+        Instruction::new_with_bytes(program_id, &data[..len], metas)
+    };
+    let metas = vec![AccountMeta::new_readonly(owner, true), AccountMeta::new(counter, false)];
+    let accounts = vec![(owner, Account::default()), (counter, counter_account.clone())];
+
+    let increment = instruction(ProgramInstructions::Increment {}, metas.clone());
+    let decrement = instruction(ProgramInstructions::Decrement {}, metas);
+    let get_count = instruction(
+        ProgramInstructions::GetCount {},
+        vec![AccountMeta::new_readonly(counter, false)],
+    );
+    let get_count_accounts = vec![(counter, counter_account)];
+
+    MolluskComputeUnitBencher::new(mollusk)
+        .bench(("increment", &increment, &accounts))
+        .bench(("decrement", &decrement, &accounts))
+        .bench(("get_count", &get_count, &get_count_accounts))
+        .must_pass(true)
+        .out_dir("../../target/benches")
+        .execute();
+}
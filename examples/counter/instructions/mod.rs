@@ -1,7 +0,0 @@
-pub mod initialize_counter;
-pub mod increment;
-pub mod decrement;
-
-pub use initialize_counter::*;
-pub use increment::*;
-pub use decrement::*;
\ No newline at end of file
@@ -8,4 +8,8 @@ define_errors! {
     CounterAlreadyInitialized = 6004,
     CounterNotInitialized = 6005,
     CounterUnderflow = 6006,
+    GlobalCounterKeyIncorrect = 6007,
+    GlobalCounterNotInitialized = 6008,
+    CooldownActive = 6009,
+    CounterAlreadyMigrated = 6010,
 }
\ No newline at end of file
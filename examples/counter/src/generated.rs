@@ -0,0 +1,741 @@
+use shank::ShankInstruction;
+use shank::ShankType;
+use pinocchio::program_error::ProgramError;
+
+/// Added to every declared error code, `errors.base` in jiminy.toml
+pub const ERROR_BASE: u32 = 1000;
+
+/// The `E` a `Custom(code)` from `program_id` stands for
+///
+/// `None` if another program returned it or `E` has no such code.
+pub fn decode_error<E: TryFrom<u32>>(
+    program_id: &pinocchio::pubkey::Pubkey,
+    code: u32,
+) -> Option<E> {
+    if program_id != &crate::ID {
+        return None;
+    }
+    E::try_from(code).ok()
+}
+
+// Generated error enum: CounterProgramError
+#[derive(Clone, Debug, PartialEq, ShankType)]
+#[non_exhaustive]
+pub enum CounterProgramError {
+    InvalidDiscriminator = 7001,
+    Unauthorized = 7002,
+    CounterKeyIncorrect = 7003,
+    CounterAlreadyInitialized = 7004,
+    CounterNotInitialized = 7005,
+    CounterUnderflow = 7006,
+    GlobalCounterKeyIncorrect = 7007,
+    GlobalCounterNotInitialized = 7008,
+    CooldownActive = 7009,
+    CounterAlreadyMigrated = 7010,
+}
+
+impl core::fmt::Display for CounterProgramError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::InvalidDiscriminator => "InvalidDiscriminator",
+            Self::Unauthorized => "Unauthorized",
+            Self::CounterKeyIncorrect => "CounterKeyIncorrect",
+            Self::CounterAlreadyInitialized => "CounterAlreadyInitialized",
+            Self::CounterNotInitialized => "CounterNotInitialized",
+            Self::CounterUnderflow => "CounterUnderflow",
+            Self::GlobalCounterKeyIncorrect => "GlobalCounterKeyIncorrect",
+            Self::GlobalCounterNotInitialized => "GlobalCounterNotInitialized",
+            Self::CooldownActive => "CooldownActive",
+            Self::CounterAlreadyMigrated => "CounterAlreadyMigrated",
+        })
+    }
+}
+
+impl From<CounterProgramError> for ProgramError {
+    fn from(e: CounterProgramError) -> Self {
+        Self::Custom(e as u32)
+    }
+}
+
+impl jiminy::dispatch::HasInvalidDiscriminator for CounterProgramError {
+    const INVALID_DISCRIMINATOR: Self = Self::InvalidDiscriminator;
+}
+
+impl TryFrom<u32> for CounterProgramError {
+    type Error = u32;
+
+    fn try_from(code: u32) -> Result<Self, u32> {
+        match code {
+            7001 => Ok(Self::InvalidDiscriminator),
+            7002 => Ok(Self::Unauthorized),
+            7003 => Ok(Self::CounterKeyIncorrect),
+            7004 => Ok(Self::CounterAlreadyInitialized),
+            7005 => Ok(Self::CounterNotInitialized),
+            7006 => Ok(Self::CounterUnderflow),
+            7007 => Ok(Self::GlobalCounterKeyIncorrect),
+            7008 => Ok(Self::GlobalCounterNotInitialized),
+            7009 => Ok(Self::CooldownActive),
+            7010 => Ok(Self::CounterAlreadyMigrated),
+            _ => Err(code),
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Clone, Debug, PartialEq, ShankInstruction)]
+pub enum ProgramInstructions {
+    #[account(0, signer, writable, name = "owner", desc = "Owner of the counter")]
+    #[account(1, writable, name = "counter", desc = "Counter PDA to be initialized")]
+    #[account(2, name = "system_program", desc = "System program")]
+    InitializeCounter {
+    },
+
+    #[account(0, signer, name = "owner", desc = "Owner of the counter")]
+    #[account(1, writable, name = "counter", desc = "Counter PDA to increment")]
+    Increment {
+    },
+
+    #[account(0, signer, name = "owner", desc = "Owner of the counter")]
+    #[account(1, writable, name = "counter", desc = "Counter PDA to decrement")]
+    Decrement {
+    },
+
+    /// Returns `u64` as return data
+    #[account(0, name = "counter", desc = "Counter PDA to read")]
+    GetCount {
+    },
+
+    #[account(0, signer, writable, name = "owner", desc = "Owner of the counter")]
+    #[account(1, writable, name = "counter", desc = "Counter PDA to be initialized")]
+    #[account(2, writable, name = "global", desc = "Global counter PDA, created on first use")]
+    #[account(3, name = "system_program", desc = "System program")]
+    InitializeCounterV2 {
+    },
+
+    #[account(0, signer, name = "owner", desc = "Owner of the counter")]
+    #[account(1, writable, name = "counter", desc = "Counter PDA to increment")]
+    #[account(2, writable, name = "global", desc = "Global counter PDA")]
+    IncrementV2 {
+    },
+
+    #[account(0, signer, name = "owner", desc = "Owner of the counter")]
+    #[account(1, writable, name = "counter", desc = "Counter PDA to decrement")]
+    #[account(2, writable, name = "global", desc = "Global counter PDA")]
+    DecrementV2 {
+    },
+
+    /// Set the slots that must pass between two increments or decrements
+    #[account(0, signer, name = "owner", desc = "Owner of the counter")]
+    #[account(1, writable, name = "counter", desc = "Counter PDA to rate limit")]
+    SetCooldown {
+        /// Slots between operations, zero to remove the limit
+        cooldown_slots: [u8; 8],
+    },
+
+    /// Grow a counter created before the cooldown to the current layout and headroom
+    #[account(0, signer, writable, name = "owner", desc = "Owner of the counter, pays the extra rent")]
+    #[account(1, writable, name = "counter", desc = "Counter PDA to migrate")]
+    #[account(2, name = "system_program", desc = "System program")]
+    MigrateCounter {
+    },
+
+    /// Create a counter for `owner` with the rent paid by a separate `payer`
+    #[account(0, signer, writable, name = "payer", desc = "Pays the counter's rent, e.g. a custodial service")]
+    #[account(1, signer, name = "owner", desc = "Owner of the counter")]
+    #[account(2, writable, name = "counter", desc = "Counter PDA to be initialized")]
+    #[account(3, name = "system_program", desc = "System program")]
+    InitializeCounterWithPayer {
+    },
+
+    /// Log the count as a CountSnapshot event and return it, anyone can call it
+    /// Returns `u64` as return data
+    #[account(0, name = "counter", desc = "Counter PDA to snapshot")]
+    SnapshotCount {
+    },
+
+    /// Read-only view, only built with the `views` feature (discriminator 200)
+    /// Returns `Counter` as return data
+    #[account(0, name = "counter", desc = "Counter account to read")]
+    ViewCounter {
+    } = 200,
+
+    /// Read-only view, only built with the `views` feature (discriminator 201)
+    /// Returns `GlobalCounter` as return data
+    #[account(0, name = "global_counter", desc = "GlobalCounter account to read")]
+    ViewGlobalCounter {
+    } = 201,
+
+}
+
+/// Longest instruction data `ProgramInstructions::pack` writes, discriminator included
+pub const MAX_INSTRUCTION_DATA_LEN: usize = jiminy::dispatch::max_len(&[
+    1,
+    1,
+    1,
+    1,
+    1,
+    1,
+    1,
+    1 + crate::instructions::SetCooldownData::LEN,
+    1,
+    1,
+    1,
+    1,
+    1,
+]);
+
+impl ProgramInstructions {
+    /// Instruction data for this instruction: the discriminator, the data struct's bytes (the borsh encoded schema for a `data_parser`), then any trailing bytes
+    ///
+    /// Panics if the trailing bytes are longer than the instruction's `max_len`.
+    pub fn pack(&self) -> ([u8; MAX_INSTRUCTION_DATA_LEN], usize) {
+        let mut buf = [0u8; MAX_INSTRUCTION_DATA_LEN];
+        let len = match self {
+            Self::InitializeCounter {} => {
+                buf[0] = 0;
+                1
+            }
+            Self::Increment {} => {
+                buf[0] = 1;
+                1
+            }
+            Self::Decrement {} => {
+                buf[0] = 2;
+                1
+            }
+            Self::GetCount {} => {
+                buf[0] = 3;
+                1
+            }
+            Self::InitializeCounterV2 {} => {
+                buf[0] = 4;
+                1
+            }
+            Self::IncrementV2 {} => {
+                buf[0] = 5;
+                1
+            }
+            Self::DecrementV2 {} => {
+                buf[0] = 6;
+                1
+            }
+            Self::SetCooldown { cooldown_slots } => {
+                buf[0] = 7;
+                let data = crate::instructions::SetCooldownData {
+                    cooldown_slots: *cooldown_slots,
+                };
+                let end = 1 + crate::instructions::SetCooldownData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                end
+            }
+            Self::MigrateCounter {} => {
+                buf[0] = 8;
+                1
+            }
+            Self::InitializeCounterWithPayer {} => {
+                buf[0] = 9;
+                1
+            }
+            Self::SnapshotCount {} => {
+                buf[0] = 10;
+                1
+            }
+            Self::ViewCounter {} => {
+                buf[0] = 200;
+                1
+            }
+            Self::ViewGlobalCounter {} => {
+                buf[0] = 201;
+                1
+            }
+        };
+        (buf, len)
+    }
+
+    /// Parse instruction data the way the program does, deprecated discriminators included
+    pub fn unpack(data: &[u8]) -> Result<Self, pinocchio::program_error::ProgramError> {
+        let (&discriminator, data) = data.split_first().ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+        Ok(match discriminator {
+            0 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::InitializeCounter {
+                }
+            }
+            1 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::Increment {
+                }
+            }
+            2 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::Decrement {
+                }
+            }
+            3 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::GetCount {
+                }
+            }
+            4 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::InitializeCounterV2 {
+                }
+            }
+            5 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::IncrementV2 {
+                }
+            }
+            6 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::DecrementV2 {
+                }
+            }
+            7 => {
+                let fixed = data.get(..crate::instructions::SetCooldownData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::SetCooldownData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::SetCooldownData::LEN..];
+                if !tail.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::SetCooldown {
+                    cooldown_slots: parsed.cooldown_slots,
+                }
+            }
+            8 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::MigrateCounter {
+                }
+            }
+            9 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::InitializeCounterWithPayer {
+                }
+            }
+            10 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::SnapshotCount {
+                }
+            }
+            200 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::ViewCounter {
+                }
+            }
+            201 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::ViewGlobalCounter {
+                }
+            }
+            _ => return Err(pinocchio::program_error::ProgramError::InvalidInstructionData),
+        })
+    }
+}
+
+// ShankAccount definitions for state structs
+// These are generated for IDL compatibility
+/// Account size: 57 bytes
+/// Allocated with 96 bytes of headroom, 153 bytes
+#[repr(C)]
+#[derive(Clone, shank::ShankAccount)]
+pub struct Counter {
+    pub owner: pinocchio::pubkey::Pubkey,
+    pub count: [u8; 8],
+    pub bump: u8,
+    pub cooldown_slots: [u8; 8],
+    pub last_op_slot: [u8; 8],
+}
+
+impl Counter {
+    /// Data bytes of the account, what `create_pda!` allocates
+    pub const SIZE: usize = 57;
+    /// Rent-exempt minimum at the default rent
+    pub const RENT_EXEMPT_LAMPORTS_AT_DEFAULT_RATE: u64 =
+        Self::rent_exempt_lamports(jiminy::rent::DEFAULT_LAMPORTS_PER_BYTE_YEAR);
+
+    /// Bytes to allocate when creating the account
+    pub const fn space() -> usize {
+        Self::SIZE
+    }
+
+    /// Rent-exempt minimum at `lamports_per_byte_year`
+    pub const fn rent_exempt_lamports(lamports_per_byte_year: u64) -> u64 {
+        jiminy::rent::exempt_lamports(Self::space(), lamports_per_byte_year)
+    }
+}
+
+const _: () = assert!(
+    Counter::space() == crate::state::Counter::LEN,
+    "Counter size differs from the program"
+);
+
+/// Account size: 17 bytes
+#[repr(C)]
+#[derive(Clone, shank::ShankAccount)]
+pub struct GlobalCounter {
+    pub total_counters: [u8; 8],
+    pub total_increments: [u8; 8],
+    pub bump: u8,
+}
+
+impl GlobalCounter {
+    /// Data bytes of the account, what `create_pda!` allocates
+    pub const SIZE: usize = 17;
+    /// Rent-exempt minimum at the default rent
+    pub const RENT_EXEMPT_LAMPORTS_AT_DEFAULT_RATE: u64 =
+        Self::rent_exempt_lamports(jiminy::rent::DEFAULT_LAMPORTS_PER_BYTE_YEAR);
+
+    /// Bytes to allocate when creating the account
+    pub const fn space() -> usize {
+        Self::SIZE
+    }
+
+    /// Rent-exempt minimum at `lamports_per_byte_year`
+    pub const fn rent_exempt_lamports(lamports_per_byte_year: u64) -> u64 {
+        jiminy::rent::exempt_lamports(Self::space(), lamports_per_byte_year)
+    }
+}
+
+const _: () = assert!(
+    GlobalCounter::space() == crate::state::GlobalCounter::LEN,
+    "GlobalCounter size differs from the program"
+);
+
+pub type InstructionHandler = fn(
+    &[pinocchio::account_info::AccountInfo],
+    &[u8],
+) -> pinocchio::ProgramResult;
+
+// Read-only view of `crate::state::Counter`, returns the account data as return data
+#[cfg(feature = "views")]
+pub mod view_counter {
+    jiminy::define_instruction_with_metadata!(
+        discriminant: 200,
+        ViewCounter,
+        accounts: {
+            counter: program, desc: "Counter account to read",
+        },
+        data: {},
+        returns: crate::state::Counter,
+        process: {
+            // Checked shared borrow, the account is never written
+            let data = counter.try_borrow_data()?;
+            bytemuck::try_from_bytes::<crate::state::Counter>(&data)
+                .copied()
+                .map_err(|_| ProgramError::InvalidAccountData)
+        }
+    );
+}
+
+// Read-only view of `crate::state::GlobalCounter`, returns the account data as return data
+#[cfg(feature = "views")]
+pub mod view_global_counter {
+    jiminy::define_instruction_with_metadata!(
+        discriminant: 201,
+        ViewGlobalCounter,
+        accounts: {
+            global_counter: program, desc: "GlobalCounter account to read",
+        },
+        data: {},
+        returns: crate::state::GlobalCounter,
+        process: {
+            // Checked shared borrow, the account is never written
+            let data = global_counter.try_borrow_data()?;
+            bytemuck::try_from_bytes::<crate::state::GlobalCounter>(&data)
+                .copied()
+                .map_err(|_| ProgramError::InvalidAccountData)
+        }
+    );
+}
+
+fn handle_initialize_counter(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::InitializeCounterInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_increment(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::IncrementInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_decrement(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::DecrementInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_get_count(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::GetCountInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_initialize_counter_v2(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::InitializeCounterV2Instruction::try_from((accounts, data))?.process()
+}
+
+fn handle_increment_v2(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::IncrementV2Instruction::try_from((accounts, data))?.process()
+}
+
+fn handle_decrement_v2(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::DecrementV2Instruction::try_from((accounts, data))?.process()
+}
+
+fn handle_set_cooldown(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::SetCooldownInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_migrate_counter(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::MigrateCounterInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_initialize_counter_with_payer(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::InitializeCounterWithPayerInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_snapshot_count(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::SnapshotCountInstruction::try_from((accounts, data))?.process()
+}
+
+#[cfg(feature = "views")]
+fn handle_view_counter(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    view_counter::ViewCounterInstruction::try_from((accounts, data))?.process()
+}
+
+#[cfg(feature = "views")]
+fn handle_view_global_counter(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    view_global_counter::ViewGlobalCounterInstruction::try_from((accounts, data))?.process()
+}
+
+/// Instructions of the program, read-only views not counted
+pub const INSTRUCTION_COUNT: usize = 11;
+
+/// `(discriminator, name)` of each instruction, by discriminator
+pub const INSTRUCTIONS: [(u8, &str); INSTRUCTION_COUNT] = [
+    (0, "InitializeCounter"),
+    (1, "Increment"),
+    (2, "Decrement"),
+    (3, "GetCount"),
+    (4, "InitializeCounterV2"),
+    (5, "IncrementV2"),
+    (6, "DecrementV2"),
+    (7, "SetCooldown"),
+    (8, "MigrateCounter"),
+    (9, "InitializeCounterWithPayer"),
+    (10, "SnapshotCount"),
+];
+
+/// Instruction name of a discriminator, deprecated aliases included
+pub const fn instruction_name(discriminator: u8) -> Option<&'static str> {
+    match discriminator {
+        0 => Some("InitializeCounter"),
+        1 => Some("Increment"),
+        2 => Some("Decrement"),
+        3 => Some("GetCount"),
+        4 => Some("InitializeCounterV2"),
+        5 => Some("IncrementV2"),
+        6 => Some("DecrementV2"),
+        7 => Some("SetCooldown"),
+        8 => Some("MigrateCounter"),
+        9 => Some("InitializeCounterWithPayer"),
+        10 => Some("SnapshotCount"),
+        #[cfg(feature = "views")]
+        200 => Some("ViewCounter"),
+        #[cfg(feature = "views")]
+        201 => Some("ViewGlobalCounter"),
+        _ => None,
+    }
+}
+
+pub const DISPATCH: [Option<InstructionHandler>; 256] = {
+    let mut table: [Option<InstructionHandler>; 256] = [None; 256];
+    table[0] = Some(handle_initialize_counter);
+    table[1] = Some(handle_increment);
+    table[2] = Some(handle_decrement);
+    table[3] = Some(handle_get_count);
+    table[4] = Some(handle_initialize_counter_v2);
+    table[5] = Some(handle_increment_v2);
+    table[6] = Some(handle_decrement_v2);
+    table[7] = Some(handle_set_cooldown);
+    table[8] = Some(handle_migrate_counter);
+    table[9] = Some(handle_initialize_counter_with_payer);
+    table[10] = Some(handle_snapshot_count);
+    #[cfg(feature = "views")]
+    { table[200] = Some(handle_view_counter); }
+    #[cfg(feature = "views")]
+    { table[201] = Some(handle_view_global_counter); }
+    table
+};
+
+#[cfg(not(feature = "match-dispatch"))]
+pub fn process_instruction(
+    program_id: &pinocchio::pubkey::Pubkey,
+    accounts: &[pinocchio::account_info::AccountInfo],
+    instruction_data: &[u8],
+) -> pinocchio::ProgramResult {
+    if !jiminy::perf::keys_eq(program_id, &crate::ID) {
+        return Err(pinocchio::program_error::ProgramError::IncorrectProgramId);
+    }
+
+    #[cfg(feature = "host-stubs")]
+    if let Some(discriminator) = instruction_data.first() {
+        jiminy::testing::record_coverage(*discriminator);
+    }
+
+    match instruction_data.split_first() {
+        Some((discriminator, data)) => match DISPATCH[*discriminator as usize] {
+            Some(handler) => handler(accounts, data),
+            None => Err(<CounterProgramError as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into()),
+        },
+        None => Err(<CounterProgramError as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into()),
+    }
+}
+
+#[cfg(feature = "match-dispatch")]
+pub fn process_instruction(
+    program_id: &pinocchio::pubkey::Pubkey,
+    accounts: &[pinocchio::account_info::AccountInfo],
+    instruction_data: &[u8],
+) -> pinocchio::ProgramResult {
+    if !jiminy::perf::keys_eq(program_id, &crate::ID) {
+        return Err(pinocchio::program_error::ProgramError::IncorrectProgramId);
+    }
+
+    #[cfg(feature = "host-stubs")]
+    if let Some(discriminator) = instruction_data.first() {
+        jiminy::testing::record_coverage(*discriminator);
+    }
+
+    match instruction_data.first() {
+        Some(0) => {
+            crate::instructions::InitializeCounterInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(1) => {
+            crate::instructions::IncrementInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(2) => {
+            crate::instructions::DecrementInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(3) => {
+            crate::instructions::GetCountInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(4) => {
+            crate::instructions::InitializeCounterV2Instruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(5) => {
+            crate::instructions::IncrementV2Instruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(6) => {
+            crate::instructions::DecrementV2Instruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(7) => {
+            crate::instructions::SetCooldownInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(8) => {
+            crate::instructions::MigrateCounterInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(9) => {
+            crate::instructions::InitializeCounterWithPayerInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(10) => {
+            crate::instructions::SnapshotCountInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        #[cfg(feature = "views")]
+        Some(200) => {
+            view_counter::ViewCounterInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        #[cfg(feature = "views")]
+        Some(201) => {
+            view_global_counter::ViewGlobalCounterInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        _ => Err(<CounterProgramError as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into()),
+    }
+}
+
+// Layout regression tests, `[layout] golden` in jiminy.toml
+#[cfg(test)]
+mod layout_tests {
+    #[test]
+    fn counter_layout() {
+        assert_eq!(core::mem::size_of::<crate::state::Counter>(), 57);
+        assert_eq!(core::mem::offset_of!(crate::state::Counter, owner), 0);
+        assert_eq!(core::mem::offset_of!(crate::state::Counter, count), 32);
+        assert_eq!(core::mem::offset_of!(crate::state::Counter, bump), 40);
+        assert_eq!(core::mem::offset_of!(crate::state::Counter, cooldown_slots), 41);
+        assert_eq!(core::mem::offset_of!(crate::state::Counter, last_op_slot), 49);
+        jiminy::testing::check_layout::<crate::state::Counter>(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/layouts"),
+            2,
+        );
+    }
+
+    #[test]
+    fn global_counter_layout() {
+        assert_eq!(core::mem::size_of::<crate::state::GlobalCounter>(), 17);
+        assert_eq!(core::mem::offset_of!(crate::state::GlobalCounter, total_counters), 0);
+        assert_eq!(core::mem::offset_of!(crate::state::GlobalCounter, total_increments), 8);
+        assert_eq!(core::mem::offset_of!(crate::state::GlobalCounter, bump), 16);
+        jiminy::testing::check_layout::<crate::state::GlobalCounter>(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/layouts"),
+            1,
+        );
+    }
+}
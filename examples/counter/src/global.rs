@@ -0,0 +1,53 @@
+use crate::{
+    state::{GlobalCounter, GlobalCounterInit, GLOBAL_SEED},
+    CounterProgramError,
+};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+/// Create the global counter PDA on first use, paid by `payer`
+///
+/// Transactions writing the same account are serialized, so the second of two
+/// racing first calls sees the account created by the first and only checks it.
+pub fn init_global_if_needed(payer: &AccountInfo, global: &AccountInfo) -> ProgramResult {
+    if global.is_owned_by(&crate::ID) {
+        return check_global(global);
+    }
+
+    let (global_pda, global_bump) =
+        jiminy::syscalls::find_program_address(&[GLOBAL_SEED], &crate::ID);
+    if global.key() != &global_pda {
+        return Err(CounterProgramError::GlobalCounterKeyIncorrect.into());
+    }
+
+    // Also succeeds when someone sent lamports to the address first
+    create_pda!(
+        from: payer,
+        to: global,
+        space: GlobalCounter::LEN,
+        seeds: [GLOBAL_SEED],
+        bump: global_bump
+    );
+
+    load_mut!(global, GlobalCounter).init(GlobalCounterInit {
+        total_counters: 0u64.to_le_bytes(),
+        total_increments: 0u64.to_le_bytes(),
+        bump: global_bump,
+    });
+    Ok(())
+}
+
+/// Fail unless `global` is the initialized global counter PDA
+pub fn check_global(global: &AccountInfo) -> ProgramResult {
+    if !global.is_owned_by(&crate::ID) {
+        return Err(CounterProgramError::GlobalCounterNotInitialized.into());
+    }
+    let global_bump = read_state!(global, GlobalCounter, |g| g.bump);
+    assert_pda!(global, seeds: [GLOBAL_SEED], bump: global_bump,
+        error: CounterProgramError::GlobalCounterKeyIncorrect);
+    Ok(())
+}
+
+/// Add `delta` to one of the global totals, saturating at zero and `u64::MAX`
+pub fn add_to_total(total: &mut [u8; 8], delta: i64) {
+    *total = u64::from_le_bytes(*total).saturating_add_signed(delta).to_le_bytes();
+}
@@ -0,0 +1,27 @@
+use super::decrement::decrement_counter;
+use crate::{
+    global::{add_to_total, check_global},
+    state::GlobalCounter,
+};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 6,
+    DecrementV2,
+    accounts: {
+        owner: signer, desc: "Owner of the counter",
+        counter: program => writable, desc: "Counter PDA to decrement",
+        global: program => writable, desc: "Global counter PDA",
+    },
+    data: {},
+    process: {
+        check_global(global)?;
+        decrement_counter(owner, counter)?;
+
+        with_state!(global, GlobalCounter, |global_state| {
+            add_to_total(&mut global_state.total_increments, -1);
+        });
+
+        Ok(())
+    }
+);
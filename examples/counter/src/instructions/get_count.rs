@@ -0,0 +1,16 @@
+use crate::state::Counter;
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 3,
+    GetCount,
+    accounts: {
+        counter: program, desc: "Counter PDA to read",
+    },
+    data: {},
+    returns: u64,
+    process: {
+        // Callers read the count back from the return data
+        Ok(read_state!(counter, Counter, prefix, |c| u64::from_le_bytes(c.count)))
+    }
+);
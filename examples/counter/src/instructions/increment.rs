@@ -0,0 +1,46 @@
+use crate::{
+    state::{Counter, COUNTER_SEED},
+    CounterProgramError,
+};
+use jiminy::{define_instruction_with_metadata, syscalls};
+
+define_instruction_with_metadata!(
+    discriminant: 1,
+    Increment,
+    accounts: {
+        owner: signer, desc: "Owner of the counter",
+        counter: program => writable, desc: "Counter PDA to increment",
+    },
+    data: {},
+    process: {
+        increment_counter(owner, counter)
+    }
+);
+
+/// Owner check, PDA check and the increment itself, shared with `IncrementV2`
+pub(crate) fn increment_counter(owner: &AccountInfo, counter: &AccountInfo) -> ProgramResult {
+    // Load the counter state
+    let counter_state = load_prefix_mut!(counter, Counter);
+
+    // Verify the owner
+    if counter_state.owner != *owner.key() {
+        return Err(CounterProgramError::Unauthorized.into());
+    }
+
+    // Validate the PDA
+    assert_pda!(counter,
+        seeds: [COUNTER_SEED, owner.key().as_ref()],
+        bump: counter_state.bump,
+        error: CounterProgramError::CounterKeyIncorrect
+    );
+
+    // Rate limit by the owner's cooldown
+    counter_state.start_op(syscalls::clock()?.slot)?;
+
+    // Increment the counter
+    let current_count = u64::from_le_bytes(counter_state.count);
+    let new_count = current_count.saturating_add(1);
+    counter_state.count = new_count.to_le_bytes();
+
+    Ok(())
+}
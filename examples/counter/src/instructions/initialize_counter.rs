@@ -0,0 +1,61 @@
+use crate::{
+    state::{Counter, CounterInit, COUNTER_SEED, COUNTER_SPACE},
+    CounterProgramError,
+};
+use jiminy::{define_instruction_with_metadata, syscalls};
+
+define_instruction_with_metadata!(
+    discriminant: 0,
+    InitializeCounter,
+    accounts: {
+        owner: signer => writable, desc: "Owner of the counter",
+        counter: uninitialized, desc: "Counter PDA to be initialized",
+        system_program,
+    },
+    data: {},
+    process: {
+        initialize_counter(owner, owner, counter)
+    }
+);
+
+/// Create and initialize the counter PDA, shared with `InitializeCounterV2` and
+/// `InitializeCounterWithPayer`; `payer` funds the rent and may be `owner`
+pub(crate) fn initialize_counter(
+    payer: &AccountInfo,
+    owner: &AccountInfo,
+    counter: &AccountInfo,
+) -> ProgramResult {
+    // Derive the counter PDA
+    let (counter_pda, counter_bump) = syscalls::find_program_address(
+        &[
+            COUNTER_SEED,
+            owner.key().as_ref(),
+        ],
+        &crate::ID,
+    );
+
+    // Verify the counter PDA matches
+    if counter.key().ne(&counter_pda) {
+        return Err(CounterProgramError::CounterKeyIncorrect.into());
+    }
+
+    // Create the counter PDA, with room for the fields it will grow
+    create_pda!(
+        payer: payer,
+        to: counter,
+        space: COUNTER_SPACE,
+        seeds: [COUNTER_SEED, owner.key().as_ref()],
+        bump: counter_bump
+    );
+
+    // Initialize the counter state
+    load_prefix_mut!(counter, Counter).init(CounterInit {
+        owner: owner.key().into(),
+        count: 0u64.to_le_bytes(),
+        bump: counter_bump,
+        cooldown_slots: [0; 8],
+        last_op_slot: [0; 8],
+    });
+
+    Ok(())
+}
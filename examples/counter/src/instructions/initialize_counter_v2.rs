@@ -0,0 +1,30 @@
+use super::initialize_counter::initialize_counter;
+use crate::{
+    global::{add_to_total, init_global_if_needed},
+    state::GlobalCounter,
+};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 4,
+    InitializeCounterV2,
+    // `system_program` is used by the `create_pda!` in `initialize_counter`
+    // jiminy: allow(unused_accounts)
+    accounts: {
+        owner: signer => writable, desc: "Owner of the counter",
+        counter: uninitialized, desc: "Counter PDA to be initialized",
+        global: any => writable, desc: "Global counter PDA, created on first use",
+        system_program,
+    },
+    data: {},
+    process: {
+        init_global_if_needed(owner, global)?;
+        initialize_counter(owner, owner, counter)?;
+
+        with_state!(global, GlobalCounter, |global_state| {
+            add_to_total(&mut global_state.total_counters, 1);
+        });
+
+        Ok(())
+    }
+);
@@ -0,0 +1,20 @@
+use super::initialize_counter::initialize_counter;
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 9,
+    InitializeCounterWithPayer,
+    desc: "Create a counter for `owner` with the rent paid by a separate `payer`",
+    // `system_program` is used by the `create_pda!` in `initialize_counter`
+    // jiminy: allow(unused_accounts)
+    accounts: {
+        payer: signer => writable, desc: "Pays the counter's rent, e.g. a custodial service",
+        owner: signer, desc: "Owner of the counter",
+        counter: uninitialized, desc: "Counter PDA to be initialized",
+        system_program,
+    },
+    data: {},
+    process: {
+        initialize_counter(payer, owner, counter)
+    }
+);
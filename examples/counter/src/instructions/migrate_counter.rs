@@ -0,0 +1,50 @@
+use crate::{
+    state::{Counter, COUNTER_SEED, COUNTER_SPACE, COUNTER_V1_LEN},
+    CounterProgramError,
+};
+use jiminy::{define_instruction_with_metadata, syscalls};
+
+define_instruction_with_metadata!(
+    discriminant: 8,
+    MigrateCounter,
+    desc: "Grow a counter created before the cooldown to the current layout and headroom",
+    accounts: {
+        owner: signer => writable, desc: "Owner of the counter, pays the extra rent",
+        counter: program => writable, desc: "Counter PDA to migrate",
+        system_program,
+    },
+    data: {},
+    process: {
+        // Counters created before the cooldown are too short to load as `Counter`
+        if counter.data_len() != COUNTER_V1_LEN {
+            return Err(CounterProgramError::CounterAlreadyMigrated.into());
+        }
+        counter.resize(COUNTER_SPACE)?;
+
+        {
+            let counter_state = load_prefix_mut!(counter, Counter);
+            if counter_state.owner != *owner.key() {
+                return Err(CounterProgramError::Unauthorized.into());
+            }
+            assert_pda!(counter,
+                seeds: [COUNTER_SEED, owner.key().as_ref()],
+                bump: counter_state.bump,
+                error: CounterProgramError::CounterKeyIncorrect
+            );
+
+            // No cooldown until the owner sets one
+            counter_state.cooldown_slots = [0; 8];
+            counter_state.last_op_slot = [0; 8];
+        }
+
+        // Top the counter up to the rent-exempt minimum of its new size
+        let missing = syscalls::rent()?
+            .minimum_balance(COUNTER_SPACE)
+            .saturating_sub(counter.lamports());
+        if missing > 0 {
+            transfer_sol!(owner, counter, missing);
+        }
+
+        Ok(())
+    }
+);
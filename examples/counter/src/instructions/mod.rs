@@ -0,0 +1,23 @@
+pub mod initialize_counter;
+pub mod increment;
+pub mod decrement;
+pub mod get_count;
+pub mod initialize_counter_v2;
+pub mod increment_v2;
+pub mod decrement_v2;
+pub mod set_cooldown;
+pub mod migrate_counter;
+pub mod initialize_counter_with_payer;
+pub mod snapshot_count;
+
+pub use initialize_counter::*;
+pub use increment::*;
+pub use decrement::*;
+pub use get_count::*;
+pub use initialize_counter_v2::*;
+pub use increment_v2::*;
+pub use decrement_v2::*;
+pub use set_cooldown::*;
+pub use migrate_counter::*;
+pub use initialize_counter_with_payer::*;
+pub use snapshot_count::*;
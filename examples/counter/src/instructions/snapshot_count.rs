@@ -0,0 +1,26 @@
+use crate::state::{CountSnapshot, Counter};
+use jiminy::{define_instruction_with_metadata, syscalls};
+
+define_instruction_with_metadata!(
+    discriminant: 10,
+    SnapshotCount,
+    desc: "Log the count as a CountSnapshot event and return it, anyone can call it",
+    accounts: {
+        counter: program, desc: "Counter PDA to snapshot",
+    },
+    data: {},
+    returns: u64,
+    process: {
+        // `program` and the loader reject uninitialized and closed counters, the
+        // headroom after `Counter` is ignored
+        let counter_state = read_state!(counter, Counter, prefix, |c| *c);
+        emit_event!(CountSnapshot {
+            owner: counter_state.owner,
+            count: counter_state.count,
+            slot: syscalls::clock()?.slot.to_le_bytes(),
+        });
+
+        // CPI callers read the count back from the return data
+        Ok(u64::from_le_bytes(counter_state.count))
+    }
+);
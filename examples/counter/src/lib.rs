@@ -1,10 +1,14 @@
 #![no_std]
 #![allow(unexpected_cfgs)]
 
-use pinocchio::entrypoint;
-
 #[macro_use]
-pub mod jiminy;
+extern crate jiminy;
+
+// Without the entrypoint nothing sets up the panic handler, std's stands in
+#[cfg(feature = "no-entrypoint")]
+extern crate std;
+
+pub mod global;
 pub mod instructions;
 pub mod state;
 
@@ -18,4 +22,5 @@ pinocchio_pubkey::declare_id!("Cntrt7BXEtNAnSo9ecGs9n9KkHGDF73Shr3xqFvsvQTJ");
 pub mod generated;
 pub use generated::*;
 
-entrypoint!(process_instruction);
+#[cfg(not(feature = "no-entrypoint"))]
+jiminy_entrypoint!(process_instruction);
@@ -0,0 +1,60 @@
+use crate::CounterProgramError;
+use jiminy::{define_events, define_state, space_with_headroom, PubkeyBytes};
+use pinocchio::ProgramResult;
+
+// Seeds
+pub const COUNTER_SEED: &[u8; 7] = b"counter";
+pub const GLOBAL_SEED: &[u8; 6] = b"global";
+
+define_state! {
+    pub struct Counter {
+        pub owner: PubkeyBytes,
+        pub count: [u8; 8],
+        pub bump: u8,
+        // Slots that must pass between two increments or decrements, zero for no limit
+        pub cooldown_slots: [u8; 8],
+        // Slot of the last increment or decrement
+        pub last_op_slot: [u8; 8],
+    }
+
+    // Singleton PDA, only the v2 instructions keep it up to date
+    pub struct GlobalCounter {
+        pub total_counters: [u8; 8],
+        // Net increments, decrements count against it
+        pub total_increments: [u8; 8],
+        pub bump: u8,
+    }
+}
+
+define_events! {
+    /// Logged by SnapshotCount, see `emit_event!`
+    pub struct CountSnapshot {
+        pub owner: PubkeyBytes,
+        pub count: [u8; 8],
+        // Slot the snapshot was taken at
+        pub slot: [u8; 8],
+    }
+}
+
+// Size of counters created before the cooldown, see `MigrateCounter`
+pub const COUNTER_V1_LEN: usize = Counter::LEN - 8 - 8;
+
+// Room for a delegate key and a history of the last eight counts
+pub const COUNTER_HEADROOM: usize = 96;
+
+// What counters are created with; they load with `load_prefix!`, so readers of
+// the current layout ignore the headroom
+pub const COUNTER_SPACE: usize = space_with_headroom!(Counter, COUNTER_HEADROOM);
+
+impl Counter {
+    /// Fail with `CooldownActive` unless `cooldown_slots` have passed since the last
+    /// increment or decrement, then record `slot` as the last one
+    pub fn start_op(&mut self, slot: u64) -> ProgramResult {
+        let elapsed = slot.saturating_sub(u64::from_le_bytes(self.last_op_slot));
+        if elapsed < u64::from_le_bytes(self.cooldown_slots) {
+            return Err(CounterProgramError::CooldownActive.into());
+        }
+        self.last_op_slot = slot.to_le_bytes();
+        Ok(())
+    }
+}
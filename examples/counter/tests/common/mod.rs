@@ -0,0 +1,66 @@
+//! Accounts for running the counter program on the host with `jiminy::testing::process`
+
+#![allow(dead_code)]
+
+use bytemuck::Zeroable;
+use counter::state::{Counter, COUNTER_SEED, COUNTER_SPACE};
+use counter::ProgramInstructions;
+use jiminy::testing::{process, stubs, HostAccount};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+
+pub const LAMPORTS: u64 = 1_000_000_000;
+pub const COUNTER_BUMP: u8 = 254;
+pub const OWNER: Pubkey = [1; 32];
+
+pub fn counter_key(owner: &Pubkey) -> Pubkey {
+    let seeds = [COUNTER_SEED.as_ref(), owner.as_ref()];
+    pinocchio_pubkey::derive_address(&seeds, Some(COUNTER_BUMP), &counter::ID)
+}
+
+/// `OWNER`'s counter at `count`, rate limited to one operation per `cooldown_slots`
+pub fn counter_state(count: u64, cooldown_slots: u64) -> Counter {
+    let mut counter = Counter::zeroed();
+    counter.owner = OWNER.into();
+    counter.count = count.to_le_bytes();
+    counter.bump = COUNTER_BUMP;
+    counter.cooldown_slots = cooldown_slots.to_le_bytes();
+    counter
+}
+
+/// The counter account as created, with its headroom after `state`
+pub fn counter_account(state: &Counter) -> HostAccount {
+    let mut data = vec![0; COUNTER_SPACE];
+    data[..Counter::LEN].copy_from_slice(bytemuck::bytes_of(state));
+    HostAccount::new(counter_key(&OWNER), counter::ID, LAMPORTS, &data).writable()
+}
+
+pub fn wallet(key: Pubkey) -> HostAccount {
+    HostAccount::new(key, pinocchio_system::ID, LAMPORTS, &[])
+        .signer()
+        .writable()
+}
+
+/// `OWNER` and its counter, the accounts Increment and Decrement take
+pub fn owner_accounts(state: &Counter) -> Vec<HostAccount> {
+    vec![wallet(OWNER), counter_account(state)]
+}
+
+pub fn counter_of(account: &HostAccount) -> Counter {
+    bytemuck::pod_read_unaligned(&account.data[..Counter::LEN])
+}
+
+pub fn pack(instruction: ProgramInstructions) -> Vec<u8> {
+    let (data, len) = instruction.pack();
+    data[..len].to_vec()
+}
+
+/// Run one instruction against `accounts`, keeping the stubs' clock and records
+pub fn run(accounts: &mut [HostAccount], data: &[u8]) -> Result<(), ProgramError> {
+    process(counter::process_instruction, &counter::ID, accounts, data)
+}
+
+/// [`run`] from a fresh set of stubs
+pub fn run_fresh(accounts: &mut [HostAccount], data: &[u8]) -> Result<(), ProgramError> {
+    stubs::reset();
+    run(accounts, data)
+}
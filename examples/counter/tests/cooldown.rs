@@ -0,0 +1,105 @@
+//! The owner's cooldown between two increments or decrements, set with SetCooldown
+
+mod common;
+
+use common::*;
+use counter::{CounterProgramError, ProgramInstructions};
+use jiminy::testing::{stubs, HostAccount};
+use pinocchio::program_error::ProgramError;
+use pinocchio::sysvars::clock::Clock;
+
+fn at_slot(
+    accounts: &mut [HostAccount],
+    slot: u64,
+    instruction: ProgramInstructions,
+) -> Result<(), ProgramError> {
+    stubs::set_clock(Clock {
+        slot,
+        ..stubs::clock()
+    });
+    run(accounts, &pack(instruction))
+}
+
+fn cooldown_active() -> Result<(), ProgramError> {
+    Err(CounterProgramError::CooldownActive.into())
+}
+
+#[test]
+fn operations_wait_for_the_cooldown() {
+    stubs::reset();
+    let mut accounts = owner_accounts(&counter_state(5, 10));
+    at_slot(&mut accounts, 100, ProgramInstructions::Increment {}).unwrap();
+    assert_eq!(
+        u64::from_le_bytes(counter_of(&accounts[1]).last_op_slot),
+        100
+    );
+
+    // Decrements share the cooldown with increments
+    assert_eq!(
+        at_slot(&mut accounts, 105, ProgramInstructions::Decrement {}),
+        cooldown_active()
+    );
+    assert_eq!(
+        at_slot(&mut accounts, 109, ProgramInstructions::Increment {}),
+        cooldown_active()
+    );
+    assert_eq!(u64::from_le_bytes(counter_of(&accounts[1]).count), 6);
+
+    at_slot(&mut accounts, 110, ProgramInstructions::Decrement {}).unwrap();
+    let counter = counter_of(&accounts[1]);
+    assert_eq!(u64::from_le_bytes(counter.count), 5);
+    assert_eq!(u64::from_le_bytes(counter.last_op_slot), 110);
+}
+
+#[test]
+fn no_cooldown_allows_an_operation_every_slot() {
+    stubs::reset();
+    let mut accounts = owner_accounts(&counter_state(0, 0));
+    for _ in 0..3 {
+        at_slot(&mut accounts, 1, ProgramInstructions::Increment {}).unwrap();
+    }
+    assert_eq!(u64::from_le_bytes(counter_of(&accounts[1]).count), 3);
+}
+
+#[test]
+fn set_cooldown_changes_the_limit() {
+    stubs::reset();
+    let mut accounts = owner_accounts(&counter_state(0, 0));
+    at_slot(&mut accounts, 1, ProgramInstructions::Increment {}).unwrap();
+
+    let set = ProgramInstructions::SetCooldown {
+        cooldown_slots: 3u64.to_le_bytes(),
+    };
+    run(&mut accounts, &pack(set)).unwrap();
+    assert_eq!(
+        u64::from_le_bytes(counter_of(&accounts[1]).cooldown_slots),
+        3
+    );
+    assert_eq!(
+        at_slot(&mut accounts, 3, ProgramInstructions::Increment {}),
+        cooldown_active()
+    );
+    at_slot(&mut accounts, 4, ProgramInstructions::Increment {}).unwrap();
+
+    // Zero removes the limit again
+    let clear = ProgramInstructions::SetCooldown {
+        cooldown_slots: [0; 8],
+    };
+    run(&mut accounts, &pack(clear)).unwrap();
+    at_slot(&mut accounts, 4, ProgramInstructions::Increment {}).unwrap();
+    assert_eq!(u64::from_le_bytes(counter_of(&accounts[1]).count), 3);
+}
+
+#[test]
+fn only_the_owner_sets_the_cooldown() {
+    let mut accounts = owner_accounts(&counter_state(0, 0));
+    accounts[0] = wallet([2; 32]);
+    let set = ProgramInstructions::SetCooldown {
+        cooldown_slots: u64::MAX.to_le_bytes(),
+    };
+    assert_eq!(
+        run_fresh(&mut accounts, &pack(set)),
+        Err(CounterProgramError::Unauthorized.into())
+    );
+    assert_eq!(counter_of(&accounts[1]).cooldown_slots, [0; 8]);
+}
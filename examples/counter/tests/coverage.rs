@@ -0,0 +1,92 @@
+//! Every counter instruction runs once in a few counters' lives, and the coverage
+//! report counts them all
+//!
+//! The only test in its binary, so no other test adds to or resets the record
+//! while it runs.
+
+mod common;
+
+use common::*;
+use counter::state::{COUNTER_SEED, COUNTER_V1_LEN, GLOBAL_SEED};
+use counter::ProgramInstructions;
+use jiminy::testing::{coverage_report, reset_coverage, stubs, HostAccount};
+use pinocchio::pubkey::Pubkey;
+
+// Where the stubbed bump search puts `seeds`
+fn pda<const N: usize>(seeds: &[&[u8]; N]) -> Pubkey {
+    stubs::find_program_address(seeds, &counter::ID).0
+}
+
+fn new_account(key: Pubkey) -> HostAccount {
+    HostAccount::new(key, pinocchio_system::ID, 0, &[]).writable()
+}
+
+// `owner`'s counter from before the cooldown, still to be migrated
+fn v1_counter(owner: Pubkey) -> HostAccount {
+    let mut state = counter_state(4, 0);
+    state.owner = owner.into();
+    state.bump = stubs::FOUND_BUMP;
+    let data = &bytemuck::bytes_of(&state)[..COUNTER_V1_LEN];
+    HostAccount::new(pda(&[COUNTER_SEED, &owner]), counter::ID, LAMPORTS, data).writable()
+}
+
+// Run `instruction` on the accounts of `ledger` at `indices`, then write them back
+fn call(ledger: &mut [HostAccount], indices: &[usize], instruction: ProgramInstructions) {
+    let mut accounts: Vec<HostAccount> = indices.iter().map(|&i| ledger[i].clone()).collect();
+    let name = format!("{instruction:?}");
+    if let Err(error) = run(&mut accounts, &pack(instruction)) {
+        panic!("{name} failed: {error:?}");
+    }
+    for (&i, account) in indices.iter().zip(accounts) {
+        ledger[i] = account;
+    }
+}
+
+#[test]
+fn every_instruction_is_covered() {
+    stubs::reset();
+    reset_coverage();
+
+    let (owner, payer, other) = (OWNER, [2; 32], [3; 32]);
+    let mut ledger = [
+        wallet(owner),
+        new_account(pda(&[COUNTER_SEED, &owner])),
+        HostAccount::new(pinocchio_system::ID, Pubkey::default(), 1, &[]),
+        wallet(payer),
+        new_account(pda(&[COUNTER_SEED, &payer])),
+        new_account(pda(&[GLOBAL_SEED])),
+        wallet(other),
+        v1_counter(other),
+    ];
+    let [owner, counter, system, payer, payer_counter, global, other, old_counter] =
+        [0, 1, 2, 3, 4, 5, 6, 7];
+
+    call(&mut ledger, &[owner, counter, system], ProgramInstructions::InitializeCounter {});
+    call(&mut ledger, &[owner, counter], ProgramInstructions::Increment {});
+    call(&mut ledger, &[owner, counter], ProgramInstructions::Increment {});
+    call(&mut ledger, &[owner, counter], ProgramInstructions::Decrement {});
+    call(&mut ledger, &[counter], ProgramInstructions::GetCount {});
+    assert_eq!(stubs::return_data(), 1u64.to_le_bytes());
+    call(&mut ledger, &[counter], ProgramInstructions::SnapshotCount {});
+    let cooldown = ProgramInstructions::SetCooldown {
+        cooldown_slots: [0; 8],
+    };
+    call(&mut ledger, &[owner, counter], cooldown);
+
+    // The first V2 counter creates the global counter
+    let v2 = [payer, payer_counter, global, system];
+    call(&mut ledger, &v2, ProgramInstructions::InitializeCounterV2 {});
+    call(&mut ledger, &v2[..3], ProgramInstructions::IncrementV2 {});
+    call(&mut ledger, &v2[..3], ProgramInstructions::DecrementV2 {});
+
+    call(&mut ledger, &[other, old_counter, system], ProgramInstructions::MigrateCounter {});
+    // `payer` pays for `owner`'s counter, created again from scratch
+    ledger[counter] = new_account(pda(&[COUNTER_SEED, &OWNER]));
+    let with_payer = ProgramInstructions::InitializeCounterWithPayer {};
+    call(&mut ledger, &[payer, owner, counter, system], with_payer);
+
+    let report = coverage_report(&counter::INSTRUCTIONS);
+    println!("{report}");
+    report.assert_complete();
+    assert_eq!(report.tested.len(), counter::INSTRUCTION_COUNT);
+}
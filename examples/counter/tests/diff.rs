@@ -0,0 +1,69 @@
+//! What an instruction changed, reported field by field by `jiminy::testing::Differ`
+
+mod common;
+
+use common::*;
+use counter::state::Counter;
+use counter::{CounterProgramError, ProgramInstructions};
+use jiminy::testing::{stubs, AccountSnapshot, Differ, HostAccount};
+use pinocchio::sysvars::clock::Clock;
+
+fn snapshots(accounts: &[HostAccount]) -> Vec<AccountSnapshot> {
+    accounts.iter().map(HostAccount::snapshot).collect()
+}
+
+fn differ() -> Differ {
+    Differ::new().label(OWNER, "owner").state::<Counter>(counter_key(&OWNER), "counter")
+}
+
+#[test]
+fn increment_only_changes_the_count() {
+    let mut accounts = owner_accounts(&counter_state(41, 0));
+    let before = snapshots(&accounts);
+    run_fresh(&mut accounts, &pack(ProgramInstructions::Increment {})).unwrap();
+
+    let diff = differ().diff(&before, &snapshots(&accounts));
+    diff.assert_only(&["counter.count"]);
+    let change = &diff.account("counter").unwrap().fields[0];
+    assert_eq!(change.before, 41u64.to_le_bytes());
+    assert_eq!(change.after, 42u64.to_le_bytes());
+}
+
+#[test]
+fn increment_records_the_slot_of_the_operation() {
+    let mut accounts = owner_accounts(&counter_state(0, 0));
+    let before = snapshots(&accounts);
+    stubs::reset();
+    stubs::set_clock(Clock { slot: 7, ..stubs::clock() });
+    run(&mut accounts, &pack(ProgramInstructions::Increment {})).unwrap();
+
+    let diff = differ().diff(&before, &snapshots(&accounts));
+    diff.assert_only(&["counter.count", "counter.last_op_slot"]);
+    let report = diff.to_string();
+    assert!(report.starts_with("counter (Counter):\n"), "{report}");
+    assert!(report.contains("  last_op_slot: "), "{report}");
+}
+
+#[test]
+fn failed_instruction_changes_nothing() {
+    let mut state = counter_state(5, 0);
+    state.owner = [9; 32].into();
+    let mut accounts = owner_accounts(&state);
+    let before = snapshots(&accounts);
+    assert_eq!(
+        run_fresh(&mut accounts, &pack(ProgramInstructions::Decrement {})),
+        Err(CounterProgramError::Unauthorized.into())
+    );
+    differ().diff(&before, &snapshots(&accounts)).assert_unchanged();
+}
+
+#[test]
+fn unregistered_accounts_report_raw_data() {
+    let mut accounts = owner_accounts(&counter_state(1, 0));
+    let before = snapshots(&accounts);
+    run_fresh(&mut accounts, &pack(ProgramInstructions::Decrement {})).unwrap();
+
+    let diff = jiminy::testing::diff(&before, &snapshots(&accounts));
+    let label = diff.accounts[0].label.clone();
+    assert_eq!(diff.changes(), [format!("{label}.data")]);
+}
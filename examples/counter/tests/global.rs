@@ -0,0 +1,138 @@
+//! Two owners' V2 counters share the global counter: it counts both counters, and
+//! its net increments follow their counts through interleaved calls
+
+mod common;
+
+use common::*;
+use counter::state::{GlobalCounter, COUNTER_SEED, GLOBAL_SEED};
+use counter::{CounterProgramError, ProgramInstructions};
+use jiminy::testing::{stubs, HostAccount};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+
+const OTHER: Pubkey = [2; 32];
+
+// Where the stubbed bump search puts `seeds`
+fn pda<const N: usize>(seeds: &[&[u8]; N]) -> Pubkey {
+    stubs::find_program_address(seeds, &counter::ID).0
+}
+
+fn new_account(key: Pubkey) -> HostAccount {
+    HostAccount::new(key, pinocchio_system::ID, 0, &[]).writable()
+}
+
+struct Ledger {
+    accounts: [HostAccount; 6],
+}
+
+// Indices into `Ledger::accounts`
+const SYSTEM: usize = 0;
+const GLOBAL: usize = 1;
+
+impl Ledger {
+    /// Wallets and uncreated counters of `OWNER` and `OTHER`, and no global counter yet
+    fn new() -> Self {
+        stubs::reset();
+        Self {
+            accounts: [
+                HostAccount::new(pinocchio_system::ID, Pubkey::default(), 1, &[]),
+                new_account(pda(&[GLOBAL_SEED])),
+                wallet(OWNER),
+                new_account(pda(&[COUNTER_SEED, &OWNER])),
+                wallet(OTHER),
+                new_account(pda(&[COUNTER_SEED, &OTHER])),
+            ],
+        }
+    }
+
+    // The wallet and counter indices of `owner`
+    fn of(owner: Pubkey) -> (usize, usize) {
+        if owner == OWNER {
+            (2, 3)
+        } else {
+            (4, 5)
+        }
+    }
+
+    /// Run `instruction` for `owner`, passing the global counter after the
+    /// counter for the V2 instructions, then write the accounts back
+    fn call(&mut self, owner: Pubkey, instruction: ProgramInstructions) -> Result<(), ProgramError> {
+        let (wallet, counter) = Self::of(owner);
+        let indices: &[usize] = match instruction {
+            ProgramInstructions::InitializeCounterV2 {} => &[wallet, counter, GLOBAL, SYSTEM],
+            ProgramInstructions::IncrementV2 {} | ProgramInstructions::DecrementV2 {} => {
+                &[wallet, counter, GLOBAL]
+            }
+            _ => &[wallet, counter],
+        };
+        let mut accounts: Vec<HostAccount> =
+            indices.iter().map(|&i| self.accounts[i].clone()).collect();
+        run(&mut accounts, &pack(instruction))?;
+        for (&i, account) in indices.iter().zip(accounts) {
+            self.accounts[i] = account;
+        }
+        Ok(())
+    }
+
+    fn count(&self, owner: Pubkey) -> u64 {
+        u64::from_le_bytes(counter_of(&self.accounts[Self::of(owner).1]).count)
+    }
+
+    /// `(total_counters, total_increments)` of the global counter
+    fn totals(&self) -> (u64, u64) {
+        let global: GlobalCounter =
+            bytemuck::pod_read_unaligned(&self.accounts[GLOBAL].data[..GlobalCounter::LEN]);
+        (
+            u64::from_le_bytes(global.total_counters),
+            u64::from_le_bytes(global.total_increments),
+        )
+    }
+}
+
+#[test]
+fn totals_follow_both_owners_counters() {
+    let mut ledger = Ledger::new();
+    // The first V2 counter creates the global counter, the second only adds to it
+    ledger.call(OWNER, ProgramInstructions::InitializeCounterV2 {}).unwrap();
+    assert_eq!(ledger.totals(), (1, 0));
+    ledger.call(OTHER, ProgramInstructions::InitializeCounterV2 {}).unwrap();
+    assert_eq!(ledger.totals(), (2, 0));
+
+    let steps = [
+        (OWNER, ProgramInstructions::IncrementV2 {}),
+        (OTHER, ProgramInstructions::IncrementV2 {}),
+        (OWNER, ProgramInstructions::IncrementV2 {}),
+        (OTHER, ProgramInstructions::DecrementV2 {}),
+        (OTHER, ProgramInstructions::IncrementV2 {}),
+        (OWNER, ProgramInstructions::DecrementV2 {}),
+        (OTHER, ProgramInstructions::IncrementV2 {}),
+        (OWNER, ProgramInstructions::IncrementV2 {}),
+    ];
+    for (owner, instruction) in steps {
+        ledger.call(owner, instruction).unwrap();
+        let net = ledger.count(OWNER) + ledger.count(OTHER);
+        assert_eq!(ledger.totals(), (2, net));
+    }
+    assert_eq!((ledger.count(OWNER), ledger.count(OTHER)), (2, 2));
+    assert_eq!(ledger.totals(), (2, 4));
+}
+
+#[test]
+fn failed_and_v1_calls_leave_the_totals() {
+    let mut ledger = Ledger::new();
+    ledger.call(OWNER, ProgramInstructions::InitializeCounterV2 {}).unwrap();
+    ledger.call(OTHER, ProgramInstructions::InitializeCounterV2 {}).unwrap();
+    ledger.call(OWNER, ProgramInstructions::IncrementV2 {}).unwrap();
+
+    // `OTHER`'s counter is at zero, the failed decrement writes nothing back
+    assert_eq!(
+        ledger.call(OTHER, ProgramInstructions::DecrementV2 {}),
+        Err(CounterProgramError::CounterUnderflow.into())
+    );
+    assert_eq!(ledger.totals(), (2, 1));
+
+    // The v1 instructions don't take the global counter
+    ledger.call(OTHER, ProgramInstructions::Increment {}).unwrap();
+    ledger.call(OWNER, ProgramInstructions::Decrement {}).unwrap();
+    assert_eq!((ledger.count(OWNER), ledger.count(OTHER)), (0, 1));
+    assert_eq!(ledger.totals(), (2, 1));
+}
@@ -0,0 +1,67 @@
+//! InitializeCounter creates the counter PDA whether or not someone sent lamports
+//! to its address first, which would make a plain CreateAccount fail
+
+mod common;
+
+use common::*;
+use counter::state::{Counter, COUNTER_SEED, COUNTER_SPACE};
+use counter::ProgramInstructions;
+use jiminy::testing::{stubs, HostAccount};
+use pinocchio::pubkey::Pubkey;
+
+// `OWNER`, its counter's address holding `lamports` and the system program
+fn initialize_accounts(lamports: u64) -> [HostAccount; 3] {
+    let key = stubs::find_program_address(&[COUNTER_SEED, OWNER.as_ref()], &counter::ID).0;
+    [
+        wallet(OWNER),
+        HostAccount::new(key, pinocchio_system::ID, lamports, &[]).writable(),
+        HostAccount::new(pinocchio_system::ID, Pubkey::default(), 1, &[]),
+    ]
+}
+
+// Run InitializeCounter, returning the accounts it leaves and the rent it costs
+fn initialize(lamports: u64) -> ([HostAccount; 3], u64) {
+    let mut accounts = initialize_accounts(lamports);
+    run_fresh(&mut accounts, &pack(ProgramInstructions::InitializeCounter {})).unwrap();
+    (accounts, stubs::rent().minimum_balance(COUNTER_SPACE))
+}
+
+// The counter is the program's, sized for its headroom and holds a new `OWNER` count
+fn assert_initialized(counter: &HostAccount, rent: u64) {
+    assert_eq!(counter.owner, counter::ID);
+    assert_eq!(counter.data.len(), COUNTER_SPACE);
+    assert_eq!(counter.lamports, rent);
+
+    let mut expected = counter_state(0, 0);
+    expected.bump = stubs::FOUND_BUMP;
+    assert_eq!(&counter.data[..Counter::LEN], bytemuck::bytes_of(&expected));
+}
+
+#[test]
+fn empty_address_is_created_in_one_cpi() {
+    let (accounts, rent) = initialize(0);
+    assert_eq!(stubs::cpi_calls(), ["system::create_account"]);
+    assert_initialized(&accounts[1], rent);
+    assert_eq!(accounts[0].lamports, LAMPORTS - rent);
+}
+
+#[test]
+fn prefunded_address_is_topped_up_allocated_and_assigned() {
+    let (accounts, rent) = initialize(1);
+    assert_eq!(
+        stubs::cpi_calls(),
+        ["system::transfer", "system::allocate", "system::assign"]
+    );
+    assert_initialized(&accounts[1], rent);
+    // The owner pays only what the lamport already there didn't cover
+    assert_eq!(accounts[0].lamports, LAMPORTS - (rent - 1));
+}
+
+#[test]
+fn address_funded_past_the_rent_keeps_its_lamports() {
+    let funded = 2 * stubs::rent().minimum_balance(COUNTER_SPACE);
+    let (accounts, _) = initialize(funded);
+    assert_eq!(stubs::cpi_calls(), ["system::allocate", "system::assign"]);
+    assert_initialized(&accounts[1], funded);
+    assert_eq!(accounts[0].lamports, LAMPORTS);
+}
@@ -0,0 +1,80 @@
+//! Every instruction round trips: the bytes `ProgramInstructions::pack` writes unpack
+//! to the same value, and the instruction's `parse_data`, which its `try_from` runs
+//! on chain, reads back exactly the data that was packed
+
+mod common;
+
+use common::{counter_of, counter_state, owner_accounts, run_fresh};
+use counter::instructions::*;
+use counter::{ProgramInstructions, INSTRUCTIONS};
+use jiminy::error::JiminyError;
+
+fn pack(instruction: &ProgramInstructions) -> Vec<u8> {
+    let (buf, len) = instruction.pack();
+    let bytes = buf[..len].to_vec();
+    assert_eq!(ProgramInstructions::unpack(&bytes).as_ref(), Ok(instruction));
+    bytes
+}
+
+// Pack each sample and parse it back through `<Instruction>::parse_data`, returning
+// the discriminators covered
+macro_rules! round_trip {
+    ($($instruction:ty => $sample:expr),* $(,)?) => {{
+        let mut covered = Vec::new();
+        $(
+            let bytes = pack(&$sample);
+            let (data, rest, ()) = <$instruction>::parse_data(&bytes[1..]).unwrap();
+            let parsed = [bytemuck::bytes_of(&data), rest].concat();
+            assert_eq!(parsed, &bytes[1..], stringify!($instruction));
+            covered.push(bytes[0]);
+        )*
+        covered
+    }};
+}
+
+#[test]
+fn every_instruction_round_trips() {
+    let covered = round_trip!(
+        InitializeCounterInstruction => ProgramInstructions::InitializeCounter {},
+        IncrementInstruction => ProgramInstructions::Increment {},
+        DecrementInstruction => ProgramInstructions::Decrement {},
+        GetCountInstruction => ProgramInstructions::GetCount {},
+        InitializeCounterV2Instruction => ProgramInstructions::InitializeCounterV2 {},
+        IncrementV2Instruction => ProgramInstructions::IncrementV2 {},
+        DecrementV2Instruction => ProgramInstructions::DecrementV2 {},
+        SetCooldownInstruction => ProgramInstructions::SetCooldown {
+            cooldown_slots: 150u64.to_le_bytes(),
+        },
+        MigrateCounterInstruction => ProgramInstructions::MigrateCounter {},
+        InitializeCounterWithPayerInstruction => ProgramInstructions::InitializeCounterWithPayer {},
+        SnapshotCountInstruction => ProgramInstructions::SnapshotCount {},
+    );
+    for (discriminator, name) in INSTRUCTIONS {
+        assert!(covered.contains(&discriminator), "{name} has no round trip sample");
+    }
+}
+
+#[test]
+fn views_round_trip() {
+    pack(&ProgramInstructions::ViewCounter {});
+    pack(&ProgramInstructions::ViewGlobalCounter {});
+}
+
+#[test]
+fn parse_data_rejects_what_try_from_rejects() {
+    // Short and trailing data fail before any account is looked at
+    assert!(SetCooldownInstruction::parse_data(&[0; 7]).is_err());
+    assert!(SetCooldownInstruction::parse_data(&[0; 9]).is_err());
+    assert!(IncrementInstruction::parse_data(&[0]).is_err());
+}
+
+#[test]
+fn trailing_bytes_fail_the_instruction() {
+    let mut accounts = owner_accounts(&counter_state(5, 0));
+    let data = [pack(&ProgramInstructions::Increment {}), vec![0; 3]].concat();
+    assert_eq!(
+        run_fresh(&mut accounts, &data),
+        Err(JiminyError::TrailingInstructionData.into())
+    );
+    assert_eq!(u64::from_le_bytes(counter_of(&accounts[1]).count), 5);
+}
@@ -0,0 +1,61 @@
+//! SnapshotCount logs the count as a `CountSnapshot` event and returns it
+
+mod common;
+
+use common::*;
+use counter::state::CountSnapshot;
+use counter::ProgramInstructions;
+use jiminy::testing::{stubs, HostAccount};
+use pinocchio::program_error::ProgramError;
+use pinocchio::sysvars::clock::Clock;
+
+// Anyone can snapshot, so the counter is the only account
+fn snapshot(count: u64) -> (HostAccount, Result<(), ProgramError>) {
+    let mut accounts = [counter_account(&counter_state(count, 0))];
+    let result = run(&mut accounts, &pack(ProgramInstructions::SnapshotCount {}));
+    let [counter] = accounts;
+    (counter, result)
+}
+
+#[test]
+fn count_is_returned_little_endian() {
+    stubs::reset();
+    let (_, result) = snapshot(1234);
+    result.unwrap();
+    assert_eq!(stubs::return_data(), 1234u64.to_le_bytes());
+}
+
+#[test]
+fn count_is_logged_with_the_owner_and_slot() {
+    stubs::reset();
+    stubs::set_clock(Clock {
+        slot: 99,
+        ..stubs::clock()
+    });
+    snapshot(7).1.unwrap();
+
+    let events = stubs::events::<CountSnapshot>("CountSnapshot");
+    assert_eq!(events.len(), 1);
+    assert_eq!(events[0].owner, OWNER);
+    assert_eq!(u64::from_le_bytes(events[0].count), 7);
+    assert_eq!(u64::from_le_bytes(events[0].slot), 99);
+}
+
+#[test]
+fn snapshot_changes_nothing() {
+    stubs::reset();
+    let before = counter_account(&counter_state(7, 0));
+    let (after, result) = snapshot(7);
+    result.unwrap();
+    assert_eq!(after, before);
+    assert!(stubs::cpi_calls().is_empty());
+}
+
+#[test]
+fn uninitialized_counters_are_rejected() {
+    stubs::reset();
+    let mut accounts = [HostAccount::new(counter_key(&OWNER), pinocchio_system::ID, 0, &[])];
+    assert!(run(&mut accounts, &pack(ProgramInstructions::SnapshotCount {})).is_err());
+    assert!(stubs::return_data().is_empty());
+    assert!(stubs::logged_data().is_empty());
+}
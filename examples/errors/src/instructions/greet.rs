@@ -0,0 +1,21 @@
+use crate::NameError;
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 0,
+    Greet,
+    desc: "Log a greeting to the name in the trailing bytes",
+    accounts: {
+        user: signer, desc: "Whoever greets",
+    },
+    data: {},
+    rest: name, max_len: 32,
+    process: {
+        if name.is_empty() {
+            return Err(NameError::Empty.into());
+        }
+        let name = core::str::from_utf8(name).map_err(|_| NameError::NotUtf8)?;
+        pinocchio_log::log!("Hello {}", name);
+        Ok(())
+    }
+);
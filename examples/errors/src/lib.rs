@@ -0,0 +1,41 @@
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+//! Two `define_errors!` enums in one module and a `jiminy_program!` dispatch, no build script
+
+#[macro_use]
+extern crate jiminy;
+
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+};
+
+pub mod instructions {
+    pub mod greet;
+    pub use greet::*;
+}
+pub use instructions::*;
+
+pinocchio_pubkey::declare_id!("B6kbXdkYmcvnHEM89s7HH9QUdrS4xsG7F7pfQdL6ooqT");
+
+define_errors! {
+    GreetingError,
+    /// Unknown instruction discriminator
+    InvalidDiscriminator = 6001,
+}
+
+define_errors! {
+    NameError,
+    /// Name is empty
+    Empty = 6101,
+    /// Name is not UTF-8
+    NotUtf8 = 6102,
+}
+
+// Unknown discriminators fail with `GreetingError::INVALID_DISCRIMINATOR`
+jiminy_program!(
+    error_type: GreetingError,
+    0 => Greet,
+);
+
+jiminy_entrypoint!(process_instruction);
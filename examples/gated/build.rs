@@ -0,0 +1,3 @@
+fn main() -> std::io::Result<()> {
+    jiminy_build::Config::from_manifest_dir().generate()
+}
@@ -0,0 +1,8 @@
+// Parsed by the build script, the enum is generated in generated.rs
+define_errors! {
+    GatedProgramError,
+    InvalidDiscriminator = 6001,
+    NotCounterOwner = 6002,
+    CounterKeyIncorrect = 6003,
+    CountTooLow = 6004,
+}
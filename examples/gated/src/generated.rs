@@ -0,0 +1,194 @@
+use shank::ShankInstruction;
+use shank::ShankType;
+use pinocchio::program_error::ProgramError;
+
+/// Added to every declared error code, `errors.base` in jiminy.toml
+pub const ERROR_BASE: u32 = 2000;
+
+/// The `E` a `Custom(code)` from `program_id` stands for
+///
+/// `None` if another program returned it or `E` has no such code.
+pub fn decode_error<E: TryFrom<u32>>(
+    program_id: &pinocchio::pubkey::Pubkey,
+    code: u32,
+) -> Option<E> {
+    if program_id != &crate::ID {
+        return None;
+    }
+    E::try_from(code).ok()
+}
+
+// Generated error enum: GatedProgramError
+#[derive(Clone, Debug, PartialEq, ShankType)]
+#[non_exhaustive]
+pub enum GatedProgramError {
+    InvalidDiscriminator = 8001,
+    NotCounterOwner = 8002,
+    CounterKeyIncorrect = 8003,
+    CountTooLow = 8004,
+}
+
+impl core::fmt::Display for GatedProgramError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::InvalidDiscriminator => "InvalidDiscriminator",
+            Self::NotCounterOwner => "NotCounterOwner",
+            Self::CounterKeyIncorrect => "CounterKeyIncorrect",
+            Self::CountTooLow => "CountTooLow",
+        })
+    }
+}
+
+impl From<GatedProgramError> for ProgramError {
+    fn from(e: GatedProgramError) -> Self {
+        Self::Custom(e as u32)
+    }
+}
+
+impl jiminy::dispatch::HasInvalidDiscriminator for GatedProgramError {
+    const INVALID_DISCRIMINATOR: Self = Self::InvalidDiscriminator;
+}
+
+impl TryFrom<u32> for GatedProgramError {
+    type Error = u32;
+
+    fn try_from(code: u32) -> Result<Self, u32> {
+        match code {
+            8001 => Ok(Self::InvalidDiscriminator),
+            8002 => Ok(Self::NotCounterOwner),
+            8003 => Ok(Self::CounterKeyIncorrect),
+            8004 => Ok(Self::CountTooLow),
+            _ => Err(code),
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Clone, Debug, PartialEq, ShankInstruction)]
+pub enum ProgramInstructions {
+    /// Succeed only when the caller's counter from the counter program is above THRESHOLD
+    #[account(0, signer, name = "caller", desc = "Owner of the counter")]
+    #[account(1, name = "counter", desc = "The caller's counter PDA, owned by the counter program")]
+    PassGate {
+    },
+
+}
+
+/// Longest instruction data `ProgramInstructions::pack` writes, discriminator included
+pub const MAX_INSTRUCTION_DATA_LEN: usize = jiminy::dispatch::max_len(&[
+    1,
+]);
+
+impl ProgramInstructions {
+    /// Instruction data for this instruction: the discriminator, the data struct's bytes (the borsh encoded schema for a `data_parser`), then any trailing bytes
+    ///
+    /// Panics if the trailing bytes are longer than the instruction's `max_len`.
+    pub fn pack(&self) -> ([u8; MAX_INSTRUCTION_DATA_LEN], usize) {
+        let mut buf = [0u8; MAX_INSTRUCTION_DATA_LEN];
+        let len = match self {
+            Self::PassGate {} => {
+                buf[0] = 0;
+                1
+            }
+        };
+        (buf, len)
+    }
+
+    /// Parse instruction data the way the program does, deprecated discriminators included
+    pub fn unpack(data: &[u8]) -> Result<Self, pinocchio::program_error::ProgramError> {
+        let (&discriminator, data) = data.split_first().ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+        Ok(match discriminator {
+            0 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::PassGate {
+                }
+            }
+            _ => return Err(pinocchio::program_error::ProgramError::InvalidInstructionData),
+        })
+    }
+}
+
+// ShankAccount definitions for state structs
+// These are generated for IDL compatibility
+pub type InstructionHandler = fn(
+    &[pinocchio::account_info::AccountInfo],
+    &[u8],
+) -> pinocchio::ProgramResult;
+
+fn handle_pass_gate(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::PassGateInstruction::try_from((accounts, data))?.process()
+}
+
+/// Instructions of the program, read-only views not counted
+pub const INSTRUCTION_COUNT: usize = 1;
+
+/// `(discriminator, name)` of each instruction, by discriminator
+pub const INSTRUCTIONS: [(u8, &str); INSTRUCTION_COUNT] = [
+    (0, "PassGate"),
+];
+
+/// Instruction name of a discriminator, deprecated aliases included
+pub const fn instruction_name(discriminator: u8) -> Option<&'static str> {
+    match discriminator {
+        0 => Some("PassGate"),
+        _ => None,
+    }
+}
+
+pub const DISPATCH: [Option<InstructionHandler>; 256] = {
+    let mut table: [Option<InstructionHandler>; 256] = [None; 256];
+    table[0] = Some(handle_pass_gate);
+    table
+};
+
+#[cfg(not(feature = "match-dispatch"))]
+pub fn process_instruction(
+    program_id: &pinocchio::pubkey::Pubkey,
+    accounts: &[pinocchio::account_info::AccountInfo],
+    instruction_data: &[u8],
+) -> pinocchio::ProgramResult {
+    if !jiminy::perf::keys_eq(program_id, &crate::ID) {
+        return Err(pinocchio::program_error::ProgramError::IncorrectProgramId);
+    }
+
+    #[cfg(feature = "host-stubs")]
+    if let Some(discriminator) = instruction_data.first() {
+        jiminy::testing::record_coverage(*discriminator);
+    }
+
+    match instruction_data.split_first() {
+        Some((discriminator, data)) => match DISPATCH[*discriminator as usize] {
+            Some(handler) => handler(accounts, data),
+            None => Err(<GatedProgramError as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into()),
+        },
+        None => Err(<GatedProgramError as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into()),
+    }
+}
+
+#[cfg(feature = "match-dispatch")]
+pub fn process_instruction(
+    program_id: &pinocchio::pubkey::Pubkey,
+    accounts: &[pinocchio::account_info::AccountInfo],
+    instruction_data: &[u8],
+) -> pinocchio::ProgramResult {
+    if !jiminy::perf::keys_eq(program_id, &crate::ID) {
+        return Err(pinocchio::program_error::ProgramError::IncorrectProgramId);
+    }
+
+    #[cfg(feature = "host-stubs")]
+    if let Some(discriminator) = instruction_data.first() {
+        jiminy::testing::record_coverage(*discriminator);
+    }
+
+    match instruction_data.first() {
+        Some(0) => {
+            crate::instructions::PassGateInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        _ => Err(<GatedProgramError as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into()),
+    }
+}
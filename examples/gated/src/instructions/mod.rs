@@ -0,0 +1,3 @@
+pub mod pass_gate;
+
+pub use pass_gate::*;
@@ -0,0 +1,44 @@
+use crate::{Counter, GatedProgramError};
+use counter::state::COUNTER_SEED;
+use jiminy::define_instruction_with_metadata;
+
+/// Count the caller's counter has to be above to pass
+pub const THRESHOLD: u64 = 10;
+
+define_instruction_with_metadata!(
+    discriminant: 0,
+    PassGate,
+    desc: "Succeed only when the caller's counter from the counter program is above THRESHOLD",
+    accounts: {
+        caller: signer, desc: "Owner of the counter",
+        counter: any, desc: "The caller's counter PDA, owned by the counter program",
+            label: "Counter", explorer_hint: pda,
+    },
+    data: {},
+    process: {
+        // Counters are allocated with headroom, so only the prefix is read
+        let (owner, count, bump) = {
+            let counter_state = load_foreign!(counter, Counter, owner: counter::ID, prefix);
+            (counter_state.owner, u64::from_le_bytes(counter_state.count), counter_state.bump)
+        };
+        if owner != *caller.key() {
+            return Err(GatedProgramError::NotCounterOwner.into());
+        }
+
+        // The counter program's PDA for the caller, not just any account it owns
+        let expected = derive_pda!(
+            seeds: [COUNTER_SEED, caller.key().as_ref()],
+            bump: bump,
+            program: &counter::ID
+        );
+        if counter.key() != &expected {
+            return Err(GatedProgramError::CounterKeyIncorrect.into());
+        }
+
+        if count <= THRESHOLD {
+            fail!(GatedProgramError::CountTooLow, "count {} is not above {}", count, THRESHOLD);
+        }
+        pinocchio_log::log!("Gate passed with count {}", count);
+        Ok(())
+    }
+);
@@ -0,0 +1,21 @@
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+#[macro_use]
+extern crate jiminy;
+
+pub mod error;
+pub mod instructions;
+
+// The counter program's state, re-exported so this program's clients decode the
+// accounts it reads with the same type
+pub use counter::state::Counter;
+
+pinocchio_pubkey::declare_id!("F2nyzMEPMTGv6tDippb9VeByPQNxSonpmEwg7ZZTsQTT");
+
+// Written by the build script
+pub mod generated;
+pub use generated::*;
+
+#[cfg(not(feature = "no-entrypoint"))]
+jiminy_entrypoint!(process_instruction);
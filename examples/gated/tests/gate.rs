@@ -0,0 +1,93 @@
+//! PassGate reads counters the counter program created and incremented, run on the
+//! host one program after the other
+
+use counter::state::COUNTER_SEED;
+use gated::instructions::THRESHOLD;
+use gated::{GatedProgramError, ProgramInstructions};
+use jiminy::testing::{process, stubs, HostAccount};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+
+const LAMPORTS: u64 = 1_000_000_000;
+const OWNER: Pubkey = [1; 32];
+
+fn wallet(key: Pubkey) -> HostAccount {
+    HostAccount::new(key, pinocchio_system::ID, LAMPORTS, &[])
+        .signer()
+        .writable()
+}
+
+fn run_counter(accounts: &mut [HostAccount], instruction: counter::ProgramInstructions) {
+    let (data, len) = instruction.pack();
+    process(counter::process_instruction, &counter::ID, accounts, &data[..len]).unwrap();
+}
+
+// `owner`'s counter, created by the counter program and incremented `count` times
+fn counter_at(owner: Pubkey, count: u64) -> HostAccount {
+    stubs::reset();
+    let key = stubs::find_program_address(&[COUNTER_SEED, owner.as_ref()], &counter::ID).0;
+    let mut accounts = [
+        wallet(owner),
+        HostAccount::new(key, pinocchio_system::ID, 0, &[]).writable(),
+        HostAccount::new(pinocchio_system::ID, Pubkey::default(), 1, &[]),
+    ];
+    run_counter(&mut accounts, counter::ProgramInstructions::InitializeCounter {});
+    let [owner, counter, _] = accounts;
+    let mut accounts = [owner, counter];
+    for _ in 0..count {
+        run_counter(&mut accounts, counter::ProgramInstructions::Increment {});
+    }
+    let [_, counter] = accounts;
+    counter
+}
+
+fn pass_gate(caller: Pubkey, counter: HostAccount) -> Result<(), ProgramError> {
+    let (data, len) = ProgramInstructions::PassGate {}.pack();
+    let mut accounts = [wallet(caller), counter];
+    process(gated::process_instruction, &gated::ID, &mut accounts, &data[..len])
+}
+
+#[test]
+fn counter_above_the_threshold_passes() {
+    let counter = counter_at(OWNER, THRESHOLD + 1);
+    assert_eq!(counter.owner, counter::ID);
+    pass_gate(OWNER, counter).unwrap();
+}
+
+#[test]
+fn counter_at_the_threshold_fails() {
+    assert_eq!(
+        pass_gate(OWNER, counter_at(OWNER, THRESHOLD)),
+        Err(GatedProgramError::CountTooLow.into())
+    );
+}
+
+#[test]
+fn counter_owned_by_another_program_fails() {
+    // The same bytes, owned by this program instead of the counter program
+    let mut counter = counter_at(OWNER, THRESHOLD + 1);
+    counter.owner = gated::ID;
+    assert_eq!(
+        pass_gate(OWNER, counter),
+        Err(ProgramError::InvalidAccountOwner)
+    );
+}
+
+#[test]
+fn another_wallets_counter_fails() {
+    assert_eq!(
+        pass_gate([2; 32], counter_at(OWNER, THRESHOLD + 1)),
+        Err(GatedProgramError::NotCounterOwner.into())
+    );
+}
+
+#[test]
+fn counter_state_at_another_address_fails() {
+    // The owner's state copied into an account the counter program owns
+    // elsewhere, not its PDA for the owner
+    let mut counter = counter_at(OWNER, THRESHOLD + 1);
+    counter.key = [9; 32];
+    assert_eq!(
+        pass_gate(OWNER, counter),
+        Err(GatedProgramError::CounterKeyIncorrect.into())
+    );
+}
@@ -0,0 +1,130 @@
+use shank::ShankInstruction;
+
+#[repr(u8)]
+#[derive(Clone, Debug, PartialEq, ShankInstruction)]
+pub enum ProgramInstructions {
+    /// Log a greeting for the signer
+    #[account(0, signer, name = "user", desc = "Whoever says hello")]
+    SayHello {
+    },
+
+}
+
+/// Longest instruction data `ProgramInstructions::pack` writes, discriminator included
+pub const MAX_INSTRUCTION_DATA_LEN: usize = jiminy::dispatch::max_len(&[
+    1,
+]);
+
+impl ProgramInstructions {
+    /// Instruction data for this instruction: the discriminator, the data struct's bytes (the borsh encoded schema for a `data_parser`), then any trailing bytes
+    ///
+    /// Panics if the trailing bytes are longer than the instruction's `max_len`.
+    pub fn pack(&self) -> ([u8; MAX_INSTRUCTION_DATA_LEN], usize) {
+        let mut buf = [0u8; MAX_INSTRUCTION_DATA_LEN];
+        let len = match self {
+            Self::SayHello {} => {
+                buf[0] = 0;
+                1
+            }
+        };
+        (buf, len)
+    }
+
+    /// Parse instruction data the way the program does, deprecated discriminators included
+    pub fn unpack(data: &[u8]) -> Result<Self, pinocchio::program_error::ProgramError> {
+        let (&discriminator, data) = data.split_first().ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+        Ok(match discriminator {
+            0 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::SayHello {
+                }
+            }
+            _ => return Err(pinocchio::program_error::ProgramError::InvalidInstructionData),
+        })
+    }
+}
+
+// ShankAccount definitions for state structs
+// These are generated for IDL compatibility
+pub type InstructionHandler = fn(
+    &[pinocchio::account_info::AccountInfo],
+    &[u8],
+) -> pinocchio::ProgramResult;
+
+fn handle_say_hello(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::SayHelloInstruction::try_from((accounts, data))?.process()
+}
+
+/// Instructions of the program, read-only views not counted
+pub const INSTRUCTION_COUNT: usize = 1;
+
+/// `(discriminator, name)` of each instruction, by discriminator
+pub const INSTRUCTIONS: [(u8, &str); INSTRUCTION_COUNT] = [
+    (0, "SayHello"),
+];
+
+/// Instruction name of a discriminator, deprecated aliases included
+pub const fn instruction_name(discriminator: u8) -> Option<&'static str> {
+    match discriminator {
+        0 => Some("SayHello"),
+        _ => None,
+    }
+}
+
+pub const DISPATCH: [Option<InstructionHandler>; 256] = {
+    let mut table: [Option<InstructionHandler>; 256] = [None; 256];
+    table[0] = Some(handle_say_hello);
+    table
+};
+
+#[cfg(not(feature = "match-dispatch"))]
+pub fn process_instruction(
+    program_id: &pinocchio::pubkey::Pubkey,
+    accounts: &[pinocchio::account_info::AccountInfo],
+    instruction_data: &[u8],
+) -> pinocchio::ProgramResult {
+    if !jiminy::perf::keys_eq(program_id, &crate::ID) {
+        return Err(pinocchio::program_error::ProgramError::IncorrectProgramId);
+    }
+
+    #[cfg(feature = "host-stubs")]
+    if let Some(discriminator) = instruction_data.first() {
+        jiminy::testing::record_coverage(*discriminator);
+    }
+
+    match instruction_data.split_first() {
+        Some((discriminator, data)) => match DISPATCH[*discriminator as usize] {
+            Some(handler) => handler(accounts, data),
+            None => Err(pinocchio::program_error::ProgramError::InvalidInstructionData),
+        },
+        None => Err(pinocchio::program_error::ProgramError::InvalidInstructionData),
+    }
+}
+
+#[cfg(feature = "match-dispatch")]
+pub fn process_instruction(
+    program_id: &pinocchio::pubkey::Pubkey,
+    accounts: &[pinocchio::account_info::AccountInfo],
+    instruction_data: &[u8],
+) -> pinocchio::ProgramResult {
+    if !jiminy::perf::keys_eq(program_id, &crate::ID) {
+        return Err(pinocchio::program_error::ProgramError::IncorrectProgramId);
+    }
+
+    #[cfg(feature = "host-stubs")]
+    if let Some(discriminator) = instruction_data.first() {
+        jiminy::testing::record_coverage(*discriminator);
+    }
+
+    match instruction_data.first() {
+        Some(0) => {
+            crate::instructions::SayHelloInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        _ => Err(pinocchio::program_error::ProgramError::InvalidInstructionData),
+    }
+}
@@ -0,0 +1,17 @@
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 0,
+    SayHello,
+    desc: "Log a greeting for the signer",
+    // `user` is only there to sign
+    // jiminy: allow(unused_accounts)
+    accounts: {
+        user: signer, desc: "Whoever says hello",
+    },
+    data: {},
+    process: {
+        pinocchio_log::log!("Hello from jiminy");
+        Ok(())
+    }
+);
@@ -0,0 +1,19 @@
+#![no_std]
+#![allow(unexpected_cfgs)]
+
+#[macro_use]
+extern crate jiminy;
+
+// The dispatch finds each instruction at `crate::instructions`
+pub mod instructions {
+    pub mod say_hello;
+    pub use say_hello::*;
+}
+
+pinocchio_pubkey::declare_id!("Brff3Ccu6BXV3mHUHzL8sfSgHk16NC8wect2cNc1AmEP");
+
+// Written by the build script
+pub mod generated;
+pub use generated::*;
+
+jiminy_entrypoint!(process_instruction);
@@ -0,0 +1,339 @@
+//! Compute units of the vote instructions under mollusk, and the size of the program
+//!
+//! - `redeem_with_fee` checks the vote vault for the payout and again for the fee;
+//!   `redeem_fee_waived` is the platform authority's own position, which takes no
+//!   fee and checks the vault once.
+//! - `initialize_position` searches the position bump once in `assert_pda_cached!`
+//!   and creates the account with the cached bump.
+//! - `update_position` checks the fee vault against the key stored on the platform
+//!   instead of deriving it.
+//!
+//! ```bash
+//! cargo build-sbf --manifest-path examples/vote/Cargo.toml
+//! SBF_OUT_DIR=$PWD/target/deploy cargo bench -p vote --bench compute_units
+//! ```
+//!
+//! The bencher writes `target/benches/compute_units.md` with the delta to the previous
+//! run, so bench once, change the build and bench again:
+//!
+//! - the dispatch: rebuild with `--features match-dispatch`, the bench also prints the
+//!   size of `vote.so`;
+//! - the PDA cache: search the bump with `find_program_address` in
+//!   `initialize_position.rs` and pass it to `create_pda!`.
+
+use bytemuck::Zeroable;
+use mollusk_svm::Mollusk;
+use mollusk_svm_bencher::MolluskComputeUnitBencher;
+use solana_sdk::{
+    account::Account,
+    instruction::{AccountMeta, Instruction},
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use std::path::Path;
+use vote::state::{
+    FeePolicyKind, Platform, Position, Treasury, Vote, PLATFORM_SEED, POSITION_SEED, RECEIPT_SEED,
+    UNRESOLVED,
+};
+use vote::{FundingMode, ProgramInstructions};
+
+const STAKE: u64 = 1_000_000;
+
+struct Bench {
+    instruction: Instruction,
+    accounts: Vec<(Pubkey, Account)>,
+}
+
+fn program_account(mollusk: &Mollusk, owner: &Pubkey, data: &[u8]) -> Account {
+    Account {
+        lamports: mollusk.sysvars.rent.minimum_balance(data.len()),
+        data: data.to_vec(),
+        owner: *owner,
+        executable: false,
+        rent_epoch: 0,
+    }
+}
+
+fn token_account(mollusk: &Mollusk, mint: &Pubkey, owner: &Pubkey, amount: u64) -> Account {
+    let mut data = vec![0; spl_token::state::Account::LEN];
+    spl_token::state::Account {
+        mint: *mint,
+        owner: *owner,
+        amount,
+        state: spl_token::state::AccountState::Initialized,
+        ..Default::default()
+    }
+    .pack_into_slice(&mut data);
+    program_account(mollusk, &spl_token::ID, &data)
+}
+
+// A vote on `mint` with a 1% fee platform, and the PDAs a position of `authority` uses
+struct Setup {
+    program_id: Pubkey,
+    vote: Pubkey,
+    mint: Pubkey,
+    fee_vault: Pubkey,
+    treasury: Pubkey,
+    platform: Pubkey,
+    vote_vault: Pubkey,
+    position: Pubkey,
+    position_bump: u8,
+    vote_state: Vote,
+    platform_state: Platform,
+}
+
+impl Setup {
+    fn new(authority: Pubkey, platform_authority: Pubkey) -> Self {
+        let program_id = Pubkey::new_from_array(vote::ID);
+        let vote = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let fee_vault = Pubkey::new_unique();
+        let treasury = Pubkey::new_unique();
+        let (platform, platform_bump) =
+            Pubkey::find_program_address(&[PLATFORM_SEED], &program_id);
+        let (vote_vault, vault_bump) = Pubkey::find_program_address(&[vote.as_ref()], &program_id);
+        let (position, position_bump) = Pubkey::find_program_address(
+            &[POSITION_SEED, vote.as_ref(), authority.as_ref()],
+            &program_id,
+        );
+
+        let mut vote_state = Vote::zeroed();
+        vote_state.deadline = jiminy::Deadline::unix(i64::MAX);
+        vote_state.vault_bump = vault_bump;
+        vote_state.outcome_count = 2;
+        vote_state.resolved_outcome = UNRESOLVED;
+        vote_state.token = mint.to_bytes().into();
+
+        let mut platform_state = Platform::zeroed();
+        platform_state.authority = platform_authority.to_bytes().into();
+        platform_state.fee = 100u16.to_le_bytes();
+        platform_state.platform_bump = platform_bump;
+        platform_state.fee_policy = FeePolicyKind::Bps.to_u8();
+        platform_state.vault = fee_vault.to_bytes().into();
+        platform_state.treasury = treasury.to_bytes().into();
+
+        Self {
+            program_id,
+            vote,
+            mint,
+            fee_vault,
+            treasury,
+            platform,
+            vote_vault,
+            position,
+            position_bump,
+            vote_state,
+            platform_state,
+        }
+    }
+
+    fn state(&self, mollusk: &Mollusk, data: &[u8]) -> Account {
+        program_account(mollusk, &self.program_id, data)
+    }
+
+    fn mint_account(&self, mollusk: &Mollusk) -> Account {
+        let mut data = vec![0; spl_token::state::Mint::LEN];
+        spl_token::state::Mint {
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        }
+        .pack_into_slice(&mut data);
+        program_account(mollusk, &spl_token::ID, &data)
+    }
+
+    // The token and system programs go last, past the declared accounts, so the
+    // CPIs find them
+    fn bench(
+        &self,
+        instruction: ProgramInstructions,
+        keys: &[(Pubkey, bool, bool)],
+        mut accounts: Vec<(Pubkey, Account)>,
+    ) -> Bench {
+        let programs = [
+            mollusk_svm_programs_token::token::keyed_account(),
+            mollusk_svm::program::keyed_account_for_system_program(),
+        ];
+        let (data, len) = instruction.pack();
+        let metas = keys
+            .iter()
+            .map(|&(key, signer, writable)| match writable {
+                true => AccountMeta::new(key, signer),
+                false => AccountMeta::new_readonly(key, signer),
+            })
+            .chain(programs.iter().map(|(key, _)| AccountMeta::new_readonly(*key, false)))
+            .collect();
+        accounts.extend(programs);
+        Bench {
+            instruction: Instruction::new_with_bytes(self.program_id, &data[..len], metas),
+            accounts,
+        }
+    }
+}
+
+// The first 500_000 of a 1_500_000 reward, so the position stays open
+fn redeem(mollusk: &Mollusk, authority: Pubkey, platform_authority: Pubkey) -> Bench {
+    let mut setup = Setup::new(authority, platform_authority);
+    setup.vote_state.deadline = jiminy::Deadline::unix(0);
+    setup.vote_state.tallies[0] = STAKE.to_le_bytes();
+    setup.vote_state.tallies[1] = (STAKE / 2).to_le_bytes();
+    let (receipt, _) = Pubkey::find_program_address(
+        &[RECEIPT_SEED, setup.vote.as_ref(), authority.as_ref()],
+        &setup.program_id,
+    );
+
+    let mut position_state = Position::zeroed();
+    position_state.amount = STAKE.into();
+    position_state.bump = setup.position_bump;
+
+    let keys = [
+        (authority, true, true),
+        (setup.vote, false, true),
+        (Pubkey::new_unique(), false, true),
+        (Pubkey::new_unique(), false, true),
+        (Pubkey::new_unique(), false, true),
+        (setup.position, false, true),
+        (receipt, false, false),
+        (setup.platform, false, true),
+        (setup.treasury, false, true),
+        (setup.fee_vault, false, true),
+        (setup.mint, false, false),
+        (setup.vote_vault, false, false),
+    ];
+    let accounts = vec![
+        (authority, Account::new(10_000_000_000, 0, &solana_sdk::system_program::ID)),
+        (setup.vote, setup.state(mollusk, bytemuck::bytes_of(&setup.vote_state))),
+        (keys[2].0, token_account(mollusk, &setup.mint, &setup.vote_vault, 2 * STAKE)),
+        (keys[3].0, token_account(mollusk, &setup.mint, &authority, 0)),
+        (keys[4].0, token_account(mollusk, &setup.mint, &setup.fee_vault, 0)),
+        (setup.position, setup.state(mollusk, bytemuck::bytes_of(&position_state))),
+        (receipt, Account::default()),
+        (setup.platform, setup.state(mollusk, bytemuck::bytes_of(&setup.platform_state))),
+        (setup.treasury, setup.state(mollusk, bytemuck::bytes_of(&Treasury::zeroed()))),
+        (setup.fee_vault, setup.state(mollusk, &[])),
+        (setup.mint, setup.mint_account(mollusk)),
+        (setup.vote_vault, Account::default()),
+    ];
+
+    let redeem = ProgramInstructions::RedeemWinnings {
+        max_amount: (STAKE / 2).to_le_bytes(),
+    };
+    setup.bench(redeem, &keys, accounts)
+}
+
+// A new position of `STAKE` on outcome 1, creating the position PDA
+fn initialize_position(mollusk: &Mollusk, authority: Pubkey) -> Bench {
+    let setup = Setup::new(authority, Pubkey::new_unique());
+    let keys = [
+        (authority, true, true),
+        (setup.vote, false, true),
+        (Pubkey::new_unique(), false, true),
+        (Pubkey::new_unique(), false, true),
+        (Pubkey::new_unique(), false, true),
+        (Pubkey::new_unique(), false, true),
+        (setup.position, false, true),
+        (setup.platform, false, true),
+        (setup.treasury, false, true),
+        (setup.fee_vault, false, false),
+        (setup.mint, false, false),
+        (setup.vote_vault, false, false),
+        (Pubkey::new_unique(), false, false),
+    ];
+    let accounts = vec![
+        (authority, Account::new(10_000_000_000, 0, &solana_sdk::system_program::ID)),
+        (setup.vote, setup.state(mollusk, bytemuck::bytes_of(&setup.vote_state))),
+        (keys[2].0, token_account(mollusk, &setup.mint, &setup.vote_vault, 0)),
+        (keys[3].0, token_account(mollusk, &setup.mint, &authority, 2 * STAKE)),
+        (keys[4].0, token_account(mollusk, &setup.mint, &authority, STAKE)),
+        (keys[5].0, token_account(mollusk, &setup.mint, &setup.fee_vault, 0)),
+        (setup.position, Account::default()),
+        (setup.platform, setup.state(mollusk, bytemuck::bytes_of(&setup.platform_state))),
+        (setup.treasury, setup.state(mollusk, bytemuck::bytes_of(&Treasury::zeroed()))),
+        (setup.fee_vault, setup.state(mollusk, &[])),
+        (setup.mint, setup.mint_account(mollusk)),
+        (setup.vote_vault, Account::default()),
+        (keys[12].0, Account::default()),
+    ];
+
+    // Amounts are big-endian in the position instructions
+    let initialize = ProgramInstructions::InitializePosition {
+        amount: STAKE.to_be_bytes(),
+        side: 1,
+        funding_mode: FundingMode::Owner,
+        allow_crank: 0,
+    };
+    setup.bench(initialize, &keys, accounts)
+}
+
+// `STAKE` more on an existing position of `STAKE`
+fn update_position(mollusk: &Mollusk, authority: Pubkey) -> Bench {
+    let mut setup = Setup::new(authority, Pubkey::new_unique());
+    setup.vote_state.tallies[1] = STAKE.to_le_bytes();
+    let mut position_state = Position::zeroed();
+    position_state.amount = STAKE.into();
+    position_state.side = 1;
+    position_state.bump = setup.position_bump;
+
+    let keys = [
+        (authority, true, true),
+        (setup.vote, false, true),
+        (setup.vote_vault, false, true),
+        (Pubkey::new_unique(), false, true),
+        (Pubkey::new_unique(), false, true),
+        (Pubkey::new_unique(), false, true),
+        (Pubkey::new_unique(), false, true),
+        (setup.position, false, true),
+        (setup.platform, false, true),
+        (setup.treasury, false, true),
+        (setup.fee_vault, false, false),
+        (setup.mint, false, false),
+    ];
+    let accounts = vec![
+        (authority, Account::new(10_000_000_000, 0, &solana_sdk::system_program::ID)),
+        (setup.vote, setup.state(mollusk, bytemuck::bytes_of(&setup.vote_state))),
+        (setup.vote_vault, Account::default()),
+        (keys[3].0, token_account(mollusk, &setup.mint, &setup.vote_vault, STAKE)),
+        (keys[4].0, token_account(mollusk, &setup.mint, &authority, 2 * STAKE)),
+        (keys[5].0, token_account(mollusk, &setup.mint, &authority, STAKE)),
+        (keys[6].0, token_account(mollusk, &setup.mint, &setup.fee_vault, 0)),
+        (setup.position, setup.state(mollusk, bytemuck::bytes_of(&position_state))),
+        (setup.platform, setup.state(mollusk, bytemuck::bytes_of(&setup.platform_state))),
+        (setup.treasury, setup.state(mollusk, bytemuck::bytes_of(&Treasury::zeroed()))),
+        (setup.fee_vault, setup.state(mollusk, &[])),
+        (setup.mint, setup.mint_account(mollusk)),
+    ];
+
+    let update = ProgramInstructions::UpdatePosition {
+        amount: STAKE.to_be_bytes(),
+        funding_mode: FundingMode::Owner,
+    };
+    setup.bench(update, &keys, accounts)
+}
+
+fn main() {
+    let program_id = Pubkey::new_from_array(vote::ID);
+    let mut mollusk = Mollusk::new(&program_id, "vote");
+    mollusk_svm_programs_token::token::add_program(&mut mollusk);
+
+    // The code size to compare with a `match-dispatch` build
+    if let Some(dir) = std::env::var_os("SBF_OUT_DIR") {
+        if let Ok(metadata) = std::fs::metadata(Path::new(&dir).join("vote.so")) {
+            println!("vote.so: {} bytes", metadata.len());
+        }
+    }
+
+    let authority = Pubkey::new_unique();
+    let with_fee = redeem(&mollusk, authority, Pubkey::new_unique());
+    let fee_waived = redeem(&mollusk, authority, authority);
+    let initialize = initialize_position(&mollusk, authority);
+    let update = update_position(&mollusk, authority);
+
+    MolluskComputeUnitBencher::new(mollusk)
+        .bench(("redeem_with_fee", &with_fee.instruction, &with_fee.accounts))
+        .bench(("redeem_fee_waived", &fee_waived.instruction, &fee_waived.accounts))
+        .bench(("initialize_position", &initialize.instruction, &initialize.accounts))
+        .bench(("update_position", &update.instruction, &update.accounts))
+        .must_pass(true)
+        .out_dir("../../target/benches")
+        .execute();
+}
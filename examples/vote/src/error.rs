@@ -0,0 +1,89 @@
+// Define errors using the define_errors! macro
+// This will be parsed by the build script and generated in generated.rs
+define_errors! {
+    PTokenProgramError,
+    /// Unknown instruction discriminator
+    InvalidDiscriminator = 6001,
+    /// Platform account is not the platform PDA
+    PlatformKeyIncorrect = 6002,
+    /// Fee vault is not the platform's vault PDA
+    VaultKeyIncorrect = 6003,
+    /// Vote vault is not the vote's vault PDA
+    VoteVaultKeyIncorrect = 6004,
+    /// Position is not the PDA for this vote and authority
+    PositionKeyIncorrect = 6005,
+    /// Vote vault token account is not the vault's associated token account
+    VoteVaultTokenAccountIncorrect = 6006,
+    /// The vote has already ended
+    VoteHasAlreadyEnded = 6007,
+    /// The vote is still running
+    VoteIsStillRunning = 6008,
+    /// The vote ended in a tie
+    VoteWasTied = 6009,
+    /// The position is on the losing side
+    DidNotVoteForWinningSide = 6010,
+    /// Side must be below the vote's outcome count, 0 (false) or 1 (true) on two-outcome votes
+    InvalidSide = 6011,
+    /// The vote vault balance did not grow by the transferred amount
+    VaultBalanceMismatch = 6012,
+    /// The platform was re-entered while an adapter call was in progress
+    ReentrancyDetected = 6013,
+    /// Update flags are empty or contain unknown bits
+    InvalidUpdateFlags = 6014,
+    /// Fee is above the platform maximum
+    FeeTooHigh = 6015,
+    /// Not enough stake took part, refund the position instead
+    QuorumNotMet = 6016,
+    /// Enough stake took part, redeem the position instead
+    QuorumWasMet = 6017,
+    /// Signer is not the vote's resolver
+    ResolverMismatch = 6018,
+    /// The resolver can still resolve the vote
+    AwaitingResolution = 6019,
+    /// The resolution window has passed, the vote resolves by tally
+    ResolutionWindowClosed = 6020,
+    /// The resolver already picked an outcome
+    AlreadyResolved = 6021,
+    /// Deadline kind must be 0 (unix timestamp), 1 (slot) or 2 (epoch)
+    InvalidDeadlineKind = 6022,
+    /// Fee token account is not in the platform's fee mint
+    FeeMintMismatch = 6023,
+    /// Fee ratio denominator must be nonzero when a fee mint is set
+    InvalidFeeRatio = 6024,
+    /// Platform account already has the current layout
+    PlatformAlreadyMigrated = 6025,
+    /// The position would hold more than the vote's `max_position`
+    PositionCapExceeded = 6026,
+    /// The vote is whitelist-only and the wallet has no whitelist entry
+    NotWhitelisted = 6027,
+    /// Signer is not the vote's creator
+    CreatorMismatch = 6028,
+    /// Whitelist entry is not the PDA for this vote and wallet
+    WhitelistEntryKeyIncorrect = 6029,
+    /// Token account's delegate is not the authority
+    DelegateMismatch = 6030,
+    /// Token account's delegated amount is below what the position spends from it
+    InsufficientDelegation = 6031,
+    /// Outcome count must be 2 to 8
+    InvalidOutcomeCount = 6032,
+    /// Vote account already has the current layout
+    VoteAlreadyMigrated = 6033,
+    /// Cranker is not the platform authority and the position doesn't allow cranks
+    CrankNotAllowed = 6034,
+    /// Token account is not owned by the beneficiary
+    BeneficiaryMismatch = 6035,
+    /// Vote ends sooner than the platform's `min_duration`
+    VoteTooShort = 6036,
+    /// Vote ends later than the platform's `max_duration`
+    VoteTooLong = 6037,
+    /// Minimum vote duration is above the maximum
+    InvalidDurationBounds = 6038,
+    /// Treasury account is not the platform's treasury
+    TreasuryKeyIncorrect = 6039,
+    /// Claim is more than the fees the treasury has recorded
+    ClaimExceedsFees = 6040,
+    /// The same token account was passed twice
+    DuplicateTokenAccount = 6041,
+    /// Amount is zero, the instruction would move nothing
+    ZeroAmount = 6042,
+}
@@ -0,0 +1,51 @@
+//! Events the vote instructions log for indexers, see `emit_event!`
+//!
+//! Every stake change carries the position's new amount and its outcome's new
+//! tally, so an indexer can follow each vote's tallies from the logs alone.
+
+use jiminy::{define_events, time::Deadline, LeU64, PubkeyBytes};
+
+define_events! {
+    /// Logged by InitializeVote, InitializeVoteWithPayer and InitializeMultiOutcomeVote
+    pub struct VoteCreated {
+        pub vote: PubkeyBytes,
+        pub token: PubkeyBytes,
+        pub creator: PubkeyBytes,
+        // Positions open up to and including the deadline, a timestamp, slot or epoch
+        pub deadline: Deadline,
+        // Minimum total stake for the vote to resolve, below it positions are refunded
+        pub min_total: LeU64,
+        pub outcome_count: u8,
+    }
+
+    /// Logged by InitializePosition and UpdatePosition
+    pub struct PositionChanged {
+        pub vote: PubkeyBytes,
+        pub authority: PubkeyBytes,
+        // Outcome the stake is on
+        pub side: u8,
+        // The position's stake after the change
+        pub new_amount: LeU64,
+        // The outcome's tally after the change
+        pub tally: LeU64,
+    }
+
+    /// Logged by RedeemWinnings and RedeemFor for every payout
+    pub struct WinningsRedeemed {
+        pub vote: PubkeyBytes,
+        pub authority: PubkeyBytes,
+        // Tokens paid to the authority
+        pub reward: LeU64,
+        // Tokens paid to the platform's vault
+        pub fee: LeU64,
+        // Reward still owed, zero once the position is closed
+        pub remaining: LeU64,
+    }
+
+    /// Logged by RefundPosition, the vote missed its quorum and the stake went back
+    pub struct VoteCancelled {
+        pub vote: PubkeyBytes,
+        pub authority: PubkeyBytes,
+        pub refund: LeU64,
+    }
+}
@@ -0,0 +1,2178 @@
+use shank::ShankInstruction;
+use shank::ShankType;
+use pinocchio::program_error::ProgramError;
+
+/// Added to every declared error code, `errors.base` in jiminy.toml
+pub const ERROR_BASE: u32 = 0;
+
+/// The `E` a `Custom(code)` from `program_id` stands for
+///
+/// `None` if another program returned it or `E` has no such code.
+pub fn decode_error<E: TryFrom<u32>>(
+    program_id: &pinocchio::pubkey::Pubkey,
+    code: u32,
+) -> Option<E> {
+    if program_id != &crate::ID {
+        return None;
+    }
+    E::try_from(code).ok()
+}
+
+// Generated error enum: PTokenProgramError
+#[derive(Clone, Debug, PartialEq, ShankType)]
+#[non_exhaustive]
+pub enum PTokenProgramError {
+    /// Unknown instruction discriminator
+    InvalidDiscriminator = 6001,
+    /// Platform account is not the platform PDA
+    PlatformKeyIncorrect = 6002,
+    /// Fee vault is not the platform's vault PDA
+    VaultKeyIncorrect = 6003,
+    /// Vote vault is not the vote's vault PDA
+    VoteVaultKeyIncorrect = 6004,
+    /// Position is not the PDA for this vote and authority
+    PositionKeyIncorrect = 6005,
+    /// Vote vault token account is not the vault's associated token account
+    VoteVaultTokenAccountIncorrect = 6006,
+    /// The vote has already ended
+    VoteHasAlreadyEnded = 6007,
+    /// The vote is still running
+    VoteIsStillRunning = 6008,
+    /// The vote ended in a tie
+    VoteWasTied = 6009,
+    /// The position is on the losing side
+    DidNotVoteForWinningSide = 6010,
+    /// Side must be below the vote's outcome count, 0 (false) or 1 (true) on two-outcome votes
+    InvalidSide = 6011,
+    /// The vote vault balance did not grow by the transferred amount
+    VaultBalanceMismatch = 6012,
+    /// The platform was re-entered while an adapter call was in progress
+    ReentrancyDetected = 6013,
+    /// Update flags are empty or contain unknown bits
+    InvalidUpdateFlags = 6014,
+    /// Fee is above the platform maximum
+    FeeTooHigh = 6015,
+    /// Not enough stake took part, refund the position instead
+    QuorumNotMet = 6016,
+    /// Enough stake took part, redeem the position instead
+    QuorumWasMet = 6017,
+    /// Signer is not the vote's resolver
+    ResolverMismatch = 6018,
+    /// The resolver can still resolve the vote
+    AwaitingResolution = 6019,
+    /// The resolution window has passed, the vote resolves by tally
+    ResolutionWindowClosed = 6020,
+    /// The resolver already picked an outcome
+    AlreadyResolved = 6021,
+    /// Deadline kind must be 0 (unix timestamp), 1 (slot) or 2 (epoch)
+    InvalidDeadlineKind = 6022,
+    /// Fee token account is not in the platform's fee mint
+    FeeMintMismatch = 6023,
+    /// Fee ratio denominator must be nonzero when a fee mint is set
+    InvalidFeeRatio = 6024,
+    /// Platform account already has the current layout
+    PlatformAlreadyMigrated = 6025,
+    /// The position would hold more than the vote's `max_position`
+    PositionCapExceeded = 6026,
+    /// The vote is whitelist-only and the wallet has no whitelist entry
+    NotWhitelisted = 6027,
+    /// Signer is not the vote's creator
+    CreatorMismatch = 6028,
+    /// Whitelist entry is not the PDA for this vote and wallet
+    WhitelistEntryKeyIncorrect = 6029,
+    /// Token account's delegate is not the authority
+    DelegateMismatch = 6030,
+    /// Token account's delegated amount is below what the position spends from it
+    InsufficientDelegation = 6031,
+    /// Outcome count must be 2 to 8
+    InvalidOutcomeCount = 6032,
+    /// Vote account already has the current layout
+    VoteAlreadyMigrated = 6033,
+    /// Cranker is not the platform authority and the position doesn't allow cranks
+    CrankNotAllowed = 6034,
+    /// Token account is not owned by the beneficiary
+    BeneficiaryMismatch = 6035,
+    /// Vote ends sooner than the platform's `min_duration`
+    VoteTooShort = 6036,
+    /// Vote ends later than the platform's `max_duration`
+    VoteTooLong = 6037,
+    /// Minimum vote duration is above the maximum
+    InvalidDurationBounds = 6038,
+    /// Treasury account is not the platform's treasury
+    TreasuryKeyIncorrect = 6039,
+    /// Claim is more than the fees the treasury has recorded
+    ClaimExceedsFees = 6040,
+    /// The same token account was passed twice
+    DuplicateTokenAccount = 6041,
+    /// Amount is zero, the instruction would move nothing
+    ZeroAmount = 6042,
+}
+
+impl core::fmt::Display for PTokenProgramError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::InvalidDiscriminator => "Unknown instruction discriminator",
+            Self::PlatformKeyIncorrect => "Platform account is not the platform PDA",
+            Self::VaultKeyIncorrect => "Fee vault is not the platform's vault PDA",
+            Self::VoteVaultKeyIncorrect => "Vote vault is not the vote's vault PDA",
+            Self::PositionKeyIncorrect => "Position is not the PDA for this vote and authority",
+            Self::VoteVaultTokenAccountIncorrect => "Vote vault token account is not the vault's associated token account",
+            Self::VoteHasAlreadyEnded => "The vote has already ended",
+            Self::VoteIsStillRunning => "The vote is still running",
+            Self::VoteWasTied => "The vote ended in a tie",
+            Self::DidNotVoteForWinningSide => "The position is on the losing side",
+            Self::InvalidSide => "Side must be below the vote's outcome count, 0 (false) or 1 (true) on two-outcome votes",
+            Self::VaultBalanceMismatch => "The vote vault balance did not grow by the transferred amount",
+            Self::ReentrancyDetected => "The platform was re-entered while an adapter call was in progress",
+            Self::InvalidUpdateFlags => "Update flags are empty or contain unknown bits",
+            Self::FeeTooHigh => "Fee is above the platform maximum",
+            Self::QuorumNotMet => "Not enough stake took part, refund the position instead",
+            Self::QuorumWasMet => "Enough stake took part, redeem the position instead",
+            Self::ResolverMismatch => "Signer is not the vote's resolver",
+            Self::AwaitingResolution => "The resolver can still resolve the vote",
+            Self::ResolutionWindowClosed => "The resolution window has passed, the vote resolves by tally",
+            Self::AlreadyResolved => "The resolver already picked an outcome",
+            Self::InvalidDeadlineKind => "Deadline kind must be 0 (unix timestamp), 1 (slot) or 2 (epoch)",
+            Self::FeeMintMismatch => "Fee token account is not in the platform's fee mint",
+            Self::InvalidFeeRatio => "Fee ratio denominator must be nonzero when a fee mint is set",
+            Self::PlatformAlreadyMigrated => "Platform account already has the current layout",
+            Self::PositionCapExceeded => "The position would hold more than the vote's `max_position`",
+            Self::NotWhitelisted => "The vote is whitelist-only and the wallet has no whitelist entry",
+            Self::CreatorMismatch => "Signer is not the vote's creator",
+            Self::WhitelistEntryKeyIncorrect => "Whitelist entry is not the PDA for this vote and wallet",
+            Self::DelegateMismatch => "Token account's delegate is not the authority",
+            Self::InsufficientDelegation => "Token account's delegated amount is below what the position spends from it",
+            Self::InvalidOutcomeCount => "Outcome count must be 2 to 8",
+            Self::VoteAlreadyMigrated => "Vote account already has the current layout",
+            Self::CrankNotAllowed => "Cranker is not the platform authority and the position doesn't allow cranks",
+            Self::BeneficiaryMismatch => "Token account is not owned by the beneficiary",
+            Self::VoteTooShort => "Vote ends sooner than the platform's `min_duration`",
+            Self::VoteTooLong => "Vote ends later than the platform's `max_duration`",
+            Self::InvalidDurationBounds => "Minimum vote duration is above the maximum",
+            Self::TreasuryKeyIncorrect => "Treasury account is not the platform's treasury",
+            Self::ClaimExceedsFees => "Claim is more than the fees the treasury has recorded",
+            Self::DuplicateTokenAccount => "The same token account was passed twice",
+            Self::ZeroAmount => "Amount is zero, the instruction would move nothing",
+        })
+    }
+}
+
+impl From<PTokenProgramError> for ProgramError {
+    fn from(e: PTokenProgramError) -> Self {
+        Self::Custom(e as u32)
+    }
+}
+
+impl jiminy::dispatch::HasInvalidDiscriminator for PTokenProgramError {
+    const INVALID_DISCRIMINATOR: Self = Self::InvalidDiscriminator;
+}
+
+impl TryFrom<u32> for PTokenProgramError {
+    type Error = u32;
+
+    fn try_from(code: u32) -> Result<Self, u32> {
+        match code {
+            6001 => Ok(Self::InvalidDiscriminator),
+            6002 => Ok(Self::PlatformKeyIncorrect),
+            6003 => Ok(Self::VaultKeyIncorrect),
+            6004 => Ok(Self::VoteVaultKeyIncorrect),
+            6005 => Ok(Self::PositionKeyIncorrect),
+            6006 => Ok(Self::VoteVaultTokenAccountIncorrect),
+            6007 => Ok(Self::VoteHasAlreadyEnded),
+            6008 => Ok(Self::VoteIsStillRunning),
+            6009 => Ok(Self::VoteWasTied),
+            6010 => Ok(Self::DidNotVoteForWinningSide),
+            6011 => Ok(Self::InvalidSide),
+            6012 => Ok(Self::VaultBalanceMismatch),
+            6013 => Ok(Self::ReentrancyDetected),
+            6014 => Ok(Self::InvalidUpdateFlags),
+            6015 => Ok(Self::FeeTooHigh),
+            6016 => Ok(Self::QuorumNotMet),
+            6017 => Ok(Self::QuorumWasMet),
+            6018 => Ok(Self::ResolverMismatch),
+            6019 => Ok(Self::AwaitingResolution),
+            6020 => Ok(Self::ResolutionWindowClosed),
+            6021 => Ok(Self::AlreadyResolved),
+            6022 => Ok(Self::InvalidDeadlineKind),
+            6023 => Ok(Self::FeeMintMismatch),
+            6024 => Ok(Self::InvalidFeeRatio),
+            6025 => Ok(Self::PlatformAlreadyMigrated),
+            6026 => Ok(Self::PositionCapExceeded),
+            6027 => Ok(Self::NotWhitelisted),
+            6028 => Ok(Self::CreatorMismatch),
+            6029 => Ok(Self::WhitelistEntryKeyIncorrect),
+            6030 => Ok(Self::DelegateMismatch),
+            6031 => Ok(Self::InsufficientDelegation),
+            6032 => Ok(Self::InvalidOutcomeCount),
+            6033 => Ok(Self::VoteAlreadyMigrated),
+            6034 => Ok(Self::CrankNotAllowed),
+            6035 => Ok(Self::BeneficiaryMismatch),
+            6036 => Ok(Self::VoteTooShort),
+            6037 => Ok(Self::VoteTooLong),
+            6038 => Ok(Self::InvalidDurationBounds),
+            6039 => Ok(Self::TreasuryKeyIncorrect),
+            6040 => Ok(Self::ClaimExceedsFees),
+            6041 => Ok(Self::DuplicateTokenAccount),
+            6042 => Ok(Self::ZeroAmount),
+            _ => Err(code),
+        }
+    }
+}
+
+extern crate alloc;
+use alloc::vec::Vec;
+use alloc::string::String;
+
+#[repr(u8)]
+#[derive(Clone, Debug, PartialEq, ShankInstruction)]
+pub enum ProgramInstructions {
+    /// Create the platform PDA with its fee and treasury and fund its fee vault
+    #[account(0, signer, writable, name = "authority", desc = "Authority of the vault")]
+    #[account(1, writable, name = "platform", desc = "Platform pda key")]
+    #[account(2, writable, name = "vault", desc = "platforms fee vault pda")]
+    #[account(3, writable, name = "treasury", desc = "Treasury pda counting the fees in the vault")]
+    #[account(4, name = "system_program", desc = "System program")]
+    InitializePlatform {
+        /// Fee in basis points, little-endian, at most MAX_FEE_BPS
+        fee: [u8; 2],
+        /// Bump of the platform PDA
+        platform_bump: u8,
+        /// Bump of the fee vault PDA
+        vault_bump: u8,
+        /// Bump of the treasury PDA
+        treasury_bump: u8,
+    },
+
+    /// Create a true/false vote on a token with its vault and vault token account
+    #[account(0, signer, writable, name = "authority", desc = "Authority of the vault")]
+    #[account(1, signer, writable, name = "vote", desc = "new vote account")]
+    #[account(2, writable, name = "vault", desc = "platforms fee vault pda")]
+    #[account(3, writable, name = "vote_vault", desc = "votes vault pda")]
+    #[account(4, writable, name = "vote_vault_token_account", desc = "votes token account for storing funds")]
+    #[account(5, writable, name = "platform", desc = "Platform pda key, counts the vote in its stats")]
+    #[account(6, writable, name = "treasury", desc = "Treasury pda, counts the fee")]
+    #[account(7, name = "token", desc = "vote token")]
+    #[account(8, name = "rent", desc = "Rent sysvar")]
+    #[account(9, name = "system_program", desc = "System program")]
+    #[account(10, name = "token_program", desc = "Token program")]
+    #[account(11, name = "associated_token_program", desc = "Associated Token program")]
+    InitializeVote {
+        /// 0 counts `time_to_add` in seconds, 1 in slots, 2 in epochs
+        deadline_kind: u8,
+        /// How long the vote runs, little-endian u64 in units of `deadline_kind`
+        time_to_add: [u8; 8],
+        /// Minimum total stake for the vote to resolve, below it positions are refunded
+        min_total: [u8; 8],
+        /// Key that picks the outcome, all zeros to resolve by tally
+        resolver: [u8; 32],
+        /// Most stake one position can hold, zero for no cap
+        max_position: [u8; 8],
+        /// Only let wallets added with AddToWhitelist open positions
+        whitelist_only: bool,
+        label: Vec<u8>,
+    },
+
+    /// Open a position on one outcome of a vote, paying the platform fee
+    /// Transfers `init_amount` from `authority_token_account` to `vote_vault_token_account`, authority `authority`
+    /// Transfers `fee_amount` from `fee_token_account` to `vault_token_account`, authority `authority`
+    /// Expects `vote_vault_token_account` tokens to increase by `init_amount`
+    /// Expects `vault_token_account` tokens to increase by `fee_amount`
+    /// Expects `authority_token_account` tokens to decrease by `authority_spend`
+    #[account(0, signer, writable, name = "authority", desc = "Authority of the vault")]
+    #[account(1, writable, name = "vote", desc = "vote account")]
+    #[account(2, writable, name = "vote_vault_token_account", desc = "votes token account for storing funds")]
+    #[account(3, writable, name = "authority_token_account", desc = "authorities token account for storing funds")]
+    #[account(4, writable, name = "fee_token_account", desc = "authorities token account paying the fee, in the fee mint if set")]
+    #[account(5, writable, name = "vault_token_account", desc = "vault token account for storing funds")]
+    #[account(6, writable, name = "position", desc = "position pda for voting on one side")]
+    #[account(7, writable, name = "platform", desc = "Platform pda key, counts the stake and pays the position rent when it sponsors rent")]
+    #[account(8, writable, name = "treasury", desc = "Treasury pda, counts the fee")]
+    #[account(9, name = "vault", desc = "platforms fee vault pda")]
+    #[account(10, name = "token", desc = "vote token")]
+    #[account(11, name = "vote_vault", desc = "votes vault pda")]
+    #[account(12, name = "whitelist_entry", desc = "whitelist entry pda for vote and authority, checked on whitelist-only votes")]
+    InitializePosition {
+        /// Stake in vote token base units
+        amount: [u8; 8],
+        /// Outcome the stake goes to, 0 (false) or 1 (true) on two-outcome votes
+        side: u8,
+        /// 0 if `authority` owns the token accounts, 1 if it spends as their delegate
+        funding_mode: FundingMode,
+        /// Nonzero to let anyone redeem the position for `authority` with RedeemFor
+        allow_crank: u8,
+    },
+
+    /// Add stake to an existing position, paying the platform fee
+    /// Transfers `update_amount` from `authority_token_account` to `vote_vault_token_account`, authority `authority`
+    /// Transfers `fee_amount` from `fee_token_account` to `vault_token_account`, authority `authority`
+    /// Expects `vote_vault_token_account` tokens to increase by `update_amount`
+    /// Expects `vault_token_account` tokens to increase by `fee_amount`
+    /// Expects `authority_token_account` tokens to decrease by `authority_spend`
+    #[account(0, signer, writable, name = "authority", desc = "Authority of the vault")]
+    #[account(1, writable, name = "vote", desc = "vote account")]
+    #[account(2, writable, name = "vote_vault", desc = "votes vault pda")]
+    #[account(3, writable, name = "vote_vault_token_account", desc = "votes token account for storing funds")]
+    #[account(4, writable, name = "authority_token_account", desc = "authorities token account for storing funds")]
+    #[account(5, writable, name = "fee_token_account", desc = "authorities token account paying the fee, in the fee mint if set")]
+    #[account(6, writable, name = "vault_token_account", desc = "vault token account for storing funds")]
+    #[account(7, writable, name = "position", desc = "position pda for voting on one side")]
+    #[account(8, writable, name = "platform", desc = "Platform pda key, counts the stake in its stats")]
+    #[account(9, writable, name = "treasury", desc = "Treasury pda, counts the fee")]
+    #[account(10, name = "vault", desc = "platforms fee vault pda")]
+    #[account(11, name = "token", desc = "vote token")]
+    UpdatePosition {
+        /// Stake to add, in vote token base units
+        amount: [u8; 8],
+        /// 0 if `authority` owns the token accounts, 1 if it spends as their delegate
+        funding_mode: FundingMode,
+    },
+
+    /// Pay a winning position its stake plus its share of the losing outcomes, or a chunk of it
+    #[account(0, signer, writable, name = "authority", desc = "Authority of the vault")]
+    #[account(1, writable, name = "vote", desc = "vote account")]
+    #[account(2, writable, name = "vote_vault_token_account", desc = "votes token account for storing funds")]
+    #[account(3, writable, name = "authority_token_account", desc = "authorities token account for storing funds")]
+    #[account(4, writable, name = "vault_token_account", desc = "vault token account for storing funds")]
+    #[account(5, writable, name = "position", desc = "position pda for voting on one side")]
+    #[account(6, writable, name = "claim_receipt", desc = "receipt pda for vote and authority, created by the last redemption")]
+    #[account(7, writable, name = "platform", desc = "Platform pda key, counts the fee in its stats")]
+    #[account(8, writable, name = "treasury", desc = "Treasury pda, counts the fee")]
+    #[account(9, writable, name = "vault", desc = "platforms fee vault pda")]
+    #[account(10, name = "token", desc = "vote token")]
+    #[account(11, name = "vote_vault", desc = "votes vault pda")]
+    RedeemWinnings {
+        /// Most to pay out now, zero for everything left
+        max_amount: [u8; 8],
+    },
+
+    /// Call an adapter program with the platform PDA, guarded against reentrancy
+    #[account(0, signer, name = "authority", desc = "Authority calling the adapter")]
+    #[account(1, writable, name = "platform", desc = "Platform pda key, holds the reentrancy lock")]
+    #[account(2, name = "adapter_program", desc = "Adapter program to call")]
+    #[account(3, name = "vote_program", desc = "This program, so the adapter can call back into it")]
+    CallAdapter {
+        adapter_data: Vec<u8>,
+    },
+
+    /// Return the whole stake of a position on a vote that missed its quorum
+    /// Transfers `refund_amount` from `vote_vault_token_account` to `authority_token_account`, authority `vote_vault` (PDA signed)
+    #[account(0, signer, writable, name = "authority", desc = "Authority of the position")]
+    #[account(1, writable, name = "vote_vault_token_account", desc = "votes token account for storing funds")]
+    #[account(2, writable, name = "authority_token_account", desc = "authorities token account receiving the refund")]
+    #[account(3, writable, name = "position", desc = "position pda being refunded")]
+    #[account(4, name = "vote", desc = "vote account")]
+    #[account(5, name = "vote_vault", desc = "votes vault pda")]
+    RefundPosition {
+    },
+
+    /// Let the vote's resolver pick the winning outcome after the deadline
+    #[account(0, signer, name = "resolver", desc = "Resolver set when the vote was created")]
+    #[account(1, writable, name = "vote", desc = "vote account")]
+    ResolveVote {
+        /// Winning outcome, 1 for true and 0 for false on two-outcome votes
+        outcome: u8,
+    },
+
+    /// Let a wallet open a position on a whitelist-only vote
+    #[account(0, signer, writable, name = "creator", desc = "Creator of the vote, pays the entry rent")]
+    #[account(1, writable, name = "whitelist_entry", desc = "whitelist entry pda for vote and wallet")]
+    #[account(2, name = "vote", desc = "vote account")]
+    #[account(3, name = "system_program", desc = "System program")]
+    AddToWhitelist {
+        /// Wallet allowed to open a position
+        wallet: [u8; 32],
+    },
+
+    /// Return the platform's vote, volume and fee totals
+    /// Returns `PlatformStats` as return data
+    #[account(0, name = "platform", desc = "Platform pda key")]
+    ViewPlatformStats {
+    },
+
+    /// Refund and close many positions on a vote that missed its quorum, the rent goes to the cranker
+    /// Remaining accounts `positions`: (position, owner token account) pairs, the owner being the position's authority
+    #[account(0, signer, writable, name = "cranker", desc = "Anyone, receives the closed positions' rent")]
+    #[account(1, writable, name = "vote_vault_token_account", desc = "votes token account for storing funds")]
+    #[account(2, name = "vote", desc = "vote account")]
+    #[account(3, name = "vote_vault", desc = "votes vault pda")]
+    BatchClosePositions {
+    },
+
+    /// Create a true/false vote like InitializeVote, with the rent, vault deposit and fee paid by a separate `payer`
+    #[account(0, signer, writable, name = "payer", desc = "Pays for the vote, its vault and the platform fee")]
+    #[account(1, signer, name = "authority", desc = "Creator of the vote")]
+    #[account(2, signer, writable, name = "vote", desc = "new vote account")]
+    #[account(3, writable, name = "vault", desc = "platforms fee vault pda")]
+    #[account(4, writable, name = "vote_vault", desc = "votes vault pda")]
+    #[account(5, writable, name = "vote_vault_token_account", desc = "votes token account for storing funds")]
+    #[account(6, writable, name = "platform", desc = "Platform pda key, counts the vote in its stats")]
+    #[account(7, writable, name = "treasury", desc = "Treasury pda, counts the fee")]
+    #[account(8, name = "token", desc = "vote token")]
+    #[account(9, name = "rent", desc = "Rent sysvar")]
+    #[account(10, name = "system_program", desc = "System program")]
+    #[account(11, name = "token_program", desc = "Token program")]
+    #[account(12, name = "associated_token_program", desc = "Associated Token program")]
+    InitializeVoteWithPayer {
+        /// 0 counts `time_to_add` in seconds, 1 in slots, 2 in epochs
+        deadline_kind: u8,
+        /// How long the vote runs, little-endian u64 in units of `deadline_kind`
+        time_to_add: [u8; 8],
+        /// Minimum total stake for the vote to resolve, below it positions are refunded
+        min_total: [u8; 8],
+        /// Key that picks the outcome, all zeros to resolve by tally
+        resolver: [u8; 32],
+        /// Most stake one position can hold, zero for no cap
+        max_position: [u8; 8],
+        /// Only let wallets added with AddToWhitelist open positions
+        whitelist_only: bool,
+        label: Vec<u8>,
+    },
+
+    /// Create a vote like InitializeVote, with 2 to 8 outcomes instead of true and false
+    #[account(0, signer, writable, name = "authority", desc = "Authority of the vault")]
+    #[account(1, signer, writable, name = "vote", desc = "new vote account")]
+    #[account(2, writable, name = "vault", desc = "platforms fee vault pda")]
+    #[account(3, writable, name = "vote_vault", desc = "votes vault pda")]
+    #[account(4, writable, name = "vote_vault_token_account", desc = "votes token account for storing funds")]
+    #[account(5, writable, name = "platform", desc = "Platform pda key, counts the vote in its stats")]
+    #[account(6, writable, name = "treasury", desc = "Treasury pda, counts the fee")]
+    #[account(7, name = "token", desc = "vote token")]
+    #[account(8, name = "rent", desc = "Rent sysvar")]
+    #[account(9, name = "system_program", desc = "System program")]
+    #[account(10, name = "token_program", desc = "Token program")]
+    #[account(11, name = "associated_token_program", desc = "Associated Token program")]
+    InitializeMultiOutcomeVote {
+        /// Number of outcomes positions pick from, 2 to 8
+        outcome_count: u8,
+        /// 0 counts `time_to_add` in seconds, 1 in slots, 2 in epochs
+        deadline_kind: u8,
+        /// How long the vote runs, little-endian u64 in units of `deadline_kind`
+        time_to_add: [u8; 8],
+        /// Minimum total stake for the vote to resolve, below it positions are refunded
+        min_total: [u8; 8],
+        /// Key that picks the outcome, all zeros to resolve by tally
+        resolver: [u8; 32],
+        /// Most stake one position can hold, zero for no cap
+        max_position: [u8; 8],
+        /// Only let wallets added with AddToWhitelist open positions
+        whitelist_only: bool,
+        label: Vec<u8>,
+    },
+
+    /// Grow a vote created before multi-outcome tallies or the resolver tag to the current layout
+    #[account(0, signer, writable, name = "payer", desc = "Anyone, pays the extra rent")]
+    #[account(1, writable, name = "vote", desc = "vote account")]
+    #[account(2, name = "system_program", desc = "System program")]
+    MigrateVote {
+    },
+
+    /// Redeem a winning position for its authority, paid to the authority's token account
+    #[account(0, signer, writable, name = "cranker", desc = "Platform authority, or anyone if the position allows cranks; pays the receipt rent")]
+    #[account(1, writable, name = "vote", desc = "vote account")]
+    #[account(2, writable, name = "vote_vault_token_account", desc = "votes token account for storing funds")]
+    #[account(3, writable, name = "beneficiary_token_account", desc = "beneficiary's token account receiving the winnings")]
+    #[account(4, writable, name = "vault_token_account", desc = "vault token account for storing funds")]
+    #[account(5, writable, name = "position", desc = "beneficiary's position pda")]
+    #[account(6, writable, name = "claim_receipt", desc = "receipt pda for vote and beneficiary, created by the redemption")]
+    #[account(7, writable, name = "platform", desc = "Platform pda key, counts the fee in its stats")]
+    #[account(8, writable, name = "treasury", desc = "Treasury pda, counts the fee")]
+    #[account(9, writable, name = "vault", desc = "platforms fee vault pda")]
+    #[account(10, name = "beneficiary", desc = "Authority of the position, receives the winnings")]
+    #[account(11, name = "token", desc = "vote token")]
+    #[account(12, name = "vote_vault", desc = "votes vault pda")]
+    RedeemFor {
+    },
+
+    /// Relabel a vote and change its position cap or whitelist before it ends
+    #[account(0, signer, name = "creator", desc = "Creator of the vote")]
+    #[account(1, writable, name = "vote", desc = "vote account")]
+    UpdateVoteSettings {
+        /// New label, at most 64 bytes
+        label: String,
+        /// New position cap, zero for no cap
+        max_position: Option<u64>,
+        /// Whether only whitelisted wallets can open positions
+        whitelist_only: Option<bool>,
+    },
+
+    /// Rebate fees to a wallet with a voucher the platform authority signed off-chain
+    #[account(0, signer, name = "wallet", desc = "Wallet the voucher is for, sends the transaction")]
+    #[account(1, signer, name = "authority", desc = "Authority of the platform, signed the voucher off-chain")]
+    #[account(2, writable, name = "treasury", desc = "Treasury pda, the rebate comes off its counters")]
+    #[account(3, writable, name = "vault_token_account", desc = "vault token account the rebate comes from")]
+    #[account(4, writable, name = "wallet_token_account", desc = "wallet's token account receiving the rebate")]
+    #[account(5, name = "platform", desc = "Platform pda key")]
+    #[account(6, name = "vault", desc = "platforms fee vault pda")]
+    #[account(7, name = "token_program", desc = "Token program")]
+    RedeemFeeVoucher {
+        /// Tokens to rebate from `vault_token_account`, little-endian
+        rebate: [u8; 8],
+        /// Last slot the voucher can be redeemed in, little-endian
+        expires_at_slot: [u8; 8],
+    },
+
+    /// Change the platform fee, fee mint, rent sponsoring, vote durations or authority, selected by `flags`
+    /// Also accepts the deprecated discriminators 1
+    #[account(0, signer, writable, name = "authority", desc = "Authority of the vault")]
+    #[account(1, writable, name = "platform", desc = "Platform pda key")]
+    #[account(2, name = "new_authority", desc = "New authority of the vault, must sign when UPDATE_AUTHORITY is set")]
+    #[account(3, name = "vault", desc = "platforms fee vault pda")]
+    #[account(4, name = "rent", desc = "Rent sysvar")]
+    #[account(5, name = "system_program", desc = "System program")]
+    UpdatePlatform {
+        /// UPDATE_FEE, UPDATE_AUTHORITY, UPDATE_FEE_MINT, UPDATE_SPONSOR_RENT and UPDATE_DURATIONS bits
+        flags: u8,
+        /// Fee in basis points, little-endian, read with UPDATE_FEE
+        new_fee: [u8; 2],
+        /// Mint fees are paid in, all zeros for the vote token, read with UPDATE_FEE_MINT
+        new_fee_mint: [u8; 32],
+        /// Fee-mint tokens per vote token numerator, little-endian
+        new_fee_ratio_num: [u8; 8],
+        /// Fee-mint tokens per vote token denominator, little-endian, nonzero with a fee mint
+        new_fee_ratio_den: [u8; 8],
+        /// 1 to pay position rent from the platform account, read with UPDATE_SPONSOR_RENT
+        new_sponsor_rent: bool,
+        /// Shortest vote in seconds, little-endian, zero for no minimum, read with UPDATE_DURATIONS
+        new_min_duration: [u8; 8],
+        /// Longest vote in seconds, little-endian, zero for no maximum
+        new_max_duration: [u8; 8],
+    },
+
+    /// Hand the platform to the program's upgrade authority and clear its fee
+    #[account(0, signer, name = "upgrade_authority", desc = "Upgrade authority of the program")]
+    #[account(1, writable, name = "platform", desc = "Platform pda key")]
+    #[account(2, name = "programdata", desc = "Programdata account of this program")]
+    #[account(3, name = "program", desc = "This program")]
+    AdminResetPlatform {
+    },
+
+    /// Grow a platform created before the fee mint, sponsor, stats, vault, fee policy, duration or treasury fields to the current layout
+    #[account(0, signer, writable, name = "authority", desc = "Authority of the platform, pays the extra rent")]
+    #[account(1, writable, name = "platform", desc = "Platform pda key")]
+    #[account(2, writable, name = "treasury", desc = "Treasury pda created by the migration")]
+    #[account(3, name = "system_program", desc = "System program")]
+    MigratePlatform {
+    },
+
+    /// Change how the platform computes position and redemption fees
+    #[account(0, signer, name = "authority", desc = "Authority of the platform")]
+    #[account(1, writable, name = "platform", desc = "Platform pda key")]
+    SetFeePolicy {
+        /// Bps, Flat or Tiered
+        kind: FeePolicyKind,
+        /// Basis points, little-endian, at most MAX_FEE_BPS; ignored for Flat
+        bps: [u8; 2],
+        /// Little-endian: the minimum fee (Bps), the fee (Flat) or the tier threshold (Tiered)
+        first: [u8; 8],
+        /// Little-endian: the fee-free amount below which nothing is charged (Bps, Flat) or
+        /// the basis points above the threshold (Tiered)
+        second: [u8; 8],
+    },
+
+    /// Withdraw collected fees from the vault, at most what the treasury has counted
+    #[account(0, signer, name = "authority", desc = "Authority of the platform")]
+    #[account(1, writable, name = "treasury", desc = "Treasury pda, the claim comes off its counters")]
+    #[account(2, writable, name = "vault", desc = "platforms fee vault pda")]
+    #[account(3, writable, name = "vault_token_account", desc = "vault token account the tokens come from")]
+    #[account(4, writable, name = "destination", desc = "Receives the lamports")]
+    #[account(5, writable, name = "destination_token_account", desc = "Receives the tokens")]
+    #[account(6, name = "platform", desc = "Platform pda key")]
+    #[account(7, name = "token_program", desc = "Token program")]
+    ClaimFees {
+        /// Lamports to withdraw, little-endian
+        lamports: [u8; 8],
+        /// Tokens to withdraw from `vault_token_account`, little-endian
+        tokens: [u8; 8],
+    },
+
+    /// Record how far the vault's balances are from the treasury's fee counters
+    /// Remaining accounts `vault_token_accounts`: Every token account of the vault, each once
+    #[account(0, writable, name = "treasury", desc = "Treasury pda, stores the gaps")]
+    #[account(1, name = "platform", desc = "Platform pda key")]
+    #[account(2, name = "vault", desc = "platforms fee vault pda")]
+    ReconcileTreasury {
+    },
+
+    /// Read-only view, only built with the `views` feature (discriminator 200)
+    /// Returns `Platform` as return data
+    #[account(0, name = "platform", desc = "Platform account to read")]
+    ViewPlatform {
+    } = 200,
+
+    /// Read-only view, only built with the `views` feature (discriminator 201)
+    /// Returns `Treasury` as return data
+    #[account(0, name = "treasury", desc = "Treasury account to read")]
+    ViewTreasury {
+    } = 201,
+
+    /// Read-only view, only built with the `views` feature (discriminator 202)
+    /// Returns `Vote` as return data
+    #[account(0, name = "vote", desc = "Vote account to read")]
+    ViewVote {
+    } = 202,
+
+    /// Read-only view, only built with the `views` feature (discriminator 203)
+    /// Returns `Position` as return data
+    #[account(0, name = "position", desc = "Position account to read")]
+    ViewPosition {
+    } = 203,
+
+    /// Read-only view, only built with the `views` feature (discriminator 204)
+    /// Returns `WhitelistEntry` as return data
+    #[account(0, name = "whitelist_entry", desc = "WhitelistEntry account to read")]
+    ViewWhitelistEntry {
+    } = 204,
+
+    /// Read-only view, only built with the `views` feature (discriminator 205)
+    /// Returns `ClaimReceipt` as return data
+    #[account(0, name = "claim_receipt", desc = "ClaimReceipt account to read")]
+    ViewClaimReceipt {
+    } = 205,
+
+}
+
+/// Longest instruction data `ProgramInstructions::pack` writes, discriminator included
+pub const MAX_INSTRUCTION_DATA_LEN: usize = jiminy::dispatch::max_len(&[
+    1 + crate::instructions::InitializePlatformData::LEN,
+    1 + crate::instructions::InitializeVoteData::LEN + jiminy::dispatch::rest_max_len(crate::instructions::InitializeVoteInstruction::describe().rest),
+    1 + crate::instructions::InitializePositionData::LEN,
+    1 + crate::instructions::UpdatePositionData::LEN,
+    1 + crate::instructions::RedeemWinningsData::LEN,
+    1 + jiminy::dispatch::rest_max_len(crate::instructions::CallAdapterInstruction::describe().rest),
+    1,
+    1 + crate::instructions::ResolveVoteData::LEN,
+    1 + crate::instructions::AddToWhitelistData::LEN,
+    1,
+    1,
+    1 + crate::instructions::InitializeVoteWithPayerData::LEN + jiminy::dispatch::rest_max_len(crate::instructions::InitializeVoteWithPayerInstruction::describe().rest),
+    1 + crate::instructions::InitializeMultiOutcomeVoteData::LEN + jiminy::dispatch::rest_max_len(crate::instructions::InitializeMultiOutcomeVoteInstruction::describe().rest),
+    1,
+    1,
+    1 + jiminy::dispatch::rest_max_len(crate::instructions::UpdateVoteSettingsInstruction::describe().data_parser),
+    1 + crate::instructions::RedeemFeeVoucherData::LEN,
+    1 + crate::instructions::UpdatePlatformData::LEN,
+    1,
+    1,
+    1 + crate::instructions::SetFeePolicyData::LEN,
+    1 + crate::instructions::ClaimFeesData::LEN,
+    1,
+    1,
+    1,
+    1,
+    1,
+    1,
+    1,
+]);
+
+impl ProgramInstructions {
+    /// Instruction data for this instruction: the discriminator, the data struct's bytes (the borsh encoded schema for a `data_parser`), then any trailing bytes
+    ///
+    /// Panics if the trailing bytes are longer than the instruction's `max_len`.
+    pub fn pack(&self) -> ([u8; MAX_INSTRUCTION_DATA_LEN], usize) {
+        let mut buf = [0u8; MAX_INSTRUCTION_DATA_LEN];
+        let len = match self {
+            Self::InitializePlatform { fee, platform_bump, vault_bump, treasury_bump } => {
+                buf[0] = 0;
+                let data = crate::instructions::InitializePlatformData {
+                    fee: *fee,
+                    platform_bump: *platform_bump,
+                    vault_bump: *vault_bump,
+                    treasury_bump: *treasury_bump,
+                };
+                let end = 1 + crate::instructions::InitializePlatformData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                end
+            }
+            Self::InitializeVote { deadline_kind, time_to_add, min_total, resolver, max_position, whitelist_only, label } => {
+                buf[0] = 2;
+                let data = crate::instructions::InitializeVoteData {
+                    deadline_kind: *deadline_kind,
+                    time_to_add: *time_to_add,
+                    min_total: *min_total,
+                    resolver: *resolver,
+                    max_position: *max_position,
+                    whitelist_only: u8::from(*whitelist_only),
+                };
+                let end = 1 + crate::instructions::InitializeVoteData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                buf[end..end + label.len()].copy_from_slice(label);
+                end + label.len()
+            }
+            Self::InitializePosition { amount, side, funding_mode, allow_crank } => {
+                buf[0] = 3;
+                let data = crate::instructions::InitializePositionData {
+                    amount: *amount,
+                    side: *side,
+                    funding_mode: *funding_mode as u8,
+                    allow_crank: *allow_crank,
+                };
+                let end = 1 + crate::instructions::InitializePositionData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                end
+            }
+            Self::UpdatePosition { amount, funding_mode } => {
+                buf[0] = 4;
+                let data = crate::instructions::UpdatePositionData {
+                    amount: *amount,
+                    funding_mode: *funding_mode as u8,
+                };
+                let end = 1 + crate::instructions::UpdatePositionData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                end
+            }
+            Self::RedeemWinnings { max_amount } => {
+                buf[0] = 5;
+                let data = crate::instructions::RedeemWinningsData {
+                    max_amount: *max_amount,
+                };
+                let end = 1 + crate::instructions::RedeemWinningsData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                end
+            }
+            Self::CallAdapter { adapter_data } => {
+                buf[0] = 6;
+                buf[1..1 + adapter_data.len()].copy_from_slice(adapter_data);
+                1 + adapter_data.len()
+            }
+            Self::RefundPosition {} => {
+                buf[0] = 8;
+                1
+            }
+            Self::ResolveVote { outcome } => {
+                buf[0] = 9;
+                let data = crate::instructions::ResolveVoteData {
+                    outcome: *outcome,
+                };
+                let end = 1 + crate::instructions::ResolveVoteData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                end
+            }
+            Self::AddToWhitelist { wallet } => {
+                buf[0] = 11;
+                let data = crate::instructions::AddToWhitelistData {
+                    wallet: *wallet,
+                };
+                let end = 1 + crate::instructions::AddToWhitelistData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                end
+            }
+            Self::ViewPlatformStats {} => {
+                buf[0] = 12;
+                1
+            }
+            Self::BatchClosePositions {} => {
+                buf[0] = 13;
+                1
+            }
+            Self::InitializeVoteWithPayer { deadline_kind, time_to_add, min_total, resolver, max_position, whitelist_only, label } => {
+                buf[0] = 14;
+                let data = crate::instructions::InitializeVoteWithPayerData {
+                    deadline_kind: *deadline_kind,
+                    time_to_add: *time_to_add,
+                    min_total: *min_total,
+                    resolver: *resolver,
+                    max_position: *max_position,
+                    whitelist_only: u8::from(*whitelist_only),
+                };
+                let end = 1 + crate::instructions::InitializeVoteWithPayerData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                buf[end..end + label.len()].copy_from_slice(label);
+                end + label.len()
+            }
+            Self::InitializeMultiOutcomeVote { outcome_count, deadline_kind, time_to_add, min_total, resolver, max_position, whitelist_only, label } => {
+                buf[0] = 15;
+                let data = crate::instructions::InitializeMultiOutcomeVoteData {
+                    outcome_count: *outcome_count,
+                    deadline_kind: *deadline_kind,
+                    time_to_add: *time_to_add,
+                    min_total: *min_total,
+                    resolver: *resolver,
+                    max_position: *max_position,
+                    whitelist_only: u8::from(*whitelist_only),
+                };
+                let end = 1 + crate::instructions::InitializeMultiOutcomeVoteData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                buf[end..end + label.len()].copy_from_slice(label);
+                end + label.len()
+            }
+            Self::MigrateVote {} => {
+                buf[0] = 16;
+                1
+            }
+            Self::RedeemFor {} => {
+                buf[0] = 18;
+                1
+            }
+            Self::UpdateVoteSettings { label, max_position, whitelist_only } => {
+                buf[0] = 19;
+                let mut writer = jiminy::borsh::Writer::new(&mut buf[1..]);
+                writer.put(label.as_str());
+                writer.put(max_position);
+                writer.put(whitelist_only);
+                1 + writer.len()
+            }
+            Self::RedeemFeeVoucher { rebate, expires_at_slot } => {
+                buf[0] = 22;
+                let data = crate::instructions::RedeemFeeVoucherData {
+                    rebate: *rebate,
+                    expires_at_slot: *expires_at_slot,
+                };
+                let end = 1 + crate::instructions::RedeemFeeVoucherData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                end
+            }
+            Self::UpdatePlatform { flags, new_fee, new_fee_mint, new_fee_ratio_num, new_fee_ratio_den, new_sponsor_rent, new_min_duration, new_max_duration } => {
+                buf[0] = 32;
+                let data = crate::instructions::UpdatePlatformData {
+                    flags: *flags,
+                    new_fee: *new_fee,
+                    new_fee_mint: *new_fee_mint,
+                    new_fee_ratio_num: *new_fee_ratio_num,
+                    new_fee_ratio_den: *new_fee_ratio_den,
+                    new_sponsor_rent: u8::from(*new_sponsor_rent),
+                    new_min_duration: *new_min_duration,
+                    new_max_duration: *new_max_duration,
+                };
+                let end = 1 + crate::instructions::UpdatePlatformData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                end
+            }
+            Self::AdminResetPlatform {} => {
+                buf[0] = 33;
+                1
+            }
+            Self::MigratePlatform {} => {
+                buf[0] = 34;
+                1
+            }
+            Self::SetFeePolicy { kind, bps, first, second } => {
+                buf[0] = 35;
+                let data = crate::instructions::SetFeePolicyData {
+                    kind: *kind as u8,
+                    bps: *bps,
+                    first: *first,
+                    second: *second,
+                };
+                let end = 1 + crate::instructions::SetFeePolicyData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                end
+            }
+            Self::ClaimFees { lamports, tokens } => {
+                buf[0] = 36;
+                let data = crate::instructions::ClaimFeesData {
+                    lamports: *lamports,
+                    tokens: *tokens,
+                };
+                let end = 1 + crate::instructions::ClaimFeesData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                end
+            }
+            Self::ReconcileTreasury {} => {
+                buf[0] = 37;
+                1
+            }
+            Self::ViewPlatform {} => {
+                buf[0] = 200;
+                1
+            }
+            Self::ViewTreasury {} => {
+                buf[0] = 201;
+                1
+            }
+            Self::ViewVote {} => {
+                buf[0] = 202;
+                1
+            }
+            Self::ViewPosition {} => {
+                buf[0] = 203;
+                1
+            }
+            Self::ViewWhitelistEntry {} => {
+                buf[0] = 204;
+                1
+            }
+            Self::ViewClaimReceipt {} => {
+                buf[0] = 205;
+                1
+            }
+        };
+        (buf, len)
+    }
+
+    /// Parse instruction data the way the program does, deprecated discriminators included
+    pub fn unpack(data: &[u8]) -> Result<Self, pinocchio::program_error::ProgramError> {
+        let (&discriminator, data) = data.split_first().ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+        Ok(match discriminator {
+            0 => {
+                let fixed = data.get(..crate::instructions::InitializePlatformData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::InitializePlatformData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::InitializePlatformData::LEN..];
+                if !tail.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::InitializePlatform {
+                    fee: parsed.fee,
+                    platform_bump: parsed.platform_bump,
+                    vault_bump: parsed.vault_bump,
+                    treasury_bump: parsed.treasury_bump,
+                }
+            }
+            2 => {
+                let fixed = data.get(..crate::instructions::InitializeVoteData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::InitializeVoteData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::InitializeVoteData::LEN..];
+                let max_len = jiminy::dispatch::rest_max_len(crate::instructions::InitializeVoteInstruction::describe().rest);
+                if tail.len() > max_len {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                let label = tail.to_vec();
+                Self::InitializeVote {
+                    deadline_kind: parsed.deadline_kind,
+                    time_to_add: parsed.time_to_add,
+                    min_total: parsed.min_total,
+                    resolver: parsed.resolver,
+                    max_position: parsed.max_position,
+                    whitelist_only: match parsed.whitelist_only { 0 => false, 1 => true, _ => return Err(pinocchio::program_error::ProgramError::InvalidInstructionData) },
+                    label,
+                }
+            }
+            3 => {
+                let fixed = data.get(..crate::instructions::InitializePositionData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::InitializePositionData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::InitializePositionData::LEN..];
+                if !tail.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::InitializePosition {
+                    amount: parsed.amount,
+                    side: parsed.side,
+                    funding_mode: match parsed.funding_mode { 0 => FundingMode::Owner, 1 => FundingMode::Delegate, _ => return Err(jiminy::error::JiminyError::InvalidEnumValue.into()) },
+                    allow_crank: parsed.allow_crank,
+                }
+            }
+            4 => {
+                let fixed = data.get(..crate::instructions::UpdatePositionData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::UpdatePositionData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::UpdatePositionData::LEN..];
+                if !tail.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::UpdatePosition {
+                    amount: parsed.amount,
+                    funding_mode: match parsed.funding_mode { 0 => FundingMode::Owner, 1 => FundingMode::Delegate, _ => return Err(jiminy::error::JiminyError::InvalidEnumValue.into()) },
+                }
+            }
+            5 => {
+                let fixed = data.get(..crate::instructions::RedeemWinningsData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::RedeemWinningsData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::RedeemWinningsData::LEN..];
+                if !tail.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::RedeemWinnings {
+                    max_amount: parsed.max_amount,
+                }
+            }
+            6 => {
+                let max_len = jiminy::dispatch::rest_max_len(crate::instructions::CallAdapterInstruction::describe().rest);
+                if data.len() > max_len {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                let adapter_data = data.to_vec();
+                Self::CallAdapter {
+                    adapter_data,
+                }
+            }
+            8 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::RefundPosition {
+                }
+            }
+            9 => {
+                let fixed = data.get(..crate::instructions::ResolveVoteData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::ResolveVoteData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::ResolveVoteData::LEN..];
+                if !tail.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::ResolveVote {
+                    outcome: parsed.outcome,
+                }
+            }
+            11 => {
+                let fixed = data.get(..crate::instructions::AddToWhitelistData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::AddToWhitelistData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::AddToWhitelistData::LEN..];
+                if !tail.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::AddToWhitelist {
+                    wallet: parsed.wallet,
+                }
+            }
+            12 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::ViewPlatformStats {
+                }
+            }
+            13 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::BatchClosePositions {
+                }
+            }
+            14 => {
+                let fixed = data.get(..crate::instructions::InitializeVoteWithPayerData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::InitializeVoteWithPayerData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::InitializeVoteWithPayerData::LEN..];
+                let max_len = jiminy::dispatch::rest_max_len(crate::instructions::InitializeVoteWithPayerInstruction::describe().rest);
+                if tail.len() > max_len {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                let label = tail.to_vec();
+                Self::InitializeVoteWithPayer {
+                    deadline_kind: parsed.deadline_kind,
+                    time_to_add: parsed.time_to_add,
+                    min_total: parsed.min_total,
+                    resolver: parsed.resolver,
+                    max_position: parsed.max_position,
+                    whitelist_only: match parsed.whitelist_only { 0 => false, 1 => true, _ => return Err(pinocchio::program_error::ProgramError::InvalidInstructionData) },
+                    label,
+                }
+            }
+            15 => {
+                let fixed = data.get(..crate::instructions::InitializeMultiOutcomeVoteData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::InitializeMultiOutcomeVoteData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::InitializeMultiOutcomeVoteData::LEN..];
+                let max_len = jiminy::dispatch::rest_max_len(crate::instructions::InitializeMultiOutcomeVoteInstruction::describe().rest);
+                if tail.len() > max_len {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                let label = tail.to_vec();
+                Self::InitializeMultiOutcomeVote {
+                    outcome_count: parsed.outcome_count,
+                    deadline_kind: parsed.deadline_kind,
+                    time_to_add: parsed.time_to_add,
+                    min_total: parsed.min_total,
+                    resolver: parsed.resolver,
+                    max_position: parsed.max_position,
+                    whitelist_only: match parsed.whitelist_only { 0 => false, 1 => true, _ => return Err(pinocchio::program_error::ProgramError::InvalidInstructionData) },
+                    label,
+                }
+            }
+            16 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::MigrateVote {
+                }
+            }
+            18 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::RedeemFor {
+                }
+            }
+            19 => {
+                let mut reader = jiminy::borsh::Reader::new(data);
+                let label = String::from(reader.read::<&str>()?);
+                let max_position = reader.read()?;
+                let whitelist_only = reader.read()?;
+                reader.finish()?;
+                Self::UpdateVoteSettings {
+                    label,
+                    max_position,
+                    whitelist_only,
+                }
+            }
+            22 => {
+                let fixed = data.get(..crate::instructions::RedeemFeeVoucherData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::RedeemFeeVoucherData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::RedeemFeeVoucherData::LEN..];
+                if !tail.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::RedeemFeeVoucher {
+                    rebate: parsed.rebate,
+                    expires_at_slot: parsed.expires_at_slot,
+                }
+            }
+            32 | 1 => {
+                let fixed = data.get(..crate::instructions::UpdatePlatformData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::UpdatePlatformData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::UpdatePlatformData::LEN..];
+                if !tail.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::UpdatePlatform {
+                    flags: parsed.flags,
+                    new_fee: parsed.new_fee,
+                    new_fee_mint: parsed.new_fee_mint,
+                    new_fee_ratio_num: parsed.new_fee_ratio_num,
+                    new_fee_ratio_den: parsed.new_fee_ratio_den,
+                    new_sponsor_rent: match parsed.new_sponsor_rent { 0 => false, 1 => true, _ => return Err(pinocchio::program_error::ProgramError::InvalidInstructionData) },
+                    new_min_duration: parsed.new_min_duration,
+                    new_max_duration: parsed.new_max_duration,
+                }
+            }
+            33 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::AdminResetPlatform {
+                }
+            }
+            34 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::MigratePlatform {
+                }
+            }
+            35 => {
+                let fixed = data.get(..crate::instructions::SetFeePolicyData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::SetFeePolicyData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::SetFeePolicyData::LEN..];
+                if !tail.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::SetFeePolicy {
+                    kind: match parsed.kind { 0 => FeePolicyKind::Bps, 1 => FeePolicyKind::Flat, 2 => FeePolicyKind::Tiered, _ => return Err(jiminy::error::JiminyError::InvalidEnumValue.into()) },
+                    bps: parsed.bps,
+                    first: parsed.first,
+                    second: parsed.second,
+                }
+            }
+            36 => {
+                let fixed = data.get(..crate::instructions::ClaimFeesData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::ClaimFeesData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::ClaimFeesData::LEN..];
+                if !tail.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::ClaimFees {
+                    lamports: parsed.lamports,
+                    tokens: parsed.tokens,
+                }
+            }
+            37 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::ReconcileTreasury {
+                }
+            }
+            200 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::ViewPlatform {
+                }
+            }
+            201 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::ViewTreasury {
+                }
+            }
+            202 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::ViewVote {
+                }
+            }
+            203 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::ViewPosition {
+                }
+            }
+            204 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::ViewWhitelistEntry {
+                }
+            }
+            205 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::ViewClaimReceipt {
+                }
+            }
+            _ => return Err(pinocchio::program_error::ProgramError::InvalidInstructionData),
+        })
+    }
+}
+
+/// Outcomes of a two-outcome vote, the indices `Position::side` holds on them
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, shank::ShankType)]
+pub enum Side {
+    False = 0,
+    True = 1,
+}
+
+/// How the platform computes its fee, see `utils::FeePolicy`
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, shank::ShankType)]
+pub enum FeePolicyKind {
+    Bps = 0,
+    Flat = 1,
+    Tiered = 2,
+}
+
+/// Whose approval moves the stake into a position
+#[repr(u8)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, shank::ShankType)]
+pub enum FundingMode {
+    Owner = 0,
+    Delegate = 1,
+}
+
+// ShankAccount definitions for state structs
+// These are generated for IDL compatibility
+/// Account size: 207 bytes
+#[repr(C)]
+#[derive(Clone, shank::ShankAccount)]
+pub struct Platform {
+    pub authority: pinocchio::pubkey::Pubkey,
+    pub fee: [u8; 2],
+    pub platform_bump: u8,
+    pub vault_bump: u8,
+    pub reentrancy_lock: u8,
+    pub fee_mint: [u8; 32],
+    pub fee_ratio_num: [u8; 8],
+    pub fee_ratio_den: [u8; 8],
+    pub sponsor_rent: u8,
+    pub total_votes: [u8; 8],
+    pub total_volume: [u8; 8],
+    pub total_fees: [u8; 8],
+    pub vault: pinocchio::pubkey::Pubkey,
+    pub fee_policy: u8,
+    pub fee_params: [[u8; 8]; 2],
+    pub min_duration: [u8; 8],
+    pub max_duration: [u8; 8],
+    pub treasury: pinocchio::pubkey::Pubkey,
+}
+
+impl Platform {
+    /// Data bytes of the account, what `create_pda!` allocates
+    pub const SIZE: usize = 207;
+    /// Rent-exempt minimum at the default rent
+    pub const RENT_EXEMPT_LAMPORTS_AT_DEFAULT_RATE: u64 =
+        Self::rent_exempt_lamports(jiminy::rent::DEFAULT_LAMPORTS_PER_BYTE_YEAR);
+
+    /// Bytes to allocate when creating the account
+    pub const fn space() -> usize {
+        Self::SIZE
+    }
+
+    /// Rent-exempt minimum at `lamports_per_byte_year`
+    pub const fn rent_exempt_lamports(lamports_per_byte_year: u64) -> u64 {
+        jiminy::rent::exempt_lamports(Self::space(), lamports_per_byte_year)
+    }
+}
+
+const _: () = assert!(
+    Platform::space() == crate::state::Platform::LEN,
+    "Platform size differs from the program"
+);
+
+/// Account size: 49 bytes
+#[repr(C)]
+#[derive(Clone, shank::ShankAccount)]
+pub struct Treasury {
+    pub fees_collected_lamports: u64,
+    pub fees_collected_tokens: u64,
+    pub bump: u8,
+    pub lamport_gap: [u8; 8],
+    pub token_gap: [u8; 8],
+    pub reconciled_slot: u64,
+    pub fees_waived_tokens: u64,
+}
+
+impl Treasury {
+    /// Data bytes of the account, what `create_pda!` allocates
+    pub const SIZE: usize = 49;
+    /// Rent-exempt minimum at the default rent
+    pub const RENT_EXEMPT_LAMPORTS_AT_DEFAULT_RATE: u64 =
+        Self::rent_exempt_lamports(jiminy::rent::DEFAULT_LAMPORTS_PER_BYTE_YEAR);
+
+    /// Bytes to allocate when creating the account
+    pub const fn space() -> usize {
+        Self::SIZE
+    }
+
+    /// Rent-exempt minimum at `lamports_per_byte_year`
+    pub const fn rent_exempt_lamports(lamports_per_byte_year: u64) -> u64 {
+        jiminy::rent::exempt_lamports(Self::space(), lamports_per_byte_year)
+    }
+}
+
+const _: () = assert!(
+    Treasury::space() == crate::state::Treasury::LEN,
+    "Treasury size differs from the program"
+);
+
+/// Account size: 254 bytes
+#[repr(C)]
+#[derive(Clone, shank::ShankAccount)]
+pub struct Vote {
+    pub token: pinocchio::pubkey::Pubkey,
+    pub tallies: [[u8; 8]; 8],
+    /// Deadline kind (0 unix timestamp, 1 slot, 2 epoch), then the little-endian value
+    pub deadline: [u8; 9],
+    pub min_total: [u8; 8],
+    /// Tag byte, then the value, zeroed when empty
+    pub resolver: Option<pinocchio::pubkey::Pubkey>,
+    pub resolved_outcome: u8,
+    pub vault_bump: u8,
+    /// String, at most 64 bytes
+    #[idl_type("string")]
+    pub label: [u8; 64],
+    pub creator: pinocchio::pubkey::Pubkey,
+    pub max_position: [u8; 8],
+    pub whitelist_only: bool,
+    pub outcome_count: u8,
+}
+
+impl Vote {
+    /// Data bytes of the account, what `create_pda!` allocates
+    pub const SIZE: usize = 254;
+    /// Rent-exempt minimum at the default rent
+    pub const RENT_EXEMPT_LAMPORTS_AT_DEFAULT_RATE: u64 =
+        Self::rent_exempt_lamports(jiminy::rent::DEFAULT_LAMPORTS_PER_BYTE_YEAR);
+
+    /// Bytes to allocate when creating the account
+    pub const fn space() -> usize {
+        Self::SIZE
+    }
+
+    /// Rent-exempt minimum at `lamports_per_byte_year`
+    pub const fn rent_exempt_lamports(lamports_per_byte_year: u64) -> u64 {
+        jiminy::rent::exempt_lamports(Self::space(), lamports_per_byte_year)
+    }
+}
+
+const _: () = assert!(
+    Vote::space() == crate::state::Vote::LEN,
+    "Vote size differs from the program"
+);
+
+/// Account size: 35 bytes
+#[repr(C)]
+#[derive(Clone, shank::ShankAccount)]
+pub struct Position {
+    pub amount: u64,
+    pub side: u8,
+    pub bump: u8,
+    pub allow_crank: u8,
+    pub created_at_slot: u64,
+    pub remaining_entitlement: u64,
+    pub updated_at_slot: u64,
+}
+
+impl Position {
+    /// Data bytes of the account, what `create_pda!` allocates
+    pub const SIZE: usize = 35;
+    /// Rent-exempt minimum at the default rent
+    pub const RENT_EXEMPT_LAMPORTS_AT_DEFAULT_RATE: u64 =
+        Self::rent_exempt_lamports(jiminy::rent::DEFAULT_LAMPORTS_PER_BYTE_YEAR);
+
+    /// Bytes to allocate when creating the account
+    pub const fn space() -> usize {
+        Self::SIZE
+    }
+
+    /// Rent-exempt minimum at `lamports_per_byte_year`
+    pub const fn rent_exempt_lamports(lamports_per_byte_year: u64) -> u64 {
+        jiminy::rent::exempt_lamports(Self::space(), lamports_per_byte_year)
+    }
+}
+
+const _: () = assert!(
+    Position::space() == crate::state::Position::LEN,
+    "Position size differs from the program"
+);
+
+/// Account size: 65 bytes
+#[repr(C)]
+#[derive(Clone, shank::ShankAccount)]
+pub struct WhitelistEntry {
+    pub vote: [u8; 32],
+    pub wallet: [u8; 32],
+    pub bump: u8,
+}
+
+impl WhitelistEntry {
+    /// Data bytes of the account, what `create_pda!` allocates
+    pub const SIZE: usize = 65;
+    /// Rent-exempt minimum at the default rent
+    pub const RENT_EXEMPT_LAMPORTS_AT_DEFAULT_RATE: u64 =
+        Self::rent_exempt_lamports(jiminy::rent::DEFAULT_LAMPORTS_PER_BYTE_YEAR);
+
+    /// Bytes to allocate when creating the account
+    pub const fn space() -> usize {
+        Self::SIZE
+    }
+
+    /// Rent-exempt minimum at `lamports_per_byte_year`
+    pub const fn rent_exempt_lamports(lamports_per_byte_year: u64) -> u64 {
+        jiminy::rent::exempt_lamports(Self::space(), lamports_per_byte_year)
+    }
+}
+
+const _: () = assert!(
+    WhitelistEntry::space() == crate::state::WhitelistEntry::LEN,
+    "WhitelistEntry size differs from the program"
+);
+
+/// Account size: 1 bytes
+#[repr(C)]
+#[derive(Clone, shank::ShankAccount)]
+pub struct ClaimReceipt {
+    pub bump: u8,
+}
+
+impl ClaimReceipt {
+    /// Data bytes of the account, what `create_pda!` allocates
+    pub const SIZE: usize = 1;
+    /// Rent-exempt minimum at the default rent
+    pub const RENT_EXEMPT_LAMPORTS_AT_DEFAULT_RATE: u64 =
+        Self::rent_exempt_lamports(jiminy::rent::DEFAULT_LAMPORTS_PER_BYTE_YEAR);
+
+    /// Bytes to allocate when creating the account
+    pub const fn space() -> usize {
+        Self::SIZE
+    }
+
+    /// Rent-exempt minimum at `lamports_per_byte_year`
+    pub const fn rent_exempt_lamports(lamports_per_byte_year: u64) -> u64 {
+        jiminy::rent::exempt_lamports(Self::space(), lamports_per_byte_year)
+    }
+}
+
+const _: () = assert!(
+    ClaimReceipt::space() == crate::state::ClaimReceipt::LEN,
+    "ClaimReceipt size differs from the program"
+);
+
+pub type InstructionHandler = fn(
+    &[pinocchio::account_info::AccountInfo],
+    &[u8],
+) -> pinocchio::ProgramResult;
+
+// Read-only view of `crate::state::Platform`, returns the account data as return data
+#[cfg(feature = "views")]
+pub mod view_platform {
+    jiminy::define_instruction_with_metadata!(
+        discriminant: 200,
+        ViewPlatform,
+        accounts: {
+            platform: program, desc: "Platform account to read",
+        },
+        data: {},
+        returns: crate::state::Platform,
+        process: {
+            // Checked shared borrow, the account is never written
+            let data = platform.try_borrow_data()?;
+            bytemuck::try_from_bytes::<crate::state::Platform>(&data)
+                .copied()
+                .map_err(|_| ProgramError::InvalidAccountData)
+        }
+    );
+}
+
+// Read-only view of `crate::state::Treasury`, returns the account data as return data
+#[cfg(feature = "views")]
+pub mod view_treasury {
+    jiminy::define_instruction_with_metadata!(
+        discriminant: 201,
+        ViewTreasury,
+        accounts: {
+            treasury: program, desc: "Treasury account to read",
+        },
+        data: {},
+        returns: crate::state::Treasury,
+        process: {
+            // Checked shared borrow, the account is never written
+            let data = treasury.try_borrow_data()?;
+            bytemuck::try_from_bytes::<crate::state::Treasury>(&data)
+                .copied()
+                .map_err(|_| ProgramError::InvalidAccountData)
+        }
+    );
+}
+
+// Read-only view of `crate::state::Vote`, returns the account data as return data
+#[cfg(feature = "views")]
+pub mod view_vote {
+    jiminy::define_instruction_with_metadata!(
+        discriminant: 202,
+        ViewVote,
+        accounts: {
+            vote: program, desc: "Vote account to read",
+        },
+        data: {},
+        returns: crate::state::Vote,
+        process: {
+            // Checked shared borrow, the account is never written
+            let data = vote.try_borrow_data()?;
+            bytemuck::try_from_bytes::<crate::state::Vote>(&data)
+                .copied()
+                .map_err(|_| ProgramError::InvalidAccountData)
+        }
+    );
+}
+
+// Read-only view of `crate::state::Position`, returns the account data as return data
+#[cfg(feature = "views")]
+pub mod view_position {
+    jiminy::define_instruction_with_metadata!(
+        discriminant: 203,
+        ViewPosition,
+        accounts: {
+            position: program, desc: "Position account to read",
+        },
+        data: {},
+        returns: crate::state::Position,
+        process: {
+            // Checked shared borrow, the account is never written
+            let data = position.try_borrow_data()?;
+            bytemuck::try_from_bytes::<crate::state::Position>(&data)
+                .copied()
+                .map_err(|_| ProgramError::InvalidAccountData)
+        }
+    );
+}
+
+// Read-only view of `crate::state::WhitelistEntry`, returns the account data as return data
+#[cfg(feature = "views")]
+pub mod view_whitelist_entry {
+    jiminy::define_instruction_with_metadata!(
+        discriminant: 204,
+        ViewWhitelistEntry,
+        accounts: {
+            whitelist_entry: program, desc: "WhitelistEntry account to read",
+        },
+        data: {},
+        returns: crate::state::WhitelistEntry,
+        process: {
+            // Checked shared borrow, the account is never written
+            let data = whitelist_entry.try_borrow_data()?;
+            bytemuck::try_from_bytes::<crate::state::WhitelistEntry>(&data)
+                .copied()
+                .map_err(|_| ProgramError::InvalidAccountData)
+        }
+    );
+}
+
+// Read-only view of `crate::state::ClaimReceipt`, returns the account data as return data
+#[cfg(feature = "views")]
+pub mod view_claim_receipt {
+    jiminy::define_instruction_with_metadata!(
+        discriminant: 205,
+        ViewClaimReceipt,
+        accounts: {
+            claim_receipt: program, desc: "ClaimReceipt account to read",
+        },
+        data: {},
+        returns: crate::state::ClaimReceipt,
+        process: {
+            // Checked shared borrow, the account is never written
+            let data = claim_receipt.try_borrow_data()?;
+            bytemuck::try_from_bytes::<crate::state::ClaimReceipt>(&data)
+                .copied()
+                .map_err(|_| ProgramError::InvalidAccountData)
+        }
+    );
+}
+
+fn handle_initialize_platform(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::InitializePlatformInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_initialize_vote(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::InitializeVoteInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_initialize_position(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::InitializePositionInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_update_position(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::UpdatePositionInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_redeem_winnings(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::RedeemWinningsInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_call_adapter(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::CallAdapterInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_refund_position(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::RefundPositionInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_resolve_vote(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::ResolveVoteInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_add_to_whitelist(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::AddToWhitelistInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_view_platform_stats(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::ViewPlatformStatsInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_batch_close_positions(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::BatchClosePositionsInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_initialize_vote_with_payer(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::InitializeVoteWithPayerInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_initialize_multi_outcome_vote(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::InitializeMultiOutcomeVoteInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_migrate_vote(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::MigrateVoteInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_redeem_for(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::RedeemForInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_update_vote_settings(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::UpdateVoteSettingsInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_redeem_fee_voucher(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::RedeemFeeVoucherInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_update_platform(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::UpdatePlatformInstruction::try_from((accounts, data))?.process()
+}
+
+// Deprecated alias of discriminator 32
+fn handle_update_platform_1(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    jiminy::dispatch::deprecated_discriminator(1, 32);
+    handle_update_platform(accounts, data)
+}
+
+fn handle_admin_reset_platform(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::AdminResetPlatformInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_migrate_platform(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::MigratePlatformInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_set_fee_policy(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::SetFeePolicyInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_claim_fees(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::ClaimFeesInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_reconcile_treasury(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::ReconcileTreasuryInstruction::try_from((accounts, data))?.process()
+}
+
+#[cfg(feature = "views")]
+fn handle_view_platform(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    view_platform::ViewPlatformInstruction::try_from((accounts, data))?.process()
+}
+
+#[cfg(feature = "views")]
+fn handle_view_treasury(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    view_treasury::ViewTreasuryInstruction::try_from((accounts, data))?.process()
+}
+
+#[cfg(feature = "views")]
+fn handle_view_vote(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    view_vote::ViewVoteInstruction::try_from((accounts, data))?.process()
+}
+
+#[cfg(feature = "views")]
+fn handle_view_position(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    view_position::ViewPositionInstruction::try_from((accounts, data))?.process()
+}
+
+#[cfg(feature = "views")]
+fn handle_view_whitelist_entry(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    view_whitelist_entry::ViewWhitelistEntryInstruction::try_from((accounts, data))?.process()
+}
+
+#[cfg(feature = "views")]
+fn handle_view_claim_receipt(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    view_claim_receipt::ViewClaimReceiptInstruction::try_from((accounts, data))?.process()
+}
+
+/// Instructions of the program, read-only views not counted
+pub const INSTRUCTION_COUNT: usize = 23;
+
+/// `(discriminator, name)` of each instruction, by discriminator
+pub const INSTRUCTIONS: [(u8, &str); INSTRUCTION_COUNT] = [
+    (0, "InitializePlatform"),
+    (2, "InitializeVote"),
+    (3, "InitializePosition"),
+    (4, "UpdatePosition"),
+    (5, "RedeemWinnings"),
+    (6, "CallAdapter"),
+    (8, "RefundPosition"),
+    (9, "ResolveVote"),
+    (11, "AddToWhitelist"),
+    (12, "ViewPlatformStats"),
+    (13, "BatchClosePositions"),
+    (14, "InitializeVoteWithPayer"),
+    (15, "InitializeMultiOutcomeVote"),
+    (16, "MigrateVote"),
+    (18, "RedeemFor"),
+    (19, "UpdateVoteSettings"),
+    (22, "RedeemFeeVoucher"),
+    (32, "UpdatePlatform"),
+    (33, "AdminResetPlatform"),
+    (34, "MigratePlatform"),
+    (35, "SetFeePolicy"),
+    (36, "ClaimFees"),
+    (37, "ReconcileTreasury"),
+];
+
+/// Instruction name of a discriminator, deprecated aliases included
+pub const fn instruction_name(discriminator: u8) -> Option<&'static str> {
+    match discriminator {
+        0 => Some("InitializePlatform"),
+        2 => Some("InitializeVote"),
+        3 => Some("InitializePosition"),
+        4 => Some("UpdatePosition"),
+        5 => Some("RedeemWinnings"),
+        6 => Some("CallAdapter"),
+        8 => Some("RefundPosition"),
+        9 => Some("ResolveVote"),
+        11 => Some("AddToWhitelist"),
+        12 => Some("ViewPlatformStats"),
+        13 => Some("BatchClosePositions"),
+        14 => Some("InitializeVoteWithPayer"),
+        15 => Some("InitializeMultiOutcomeVote"),
+        16 => Some("MigrateVote"),
+        18 => Some("RedeemFor"),
+        19 => Some("UpdateVoteSettings"),
+        22 => Some("RedeemFeeVoucher"),
+        32 | 1 => Some("UpdatePlatform"),
+        33 => Some("AdminResetPlatform"),
+        34 => Some("MigratePlatform"),
+        35 => Some("SetFeePolicy"),
+        36 => Some("ClaimFees"),
+        37 => Some("ReconcileTreasury"),
+        #[cfg(feature = "views")]
+        200 => Some("ViewPlatform"),
+        #[cfg(feature = "views")]
+        201 => Some("ViewTreasury"),
+        #[cfg(feature = "views")]
+        202 => Some("ViewVote"),
+        #[cfg(feature = "views")]
+        203 => Some("ViewPosition"),
+        #[cfg(feature = "views")]
+        204 => Some("ViewWhitelistEntry"),
+        #[cfg(feature = "views")]
+        205 => Some("ViewClaimReceipt"),
+        _ => None,
+    }
+}
+
+pub const DISPATCH: [Option<InstructionHandler>; 256] = {
+    let mut table: [Option<InstructionHandler>; 256] = [None; 256];
+    table[0] = Some(handle_initialize_platform);
+    table[2] = Some(handle_initialize_vote);
+    table[3] = Some(handle_initialize_position);
+    table[4] = Some(handle_update_position);
+    table[5] = Some(handle_redeem_winnings);
+    table[6] = Some(handle_call_adapter);
+    table[8] = Some(handle_refund_position);
+    table[9] = Some(handle_resolve_vote);
+    table[11] = Some(handle_add_to_whitelist);
+    table[12] = Some(handle_view_platform_stats);
+    table[13] = Some(handle_batch_close_positions);
+    table[14] = Some(handle_initialize_vote_with_payer);
+    table[15] = Some(handle_initialize_multi_outcome_vote);
+    table[16] = Some(handle_migrate_vote);
+    table[18] = Some(handle_redeem_for);
+    table[19] = Some(handle_update_vote_settings);
+    table[22] = Some(handle_redeem_fee_voucher);
+    table[32] = Some(handle_update_platform);
+    table[1] = Some(handle_update_platform_1);
+    table[33] = Some(handle_admin_reset_platform);
+    table[34] = Some(handle_migrate_platform);
+    table[35] = Some(handle_set_fee_policy);
+    table[36] = Some(handle_claim_fees);
+    table[37] = Some(handle_reconcile_treasury);
+    #[cfg(feature = "views")]
+    { table[200] = Some(handle_view_platform); }
+    #[cfg(feature = "views")]
+    { table[201] = Some(handle_view_treasury); }
+    #[cfg(feature = "views")]
+    { table[202] = Some(handle_view_vote); }
+    #[cfg(feature = "views")]
+    { table[203] = Some(handle_view_position); }
+    #[cfg(feature = "views")]
+    { table[204] = Some(handle_view_whitelist_entry); }
+    #[cfg(feature = "views")]
+    { table[205] = Some(handle_view_claim_receipt); }
+    table
+};
+
+#[cfg(not(feature = "match-dispatch"))]
+pub fn process_instruction(
+    program_id: &pinocchio::pubkey::Pubkey,
+    accounts: &[pinocchio::account_info::AccountInfo],
+    instruction_data: &[u8],
+) -> pinocchio::ProgramResult {
+    if !jiminy::perf::keys_eq(program_id, &crate::ID) {
+        return Err(pinocchio::program_error::ProgramError::IncorrectProgramId);
+    }
+
+    #[cfg(feature = "host-stubs")]
+    if let Some(discriminator) = instruction_data.first() {
+        jiminy::testing::record_coverage(*discriminator);
+    }
+
+    match instruction_data.split_first() {
+        Some((discriminator, data)) => match DISPATCH[*discriminator as usize] {
+            Some(handler) => handler(accounts, data),
+            None => Err(<PTokenProgramError as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into()),
+        },
+        None => Err(<PTokenProgramError as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into()),
+    }
+}
+
+#[cfg(feature = "match-dispatch")]
+pub fn process_instruction(
+    program_id: &pinocchio::pubkey::Pubkey,
+    accounts: &[pinocchio::account_info::AccountInfo],
+    instruction_data: &[u8],
+) -> pinocchio::ProgramResult {
+    if !jiminy::perf::keys_eq(program_id, &crate::ID) {
+        return Err(pinocchio::program_error::ProgramError::IncorrectProgramId);
+    }
+
+    #[cfg(feature = "host-stubs")]
+    if let Some(discriminator) = instruction_data.first() {
+        jiminy::testing::record_coverage(*discriminator);
+    }
+
+    match instruction_data.first() {
+        Some(0) => {
+            crate::instructions::InitializePlatformInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(2) => {
+            crate::instructions::InitializeVoteInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(3) => {
+            crate::instructions::InitializePositionInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(4) => {
+            crate::instructions::UpdatePositionInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(5) => {
+            crate::instructions::RedeemWinningsInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(6) => {
+            crate::instructions::CallAdapterInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(8) => {
+            crate::instructions::RefundPositionInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(9) => {
+            crate::instructions::ResolveVoteInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(11) => {
+            crate::instructions::AddToWhitelistInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(12) => {
+            crate::instructions::ViewPlatformStatsInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(13) => {
+            crate::instructions::BatchClosePositionsInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(14) => {
+            crate::instructions::InitializeVoteWithPayerInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(15) => {
+            crate::instructions::InitializeMultiOutcomeVoteInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(16) => {
+            crate::instructions::MigrateVoteInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(18) => {
+            crate::instructions::RedeemForInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(19) => {
+            crate::instructions::UpdateVoteSettingsInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(22) => {
+            crate::instructions::RedeemFeeVoucherInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(32) => {
+            crate::instructions::UpdatePlatformInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(1) => {
+            jiminy::dispatch::deprecated_discriminator(1, 32);
+            crate::instructions::UpdatePlatformInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(33) => {
+            crate::instructions::AdminResetPlatformInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(34) => {
+            crate::instructions::MigratePlatformInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(35) => {
+            crate::instructions::SetFeePolicyInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(36) => {
+            crate::instructions::ClaimFeesInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(37) => {
+            crate::instructions::ReconcileTreasuryInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        #[cfg(feature = "views")]
+        Some(200) => {
+            view_platform::ViewPlatformInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        #[cfg(feature = "views")]
+        Some(201) => {
+            view_treasury::ViewTreasuryInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        #[cfg(feature = "views")]
+        Some(202) => {
+            view_vote::ViewVoteInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        #[cfg(feature = "views")]
+        Some(203) => {
+            view_position::ViewPositionInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        #[cfg(feature = "views")]
+        Some(204) => {
+            view_whitelist_entry::ViewWhitelistEntryInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        #[cfg(feature = "views")]
+        Some(205) => {
+            view_claim_receipt::ViewClaimReceiptInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        _ => Err(<PTokenProgramError as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into()),
+    }
+}
+
+// Layout regression tests, `[layout] golden` in jiminy.toml
+#[cfg(test)]
+mod layout_tests {
+    #[test]
+    fn platform_layout() {
+        assert_eq!(core::mem::size_of::<crate::state::Platform>(), 207);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, authority), 0);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, fee), 32);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, platform_bump), 34);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, vault_bump), 35);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, reentrancy_lock), 36);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, fee_mint), 37);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, fee_ratio_num), 69);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, fee_ratio_den), 77);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, sponsor_rent), 85);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, total_votes), 86);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, total_volume), 94);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, total_fees), 102);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, vault), 110);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, fee_policy), 142);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, fee_params), 143);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, min_duration), 159);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, max_duration), 167);
+        assert_eq!(core::mem::offset_of!(crate::state::Platform, treasury), 175);
+        jiminy::testing::check_layout::<crate::state::Platform>(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/layouts"),
+            8,
+        );
+    }
+
+    #[test]
+    fn treasury_layout() {
+        assert_eq!(core::mem::size_of::<crate::state::Treasury>(), 49);
+        assert_eq!(core::mem::offset_of!(crate::state::Treasury, fees_collected_lamports), 0);
+        assert_eq!(core::mem::offset_of!(crate::state::Treasury, fees_collected_tokens), 8);
+        assert_eq!(core::mem::offset_of!(crate::state::Treasury, bump), 16);
+        assert_eq!(core::mem::offset_of!(crate::state::Treasury, lamport_gap), 17);
+        assert_eq!(core::mem::offset_of!(crate::state::Treasury, token_gap), 25);
+        assert_eq!(core::mem::offset_of!(crate::state::Treasury, reconciled_slot), 33);
+        assert_eq!(core::mem::offset_of!(crate::state::Treasury, fees_waived_tokens), 41);
+        jiminy::testing::check_layout::<crate::state::Treasury>(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/layouts"),
+            1,
+        );
+    }
+
+    #[test]
+    fn vote_layout() {
+        assert_eq!(core::mem::size_of::<crate::state::Vote>(), 254);
+        assert_eq!(core::mem::offset_of!(crate::state::Vote, token), 0);
+        assert_eq!(core::mem::offset_of!(crate::state::Vote, tallies), 32);
+        assert_eq!(core::mem::offset_of!(crate::state::Vote, deadline), 96);
+        assert_eq!(core::mem::offset_of!(crate::state::Vote, min_total), 105);
+        assert_eq!(core::mem::offset_of!(crate::state::Vote, resolver), 113);
+        assert_eq!(core::mem::offset_of!(crate::state::Vote, resolved_outcome), 146);
+        assert_eq!(core::mem::offset_of!(crate::state::Vote, vault_bump), 147);
+        assert_eq!(core::mem::offset_of!(crate::state::Vote, label), 148);
+        assert_eq!(core::mem::offset_of!(crate::state::Vote, creator), 212);
+        assert_eq!(core::mem::offset_of!(crate::state::Vote, max_position), 244);
+        assert_eq!(core::mem::offset_of!(crate::state::Vote, whitelist_only), 252);
+        assert_eq!(core::mem::offset_of!(crate::state::Vote, outcome_count), 253);
+        jiminy::testing::check_layout::<crate::state::Vote>(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/layouts"),
+            3,
+        );
+    }
+
+    #[test]
+    fn position_layout() {
+        assert_eq!(core::mem::size_of::<crate::state::Position>(), 35);
+        assert_eq!(core::mem::offset_of!(crate::state::Position, amount), 0);
+        assert_eq!(core::mem::offset_of!(crate::state::Position, side), 8);
+        assert_eq!(core::mem::offset_of!(crate::state::Position, bump), 9);
+        assert_eq!(core::mem::offset_of!(crate::state::Position, allow_crank), 10);
+        assert_eq!(core::mem::offset_of!(crate::state::Position, created_at_slot), 11);
+        assert_eq!(core::mem::offset_of!(crate::state::Position, remaining_entitlement), 19);
+        assert_eq!(core::mem::offset_of!(crate::state::Position, updated_at_slot), 27);
+        jiminy::testing::check_layout::<crate::state::Position>(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/layouts"),
+            2,
+        );
+    }
+
+    #[test]
+    fn whitelist_entry_layout() {
+        assert_eq!(core::mem::size_of::<crate::state::WhitelistEntry>(), 65);
+        assert_eq!(core::mem::offset_of!(crate::state::WhitelistEntry, vote), 0);
+        assert_eq!(core::mem::offset_of!(crate::state::WhitelistEntry, wallet), 32);
+        assert_eq!(core::mem::offset_of!(crate::state::WhitelistEntry, bump), 64);
+        jiminy::testing::check_layout::<crate::state::WhitelistEntry>(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/layouts"),
+            1,
+        );
+    }
+
+    #[test]
+    fn claim_receipt_layout() {
+        assert_eq!(core::mem::size_of::<crate::state::ClaimReceipt>(), 1);
+        assert_eq!(core::mem::offset_of!(crate::state::ClaimReceipt, bump), 0);
+        jiminy::testing::check_layout::<crate::state::ClaimReceipt>(
+            concat!(env!("CARGO_MANIFEST_DIR"), "/layouts"),
+            1,
+        );
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{
+    state::{Vote, WhitelistEntry, WhitelistEntryInit, WHITELIST_SEED},
+    PTokenProgramError,
+};
+use jiminy::{define_instruction_with_metadata, pda::PdaCache};
+
+define_instruction_with_metadata!(
+    discriminant: 11,
+    AddToWhitelist,
+    desc: "Let a wallet open a position on a whitelist-only vote",
+    accounts: {
+        creator: signer => writable, desc: "Creator of the vote, pays the entry rent",
+        whitelist_entry: uninitialized, desc: "whitelist entry pda for vote and wallet",
+        vote: program, desc: "vote account",
+        system_program,
+    },
+    data: {
+        /// Wallet allowed to open a position
+        wallet: [u8; 32],
+    },
+    process: {
+        if read_state!(vote, Vote, |v| v.creator) != *creator.key() {
+            return Err(PTokenProgramError::CreatorMismatch.into());
+        }
+
+        let mut pdas = PdaCache::<1>::new();
+        let entry_bump = assert_pda_cached!(pdas, whitelist_entry,
+            seeds: [WHITELIST_SEED, vote.key().as_ref(), wallet.as_ref()],
+            error: PTokenProgramError::WhitelistEntryKeyIncorrect, context: "whitelist_entry");
+        create_pda_cached!(pdas,
+            from: creator,
+            to: whitelist_entry,
+            space: WhitelistEntry::LEN,
+            seeds: [WHITELIST_SEED, vote.key().as_ref(), wallet.as_ref()]
+        );
+
+        load_mut!(whitelist_entry, WhitelistEntry).init(WhitelistEntryInit {
+            vote: *vote.key(),
+            wallet,
+            bump: entry_bump,
+        });
+
+        Ok(())
+    }
+);
@@ -0,0 +1,38 @@
+use crate::{
+    state::{Platform, PLATFORM_SEED},
+    utils::FeePolicy,
+    PTokenProgramError,
+};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 33,
+    AdminResetPlatform,
+    desc: "Hand the platform to the program's upgrade authority and clear its fee",
+    accounts: {
+        upgrade_authority: signer, desc: "Upgrade authority of the program",
+        platform: program => writable, desc: "Platform pda key",
+        programdata: any, desc: "Programdata account of this program",
+        program: any, desc: "This program",
+    },
+    data: {},
+    process: {
+        // Recovery path for a lost or compromised platform authority
+        assert_upgrade_authority!(program, programdata, upgrade_authority);
+
+        let platform_state = load_mut!(platform, Platform);
+        assert_pda!(platform, seeds: [PLATFORM_SEED], bump: platform_state.platform_bump,
+            error: PTokenProgramError::PlatformKeyIncorrect, context: "platform");
+
+        // Hand the platform to the upgrade authority with no fee
+        platform_state.authority = upgrade_authority.key().into();
+        platform_state.set_fee_policy(FeePolicy::Bps {
+            bps: 0,
+            min: 0,
+            free_below: 0,
+        });
+        platform_state.reentrancy_lock = 0;
+
+        Ok(())
+    }
+);
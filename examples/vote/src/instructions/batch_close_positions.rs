@@ -0,0 +1,60 @@
+use crate::{
+    state::{Position, TokenAccountData, Vote, POSITION_SEED},
+    PTokenProgramError,
+};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 13,
+    BatchClosePositions,
+    desc: "Refund and close many positions on a vote that missed its quorum, the rent goes to the cranker",
+    accounts: {
+        cranker: signer => writable, desc: "Anyone, receives the closed positions' rent",
+        vote_vault_token_account: token => writable, desc: "votes token account for storing funds",
+        vote: program, desc: "vote account",
+        vote_vault: any, desc: "votes vault pda",
+    },
+    remaining_accounts: positions, desc: "(position, owner token account) pairs, the owner being the position's authority",
+    data: {},
+    context: ctx,
+    prepare: {
+        // Copy the vote out so no data borrow is held across the transfers
+        let vote_state = read_state!(vote, Vote, |v| *v);
+        assert_pda!(vote_vault, seeds: [vote.key().as_ref()], bump: vote_state.vault_bump,
+            error: PTokenProgramError::VoteVaultKeyIncorrect, context: "vote_vault");
+
+        // Same conditions as RefundPosition
+        if !vote_state.deadline.is_reached(ctx.clock()?) {
+            return Err(PTokenProgramError::VoteIsStillRunning.into());
+        }
+        if vote_state.quorum_met()? {
+            return Err(PTokenProgramError::QuorumWasMet.into());
+        }
+        let vault_bump = [vote_state.vault_bump];
+    },
+    process: {
+        for pair in positions.typed().chunks_of(2)? {
+            let mut pair = pair.typed();
+            let position = next_validated!(pair, program => writable);
+            let owner_token_account = next_validated!(pair, token => writable);
+
+            // The position is derived from its authority, which must own the token account
+            let owner = read_state!(owner_token_account, TokenAccountData, |t| t.owner);
+            let position_state = read_state!(position, Position, |p| *p);
+            assert_pda!(position,
+                seeds: [POSITION_SEED, vote.key().as_ref(), owner.as_ref()],
+                bump: position_state.bump,
+                error: PTokenProgramError::PositionKeyIncorrect, context: "position");
+
+            // The whole stake goes back, no fee is taken
+            let refund_amount = position_state.amount.get();
+            transfer_tokens!(vote_vault_token_account, owner_token_account, vote_vault, refund_amount,
+                seeds: [vote.key().as_ref(), &vault_bump]);
+
+            // Closed positions fail `program` validation, so a repeated pair can't refund twice
+            close_account!(position, cranker);
+        }
+
+        Ok(())
+    }
+);
@@ -0,0 +1,46 @@
+use crate::{
+    state::{Platform, PLATFORM_SEED},
+    PTokenProgramError,
+};
+use jiminy::{define_instruction_with_metadata, syscalls};
+use pinocchio::instruction::{AccountMeta, Instruction};
+
+// Max length of the instruction data forwarded to the adapter
+pub const MAX_ADAPTER_DATA_LEN: usize = 256;
+
+define_instruction_with_metadata!(
+    discriminant: 6,
+    CallAdapter,
+    desc: "Call an adapter program with the platform PDA, guarded against reentrancy",
+    accounts: {
+        authority: signer, desc: "Authority calling the adapter",
+        platform: program => writable, desc: "Platform pda key, holds the reentrancy lock",
+        adapter_program: any, desc: "Adapter program to call",
+        vote_program: address(crate::ID), desc: "This program, so the adapter can call back into it",
+    },
+    data: {},
+    rest: adapter_data, max_len: MAX_ADAPTER_DATA_LEN,
+    process: {
+        // Copy the bump out so the platform isn't borrowed while the guard holds it
+        let platform_bump = read_state!(platform, Platform, |p| p.platform_bump);
+        assert_pda!(platform, seeds: [PLATFORM_SEED], bump: platform_bump,
+            error: PTokenProgramError::PlatformKeyIncorrect, context: "platform");
+
+        // The adapter is untrusted: anything it does to call back into this
+        // program before returning fails with ReentrancyDetected
+        reentrancy_guard!(platform, Platform, reentrancy_lock,
+            error: PTokenProgramError::ReentrancyDetected, {
+            let account_metas = [
+                AccountMeta::readonly_signer(authority.key()),
+                AccountMeta::writable(platform.key()),
+                AccountMeta::readonly(vote_program.key()),
+            ];
+            let adapter_ix = Instruction {
+                program_id: adapter_program.key(),
+                accounts: &account_metas,
+                data: adapter_data,
+            };
+            syscalls::invoke("adapter", &adapter_ix, &[authority, platform, vote_program])
+        })
+    }
+);
@@ -0,0 +1,55 @@
+use crate::{
+    state::{Platform, Treasury, PLATFORM_SEED},
+    PTokenProgramError,
+};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 36,
+    ClaimFees,
+    desc: "Withdraw collected fees from the vault, at most what the treasury has counted",
+    accounts: {
+        authority: signer, desc: "Authority of the platform",
+        treasury: program => writable, desc: "Treasury pda, the claim comes off its counters",
+        vault: any => writable, desc: "platforms fee vault pda",
+        vault_token_account: token => writable, desc: "vault token account the tokens come from",
+        destination: any => writable, desc: "Receives the lamports",
+        destination_token_account: token => writable, desc: "Receives the tokens",
+        platform: program, desc: "Platform pda key",
+        token_program,
+    },
+    data: {
+        /// Lamports to withdraw, little-endian
+        lamports: [u8; 8],
+        /// Tokens to withdraw from `vault_token_account`, little-endian
+        tokens: [u8; 8],
+    },
+    process: {
+        let platform_state = read_state!(platform, Platform, |p| *p);
+        assert_pda!(platform, seeds: [PLATFORM_SEED], bump: platform_state.platform_bump,
+            error: PTokenProgramError::PlatformKeyIncorrect, context: "platform");
+        if platform_state.authority != *authority.key() {
+            return Err(ProgramError::IncorrectAuthority);
+        }
+        // `treasury` comes before `platform`, so it can't be declared `key_in`
+        if !jiminy::perf::keys_eq(platform_state.treasury.as_key(), treasury.key()) {
+            fail!(PTokenProgramError::TreasuryKeyIncorrect,
+                "treasury is not the platform's treasury");
+        }
+        jiminy::vault::check_token_owner(vault_token_account, vault.key())?;
+
+        // Only counted fees leave, the rest of the vault stays where it is
+        let lamports = u64::from_le_bytes(lamports);
+        let tokens = u64::from_le_bytes(tokens);
+        load_mut!(treasury, Treasury).claim(lamports, tokens)?;
+
+        vault_withdraw_sol!(vault, seeds: [platform.key().as_ref()],
+            bump: platform_state.vault_bump, error: PTokenProgramError::VaultKeyIncorrect,
+            to: destination, amount: lamports);
+        vault_withdraw_tokens!(vault, seeds: [platform.key().as_ref()],
+            bump: platform_state.vault_bump, error: PTokenProgramError::VaultKeyIncorrect,
+            from: vault_token_account, to: destination_token_account, amount: tokens);
+
+        Ok(())
+    }
+);
@@ -0,0 +1,101 @@
+/// Create the platform PDA with its fee and treasury and fund its fee vault
+#[jiminy::instruction(discriminant = 0)]
+pub mod initialize_platform {
+    use crate::{
+        state::{
+            FeePolicyKind, Platform, PlatformInit, Treasury, TreasuryInit, PLATFORM_SEED,
+            TREASURY_SEED,
+        },
+        utils::MAX_FEE_BPS,
+        PTokenProgramError,
+    };
+
+    accounts! {
+        authority: signer => writable, desc: "Authority of the vault",
+        platform: uninitialized, desc: "Platform pda key", label: "Platform Config",
+            explorer_hint: pda,
+        vault: any => writable, desc: "platforms fee vault pda",
+        treasury: uninitialized, desc: "Treasury pda counting the fees in the vault",
+        system_program,
+    }
+
+    data! {
+        /// Fee in basis points, little-endian, at most MAX_FEE_BPS
+        fee: [u8; 2],
+        /// Bump of the platform PDA
+        platform_bump: u8,
+        /// Bump of the fee vault PDA
+        vault_bump: u8,
+        /// Bump of the treasury PDA
+        treasury_bump: u8,
+    }
+
+    process! {
+        if u16::from_le_bytes(fee) > MAX_FEE_BPS {
+            return Err(PTokenProgramError::FeeTooHigh.into());
+        }
+
+        let vault_key = assert_pda!(vault, seeds: [platform.key().as_ref()], bump: vault_bump,
+            error: PTokenProgramError::VaultKeyIncorrect, context: "vault");
+        let treasury_key = assert_pda!(treasury,
+            seeds: [TREASURY_SEED, platform.key().as_ref()], bump: treasury_bump,
+            error: PTokenProgramError::TreasuryKeyIncorrect, context: "treasury");
+
+        // Create platform account
+        create_pda!(
+            from: authority,
+            to: platform,
+            space: Platform::LEN,
+            seeds: [PLATFORM_SEED],
+            bump: platform_bump
+        );
+
+        // Initialize platform state
+        load_mut!(platform, Platform).init(PlatformInit {
+            authority: authority.key().into(),
+            fee,
+            platform_bump,
+            vault_bump,
+            reentrancy_lock: 0,
+            // Fees start out in the vote token, see UpdatePlatform's UPDATE_FEE_MINT
+            fee_mint: [0; 32],
+            fee_ratio_num: [0; 8],
+            fee_ratio_den: [0; 8],
+            // Users pay their own position rent until UpdatePlatform's UPDATE_SPONSOR_RENT
+            sponsor_rent: 0,
+            total_votes: [0; 8],
+            total_volume: [0; 8],
+            total_fees: [0; 8],
+            vault: vault_key.into(),
+            // Plain basis points until SetFeePolicy
+            fee_policy: FeePolicyKind::Bps.to_u8(),
+            fee_params: [[0; 8]; 2],
+            // Votes can run for any duration until UpdatePlatform's UPDATE_DURATIONS
+            min_duration: [0; 8],
+            max_duration: [0; 8],
+            treasury: treasury_key.into(),
+        });
+
+        create_pda!(
+            from: authority,
+            to: treasury,
+            space: Treasury::LEN,
+            seeds: [TREASURY_SEED, platform.key().as_ref()],
+            bump: treasury_bump
+        );
+        load_mut!(treasury, Treasury).init(TreasuryInit {
+            fees_collected_lamports: 0.into(),
+            fees_collected_tokens: 0.into(),
+            bump: treasury_bump,
+            lamport_gap: [0; 8],
+            token_gap: [0; 8],
+            reconciled_slot: 0.into(),
+            fees_waived_tokens: 0.into(),
+        });
+
+        // Initialize vault
+        transfer_sol!(authority, vault, (0.01 * 1e9) as u64);
+
+        Ok(())
+    }
+}
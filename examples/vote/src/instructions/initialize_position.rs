@@ -0,0 +1,190 @@
+use crate::{
+    events::PositionChanged,
+    state::{
+        FundingMode, Platform, Position, PositionInit, TokenAccountData, Treasury, Vote,
+        WhitelistEntry, PLATFORM_SEED, POSITION_SEED, WHITELIST_SEED,
+    },
+    utils::{check_delegation, check_fee_accounts},
+    PTokenProgramError,
+};
+use jiminy::{define_instruction_with_metadata, pda::PdaCache};
+
+define_instruction_with_metadata!(
+    discriminant: 3,
+    InitializePosition,
+    desc: "Open a position on one outcome of a vote, paying the platform fee",
+    accounts: {
+        authority: signer => writable, desc: "Authority of the vault",
+        vote: program => writable, desc: "vote account",
+        vote_vault_token_account: token => writable, desc: "votes token account for storing funds",
+        authority_token_account: token => writable, desc: "authorities token account for storing funds",
+        fee_token_account: token => writable, desc: "authorities token account paying the fee, in the fee mint if set",
+        vault_token_account: token => writable, desc: "vault token account for storing funds",
+        position: uninitialized, desc: "position pda for voting on one side",
+        platform: program => writable, desc: "Platform pda key, counts the stake and pays the position rent when it sponsors rent",
+        treasury: key_in(Platform::treasury of platform) => writable, desc: "Treasury pda, counts the fee",
+        vault: key_in(Platform::vault of platform), desc: "platforms fee vault pda",
+        token: token, desc: "vote token",
+        vote_vault: any, desc: "votes vault pda",
+        whitelist_entry: any, desc: "whitelist entry pda for vote and authority, checked on whitelist-only votes",
+    },
+    data: {
+        /// Stake in vote token base units
+        amount: [u8; 8],
+        /// Outcome the stake goes to, 0 (false) or 1 (true) on two-outcome votes
+        side: u8,
+        /// 0 if `authority` owns the token accounts, 1 if it spends as their delegate
+        funding_mode: FundingMode,
+        /// Nonzero to let anyone redeem the position for `authority` with RedeemFor
+        allow_crank: u8,
+    },
+    context: ctx,
+    prepare: {
+        // Amounts are big-endian in the instruction data
+        // jiminy: allow(byte_order)
+        let init_amount = u64::from_be_bytes(amount);
+        // A position with no stake would hold rent and could never win anything
+        if init_amount == 0 {
+            return Err(PTokenProgramError::ZeroAmount.into());
+        }
+
+        // Handle extra security checks here
+        // mainly that platform, vote_vault, and position_pda are correct
+        let platform_state = load_mut!(platform, Platform);
+        let vote_state = load_mut!(vote, Vote);
+
+        // Validate all PDAs at once
+        validate_pdas!(
+            platform => seeds: [PLATFORM_SEED], bump: platform_state.platform_bump,
+                error: PTokenProgramError::PlatformKeyIncorrect, context: "platform";
+            vote_vault => seeds: [vote.key().as_ref()], bump: vote_state.vault_bump,
+                error: PTokenProgramError::VoteVaultKeyIncorrect, context: "vote_vault"
+        );
+        // The stake must land in the vote's vault, not any token account the caller passes
+        jiminy::vault::check_token_owner(vote_vault_token_account, vote_vault.key())?;
+
+        // cant use derive_address yet for security concerns
+        // find the position PDA once and reuse it when creating the account
+        let mut pdas = PdaCache::<1>::new();
+        let position_bump = assert_pda_cached!(pdas, position,
+            seeds: [POSITION_SEED, vote.key().as_ref(), authority.key().as_ref()],
+            error: PTokenProgramError::PositionKeyIncorrect, context: "position");
+
+        // Don't let user create or update positions if the vote
+        // has already ended
+        if vote_state.deadline.is_expired(ctx.clock()?) {
+            return Err(PTokenProgramError::VoteHasAlreadyEnded.into());
+        }
+        vote_state.check_outcome(side)?;
+        // Before the position is created, so a rejected stake costs no rent
+        vote_state.check_position_cap(init_amount)?;
+
+        // Whitelist-only votes need the wallet's entry, created by AddToWhitelist
+        if vote_state.is_whitelist_only()? {
+            if !whitelist_entry.is_owned_by(&crate::ID) {
+                return Err(PTokenProgramError::NotWhitelisted.into());
+            }
+            let entry = read_state!(whitelist_entry, WhitelistEntry, |e| *e);
+            assert_pda!(whitelist_entry,
+                seeds: [WHITELIST_SEED, vote.key().as_ref(), authority.key().as_ref()],
+                bump: entry.bump,
+                error: PTokenProgramError::WhitelistEntryKeyIncorrect, context: "whitelist_entry");
+            if !entry.admits(vote.key(), authority.key()) {
+                return Err(PTokenProgramError::NotWhitelisted.into());
+            }
+        }
+
+        // Initialize the position account, with the rent paid by the platform if it sponsors it
+        // and the slot it's created in recorded for the `flashloan-guard` age check
+        let created_at_slot = if platform_state.sponsors_rent() {
+            create_pda_sponsored!(
+                sponsor: platform,
+                to: position,
+                space: Position::LEN,
+                seeds: [POSITION_SEED, vote.key().as_ref(), authority.key().as_ref()],
+                bump: position_bump,
+                created_at_slot: Position.created_at_slot
+            )
+        } else {
+            create_pda_cached!(pdas,
+                from: authority,
+                to: position,
+                space: Position::LEN,
+                seeds: [POSITION_SEED, vote.key().as_ref(), authority.key().as_ref()],
+                created_at_slot: Position.created_at_slot
+            )
+        };
+
+        // Appropriate token and fee amounts
+        // Fees are counted in the vote token and paid in the platform's fee mint, if set
+        check_fee_accounts(platform_state, vote_state.token.as_key(), fee_token_account,
+            vault_token_account)?;
+        jiminy::vault::check_token_owner(vault_token_account, vault.key())?;
+        let fee_amount = platform_state.fee_policy()?.compute(init_amount)?;
+        let fee_amount = platform_state.fee_in_fee_mint(fee_amount)?;
+        // The platform authority's own positions don't pay it a fee
+        let (fee_amount, waived_fee) = platform_state.fee_for(authority.key(), fee_amount);
+        // The fee comes out of the same account when the fee mint is the vote token
+        let authority_spend = if fee_token_account.key() == authority_token_account.key() {
+            init_amount + fee_amount
+        } else {
+            init_amount
+        };
+        // A delegate moves tokens it doesn't own, so check its approvals before the
+        // transfers; the token program then takes `authority` as the delegate
+        if funding_mode == FundingMode::Delegate {
+            check_delegation(authority_token_account, authority.key(), authority_spend)?;
+            if fee_token_account.key() != authority_token_account.key() {
+                check_delegation(fee_token_account, authority.key(), fee_amount)?;
+            }
+        }
+        let vault_balance_before =
+            read_state!(vote_vault_token_account, TokenAccountData, |t| t.amount());
+    },
+    transfers: {
+        // Initialize the position vault by sending it some tokens
+        authority_token_account => vote_vault_token_account: init_amount, authority: authority;
+        // Take our fee, skipped when it's zero
+        fee_token_account => vault_token_account: fee_amount, authority: authority;
+    },
+    invariants: {
+        vote_vault_token_account.tokens: increases_by(init_amount),
+        vault_token_account.tokens: increases_by(fee_amount),
+        authority_token_account.tokens: decreases_by(authority_spend),
+    },
+    process: {
+        // The transfer CPIs changed the vault's data, so read it again
+        let vault_balance_after =
+            read_state!(vote_vault_token_account, TokenAccountData, |t| t.amount());
+        if vault_balance_before.checked_add(init_amount) != Some(vault_balance_after) {
+            return Err(PTokenProgramError::VaultBalanceMismatch.into());
+        }
+
+        platform_state.record_stake(init_amount, fee_amount)?;
+        let treasury_state = load_mut!(treasury, Treasury);
+        treasury_state.record_tokens(fee_amount)?;
+        treasury_state.record_waived_tokens(waived_fee)?;
+
+        // lastly set position account data
+        load_mut!(position, Position).init(PositionInit {
+            amount: init_amount.into(),
+            side,
+            bump: position_bump,
+            allow_crank,
+            created_at_slot: created_at_slot.into(),
+            remaining_entitlement: 0.into(),
+            updated_at_slot: 0.into(),
+        });
+
+        vote_state.add_stake(side, init_amount)?;
+        emit_event!(PositionChanged {
+            vote: vote.key().into(),
+            authority: authority.key().into(),
+            side,
+            new_amount: init_amount.into(),
+            tally: vote_state.tally(side).into(),
+        });
+
+        Ok(())
+    }
+);
@@ -0,0 +1,227 @@
+use crate::{
+    events::VoteCreated,
+    state::{
+        Platform, Treasury, Vote, VoteInit, MAX_LABEL_LEN, MAX_OUTCOMES, PLATFORM_SEED, UNRESOLVED,
+    },
+    utils::calculate_fees,
+    PTokenProgramError,
+};
+use jiminy::{
+    define_instruction_with_metadata, syscalls,
+    time::{Deadline, DeadlineKind},
+    FixedStr, JiminyContext,
+};
+use pinocchio_log::log;
+
+define_instruction_with_metadata!(
+    discriminant: 2,
+    InitializeVote,
+    desc: "Create a true/false vote on a token with its vault and vault token account",
+    accounts: {
+        authority: signer => writable, desc: "Authority of the vault",
+        vote: signer => writable, desc: "new vote account",
+        vault: any => writable, desc: "platforms fee vault pda",
+        vote_vault: any => writable, desc: "votes vault pda",
+        vote_vault_token_account: uninitialized, desc: "votes token account for storing funds",
+            label: "Vote Vault", explorer_hint: token_account,
+        platform: program => writable, desc: "Platform pda key, counts the vote in its stats",
+        treasury: key_in(Platform::treasury of platform) => writable, desc: "Treasury pda, counts the fee",
+        token: token, desc: "vote token", explorer_hint: mint,
+        rent: rent_sysvar,
+        system_program,
+        token_program,
+        associated_token_program: ata_program,
+    },
+    data: {
+        /// 0 counts `time_to_add` in seconds, 1 in slots, 2 in epochs
+        deadline_kind: u8,
+        /// How long the vote runs, little-endian u64 in units of `deadline_kind`
+        time_to_add: [u8; 8],
+        /// Minimum total stake for the vote to resolve, below it positions are refunded
+        min_total: [u8; 8],
+        /// Key that picks the outcome, all zeros to resolve by tally
+        resolver: [u8; 32],
+        /// Most stake one position can hold, zero for no cap
+        max_position: [u8; 8],
+        /// Only let wallets added with AddToWhitelist open positions
+        whitelist_only: u8 => bool,
+    },
+    rest: label, max_len: MAX_LABEL_LEN,
+    context: ctx,
+    process: {
+        let accounts = InitializeVote {
+            authority,
+            vote,
+            vault,
+            vote_vault,
+            vote_vault_token_account,
+            platform,
+            treasury,
+            token,
+            rent,
+            system_program,
+            token_program,
+            associated_token_program,
+        };
+        let data = InitializeVoteData {
+            deadline_kind,
+            time_to_add,
+            min_total,
+            resolver,
+            max_position,
+            whitelist_only,
+        };
+        initialize_vote(&ctx, authority, &accounts, data, 2, label)
+    }
+);
+
+/// Check the vote's PDAs, create the vote and its vault token account and take the
+/// platform fee, shared with `InitializeVoteWithPayer` and `InitializeMultiOutcomeVote`;
+/// `payer` funds every lamport and may be the authority, which stays the vote's creator
+pub(crate) fn initialize_vote(
+    ctx: &JiminyContext,
+    payer: &AccountInfo,
+    accounts: &InitializeVote,
+    data: InitializeVoteData,
+    outcome_count: u8,
+    label: &[u8],
+) -> ProgramResult {
+    let InitializeVote {
+        authority,
+        vote,
+        vault,
+        vote_vault,
+        vote_vault_token_account,
+        platform,
+        treasury,
+        token,
+        system_program,
+        token_program,
+        ..
+    } = *accounts;
+    let InitializeVoteData {
+        deadline_kind,
+        time_to_add,
+        min_total,
+        resolver,
+        max_position,
+        whitelist_only,
+    } = data;
+    let deadline_kind =
+        DeadlineKind::from_u8(deadline_kind).ok_or(PTokenProgramError::InvalidDeadlineKind)?;
+    let time_to_add = u64::from_le_bytes(time_to_add);
+    if !(2..=MAX_OUTCOMES).contains(&(outcome_count as usize)) {
+        return Err(PTokenProgramError::InvalidOutcomeCount.into());
+    }
+
+    // Handle extra checks here
+    // mainly that platform, vault, and vote_vault are correct
+    let platform_state = load_mut!(platform, Platform);
+    assert_pda!(platform, seeds: [PLATFORM_SEED], bump: platform_state.platform_bump,
+        error: PTokenProgramError::PlatformKeyIncorrect, context: "platform");
+    platform_state.check_vote_duration(deadline_kind, time_to_add)?;
+    // `vault` comes before `platform`, so it can't be declared `key_in`
+    if !jiminy::perf::keys_eq(platform_state.vault.as_key(), vault.key()) {
+        fail!(PTokenProgramError::VaultKeyIncorrect, "vault is not the platform's fee vault");
+    }
+    // cant use derive_address yet for security concerns
+    // find the vault PDA
+    let (vote_vault_pda, vote_vault_bump) =
+        syscalls::find_program_address(&[vote.key().as_ref()], &crate::ID);
+    // check that it matches what the user supplied:
+    if vote_vault.key().ne(&vote_vault_pda) {
+        return Err(PTokenProgramError::VoteVaultKeyIncorrect.into());
+    }
+    // make sure the token account is correct for the vault and then make it
+    let (vote_vault_token_account_pda, _vote_vault_token_account_bump) =
+        syscalls::find_program_address(
+            &[
+                vote_vault_pda.as_ref(),
+                pinocchio_token::ID.as_ref(),
+                token.key().as_ref(),
+            ],
+            &pinocchio_associated_token_account::ID,
+        );
+    // check that it matches what the user supplied:
+    if vote_vault_token_account
+        .key()
+        .ne(&vote_vault_token_account_pda)
+    {
+        return Err(PTokenProgramError::VoteVaultTokenAccountIncorrect.into());
+    }
+
+    // Initialize the vote account using create_pda macro
+    // Note: vote is a signer account, so we can't use create_pda here
+    // Keep the manual CreateAccount for signer accounts
+    let lamports = ctx.rent()?.minimum_balance(Vote::LEN);
+    jiminy::payer::check(payer, lamports)?;
+    syscalls::cpi_applying(
+        "system::create_account",
+        || {
+            pinocchio_system::instructions::CreateAccount {
+                from: payer,
+                to: vote,
+                space: Vote::LEN as u64,
+                lamports,
+                owner: &crate::ID,
+            }
+            .invoke()
+        },
+        || syscalls::system::create_account(payer, vote, lamports, Vote::LEN as u64, &crate::ID),
+    )?;
+    log!("the vote account was made");
+
+    create_ata!(
+        payer: payer,
+        ata: vote_vault_token_account,
+        wallet: vote_vault,
+        mint: token,
+        system_program: system_program,
+        token_program: token_program
+    );
+    log!("the ata was made");
+
+    // set vote account data
+    // get the current clock onchain and add however long the user wants for the vote to it.
+    // dont let the user arbitratily choose a deadline for safety.
+    let deadline = Deadline::checked_from_now(deadline_kind, ctx.clock()?, time_to_add)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    load_mut!(vote, Vote).init(VoteInit {
+        token: token.key().into(),
+        tallies: [0u64.to_le_bytes(); MAX_OUTCOMES],
+        deadline,
+        min_total,
+        // All zeros in the instruction data for no resolver
+        resolver: (resolver != [0; 32]).then_some(resolver).into(),
+        resolved_outcome: UNRESOLVED,
+        vault_bump: vote_vault_bump,
+        label: FixedStr::from_bytes(label)?,
+        creator: authority.key().into(),
+        max_position,
+        whitelist_only: (whitelist_only != 0).into(),
+        outcome_count,
+    });
+
+    let init_sol = (0.01 * 1e9) as u64;
+    // The policy's amounts are in tokens, the SOL fee only takes the rate
+    let fee_sol = calculate_fees(init_sol, u16::from_le_bytes(platform_state.fee))?;
+    platform_state.record_vote()?;
+    load_mut!(treasury, Treasury).record_lamports(fee_sol)?;
+    // Initialize the vote vault by sending it some sol
+    vault_deposit_sol!(vote_vault, seeds: [vote.key().as_ref()], bump: vote_vault_bump,
+        error: PTokenProgramError::VoteVaultKeyIncorrect,
+        from: payer, amount: init_sol);
+    // Take our fee
+    transfer_sol!(payer, vault, fee_sol);
+
+    emit_event!(VoteCreated {
+        vote: vote.key().into(),
+        token: token.key().into(),
+        creator: authority.key().into(),
+        deadline,
+        min_total: u64::from_le_bytes(min_total).into(),
+        outcome_count,
+    });
+
+    Ok(())
+}
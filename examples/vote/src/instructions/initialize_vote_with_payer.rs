@@ -0,0 +1,65 @@
+use super::initialize_vote::{initialize_vote, InitializeVote, InitializeVoteData};
+use crate::state::{Platform, MAX_LABEL_LEN};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 14,
+    InitializeVoteWithPayer,
+    desc: "Create a true/false vote like InitializeVote, with the rent, vault deposit and fee paid by a separate `payer`",
+    accounts: {
+        payer: signer => writable, desc: "Pays for the vote, its vault and the platform fee",
+        authority: signer, desc: "Creator of the vote",
+        vote: signer => writable, desc: "new vote account",
+        vault: any => writable, desc: "platforms fee vault pda",
+        vote_vault: any => writable, desc: "votes vault pda",
+        vote_vault_token_account: uninitialized, desc: "votes token account for storing funds",
+        platform: program => writable, desc: "Platform pda key, counts the vote in its stats",
+        treasury: key_in(Platform::treasury of platform) => writable, desc: "Treasury pda, counts the fee",
+        token: token, desc: "vote token",
+        rent: rent_sysvar,
+        system_program,
+        token_program,
+        associated_token_program: ata_program,
+    },
+    data: {
+        /// 0 counts `time_to_add` in seconds, 1 in slots, 2 in epochs
+        deadline_kind: u8,
+        /// How long the vote runs, little-endian u64 in units of `deadline_kind`
+        time_to_add: [u8; 8],
+        /// Minimum total stake for the vote to resolve, below it positions are refunded
+        min_total: [u8; 8],
+        /// Key that picks the outcome, all zeros to resolve by tally
+        resolver: [u8; 32],
+        /// Most stake one position can hold, zero for no cap
+        max_position: [u8; 8],
+        /// Only let wallets added with AddToWhitelist open positions
+        whitelist_only: u8 => bool,
+    },
+    rest: label, max_len: MAX_LABEL_LEN,
+    context: ctx,
+    process: {
+        let accounts = InitializeVote {
+            authority,
+            vote,
+            vault,
+            vote_vault,
+            vote_vault_token_account,
+            platform,
+            treasury,
+            token,
+            rent,
+            system_program,
+            token_program,
+            associated_token_program,
+        };
+        let data = InitializeVoteData {
+            deadline_kind,
+            time_to_add,
+            min_total,
+            resolver,
+            max_position,
+            whitelist_only,
+        };
+        initialize_vote(&ctx, payer, &accounts, data, 2, label)
+    }
+);
@@ -0,0 +1,116 @@
+use crate::{
+    state::{
+        FeePolicyKind, Platform, Treasury, TreasuryInit, PLATFORM_SEED, PLATFORM_V1_LEN,
+        PLATFORM_V2_LEN, PLATFORM_V3_LEN, PLATFORM_V4_LEN, PLATFORM_V5_LEN, PLATFORM_V6_LEN,
+        PLATFORM_V7_LEN, TREASURY_SEED,
+    },
+    PTokenProgramError,
+};
+use jiminy::{define_instruction_with_metadata, pda::PdaCache};
+
+define_instruction_with_metadata!(
+    discriminant: 34,
+    MigratePlatform,
+    desc: "Grow a platform created before the fee mint, sponsor, stats, vault, fee policy, duration or treasury fields to the current layout",
+    accounts: {
+        authority: signer => writable, desc: "Authority of the platform, pays the extra rent",
+        platform: program => writable, desc: "Platform pda key",
+        treasury: uninitialized, desc: "Treasury pda created by the migration",
+        system_program,
+    },
+    data: {},
+    context: ctx,
+    process: {
+        // Platforms created before `fee_mint`, `sponsor_rent`, the stats, `vault`, the
+        // fee policy, the duration bounds or the treasury existed are too short to load as
+        // `Platform`
+        let old_len = platform.data_len();
+        let old_lens = [
+            PLATFORM_V1_LEN,
+            PLATFORM_V2_LEN,
+            PLATFORM_V3_LEN,
+            PLATFORM_V4_LEN,
+            PLATFORM_V5_LEN,
+            PLATFORM_V6_LEN,
+            PLATFORM_V7_LEN,
+        ];
+        if !old_lens.contains(&old_len) {
+            return Err(PTokenProgramError::PlatformAlreadyMigrated.into());
+        }
+        platform.resize(Platform::LEN)?;
+
+        let mut pdas = PdaCache::<1>::new();
+        let treasury_bump = assert_pda_cached!(pdas, treasury,
+            seeds: [TREASURY_SEED, platform.key().as_ref()],
+            error: PTokenProgramError::TreasuryKeyIncorrect, context: "treasury");
+
+        {
+            let platform_state = load_mut!(platform, Platform);
+            assert_pda!(platform, seeds: [PLATFORM_SEED], bump: platform_state.platform_bump,
+                error: PTokenProgramError::PlatformKeyIncorrect, context: "platform");
+            if platform_state.authority != *authority.key() {
+                return Err(pinocchio::program_error::ProgramError::IncorrectAuthority);
+            }
+
+            // Fees stay in the vote token until the authority sets a fee mint
+            if old_len == PLATFORM_V1_LEN {
+                platform_state.fee_mint = [0; 32];
+                platform_state.fee_ratio_num = [0; 8];
+                platform_state.fee_ratio_den = [0; 8];
+            }
+            if old_len < PLATFORM_V3_LEN {
+                platform_state.sponsor_rent = 0;
+            }
+            // Stats count from the migration on
+            if old_len < PLATFORM_V4_LEN {
+                platform_state.total_votes = [0; 8];
+                platform_state.total_volume = [0; 8];
+                platform_state.total_fees = [0; 8];
+            }
+            if old_len < PLATFORM_V5_LEN {
+                platform_state.vault =
+                    derive_pda!(seeds: [platform.key().as_ref()], bump: platform_state.vault_bump)
+                        .into();
+            }
+            // The fee stays plain basis points of `fee`
+            if old_len < PLATFORM_V6_LEN {
+                platform_state.fee_policy = FeePolicyKind::Bps.to_u8();
+                platform_state.fee_params = [[0; 8]; 2];
+            }
+            // Votes stay unbounded until the authority sets the durations
+            if old_len < PLATFORM_V7_LEN {
+                platform_state.min_duration = [0; 8];
+                platform_state.max_duration = [0; 8];
+            }
+            platform_state.treasury = treasury.key().into();
+        }
+
+        // Fees taken before the migration aren't counted, ReconcileTreasury shows them as
+        // the gap between the vault and the counters
+        create_pda_cached!(pdas,
+            from: authority,
+            to: treasury,
+            space: Treasury::LEN,
+            seeds: [TREASURY_SEED, platform.key().as_ref()]
+        );
+        load_mut!(treasury, Treasury).init(TreasuryInit {
+            fees_collected_lamports: 0.into(),
+            fees_collected_tokens: 0.into(),
+            bump: treasury_bump,
+            lamport_gap: [0; 8],
+            token_gap: [0; 8],
+            reconciled_slot: 0.into(),
+            fees_waived_tokens: 0.into(),
+        });
+
+        // Top the platform up to the rent-exempt minimum of its new size
+        let missing = ctx.rent()?
+            .minimum_balance(Platform::LEN)
+            .saturating_sub(platform.lamports());
+        if missing > 0 {
+            transfer_sol!(authority, platform, missing);
+        }
+
+        Ok(())
+    }
+);
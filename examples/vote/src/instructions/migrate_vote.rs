@@ -0,0 +1,65 @@
+use crate::{
+    state::{Vote, MAX_OUTCOMES, VOTE_V1_LEN, VOTE_V2_LEN},
+    PTokenProgramError,
+};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 16,
+    MigrateVote,
+    desc: "Grow a vote created before multi-outcome tallies or the resolver tag to the current layout",
+    accounts: {
+        payer: signer => writable, desc: "Anyone, pays the extra rent",
+        vote: program => writable, desc: "vote account",
+        system_program,
+    },
+    data: {},
+    context: ctx,
+    process: {
+        // Older votes are too short to load as `Vote`
+        let len = vote.data_len();
+        if len != VOTE_V1_LEN && len != VOTE_V2_LEN {
+            return Err(PTokenProgramError::VoteAlreadyMigrated.into());
+        }
+        vote.resize(Vote::LEN)?;
+
+        let old_tallies = {
+            let mut data = vote.try_borrow_mut_data()?;
+            // The first layout had `true_votes` then `false_votes` where `tallies` starts,
+            // move the fields after them up past the longer tally array
+            let old_tallies = (len == VOTE_V1_LEN).then(|| {
+                let tallies = core::mem::offset_of!(Vote, tallies);
+                let rest = tallies + 2 * 8;
+                let true_votes: [u8; 8] = data[tallies..tallies + 8].try_into().unwrap();
+                let false_votes: [u8; 8] = data[tallies + 8..rest].try_into().unwrap();
+                data.copy_within(rest..VOTE_V1_LEN, core::mem::offset_of!(Vote, deadline));
+                (true_votes, false_votes)
+            });
+
+            // Then `resolver` was a bare key, all zeros for none, without its tag byte
+            let resolver = core::mem::offset_of!(Vote, resolver);
+            data.copy_within(resolver..VOTE_V2_LEN, resolver + 1);
+            data[resolver] = (data[resolver + 1..resolver + 33] != [0; 32]) as u8;
+            old_tallies
+        };
+
+        if let Some((true_votes, false_votes)) = old_tallies {
+            let vote_state = load_mut!(vote, Vote);
+            // Outcome 0 is false and 1 is true, matching `Side` and the old positions
+            vote_state.tallies = [[0; 8]; MAX_OUTCOMES];
+            vote_state.tallies[0] = false_votes;
+            vote_state.tallies[1] = true_votes;
+            vote_state.outcome_count = 2;
+        }
+
+        // Top the vote up to the rent-exempt minimum of its new size
+        let missing = ctx.rent()?
+            .minimum_balance(Vote::LEN)
+            .saturating_sub(vote.lamports());
+        if missing > 0 {
+            transfer_sol!(payer, vote, missing);
+        }
+
+        Ok(())
+    }
+);
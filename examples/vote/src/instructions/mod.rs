@@ -0,0 +1,47 @@
+pub mod add_to_whitelist;
+pub mod admin_reset_platform;
+pub mod batch_close_positions;
+pub mod call_adapter;
+pub mod claim_fees;
+pub mod initialize_multi_outcome_vote;
+pub mod initialize_platform;
+pub mod initialize_position;
+pub mod initialize_vote;
+pub mod initialize_vote_with_payer;
+pub mod migrate_platform;
+pub mod migrate_vote;
+pub mod reconcile_treasury;
+pub mod redeem_fee_voucher;
+pub mod redeem_for;
+pub mod redeem_winnings;
+pub mod refund_position;
+pub mod resolve_vote;
+pub mod set_fee_policy;
+pub mod update_platform;
+pub mod update_position;
+pub mod update_vote_settings;
+pub mod view_platform_stats;
+
+pub use add_to_whitelist::*;
+pub use admin_reset_platform::*;
+pub use batch_close_positions::*;
+pub use call_adapter::*;
+pub use claim_fees::*;
+pub use initialize_multi_outcome_vote::*;
+pub use initialize_platform::*;
+pub use initialize_position::*;
+pub use initialize_vote::*;
+pub use initialize_vote_with_payer::*;
+pub use migrate_platform::*;
+pub use migrate_vote::*;
+pub use reconcile_treasury::*;
+pub use redeem_fee_voucher::*;
+pub use redeem_for::*;
+pub use redeem_winnings::*;
+pub use refund_position::*;
+pub use resolve_vote::*;
+pub use set_fee_policy::*;
+pub use update_platform::*;
+pub use update_position::*;
+pub use update_vote_settings::*;
+pub use view_platform_stats::*;
@@ -0,0 +1,58 @@
+use crate::{
+    state::{Platform, TokenAccountData, Treasury, PLATFORM_SEED},
+    PTokenProgramError,
+};
+use jiminy::define_instruction_with_metadata;
+use pinocchio_log::log;
+
+define_instruction_with_metadata!(
+    discriminant: 37,
+    ReconcileTreasury,
+    desc: "Record how far the vault's balances are from the treasury's fee counters",
+    accounts: {
+        treasury: program => writable, desc: "Treasury pda, stores the gaps",
+        platform: program, desc: "Platform pda key",
+        vault: any, desc: "platforms fee vault pda",
+    },
+    remaining_accounts: vault_token_accounts, desc: "Every token account of the vault, each once",
+    data: {},
+    context: ctx,
+    process: {
+        let platform_state = read_state!(platform, Platform, |p| *p);
+        assert_pda!(platform, seeds: [PLATFORM_SEED], bump: platform_state.platform_bump,
+            error: PTokenProgramError::PlatformKeyIncorrect, context: "platform");
+        if !jiminy::perf::keys_eq(platform_state.treasury.as_key(), treasury.key()) {
+            fail!(PTokenProgramError::TreasuryKeyIncorrect,
+                "treasury is not the platform's treasury");
+        }
+        if !jiminy::perf::keys_eq(platform_state.vault.as_key(), vault.key()) {
+            fail!(PTokenProgramError::VaultKeyIncorrect, "vault is not the platform's fee vault");
+        }
+
+        // The vault's rent-exempt minimum was never a fee
+        let lamports = vault
+            .lamports()
+            .saturating_sub(ctx.rent()?.minimum_balance(vault.data_len()));
+
+        // Summed across mints like the counter, so a repeated account would count twice
+        let mut tokens = 0u64;
+        for (index, token_account) in vault_token_accounts.iter().enumerate() {
+            validate_account!(token_account, token);
+            jiminy::vault::check_token_owner(token_account, vault.key())?;
+            if vault_token_accounts[..index]
+                .iter()
+                .any(|seen| jiminy::perf::keys_eq(seen.key(), token_account.key()))
+            {
+                return Err(PTokenProgramError::DuplicateTokenAccount.into());
+            }
+            let amount = read_state!(token_account, TokenAccountData, |t| t.amount());
+            tokens = tokens.checked_add(amount).ok_or(ProgramError::ArithmeticOverflow)?;
+        }
+
+        let (lamport_gap, token_gap) =
+            load_mut!(treasury, Treasury).record_gaps(lamports, tokens, ctx.clock()?.slot);
+        log!("treasury gaps: {} lamports, {} tokens", lamport_gap, token_gap);
+
+        Ok(())
+    }
+);
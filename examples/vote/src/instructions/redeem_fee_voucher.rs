@@ -0,0 +1,56 @@
+use crate::{
+    state::{Platform, TokenAccountData, Treasury, PLATFORM_SEED},
+    PTokenProgramError,
+};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 22,
+    RedeemFeeVoucher,
+    desc: "Rebate fees to a wallet with a voucher the platform authority signed off-chain",
+    accounts: {
+        wallet: signer, desc: "Wallet the voucher is for, sends the transaction",
+        authority: signer, desc: "Authority of the platform, signed the voucher off-chain",
+        treasury: program => writable, desc: "Treasury pda, the rebate comes off its counters",
+        vault_token_account: token => writable, desc: "vault token account the rebate comes from",
+        wallet_token_account: token => writable, desc: "wallet's token account receiving the rebate",
+        platform: program, desc: "Platform pda key",
+        vault: key_in(Platform::vault of platform), desc: "platforms fee vault pda",
+        token_program,
+    },
+    data: {
+        /// Tokens to rebate from `vault_token_account`, little-endian
+        rebate: [u8; 8],
+        /// Last slot the voucher can be redeemed in, little-endian
+        expires_at_slot: [u8; 8],
+    },
+    expiry: slot_field(expires_at_slot),
+    process: {
+        let platform_state = read_state!(platform, Platform, |p| *p);
+        assert_pda!(platform, seeds: [PLATFORM_SEED], bump: platform_state.platform_bump,
+            error: PTokenProgramError::PlatformKeyIncorrect, context: "platform");
+        if platform_state.authority != *authority.key() {
+            return Err(ProgramError::IncorrectAuthority);
+        }
+        // `treasury` comes before `platform`, so it can't be declared `key_in`
+        if !jiminy::perf::keys_eq(platform_state.treasury.as_key(), treasury.key()) {
+            fail!(PTokenProgramError::TreasuryKeyIncorrect,
+                "treasury is not the platform's treasury");
+        }
+        jiminy::vault::check_token_owner(vault_token_account, vault.key())?;
+        // The rebate only goes to a token account the wallet owns
+        if read_state!(wallet_token_account, TokenAccountData, |t| t.owner) != *wallet.key() {
+            return Err(PTokenProgramError::BeneficiaryMismatch.into());
+        }
+
+        // Rebated fees were counted, so they come off the counters like a claim
+        let rebate = u64::from_le_bytes(rebate);
+        load_mut!(treasury, Treasury).claim(0, rebate)?;
+
+        vault_withdraw_tokens!(vault, seeds: [platform.key().as_ref()],
+            bump: platform_state.vault_bump, error: PTokenProgramError::VaultKeyIncorrect,
+            from: vault_token_account, to: wallet_token_account, amount: rebate);
+
+        Ok(())
+    }
+);
@@ -0,0 +1,99 @@
+use crate::{
+    events::WinningsRedeemed,
+    state::{
+        create_claim_receipt, ClaimReceipt, Platform, Position, TokenAccountData, Treasury,
+        Vote, PLATFORM_SEED, POSITION_SEED,
+    },
+    utils::{redemption_chunk, redemption_payout},
+    PTokenProgramError,
+};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 18,
+    RedeemFor,
+    desc: "Redeem a winning position for its authority, paid to the authority's token account",
+    // The `token` mint stays for clients built against the old account list
+    // jiminy: allow(unused_accounts)
+    accounts: {
+        cranker: signer => writable, desc: "Platform authority, or anyone if the position allows cranks; pays the receipt rent",
+        vote: program => writable, desc: "vote account",
+        vote_vault_token_account: token => writable, desc: "votes token account for storing funds",
+        beneficiary_token_account: token => writable, desc: "beneficiary's token account receiving the winnings",
+        vault_token_account: token => writable, desc: "vault token account for storing funds",
+        position: program => writable, desc: "beneficiary's position pda",
+        claim_receipt: uninitialized, desc: "receipt pda for vote and beneficiary, created by the redemption",
+        platform: program => writable, desc: "Platform pda key, counts the fee in its stats",
+        treasury: key_in(Platform::treasury of platform) => writable, desc: "Treasury pda, counts the fee",
+        vault: key_in(Platform::vault of platform) => writable, desc: "platforms fee vault pda",
+        beneficiary: any, desc: "Authority of the position, receives the winnings",
+        token: token, desc: "vote token",
+        vote_vault: any, desc: "votes vault pda",
+    },
+    data: {},
+    context: ctx,
+    process: {
+        let platform_state = load_mut!(platform, Platform);
+        let vote_state = load_mut!(vote, Vote);
+
+        assert_pda!(platform, seeds: [PLATFORM_SEED], bump: platform_state.platform_bump,
+            error: PTokenProgramError::PlatformKeyIncorrect, context: "platform");
+
+        // The position is the beneficiary's, the cranker only signs
+        let position_state = read_state!(position, Position, |p| *p);
+        assert_pda!(position,
+            seeds: [POSITION_SEED, vote.key().as_ref(), beneficiary.key().as_ref()],
+            bump: position_state.bump,
+            error: PTokenProgramError::PositionKeyIncorrect, context: "position");
+
+        // The platform authority can always crank, anyone else needs the position's approval
+        if cranker.key() != platform_state.authority.as_key() && !position_state.allows_crank() {
+            return Err(PTokenProgramError::CrankNotAllowed.into());
+        }
+
+        // Winnings only go to a token account the beneficiary owns
+        if read_state!(beneficiary_token_account, TokenAccountData, |t| t.owner)
+            != *beneficiary.key()
+        {
+            return Err(PTokenProgramError::BeneficiaryMismatch.into());
+        }
+
+        jiminy::vault::check_token_owner(vault_token_account, vault.key())?;
+
+        // Same conditions and amounts as RedeemWinnings
+        let payout = redemption_payout(vote_state, &position_state, &platform_state.fee_policy()?,
+            ctx.clock()?)?;
+        // Pays whatever a partial RedeemWinnings left
+        let chunk = redemption_chunk(&position_state, &payout, 0);
+        // The platform authority's own positions don't pay it a fee
+        let (fee, waived_fee) = platform_state.fee_for(beneficiary.key(), chunk.fee);
+
+        // The receipt is keyed on the beneficiary, so neither path can redeem the vote again
+        create_claim_receipt!(claim_receipt, vote: vote, authority: beneficiary, payer: cranker);
+
+        vault_withdraw_tokens!(vote_vault, seeds: [vote.key().as_ref()], bump: vote_state.vault_bump,
+            error: PTokenProgramError::VoteVaultKeyIncorrect,
+            from: vote_vault_token_account, to: beneficiary_token_account, amount: chunk.amount);
+        if fee > 0 {
+            vault_withdraw_tokens!(vote_vault, seeds: [vote.key().as_ref()],
+                bump: vote_state.vault_bump, error: PTokenProgramError::VoteVaultKeyIncorrect,
+                from: vote_vault_token_account, to: vault_token_account, amount: fee);
+        }
+
+        platform_state.record_fee(fee)?;
+        let treasury_state = load_mut!(treasury, Treasury);
+        treasury_state.record_tokens(fee)?;
+        treasury_state.record_waived_tokens(waived_fee)?;
+        emit_event!(WinningsRedeemed {
+            vote: vote.key().into(),
+            authority: beneficiary.key().into(),
+            reward: chunk.amount.into(),
+            fee: fee.into(),
+            remaining: chunk.remaining.into(),
+        });
+
+        close_account!(position, vault);
+
+        Ok(())
+    }
+);
@@ -0,0 +1,104 @@
+use crate::{
+    events::WinningsRedeemed,
+    state::{
+        create_claim_receipt, ClaimReceipt, Platform, Position, Treasury, Vote, PLATFORM_SEED,
+        POSITION_SEED,
+    },
+    utils::{redemption_chunk, redemption_payout},
+    PTokenProgramError,
+};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 5,
+    RedeemWinnings,
+    desc: "Pay a winning position its stake plus its share of the losing outcomes, or a chunk of it",
+    // The `token` mint stays for clients built against the old account list
+    // jiminy: allow(unused_accounts)
+    accounts: {
+        authority: signer => writable, desc: "Authority of the vault",
+        vote: program => writable, desc: "vote account",
+        vote_vault_token_account: token => writable, desc: "votes token account for storing funds",
+        authority_token_account: token => writable, desc: "authorities token account for storing funds",
+        vault_token_account: token => writable, desc: "vault token account for storing funds",
+        position: program => writable, desc: "position pda for voting on one side",
+        claim_receipt: uninitialized, desc: "receipt pda for vote and authority, created by the last redemption",
+        platform: program => writable, desc: "Platform pda key, counts the fee in its stats",
+        treasury: key_in(Platform::treasury of platform) => writable, desc: "Treasury pda, counts the fee",
+        vault: key_in(Platform::vault of platform) => writable, desc: "platforms fee vault pda",
+        token: token, desc: "vote token",
+        vote_vault: any, desc: "votes vault pda",
+    },
+    data: {
+        /// Most to pay out now, zero for everything left
+        max_amount: [u8; 8],
+    },
+    context: ctx,
+    process: {
+
+        // Handle extra security checks here
+        // mainly that platform and position_pda are correct
+        let platform_state = load_mut!(platform, Platform);
+        let vote_state = load_mut!(vote, Vote);
+
+        assert_pda!(platform, seeds: [PLATFORM_SEED], bump: platform_state.platform_bump,
+            error: PTokenProgramError::PlatformKeyIncorrect, context: "platform");
+
+        // Copy the position out so its data borrow is released before the
+        // account is closed below
+        let position_state = read_state!(position, Position, |p| *p);
+        // Validate position PDA
+        assert_pda!(position,
+            seeds: [POSITION_SEED, vote.key().as_ref(), authority.key().as_ref()],
+            bump: position_state.bump,
+            error: PTokenProgramError::PositionKeyIncorrect, context: "position");
+
+        jiminy::vault::check_token_owner(vault_token_account, vault.key())?;
+
+        // Fails unless the vote is over and the position is on the winning outcome
+        let payout = redemption_payout(vote_state, &position_state, &platform_state.fee_policy()?,
+            ctx.clock()?)?;
+        // Large payouts can be taken in chunks, the first one pays the whole fee
+        let chunk = redemption_chunk(&position_state, &payout, u64::from_le_bytes(max_amount));
+        // The platform authority's own positions don't pay it a fee
+        let (fee, waived_fee) = platform_state.fee_for(authority.key(), chunk.fee);
+
+        // Pay out the reward, signed by the vote vault
+        vault_withdraw_tokens!(vote_vault, seeds: [vote.key().as_ref()], bump: vote_state.vault_bump,
+            error: PTokenProgramError::VoteVaultKeyIncorrect,
+            from: vote_vault_token_account, to: authority_token_account, amount: chunk.amount);
+        // Take our fee
+        if fee > 0 {
+            vault_withdraw_tokens!(vote_vault, seeds: [vote.key().as_ref()],
+                bump: vote_state.vault_bump, error: PTokenProgramError::VoteVaultKeyIncorrect,
+                from: vote_vault_token_account, to: vault_token_account, amount: fee);
+        }
+
+        platform_state.record_fee(fee)?;
+        let treasury_state = load_mut!(treasury, Treasury);
+        treasury_state.record_tokens(fee)?;
+        treasury_state.record_waived_tokens(waived_fee)?;
+        emit_event!(WinningsRedeemed {
+            vote: vote.key().into(),
+            authority: authority.key().into(),
+            reward: chunk.amount.into(),
+            fee: fee.into(),
+            remaining: chunk.remaining.into(),
+        });
+
+        // Keep the position open until the whole reward is paid
+        if chunk.remaining > 0 {
+            load_mut!(position, Position).remaining_entitlement.set(chunk.remaining);
+            return Ok(());
+        }
+
+        // Besides closing the position, leave a receipt so the wallet can't redeem
+        // this vote again even with a position reopened at the same address
+        create_claim_receipt!(claim_receipt, vote: vote, authority: authority, payer: authority);
+
+        // lastly close the position account data so it can no longer be redeemed.
+        close_account!(position, vault);
+
+        Ok(())
+    }
+);
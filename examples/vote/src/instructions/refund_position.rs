@@ -0,0 +1,64 @@
+use crate::{
+    events::VoteCancelled,
+    state::{Position, Vote, POSITION_SEED},
+    PTokenProgramError,
+};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 8,
+    RefundPosition,
+    desc: "Return the whole stake of a position on a vote that missed its quorum",
+    accounts: {
+        authority: signer => writable, desc: "Authority of the position",
+        vote_vault_token_account: token => writable, desc: "votes token account for storing funds",
+        authority_token_account: token => writable, desc: "authorities token account receiving the refund",
+        position: program => writable, desc: "position pda being refunded",
+        vote: program, desc: "vote account",
+        vote_vault: any, desc: "votes vault pda",
+    },
+    data: {},
+    context: ctx,
+    prepare: {
+        // Copy the states out so no data borrow is held across the transfer or the close
+        let vote_state = read_state!(vote, Vote, |v| *v);
+        let position_state = read_state!(position, Position, |p| *p);
+
+        assert_pda!(vote_vault, seeds: [vote.key().as_ref()], bump: vote_state.vault_bump,
+            error: PTokenProgramError::VoteVaultKeyIncorrect, context: "vote_vault");
+        assert_pda!(position,
+            seeds: [POSITION_SEED, vote.key().as_ref(), authority.key().as_ref()],
+            bump: position_state.bump,
+            error: PTokenProgramError::PositionKeyIncorrect, context: "position");
+
+        // Whether the vote is void is only settled once it has ended
+        if !vote_state.deadline.is_reached(ctx.clock()?) {
+            return Err(PTokenProgramError::VoteIsStillRunning.into());
+        }
+
+        // Only void votes are refunded, the others resolve through RedeemWinnings
+        if vote_state.quorum_met()? {
+            return Err(PTokenProgramError::QuorumWasMet.into());
+        }
+
+        // The whole stake goes back, no fee is taken
+        let refund_amount = position_state.amount.get();
+        let vault_bump = [vote_state.vault_bump];
+    },
+    // The position's authority signs and picks where its own refund goes
+    // jiminy: allow(recipients)
+    transfers: {
+        vote_vault_token_account => authority_token_account: refund_amount, authority: vote_vault, seeds: [vote.key().as_ref(), &vault_bump];
+    },
+    process: {
+        // Close the position so it can't be refunded twice
+        close_account!(position, authority);
+        emit_event!(VoteCancelled {
+            vote: vote.key().into(),
+            authority: authority.key().into(),
+            refund: refund_amount.into(),
+        });
+
+        Ok(())
+    }
+);
@@ -0,0 +1,44 @@
+use crate::{
+    state::{Vote, UNRESOLVED},
+    PTokenProgramError,
+};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 9,
+    ResolveVote,
+    desc: "Let the vote's resolver pick the winning outcome after the deadline",
+    accounts: {
+        resolver: signer, desc: "Resolver set when the vote was created",
+        vote: program => writable, desc: "vote account",
+    },
+    data: {
+        /// Winning outcome, 1 for true and 0 for false on two-outcome votes
+        outcome: u8,
+    },
+    context: ctx,
+    process: {
+        let vote_state = load_mut!(vote, Vote);
+
+        if vote_state.resolver.get()? != Some(resolver.key()) {
+            return Err(PTokenProgramError::ResolverMismatch.into());
+        }
+
+        // Resolve between the deadline and the timeout, after that the tally decides
+        let clock = ctx.clock()?;
+        if !vote_state.deadline.is_reached(clock) {
+            return Err(PTokenProgramError::VoteIsStillRunning.into());
+        }
+        if vote_state.resolution_deadline().is_reached(clock) {
+            return Err(PTokenProgramError::ResolutionWindowClosed.into());
+        }
+
+        if vote_state.resolved_outcome != UNRESOLVED {
+            return Err(PTokenProgramError::AlreadyResolved.into());
+        }
+        vote_state.check_outcome(outcome)?;
+        vote_state.resolved_outcome = outcome;
+
+        Ok(())
+    }
+);
@@ -0,0 +1,41 @@
+use crate::{
+    state::{FeePolicyKind, Platform, PLATFORM_SEED},
+    utils::FeePolicy,
+    PTokenProgramError,
+};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 35,
+    SetFeePolicy,
+    desc: "Change how the platform computes position and redemption fees",
+    accounts: {
+        authority: signer, desc: "Authority of the platform",
+        platform: program => writable, desc: "Platform pda key",
+    },
+    data: {
+        /// Bps, Flat or Tiered
+        kind: FeePolicyKind,
+        /// Basis points, little-endian, at most MAX_FEE_BPS; ignored for Flat
+        bps: [u8; 2],
+        /// Little-endian: the minimum fee (Bps), the fee (Flat) or the tier threshold (Tiered)
+        first: [u8; 8],
+        /// Little-endian: the fee-free amount below which nothing is charged (Bps, Flat) or
+        /// the basis points above the threshold (Tiered)
+        second: [u8; 8],
+    },
+    process: {
+        let platform_state = load_mut!(platform, Platform);
+        assert_pda!(platform, seeds: [PLATFORM_SEED], bump: platform_state.platform_bump,
+            error: PTokenProgramError::PlatformKeyIncorrect, context: "platform");
+        if platform_state.authority != *authority.key() {
+            return Err(pinocchio::program_error::ProgramError::IncorrectAuthority);
+        }
+
+        let policy = FeePolicy::from_params(kind, u16::from_le_bytes(bps), [first, second])?;
+        policy.validate()?;
+        platform_state.set_fee_policy(policy);
+
+        Ok(())
+    }
+);
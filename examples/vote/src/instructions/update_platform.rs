@@ -0,0 +1,111 @@
+use crate::{
+    state::{Platform, PLATFORM_SEED},
+    utils::MAX_FEE_BPS,
+    PTokenProgramError,
+};
+use jiminy::define_instruction_with_metadata;
+
+// `flags` bits selecting which platform fields to update
+pub const UPDATE_FEE: u8 = 1 << 0;
+pub const UPDATE_AUTHORITY: u8 = 1 << 1;
+pub const UPDATE_FEE_MINT: u8 = 1 << 2;
+pub const UPDATE_SPONSOR_RENT: u8 = 1 << 3;
+pub const UPDATE_DURATIONS: u8 = 1 << 4;
+
+define_instruction_with_metadata!(
+    discriminant: 32,
+    UpdatePlatform,
+    desc: "Change the platform fee, fee mint, rent sponsoring, vote durations or authority, selected by `flags`",
+    deprecated_aliases: [1],
+    // `vault`, `rent` and `system_program` stay for clients built against the old account list
+    // jiminy: allow(unused_accounts)
+    accounts: {
+        authority: signer => writable, desc: "Authority of the vault", explorer_hint: wallet,
+        platform: program => writable, desc: "Platform pda key", label: "Platform Config",
+            explorer_hint: pda,
+        new_authority: any, desc: "New authority of the vault, must sign when UPDATE_AUTHORITY is set",
+        vault: any, desc: "platforms fee vault pda",
+        rent: rent_sysvar,
+        system_program,
+    },
+    data: {
+        /// UPDATE_FEE, UPDATE_AUTHORITY, UPDATE_FEE_MINT, UPDATE_SPONSOR_RENT and UPDATE_DURATIONS bits
+        flags: u8,
+        /// Fee in basis points, little-endian, read with UPDATE_FEE
+        new_fee: [u8; 2],
+        /// Mint fees are paid in, all zeros for the vote token, read with UPDATE_FEE_MINT
+        new_fee_mint: [u8; 32],
+        /// Fee-mint tokens per vote token numerator, little-endian
+        new_fee_ratio_num: [u8; 8],
+        /// Fee-mint tokens per vote token denominator, little-endian, nonzero with a fee mint
+        new_fee_ratio_den: [u8; 8],
+        /// 1 to pay position rent from the platform account, read with UPDATE_SPONSOR_RENT
+        new_sponsor_rent: u8 => bool,
+        /// Shortest vote in seconds, little-endian, zero for no minimum, read with UPDATE_DURATIONS
+        new_min_duration: [u8; 8],
+        /// Longest vote in seconds, little-endian, zero for no maximum
+        new_max_duration: [u8; 8],
+    },
+    process: {
+        // Load platform state
+        let platform_state = load_mut!(platform, Platform);
+
+        // Validate platform PDA
+        assert_pda!(platform, seeds: [PLATFORM_SEED], bump: platform_state.platform_bump,
+            error: PTokenProgramError::PlatformKeyIncorrect, context: "platform");
+
+        // Verify current authority
+        if platform_state.authority != *authority.key() {
+            return Err(pinocchio::program_error::ProgramError::IncorrectAuthority);
+        }
+
+        // Only the fields selected by `flags` change
+        let known = UPDATE_FEE
+            | UPDATE_AUTHORITY
+            | UPDATE_FEE_MINT
+            | UPDATE_SPONSOR_RENT
+            | UPDATE_DURATIONS;
+        if flags == 0 || flags & !known != 0 {
+            return Err(PTokenProgramError::InvalidUpdateFlags.into());
+        }
+
+        if flags & UPDATE_FEE != 0 {
+            if u16::from_le_bytes(new_fee) > MAX_FEE_BPS {
+                return Err(PTokenProgramError::FeeTooHigh.into());
+            }
+            platform_state.fee = new_fee;
+        }
+
+        if flags & UPDATE_FEE_MINT != 0 {
+            if new_fee_mint != [0; 32] && u64::from_le_bytes(new_fee_ratio_den) == 0 {
+                return Err(PTokenProgramError::InvalidFeeRatio.into());
+            }
+            platform_state.fee_mint = new_fee_mint;
+            platform_state.fee_ratio_num = new_fee_ratio_num;
+            platform_state.fee_ratio_den = new_fee_ratio_den;
+        }
+
+        if flags & UPDATE_SPONSOR_RENT != 0 {
+            platform_state.sponsor_rent = (new_sponsor_rent != 0) as u8;
+        }
+
+        if flags & UPDATE_DURATIONS != 0 {
+            let max = u64::from_le_bytes(new_max_duration);
+            if max != 0 && u64::from_le_bytes(new_min_duration) > max {
+                return Err(PTokenProgramError::InvalidDurationBounds.into());
+            }
+            platform_state.min_duration = new_min_duration;
+            platform_state.max_duration = new_max_duration;
+        }
+
+        // The new authority has to sign so control can't move to a key nobody holds
+        if flags & UPDATE_AUTHORITY != 0 {
+            if !new_authority.is_signer() {
+                return Err(pinocchio::program_error::ProgramError::MissingRequiredSignature);
+            }
+            platform_state.authority = new_authority.key().into();
+        }
+
+        Ok(())
+    }
+);
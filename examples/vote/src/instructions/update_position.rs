@@ -0,0 +1,131 @@
+use crate::{
+    events::PositionChanged,
+    state::{FundingMode, Platform, Position, Treasury, Vote, PLATFORM_SEED, POSITION_SEED},
+    utils::{check_delegation, check_fee_accounts},
+    PTokenProgramError,
+};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 4,
+    UpdatePosition,
+    desc: "Add stake to an existing position, paying the platform fee",
+    accounts: {
+        authority: signer => writable, desc: "Authority of the vault",
+        vote: any => writable, desc: "vote account",
+        vote_vault: any => writable, desc: "votes vault pda",
+        vote_vault_token_account: any => writable, desc: "votes token account for storing funds",
+        authority_token_account: any => writable, desc: "authorities token account for storing funds",
+        fee_token_account: any => writable, desc: "authorities token account paying the fee, in the fee mint if set",
+        vault_token_account: any => writable, desc: "vault token account for storing funds",
+        position: any => writable, desc: "position pda for voting on one side",
+        platform: any => writable, desc: "Platform pda key, counts the stake in its stats",
+        treasury: key_in(Platform::treasury of platform) => writable, desc: "Treasury pda, counts the fee",
+        vault: key_in(Platform::vault of platform), desc: "platforms fee vault pda",
+        token: any, desc: "vote token",
+    },
+    data: {
+        /// Stake to add, in vote token base units
+        amount: [u8; 8],
+        /// 0 if `authority` owns the token accounts, 1 if it spends as their delegate
+        funding_mode: FundingMode,
+    },
+    context: ctx,
+    prepare: {
+        // Handle extra security checks here
+        // mainly that platform, vote_vault, and position_pda are correct
+        let platform_state = load_mut!(platform, Platform);
+        let vote_state = load_mut!(vote, Vote);
+
+        // Validate all PDAs at once
+        validate_pdas!(
+            platform => seeds: [PLATFORM_SEED], bump: platform_state.platform_bump,
+                error: PTokenProgramError::PlatformKeyIncorrect, context: "platform";
+            vote_vault => seeds: [vote.key().as_ref()], bump: vote_state.vault_bump,
+                error: PTokenProgramError::VoteVaultKeyIncorrect, context: "vote_vault"
+        );
+        jiminy::vault::check_token_owner(vote_vault_token_account, vote_vault.key())?;
+
+        let position_state = load_mut!(position, Position);
+        // Validate position PDA
+        assert_pda!(position,
+            seeds: [POSITION_SEED, vote.key().as_ref(), authority.key().as_ref()],
+            bump: position_state.bump,
+            error: PTokenProgramError::PositionKeyIncorrect, context: "position");
+
+        // Don't let user create or update positions if the vote
+        // has already ended
+        if vote_state.deadline.is_expired(ctx.clock()?) {
+            return Err(PTokenProgramError::VoteHasAlreadyEnded.into());
+        }
+
+        // Appropriate token and fee amounts
+        // Amounts are big-endian in the instruction data
+        // jiminy: allow(byte_order)
+        let update_amount = u64::from_be_bytes(amount);
+        // Adding nothing would only burn compute on two empty transfers
+        if update_amount == 0 {
+            return Err(PTokenProgramError::ZeroAmount.into());
+        }
+        let new_amount = position_state
+            .amount
+            .get()
+            .checked_add(update_amount)
+            .ok_or(ProgramError::ArithmeticOverflow)?;
+        vote_state.check_position_cap(new_amount)?;
+        // Fees are counted in the vote token and paid in the platform's fee mint, if set
+        check_fee_accounts(platform_state, vote_state.token.as_key(), fee_token_account,
+            vault_token_account)?;
+        jiminy::vault::check_token_owner(vault_token_account, vault.key())?;
+        let fee_amount = platform_state.fee_policy()?.compute(update_amount)?;
+        let fee_amount = platform_state.fee_in_fee_mint(fee_amount)?;
+        // The platform authority's own positions don't pay it a fee
+        let (fee_amount, waived_fee) = platform_state.fee_for(authority.key(), fee_amount);
+        // The fee comes out of the same account when the fee mint is the vote token
+        let authority_spend = if fee_token_account.key() == authority_token_account.key() {
+            update_amount + fee_amount
+        } else {
+            update_amount
+        };
+        // A delegate moves tokens it doesn't own, so check its approvals before the
+        // transfers; the token program then takes `authority` as the delegate
+        if funding_mode == FundingMode::Delegate {
+            check_delegation(authority_token_account, authority.key(), authority_spend)?;
+            if fee_token_account.key() != authority_token_account.key() {
+                check_delegation(fee_token_account, authority.key(), fee_amount)?;
+            }
+        }
+    },
+    transfers: {
+        // Transfer tokens to vote vault
+        authority_token_account => vote_vault_token_account: update_amount, authority: authority;
+        // Take our fee, skipped when it's zero
+        fee_token_account => vault_token_account: fee_amount, authority: authority;
+    },
+    invariants: {
+        vote_vault_token_account.tokens: increases_by(update_amount),
+        vault_token_account.tokens: increases_by(fee_amount),
+        authority_token_account.tokens: decreases_by(authority_spend),
+    },
+    process: {
+        platform_state.record_stake(update_amount, fee_amount)?;
+        let treasury_state = load_mut!(treasury, Treasury);
+        treasury_state.record_tokens(fee_amount)?;
+        treasury_state.record_waived_tokens(waived_fee)?;
+
+        position_state.amount.set(new_amount);
+        // For the `flashloan-guard` age check, like `created_at_slot`
+        position_state.updated_at_slot.set(ctx.clock()?.slot);
+
+        vote_state.add_stake(position_state.side, update_amount)?;
+        emit_event!(PositionChanged {
+            vote: vote.key().into(),
+            authority: authority.key().into(),
+            side: position_state.side,
+            new_amount: new_amount.into(),
+            tally: vote_state.tally(position_state.side).into(),
+        });
+
+        Ok(())
+    }
+);
@@ -0,0 +1,64 @@
+use crate::{state::Vote, PTokenProgramError};
+use jiminy::{borsh::Reader, define_instruction_with_metadata, fixed_str::FixedStr};
+
+/// Borsh encoded `UpdateVoteSettings` data, `None` leaves the setting as is
+pub struct UpdateVoteSettingsArgs {
+    pub label: FixedStr<64>,
+    pub max_position: Option<u64>,
+    pub whitelist_only: Option<bool>,
+}
+
+// The label's `u32` length and up to 64 bytes, then the two options
+const MAX_SETTINGS_LEN: usize = 4 + 64 + 9 + 2;
+
+pub fn parse_update_vote_settings(data: &[u8]) -> Result<UpdateVoteSettingsArgs, ProgramError> {
+    let mut reader = Reader::new(data);
+    let label: &str = reader.read()?;
+    let args = UpdateVoteSettingsArgs {
+        label: FixedStr::from_bytes(label.as_bytes())?,
+        max_position: reader.read()?,
+        whitelist_only: reader.read()?,
+    };
+    reader.finish()?;
+    Ok(args)
+}
+
+define_instruction_with_metadata!(
+    discriminant: 19,
+    UpdateVoteSettings,
+    desc: "Relabel a vote and change its position cap or whitelist before it ends",
+    accounts: {
+        creator: signer, desc: "Creator of the vote",
+        vote: program => writable, desc: "vote account",
+    },
+    data_parser: parse_update_vote_settings => args: UpdateVoteSettingsArgs,
+        max_len: MAX_SETTINGS_LEN,
+    data_schema: {
+        /// New label, at most 64 bytes
+        label: String,
+        /// New position cap, zero for no cap
+        max_position: Option<u64>,
+        /// Whether only whitelisted wallets can open positions
+        whitelist_only: Option<bool>,
+    },
+    context: ctx,
+    process: {
+        let vote_state = load_mut!(vote, Vote);
+        if vote_state.creator != *creator.key() {
+            return Err(PTokenProgramError::CreatorMismatch.into());
+        }
+        if vote_state.deadline.is_expired(ctx.clock()?) {
+            return Err(PTokenProgramError::VoteHasAlreadyEnded.into());
+        }
+
+        vote_state.label = args.label;
+        if let Some(max_position) = args.max_position {
+            vote_state.max_position = max_position.to_le_bytes();
+        }
+        if let Some(whitelist_only) = args.whitelist_only {
+            vote_state.whitelist_only.set(whitelist_only);
+        }
+
+        Ok(())
+    }
+);
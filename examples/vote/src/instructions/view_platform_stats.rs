@@ -0,0 +1,23 @@
+use crate::{
+    state::{Platform, PlatformStats, PLATFORM_SEED},
+    PTokenProgramError,
+};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 12,
+    ViewPlatformStats,
+    desc: "Return the platform's vote, volume and fee totals",
+    accounts: {
+        platform: program, desc: "Platform pda key",
+    },
+    data: {},
+    returns: PlatformStats,
+    process: {
+        let platform_state = read_state!(platform, Platform, |p| *p);
+        assert_pda!(platform, seeds: [PLATFORM_SEED], bump: platform_state.platform_bump,
+            error: PTokenProgramError::PlatformKeyIncorrect, context: "platform");
+
+        Ok(platform_state.stats())
+    }
+);
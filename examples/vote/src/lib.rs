@@ -1,10 +1,10 @@
 #![no_std]
 #![allow(unexpected_cfgs)]
 
-use pinocchio::entrypoint;
-
 #[macro_use]
-pub mod jiminy;
+extern crate jiminy;
+
+pub mod events;
 pub mod instructions;
 pub mod state;
 pub mod utils;
@@ -20,4 +20,4 @@ pub mod generated;
 pub use generated::*;
 
 
-entrypoint!(process_instruction);
+jiminy_entrypoint!(process_instruction);
@@ -0,0 +1,496 @@
+use crate::{utils::FeePolicy, PTokenProgramError};
+use jiminy::{
+    define_enum, define_marker, define_state,
+    math::mul_div,
+    time::{Deadline, DeadlineKind},
+    FixedStr, LeU64, PodBool, PodOption, PubkeyBytes,
+};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey, sysvars::clock::Clock};
+
+// Seeds
+pub const PLATFORM_SEED: &[u8; 6] = b"config";
+pub const POSITION_SEED: &[u8; 8] = b"position";
+pub const WHITELIST_SEED: &[u8; 9] = b"whitelist";
+pub const RECEIPT_SEED: &[u8; 7] = b"receipt";
+pub const TREASURY_SEED: &[u8; 8] = b"treasury";
+
+define_enum! {
+    /// Outcomes of a two-outcome vote, the indices `Position::side` holds on them
+    pub enum Side {
+        False = 0,
+        True = 1,
+    }
+}
+
+define_enum! {
+    /// How the platform computes its fee, see `utils::FeePolicy`
+    pub enum FeePolicyKind {
+        /// Basis points of the amount, with a minimum and a fee-free threshold
+        Bps = 0,
+        /// The same fee on every amount above a fee-free threshold
+        Flat = 1,
+        /// Basis points up to a threshold, other basis points above it
+        Tiered = 2,
+    }
+}
+
+define_enum! {
+    /// Whose approval moves the stake into a position
+    pub enum FundingMode {
+        /// `authority` owns the token accounts
+        Owner = 0,
+        /// `authority` spends from the token accounts as their SPL delegate
+        Delegate = 1,
+    }
+}
+
+/// SPL token account layout, only read to check balances around transfers
+#[repr(C)]
+#[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct TokenAccountData {
+    pub mint: [u8; 32],
+    pub owner: [u8; 32],
+    pub amount: [u8; 8],
+    pub delegate_tag: [u8; 4],
+    pub delegate: [u8; 32],
+    pub state: u8,
+    pub is_native_tag: [u8; 4],
+    pub is_native: [u8; 8],
+    pub delegated_amount: [u8; 8],
+    pub close_authority_tag: [u8; 4],
+    pub close_authority: [u8; 32],
+}
+
+impl TokenAccountData {
+    pub const LEN: usize = ::core::mem::size_of::<Self>();
+
+    pub const fn amount(&self) -> u64 {
+        u64::from_le_bytes(self.amount)
+    }
+
+    /// The approved delegate, `None` when the account has none
+    pub fn delegate(&self) -> Option<&Pubkey> {
+        (u32::from_le_bytes(self.delegate_tag) == 1).then_some(&self.delegate)
+    }
+
+    pub const fn delegated_amount(&self) -> u64 {
+        u64::from_le_bytes(self.delegated_amount)
+    }
+}
+
+// `Vote::resolved_outcome` until the resolver picks an outcome
+pub const UNRESOLVED: u8 = u8::MAX;
+
+// Most outcomes a vote can have, the length of `Vote::tallies`
+pub const MAX_OUTCOMES: usize = 8;
+
+// Time after the deadline the resolver has to resolve before the tally decides,
+// about 7 days in each deadline unit (400ms slots, 2-day epochs)
+pub const RESOLVER_TIMEOUT: u64 = 7 * 24 * 60 * 60;
+pub const RESOLVER_TIMEOUT_SLOTS: u64 = RESOLVER_TIMEOUT * 5 / 2;
+pub const RESOLVER_TIMEOUT_EPOCHS: u64 = 4;
+
+// Length of an epoch in seconds, to hold epoch deadlines to the duration bounds
+pub const SECONDS_PER_EPOCH: u64 = 2 * 24 * 60 * 60;
+
+// Max length of a vote label, stored zero-padded in `Vote::label`
+pub const MAX_LABEL_LEN: usize = FixedStr::<64>::CAPACITY;
+
+define_state! {
+    pub struct Platform {
+        pub authority: PubkeyBytes,
+        // Fee in basis points, the rate of the bps and tiered fee policies
+        pub fee: [u8; 2],
+        pub platform_bump: u8,
+        pub vault_bump: u8,
+        // Set while CallAdapter is calling out, see `reentrancy_guard!`
+        pub reentrancy_lock: u8,
+        // Mint position fees are paid in, all zeros to take them in the vote token
+        pub fee_mint: [u8; 32],
+        // Fee-mint tokens per vote token, as numerator / denominator
+        pub fee_ratio_num: [u8; 8],
+        pub fee_ratio_den: [u8; 8],
+        // Nonzero to pay position rent out of the platform account's lamports
+        pub sponsor_rent: u8,
+        // Stats, token amounts are summed across every vote's mint
+        pub total_votes: [u8; 8],
+        pub total_volume: [u8; 8],
+        pub total_fees: [u8; 8],
+        // Fee vault PDA, stored so instructions check it with `key_in` instead of deriving it
+        pub vault: PubkeyBytes,
+        // `FeePolicyKind` and its little-endian parameters, see `FeePolicy::to_params`
+        pub fee_policy: u8,
+        pub fee_params: [[u8; 8]; 2],
+        // Bounds on how long a vote runs, in seconds, zero for no bound
+        pub min_duration: [u8; 8],
+        pub max_duration: [u8; 8],
+        // Treasury PDA, stored so instructions check it with `key_in` like `vault`
+        pub treasury: PubkeyBytes,
+    }
+
+    // PDA of the platform counting the fees in the vault apart from its other lamports
+    pub struct Treasury {
+        // Fees sent to the vault and not claimed yet, tokens summed across mints
+        pub fees_collected_lamports: LeU64,
+        pub fees_collected_tokens: LeU64,
+        pub bump: u8,
+        // Balances minus the counters at the last ReconcileTreasury, little-endian i64
+        pub lamport_gap: [u8; 8],
+        pub token_gap: [u8; 8],
+        pub reconciled_slot: LeU64,
+        // Fees the platform authority didn't pay on its own positions, in tokens
+        pub fees_waived_tokens: LeU64,
+    }
+
+    pub struct Vote {
+        pub token: PubkeyBytes,
+        // Little-endian stake per outcome, only the first `outcome_count` are used
+        pub tallies: [[u8; 8]; 8],
+        // Positions open up to and including the deadline, redemption opens at it
+        pub deadline: Deadline,
+        // Minimum total stake for the vote to resolve, below it positions are refunded
+        pub min_total: [u8; 8],
+        // Key that resolves the vote, none to resolve by tally
+        pub resolver: PodOption<[u8; 32]>,
+        // Outcome picked by the resolver, `UNRESOLVED` until then
+        pub resolved_outcome: u8,
+        pub vault_bump: u8,
+        pub label: FixedStr<64>,
+        // Signer of InitializeVote, manages the whitelist
+        pub creator: PubkeyBytes,
+        // Most stake one position can hold, zero for no cap
+        pub max_position: [u8; 8],
+        // Only open positions for wallets with a `WhitelistEntry`
+        pub whitelist_only: PodBool,
+        // Number of outcomes positions pick from, 2 to `MAX_OUTCOMES`
+        pub outcome_count: u8,
+    }
+
+    pub struct Position {
+        pub amount: LeU64,
+        // Index of the outcome the stake is on
+        pub side: u8,
+        pub bump: u8,
+        // Nonzero to let anyone redeem the position for its authority with RedeemFor
+        pub allow_crank: u8,
+        // Slot InitializePosition created the position in
+        pub created_at_slot: LeU64,
+        // Reward still owed after a partial RedeemWinnings, zero until the first one
+        pub remaining_entitlement: LeU64,
+        // Slot UpdatePosition last added stake in, zero until it does
+        pub updated_at_slot: LeU64,
+    }
+
+    // PDA of vote + wallet, lets the wallet open a position on a whitelist-only vote
+    pub struct WhitelistEntry {
+        pub vote: [u8; 32],
+        pub wallet: [u8; 32],
+        pub bump: u8,
+    }
+}
+
+// PDA of vote + wallet, created when the wallet redeems its winnings
+define_marker!(ClaimReceipt, seeds: [RECEIPT_SEED, vote, authority]);
+
+// Size of votes created before `resolver` had a tag byte, see `MigrateVote`
+pub const VOTE_V2_LEN: usize = Vote::LEN - 1;
+// Size of votes created before `tallies` replaced the true and false tallies
+pub const VOTE_V1_LEN: usize = VOTE_V2_LEN - (MAX_OUTCOMES - 2) * 8 - 1;
+
+// Size of platforms created before the treasury
+pub const PLATFORM_V7_LEN: usize = Platform::LEN - 32;
+// Size of platforms created before the vote duration bounds
+pub const PLATFORM_V6_LEN: usize = PLATFORM_V7_LEN - 8 - 8;
+// Size of platforms created before the fee policy
+pub const PLATFORM_V5_LEN: usize = PLATFORM_V6_LEN - 1 - 16;
+// Size of platforms created before `vault`
+pub const PLATFORM_V4_LEN: usize = PLATFORM_V5_LEN - 32;
+// Size of platforms created before the stats
+pub const PLATFORM_V3_LEN: usize = PLATFORM_V4_LEN - 8 - 8 - 8;
+// Size of platforms created before `sponsor_rent`
+pub const PLATFORM_V2_LEN: usize = PLATFORM_V3_LEN - 1;
+// Size of platforms created before `fee_mint` and the fee ratio, see `MigratePlatform`
+pub const PLATFORM_V1_LEN: usize = PLATFORM_V2_LEN - 32 - 8 - 8;
+
+/// The platform's stats counters, returned by ViewPlatformStats
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct PlatformStats {
+    pub total_votes: [u8; 8],
+    pub total_volume: [u8; 8],
+    pub total_fees: [u8; 8],
+}
+
+impl Platform {
+    /// Mint the position fees are paid in, `None` when they are taken in the vote token
+    pub fn fee_mint(&self) -> Option<&Pubkey> {
+        (self.fee_mint != [0; 32]).then_some(&self.fee_mint)
+    }
+
+    /// The policy position and redemption fees are computed with
+    pub fn fee_policy(&self) -> Result<FeePolicy, ProgramError> {
+        FeePolicy::from_params(
+            FeePolicyKind::try_from(self.fee_policy)?,
+            u16::from_le_bytes(self.fee),
+            self.fee_params,
+        )
+    }
+
+    pub fn set_fee_policy(&mut self, policy: FeePolicy) {
+        let (kind, bps, params) = policy.to_params();
+        self.fee_policy = kind.to_u8();
+        if let Some(bps) = bps {
+            self.fee = bps.to_le_bytes();
+        }
+        self.fee_params = params;
+    }
+
+    /// Fail unless a vote running `duration` units of `kind` is within the duration bounds
+    ///
+    /// Slots count as 400ms and epochs as `SECONDS_PER_EPOCH`, both bounds are inclusive.
+    pub fn check_vote_duration(
+        &self,
+        kind: DeadlineKind,
+        duration: u64,
+    ) -> Result<(), ProgramError> {
+        let seconds = match kind {
+            DeadlineKind::Unix => duration,
+            DeadlineKind::Slot => duration / 5 * 2 + duration % 5 * 2 / 5,
+            DeadlineKind::Epoch => duration.saturating_mul(SECONDS_PER_EPOCH),
+        };
+        if seconds < u64::from_le_bytes(self.min_duration) {
+            return Err(PTokenProgramError::VoteTooShort.into());
+        }
+        let max = u64::from_le_bytes(self.max_duration);
+        if max != 0 && seconds > max {
+            return Err(PTokenProgramError::VoteTooLong.into());
+        }
+        Ok(())
+    }
+
+    /// `fee` split into what `authority` pays and what is waived, `(charged, waived)`
+    ///
+    /// The platform authority's own positions pay no fee, it would only go to itself.
+    pub fn fee_for(&self, authority: &Pubkey, fee: u64) -> (u64, u64) {
+        if jiminy::perf::keys_eq(self.authority.as_key(), authority) {
+            (0, fee)
+        } else {
+            (fee, 0)
+        }
+    }
+
+    /// Whether position rent comes out of the platform account instead of the user
+    pub fn sponsors_rent(&self) -> bool {
+        self.sponsor_rent != 0
+    }
+
+    /// Count a created vote
+    pub fn record_vote(&mut self) -> Result<(), ProgramError> {
+        add_stat(&mut self.total_votes, 1)
+    }
+
+    /// Count stake put into a position and the fee taken for it
+    pub fn record_stake(&mut self, amount: u64, fee: u64) -> Result<(), ProgramError> {
+        add_stat(&mut self.total_volume, amount)?;
+        add_stat(&mut self.total_fees, fee)
+    }
+
+    pub fn record_fee(&mut self, fee: u64) -> Result<(), ProgramError> {
+        add_stat(&mut self.total_fees, fee)
+    }
+
+    pub fn stats(&self) -> PlatformStats {
+        PlatformStats {
+            total_votes: self.total_votes,
+            total_volume: self.total_volume,
+            total_fees: self.total_fees,
+        }
+    }
+
+    /// Converts a fee counted in vote tokens into the fee mint
+    ///
+    /// Returns `fee` unchanged when no fee mint is set.
+    pub fn fee_in_fee_mint(&self, fee: u64) -> Result<u64, ProgramError> {
+        if self.fee_mint().is_none() {
+            return Ok(fee);
+        }
+        mul_div(
+            fee,
+            u64::from_le_bytes(self.fee_ratio_num),
+            u64::from_le_bytes(self.fee_ratio_den),
+        )
+        .ok_or(ProgramError::ArithmeticOverflow)
+    }
+}
+
+impl Vote {
+    /// Whether enough stake took part for the vote to resolve
+    pub fn quorum_met(&self) -> Result<bool, ProgramError> {
+        Ok(self.total_stake()? >= u64::from_le_bytes(self.min_total))
+    }
+
+    /// The tallies of the vote's outcomes, indexed by outcome
+    pub fn outcome_tallies(&self) -> &[[u8; 8]] {
+        &self.tallies[..(self.outcome_count as usize).min(MAX_OUTCOMES)]
+    }
+
+    /// Fail with `InvalidSide` unless `outcome` is one of the vote's outcomes
+    pub fn check_outcome(&self, outcome: u8) -> Result<(), ProgramError> {
+        if outcome as usize >= self.outcome_tallies().len() {
+            return Err(PTokenProgramError::InvalidSide.into());
+        }
+        Ok(())
+    }
+
+    /// Stake on `outcome`, zero for an outcome the vote doesn't have
+    pub fn tally(&self, outcome: u8) -> u64 {
+        self.outcome_tallies()
+            .get(outcome as usize)
+            .map_or(0, |tally| u64::from_le_bytes(*tally))
+    }
+
+    /// Stake on every outcome together
+    pub fn total_stake(&self) -> Result<u64, ProgramError> {
+        self.outcome_tallies()
+            .iter()
+            .try_fold(0u64, |total, tally| total.checked_add(u64::from_le_bytes(*tally)))
+            .ok_or(ProgramError::ArithmeticOverflow)
+    }
+
+    /// Count `amount` of stake on `outcome`
+    pub fn add_stake(&mut self, outcome: u8, amount: u64) -> Result<(), ProgramError> {
+        self.check_outcome(outcome)?;
+        add_stat(&mut self.tallies[outcome as usize], amount)
+    }
+
+    /// The outcome with the most stake, `None` when several share the lead
+    pub fn leader(&self) -> Option<u8> {
+        let tallies = self.outcome_tallies();
+        let top = tallies.iter().map(|tally| u64::from_le_bytes(*tally)).max()?;
+        let mut leaders = tallies
+            .iter()
+            .enumerate()
+            .filter(|(_, tally)| u64::from_le_bytes(**tally) == top);
+        let (leader, _) = leaders.next()?;
+        leaders.next().is_none().then_some(leader as u8)
+    }
+
+    pub fn is_whitelist_only(&self) -> Result<bool, ProgramError> {
+        self.whitelist_only.get()
+    }
+
+    /// Fail with `PositionCapExceeded` if a position holding `total` is over `max_position`
+    pub fn check_position_cap(&self, total: u64) -> Result<(), ProgramError> {
+        let cap = u64::from_le_bytes(self.max_position);
+        if cap != 0 && total > cap {
+            return Err(PTokenProgramError::PositionCapExceeded.into());
+        }
+        Ok(())
+    }
+
+    pub fn has_resolver(&self) -> Result<bool, ProgramError> {
+        self.resolver.is_some()
+    }
+
+    /// End of the window in which the resolver can resolve, in the deadline's unit
+    pub fn resolution_deadline(&self) -> Deadline {
+        let timeout = match self.deadline.kind() {
+            Some(DeadlineKind::Slot) => RESOLVER_TIMEOUT_SLOTS,
+            Some(DeadlineKind::Epoch) => RESOLVER_TIMEOUT_EPOCHS,
+            _ => RESOLVER_TIMEOUT,
+        };
+        self.deadline.extended_by(timeout)
+    }
+
+    /// How the vote is decided at `clock`, which must have reached the deadline
+    ///
+    /// Fails with `AwaitingResolution` while a resolver is set, hasn't
+    /// resolved yet and the resolution deadline isn't reached.
+    pub fn resolution(&self, clock: &Clock) -> Result<Resolution, ProgramError> {
+        if !self.has_resolver()? {
+            return Ok(Resolution::Tally);
+        }
+        if self.resolved_outcome != UNRESOLVED {
+            return Ok(Resolution::Resolved(self.resolved_outcome));
+        }
+        if !self.resolution_deadline().is_reached(clock) {
+            return Err(PTokenProgramError::AwaitingResolution.into());
+        }
+        Ok(Resolution::Tally)
+    }
+}
+
+impl Position {
+    /// Whether a cranker other than the platform authority can redeem it with RedeemFor
+    pub fn allows_crank(&self) -> bool {
+        self.allow_crank != 0
+    }
+}
+
+impl Treasury {
+    /// Count lamports the vault took as fees
+    pub fn record_lamports(&mut self, fee: u64) -> Result<(), ProgramError> {
+        self.fees_collected_lamports.checked_add_assign(fee)
+    }
+
+    /// Count tokens the vault's token accounts took as fees
+    pub fn record_tokens(&mut self, fee: u64) -> Result<(), ProgramError> {
+        self.fees_collected_tokens.checked_add_assign(fee)
+    }
+
+    /// Count a fee the platform authority didn't pay, see `Platform::fee_for`
+    pub fn record_waived_tokens(&mut self, fee: u64) -> Result<(), ProgramError> {
+        self.fees_waived_tokens.checked_add_assign(fee)
+    }
+
+    /// Take a claim off the counters, `ClaimExceedsFees` if either is short
+    pub fn claim(&mut self, lamports: u64, tokens: u64) -> Result<(), ProgramError> {
+        let (Some(lamports_left), Some(tokens_left)) = (
+            self.fees_collected_lamports.get().checked_sub(lamports),
+            self.fees_collected_tokens.get().checked_sub(tokens),
+        ) else {
+            return Err(PTokenProgramError::ClaimExceedsFees.into());
+        };
+        self.fees_collected_lamports.set(lamports_left);
+        self.fees_collected_tokens.set(tokens_left);
+        Ok(())
+    }
+
+    /// Record how far the balances are from the counters, positive when they hold more
+    pub fn record_gaps(&mut self, lamports: u64, tokens: u64, slot: u64) -> (i64, i64) {
+        let gap = |balance: u64, counted: u64| {
+            (balance as i128 - counted as i128).clamp(i64::MIN as i128, i64::MAX as i128) as i64
+        };
+        let lamport_gap = gap(lamports, self.fees_collected_lamports.get());
+        let token_gap = gap(tokens, self.fees_collected_tokens.get());
+        self.lamport_gap = lamport_gap.to_le_bytes();
+        self.token_gap = token_gap.to_le_bytes();
+        self.reconciled_slot.set(slot);
+        (lamport_gap, token_gap)
+    }
+}
+
+impl WhitelistEntry {
+    /// Whether this entry lets `wallet` open a position on `vote`
+    pub fn admits(&self, vote: &Pubkey, wallet: &Pubkey) -> bool {
+        self.vote == *vote && self.wallet == *wallet
+    }
+}
+
+// Little-endian `stat += amount`, failing instead of wrapping
+fn add_stat(stat: &mut [u8; 8], amount: u64) -> Result<(), ProgramError> {
+    let total = u64::from_le_bytes(*stat)
+        .checked_add(amount)
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+    *stat = total.to_le_bytes();
+    Ok(())
+}
+
+/// How a finished vote picks its winning outcome
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Resolution {
+    /// The resolver picked the outcome
+    Resolved(u8),
+    /// The outcome with the most stake wins, there is no resolver or it timed out
+    Tally,
+}
@@ -0,0 +1,258 @@
+use crate::{
+    state::{FeePolicyKind, Platform, Position, Resolution, TokenAccountData, Vote},
+    PTokenProgramError,
+};
+use jiminy::math::mul_div;
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey,
+    sysvars::clock::Clock, ProgramResult,
+};
+
+// Highest platform fee, in basis points
+pub const MAX_FEE_BPS: u16 = 1_000;
+
+/// `bps` basis points of `amount`, rounded down
+pub fn calculate_fees(amount: u64, bps: u16) -> Result<u64, ProgramError> {
+    mul_div(amount, bps as u64, 10_000).ok_or(ProgramError::ArithmeticOverflow)
+}
+
+/// How the platform fee on a position or redemption amount is computed
+///
+/// Stored in `Platform` as a `FeePolicyKind` tag, the basis points in `fee`
+/// and two little-endian words in `fee_params`. A fee never exceeds the amount.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FeePolicy {
+    /// `bps` of the amount but at least `min`, nothing on amounts below `free_below`
+    Bps { bps: u16, min: u64, free_below: u64 },
+    /// `fee` on every amount, nothing on amounts below `free_below`
+    Flat { fee: u64, free_below: u64 },
+    /// `bps` of the amount up to `threshold` plus `large_bps` of the rest, so
+    /// large positions pay a smaller rate on their excess
+    Tiered { bps: u16, threshold: u64, large_bps: u16 },
+}
+
+impl FeePolicy {
+    pub fn compute(&self, amount: u64) -> Result<u64, ProgramError> {
+        if amount < self.free_below() {
+            return Ok(0);
+        }
+        let fee = match *self {
+            FeePolicy::Bps { bps, min, .. } => calculate_fees(amount, bps)?.max(min),
+            FeePolicy::Flat { fee, .. } => fee,
+            FeePolicy::Tiered {
+                bps,
+                threshold,
+                large_bps,
+            } => {
+                let small = amount.min(threshold);
+                calculate_fees(small, bps)?
+                    .checked_add(calculate_fees(amount - small, large_bps)?)
+                    .ok_or(ProgramError::ArithmeticOverflow)?
+            }
+        };
+        Ok(fee.min(amount))
+    }
+
+    fn free_below(&self) -> u64 {
+        match *self {
+            FeePolicy::Bps { free_below, .. } | FeePolicy::Flat { free_below, .. } => free_below,
+            FeePolicy::Tiered { .. } => 0,
+        }
+    }
+
+    /// Fail with `FeeTooHigh` if a rate is above `MAX_FEE_BPS`
+    pub fn validate(&self) -> ProgramResult {
+        let too_high = match *self {
+            FeePolicy::Bps { bps, .. } => bps > MAX_FEE_BPS,
+            FeePolicy::Flat { .. } => false,
+            FeePolicy::Tiered { bps, large_bps, .. } => bps.max(large_bps) > MAX_FEE_BPS,
+        };
+        if too_high {
+            return Err(PTokenProgramError::FeeTooHigh.into());
+        }
+        Ok(())
+    }
+
+    /// The policy stored as `kind`, the platform's `fee` as `bps` and `fee_params`
+    pub fn from_params(
+        kind: FeePolicyKind,
+        bps: u16,
+        params: [[u8; 8]; 2],
+    ) -> Result<Self, ProgramError> {
+        let [first, second] = params.map(u64::from_le_bytes);
+        Ok(match kind {
+            FeePolicyKind::Bps => FeePolicy::Bps {
+                bps,
+                min: first,
+                free_below: second,
+            },
+            FeePolicyKind::Flat => FeePolicy::Flat {
+                fee: first,
+                free_below: second,
+            },
+            FeePolicyKind::Tiered => FeePolicy::Tiered {
+                bps,
+                threshold: first,
+                large_bps: u16::try_from(second)
+                    .map_err(|_| ProgramError::InvalidAccountData)?,
+            },
+        })
+    }
+
+    /// The tag, the basis points for `fee` (`None` for a flat fee) and the
+    /// `fee_params` words, the inverse of [`FeePolicy::from_params`]
+    pub fn to_params(&self) -> (FeePolicyKind, Option<u16>, [[u8; 8]; 2]) {
+        let (kind, bps, first, second) = match *self {
+            FeePolicy::Bps {
+                bps,
+                min,
+                free_below,
+            } => (FeePolicyKind::Bps, Some(bps), min, free_below),
+            FeePolicy::Flat { fee, free_below } => (FeePolicyKind::Flat, None, fee, free_below),
+            FeePolicy::Tiered {
+                bps,
+                threshold,
+                large_bps,
+            } => (FeePolicyKind::Tiered, Some(bps), threshold, large_bps as u64),
+        };
+        (kind, bps, [first.to_le_bytes(), second.to_le_bytes()])
+    }
+}
+
+/// Checks the mints of the accounts a position fee moves between
+///
+/// Both must hold the platform's fee mint, or `vote_token` when none is set.
+/// The caller checks that the platform `vault` owns `vault_token_account`, where
+/// the `recipients` lint can see it.
+pub fn check_fee_accounts(
+    platform: &Platform,
+    vote_token: &Pubkey,
+    fee_token_account: &AccountInfo,
+    vault_token_account: &AccountInfo,
+) -> ProgramResult {
+    let fee_mint = *platform.fee_mint().unwrap_or(vote_token);
+    if load_prefix!(fee_token_account, TokenAccountData).mint != fee_mint
+        || load_prefix!(vault_token_account, TokenAccountData).mint != fee_mint
+    {
+        return Err(PTokenProgramError::FeeMintMismatch.into());
+    }
+    Ok(())
+}
+
+/// Checks that `delegate` is approved to move `amount` out of `token_account`
+///
+/// Used when a position is funded with `FundingMode::Delegate`, so a missing or
+/// short approval fails with a program error instead of inside the token program.
+pub fn check_delegation(
+    token_account: &AccountInfo,
+    delegate: &Pubkey,
+    amount: u64,
+) -> ProgramResult {
+    let token = read_state!(token_account, TokenAccountData, |t| *t);
+    if token.delegate() != Some(delegate) {
+        return Err(PTokenProgramError::DelegateMismatch.into());
+    }
+    if token.delegated_amount() < amount {
+        return Err(PTokenProgramError::InsufficientDelegation.into());
+    }
+    Ok(())
+}
+
+/// What a winning position is paid out of the vote vault
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Payout {
+    /// Stake plus the share of the losing outcomes, sent to the position's authority
+    pub reward: u64,
+    /// Platform fee on the reward, sent to the fee vault
+    pub fee: u64,
+}
+
+/// The payout of `position` on `vote` at `clock`
+///
+/// Shared by RedeemWinnings and RedeemFor so the two can't drift. Fails like
+/// a redemption would: while the vote runs, below quorum, on a tie or for a
+/// position on a losing outcome.
+pub fn redemption_payout(
+    vote: &Vote,
+    position: &Position,
+    fee_policy: &FeePolicy,
+    clock: &Clock,
+) -> Result<Payout, ProgramError> {
+    // purposely non-inclusive to allow flashloan exploit for learning purposes
+    // I should be able to sway the votes and redeem all on the vote deadline:
+    // it is reached here while positions still see it as not expired.
+    // The `flashloan-guard` feature closes it by refusing positions created or
+    // topped up in this slot.
+    if !vote.deadline.is_reached(clock) {
+        return Err(PTokenProgramError::VoteIsStillRunning.into());
+    }
+    #[cfg(feature = "flashloan-guard")]
+    {
+        jiminy::assert_min_age!(position.created_at_slot, 1, clock: clock);
+        jiminy::assert_min_age!(position.updated_at_slot, 1, clock: clock);
+    }
+
+    // Votes without enough participation are void, positions get refunded instead
+    if !vote.quorum_met()? {
+        return Err(PTokenProgramError::QuorumNotMet.into());
+    }
+
+    // A resolved outcome wins over the tally, where a lead shared by
+    // several outcomes is a tie
+    let winner = match vote.resolution(clock)? {
+        Resolution::Resolved(outcome) => outcome,
+        Resolution::Tally => vote.leader().ok_or(PTokenProgramError::VoteWasTied)?,
+    };
+
+    // make sure user voted correctly otherwise they can't redeem.
+    if position.side != winner {
+        return Err(PTokenProgramError::DidNotVoteForWinningSide.into());
+    }
+
+    // Every losing outcome's stake is shared out over the winning one
+    let winning_total = vote.tally(winner);
+    let losing_total = vote.total_stake()? - winning_total;
+
+    let position_amount = position.amount.get();
+    let reward = mul_div(position_amount, losing_total, winning_total)
+        .and_then(|share| position_amount.checked_add(share))
+        .ok_or(ProgramError::ArithmeticOverflow)?;
+
+    Ok(Payout {
+        reward,
+        fee: fee_policy.compute(reward)?,
+    })
+}
+
+/// One redemption of a winning position, all of it or a chunk
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RedemptionChunk {
+    /// Sent to the position's authority
+    pub amount: u64,
+    /// Sent to the fee vault, the whole fee with the first chunk and zero after
+    pub fee: u64,
+    /// Reward still owed afterwards, the position closes when it's zero
+    pub remaining: u64,
+}
+
+/// What redeeming at most `max_amount` of `payout` pays, zero for everything left
+///
+/// The first redemption of a position fixes its entitlement at `payout.reward`
+/// and takes the whole fee, later ones pay from `remaining_entitlement`, so the
+/// chunks add up to a single redemption.
+pub fn redemption_chunk(position: &Position, payout: &Payout, max_amount: u64) -> RedemptionChunk {
+    let (owed, fee) = match position.remaining_entitlement.get() {
+        0 => (payout.reward, payout.fee),
+        remaining => (remaining, 0),
+    };
+    let amount = if max_amount != 0 && max_amount < owed {
+        max_amount
+    } else {
+        owed
+    };
+    RedemptionChunk {
+        amount,
+        fee,
+        remaining: owed - amount,
+    }
+}
@@ -0,0 +1,94 @@
+//! A position closed by RefundPosition or RedeemWinnings can't be used again in the
+//! same transaction, while live one-byte markers never count as closed
+
+mod common;
+
+use common::*;
+use jiminy::error::JiminyError;
+use jiminy::layout::CLOSED_MARKER;
+use jiminy::testing::{stubs, HostAccount};
+use pinocchio::program_error::ProgramError;
+use vote::ProgramInstructions;
+
+// A vote past its deadline that missed its quorum, and one position on it
+fn refund_accounts() -> [HostAccount; 6] {
+    let (vote, authority) = (key(1), key(2));
+    let mut vote_state = vote_state(0);
+    vote_state.min_total = 100u64.to_le_bytes();
+    [
+        wallet(authority),
+        owned_token_account(key(3), vault_key(&vote), 40),
+        owned_token_account(key(4), authority, 0),
+        program_account(position_key(&vote, &authority), &position_state(40, 0)).writable(),
+        program_account(vote, &vote_state),
+        HostAccount::new(vault_key(&vote), vote::ID, LAMPORTS, &[]),
+    ]
+}
+
+fn refund() -> Vec<u8> {
+    let (data, len) = ProgramInstructions::RefundPosition {}.pack();
+    data[..len].to_vec()
+}
+
+#[test]
+fn refund_leaves_the_closed_marker() {
+    let mut accounts = refund_accounts();
+    run(&mut accounts, &refund()).unwrap();
+    assert_eq!(stubs::cpi_calls(), ["token::transfer"]);
+
+    let position = &accounts[3];
+    assert_eq!(position.data, CLOSED_MARKER);
+    assert_eq!(position.lamports, 0);
+    // Still owned by the program until the runtime drops it after the transaction
+    assert_eq!(position.owner, vote::ID);
+    assert_eq!(accounts[0].lamports, 2 * LAMPORTS);
+}
+
+#[test]
+fn second_refund_fails_with_account_closed() {
+    let mut accounts = refund_accounts();
+    run(&mut accounts, &refund()).unwrap();
+    let closed: ProgramError = JiminyError::AccountClosed.into();
+    assert_eq!(run(&mut accounts, &refund()), Err(closed.clone()));
+
+    // Lamports sent to it in between don't bring the position back
+    accounts[3].lamports = LAMPORTS;
+    assert_eq!(run(&mut accounts, &refund()), Err(closed));
+    assert_eq!(accounts[3].data, CLOSED_MARKER);
+}
+
+#[test]
+fn redeem_of_a_closed_position_fails_with_account_closed() {
+    let mut vote_state = vote_state(0);
+    vote_state.tallies[0] = STAKE.to_le_bytes();
+    let mut accounts = redeem_accounts(key(2), &vote_state);
+    // What the last chunk of a first RedeemWinnings leaves in the position
+    accounts[5].data = CLOSED_MARKER.to_vec();
+    accounts[5].lamports = 0;
+    assert_eq!(
+        run(&mut accounts, &redeem(0)),
+        Err(JiminyError::AccountClosed.into())
+    );
+    assert!(stubs::cpi_calls().is_empty());
+}
+
+fn view_receipt(receipt: HostAccount) -> Result<(), ProgramError> {
+    let (data, len) = ProgramInstructions::ViewClaimReceipt {}.pack();
+    run(&mut [receipt], &data[..len])
+}
+
+#[test]
+fn receipt_with_bump_255_is_not_closed() {
+    let receipt = HostAccount::new(receipt_key(&key(1), &key(2)), vote::ID, LAMPORTS, &[255]);
+    view_receipt(receipt).unwrap();
+    assert_eq!(stubs::return_data(), [255]);
+}
+
+#[test]
+fn empty_program_account_is_too_short_not_closed() {
+    let receipt = HostAccount::new(receipt_key(&key(1), &key(2)), vote::ID, LAMPORTS, &[]);
+    assert_eq!(
+        view_receipt(receipt),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
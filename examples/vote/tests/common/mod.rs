@@ -0,0 +1,152 @@
+//! Accounts for running the vote program on the host with `jiminy::testing::process`
+//!
+//! Stubbed system and token CPIs move lamports and token amounts, but no other
+//! program runs, so each test starts from the votes and positions an
+//! instruction expects to find.
+
+#![allow(dead_code)]
+
+use bytemuck::Zeroable;
+use jiminy::testing::{process, stubs, HostAccount};
+use jiminy::{Deadline, LeU64};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+use vote::state::{
+    FeePolicyKind, Platform, Position, TokenAccountData, Treasury, Vote, PLATFORM_SEED,
+    POSITION_SEED, RECEIPT_SEED,
+};
+use vote::ProgramInstructions;
+
+pub const LAMPORTS: u64 = 1_000_000_000;
+/// What the tests' positions hold
+pub const STAKE: u64 = 1_000_000;
+pub const VAULT_BUMP: u8 = 254;
+pub const POSITION_BUMP: u8 = 253;
+pub const PLATFORM_BUMP: u8 = 252;
+// Keys of the accounts a test doesn't vary
+pub const PLATFORM_AUTHORITY: Pubkey = [40; 32];
+pub const FEE_VAULT: Pubkey = [41; 32];
+pub const TREASURY: Pubkey = [42; 32];
+pub const MINT: Pubkey = [43; 32];
+
+pub fn key(seed: u8) -> Pubkey {
+    [seed; 32]
+}
+
+pub fn vault_key(vote: &Pubkey) -> Pubkey {
+    pinocchio_pubkey::derive_address(&[vote.as_ref()], Some(VAULT_BUMP), &vote::ID)
+}
+
+pub fn position_key(vote: &Pubkey, authority: &Pubkey) -> Pubkey {
+    pinocchio_pubkey::derive_address(
+        &[POSITION_SEED, vote.as_ref(), authority.as_ref()],
+        Some(POSITION_BUMP),
+        &vote::ID,
+    )
+}
+
+/// The position key for instructions that search its bump, see `stubs::FOUND_BUMP`
+pub fn new_position_key(vote: &Pubkey, authority: &Pubkey) -> Pubkey {
+    stubs::find_program_address(&[POSITION_SEED, vote.as_ref(), authority.as_ref()], &vote::ID).0
+}
+
+pub fn platform_key() -> Pubkey {
+    pinocchio_pubkey::derive_address(&[PLATFORM_SEED], Some(PLATFORM_BUMP), &vote::ID)
+}
+
+pub fn receipt_key(vote: &Pubkey, authority: &Pubkey) -> Pubkey {
+    // The stubbed bump search settles on 255
+    pinocchio_pubkey::derive_address(
+        &[RECEIPT_SEED, vote.as_ref(), authority.as_ref()],
+        Some(255),
+        &vote::ID,
+    )
+}
+
+/// A platform taking `fee_bps` of each redemption into `FEE_VAULT`
+pub fn platform_state(fee_bps: u16) -> Platform {
+    let mut platform = Platform::zeroed();
+    platform.authority = PLATFORM_AUTHORITY.into();
+    platform.fee = fee_bps.to_le_bytes();
+    platform.platform_bump = PLATFORM_BUMP;
+    platform.fee_policy = FeePolicyKind::Bps.to_u8();
+    platform.vault = FEE_VAULT.into();
+    platform.treasury = TREASURY.into();
+    platform
+}
+
+pub fn treasury_state() -> Treasury {
+    Treasury::zeroed()
+}
+
+/// A two-outcome vote with no stake, no quorum and a deadline at `deadline` seconds
+pub fn vote_state(deadline: i64) -> Vote {
+    let mut vote = Vote::zeroed();
+    vote.deadline = Deadline::unix(deadline);
+    vote.vault_bump = VAULT_BUMP;
+    vote.outcome_count = 2;
+    vote.resolved_outcome = vote::state::UNRESOLVED;
+    vote
+}
+
+pub fn position_state(amount: u64, side: u8) -> Position {
+    let mut position = Position::zeroed();
+    position.amount = LeU64::new(amount);
+    position.side = side;
+    position.bump = POSITION_BUMP;
+    position
+}
+
+pub fn program_account<T: bytemuck::Pod>(key: Pubkey, state: &T) -> HostAccount {
+    HostAccount::new(key, vote::ID, LAMPORTS, bytemuck::bytes_of(state))
+}
+
+pub fn wallet(key: Pubkey) -> HostAccount {
+    HostAccount::new(key, pinocchio_system::ID, LAMPORTS, &[]).signer().writable()
+}
+
+/// A token account of `MINT` held by `owner`
+pub fn owned_token_account(key: Pubkey, owner: Pubkey, amount: u64) -> HostAccount {
+    let mut data = TokenAccountData::zeroed();
+    data.mint = MINT;
+    data.owner = owner;
+    data.amount = amount.to_le_bytes();
+    data.state = 1;
+    HostAccount::new(key, pinocchio_token::ID, LAMPORTS, bytemuck::bytes_of(&data)).writable()
+}
+
+/// RedeemWinnings accounts for `authority`'s position of `STAKE` on outcome 0 of `vote_state`
+pub fn redeem_accounts(authority: Pubkey, vote_state: &Vote) -> Vec<HostAccount> {
+    let vote = key(1);
+    vec![
+        wallet(authority),
+        program_account(vote, vote_state).writable(),
+        owned_token_account(key(3), vault_key(&vote), 2 * STAKE),
+        owned_token_account(key(4), authority, 0),
+        owned_token_account(key(5), FEE_VAULT, 0),
+        program_account(position_key(&vote, &authority), &position_state(STAKE, 0)).writable(),
+        HostAccount::new(receipt_key(&vote, &authority), pinocchio_system::ID, 0, &[]).writable(),
+        program_account(platform_key(), &platform_state(100)).writable(),
+        program_account(TREASURY, &treasury_state()).writable(),
+        HostAccount::new(FEE_VAULT, vote::ID, LAMPORTS, &[]).writable(),
+        HostAccount::new(MINT, pinocchio_token::ID, LAMPORTS, &[0; 82]),
+        HostAccount::new(vault_key(&vote), pinocchio_system::ID, 0, &[]),
+    ]
+}
+
+pub fn redeem(max_amount: u64) -> Vec<u8> {
+    let (data, len) = ProgramInstructions::RedeemWinnings {
+        max_amount: max_amount.to_le_bytes(),
+    }
+    .pack();
+    data[..len].to_vec()
+}
+
+pub fn state<T: bytemuck::Pod>(account: &HostAccount) -> T {
+    bytemuck::pod_read_unaligned(&account.data)
+}
+
+/// Run one instruction against `accounts` from a fresh set of stubs
+pub fn run(accounts: &mut [HostAccount], data: &[u8]) -> Result<(), ProgramError> {
+    stubs::reset();
+    process(vote::process_instruction, &vote::ID, accounts, data)
+}
@@ -0,0 +1,35 @@
+//! The coverage report names the instructions a run left out
+//!
+//! The only test in its binary, so no other test adds to or resets the record
+//! while it runs.
+
+use jiminy::testing::{coverage_report, process, reset_coverage, stubs};
+
+// Left out on purpose, and run by no other test either
+const UNTESTED: (u8, &str) = (19, "UpdateVoteSettings");
+
+#[test]
+fn report_flags_the_instruction_left_out() {
+    stubs::reset();
+    reset_coverage();
+    // The record counts what was dispatched, so runs failing on their missing
+    // accounts count too
+    for (discriminator, _) in vote::INSTRUCTIONS {
+        if discriminator != UNTESTED.0 {
+            let _ = process(vote::process_instruction, &vote::ID, &mut [], &[discriminator]);
+        }
+    }
+
+    let report = coverage_report(&vote::INSTRUCTIONS);
+    assert!(!report.is_complete());
+    assert_eq!(report.untested, [UNTESTED]);
+    assert_eq!(report.tested.len(), vote::INSTRUCTION_COUNT - 1);
+    assert_eq!(
+        report.to_string(),
+        format!(
+            "{}/{} instructions exercised\n  untested: UpdateVoteSettings (19)\n",
+            vote::INSTRUCTION_COUNT - 1,
+            vote::INSTRUCTION_COUNT
+        )
+    );
+}
@@ -0,0 +1,81 @@
+//! UpdatePlatform moved from discriminator 1 to 32: sent on its old discriminator
+//! it still runs, to the same effect, and the dispatch marks the call deprecated
+
+mod common;
+
+use common::*;
+use jiminy::testing::{stubs, HostAccount};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+use vote::instructions::{UPDATE_DURATIONS, UPDATE_FEE};
+use vote::state::Platform;
+use vote::ProgramInstructions;
+
+fn update_platform_accounts() -> Vec<HostAccount> {
+    vec![
+        wallet(PLATFORM_AUTHORITY),
+        program_account(platform_key(), &platform_state(100)).writable(),
+        HostAccount::new(key(2), pinocchio_system::ID, LAMPORTS, &[]),
+        HostAccount::new(FEE_VAULT, vote::ID, LAMPORTS, &[]),
+        HostAccount::new(pinocchio::sysvars::rent::RENT_ID, Pubkey::default(), 1, &[]),
+        HostAccount::new(pinocchio_system::ID, Pubkey::default(), 1, &[]),
+    ]
+}
+
+// UpdatePlatform raising the fee to 250 bps and bounding votes to 10..=10_000 seconds,
+// sent on `discriminator`
+fn update_platform(discriminator: u8) -> Vec<u8> {
+    let (buf, len) = ProgramInstructions::UpdatePlatform {
+        flags: UPDATE_FEE | UPDATE_DURATIONS,
+        new_fee: 250u16.to_le_bytes(),
+        new_fee_mint: [0; 32],
+        new_fee_ratio_num: [0; 8],
+        new_fee_ratio_den: [0; 8],
+        new_sponsor_rent: false,
+        new_min_duration: 10u64.to_le_bytes(),
+        new_max_duration: 10_000u64.to_le_bytes(),
+    }
+    .pack();
+    let mut data = buf[..len].to_vec();
+    assert_eq!(data[0], 32);
+    data[0] = discriminator;
+    data
+}
+
+// The accounts UpdatePlatform leaves when sent on `discriminator`
+fn run_update_platform(discriminator: u8) -> Vec<HostAccount> {
+    let mut accounts = update_platform_accounts();
+    run(&mut accounts, &update_platform(discriminator)).unwrap();
+    accounts
+}
+
+#[test]
+fn old_discriminator_updates_the_platform_like_the_new_one() {
+    let current = run_update_platform(32);
+    assert_eq!(stubs::deprecated_discriminators(), []);
+    let deprecated = run_update_platform(1);
+    assert_eq!(stubs::deprecated_discriminators(), [(1, 32)]);
+
+    for (current, deprecated) in current.iter().zip(&deprecated) {
+        assert_eq!(current.data, deprecated.data);
+        assert_eq!(current.lamports, deprecated.lamports);
+        assert_eq!(current.owner, deprecated.owner);
+    }
+    // And it did update the platform
+    let platform: Platform = state(&deprecated[1]);
+    assert_eq!(u16::from_le_bytes(platform.fee), 250);
+    assert_eq!(u64::from_le_bytes(platform.min_duration), 10);
+    assert_eq!(u64::from_le_bytes(platform.max_duration), 10_000);
+}
+
+#[test]
+fn old_discriminator_fails_like_the_new_one() {
+    // Signed by someone other than the platform authority
+    for discriminator in [32, 1] {
+        let mut accounts = update_platform_accounts();
+        accounts[0] = wallet(key(9));
+        assert_eq!(
+            run(&mut accounts, &update_platform(discriminator)),
+            Err(ProgramError::IncorrectAuthority)
+        );
+    }
+}
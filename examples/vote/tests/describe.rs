@@ -0,0 +1,40 @@
+//! `describe()` lists an instruction's accounts as its handler takes them
+
+use vote::instructions::RedeemWinningsInstruction;
+
+#[test]
+fn redeem_winnings_lists_its_accounts_in_order() {
+    let descriptor = RedeemWinningsInstruction::describe();
+    assert_eq!((descriptor.name, descriptor.discriminator), ("RedeemWinnings", 5));
+
+    let accounts: Vec<_> = descriptor
+        .accounts
+        .iter()
+        .map(|account| (account.index, account.name, account.signer, account.writable))
+        .collect();
+    assert_eq!(
+        accounts,
+        [
+            (0, "authority", true, true),
+            (1, "vote", false, true),
+            (2, "vote_vault_token_account", false, true),
+            (3, "authority_token_account", false, true),
+            (4, "vault_token_account", false, true),
+            (5, "position", false, true),
+            // Created by the last redemption, so writable without `=> writable`
+            (6, "claim_receipt", false, true),
+            (7, "platform", false, true),
+            (8, "treasury", false, true),
+            (9, "vault", false, true),
+            (10, "token", false, false),
+            (11, "vote_vault", false, false),
+        ]
+    );
+
+    let receipt = descriptor.account("claim_receipt").unwrap();
+    assert_eq!(receipt.account_type, "uninitialized");
+    let treasury = descriptor.account("treasury").unwrap();
+    assert_eq!(treasury.validation, ["writable"]);
+    assert_eq!(descriptor.fields.len(), 1);
+    assert_eq!(descriptor.fields[0].name, "max_amount");
+}
@@ -0,0 +1,160 @@
+//! InitializeVote holds a vote's length to the platform's bounds, counting slots as
+//! 400ms, and refuses a length that runs the deadline past the clock's range
+
+mod common;
+
+use common::*;
+use jiminy::testing::{process, stubs, HostAccount};
+use jiminy::time::{Deadline, DeadlineKind};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey, sysvars::clock::Clock};
+use vote::state::Vote;
+use vote::{PTokenProgramError, ProgramInstructions};
+
+// The platform's bounds in seconds
+const MIN_SECONDS: u64 = 10;
+const MAX_SECONDS: u64 = 10_000;
+// The same in slots: 25 slots are 10s, 25_002 slots 10_000.8s, which rounds down
+const MIN_SLOTS: u64 = 25;
+const MAX_SLOTS: u64 = 25_002;
+
+const UNIX: u8 = 0;
+const SLOT: u8 = 1;
+
+// The clock votes are created at
+const NOW: Clock = Clock {
+    slot: 1_000,
+    epoch_start_timestamp: 0,
+    epoch: 0,
+    leader_schedule_epoch: 0,
+    unix_timestamp: 1_000,
+};
+
+// InitializeVote by `key(2)` of vote `key(1)` on a platform with `min..=max` seconds
+fn initialize_accounts(min: u64, max: u64) -> Vec<HostAccount> {
+    let vote = key(1);
+    let vote_vault = stubs::find_program_address(&[vote.as_ref()], &vote::ID).0;
+    let vote_vault_token_account = stubs::find_program_address(
+        &[vote_vault.as_ref(), pinocchio_token::ID.as_ref(), MINT.as_ref()],
+        &pinocchio_associated_token_account::ID,
+    )
+    .0;
+    let mut platform = platform_state(100);
+    platform.min_duration = min.to_le_bytes();
+    platform.max_duration = max.to_le_bytes();
+    vec![
+        wallet(key(2)),
+        HostAccount::new(vote, pinocchio_system::ID, 0, &[]).signer().writable(),
+        HostAccount::new(FEE_VAULT, vote::ID, LAMPORTS, &[]).writable(),
+        HostAccount::new(vote_vault, pinocchio_system::ID, 0, &[]).writable(),
+        HostAccount::new(vote_vault_token_account, pinocchio_system::ID, 0, &[]).writable(),
+        program_account(platform_key(), &platform).writable(),
+        program_account(TREASURY, &treasury_state()).writable(),
+        HostAccount::new(MINT, pinocchio_token::ID, LAMPORTS, &[0; 82]),
+        HostAccount::new(pinocchio::sysvars::rent::RENT_ID, Pubkey::default(), 1, &[]),
+        HostAccount::new(pinocchio_system::ID, Pubkey::default(), 1, &[]),
+        HostAccount::new(pinocchio_token::ID, Pubkey::default(), 1, &[]),
+        HostAccount::new(pinocchio_associated_token_account::ID, Pubkey::default(), 1, &[]),
+    ]
+}
+
+// Create a vote lasting `time_to_add` of `deadline_kind` at `NOW` on a platform
+// with `min..=max` seconds, returning its deadline
+fn initialize_bounded(
+    min: u64,
+    max: u64,
+    deadline_kind: u8,
+    time_to_add: u64,
+) -> Result<Deadline, ProgramError> {
+    let mut accounts = initialize_accounts(min, max);
+    let (data, len) = ProgramInstructions::InitializeVote {
+        deadline_kind,
+        time_to_add: time_to_add.to_le_bytes(),
+        min_total: [0; 8],
+        resolver: [0; 32],
+        max_position: [0; 8],
+        whitelist_only: false,
+        label: b"rain tomorrow?".to_vec(),
+    }
+    .pack();
+    stubs::reset();
+    stubs::set_clock(NOW);
+    process(vote::process_instruction, &vote::ID, &mut accounts, &data[..len])?;
+    Ok(state::<Vote>(&accounts[1]).deadline)
+}
+
+fn initialize(deadline_kind: u8, time_to_add: u64) -> Result<Deadline, ProgramError> {
+    initialize_bounded(MIN_SECONDS, MAX_SECONDS, deadline_kind, time_to_add)
+}
+
+#[test]
+fn seconds_are_held_to_the_bounds() {
+    assert_eq!(
+        initialize(UNIX, MIN_SECONDS - 1),
+        Err(PTokenProgramError::VoteTooShort.into())
+    );
+    assert_eq!(
+        initialize(UNIX, MIN_SECONDS),
+        Ok(Deadline::unix(1_000 + MIN_SECONDS as i64))
+    );
+    assert_eq!(
+        initialize(UNIX, MAX_SECONDS),
+        Ok(Deadline::unix(1_000 + MAX_SECONDS as i64))
+    );
+    assert_eq!(
+        initialize(UNIX, MAX_SECONDS + 1),
+        Err(PTokenProgramError::VoteTooLong.into())
+    );
+}
+
+#[test]
+fn slots_are_held_to_the_bounds_at_400ms() {
+    assert_eq!(
+        initialize(SLOT, MIN_SLOTS - 1),
+        Err(PTokenProgramError::VoteTooShort.into())
+    );
+    assert_eq!(initialize(SLOT, MIN_SLOTS), Ok(Deadline::slot(1_000 + MIN_SLOTS)));
+    assert_eq!(initialize(SLOT, MAX_SLOTS), Ok(Deadline::slot(1_000 + MAX_SLOTS)));
+    assert_eq!(
+        initialize(SLOT, MAX_SLOTS + 1),
+        Err(PTokenProgramError::VoteTooLong.into())
+    );
+}
+
+#[test]
+fn deadline_past_the_clock_range_overflows() {
+    // Without a maximum nothing stops the length before the deadline is computed
+    for kind in [UNIX, SLOT] {
+        assert_eq!(
+            initialize_bounded(0, 0, kind, u64::MAX),
+            Err(ProgramError::ArithmeticOverflow),
+            "kind {kind}"
+        );
+    }
+    // The longest lengths that still fit
+    assert_eq!(
+        initialize_bounded(0, 0, UNIX, (i64::MAX - 1_000) as u64),
+        Ok(Deadline::unix(i64::MAX))
+    );
+    assert_eq!(
+        initialize_bounded(0, 0, SLOT, u64::MAX - 1_000),
+        Ok(Deadline::slot(u64::MAX))
+    );
+}
+
+#[test]
+fn checked_from_now_is_none_on_overflow() {
+    assert_eq!(
+        Deadline::checked_from_now(DeadlineKind::Unix, &NOW, (i64::MAX - 1_000) as u64 + 1),
+        None
+    );
+    assert_eq!(Deadline::checked_from_now(DeadlineKind::Slot, &NOW, u64::MAX - 999), None);
+    assert_eq!(
+        Deadline::checked_from_now(DeadlineKind::Slot, &NOW, u64::MAX - 1_000),
+        Some(Deadline::slot(u64::MAX))
+    );
+    // `from_now` saturates instead
+    assert_eq!(
+        Deadline::from_now(DeadlineKind::Slot, &NOW, u64::MAX),
+        Deadline::slot(u64::MAX)
+    );
+}
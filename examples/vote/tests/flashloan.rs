@@ -0,0 +1,115 @@
+//! At the deadline a position can still be topped up while redemptions are open.
+//! The default build lets the same slot redeem the new stake; `flashloan-guard`
+//! refuses it until a later slot, run with
+//! `cargo test -p vote --features flashloan-guard --test flashloan`
+
+mod common;
+
+use common::*;
+use jiminy::testing::{process, stubs, HostAccount};
+use pinocchio::{program_error::ProgramError, sysvars::clock::Clock};
+use vote::events::WinningsRedeemed;
+use vote::state::{Position, Vote};
+use vote::{FundingMode, ProgramInstructions};
+
+const DEADLINE: i64 = 1_000;
+// Where the position was created, long before the deadline
+const CREATED_AT_SLOT: u64 = 50;
+const DEADLINE_SLOT: u64 = 100;
+
+// Run one instruction at `DEADLINE`, in `slot`
+fn run_in(slot: u64, accounts: &mut [HostAccount], data: &[u8]) -> Result<(), ProgramError> {
+    stubs::reset();
+    stubs::set_clock(Clock {
+        slot,
+        unix_timestamp: DEADLINE,
+        ..stubs::clock()
+    });
+    process(vote::process_instruction, &vote::ID, accounts, data)
+}
+
+// Outcome 0 leads with `key(2)`'s position of `STAKE` against 500_000
+fn leading_vote() -> Vote {
+    let mut vote_state = vote_state(DEADLINE);
+    vote_state.token = MINT.into();
+    vote_state.tallies[0] = STAKE.to_le_bytes();
+    vote_state.tallies[1] = 500_000u64.to_le_bytes();
+    vote_state
+}
+
+fn old_position() -> Position {
+    let mut position = position_state(STAKE, 0);
+    position.created_at_slot = CREATED_AT_SLOT.into();
+    position
+}
+
+// UpdatePosition adding `STAKE` to the old position in the deadline slot;
+// returns the vote and the position it leaves
+fn top_up_at_the_deadline() -> (Vote, Position) {
+    let (vote, authority) = (key(1), key(2));
+    let mut accounts = [
+        wallet(authority),
+        program_account(vote, &leading_vote()).writable(),
+        HostAccount::new(vault_key(&vote), pinocchio_system::ID, 0, &[]).writable(),
+        owned_token_account(key(3), vault_key(&vote), STAKE),
+        owned_token_account(key(4), authority, STAKE),
+        owned_token_account(key(5), authority, STAKE),
+        owned_token_account(key(6), FEE_VAULT, 0),
+        program_account(position_key(&vote, &authority), &old_position()).writable(),
+        program_account(platform_key(), &platform_state(100)).writable(),
+        program_account(TREASURY, &treasury_state()).writable(),
+        HostAccount::new(FEE_VAULT, vote::ID, LAMPORTS, &[]),
+        HostAccount::new(MINT, pinocchio_token::ID, LAMPORTS, &[0; 82]),
+    ];
+    let (data, len) = ProgramInstructions::UpdatePosition {
+        amount: STAKE.to_be_bytes(),
+        funding_mode: FundingMode::Owner,
+    }
+    .pack();
+    run_in(DEADLINE_SLOT, &mut accounts, &data[..len]).unwrap();
+    (state(&accounts[1]), state(&accounts[7]))
+}
+
+// RedeemWinnings of the topped-up position, its vault holding the whole stake
+fn redeem_accounts_after_top_up() -> Vec<HostAccount> {
+    let (vote_state, position) = top_up_at_the_deadline();
+    let mut accounts = redeem_accounts(key(2), &vote_state);
+    accounts[2] = owned_token_account(key(3), vault_key(&key(1)), 3 * STAKE);
+    accounts[5] = program_account(position_key(&key(1), &key(2)), &position).writable();
+    accounts
+}
+
+#[test]
+fn top_up_at_the_deadline_records_its_slot() {
+    let (vote_state, position) = top_up_at_the_deadline();
+    assert_eq!(vote_state.tally(0), 2 * STAKE);
+    assert_eq!(position.amount.get(), 2 * STAKE);
+    assert_eq!(position.created_at_slot.get(), CREATED_AT_SLOT);
+    assert_eq!(position.updated_at_slot.get(), DEADLINE_SLOT);
+}
+
+#[cfg(not(feature = "flashloan-guard"))]
+#[test]
+fn default_build_redeems_the_top_up_in_the_same_slot() {
+    let mut accounts = redeem_accounts_after_top_up();
+    run_in(DEADLINE_SLOT, &mut accounts, &redeem(0)).unwrap();
+    // The stake added at the deadline earns its share of the losing 500_000
+    let events = stubs::events::<WinningsRedeemed>("WinningsRedeemed");
+    assert_eq!(events[0].reward.get(), 2 * STAKE + 500_000);
+}
+
+#[cfg(feature = "flashloan-guard")]
+#[test]
+fn guard_refuses_the_top_up_until_a_later_slot() {
+    let mut accounts = redeem_accounts_after_top_up();
+    assert_eq!(
+        run_in(DEADLINE_SLOT, &mut accounts, &redeem(0)),
+        Err(jiminy::error::JiminyError::AccountTooYoung.into())
+    );
+    assert!(stubs::cpi_calls().is_empty());
+
+    // A slot later the stake redeems as usual, too late for a flashloan to repay
+    run_in(DEADLINE_SLOT + 1, &mut accounts, &redeem(0)).unwrap();
+    let events = stubs::events::<WinningsRedeemed>("WinningsRedeemed");
+    assert_eq!(events[0].reward.get(), 2 * STAKE + 500_000);
+}
@@ -0,0 +1,211 @@
+//! Every instruction round trips: the bytes `ProgramInstructions::pack` writes unpack
+//! to the same value, and the instruction's `parse_data`, which its `try_from` runs
+//! on chain, reads back exactly the data that was packed
+
+use vote::instructions::*;
+use vote::{FeePolicyKind, FundingMode, ProgramInstructions, INSTRUCTIONS};
+
+fn pack(instruction: &ProgramInstructions) -> Vec<u8> {
+    let (buf, len) = instruction.pack();
+    let bytes = buf[..len].to_vec();
+    assert_eq!(ProgramInstructions::unpack(&bytes).as_ref(), Ok(instruction));
+    bytes
+}
+
+// Pack each sample and parse it back through `<Instruction>::parse_data`, returning
+// the discriminators covered
+macro_rules! round_trip {
+    ($($instruction:ty => $sample:expr),* $(,)?) => {{
+        let mut covered = Vec::new();
+        $(
+            let bytes = pack(&$sample);
+            let (data, rest, ()) = <$instruction>::parse_data(&bytes[1..]).unwrap();
+            let parsed = [bytemuck::bytes_of(&data), rest].concat();
+            assert_eq!(parsed, &bytes[1..], stringify!($instruction));
+            covered.push(bytes[0]);
+        )*
+        covered
+    }};
+}
+
+fn initialize_vote() -> ProgramInstructions {
+    ProgramInstructions::InitializeVote {
+        deadline_kind: 1,
+        time_to_add: 500u64.to_le_bytes(),
+        min_total: 1_000u64.to_le_bytes(),
+        resolver: [7; 32],
+        max_position: 50_000u64.to_le_bytes(),
+        whitelist_only: true,
+        label: b"rain tomorrow?".to_vec(),
+    }
+}
+
+fn update_platform() -> ProgramInstructions {
+    ProgramInstructions::UpdatePlatform {
+        flags: 0b1111_1111,
+        new_fee: 250u16.to_le_bytes(),
+        new_fee_mint: [9; 32],
+        new_fee_ratio_num: 3u64.to_le_bytes(),
+        new_fee_ratio_den: 4u64.to_le_bytes(),
+        new_sponsor_rent: true,
+        new_min_duration: 10u64.to_le_bytes(),
+        new_max_duration: 10_000u64.to_le_bytes(),
+    }
+}
+
+#[test]
+fn every_instruction_round_trips() {
+    let mut covered = round_trip!(
+        InitializePlatformInstruction => ProgramInstructions::InitializePlatform {
+            fee: 100u16.to_le_bytes(),
+            platform_bump: 252,
+            vault_bump: 254,
+            treasury_bump: 251,
+        },
+        InitializeVoteInstruction => initialize_vote(),
+        InitializePositionInstruction => ProgramInstructions::InitializePosition {
+            amount: 5_000u64.to_le_bytes(),
+            side: 1,
+            funding_mode: FundingMode::Delegate,
+            allow_crank: 1,
+        },
+        UpdatePositionInstruction => ProgramInstructions::UpdatePosition {
+            amount: 700u64.to_le_bytes(),
+            funding_mode: FundingMode::Owner,
+        },
+        RedeemWinningsInstruction => ProgramInstructions::RedeemWinnings {
+            max_amount: 300u64.to_le_bytes(),
+        },
+        CallAdapterInstruction => ProgramInstructions::CallAdapter {
+            adapter_data: vec![1, 2, 3, 4, 5],
+        },
+        RefundPositionInstruction => ProgramInstructions::RefundPosition {},
+        ResolveVoteInstruction => ProgramInstructions::ResolveVote { outcome: 1 },
+        AddToWhitelistInstruction => ProgramInstructions::AddToWhitelist { wallet: [3; 32] },
+        ViewPlatformStatsInstruction => ProgramInstructions::ViewPlatformStats {},
+        BatchClosePositionsInstruction => ProgramInstructions::BatchClosePositions {},
+        InitializeVoteWithPayerInstruction => ProgramInstructions::InitializeVoteWithPayer {
+            deadline_kind: 0,
+            time_to_add: 3_600u64.to_le_bytes(),
+            min_total: 0u64.to_le_bytes(),
+            resolver: [0; 32],
+            max_position: 0u64.to_le_bytes(),
+            whitelist_only: false,
+            label: Vec::new(),
+        },
+        InitializeMultiOutcomeVoteInstruction => ProgramInstructions::InitializeMultiOutcomeVote {
+            outcome_count: 4,
+            deadline_kind: 2,
+            time_to_add: 1u64.to_le_bytes(),
+            min_total: 10u64.to_le_bytes(),
+            resolver: [5; 32],
+            max_position: 0u64.to_le_bytes(),
+            whitelist_only: false,
+            label: b"which quarter?".to_vec(),
+        },
+        MigrateVoteInstruction => ProgramInstructions::MigrateVote {},
+        RedeemForInstruction => ProgramInstructions::RedeemFor {},
+        RedeemFeeVoucherInstruction => ProgramInstructions::RedeemFeeVoucher {
+            rebate: 25u64.to_le_bytes(),
+            expires_at_slot: 99u64.to_le_bytes(),
+        },
+        UpdatePlatformInstruction => update_platform(),
+        AdminResetPlatformInstruction => ProgramInstructions::AdminResetPlatform {},
+        MigratePlatformInstruction => ProgramInstructions::MigratePlatform {},
+        SetFeePolicyInstruction => ProgramInstructions::SetFeePolicy {
+            kind: FeePolicyKind::Tiered,
+            bps: 30u16.to_le_bytes(),
+            first: 1_000u64.to_le_bytes(),
+            second: 10_000u64.to_le_bytes(),
+        },
+        ClaimFeesInstruction => ProgramInstructions::ClaimFees {
+            lamports: 1_000_000u64.to_le_bytes(),
+            tokens: 42u64.to_le_bytes(),
+        },
+        ReconcileTreasuryInstruction => ProgramInstructions::ReconcileTreasury {},
+    );
+
+    // The one `data_parser` instruction returns its args instead of raw bytes
+    let bytes = pack(&ProgramInstructions::UpdateVoteSettings {
+        label: "will it snow?".into(),
+        max_position: Some(9_000),
+        whitelist_only: None,
+    });
+    let (_, rest, args) = UpdateVoteSettingsInstruction::parse_data(&bytes[1..]).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(args.label.as_str_lossy(), "will it snow?");
+    assert_eq!(args.max_position, Some(9_000));
+    assert_eq!(args.whitelist_only, None);
+    covered.push(bytes[0]);
+
+    for (discriminator, name) in INSTRUCTIONS {
+        assert!(covered.contains(&discriminator), "{name} has no round trip sample");
+    }
+}
+
+// What borsh's `to_vec` writes for `(String, Option<u64>, Option<bool>)`: a u32 LE
+// length before the label, and a 0 or 1 tag before each option's value
+const SNOW_SETTINGS: [u8; 27] = [
+    13, 0, 0, 0, 119, 105, 108, 108, 32, 105, 116, 32, 115, 110, 111, 119, 63, // "will it snow?"
+    1, 40, 35, 0, 0, 0, 0, 0, 0, // Some(9_000)
+    0, // None
+];
+// An empty label, None, Some(true)
+const EMPTY_SETTINGS: [u8; 7] = [0, 0, 0, 0, 0, 1, 1];
+
+#[test]
+fn update_vote_settings_matches_borsh() {
+    let (_, rest, args) = UpdateVoteSettingsInstruction::parse_data(&SNOW_SETTINGS).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(args.label.as_str_lossy(), "will it snow?");
+    assert_eq!(args.max_position, Some(9_000));
+    assert_eq!(args.whitelist_only, None);
+
+    let (_, rest, args) = UpdateVoteSettingsInstruction::parse_data(&EMPTY_SETTINGS).unwrap();
+    assert!(rest.is_empty());
+    assert_eq!(args.label.as_str_lossy(), "");
+    assert_eq!(args.max_position, None);
+    assert_eq!(args.whitelist_only, Some(true));
+
+    let bytes = pack(&ProgramInstructions::UpdateVoteSettings {
+        label: "will it snow?".into(),
+        max_position: Some(9_000),
+        whitelist_only: None,
+    });
+    assert_eq!(bytes, [&[19][..], &SNOW_SETTINGS].concat());
+    let bytes = pack(&ProgramInstructions::UpdateVoteSettings {
+        label: String::new(),
+        max_position: None,
+        whitelist_only: Some(true),
+    });
+    assert_eq!(bytes, [&[19][..], &EMPTY_SETTINGS].concat());
+}
+
+#[test]
+fn views_round_trip() {
+    pack(&ProgramInstructions::ViewPlatform {});
+    pack(&ProgramInstructions::ViewTreasury {});
+    pack(&ProgramInstructions::ViewVote {});
+    pack(&ProgramInstructions::ViewPosition {});
+    pack(&ProgramInstructions::ViewWhitelistEntry {});
+    pack(&ProgramInstructions::ViewClaimReceipt {});
+}
+
+#[test]
+fn deprecated_aliases_unpack_to_the_current_instruction() {
+    let (buf, len) = update_platform().pack();
+    let mut bytes = buf[..len].to_vec();
+    bytes[0] = 1;
+    assert_eq!(ProgramInstructions::unpack(&bytes), Ok(update_platform()));
+}
+
+#[test]
+fn parse_data_rejects_what_try_from_rejects() {
+    // A label longer than its `max_len` and fixed data cut short
+    let (buf, len) = initialize_vote().pack();
+    let mut long = buf[1..len].to_vec();
+    long.extend_from_slice(&[b'x'; 256]);
+    assert!(InitializeVoteInstruction::parse_data(&long).is_err());
+    assert!(InitializeVoteInstruction::parse_data(&buf[1..10]).is_err());
+    assert!(ResolveVoteInstruction::parse_data(&[1, 0]).is_err());
+}
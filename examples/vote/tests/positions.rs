@@ -0,0 +1,350 @@
+//! InitializePosition checks the outcome a position stakes on, that it stakes
+//! anything, the vote's cap on its stake, the vote's whitelist and, when a delegate
+//! funds it, the approvals on the token accounts before creating it
+
+mod common;
+
+use common::*;
+use jiminy::testing::{stubs, HostAccount, TokenAccount};
+use jiminy::PodBool;
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey};
+use vote::state::{Position, Side, TokenAccountData, Vote, WhitelistEntry, WHITELIST_SEED};
+use vote::{FundingMode, PTokenProgramError, ProgramInstructions};
+
+// A new position of `key(2)` on an open vote, up to the position's creation
+fn initialize_accounts(vote_state: Vote) -> Vec<HostAccount> {
+    let (vote, authority) = (key(1), key(2));
+    let mut vote_state = vote_state;
+    vote_state.token = MINT.into();
+    vec![
+        wallet(authority),
+        program_account(vote, &vote_state).writable(),
+        owned_token_account(key(3), vault_key(&vote), 0),
+        owned_token_account(key(4), authority, STAKE),
+        owned_token_account(key(5), authority, STAKE),
+        owned_token_account(key(6), FEE_VAULT, 0),
+        HostAccount::new(new_position_key(&vote, &authority), pinocchio_system::ID, 0, &[])
+            .writable(),
+        program_account(platform_key(), &platform_state(100)).writable(),
+        program_account(TREASURY, &treasury_state()).writable(),
+        HostAccount::new(FEE_VAULT, vote::ID, LAMPORTS, &[]),
+        HostAccount::new(MINT, pinocchio_token::ID, LAMPORTS, &[0; 82]),
+        HostAccount::new(vault_key(&vote), pinocchio_system::ID, 0, &[]),
+        HostAccount::new(key(7), pinocchio_system::ID, 0, &[]),
+    ]
+}
+
+fn initialize(amount: u64, side: u8) -> Vec<u8> {
+    initialize_funded(amount, side, FundingMode::Owner)
+}
+
+fn initialize_funded(amount: u64, side: u8, funding_mode: FundingMode) -> Vec<u8> {
+    let (data, len) = ProgramInstructions::InitializePosition {
+        // Big-endian, unlike the other amounts
+        amount: amount.to_be_bytes(),
+        side,
+        funding_mode,
+        allow_crank: 0,
+    }
+    .pack();
+    data[..len].to_vec()
+}
+
+fn tokens(account: &HostAccount) -> u64 {
+    state::<TokenAccountData>(account).amount()
+}
+
+// The position `initialize_accounts` opened with `STAKE` on `side`, the stake in
+// the vote's vault and the 1% fee in the platform's
+fn assert_created(accounts: &[HostAccount], side: u8) {
+    assert_eq!(
+        stubs::cpi_calls(),
+        ["system::create_account", "token::transfer", "token::transfer"]
+    );
+    let position = state::<Position>(&accounts[6]);
+    assert_eq!((position.amount.get(), position.side), (STAKE, side));
+    assert_eq!(state::<Vote>(&accounts[1]).tally(side), STAKE);
+    assert_eq!(tokens(&accounts[2]), STAKE);
+    assert_eq!(tokens(&accounts[3]), 0);
+    assert_eq!(tokens(&accounts[4]), STAKE - STAKE / 100);
+    assert_eq!(tokens(&accounts[5]), STAKE / 100);
+}
+
+#[test]
+fn side_past_the_outcomes_is_rejected() {
+    for side in [2, 7, 255] {
+        let mut accounts = initialize_accounts(vote_state(i64::MAX));
+        assert_eq!(
+            run(&mut accounts, &initialize(STAKE, side)),
+            Err(PTokenProgramError::InvalidSide.into()),
+            "side {side}"
+        );
+        assert!(stubs::cpi_calls().is_empty());
+    }
+}
+
+#[test]
+fn both_sides_of_a_two_outcome_vote_are_accepted() {
+    for side in [0, 1] {
+        let mut accounts = initialize_accounts(vote_state(i64::MAX));
+        run(&mut accounts, &initialize(STAKE, side)).unwrap();
+        assert_created(&accounts, side);
+    }
+}
+
+#[test]
+fn multi_outcome_votes_take_every_outcome() {
+    let mut vote_state = vote_state(i64::MAX);
+    vote_state.outcome_count = 4;
+    let mut accounts = initialize_accounts(vote_state);
+    run(&mut accounts, &initialize(STAKE, 3)).unwrap();
+    assert_created(&accounts, 3);
+
+    let mut accounts = initialize_accounts(vote_state);
+    assert_eq!(
+        run(&mut accounts, &initialize(STAKE, 4)),
+        Err(PTokenProgramError::InvalidSide.into())
+    );
+}
+
+#[test]
+fn side_helper_only_maps_the_two_outcomes() {
+    assert_eq!(Side::from_u8(0), Some(Side::False));
+    assert_eq!(Side::from_u8(1), Some(Side::True));
+    assert_eq!(Side::from_u8(255), None);
+    assert_eq!(Side::True.to_u8(), 1);
+}
+
+const WHITELIST_BUMP: u8 = 251;
+
+fn whitelist_key(vote: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    let seeds = [WHITELIST_SEED.as_ref(), vote.as_ref(), wallet.as_ref()];
+    pinocchio_pubkey::derive_address(&seeds, Some(WHITELIST_BUMP), &vote::ID)
+}
+
+// `key(2)`'s entry on a whitelist-only vote, with the wallet it admits
+fn whitelisted_accounts(admitted: Pubkey) -> Vec<HostAccount> {
+    let mut vote_state = vote_state(i64::MAX);
+    vote_state.whitelist_only = PodBool::new(true);
+    let mut accounts = initialize_accounts(vote_state);
+    let entry = WhitelistEntry {
+        vote: key(1),
+        wallet: admitted,
+        bump: WHITELIST_BUMP,
+    };
+    accounts[12] = program_account(whitelist_key(&key(1), &key(2)), &entry);
+    accounts
+}
+
+fn capped_vote(max_position: u64) -> Vote {
+    let mut vote_state = vote_state(i64::MAX);
+    vote_state.max_position = max_position.to_le_bytes();
+    vote_state
+}
+
+#[test]
+fn stake_over_the_cap_is_rejected_before_creating_the_position() {
+    let mut accounts = initialize_accounts(capped_vote(STAKE - 1));
+    assert_eq!(
+        run(&mut accounts, &initialize(STAKE, 0)),
+        Err(PTokenProgramError::PositionCapExceeded.into())
+    );
+    assert!(stubs::cpi_calls().is_empty());
+}
+
+#[test]
+fn stake_up_to_the_cap_is_accepted() {
+    for max_position in [STAKE, 0] {
+        let mut accounts = initialize_accounts(capped_vote(max_position));
+        run(&mut accounts, &initialize(STAKE, 0))
+            .unwrap_or_else(|error| panic!("cap {max_position}: {error:?}"));
+        assert_created(&accounts, 0);
+    }
+}
+
+// UpdatePosition adding `amount` to `key(2)`'s position of `STAKE` on `vote_state`
+fn update(vote_state: Vote, amount: u64) -> Result<(), ProgramError> {
+    let (vote, authority) = (key(1), key(2));
+    let mut vote_state = vote_state;
+    vote_state.token = MINT.into();
+    let mut accounts = vec![
+        wallet(authority),
+        program_account(vote, &vote_state).writable(),
+        HostAccount::new(vault_key(&vote), pinocchio_system::ID, 0, &[]).writable(),
+        owned_token_account(key(3), vault_key(&vote), STAKE),
+        owned_token_account(key(4), authority, 2 * STAKE),
+        owned_token_account(key(5), authority, STAKE),
+        owned_token_account(key(6), FEE_VAULT, 0),
+        program_account(position_key(&vote, &authority), &position_state(STAKE, 0)).writable(),
+        program_account(platform_key(), &platform_state(100)).writable(),
+        program_account(TREASURY, &treasury_state()).writable(),
+        HostAccount::new(FEE_VAULT, vote::ID, LAMPORTS, &[]),
+        HostAccount::new(MINT, pinocchio_token::ID, LAMPORTS, &[0; 82]),
+    ];
+    let (data, len) = ProgramInstructions::UpdatePosition {
+        amount: amount.to_be_bytes(),
+        funding_mode: FundingMode::Owner,
+    }
+    .pack();
+    run(&mut accounts, &data[..len])
+}
+
+#[test]
+fn update_counts_the_stake_already_held_against_the_cap() {
+    assert_eq!(
+        update(capped_vote(2 * STAKE - 1), STAKE),
+        Err(PTokenProgramError::PositionCapExceeded.into())
+    );
+    assert!(stubs::cpi_calls().is_empty());
+
+    // Up to the cap the stake and the fee move
+    update(capped_vote(2 * STAKE), STAKE).unwrap();
+    assert_eq!(stubs::cpi_calls(), ["token::transfer", "token::transfer"]);
+}
+
+#[test]
+fn whitelist_only_votes_need_an_entry() {
+    let mut vote_state = vote_state(i64::MAX);
+    vote_state.whitelist_only = PodBool::new(true);
+    let mut accounts = initialize_accounts(vote_state);
+    assert_eq!(
+        run(&mut accounts, &initialize(STAKE, 0)),
+        Err(PTokenProgramError::NotWhitelisted.into())
+    );
+    assert!(stubs::cpi_calls().is_empty());
+}
+
+#[test]
+fn whitelist_entry_of_another_wallet_is_rejected() {
+    let mut accounts = whitelisted_accounts(key(9));
+    assert_eq!(
+        run(&mut accounts, &initialize(STAKE, 0)),
+        Err(PTokenProgramError::NotWhitelisted.into())
+    );
+}
+
+#[test]
+fn whitelisted_wallets_open_positions() {
+    let mut accounts = whitelisted_accounts(key(2));
+    run(&mut accounts, &initialize(STAKE, 0)).unwrap();
+    assert_created(&accounts, 0);
+}
+
+#[test]
+fn positions_without_stake_are_rejected() {
+    let mut accounts = initialize_accounts(vote_state(i64::MAX));
+    assert_eq!(
+        run(&mut accounts, &initialize(0, 0)),
+        Err(PTokenProgramError::ZeroAmount.into())
+    );
+    assert!(stubs::cpi_calls().is_empty());
+
+    assert_eq!(
+        update(vote_state(i64::MAX), 0),
+        Err(PTokenProgramError::ZeroAmount.into())
+    );
+    assert!(stubs::cpi_calls().is_empty());
+}
+
+// The 1% fee on `STAKE`
+const FEE: u64 = STAKE / 100;
+
+// Holds the token accounts `key(2)` spends from as their delegate
+const HOLDER: Pubkey = [8; 32];
+
+// A token account of `MINT` held by `HOLDER` with `amount`, and `approval` as its
+// `(delegate, delegated_amount)`
+fn delegated_token_account(
+    key: Pubkey,
+    amount: u64,
+    approval: Option<(Pubkey, u64)>,
+) -> HostAccount {
+    let mut account = TokenAccount::new(MINT, HOLDER, amount);
+    account.delegate = approval;
+    HostAccount::new(key, pinocchio_token::ID, LAMPORTS, &account.to_bytes()).writable()
+}
+
+// `initialize_accounts` with `key(2)` spending from `HOLDER`'s stake and fee
+// accounts under the two approvals
+fn delegated_accounts(
+    stake: Option<(Pubkey, u64)>,
+    fee: Option<(Pubkey, u64)>,
+) -> Vec<HostAccount> {
+    let mut accounts = initialize_accounts(vote_state(i64::MAX));
+    accounts[3] = delegated_token_account(key(4), STAKE, stake);
+    accounts[4] = delegated_token_account(key(5), STAKE, fee);
+    accounts
+}
+
+fn run_delegated(accounts: &mut [HostAccount]) -> Result<(), ProgramError> {
+    run(accounts, &initialize_funded(STAKE, 0, FundingMode::Delegate))
+}
+
+#[test]
+fn delegate_funding_needs_the_authority_approved_on_the_stake_account() {
+    let fee = Some((key(2), FEE));
+    for stake in [None, Some((key(9), STAKE))] {
+        let mut accounts = delegated_accounts(stake, fee);
+        assert_eq!(
+            run_delegated(&mut accounts),
+            Err(PTokenProgramError::DelegateMismatch.into()),
+            "{stake:?}"
+        );
+        assert!(stubs::cpi_calls().is_empty());
+    }
+
+    let mut accounts = delegated_accounts(Some((key(2), STAKE - 1)), fee);
+    assert_eq!(
+        run_delegated(&mut accounts),
+        Err(PTokenProgramError::InsufficientDelegation.into())
+    );
+    assert!(stubs::cpi_calls().is_empty());
+}
+
+#[test]
+fn delegate_funding_checks_a_separate_fee_account_for_the_fee() {
+    let stake = Some((key(2), STAKE));
+    for fee in [None, Some((key(9), FEE))] {
+        let mut accounts = delegated_accounts(stake, fee);
+        assert_eq!(
+            run_delegated(&mut accounts),
+            Err(PTokenProgramError::DelegateMismatch.into()),
+            "{fee:?}"
+        );
+    }
+
+    let mut accounts = delegated_accounts(stake, Some((key(2), FEE - 1)));
+    assert_eq!(
+        run_delegated(&mut accounts),
+        Err(PTokenProgramError::InsufficientDelegation.into())
+    );
+
+    // Approved for exactly the stake and exactly the fee
+    let mut accounts = delegated_accounts(stake, Some((key(2), FEE)));
+    run_delegated(&mut accounts).unwrap();
+    assert_created(&accounts, 0);
+}
+
+#[test]
+fn delegate_paying_the_fee_from_the_stake_account_needs_both_approved() {
+    // The fee account is the stake account, passed twice
+    let same_account = |approved: u64| {
+        let mut accounts = initialize_accounts(vote_state(i64::MAX));
+        accounts[3] = delegated_token_account(key(4), 2 * STAKE, Some((key(2), approved)));
+        accounts[4] = accounts[3].clone();
+        accounts
+    };
+
+    let mut accounts = same_account(STAKE);
+    assert_eq!(
+        run_delegated(&mut accounts),
+        Err(PTokenProgramError::InsufficientDelegation.into())
+    );
+    assert!(stubs::cpi_calls().is_empty());
+
+    let mut accounts = same_account(STAKE + FEE);
+    run_delegated(&mut accounts).unwrap();
+    assert_eq!(tokens(&accounts[3]), 2 * STAKE - STAKE - FEE);
+    assert_eq!(tokens(&accounts[2]), STAKE);
+    assert_eq!(tokens(&accounts[5]), FEE);
+}
@@ -0,0 +1,107 @@
+//! Votes below their `min_total` are void: RedeemWinnings refuses them and
+//! RefundPosition pays every position its stake back, without a fee
+
+mod common;
+
+use common::*;
+use jiminy::testing::{stubs, HostAccount};
+use jiminy::Deadline;
+use vote::events::VoteCancelled;
+use vote::state::Vote;
+use vote::{PTokenProgramError, ProgramInstructions};
+
+// Met with `STAKE / 2` on the other outcome, so outcome 0 still wins
+const MIN_TOTAL: u64 = STAKE + STAKE / 2;
+
+// Past its deadline with `STAKE` on outcome 0 and `other` on outcome 1
+fn ended_vote(other: u64) -> Vote {
+    let mut vote_state = vote_state(0);
+    vote_state.min_total = MIN_TOTAL.to_le_bytes();
+    vote_state.tallies[0] = STAKE.to_le_bytes();
+    vote_state.tallies[1] = other.to_le_bytes();
+    vote_state
+}
+
+// The position of `key(2)` on outcome 0 of `vote_state`
+fn refund_accounts(vote_state: &Vote) -> [HostAccount; 6] {
+    let (vote, authority) = (key(1), key(2));
+    [
+        wallet(authority),
+        owned_token_account(key(3), vault_key(&vote), 2 * STAKE),
+        owned_token_account(key(4), authority, 0),
+        program_account(position_key(&vote, &authority), &position_state(STAKE, 0)).writable(),
+        program_account(vote, vote_state),
+        HostAccount::new(vault_key(&vote), vote::ID, LAMPORTS, &[]),
+    ]
+}
+
+fn refund() -> Vec<u8> {
+    let (data, len) = ProgramInstructions::RefundPosition {}.pack();
+    data[..len].to_vec()
+}
+
+#[test]
+fn redeem_fails_below_quorum() {
+    let mut accounts = redeem_accounts(key(2), &ended_vote(MIN_TOTAL - STAKE - 1));
+    assert_eq!(
+        run(&mut accounts, &redeem(STAKE / 2)),
+        Err(PTokenProgramError::QuorumNotMet.into())
+    );
+    assert!(stubs::cpi_calls().is_empty());
+}
+
+#[test]
+fn redeem_pays_once_the_quorum_is_met_exactly() {
+    let mut accounts = redeem_accounts(key(2), &ended_vote(MIN_TOTAL - STAKE));
+    // A first chunk, so the position stays open and no receipt is created
+    run(&mut accounts, &redeem(STAKE / 2)).unwrap();
+    assert_eq!(stubs::cpi_calls()[0], "token::transfer");
+}
+
+#[test]
+fn refund_returns_the_whole_stake_without_a_fee() {
+    let mut accounts = refund_accounts(&ended_vote(MIN_TOTAL - STAKE - 1));
+    run(&mut accounts, &refund()).unwrap();
+
+    // One transfer from the vault, none to the fee vault
+    assert_eq!(stubs::cpi_calls(), ["token::transfer"]);
+    let cancelled = stubs::events::<VoteCancelled>("VoteCancelled");
+    assert_eq!(cancelled.len(), 1);
+    assert_eq!(cancelled[0].refund.get(), STAKE);
+    assert_eq!(cancelled[0].authority, key(2));
+    // The position is closed, its rent back with the authority
+    assert_eq!(accounts[3].lamports, 0);
+    assert_eq!(accounts[0].lamports, 2 * LAMPORTS);
+}
+
+#[test]
+fn refund_fails_once_the_quorum_is_met() {
+    let mut accounts = refund_accounts(&ended_vote(MIN_TOTAL - STAKE));
+    assert_eq!(
+        run(&mut accounts, &refund()),
+        Err(PTokenProgramError::QuorumWasMet.into())
+    );
+    assert!(stubs::cpi_calls().is_empty());
+    assert_eq!(accounts[3].lamports, LAMPORTS);
+}
+
+#[test]
+fn refund_waits_for_the_deadline() {
+    let mut vote_state = ended_vote(0);
+    vote_state.deadline = Deadline::unix(i64::MAX);
+    let mut accounts = refund_accounts(&vote_state);
+    assert_eq!(
+        run(&mut accounts, &refund()),
+        Err(PTokenProgramError::VoteIsStillRunning.into())
+    );
+}
+
+#[test]
+fn quorum_counts_every_outcome() {
+    let mut vote_state = ended_vote(0);
+    vote_state.outcome_count = 3;
+    vote_state.tallies[2] = (MIN_TOTAL - STAKE).to_le_bytes();
+    assert!(vote_state.quorum_met().unwrap());
+    vote_state.tallies[2] = (MIN_TOTAL - STAKE - 1).to_le_bytes();
+    assert!(!vote_state.quorum_met().unwrap());
+}
@@ -0,0 +1,139 @@
+//! RedeemWinnings signs both of its vault transfers after checking the vote vault once,
+//! pays the same in chunks as in one go, and picks the winner among any number
+//! of outcomes
+
+mod common;
+
+use common::*;
+use jiminy::layout::CLOSED_MARKER;
+use jiminy::testing::{process, stubs, HostAccount};
+use vote::events::WinningsRedeemed;
+use vote::state::{Platform, Position, Treasury};
+use vote::PTokenProgramError;
+
+// Outcome 0 won with `STAKE` against 500_000, the position holds all of outcome 0
+fn won_redeem_accounts(authority: [u8; 32]) -> Vec<HostAccount> {
+    let mut vote_state = vote_state(0);
+    vote_state.tallies[0] = STAKE.to_le_bytes();
+    vote_state.tallies[1] = 500_000u64.to_le_bytes();
+    redeem_accounts(authority, &vote_state)
+}
+
+#[test]
+fn fee_transfer_reuses_the_vault_check() {
+    let mut accounts = won_redeem_accounts(key(2));
+    // A first chunk, so the position stays open and no receipt is created
+    run(&mut accounts, &redeem(500_000)).unwrap();
+    assert_eq!(stubs::cpi_calls(), ["token::transfer", "token::transfer"]);
+
+    // 1% of the 1_500_000 reward
+    let platform: Platform = state(&accounts[7]);
+    let treasury: Treasury = state(&accounts[8]);
+    assert_eq!(u64::from_le_bytes(platform.total_fees), 15_000);
+    assert_eq!(treasury.fees_collected_tokens.get(), 15_000);
+    let position: Position = state(&accounts[5]);
+    assert_eq!(position.remaining_entitlement.get(), 1_000_000);
+}
+
+#[test]
+fn waived_fee_makes_one_transfer() {
+    let mut accounts = won_redeem_accounts(PLATFORM_AUTHORITY);
+    run(&mut accounts, &redeem(500_000)).unwrap();
+    assert_eq!(stubs::cpi_calls(), ["token::transfer"]);
+    let treasury: Treasury = state(&accounts[8]);
+    assert_eq!(treasury.fees_waived_tokens.get(), 15_000);
+}
+
+#[test]
+fn wrong_vote_vault_fails_before_any_transfer() {
+    let mut accounts = won_redeem_accounts(key(2));
+    accounts[11].key = key(9);
+    assert_eq!(
+        run(&mut accounts, &redeem(500_000)),
+        Err(PTokenProgramError::VoteVaultKeyIncorrect.into())
+    );
+    assert!(stubs::cpi_calls().is_empty());
+}
+
+// Redeem `chunks` in turn, the last one closing the position; returns the
+// accounts and the reward and fee the `WinningsRedeemed` events add up to
+fn redeem_in(chunks: &[u64]) -> (Vec<HostAccount>, u64, u64) {
+    let mut accounts = won_redeem_accounts(key(2));
+    stubs::reset();
+    for &chunk in chunks {
+        process(vote::process_instruction, &vote::ID, &mut accounts, &redeem(chunk)).unwrap();
+    }
+    let events = stubs::events::<WinningsRedeemed>("WinningsRedeemed");
+    assert_eq!(events.len(), chunks.len());
+    assert_eq!(events.last().unwrap().remaining.get(), 0);
+    let reward = events.iter().map(|event| event.reward.get()).sum();
+    let fee = events.iter().map(|event| event.fee.get()).sum();
+    (accounts, reward, fee)
+}
+
+#[test]
+fn uneven_chunks_pay_what_one_redemption_does() {
+    let (whole, reward, fee) = redeem_in(&[0]);
+    assert_eq!((reward, fee), (1_500_000, 15_000));
+
+    let (chunked, chunked_reward, chunked_fee) = redeem_in(&[200_000, 700_000, 600_000]);
+    assert_eq!((chunked_reward, chunked_fee), (reward, fee));
+
+    let (platform, chunked_platform): (Platform, Platform) = (state(&whole[7]), state(&chunked[7]));
+    assert_eq!(chunked_platform.total_fees, platform.total_fees);
+    let (treasury, chunked_treasury): (Treasury, Treasury) = (state(&whole[8]), state(&chunked[8]));
+    assert_eq!(
+        chunked_treasury.fees_collected_tokens.get(),
+        treasury.fees_collected_tokens.get()
+    );
+    assert_eq!(treasury.fees_collected_tokens.get(), 15_000);
+
+    // Both end with the position closed and the receipt created
+    for accounts in [&whole, &chunked] {
+        assert_eq!(accounts[5].data, CLOSED_MARKER);
+        assert_eq!(accounts[6].owner, vote::ID);
+    }
+}
+
+// A three-outcome vote with `tallies`, redeemed by a position of `STAKE` on `side`
+fn three_outcome_redeem_accounts(tallies: [u64; 3], side: u8) -> Vec<HostAccount> {
+    let mut vote_state = vote_state(0);
+    vote_state.outcome_count = 3;
+    for (tally, stake) in vote_state.tallies.iter_mut().zip(tallies) {
+        *tally = stake.to_le_bytes();
+    }
+    let mut accounts = redeem_accounts(key(2), &vote_state);
+    accounts[5].data = bytemuck::bytes_of(&position_state(STAKE, side)).to_vec();
+    accounts
+}
+
+#[test]
+fn single_leader_of_three_takes_both_losing_outcomes() {
+    let mut accounts = three_outcome_redeem_accounts([300_000, 200_000, STAKE], 2);
+    run(&mut accounts, &redeem(0)).unwrap();
+    let events = stubs::events::<WinningsRedeemed>("WinningsRedeemed");
+    // Its stake plus the 500_000 staked on outcomes 0 and 1, with 1% of it as the fee
+    assert_eq!(events[0].reward.get(), 1_500_000);
+    assert_eq!(events[0].fee.get(), 15_000);
+    assert_eq!(accounts[5].data, CLOSED_MARKER);
+
+    let mut accounts = three_outcome_redeem_accounts([300_000, 200_000, STAKE], 0);
+    assert_eq!(
+        run(&mut accounts, &redeem(0)),
+        Err(PTokenProgramError::DidNotVoteForWinningSide.into())
+    );
+}
+
+#[test]
+fn two_of_three_sharing_the_lead_is_a_tie() {
+    // Positions on either leader and on the trailing outcome alike
+    for side in 0..3 {
+        let mut accounts = three_outcome_redeem_accounts([STAKE, STAKE, 200_000], side);
+        assert_eq!(
+            run(&mut accounts, &redeem(0)),
+            Err(PTokenProgramError::VoteWasTied.into()),
+            "side {side}"
+        );
+        assert!(stubs::cpi_calls().is_empty());
+    }
+}
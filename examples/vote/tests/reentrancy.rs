@@ -0,0 +1,92 @@
+//! CallAdapter holds the platform's reentrancy lock while the adapter runs, so
+//! an adapter calling back into CallAdapter fails with ReentrancyDetected
+
+mod common;
+
+use common::*;
+use jiminy::testing::{stubs, HostAccount};
+use pinocchio::{account_info::AccountInfo, pubkey::Pubkey, ProgramResult};
+use vote::state::Platform;
+use vote::{PTokenProgramError, ProgramInstructions};
+
+const ADAPTER: Pubkey = [9; 32];
+// Data that makes the test adapter call back in
+const REENTER: u8 = 1;
+
+fn call_adapter(adapter_data: Vec<u8>) -> Vec<u8> {
+    let (data, len) = ProgramInstructions::CallAdapter { adapter_data }.pack();
+    data[..len].to_vec()
+}
+
+// Gets `[authority, platform, vote_program]` and calls CallAdapter again on
+// them, naming this program as its own adapter
+fn adapter(_program_id: &Pubkey, accounts: &[AccountInfo], data: &[u8]) -> ProgramResult {
+    if data != [REENTER] {
+        return Ok(());
+    }
+    let [authority, platform, vote_program] = accounts else {
+        panic!("the adapter gets three accounts");
+    };
+    let accounts = [
+        authority.clone(),
+        platform.clone(),
+        vote_program.clone(),
+        vote_program.clone(),
+    ];
+    vote::process_instruction(&vote::ID, &accounts, &call_adapter(Vec::new()))
+}
+
+fn adapter_accounts() -> [HostAccount; 4] {
+    [
+        wallet(key(2)),
+        program_account(platform_key(), &platform_state(100)).writable(),
+        HostAccount::new(ADAPTER, pinocchio_system::ID, LAMPORTS, &[]),
+        HostAccount::new(vote::ID, pinocchio_system::ID, LAMPORTS, &[]),
+    ]
+}
+
+// `run` resets the stubs, so the adapter is added after it
+fn run_adapter(accounts: &mut [HostAccount], adapter_data: Vec<u8>) -> ProgramResult {
+    stubs::reset();
+    stubs::add_program(ADAPTER, adapter);
+    jiminy::testing::process(
+        vote::process_instruction,
+        &vote::ID,
+        accounts,
+        &call_adapter(adapter_data),
+    )
+}
+
+#[test]
+fn adapter_call_releases_the_lock() {
+    let mut accounts = adapter_accounts();
+    run_adapter(&mut accounts, vec![0]).unwrap();
+    assert_eq!(stubs::cpi_calls(), ["adapter"]);
+    assert_eq!(state::<Platform>(&accounts[1]).reentrancy_lock, 0);
+}
+
+#[test]
+fn adapter_calling_back_in_fails_with_reentrancy_detected() {
+    let mut accounts = adapter_accounts();
+    assert_eq!(
+        run_adapter(&mut accounts, vec![REENTER]),
+        Err(PTokenProgramError::ReentrancyDetected.into())
+    );
+    // The inner call was refused before making a CPI of its own
+    assert_eq!(stubs::cpi_calls(), ["adapter"]);
+    // And the outer one still released the lock on the way out
+    assert_eq!(state::<Platform>(&accounts[1]).reentrancy_lock, 0);
+}
+
+#[test]
+fn locked_platform_refuses_the_call() {
+    let mut platform = platform_state(100);
+    platform.reentrancy_lock = 1;
+    let mut accounts = adapter_accounts();
+    accounts[1] = program_account(platform_key(), &platform).writable();
+    assert_eq!(
+        run_adapter(&mut accounts, vec![0]),
+        Err(PTokenProgramError::ReentrancyDetected.into())
+    );
+    assert!(stubs::cpi_calls().is_empty());
+}
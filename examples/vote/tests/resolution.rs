@@ -0,0 +1,133 @@
+//! A vote is decided by its resolver when it has one and resolves in time, by the
+//! tally otherwise, and by the tally once a resolver misses its window
+
+mod common;
+
+use common::*;
+use jiminy::testing::{process, stubs, HostAccount};
+use jiminy::PodOption;
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey, sysvars::clock::Clock};
+use vote::events::WinningsRedeemed;
+use vote::state::{Vote, RESOLVER_TIMEOUT, UNRESOLVED};
+use vote::{PTokenProgramError, ProgramInstructions};
+
+const RESOLVER: Pubkey = [7; 32];
+// When the resolver's window closes for a vote ending at 0
+const TIMEOUT: i64 = RESOLVER_TIMEOUT as i64;
+
+// Run one instruction at `now` seconds
+fn run_at(now: i64, accounts: &mut [HostAccount], data: &[u8]) -> Result<(), ProgramError> {
+    stubs::reset();
+    stubs::set_clock(Clock {
+        unix_timestamp: now,
+        ..stubs::clock()
+    });
+    process(vote::process_instruction, &vote::ID, accounts, data)
+}
+
+// A vote ended at 0 where outcome 1 leads the tally 2_000_000 to 1_000_000
+fn ended_vote(resolver: Option<Pubkey>) -> Vote {
+    let mut vote_state = vote_state(0);
+    vote_state.tallies[0] = STAKE.to_le_bytes();
+    vote_state.tallies[1] = (2 * STAKE).to_le_bytes();
+    if let Some(resolver) = resolver {
+        vote_state.resolver = PodOption::some(resolver);
+    }
+    vote_state
+}
+
+// ResolveVote by `signer` at `now`; returns the vote it leaves
+fn resolve(
+    vote_state: &Vote,
+    signer: Pubkey,
+    outcome: u8,
+    now: i64,
+) -> Result<Vote, ProgramError> {
+    let mut accounts = [wallet(signer), program_account(key(1), vote_state).writable()];
+    let (data, len) = ProgramInstructions::ResolveVote { outcome }.pack();
+    run_at(now, &mut accounts, &data[..len])?;
+    Ok(state(&accounts[1]))
+}
+
+// RedeemWinnings at `now` of `key(2)`'s position of `STAKE` on outcome 0;
+// returns the reward paid
+fn redeem_outcome_0(vote_state: &Vote, now: i64) -> Result<u64, ProgramError> {
+    let mut accounts = redeem_accounts(key(2), vote_state);
+    // Enough for the whole pot and its fee
+    accounts[2] = owned_token_account(key(3), vault_key(&key(1)), 4 * STAKE);
+    run_at(now, &mut accounts, &redeem(0))?;
+    Ok(stubs::events::<WinningsRedeemed>("WinningsRedeemed")[0].reward.get())
+}
+
+#[test]
+fn resolver_pick_wins_over_the_tally() {
+    let vote_state = ended_vote(Some(RESOLVER));
+    let resolved = resolve(&vote_state, RESOLVER, 0, 1).unwrap();
+    assert_eq!(resolved.resolved_outcome, 0);
+
+    // Outcome 0 trails the tally but takes the whole pot
+    assert_eq!(redeem_outcome_0(&resolved, 1), Ok(3 * STAKE));
+    // Past the window too, a pick made in time still stands
+    assert_eq!(redeem_outcome_0(&resolved, TIMEOUT), Ok(3 * STAKE));
+
+    assert_eq!(
+        resolve(&resolved, RESOLVER, 1, 2),
+        Err(PTokenProgramError::AlreadyResolved.into())
+    );
+}
+
+#[test]
+fn only_the_resolver_resolves_and_only_after_the_deadline() {
+    let vote_state = ended_vote(Some(RESOLVER));
+    assert_eq!(
+        resolve(&vote_state, key(9), 0, 1),
+        Err(PTokenProgramError::ResolverMismatch.into())
+    );
+    assert_eq!(
+        resolve(&vote_state, RESOLVER, 0, -1),
+        Err(PTokenProgramError::VoteIsStillRunning.into())
+    );
+    assert_eq!(
+        resolve(&vote_state, RESOLVER, 2, 1),
+        Err(PTokenProgramError::InvalidSide.into())
+    );
+}
+
+#[test]
+fn votes_without_a_resolver_go_by_the_tally() {
+    let vote_state = ended_vote(None);
+    assert_eq!(
+        resolve(&vote_state, RESOLVER, 0, 1),
+        Err(PTokenProgramError::ResolverMismatch.into())
+    );
+    // Outcome 1 leads, so the position on outcome 0 lost
+    assert_eq!(
+        redeem_outcome_0(&vote_state, 1),
+        Err(PTokenProgramError::DidNotVoteForWinningSide.into())
+    );
+
+    let mut leading_0 = vote_state;
+    leading_0.tallies.swap(0, 1);
+    // The position's `STAKE` is half of outcome 0, so half the pot
+    assert_eq!(redeem_outcome_0(&leading_0, 1), Ok(3 * STAKE / 2));
+}
+
+#[test]
+fn missed_window_falls_back_to_the_tally() {
+    let mut vote_state = ended_vote(Some(RESOLVER));
+    vote_state.tallies.swap(0, 1);
+    assert_eq!(vote_state.resolved_outcome, UNRESOLVED);
+
+    // Redemptions wait for the resolver until its window closes
+    assert_eq!(
+        redeem_outcome_0(&vote_state, TIMEOUT - 1),
+        Err(PTokenProgramError::AwaitingResolution.into())
+    );
+    assert_eq!(redeem_outcome_0(&vote_state, TIMEOUT), Ok(3 * STAKE / 2));
+
+    // And the resolver can't come back and override the tally
+    assert_eq!(
+        resolve(&vote_state, RESOLVER, 1, TIMEOUT),
+        Err(PTokenProgramError::ResolutionWindowClosed.into())
+    );
+}
@@ -0,0 +1,378 @@
+//! A scripted vote, from InitializeVote to chunked redemptions, run through one
+//! set of accounts: after every step the events logged so far rebuild the
+//! platform's totals and match the vote, positions and token accounts they
+//! describe
+
+mod common;
+
+use common::*;
+use jiminy::testing::{process, stubs, HostAccount};
+use jiminy::Deadline;
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey, sysvars::clock::Clock};
+use vote::events::{PositionChanged, VoteCreated, WinningsRedeemed};
+use vote::state::{Platform, PlatformStats, Position, TokenAccountData, Vote};
+use vote::{FundingMode, PTokenProgramError, ProgramInstructions};
+
+const CREATOR: Pubkey = [2; 32];
+const ALICE: Pubkey = [20; 32];
+const BOB: Pubkey = [21; 32];
+const FEE_VAULT_TOKENS: Pubkey = [6; 32];
+const WHITELIST_ENTRY: Pubkey = [7; 32];
+const FEE_BPS: u64 = 100;
+// The vote runs from 1_000 to 1_100 seconds
+const START: i64 = 1_000;
+const DEADLINE: i64 = 1_100;
+
+fn vote_key() -> Pubkey {
+    key(1)
+}
+
+// Where the stubbed bump search puts the vote's vault and its token account
+fn vote_vault() -> Pubkey {
+    stubs::find_program_address(&[vote_key().as_ref()], &vote::ID).0
+}
+
+fn vote_vault_tokens() -> Pubkey {
+    let seeds = [vote_vault(), pinocchio_token::ID, MINT];
+    stubs::find_program_address(
+        &[seeds[0].as_ref(), seeds[1].as_ref(), seeds[2].as_ref()],
+        &pinocchio_associated_token_account::ID,
+    )
+    .0
+}
+
+// An authority's token account staking and the one paying fees, clear of the
+// keys in `common`
+fn stake_tokens(authority: Pubkey) -> Pubkey {
+    [authority[0] + 30; 32]
+}
+
+fn fee_tokens(authority: Pubkey) -> Pubkey {
+    [authority[0] + 40; 32]
+}
+
+/// The accounts of every step, and the events logged so far
+struct Chain {
+    accounts: Vec<HostAccount>,
+    votes_created: Vec<VoteCreated>,
+    positions_changed: Vec<PositionChanged>,
+    winnings_redeemed: Vec<WinningsRedeemed>,
+}
+
+impl Chain {
+    /// A platform with no votes yet, and `ALICE` and `BOB` holding `2 * STAKE`
+    /// to stake and `STAKE` for fees
+    fn new() -> Self {
+        let mut accounts = vec![
+            wallet(CREATOR),
+            HostAccount::new(vote_key(), pinocchio_system::ID, 0, &[])
+                .signer()
+                .writable(),
+            HostAccount::new(FEE_VAULT, vote::ID, LAMPORTS, &[]).writable(),
+            HostAccount::new(vote_vault(), pinocchio_system::ID, 0, &[]).writable(),
+            HostAccount::new(vote_vault_tokens(), pinocchio_system::ID, 0, &[]).writable(),
+            program_account(platform_key(), &platform_state(FEE_BPS as u16)).writable(),
+            program_account(TREASURY, &treasury_state()).writable(),
+            HostAccount::new(MINT, pinocchio_token::ID, LAMPORTS, &[0; 82]),
+            HostAccount::new(pinocchio::sysvars::rent::RENT_ID, Pubkey::default(), 1, &[]),
+            HostAccount::new(pinocchio_system::ID, Pubkey::default(), 1, &[]),
+            HostAccount::new(pinocchio_token::ID, Pubkey::default(), 1, &[]),
+            HostAccount::new(pinocchio_associated_token_account::ID, Pubkey::default(), 1, &[]),
+            owned_token_account(FEE_VAULT_TOKENS, FEE_VAULT, 0),
+            HostAccount::new(WHITELIST_ENTRY, pinocchio_system::ID, 0, &[]),
+        ];
+        for authority in [ALICE, BOB] {
+            let (position, receipt) = (
+                new_position_key(&vote_key(), &authority),
+                receipt_key(&vote_key(), &authority),
+            );
+            accounts.extend([
+                wallet(authority),
+                owned_token_account(stake_tokens(authority), authority, 2 * STAKE),
+                owned_token_account(fee_tokens(authority), authority, STAKE),
+                HostAccount::new(position, pinocchio_system::ID, 0, &[]).writable(),
+                HostAccount::new(receipt, pinocchio_system::ID, 0, &[]).writable(),
+            ]);
+        }
+        Self {
+            accounts,
+            votes_created: Vec::new(),
+            positions_changed: Vec::new(),
+            winnings_redeemed: Vec::new(),
+        }
+    }
+
+    fn account(&self, key: Pubkey) -> &HostAccount {
+        self.accounts.iter().find(|account| account.key == key).unwrap()
+    }
+
+    fn account_mut(&mut self, key: Pubkey) -> &mut HostAccount {
+        self.accounts.iter_mut().find(|account| account.key == key).unwrap()
+    }
+
+    fn tokens(&self, key: Pubkey) -> u64 {
+        state::<TokenAccountData>(self.account(key)).amount()
+    }
+
+    /// Run `instruction` at `now` seconds on the accounts at `keys`, write them
+    /// back and keep the events it logged
+    fn call(
+        &mut self,
+        now: i64,
+        keys: &[Pubkey],
+        instruction: ProgramInstructions,
+    ) -> Result<(), ProgramError> {
+        let mut accounts: Vec<_> = keys.iter().map(|key| self.account(*key).clone()).collect();
+        let (data, len) = instruction.pack();
+        stubs::reset();
+        stubs::set_clock(Clock {
+            slot: now as u64,
+            unix_timestamp: now,
+            ..stubs::clock()
+        });
+        process(vote::process_instruction, &vote::ID, &mut accounts, &data[..len])?;
+        for account in accounts {
+            *self.account_mut(account.key) = account;
+        }
+        self.votes_created.extend(stubs::events::<VoteCreated>("VoteCreated"));
+        self.positions_changed
+            .extend(stubs::events::<PositionChanged>("PositionChanged"));
+        self.winnings_redeemed
+            .extend(stubs::events::<WinningsRedeemed>("WinningsRedeemed"));
+        Ok(())
+    }
+
+    fn initialize_vote(&mut self) {
+        let keys = [
+            CREATOR,
+            vote_key(),
+            FEE_VAULT,
+            vote_vault(),
+            vote_vault_tokens(),
+            platform_key(),
+            TREASURY,
+            MINT,
+            pinocchio::sysvars::rent::RENT_ID,
+            pinocchio_system::ID,
+            pinocchio_token::ID,
+            pinocchio_associated_token_account::ID,
+        ];
+        let instruction = ProgramInstructions::InitializeVote {
+            deadline_kind: 0,
+            time_to_add: ((DEADLINE - START) as u64).to_le_bytes(),
+            min_total: [0; 8],
+            resolver: [0; 32],
+            max_position: [0; 8],
+            whitelist_only: false,
+            label: b"rain tomorrow?".to_vec(),
+        };
+        self.call(START, &keys, instruction).unwrap();
+        // The stubbed associated token program creates nothing, stand in the
+        // token account it would have made
+        *self.account_mut(vote_vault_tokens()) =
+            owned_token_account(vote_vault_tokens(), vote_vault(), 0);
+    }
+
+    fn initialize_position(&mut self, authority: Pubkey, amount: u64, side: u8) {
+        let keys = [
+            authority,
+            vote_key(),
+            vote_vault_tokens(),
+            stake_tokens(authority),
+            fee_tokens(authority),
+            FEE_VAULT_TOKENS,
+            new_position_key(&vote_key(), &authority),
+            platform_key(),
+            TREASURY,
+            FEE_VAULT,
+            MINT,
+            vote_vault(),
+            WHITELIST_ENTRY,
+        ];
+        let instruction = ProgramInstructions::InitializePosition {
+            amount: amount.to_be_bytes(),
+            side,
+            funding_mode: FundingMode::Owner,
+            allow_crank: 0,
+        };
+        self.call(START + 50, &keys, instruction).unwrap();
+    }
+
+    fn update_position(&mut self, authority: Pubkey, amount: u64) {
+        let keys = [
+            authority,
+            vote_key(),
+            vote_vault(),
+            vote_vault_tokens(),
+            stake_tokens(authority),
+            fee_tokens(authority),
+            FEE_VAULT_TOKENS,
+            new_position_key(&vote_key(), &authority),
+            platform_key(),
+            TREASURY,
+            FEE_VAULT,
+            MINT,
+        ];
+        let instruction = ProgramInstructions::UpdatePosition {
+            amount: amount.to_be_bytes(),
+            funding_mode: FundingMode::Owner,
+        };
+        self.call(START + 60, &keys, instruction).unwrap();
+    }
+
+    fn redeem(&mut self, authority: Pubkey, max_amount: u64) -> Result<(), ProgramError> {
+        let keys = [
+            authority,
+            vote_key(),
+            vote_vault_tokens(),
+            stake_tokens(authority),
+            FEE_VAULT_TOKENS,
+            new_position_key(&vote_key(), &authority),
+            receipt_key(&vote_key(), &authority),
+            platform_key(),
+            TREASURY,
+            FEE_VAULT,
+            MINT,
+            vote_vault(),
+        ];
+        let instruction = ProgramInstructions::RedeemWinnings {
+            max_amount: max_amount.to_le_bytes(),
+        };
+        self.call(DEADLINE + 100, &keys, instruction)
+    }
+
+    /// The platform's totals rebuilt from the events: a vote per `VoteCreated`,
+    /// the stake each `PositionChanged` added, and the fees, `FEE_BPS` of that
+    /// stake plus each `WinningsRedeemed::fee`
+    fn totals_from_events(&self) -> (u64, u64, u64) {
+        let mut last_amounts: Vec<(Pubkey, Pubkey, u64)> = Vec::new();
+        let mut volume = 0;
+        let mut stake_fees = 0;
+        for event in &self.positions_changed {
+            let (vote, authority) = (*event.vote.as_key(), *event.authority.as_key());
+            let new_amount = event.new_amount.get();
+            let last = last_amounts
+                .iter_mut()
+                .find(|(v, a, _)| (*v, *a) == (vote, authority));
+            let added = match last {
+                Some((_, _, amount)) => new_amount - std::mem::replace(amount, new_amount),
+                None => {
+                    last_amounts.push((vote, authority, new_amount));
+                    new_amount
+                }
+            };
+            volume += added;
+            stake_fees += added * FEE_BPS / 10_000;
+        }
+        let redeem_fees: u64 = self.winnings_redeemed.iter().map(|event| event.fee.get()).sum();
+        (self.votes_created.len() as u64, volume, stake_fees + redeem_fees)
+    }
+
+    /// The totals on the platform agree with the events, with ViewPlatformStats
+    /// and with the tokens that reached the fee vault
+    fn assert_totals(&self) {
+        let platform: Platform = state(self.account(platform_key()));
+        let stats = platform.stats();
+        let totals = (
+            u64::from_le_bytes(stats.total_votes),
+            u64::from_le_bytes(stats.total_volume),
+            u64::from_le_bytes(stats.total_fees),
+        );
+        assert_eq!(totals, self.totals_from_events());
+        assert_eq!(totals.2, self.tokens(FEE_VAULT_TOKENS));
+
+        let mut accounts = [self.account(platform_key()).clone()];
+        let (data, len) = ProgramInstructions::ViewPlatformStats {}.pack();
+        run(&mut accounts, &data[..len]).unwrap();
+        let returned: PlatformStats = bytemuck::pod_read_unaligned(&stubs::return_data());
+        assert_eq!(returned, stats);
+    }
+
+    /// The last `PositionChanged` of `authority` describes its position and the
+    /// tally of its side
+    fn assert_position_event(&self, authority: Pubkey) {
+        let event = self.positions_changed.last().unwrap();
+        let position: Position = state(self.account(new_position_key(&vote_key(), &authority)));
+        let vote: Vote = state(self.account(vote_key()));
+        assert_eq!(
+            *event,
+            PositionChanged {
+                vote: vote_key().into(),
+                authority: authority.into(),
+                side: position.side,
+                new_amount: position.amount,
+                tally: vote.tally(position.side).into(),
+            }
+        );
+    }
+}
+
+#[test]
+fn platform_totals_add_up_to_the_events() {
+    let mut chain = Chain::new();
+    chain.assert_totals();
+
+    chain.initialize_vote();
+    let vote: Vote = state(chain.account(vote_key()));
+    assert_eq!(
+        chain.votes_created,
+        [VoteCreated {
+            vote: vote_key().into(),
+            token: MINT.into(),
+            creator: CREATOR.into(),
+            deadline: Deadline::unix(DEADLINE),
+            min_total: 0u64.into(),
+            outcome_count: 2,
+        }]
+    );
+    assert_eq!(vote.deadline, Deadline::unix(DEADLINE));
+    chain.assert_totals();
+
+    chain.initialize_position(ALICE, STAKE, 0);
+    chain.assert_position_event(ALICE);
+    chain.assert_totals();
+
+    chain.initialize_position(BOB, STAKE, 1);
+    chain.assert_position_event(BOB);
+    chain.assert_totals();
+
+    chain.update_position(ALICE, STAKE);
+    chain.assert_position_event(ALICE);
+    chain.assert_totals();
+    assert_eq!(chain.positions_changed.len(), 3);
+    // The tallies the events carry are the vault's tokens
+    let vote: Vote = state(chain.account(vote_key()));
+    assert_eq!(vote.total_stake(), Ok(3 * STAKE));
+    assert_eq!(chain.tokens(vote_vault_tokens()), 3 * STAKE);
+
+    // Outcome 0 leads, `ALICE` is owed her 2 * STAKE and `BOB`'s STAKE. The
+    // first chunk takes the whole 1% fee, the second pays without one
+    chain.redeem(ALICE, STAKE).unwrap();
+    chain.assert_totals();
+    chain.redeem(ALICE, STAKE).unwrap();
+    chain.assert_totals();
+    let fee = 3 * STAKE / 100;
+    let redeemed = |reward: u64, fee: u64, remaining: u64| WinningsRedeemed {
+        vote: vote_key().into(),
+        authority: ALICE.into(),
+        reward: reward.into(),
+        fee: fee.into(),
+        remaining: remaining.into(),
+    };
+    assert_eq!(
+        chain.winnings_redeemed,
+        [redeemed(STAKE, fee, 2 * STAKE), redeemed(STAKE, 0, STAKE)]
+    );
+    let position: Position = state(chain.account(new_position_key(&vote_key(), &ALICE)));
+    assert_eq!(position.remaining_entitlement.get(), STAKE);
+    assert_eq!(chain.tokens(stake_tokens(ALICE)), 2 * STAKE);
+    assert_eq!(chain.tokens(vote_vault_tokens()), STAKE - fee);
+
+    // The losing side redeems nothing and the totals stay put
+    assert_eq!(
+        chain.redeem(BOB, 0),
+        Err(PTokenProgramError::DidNotVoteForWinningSide.into())
+    );
+    chain.assert_totals();
+    assert_eq!(chain.totals_from_events(), (1, 3 * STAKE, 3 * STAKE / 100 + fee));
+}
@@ -0,0 +1,147 @@
+//! ClaimFees takes at most what the treasury counted, ReconcileTreasury records how
+//! far the fee vault's balances are from those counters
+
+mod common;
+
+use common::*;
+use jiminy::testing::{stubs, HostAccount};
+use pinocchio::pubkey::Pubkey;
+use pinocchio::sysvars::clock::Clock;
+use vote::state::{Platform, Treasury};
+use vote::{PTokenProgramError, ProgramInstructions};
+
+const FEE_VAULT_BUMP: u8 = 250;
+// What the treasury has counted
+const FEE_LAMPORTS: u64 = 5_000;
+const FEE_TOKENS: u64 = 300;
+
+fn fee_vault_key() -> Pubkey {
+    pinocchio_pubkey::derive_address(&[platform_key().as_ref()], Some(FEE_VAULT_BUMP), &vote::ID)
+}
+
+fn platform() -> Platform {
+    let mut platform = platform_state(100);
+    platform.vault = fee_vault_key().into();
+    platform.vault_bump = FEE_VAULT_BUMP;
+    platform
+}
+
+fn counted_treasury() -> Treasury {
+    let mut treasury = treasury_state();
+    treasury.fees_collected_lamports.set(FEE_LAMPORTS);
+    treasury.fees_collected_tokens.set(FEE_TOKENS);
+    treasury
+}
+
+// The fee vault holding `lamports` above its rent-exempt minimum
+fn fee_vault(lamports: u64) -> HostAccount {
+    let rent = stubs::rent().minimum_balance(0);
+    HostAccount::new(fee_vault_key(), vote::ID, rent + lamports, &[]).writable()
+}
+
+// ClaimFees by the platform authority out of a vault holding the counted fees
+fn claim_accounts() -> Vec<HostAccount> {
+    vec![
+        wallet(PLATFORM_AUTHORITY),
+        program_account(TREASURY, &counted_treasury()).writable(),
+        fee_vault(FEE_LAMPORTS),
+        owned_token_account(key(3), fee_vault_key(), FEE_TOKENS),
+        HostAccount::new(key(4), pinocchio_system::ID, 0, &[]).writable(),
+        owned_token_account(key(5), key(4), 0),
+        program_account(platform_key(), &platform()),
+        HostAccount::new(pinocchio_token::ID, Pubkey::default(), LAMPORTS, &[]),
+    ]
+}
+
+fn claim(lamports: u64, tokens: u64) -> Vec<u8> {
+    pack(ProgramInstructions::ClaimFees {
+        lamports: lamports.to_le_bytes(),
+        tokens: tokens.to_le_bytes(),
+    })
+}
+
+fn pack(instruction: ProgramInstructions) -> Vec<u8> {
+    let (data, len) = instruction.pack();
+    data[..len].to_vec()
+}
+
+#[test]
+fn claims_above_the_counters_fail() {
+    for (lamports, tokens) in [(FEE_LAMPORTS + 1, 0), (0, FEE_TOKENS + 1)] {
+        let mut accounts = claim_accounts();
+        assert_eq!(
+            run(&mut accounts, &claim(lamports, tokens)),
+            Err(PTokenProgramError::ClaimExceedsFees.into())
+        );
+        assert_eq!(accounts[1].data, bytemuck::bytes_of(&counted_treasury()));
+        assert_eq!(accounts[4].lamports, 0);
+        assert!(stubs::cpi_calls().is_empty());
+    }
+}
+
+#[test]
+fn claim_of_exactly_the_counters_empties_them() {
+    let mut accounts = claim_accounts();
+    run(&mut accounts, &claim(FEE_LAMPORTS, FEE_TOKENS)).unwrap();
+    let treasury: Treasury = state(&accounts[1]);
+    assert_eq!(treasury.fees_collected_lamports.get(), 0);
+    assert_eq!(treasury.fees_collected_tokens.get(), 0);
+    // The vault keeps its rent, the lamports move without a CPI
+    assert_eq!(accounts[2].lamports, stubs::rent().minimum_balance(0));
+    assert_eq!(accounts[4].lamports, FEE_LAMPORTS);
+    assert_eq!(stubs::cpi_calls(), ["token::transfer"]);
+
+    // Nothing counted is left to claim
+    assert_eq!(
+        run(&mut accounts, &claim(1, 0)),
+        Err(PTokenProgramError::ClaimExceedsFees.into())
+    );
+}
+
+#[test]
+fn reconcile_records_the_gaps() {
+    // 2_000 lamports nobody counted, and 50 counted tokens missing
+    let mut accounts = [
+        program_account(TREASURY, &counted_treasury()).writable(),
+        program_account(platform_key(), &platform()),
+        fee_vault(FEE_LAMPORTS + 2_000),
+        owned_token_account(key(3), fee_vault_key(), 200),
+        owned_token_account(key(5), fee_vault_key(), FEE_TOKENS - 250),
+    ];
+    stubs::reset();
+    stubs::set_clock(Clock {
+        slot: 77,
+        ..stubs::clock()
+    });
+    jiminy::testing::process(
+        vote::process_instruction,
+        &vote::ID,
+        &mut accounts,
+        &pack(ProgramInstructions::ReconcileTreasury {}),
+    )
+    .unwrap();
+
+    let treasury: Treasury = state(&accounts[0]);
+    assert_eq!(i64::from_le_bytes(treasury.lamport_gap), 2_000);
+    assert_eq!(i64::from_le_bytes(treasury.token_gap), -50);
+    assert_eq!(treasury.reconciled_slot.get(), 77);
+    // Only the gaps are recorded, the counters stay
+    assert_eq!(treasury.fees_collected_lamports.get(), FEE_LAMPORTS);
+    assert_eq!(treasury.fees_collected_tokens.get(), FEE_TOKENS);
+}
+
+#[test]
+fn reconcile_rejects_a_token_account_listed_twice() {
+    let mut accounts = [
+        program_account(TREASURY, &counted_treasury()).writable(),
+        program_account(platform_key(), &platform()),
+        fee_vault(FEE_LAMPORTS),
+        owned_token_account(key(3), fee_vault_key(), FEE_TOKENS),
+        owned_token_account(key(3), fee_vault_key(), FEE_TOKENS),
+    ];
+    assert_eq!(
+        run(&mut accounts, &pack(ProgramInstructions::ReconcileTreasury {})),
+        Err(PTokenProgramError::DuplicateTokenAccount.into())
+    );
+    assert_eq!(accounts[0].data, bytemuck::bytes_of(&counted_treasury()));
+}
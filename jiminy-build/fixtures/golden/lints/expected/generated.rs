@@ -0,0 +1,411 @@
+use shank::ShankInstruction;
+use shank::ShankType;
+use pinocchio::program_error::ProgramError;
+
+/// Added to every declared error code, `errors.base` in jiminy.toml
+pub const ERROR_BASE: u32 = 0;
+
+/// The `E` a `Custom(code)` from `program_id` stands for
+///
+/// `None` if another program returned it or `E` has no such code.
+pub fn decode_error<E: TryFrom<u32>>(
+    program_id: &pinocchio::pubkey::Pubkey,
+    code: u32,
+) -> Option<E> {
+    if program_id != &crate::ID {
+        return None;
+    }
+    E::try_from(code).ok()
+}
+
+// Generated error enum: LintsProgramError
+#[derive(Clone, Debug, PartialEq, ShankType)]
+#[non_exhaustive]
+pub enum LintsProgramError {
+    InvalidDiscriminator = 6001,
+}
+
+impl core::fmt::Display for LintsProgramError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::InvalidDiscriminator => "InvalidDiscriminator",
+        })
+    }
+}
+
+impl From<LintsProgramError> for ProgramError {
+    fn from(e: LintsProgramError) -> Self {
+        Self::Custom(e as u32)
+    }
+}
+
+impl jiminy::dispatch::HasInvalidDiscriminator for LintsProgramError {
+    const INVALID_DISCRIMINATOR: Self = Self::InvalidDiscriminator;
+}
+
+impl TryFrom<u32> for LintsProgramError {
+    type Error = u32;
+
+    fn try_from(code: u32) -> Result<Self, u32> {
+        match code {
+            6001 => Ok(Self::InvalidDiscriminator),
+            _ => Err(code),
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Clone, Debug, PartialEq, ShankInstruction)]
+pub enum ProgramInstructions {
+    #[account(0, name = "config", desc = "Some account")]
+    #[account(1, signer, writable, name = "payer", desc = "Some account")]
+    #[account(2, writable, name = "large", desc = "Account created")]
+    #[account(3, name = "token_program", desc = "Token program")]
+    #[account(4, name = "system_program", desc = "System program")]
+    CreateLarge {
+        bump: u8,
+    },
+
+    /// Transfers `amount` from `source` to `destination`, authority `authority`
+    #[account(0, signer, name = "authority", desc = "Authority over the source")]
+    #[account(1, writable, name = "source", desc = "Token account paying out")]
+    #[account(2, writable, name = "destination", desc = "Token account paid")]
+    #[account(3, name = "token_program", desc = "Token program")]
+    PayOut {
+        amount: [u8; 8],
+    },
+
+    /// Read-only view, only built with the `views` feature (discriminator 200)
+    /// Returns `Small` as return data
+    #[account(0, name = "small", desc = "Small account to read")]
+    ViewSmall {
+    } = 200,
+
+    /// Read-only view, only built with the `views` feature (discriminator 201)
+    /// Returns `Large` as return data
+    #[account(0, name = "large", desc = "Large account to read")]
+    ViewLarge {
+    } = 201,
+
+}
+
+/// Longest instruction data `ProgramInstructions::pack` writes, discriminator included
+pub const MAX_INSTRUCTION_DATA_LEN: usize = jiminy::dispatch::max_len(&[
+    1 + crate::instructions::CreateLargeData::LEN,
+    1 + crate::instructions::PayOutData::LEN,
+    1,
+    1,
+]);
+
+impl ProgramInstructions {
+    /// Instruction data for this instruction: the discriminator, the data struct's bytes (the borsh encoded schema for a `data_parser`), then any trailing bytes
+    ///
+    /// Panics if the trailing bytes are longer than the instruction's `max_len`.
+    pub fn pack(&self) -> ([u8; MAX_INSTRUCTION_DATA_LEN], usize) {
+        let mut buf = [0u8; MAX_INSTRUCTION_DATA_LEN];
+        let len = match self {
+            Self::CreateLarge { bump } => {
+                buf[0] = 0;
+                let data = crate::instructions::CreateLargeData {
+                    bump: *bump,
+                };
+                let end = 1 + crate::instructions::CreateLargeData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                end
+            }
+            Self::PayOut { amount } => {
+                buf[0] = 1;
+                let data = crate::instructions::PayOutData {
+                    amount: *amount,
+                };
+                let end = 1 + crate::instructions::PayOutData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                end
+            }
+            Self::ViewSmall {} => {
+                buf[0] = 200;
+                1
+            }
+            Self::ViewLarge {} => {
+                buf[0] = 201;
+                1
+            }
+        };
+        (buf, len)
+    }
+
+    /// Parse instruction data the way the program does, deprecated discriminators included
+    pub fn unpack(data: &[u8]) -> Result<Self, pinocchio::program_error::ProgramError> {
+        let (&discriminator, data) = data.split_first().ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+        Ok(match discriminator {
+            0 => {
+                let fixed = data.get(..crate::instructions::CreateLargeData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::CreateLargeData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::CreateLargeData::LEN..];
+                if !tail.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::CreateLarge {
+                    bump: parsed.bump,
+                }
+            }
+            1 => {
+                let fixed = data.get(..crate::instructions::PayOutData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::PayOutData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::PayOutData::LEN..];
+                if !tail.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::PayOut {
+                    amount: parsed.amount,
+                }
+            }
+            200 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::ViewSmall {
+                }
+            }
+            201 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::ViewLarge {
+                }
+            }
+            _ => return Err(pinocchio::program_error::ProgramError::InvalidInstructionData),
+        })
+    }
+}
+
+// ShankAccount definitions for state structs
+// These are generated for IDL compatibility
+/// Account size: 32 bytes
+#[repr(C)]
+#[derive(Clone, shank::ShankAccount)]
+pub struct Small {
+    pub owner: pinocchio::pubkey::Pubkey,
+}
+
+impl Small {
+    /// Data bytes of the account, what `create_pda!` allocates
+    pub const SIZE: usize = 32;
+    /// Rent-exempt minimum at the default rent
+    pub const RENT_EXEMPT_LAMPORTS_AT_DEFAULT_RATE: u64 =
+        Self::rent_exempt_lamports(jiminy::rent::DEFAULT_LAMPORTS_PER_BYTE_YEAR);
+
+    /// Bytes to allocate when creating the account
+    pub const fn space() -> usize {
+        Self::SIZE
+    }
+
+    /// Rent-exempt minimum at `lamports_per_byte_year`
+    pub const fn rent_exempt_lamports(lamports_per_byte_year: u64) -> u64 {
+        jiminy::rent::exempt_lamports(Self::space(), lamports_per_byte_year)
+    }
+}
+
+const _: () = assert!(
+    Small::space() == crate::state::Small::LEN,
+    "Small size differs from the program"
+);
+
+/// Account size: 96 bytes
+#[repr(C)]
+#[derive(Clone, shank::ShankAccount)]
+pub struct Large {
+    pub owner: pinocchio::pubkey::Pubkey,
+    pub history: [[u8; 8]; 8],
+}
+
+impl Large {
+    /// Data bytes of the account, what `create_pda!` allocates
+    pub const SIZE: usize = 96;
+    /// Rent-exempt minimum at the default rent
+    pub const RENT_EXEMPT_LAMPORTS_AT_DEFAULT_RATE: u64 =
+        Self::rent_exempt_lamports(jiminy::rent::DEFAULT_LAMPORTS_PER_BYTE_YEAR);
+
+    /// Bytes to allocate when creating the account
+    pub const fn space() -> usize {
+        Self::SIZE
+    }
+
+    /// Rent-exempt minimum at `lamports_per_byte_year`
+    pub const fn rent_exempt_lamports(lamports_per_byte_year: u64) -> u64 {
+        jiminy::rent::exempt_lamports(Self::space(), lamports_per_byte_year)
+    }
+}
+
+const _: () = assert!(
+    Large::space() == crate::state::Large::LEN,
+    "Large size differs from the program"
+);
+
+pub type InstructionHandler = fn(
+    &[pinocchio::account_info::AccountInfo],
+    &[u8],
+) -> pinocchio::ProgramResult;
+
+// Read-only view of `crate::state::Small`, returns the account data as return data
+#[cfg(feature = "views")]
+pub mod view_small {
+    jiminy::define_instruction_with_metadata!(
+        discriminant: 200,
+        ViewSmall,
+        accounts: {
+            small: program, desc: "Small account to read",
+        },
+        data: {},
+        returns: crate::state::Small,
+        process: {
+            // Checked shared borrow, the account is never written
+            let data = small.try_borrow_data()?;
+            bytemuck::try_from_bytes::<crate::state::Small>(&data)
+                .copied()
+                .map_err(|_| ProgramError::InvalidAccountData)
+        }
+    );
+}
+
+// Read-only view of `crate::state::Large`, returns the account data as return data
+#[cfg(feature = "views")]
+pub mod view_large {
+    jiminy::define_instruction_with_metadata!(
+        discriminant: 201,
+        ViewLarge,
+        accounts: {
+            large: program, desc: "Large account to read",
+        },
+        data: {},
+        returns: crate::state::Large,
+        process: {
+            // Checked shared borrow, the account is never written
+            let data = large.try_borrow_data()?;
+            bytemuck::try_from_bytes::<crate::state::Large>(&data)
+                .copied()
+                .map_err(|_| ProgramError::InvalidAccountData)
+        }
+    );
+}
+
+fn handle_create_large(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::CreateLargeInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_pay_out(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::PayOutInstruction::try_from((accounts, data))?.process()
+}
+
+#[cfg(feature = "views")]
+fn handle_view_small(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    view_small::ViewSmallInstruction::try_from((accounts, data))?.process()
+}
+
+#[cfg(feature = "views")]
+fn handle_view_large(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    view_large::ViewLargeInstruction::try_from((accounts, data))?.process()
+}
+
+/// Instructions of the program, read-only views not counted
+pub const INSTRUCTION_COUNT: usize = 2;
+
+/// `(discriminator, name)` of each instruction, by discriminator
+pub const INSTRUCTIONS: [(u8, &str); INSTRUCTION_COUNT] = [
+    (0, "CreateLarge"),
+    (1, "PayOut"),
+];
+
+/// Instruction name of a discriminator, deprecated aliases included
+pub const fn instruction_name(discriminator: u8) -> Option<&'static str> {
+    match discriminator {
+        0 => Some("CreateLarge"),
+        1 => Some("PayOut"),
+        #[cfg(feature = "views")]
+        200 => Some("ViewSmall"),
+        #[cfg(feature = "views")]
+        201 => Some("ViewLarge"),
+        _ => None,
+    }
+}
+
+pub const DISPATCH: [Option<InstructionHandler>; 256] = {
+    let mut table: [Option<InstructionHandler>; 256] = [None; 256];
+    table[0] = Some(handle_create_large);
+    table[1] = Some(handle_pay_out);
+    #[cfg(feature = "views")]
+    { table[200] = Some(handle_view_small); }
+    #[cfg(feature = "views")]
+    { table[201] = Some(handle_view_large); }
+    table
+};
+
+#[cfg(not(feature = "match-dispatch"))]
+pub fn process_instruction(
+    program_id: &pinocchio::pubkey::Pubkey,
+    accounts: &[pinocchio::account_info::AccountInfo],
+    instruction_data: &[u8],
+) -> pinocchio::ProgramResult {
+    if !jiminy::perf::keys_eq(program_id, &crate::ID) {
+        return Err(pinocchio::program_error::ProgramError::IncorrectProgramId);
+    }
+
+    #[cfg(feature = "host-stubs")]
+    if let Some(discriminator) = instruction_data.first() {
+        jiminy::testing::record_coverage(*discriminator);
+    }
+
+    match instruction_data.split_first() {
+        Some((discriminator, data)) => match DISPATCH[*discriminator as usize] {
+            Some(handler) => handler(accounts, data),
+            None => Err(<LintsProgramError as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into()),
+        },
+        None => Err(<LintsProgramError as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into()),
+    }
+}
+
+#[cfg(feature = "match-dispatch")]
+pub fn process_instruction(
+    program_id: &pinocchio::pubkey::Pubkey,
+    accounts: &[pinocchio::account_info::AccountInfo],
+    instruction_data: &[u8],
+) -> pinocchio::ProgramResult {
+    if !jiminy::perf::keys_eq(program_id, &crate::ID) {
+        return Err(pinocchio::program_error::ProgramError::IncorrectProgramId);
+    }
+
+    #[cfg(feature = "host-stubs")]
+    if let Some(discriminator) = instruction_data.first() {
+        jiminy::testing::record_coverage(*discriminator);
+    }
+
+    match instruction_data.first() {
+        Some(0) => {
+            crate::instructions::CreateLargeInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(1) => {
+            crate::instructions::PayOutInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        #[cfg(feature = "views")]
+        Some(200) => {
+            view_small::ViewSmallInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        #[cfg(feature = "views")]
+        Some(201) => {
+            view_large::ViewLargeInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        _ => Err(<LintsProgramError as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into()),
+    }
+}
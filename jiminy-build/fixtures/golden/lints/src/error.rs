@@ -0,0 +1,4 @@
+define_errors! {
+    LintsProgramError,
+    InvalidDiscriminator = 6001,
+}
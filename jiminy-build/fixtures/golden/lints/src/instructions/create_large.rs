@@ -0,0 +1,32 @@
+use crate::state::{Large, Small};
+use jiminy::define_instruction_with_metadata;
+
+// `create_space`: created as a `Small`, loaded as a `Large`
+// `accounts`: the read-only `config` before the writable `payer`, an unpinned
+// `token_program` and two accounts sharing a desc
+// `unused_accounts`: `config` and `token_program`
+define_instruction_with_metadata!(
+    discriminant: 0,
+    CreateLarge,
+    accounts: {
+        config: any, desc: "Some account",
+        payer: signer => writable, desc: "Some account",
+        large: uninitialized, desc: "Account created",
+        token_program: any, desc: "Token program",
+        system_program,
+    },
+    data: {
+        bump: u8,
+    },
+    process: {
+        create_pda!(
+            from: payer,
+            to: large,
+            space: Small::LEN,
+            seeds: [b"large"],
+            bump: bump
+        );
+        load_mut!(large, Large).owner = payer.key().into();
+        Ok(())
+    }
+);
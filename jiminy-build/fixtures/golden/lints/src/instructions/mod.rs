@@ -0,0 +1,5 @@
+pub mod create_large;
+pub mod pay_out;
+
+pub use create_large::*;
+pub use pay_out::*;
@@ -0,0 +1,28 @@
+use jiminy::define_instruction_with_metadata;
+
+// `recipients`: nothing checks `destination`
+// `byte_order`: the big-endian read in `prepare`, the write below is allowed
+define_instruction_with_metadata!(
+    discriminant: 1,
+    PayOut,
+    accounts: {
+        authority: signer, desc: "Authority over the source",
+        source: token => writable, desc: "Token account paying out",
+        destination: token => writable, desc: "Token account paid",
+        token_program,
+    },
+    data: {
+        amount: [u8; 8],
+    },
+    prepare: {
+        let amount = u64::from_be_bytes(amount);
+    },
+    transfers: {
+        source => destination: amount, authority: authority;
+    },
+    process: {
+        // jiminy: allow(byte_order)
+        let _ = amount.to_be_bytes();
+        Ok(())
+    }
+);
@@ -0,0 +1,14 @@
+#![no_std]
+
+#[macro_use]
+extern crate jiminy;
+
+pub mod instructions;
+pub mod state;
+
+pinocchio_pubkey::declare_id!("Lints11111111111111111111111111111111111111");
+
+pub mod generated;
+pub use generated::*;
+
+jiminy_entrypoint!(process_instruction);
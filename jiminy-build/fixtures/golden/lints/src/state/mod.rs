@@ -0,0 +1,12 @@
+use jiminy::{define_state, PubkeyBytes};
+
+define_state! {
+    pub struct Small {
+        pub owner: PubkeyBytes,
+    }
+
+    pub struct Large {
+        pub owner: PubkeyBytes,
+        pub history: [[u8; 8]; 8],
+    }
+}
@@ -0,0 +1,337 @@
+use shank::ShankInstruction;
+use shank::ShankType;
+use pinocchio::program_error::ProgramError;
+
+/// Added to every declared error code, `errors.base` in jiminy.toml
+pub const ERROR_BASE: u32 = 0;
+
+/// The `E` a `Custom(code)` from `program_id` stands for
+///
+/// `None` if another program returned it or `E` has no such code.
+pub fn decode_error<E: TryFrom<u32>>(
+    program_id: &pinocchio::pubkey::Pubkey,
+    code: u32,
+) -> Option<E> {
+    if program_id != &crate::ID {
+        return None;
+    }
+    E::try_from(code).ok()
+}
+
+// Generated error enum: TallyProgramError
+#[derive(Clone, Debug, PartialEq, ShankType)]
+#[non_exhaustive]
+pub enum TallyProgramError {
+    InvalidDiscriminator = 6001,
+    Unauthorized = 6002,
+    TallyKeyIncorrect = 6003,
+}
+
+impl core::fmt::Display for TallyProgramError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str(match self {
+            Self::InvalidDiscriminator => "InvalidDiscriminator",
+            Self::Unauthorized => "Unauthorized",
+            Self::TallyKeyIncorrect => "TallyKeyIncorrect",
+        })
+    }
+}
+
+impl From<TallyProgramError> for ProgramError {
+    fn from(e: TallyProgramError) -> Self {
+        Self::Custom(e as u32)
+    }
+}
+
+impl jiminy::dispatch::HasInvalidDiscriminator for TallyProgramError {
+    const INVALID_DISCRIMINATOR: Self = Self::InvalidDiscriminator;
+}
+
+impl TryFrom<u32> for TallyProgramError {
+    type Error = u32;
+
+    fn try_from(code: u32) -> Result<Self, u32> {
+        match code {
+            6001 => Ok(Self::InvalidDiscriminator),
+            6002 => Ok(Self::Unauthorized),
+            6003 => Ok(Self::TallyKeyIncorrect),
+            _ => Err(code),
+        }
+    }
+}
+
+#[repr(u8)]
+#[derive(Clone, Debug, PartialEq, ShankInstruction)]
+pub enum ProgramInstructions {
+    /// Create the authority's tally PDA
+    #[account(0, signer, writable, name = "authority", desc = "Pays for and owns the tally")]
+    #[account(1, writable, name = "tally", desc = "Tally PDA")]
+    #[account(2, name = "system_program", desc = "System program")]
+    InitializeTally {
+        /// Bump of the tally PDA
+        bump: u8,
+    },
+
+    /// Add to the tally and return the new count
+    /// Returns `u64` as return data
+    #[account(0, signer, name = "authority", desc = "Owner of the tally")]
+    #[account(1, writable, name = "tally", desc = "Tally PDA")]
+    Bump {
+        /// Added to the count, little-endian
+        amount: [u8; 8],
+        /// Last slot the bump may land in, little-endian
+        expires_at_slot: [u8; 8],
+    },
+
+    /// Read-only view, only built with the `views` feature (discriminator 200)
+    /// Returns `Tally` as return data
+    #[account(0, name = "tally", desc = "Tally account to read")]
+    ViewTally {
+    } = 200,
+
+}
+
+/// Longest instruction data `ProgramInstructions::pack` writes, discriminator included
+pub const MAX_INSTRUCTION_DATA_LEN: usize = jiminy::dispatch::max_len(&[
+    1 + crate::instructions::InitializeTallyData::LEN,
+    1 + crate::instructions::BumpData::LEN,
+    1,
+]);
+
+impl ProgramInstructions {
+    /// Instruction data for this instruction: the discriminator, the data struct's bytes (the borsh encoded schema for a `data_parser`), then any trailing bytes
+    ///
+    /// Panics if the trailing bytes are longer than the instruction's `max_len`.
+    pub fn pack(&self) -> ([u8; MAX_INSTRUCTION_DATA_LEN], usize) {
+        let mut buf = [0u8; MAX_INSTRUCTION_DATA_LEN];
+        let len = match self {
+            Self::InitializeTally { bump } => {
+                buf[0] = 0;
+                let data = crate::instructions::InitializeTallyData {
+                    bump: *bump,
+                };
+                let end = 1 + crate::instructions::InitializeTallyData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                end
+            }
+            Self::Bump { amount, expires_at_slot } => {
+                buf[0] = 1;
+                let data = crate::instructions::BumpData {
+                    amount: *amount,
+                    expires_at_slot: *expires_at_slot,
+                };
+                let end = 1 + crate::instructions::BumpData::LEN;
+                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));
+                end
+            }
+            Self::ViewTally {} => {
+                buf[0] = 200;
+                1
+            }
+        };
+        (buf, len)
+    }
+
+    /// Parse instruction data the way the program does, deprecated discriminators included
+    pub fn unpack(data: &[u8]) -> Result<Self, pinocchio::program_error::ProgramError> {
+        let (&discriminator, data) = data.split_first().ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+        Ok(match discriminator {
+            0 => {
+                let fixed = data.get(..crate::instructions::InitializeTallyData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::InitializeTallyData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::InitializeTallyData::LEN..];
+                if !tail.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::InitializeTally {
+                    bump: parsed.bump,
+                }
+            }
+            1 => {
+                let fixed = data.get(..crate::instructions::BumpData::LEN).ok_or(pinocchio::program_error::ProgramError::InvalidInstructionData)?;
+                let parsed: crate::instructions::BumpData = bytemuck::pod_read_unaligned(fixed);
+                let tail = &data[crate::instructions::BumpData::LEN..];
+                if !tail.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::Bump {
+                    amount: parsed.amount,
+                    expires_at_slot: parsed.expires_at_slot,
+                }
+            }
+            200 => {
+                if !data.is_empty() {
+                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());
+                }
+                Self::ViewTally {
+                }
+            }
+            _ => return Err(pinocchio::program_error::ProgramError::InvalidInstructionData),
+        })
+    }
+}
+
+// ShankAccount definitions for state structs
+// These are generated for IDL compatibility
+/// Account size: 41 bytes
+#[repr(C)]
+#[derive(Clone, shank::ShankAccount)]
+pub struct Tally {
+    pub authority: pinocchio::pubkey::Pubkey,
+    pub count: [u8; 8],
+    pub bump: u8,
+}
+
+impl Tally {
+    /// Data bytes of the account, what `create_pda!` allocates
+    pub const SIZE: usize = 41;
+    /// Rent-exempt minimum at the default rent
+    pub const RENT_EXEMPT_LAMPORTS_AT_DEFAULT_RATE: u64 =
+        Self::rent_exempt_lamports(jiminy::rent::DEFAULT_LAMPORTS_PER_BYTE_YEAR);
+
+    /// Bytes to allocate when creating the account
+    pub const fn space() -> usize {
+        Self::SIZE
+    }
+
+    /// Rent-exempt minimum at `lamports_per_byte_year`
+    pub const fn rent_exempt_lamports(lamports_per_byte_year: u64) -> u64 {
+        jiminy::rent::exempt_lamports(Self::space(), lamports_per_byte_year)
+    }
+}
+
+const _: () = assert!(
+    Tally::space() == crate::state::Tally::LEN,
+    "Tally size differs from the program"
+);
+
+pub type InstructionHandler = fn(
+    &[pinocchio::account_info::AccountInfo],
+    &[u8],
+) -> pinocchio::ProgramResult;
+
+// Read-only view of `crate::state::Tally`, returns the account data as return data
+#[cfg(feature = "views")]
+pub mod view_tally {
+    jiminy::define_instruction_with_metadata!(
+        discriminant: 200,
+        ViewTally,
+        accounts: {
+            tally: program, desc: "Tally account to read",
+        },
+        data: {},
+        returns: crate::state::Tally,
+        process: {
+            // Checked shared borrow, the account is never written
+            let data = tally.try_borrow_data()?;
+            bytemuck::try_from_bytes::<crate::state::Tally>(&data)
+                .copied()
+                .map_err(|_| ProgramError::InvalidAccountData)
+        }
+    );
+}
+
+fn handle_initialize_tally(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::InitializeTallyInstruction::try_from((accounts, data))?.process()
+}
+
+fn handle_bump(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    crate::instructions::BumpInstruction::try_from((accounts, data))?.process()
+}
+
+#[cfg(feature = "views")]
+fn handle_view_tally(
+    accounts: &[pinocchio::account_info::AccountInfo],
+    data: &[u8],
+) -> pinocchio::ProgramResult {
+    view_tally::ViewTallyInstruction::try_from((accounts, data))?.process()
+}
+
+/// Instructions of the program, read-only views not counted
+pub const INSTRUCTION_COUNT: usize = 2;
+
+/// `(discriminator, name)` of each instruction, by discriminator
+pub const INSTRUCTIONS: [(u8, &str); INSTRUCTION_COUNT] = [
+    (0, "InitializeTally"),
+    (1, "Bump"),
+];
+
+/// Instruction name of a discriminator, deprecated aliases included
+pub const fn instruction_name(discriminator: u8) -> Option<&'static str> {
+    match discriminator {
+        0 => Some("InitializeTally"),
+        1 => Some("Bump"),
+        #[cfg(feature = "views")]
+        200 => Some("ViewTally"),
+        _ => None,
+    }
+}
+
+pub const DISPATCH: [Option<InstructionHandler>; 256] = {
+    let mut table: [Option<InstructionHandler>; 256] = [None; 256];
+    table[0] = Some(handle_initialize_tally);
+    table[1] = Some(handle_bump);
+    #[cfg(feature = "views")]
+    { table[200] = Some(handle_view_tally); }
+    table
+};
+
+#[cfg(not(feature = "match-dispatch"))]
+pub fn process_instruction(
+    program_id: &pinocchio::pubkey::Pubkey,
+    accounts: &[pinocchio::account_info::AccountInfo],
+    instruction_data: &[u8],
+) -> pinocchio::ProgramResult {
+    if !jiminy::perf::keys_eq(program_id, &crate::ID) {
+        return Err(pinocchio::program_error::ProgramError::IncorrectProgramId);
+    }
+
+    #[cfg(feature = "host-stubs")]
+    if let Some(discriminator) = instruction_data.first() {
+        jiminy::testing::record_coverage(*discriminator);
+    }
+
+    match instruction_data.split_first() {
+        Some((discriminator, data)) => match DISPATCH[*discriminator as usize] {
+            Some(handler) => handler(accounts, data),
+            None => Err(<TallyProgramError as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into()),
+        },
+        None => Err(<TallyProgramError as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into()),
+    }
+}
+
+#[cfg(feature = "match-dispatch")]
+pub fn process_instruction(
+    program_id: &pinocchio::pubkey::Pubkey,
+    accounts: &[pinocchio::account_info::AccountInfo],
+    instruction_data: &[u8],
+) -> pinocchio::ProgramResult {
+    if !jiminy::perf::keys_eq(program_id, &crate::ID) {
+        return Err(pinocchio::program_error::ProgramError::IncorrectProgramId);
+    }
+
+    #[cfg(feature = "host-stubs")]
+    if let Some(discriminator) = instruction_data.first() {
+        jiminy::testing::record_coverage(*discriminator);
+    }
+
+    match instruction_data.first() {
+        Some(0) => {
+            crate::instructions::InitializeTallyInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        Some(1) => {
+            crate::instructions::BumpInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        #[cfg(feature = "views")]
+        Some(200) => {
+            view_tally::ViewTallyInstruction::try_from((accounts, &instruction_data[1..]))?.process()
+        }
+        _ => Err(<TallyProgramError as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into()),
+    }
+}
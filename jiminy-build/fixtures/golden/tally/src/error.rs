@@ -0,0 +1,6 @@
+define_errors! {
+    TallyProgramError,
+    InvalidDiscriminator = 6001,
+    Unauthorized = 6002,
+    TallyKeyIncorrect = 6003,
+}
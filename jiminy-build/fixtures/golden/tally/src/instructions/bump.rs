@@ -0,0 +1,42 @@
+use crate::{
+    state::{Bumped, Tally, TALLY_SEED},
+    TallyProgramError,
+};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 1,
+    Bump,
+    desc: "Add to the tally and return the new count",
+    accounts: {
+        authority: signer, desc: "Owner of the tally",
+        tally: program => writable, desc: "Tally PDA",
+    },
+    data: {
+        /// Added to the count, little-endian
+        amount: [u8; 8],
+        /// Last slot the bump may land in, little-endian
+        expires_at_slot: [u8; 8],
+    },
+    expiry: slot_field(expires_at_slot),
+    returns: u64,
+    process: {
+        let tally_state = load_mut!(tally, Tally);
+        if tally_state.authority != *authority.key() {
+            return Err(TallyProgramError::Unauthorized.into());
+        }
+        assert_pda!(tally,
+            seeds: [TALLY_SEED, authority.key().as_ref()],
+            bump: tally_state.bump,
+            error: TallyProgramError::TallyKeyIncorrect
+        );
+        let count = u64::from_le_bytes(tally_state.count)
+            .saturating_add(u64::from_le_bytes(amount));
+        tally_state.count = count.to_le_bytes();
+        emit_event!(Bumped {
+            tally: tally.key().into(),
+            count: tally_state.count,
+        });
+        Ok(count)
+    }
+);
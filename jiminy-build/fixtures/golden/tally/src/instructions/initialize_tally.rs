@@ -0,0 +1,30 @@
+use crate::state::{Tally, TALLY_SEED};
+use jiminy::define_instruction_with_metadata;
+
+define_instruction_with_metadata!(
+    discriminant: 0,
+    InitializeTally,
+    desc: "Create the authority's tally PDA",
+    accounts: {
+        authority: signer => writable, desc: "Pays for and owns the tally",
+        tally: uninitialized, desc: "Tally PDA", label: "Tally", explorer_hint: pda,
+        system_program,
+    },
+    data: {
+        /// Bump of the tally PDA
+        bump: u8,
+    },
+    process: {
+        create_pda!(
+            from: authority,
+            to: tally,
+            space: Tally::LEN,
+            seeds: [TALLY_SEED, authority.key().as_ref()],
+            bump: bump
+        );
+        let tally_state = load_mut!(tally, Tally);
+        tally_state.authority = authority.key().into();
+        tally_state.bump = bump;
+        Ok(())
+    }
+);
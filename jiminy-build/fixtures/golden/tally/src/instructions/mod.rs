@@ -0,0 +1,5 @@
+pub mod initialize_tally;
+pub mod bump;
+
+pub use initialize_tally::*;
+pub use bump::*;
@@ -0,0 +1,19 @@
+use jiminy::{define_events, define_state, PubkeyBytes};
+
+pub const TALLY_SEED: &[u8; 5] = b"tally";
+
+define_state! {
+    pub struct Tally {
+        pub authority: PubkeyBytes,
+        pub count: [u8; 8],
+        pub bump: u8,
+    }
+}
+
+define_events! {
+    /// Logged by Bump
+    pub struct Bumped {
+        pub tally: PubkeyBytes,
+        pub count: [u8; 8],
+    }
+}
@@ -0,0 +1,34 @@
+/*
+ * Stand-ins for symbols of the vote example's `.so`, named with the v0 and
+ * legacy manglings the size report matches, for `tests/size_report.rs`. Any
+ * 64-bit little-endian ELF will do, so the object is built for the host:
+ *
+ *     gcc -c -Os -fno-asynchronous-unwind-tables -o vote.o vote.c
+ */
+
+#define SYMBOL(name) __asm__(name) __attribute__((used, noinline))
+
+/* RedeemWinnings: its module, its generated handler and its instruction type */
+long redeem_process(long a, long b) SYMBOL("_ZN4vote12instructions15redeem_winnings7process17h0000000000000001E");
+long redeem_process(long a, long b) { return a * 31 + b / 7 - (a ^ b); }
+
+long redeem_handler(long a) SYMBOL("_RNvNtCs1a_4vote9generated22handle_redeem_winnings");
+long redeem_handler(long a) { return redeem_process(a, a + 1) + redeem_process(a + 2, a); }
+
+const char redeem_metadata[96] __asm__("_ZN4vote9generated25RedeemWinningsInstruction8METADATA17h0000000000000002E")
+    __attribute__((used)) = "RedeemWinnings";
+
+/* InitializePosition */
+long position_process(long a) SYMBOL("_ZN4vote12instructions19initialize_position7process17h0000000000000003E");
+long position_process(long a) { return a * a + 3; }
+
+/* InitializeVoteWithPayer, not InitializeVote, whose module is a prefix of its own */
+long vote_with_payer(long a) SYMBOL("_ZN4vote12instructions26initialize_vote_with_payer7process17h0000000000000004E");
+long vote_with_payer(long a) { return a + 26; }
+
+/* Shared: the entrypoint and core */
+long entrypoint(long a) SYMBOL("entrypoint");
+long entrypoint(long a) { return redeem_handler(a) + position_process(a) + vote_with_payer(a); }
+
+long core_write(long a, long b, long c) SYMBOL("_ZN4core3fmt5write17h0000000000000005E");
+long core_write(long a, long b, long c) { return a * b + c * (a - b) + (c << 3); }
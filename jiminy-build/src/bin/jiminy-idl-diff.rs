@@ -0,0 +1,37 @@
+//! `jiminy-idl-diff <old.json> <new.json> [--strict]`
+//!
+//! Prints each difference between two shank IDLs as `breaking: ...` or
+//! `additive: ...`, see `jiminy_build::diff_idls`. Exits with 1 on a breaking
+//! change with `--strict`, and with 2 when an IDL can't be read or parsed.
+
+use std::env;
+use std::fs;
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    let strict = args.iter().any(|arg| arg == "--strict");
+    let paths: Vec<&String> = args.iter().filter(|arg| *arg != "--strict").collect();
+    let [old, new] = paths[..] else {
+        eprintln!("usage: jiminy-idl-diff <old.json> <new.json> [--strict]");
+        return ExitCode::from(2);
+    };
+
+    let read =
+        |path: &str| fs::read_to_string(path).map_err(|e| format!("cannot read {path}: {e}"));
+    let diff = match read(old).and_then(|old| jiminy_build::diff_idls(&old, &read(new)?)) {
+        Ok(diff) => diff,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::from(2);
+        }
+    };
+    for change in &diff.changes {
+        println!("{change}");
+    }
+    if strict && diff.is_breaking() {
+        ExitCode::FAILURE
+    } else {
+        ExitCode::SUCCESS
+    }
+}
@@ -0,0 +1,142 @@
+//! The byte-order lint: raw integer byte conversions in instruction files
+//!
+//! State fields are little-endian, so a `from_be_bytes` or `to_be_bytes` in an
+//! instruction is either a wire-format value or a bug. Each one is reported
+//! with its file and line; a `// jiminy: allow(byte_order)` comment on the line
+//! or on the line before accepts it. With `raw_le_bytes` the bare little-endian
+//! conversions are reported too, for fields that could be `jiminy::LeU64` and
+//! friends instead. Comments and string literals are skipped, and since every
+//! conversion is a single identifier, one split over several lines is reported
+//! at the line it's named on.
+
+use crate::{sorted_entries, LintConfig};
+use std::fs;
+use std::path::Path;
+
+const BIG_ENDIAN: [&str; 2] = ["from_be_bytes", "to_be_bytes"];
+const LITTLE_ENDIAN: [&str; 2] = ["from_le_bytes", "to_le_bytes"];
+
+/// One message per conversion in `src/instructions`, paths relative to `root`
+pub(crate) fn lint(root: &Path, lint: &LintConfig) -> Vec<String> {
+    let dir = root.join("src/instructions");
+    if !dir.exists() {
+        return Vec::new();
+    }
+    let mut messages = Vec::new();
+    for path in sorted_entries(&dir) {
+        if path.extension().and_then(|s| s.to_str()) != Some("rs") {
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let file = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .display()
+            .to_string();
+        messages.extend(
+            scan(&content, lint.raw_le_bytes)
+                .into_iter()
+                .map(|(line, name)| {
+                    let fix = if BIG_ENDIAN.contains(&name) {
+                        "state is little-endian, use a `jiminy::LeU64` field's `get()`/`set()`, \
+                 or add `// jiminy: allow(byte_order)` if big-endian is intended"
+                    } else {
+                        "use a `jiminy::LeU64` field's `get()`/`set()` instead"
+                    };
+                    format!("{file}:{line}: `{name}`, {fix}")
+                }),
+        );
+    }
+    messages
+}
+
+/// `(line, conversion)` of every reported conversion in `content`, lines from 1
+fn scan(content: &str, raw_le_bytes: bool) -> Vec<(usize, &'static str)> {
+    let mut found = Vec::new();
+    let mut in_block_comment = false;
+    let mut in_string = false;
+    let mut allow_next = false;
+    for (i, line) in content.lines().enumerate() {
+        let (code, comment) = split_line(line, &mut in_block_comment, &mut in_string);
+        let allowed = allows_byte_order(&comment);
+        if code.trim().is_empty() {
+            // A comment line covers the next line of code
+            allow_next |= allowed;
+            continue;
+        }
+        let skip = allowed || allow_next;
+        allow_next = false;
+        if skip {
+            continue;
+        }
+        let names = BIG_ENDIAN
+            .iter()
+            .chain(LITTLE_ENDIAN.iter().filter(|_| raw_le_bytes));
+        for &name in names {
+            if contains_word(&code, name) {
+                found.push((i + 1, name));
+            }
+        }
+    }
+    found
+}
+
+// `jiminy: allow(create_space, byte_order)` names this lint
+fn allows_byte_order(comment: &str) -> bool {
+    comment
+        .split("jiminy: allow(")
+        .skip(1)
+        .filter_map(|rest| rest.split_once(')'))
+        .any(|(list, _)| list.split(',').any(|lint| lint.trim() == "byte_order"))
+}
+
+// The line's code with string contents blanked, and its comments
+pub(crate) fn split_line(
+    line: &str,
+    in_block_comment: &mut bool,
+    in_string: &mut bool,
+) -> (String, String) {
+    let mut code = String::new();
+    let mut comment = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        if *in_block_comment {
+            if c == '*' && chars.peek() == Some(&'/') {
+                chars.next();
+                *in_block_comment = false;
+            } else {
+                comment.push(c);
+            }
+        } else if *in_string {
+            if c == '\\' {
+                chars.next();
+            } else if c == '"' {
+                *in_string = false;
+                code.push(c);
+            }
+        } else if c == '/' && chars.peek() == Some(&'/') {
+            comment.extend(chars.by_ref());
+        } else if c == '/' && chars.peek() == Some(&'*') {
+            chars.next();
+            *in_block_comment = true;
+        } else {
+            if c == '"' {
+                *in_string = true;
+            }
+            code.push(c);
+        }
+    }
+    (code, comment)
+}
+
+// `word` not inside a longer identifier
+pub(crate) fn contains_word(code: &str, word: &str) -> bool {
+    let is_ident = |c: char| c.is_alphanumeric() || c == '_';
+    code.match_indices(word).any(|(start, _)| {
+        let before = code[..start].chars().next_back();
+        let after = code[start + word.len()..].chars().next();
+        !before.is_some_and(is_ident) && !after.is_some_and(is_ident)
+    })
+}
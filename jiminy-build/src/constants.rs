@@ -0,0 +1,237 @@
+//! `JIMINY_CONSTANTS=1`: write the constants clients hardcode next to the IDL
+//!
+//! `<crate name>.constants.json` and `<crate name>.constants.ts` list the
+//! program id from `declare_id!`, every instruction's discriminator and
+//! deprecated aliases, the error codes (`errors.base` included) with their
+//! messages, and the byte-string seed constants (`const X: &[u8; N] = b"..."`).
+//! The JSON is the same data for clients in other languages.
+
+use crate::fixtures::{address, extract_seed_constants, string};
+use crate::{
+    extract_error_metadata, find_workspace_root, Config, ErrorMeta, InstructionMeta, Settings,
+};
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Write both files to `dir`
+pub(crate) fn run(config: &Config, dir: &Path, instructions: &[InstructionMeta]) -> io::Result<()> {
+    let (json, ts) = render(config, instructions);
+    let name = env::var("CARGO_PKG_NAME").unwrap();
+    fs::create_dir_all(dir)?;
+    fs::write(dir.join(format!("{name}.constants.json")), json)?;
+    fs::write(dir.join(format!("{name}.constants.ts")), ts)?;
+    println!("cargo:warning=constants written to {}", dir.display());
+    Ok(())
+}
+
+/// The constants as `(json, typescript)`
+pub(crate) fn render(config: &Config, instructions: &[InstructionMeta]) -> (String, String) {
+    let root = config.root.as_path();
+    let settings = Settings::load(root, config.workspace_root.as_deref());
+    let constants = Constants {
+        program_id: program_id(root),
+        instructions,
+        errors: extract_error_metadata(root, &settings),
+        seeds: extract_seed_constants(&root.join("src")),
+    };
+    (constants.json(), constants.typescript())
+}
+
+/// The id in the crate's `declare_id!("...")`, `None` without one
+pub(crate) fn program_id(root: &Path) -> Option<String> {
+    let content = fs::read_to_string(root.join("src/lib.rs")).ok()?;
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with("//"))
+        .find_map(|line| {
+            let (_, rest) = line.split_once("declare_id!(")?;
+            let (_, rest) = rest.split_once('"')?;
+            let (id, _) = rest.split_once('"')?;
+            Some(id.to_string())
+        })
+}
+
+/// The base58 id of the program `path` names: a well-known program's key, or for
+/// `counter::ID` the `declare_id!` of the crate at `root`'s `counter` dependency,
+/// found through its `path` (directly or in the workspace's dependencies)
+pub(crate) fn resolve_program_id(root: &Path, path: &str) -> Option<String> {
+    let known = address(path);
+    if known != path {
+        return Some(known.to_string());
+    }
+    let dependency = path.strip_suffix("::ID")?;
+    if dependency == "crate" {
+        return program_id(root);
+    }
+    let manifest = |dir: &Path| -> Option<toml::Table> {
+        fs::read_to_string(dir.join("Cargo.toml")).ok()?.parse().ok()
+    };
+    let dependencies = manifest(root)?;
+    let (name, spec) = dependencies
+        .get("dependencies")?
+        .as_table()?
+        .iter()
+        .find(|(name, _)| name.replace('-', "_") == dependency)?;
+    let dependency_root = match spec.get("path").and_then(toml::Value::as_str) {
+        Some(path) => root.join(path),
+        None if spec.get("workspace").and_then(toml::Value::as_bool) == Some(true) => {
+            let workspace = find_workspace_root(root)?;
+            let path = manifest(&workspace)?
+                .get("workspace")?
+                .get("dependencies")?
+                .get(name)?
+                .get("path")?
+                .as_str()?
+                .to_string();
+            workspace.join(path)
+        }
+        None => return None,
+    };
+    program_id(&dependency_root)
+}
+
+struct Constants<'a> {
+    program_id: Option<String>,
+    instructions: &'a [InstructionMeta],
+    errors: Vec<ErrorMeta>,
+    /// `(name, text)` of each seed constant
+    seeds: Vec<(String, String)>,
+}
+
+impl Constants<'_> {
+    fn json(&self) -> String {
+        let mut json = String::from("{\n");
+        let program_id = self
+            .program_id
+            .as_deref()
+            .map_or("null".to_string(), string);
+        writeln!(json, "  \"program_id\": {program_id},").unwrap();
+
+        let instructions: Vec<String> = self
+            .instructions
+            .iter()
+            .map(|instruction| {
+                let aliases: Vec<String> = instruction.aliases.iter().map(u8::to_string).collect();
+                format!(
+                    "    {{ \"name\": {}, \"discriminator\": {}, \"aliases\": [{}] }}",
+                    string(&instruction.name),
+                    instruction.discriminator,
+                    aliases.join(", ")
+                )
+            })
+            .collect();
+        writeln!(
+            json,
+            "  \"instructions\": [\n{}\n  ],",
+            instructions.join(",\n")
+        )
+        .unwrap();
+
+        let errors: Vec<String> = self
+            .errors()
+            .map(|(error, name, code, message)| {
+                format!(
+                    "    {{ \"enum\": {}, \"name\": {}, \"code\": {code}, \"message\": {} }}",
+                    string(error),
+                    string(name),
+                    string(&message)
+                )
+            })
+            .collect();
+        writeln!(json, "  \"errors\": [\n{}\n  ],", errors.join(",\n")).unwrap();
+
+        let seeds: Vec<String> = self
+            .seeds
+            .iter()
+            .map(|(name, text)| {
+                format!(
+                    "    {{ \"name\": {}, \"text\": {}, \"bytes\": [{}] }}",
+                    string(name),
+                    string(text),
+                    bytes(text)
+                )
+            })
+            .collect();
+        writeln!(json, "  \"seeds\": [\n{}\n  ]", seeds.join(",\n")).unwrap();
+        json.push_str("}\n");
+        json
+    }
+
+    fn typescript(&self) -> String {
+        let mut ts = String::from("// Generated by jiminy-build, do not edit\n\n");
+        match &self.program_id {
+            Some(id) => writeln!(ts, "export const PROGRAM_ID = {};\n", string(id)).unwrap(),
+            None => ts.push_str("// No `declare_id!` in src/lib.rs\n\n"),
+        }
+
+        ts.push_str("export const DISCRIMINATORS = {\n");
+        for instruction in self.instructions {
+            writeln!(ts, "  {}: {},", instruction.name, instruction.discriminator).unwrap();
+        }
+        ts.push_str("} as const;\n\n");
+
+        if self.instructions.iter().any(|i| !i.aliases.is_empty()) {
+            ts.push_str("// Deprecated discriminators that still dispatch to the instruction\n");
+            ts.push_str("export const DEPRECATED_DISCRIMINATORS = {\n");
+            for instruction in self.instructions.iter().filter(|i| !i.aliases.is_empty()) {
+                let aliases: Vec<String> = instruction.aliases.iter().map(u8::to_string).collect();
+                writeln!(ts, "  {}: [{}],", instruction.name, aliases.join(", ")).unwrap();
+            }
+            ts.push_str("} as const;\n\n");
+        }
+
+        ts.push_str("export const ERRORS = {\n");
+        for (_, name, code, message) in self.errors() {
+            writeln!(
+                ts,
+                "  {code}: {{ name: {}, message: {} }},",
+                string(name),
+                string(&message)
+            )
+            .unwrap();
+        }
+        ts.push_str("} as const;\n");
+
+        for (name, text) in &self.seeds {
+            writeln!(ts, "\n// {}", string(text)).unwrap();
+            writeln!(
+                ts,
+                "export const {name} = new Uint8Array([{}]);",
+                bytes(text)
+            )
+            .unwrap();
+        }
+        ts
+    }
+
+    /// `(enum, variant, code, message)`, the message being what `Display` shows
+    fn errors(&self) -> impl Iterator<Item = (&str, &str, u32, String)> {
+        self.errors.iter().flat_map(|error| {
+            error.variants.iter().map(move |variant| {
+                let message = if variant.docs.is_empty() {
+                    variant.name.clone()
+                } else {
+                    variant.docs.join(" ")
+                };
+                (
+                    error.name.as_str(),
+                    variant.name.as_str(),
+                    variant.code,
+                    message,
+                )
+            })
+        })
+    }
+}
+
+// The seed's bytes as written between the quotes, escapes aren't decoded
+fn bytes(text: &str) -> String {
+    text.bytes()
+        .map(|b| b.to_string())
+        .collect::<Vec<_>>()
+        .join(", ")
+}
@@ -0,0 +1,154 @@
+//! Per-account constraints for clients that resolve and preflight accounts
+//!
+//! For each instruction, what the declared account types and the PDA, key and
+//! token owner checks in the sources require of every account: `signer`,
+//! `{ "ownedBy": key }` with `initialized` or `uninitialized`, `{ "address": key }`,
+//! `{ "sameAs": account }`, `{ "keyIn": ... }`, `{ "tokenOwner": account }` and
+//! `{ "pda": { "seeds", "bumpField" } }`, the bump field being `"canonical"` when the
+//! program searches for the bump and `null` when the sources don't say. Accounts
+//! read with `load_foreign!` are `ownedBy` the other program, by its id when it can
+//! be resolved. Writability is left to the IDL's `isMut`.
+//! `JIMINY_IDL=1` adds the list to the IDL as `accountConstraints`, and
+//! `JIMINY_FIXTURES=1` writes it to `fixtures/constraints.json`.
+
+use crate::fixtures::{address, extract_seed_constants, seed_fixture, string};
+use crate::{constants, AccountMeta, InstructionMeta};
+use std::path::Path;
+
+/// The constraints of every instruction of the crate at `root`, as a JSON array
+pub(crate) fn render(root: &Path, instructions: &[InstructionMeta]) -> String {
+    let program_id = constants::program_id(root).unwrap_or_else(|| "program".to_string());
+    let seeds = extract_seed_constants(&root.join("src"));
+    let rows: Vec<String> = instructions
+        .iter()
+        .map(|instruction| {
+            let accounts: Vec<String> = instruction
+                .accounts
+                .iter()
+                .map(|account| {
+                    let mut constraints = constraints(instruction, account, &program_id, &seeds);
+                    if let Some((_, _, owner)) =
+                        instruction.state_uses.foreign.iter().find(|(a, ..)| *a == account.name)
+                    {
+                        let owner = constants::resolve_program_id(root, owner)
+                            .unwrap_or_else(|| owner.clone());
+                        constraints.push(format!("{{ \"ownedBy\": {} }}", string(&owner)));
+                        constraints.push("\"initialized\"".to_string());
+                    }
+                    format!(
+                        "      {{ \"name\": {}, \"constraints\": [{}] }}",
+                        string(&account.name),
+                        constraints.join(", ")
+                    )
+                })
+                .collect();
+            format!(
+                "  {{\n    \"instruction\": {},\n    \"accounts\": [\n{}\n    ]\n  }}",
+                string(&instruction.name),
+                accounts.join(",\n")
+            )
+        })
+        .collect();
+    format!("[\n{}\n]\n", rows.join(",\n"))
+}
+
+fn constraints(
+    instruction: &InstructionMeta,
+    account: &AccountMeta,
+    program_id: &str,
+    seeds: &[(String, String)],
+) -> Vec<String> {
+    let owned_by = |owner: &str| format!("{{ \"ownedBy\": {} }}", string(owner));
+    let arg = account.type_arg.as_deref().map(str::trim);
+
+    let mut constraints = Vec::new();
+    match account.account_type.as_str() {
+        "signer" => constraints.push("\"signer\"".to_string()),
+        "program" => {
+            constraints.push(owned_by(program_id));
+            constraints.push("\"initialized\"".to_string());
+        }
+        "token" => {
+            constraints.push(owned_by(address("pinocchio_token::ID")));
+            constraints.push("\"initialized\"".to_string());
+        }
+        // Lamports sent ahead of time are allowed, only the owner and data are checked
+        "uninitialized" => {
+            constraints.push(owned_by(address("pinocchio_system::ID")));
+            constraints.push("\"uninitialized\"".to_string());
+        }
+        "not_token" => constraints.push(format!(
+            "{{ \"notOwnedBy\": {} }}",
+            string(address("pinocchio_token::ID"))
+        )),
+        "address" => {
+            if let Some(key) = arg {
+                constraints.push(format!("{{ \"address\": {} }}", string(address(key))));
+            }
+        }
+        "same_as" => {
+            if let Some(other) = arg {
+                constraints.push(format!("{{ \"sameAs\": {} }}", string(other)));
+            }
+        }
+        // `Platform::vault of platform`
+        "key_in" => {
+            if let Some((path, of)) = arg.and_then(|arg| arg.split_once(" of ")) {
+                let (state, field) = path.split_once("::").unwrap_or(("", path));
+                constraints.push(format!(
+                    "{{ \"keyIn\": {{ \"account\": {}, \"state\": {}, \"field\": {} }} }}",
+                    string(of.trim()),
+                    string(state.trim()),
+                    string(field.trim())
+                ));
+            }
+        }
+        _ => {}
+    }
+
+    let uses = &instruction.state_uses;
+    let is_account = |name: &str| instruction.accounts.iter().any(|a| a.name == name);
+    if let Some((_, owner)) = uses
+        .token_owners
+        .iter()
+        .find(|(token, owner)| *token == account.name && is_account(owner))
+    {
+        constraints.push(format!("{{ \"tokenOwner\": {} }}", string(owner)));
+    }
+    if let Some((_, pda_seeds)) = uses.pdas.iter().find(|(pda, _)| *pda == account.name) {
+        let pda_seeds: Vec<String> = crate::top_level(pda_seeds, ',')
+            .map(|seed| seed_fixture(seed.trim(), instruction, seeds))
+            .collect();
+        let bump = match uses.bumps.iter().find(|(pda, _)| *pda == account.name) {
+            Some((_, Some(bump))) => bump_field(bump, instruction),
+            Some((_, None)) => "\"canonical\"".to_string(),
+            None => "null".to_string(),
+        };
+        constraints.push(format!(
+            "{{ \"pda\": {{ \"seeds\": [{}], \"bumpField\": {bump} }} }}",
+            pda_seeds.join(", ")
+        ));
+    }
+    constraints
+}
+
+// Where a bump comes from: a data field, a field of an account's state
+// (`platform_state.platform_bump`), or else the expression as written
+fn bump_field(bump: &str, instruction: &InstructionMeta) -> String {
+    if instruction.fields.iter().any(|f| f.name == bump) {
+        return format!("{{ \"arg\": {} }}", string(bump));
+    }
+    if let Some((holder, field)) = bump.split_once('.') {
+        let account = holder.strip_suffix("_state").unwrap_or(holder);
+        let is_field =
+            |s: &str| !s.is_empty() && s.chars().all(|c| c.is_alphanumeric() || c == '_');
+        if instruction.accounts.iter().any(|a| a.name == account) && is_field(field) {
+            return format!(
+                "{{ \"account\": {}, \"field\": {} }}",
+                string(account),
+                string(field)
+            );
+        }
+    }
+    format!("{{ \"expr\": {} }}", string(bump))
+}
@@ -0,0 +1,403 @@
+//! Named discriminator ranges from `jiminy.toml` and the report of used and free values
+//!
+//! A `[discriminators.<name>]` table reserves `range = [first, last]`, and with
+//! an `instructions` list the range belongs to those instructions alone. Once
+//! any range is declared, the build fails when a listed instruction's
+//! discriminator is outside its range, an unlisted one's is inside a listed
+//! range, or one is outside every range. Deprecated aliases are only reported.
+//! `200..=255` stays reserved for the generated view instructions.
+
+use crate::fixtures::string;
+use crate::{InstructionMeta, VIEW_DISCRIMINATOR_BASE};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// A `[discriminators.<name>]` table
+#[derive(Debug, Clone)]
+pub(crate) struct DiscriminatorRange {
+    name: String,
+    first: u8,
+    last: u8,
+    /// Instructions the range is reserved for, any unlisted one may use it when `None`
+    instructions: Option<Vec<String>>,
+}
+
+impl DiscriminatorRange {
+    fn contains(&self, discriminator: u8) -> bool {
+        (self.first..=self.last).contains(&discriminator)
+    }
+
+    fn lists(&self, instruction: &str) -> bool {
+        self.instructions
+            .iter()
+            .flatten()
+            .any(|name| name == instruction)
+    }
+}
+
+/// The `discriminators` table, sorted by first value; panics when a range is
+/// malformed, overlaps another or reaches the view range
+pub(crate) fn parse(table: &toml::Value) -> Vec<DiscriminatorRange> {
+    let table = table
+        .as_table()
+        .unwrap_or_else(|| panic!("jiminy.toml: `discriminators` must be a table"));
+    let mut ranges: Vec<DiscriminatorRange> = table
+        .iter()
+        .map(|(name, settings)| {
+            let key = format!("discriminators.{name}");
+            let settings = settings
+                .as_table()
+                .unwrap_or_else(|| panic!("jiminy.toml: `{key}` must be a table"));
+            let mut bounds = None;
+            let mut instructions = None;
+            for (setting, value) in settings {
+                match setting.as_str() {
+                    "range" => bounds = Some(parse_bounds(value, &key)),
+                    "instructions" => {
+                        instructions =
+                            Some(crate::string_list(value, &format!("{key}.instructions")))
+                    }
+                    _ => panic!("jiminy.toml: unknown setting `{key}.{setting}`"),
+                }
+            }
+            let (first, last) = bounds
+                .unwrap_or_else(|| panic!("jiminy.toml: `{key}` needs a `range = [first, last]`"));
+            DiscriminatorRange {
+                name: name.clone(),
+                first,
+                last,
+                instructions,
+            }
+        })
+        .collect();
+    ranges.sort_by_key(|range| range.first);
+
+    for pair in ranges.windows(2) {
+        if pair[1].first <= pair[0].last {
+            panic!(
+                "jiminy.toml: `discriminators.{}` ({}) and `discriminators.{}` ({}) overlap",
+                pair[0].name,
+                span(pair[0].first, pair[0].last),
+                pair[1].name,
+                span(pair[1].first, pair[1].last)
+            );
+        }
+    }
+    if let Some(range) = ranges
+        .last()
+        .filter(|range| range.last >= VIEW_DISCRIMINATOR_BASE)
+    {
+        panic!(
+            "jiminy.toml: `discriminators.{}` reaches {VIEW_DISCRIMINATOR_BASE}..=255, which is reserved for view instructions",
+            range.name
+        );
+    }
+    ranges
+}
+
+// `[32, 63]`
+fn parse_bounds(value: &toml::Value, key: &str) -> (u8, u8) {
+    value
+        .as_array()
+        .filter(|bounds| bounds.len() == 2)
+        .and_then(|bounds| {
+            let first = u8::try_from(bounds[0].as_integer()?).ok()?;
+            let last = u8::try_from(bounds[1].as_integer()?).ok()?;
+            (first <= last).then_some((first, last))
+        })
+        .unwrap_or_else(|| {
+            panic!("jiminy.toml: `{key}.range` must be `[first, last]`, two u8 with first <= last")
+        })
+}
+
+/// Panic when an instruction's discriminator is outside the range it's listed
+/// in, inside a range listing other instructions, or outside every range
+pub(crate) fn check(ranges: &[DiscriminatorRange], instructions: &[InstructionMeta]) {
+    if ranges.is_empty() {
+        return;
+    }
+    for range in ranges {
+        for listed in range.instructions.iter().flatten() {
+            if !instructions
+                .iter()
+                .any(|instruction| instruction.name == *listed)
+            {
+                panic!(
+                    "jiminy.toml: `discriminators.{}` lists `{listed}`, which is not an instruction",
+                    range.name
+                );
+            }
+            if let Some(other) = ranges
+                .iter()
+                .find(|other| other.first > range.first && other.lists(listed))
+            {
+                panic!(
+                    "jiminy.toml: `{listed}` is listed in both `discriminators.{}` and `discriminators.{}`",
+                    range.name, other.name
+                );
+            }
+        }
+    }
+
+    for instruction in instructions {
+        let name = &instruction.name;
+        let discriminator = instruction.discriminator;
+        let listed_in = ranges.iter().find(|range| range.lists(name));
+        let inside = ranges.iter().find(|range| range.contains(discriminator));
+        match (listed_in, inside) {
+            (Some(range), _) if !range.contains(discriminator) => panic!(
+                "`{name}` uses discriminator {discriminator}, outside {} which `discriminators.{}` reserves for it",
+                span(range.first, range.last),
+                range.name
+            ),
+            (None, Some(range)) if range.instructions.is_some() => panic!(
+                "`{name}` uses discriminator {discriminator}, inside {} which `discriminators.{}` reserves for other instructions; list it there or renumber it",
+                span(range.first, range.last),
+                range.name
+            ),
+            // The view range has its own check
+            (_, None) if discriminator < VIEW_DISCRIMINATOR_BASE => panic!(
+                "`{name}` uses discriminator {discriminator}, outside every range in jiminy.toml's `discriminators`"
+            ),
+            _ => {}
+        }
+    }
+}
+
+/// Write the report to `out_dir` as `discriminators.json` and `discriminators.md`
+pub(crate) fn run(
+    out_dir: &Path,
+    ranges: &[DiscriminatorRange],
+    instructions: &[InstructionMeta],
+) -> io::Result<()> {
+    let (json, markdown) = render(ranges, instructions);
+    fs::write(out_dir.join("discriminators.json"), json)?;
+    fs::write(out_dir.join("discriminators.md"), markdown)
+}
+
+/// One row of the report
+struct Group<'a> {
+    name: &'a str,
+    first: u8,
+    last: u8,
+    instructions: Option<&'a [String]>,
+    /// `(discriminator, instruction, alias)`, in order
+    used: Vec<(u8, &'a str, bool)>,
+    free: Vec<(u8, u8)>,
+}
+
+/// The report as `(json, markdown)`: per range the values in use and the free
+/// ones, then the values outside every range. Without declared ranges
+/// everything below the view range counts as one `instructions` range.
+pub(crate) fn render(
+    ranges: &[DiscriminatorRange],
+    instructions: &[InstructionMeta],
+) -> (String, String) {
+    let mut owners: [Option<(&str, bool)>; 256] = [None; 256];
+    for instruction in instructions {
+        owners[instruction.discriminator as usize] = Some((&instruction.name, false));
+        for &alias in &instruction.aliases {
+            owners[alias as usize] = Some((&instruction.name, true));
+        }
+    }
+
+    let default = [DiscriminatorRange {
+        name: "instructions".to_string(),
+        first: 0,
+        last: VIEW_DISCRIMINATOR_BASE - 1,
+        instructions: None,
+    }];
+    let views = DiscriminatorRange {
+        name: "views".to_string(),
+        first: VIEW_DISCRIMINATOR_BASE,
+        last: 255,
+        instructions: None,
+    };
+    let declared = if ranges.is_empty() {
+        &default[..]
+    } else {
+        ranges
+    };
+    let groups: Vec<Group> = declared
+        .iter()
+        .chain([&views])
+        .map(|range| {
+            let values = range.first..=range.last;
+            Group {
+                name: &range.name,
+                first: range.first,
+                last: range.last,
+                instructions: range.instructions.as_deref(),
+                used: used(&owners, values.clone()),
+                free: spans(values.filter(|&d| owners[d as usize].is_none())),
+            }
+        })
+        .collect();
+
+    let outside = (0..VIEW_DISCRIMINATOR_BASE).filter(|&d| !declared.iter().any(|r| r.contains(d)));
+    let outside_used = used(&owners, outside.clone());
+    let outside_free = spans(outside.filter(|&d| owners[d as usize].is_none()));
+
+    (
+        json(&groups, &outside_used, &outside_free),
+        markdown(&groups, &outside_used, &outside_free),
+    )
+}
+
+fn used<'a>(
+    owners: &[Option<(&'a str, bool)>; 256],
+    values: impl Iterator<Item = u8>,
+) -> Vec<(u8, &'a str, bool)> {
+    values
+        .filter_map(|d| owners[d as usize].map(|(name, alias)| (d, name, alias)))
+        .collect()
+}
+
+// Consecutive values as `(first, last)` spans
+fn spans(values: impl Iterator<Item = u8>) -> Vec<(u8, u8)> {
+    let mut spans: Vec<(u8, u8)> = Vec::new();
+    for value in values {
+        match spans.last_mut() {
+            Some((_, last)) if last.checked_add(1) == Some(value) => *last = value,
+            _ => spans.push((value, value)),
+        }
+    }
+    spans
+}
+
+// `32..=63`, or `5` for a single value
+fn span(first: u8, last: u8) -> String {
+    if first == last {
+        first.to_string()
+    } else {
+        format!("{first}..={last}")
+    }
+}
+
+fn json(groups: &[Group], outside_used: &[(u8, &str, bool)], outside_free: &[(u8, u8)]) -> String {
+    // One value per line, under a key indented by `indent`
+    let used_json = |used: &[(u8, &str, bool)], indent: &str| {
+        if used.is_empty() {
+            return "[]".to_string();
+        }
+        let items: Vec<String> = used
+            .iter()
+            .map(|(d, name, alias)| {
+                format!(
+                    "{indent}  {{ \"discriminator\": {d}, \"instruction\": {}, \"alias\": {alias} }}",
+                    string(name)
+                )
+            })
+            .collect();
+        format!("[\n{}\n{indent}]", items.join(",\n"))
+    };
+    let free_json = |free: &[(u8, u8)]| {
+        let items: Vec<String> = free
+            .iter()
+            .map(|(first, last)| format!("[{first}, {last}]"))
+            .collect();
+        format!("[{}]", items.join(", "))
+    };
+
+    let mut json = String::from("{\n  \"ranges\": [");
+    for (i, group) in groups.iter().enumerate() {
+        json.push_str(if i == 0 { "\n" } else { ",\n" });
+        json.push_str("    {\n");
+        writeln!(json, "      \"name\": {},", string(group.name)).unwrap();
+        writeln!(json, "      \"first\": {},", group.first).unwrap();
+        writeln!(json, "      \"last\": {},", group.last).unwrap();
+        let instructions = group.instructions.map_or_else(
+            || "null".to_string(),
+            |names| {
+                let names: Vec<String> = names.iter().map(|name| string(name)).collect();
+                format!("[{}]", names.join(", "))
+            },
+        );
+        writeln!(json, "      \"instructions\": {instructions},").unwrap();
+        writeln!(
+            json,
+            "      \"used\": {},",
+            used_json(&group.used, "      ")
+        )
+        .unwrap();
+        writeln!(json, "      \"free\": {}", free_json(&group.free)).unwrap();
+        json.push_str("    }");
+    }
+    json.push_str("\n  ],\n");
+    json.push_str("  \"outside\": {\n");
+    writeln!(json, "    \"used\": {},", used_json(outside_used, "    ")).unwrap();
+    writeln!(json, "    \"free\": {}", free_json(outside_free)).unwrap();
+    json.push_str("  }\n}\n");
+    json
+}
+
+fn markdown(
+    groups: &[Group],
+    outside_used: &[(u8, &str, bool)],
+    outside_free: &[(u8, u8)],
+) -> String {
+    let spans_md = |free: &[(u8, u8)]| {
+        let spans: Vec<String> = free
+            .iter()
+            .map(|&(first, last)| span(first, last))
+            .collect();
+        if spans.is_empty() {
+            "none".to_string()
+        } else {
+            spans.join(", ")
+        }
+    };
+    let used_rows = |md: &mut String, used: &[(u8, &str, bool)]| {
+        md.push_str("| Discriminator | Instruction |\n|---|---|\n");
+        for (d, name, alias) in used {
+            let alias = if *alias { " (deprecated alias)" } else { "" };
+            writeln!(md, "| {d} | {name}{alias} |").unwrap();
+        }
+    };
+
+    let mut md = String::from("# Discriminators\n\n");
+    md.push_str(
+        "Generated by jiminy-build from the instruction sources and jiminy.toml, don't edit.\n\n",
+    );
+    md.push_str("| Range | Values | Used | Free |\n|---|---|---|---|\n");
+    for group in groups {
+        let size = usize::from(group.last - group.first) + 1;
+        writeln!(
+            md,
+            "| {} | {} | {} | {} |",
+            group.name,
+            span(group.first, group.last),
+            group.used.len(),
+            size - group.used.len()
+        )
+        .unwrap();
+    }
+    if !outside_used.is_empty() || !outside_free.is_empty() {
+        writeln!(md, "\nOutside every range: {}", spans_md(outside_free)).unwrap();
+    }
+
+    for group in groups {
+        writeln!(
+            md,
+            "\n## {} ({})\n",
+            group.name,
+            span(group.first, group.last)
+        )
+        .unwrap();
+        if let Some(names) = group.instructions {
+            writeln!(md, "Reserved for {}.\n", names.join(", ")).unwrap();
+        }
+        if !group.used.is_empty() {
+            used_rows(&mut md, &group.used);
+            md.push('\n');
+        }
+        writeln!(md, "Free: {}", spans_md(&group.free)).unwrap();
+    }
+    if !outside_used.is_empty() {
+        md.push_str("\n## Outside every range\n\n");
+        used_rows(&mut md, outside_used);
+    }
+    md
+}
@@ -0,0 +1,472 @@
+//! `JIMINY_FIXTURES=1`: write a JSON account set per instruction to `fixtures/`
+//!
+//! Each file lists what a minimal valid call needs: the accounts in order with
+//! their owner, lamports and data length, the seeds of the PDAs and the mint
+//! and owner of token accounts, so tests in other languages don't have to
+//! derive them again. Anything the sources don't say is written as `null`.
+//! `constraints.json` next to them lists every instruction's account constraints.
+
+use crate::{
+    extract_enum_metadata, extract_state_metadata, snake_case, sorted_entries, AccountMeta,
+    EnumMeta, InstructionMeta, StateMeta,
+};
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+// Lamports given to signers, enough to pay fees and rent
+const SIGNER_LAMPORTS: u64 = 1_000_000_000;
+// SPL token account and mint sizes
+const TOKEN_ACCOUNT_LEN: usize = 165;
+const MINT_LEN: usize = 82;
+
+const KNOWN_ADDRESSES: [(&str, &str); 4] = [
+    ("pinocchio_system::ID", "11111111111111111111111111111111"),
+    ("pinocchio_token::ID", "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9ss623VQ5DA"),
+    (
+        "pinocchio_associated_token_account::ID",
+        "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL",
+    ),
+    (
+        "pinocchio::sysvars::rent::RENT_ID",
+        "SysvarRent111111111111111111111111111111111",
+    ),
+];
+
+/// Write `fixtures/<instruction>.json` under `root`, view instructions are skipped
+pub(crate) fn run(root: &Path, instructions: &[InstructionMeta]) -> io::Result<()> {
+    let dir = root.join("fixtures");
+    fs::create_dir_all(&dir)?;
+    for (file, json) in render(root, instructions) {
+        fs::write(dir.join(file), json)?;
+    }
+    fs::write(
+        dir.join("constraints.json"),
+        crate::constraints::render(root, instructions),
+    )?;
+    println!("cargo:warning=fixtures written to {}", dir.display());
+    Ok(())
+}
+
+/// `(<instruction>.json, fixture)` per instruction, view instructions are skipped
+pub(crate) fn render(root: &Path, instructions: &[InstructionMeta]) -> Vec<(String, String)> {
+    let states = extract_state_metadata(root);
+    let enums = extract_enum_metadata(root);
+    let context = Context {
+        states: &states,
+        enums: &enums,
+        seeds: extract_seed_constants(&root.join("src")),
+        headroom: extract_headroom(&root.join("src")),
+    };
+    instructions
+        .iter()
+        .filter(|i| i.view_of.is_none())
+        .map(|instruction| {
+            (
+                format!("{}.json", snake_case(&instruction.name)),
+                fixture(instruction, &context),
+            )
+        })
+        .collect()
+}
+
+/// Byte size of a state struct's data, `None` when a field type is unknown
+pub(crate) fn state_size(
+    states: &[StateMeta],
+    enums: &[EnumMeta],
+    state: &StateMeta,
+) -> Option<usize> {
+    let context = Context {
+        states,
+        enums,
+        seeds: Vec::new(),
+        headroom: Vec::new(),
+    };
+    context.state_size(state)
+}
+
+/// Offset of each field of a state struct, `None` when a field type is unknown
+pub(crate) fn field_offsets(
+    states: &[StateMeta],
+    enums: &[EnumMeta],
+    state: &StateMeta,
+) -> Option<Vec<usize>> {
+    let context = Context {
+        states,
+        enums,
+        seeds: Vec::new(),
+        headroom: Vec::new(),
+    };
+    let mut offset = 0;
+    state
+        .fields
+        .iter()
+        .map(|field| {
+            let start = offset;
+            offset += context.type_size(&field.field_type)?;
+            Some(start)
+        })
+        .collect()
+}
+
+struct Context<'a> {
+    states: &'a [StateMeta],
+    enums: &'a [EnumMeta],
+    /// `(name, text)` of `const NAME: &[u8; N] = b"text";`
+    seeds: Vec<(String, String)>,
+    /// `(state, extra bytes)` from `space_with_headroom!`
+    headroom: Vec<(String, usize)>,
+}
+
+impl Context<'_> {
+    // Byte size of a field type, assuming the unpadded byte-array layouts `define_state!` uses
+    fn type_size(&self, ty: &str) -> Option<usize> {
+        let ty = ty.trim();
+        if let Some(array) = ty.strip_prefix('[').and_then(|t| t.strip_suffix(']')) {
+            let (item, len) = array.rsplit_once(';')?;
+            return Some(self.type_size(item)? * len.trim().parse::<usize>().ok()?);
+        }
+        if let Some(len) = ty.strip_prefix("FixedStr<").and_then(|t| t.strip_suffix('>')) {
+            return len.trim().parse().ok();
+        }
+        // `jiminy::PodOption<T>`, a tag byte then the value at full size
+        if let Some(inner) = crate::pod_option_inner(ty) {
+            return Some(1 + self.type_size(inner)?);
+        }
+        let name = crate::last_segment(ty);
+        if self.enums.iter().any(|e| e.name == name) {
+            return Some(1);
+        }
+        if let Some(state) = self.states.iter().find(|s| s.name == name) {
+            return self.state_size(state);
+        }
+        match name {
+            "u8" | "i8" | "bool" | "PodBool" => Some(1),
+            "u16" | "i16" | "LeU16" => Some(2),
+            "u32" | "i32" | "LeU32" => Some(4),
+            "u64" | "i64" | "LeU64" => Some(8),
+            "u128" | "i128" => Some(16),
+            "Pubkey" | "PubkeyBytes" => Some(32),
+            // `jiminy::time::Deadline`, a kind byte and a u64
+            "Deadline" => Some(9),
+            _ => None,
+        }
+    }
+
+    fn state_size(&self, state: &StateMeta) -> Option<usize> {
+        state.fields.iter().map(|f| self.type_size(&f.field_type)).sum()
+    }
+
+    // What accounts of `state` are created with, its size plus any headroom
+    fn allocated_size(&self, state: &StateMeta) -> Option<usize> {
+        Some(self.state_size(state)? + headroom(&self.headroom, &state.name).unwrap_or(0))
+    }
+
+    fn state(&self, name: &str) -> Option<&StateMeta> {
+        self.states.iter().find(|s| s.name == name)
+    }
+}
+
+// Rent-exempt minimum at the default rent, `(128 + len) * 3480 * 2`
+fn rent_exempt(len: usize) -> u64 {
+    (128 + len as u64) * 3480 * 2
+}
+
+fn fixture(instruction: &InstructionMeta, context: &Context) -> String {
+    let mut json = String::new();
+    writeln!(json, "{{").unwrap();
+    writeln!(json, "  \"instruction\": {},", string(&instruction.name)).unwrap();
+    writeln!(json, "  \"discriminator\": {},", instruction.discriminator).unwrap();
+
+    writeln!(json, "  \"accounts\": [").unwrap();
+    let accounts: Vec<String> = instruction
+        .accounts
+        .iter()
+        .map(|account| account_fixture(instruction, account, context))
+        .collect();
+    writeln!(json, "{}", accounts.join(",\n")).unwrap();
+    writeln!(json, "  ],").unwrap();
+    if let Some((name, desc)) = &instruction.remaining_accounts {
+        writeln!(
+            json,
+            "  \"remaining_accounts\": {{ \"name\": {}, \"desc\": {} }},",
+            string(name),
+            string(desc)
+        )
+        .unwrap();
+    }
+
+    writeln!(json, "  \"data\": [").unwrap();
+    let fields: Vec<String> = instruction
+        .fields
+        .iter()
+        .map(|field| {
+            format!(
+                "    {{ \"name\": {}, \"type\": {}, \"size\": {} }}",
+                string(&field.name),
+                string(&field.field_type),
+                number(context.type_size(&field.wire_type))
+            )
+        })
+        .collect();
+    if !fields.is_empty() {
+        writeln!(json, "{}", fields.join(",\n")).unwrap();
+    }
+    writeln!(json, "  ],").unwrap();
+    writeln!(json, "  \"rest\": {}", optional(instruction.rest.as_deref())).unwrap();
+    writeln!(json, "}}").unwrap();
+    json
+}
+
+fn account_fixture(
+    instruction: &InstructionMeta,
+    account: &AccountMeta,
+    context: &Context,
+) -> String {
+    let uses = &instruction.state_uses;
+    let name = account.name.as_str();
+    let signer = account.attrs.iter().any(|a| a == "signer");
+    let writable = account.attrs.iter().any(|a| a == "writable");
+    let state = uses
+        .loaded
+        .iter()
+        .chain(&uses.created)
+        .find(|(loaded, _)| loaded == name)
+        .and_then(|(_, state)| context.state(state));
+    let pda = uses.pdas.iter().find(|(pda, _)| pda == name);
+    let token_owner = uses.token_owners.iter().find(|(token, _)| token == name);
+
+    let mut fields = vec![
+        format!("\"name\": {}", string(name)),
+        format!("\"signer\": {signer}"),
+        format!("\"writable\": {writable}"),
+    ];
+    // Written after the owner
+    let mut extra = Vec::new();
+    let (owner, lamports, data_len) = match account.account_type.as_str() {
+        _ if signer => ("\"system\"".to_string(), Some(SIGNER_LAMPORTS), Some(0)),
+        "uninitialized" => ("\"system\"".to_string(), Some(0), Some(0)),
+        "address" => {
+            let key = account.type_arg.as_deref().map(address);
+            extra.push(format!("\"address\": {}", optional(key)));
+            ("null".to_string(), None, None)
+        }
+        // The same account passed twice, its fixture is the other one's
+        "same_as" => {
+            extra.push(format!("\"same_as\": {}", optional(account.type_arg.as_deref())));
+            ("null".to_string(), None, None)
+        }
+        // `key_in(Platform::vault of platform)`, the key stored in another account's state
+        "key_in" => {
+            let (field, of) = account
+                .type_arg
+                .as_deref()
+                .and_then(|arg| arg.split_once(" of "))
+                .unzip();
+            let (state, field) = field.and_then(|f| f.split_once("::")).unzip();
+            extra.push(format!(
+                "\"key_in\": {{ \"state\": {}, \"field\": {}, \"account\": {} }}",
+                optional(state.map(str::trim)),
+                optional(field.map(str::trim)),
+                optional(of.map(str::trim))
+            ));
+            // A system-owned vault when the instruction also derives it
+            if pda.is_some() {
+                ("\"system\"".to_string(), None, Some(0))
+            } else {
+                ("null".to_string(), None, None)
+            }
+        }
+        "token" if is_mint(name) => ("\"token_program\"".to_string(), None, Some(MINT_LEN)),
+        // Checked against an owner, so a token account even when declared `any`
+        "token" | "any" if account.account_type == "token" || token_owner.is_some() => {
+            let mint = instruction.accounts.iter().find(|a| is_mint(&a.name));
+            extra.push(format!(
+                "\"token_account\": {{ \"mint\": {}, \"owner\": {} }}",
+                optional(mint.map(|a| a.name.as_str())),
+                optional(token_owner.map(|(_, owner)| owner.as_str()))
+            ));
+            ("\"token_program\"".to_string(), None, Some(TOKEN_ACCOUNT_LEN))
+        }
+        // Accounts loaded as state belong to the program
+        "program" | "any" if state.is_some() => {
+            ("\"program\"".to_string(), None, state.and_then(|s| context.allocated_size(s)))
+        }
+        "program" => ("\"program\"".to_string(), None, None),
+        // A PDA that isn't loaded as state is a system-owned vault
+        _ if pda.is_some() => ("\"system\"".to_string(), None, Some(0)),
+        _ => ("null".to_string(), None, None),
+    };
+    fields.push(format!("\"owner\": {owner}"));
+    fields.extend(extra);
+    if let Some(state) = state {
+        // Uninitialized accounts get the state the instruction creates
+        let key = if account.account_type == "uninitialized" { "creates" } else { "state" };
+        fields.push(format!("\"{key}\": {}", string(&state.name)));
+    }
+    let lamports = lamports.or_else(|| data_len.map(rent_exempt));
+    fields.push(format!("\"lamports\": {}", number(lamports)));
+    fields.push(format!("\"data_len\": {}", number(data_len)));
+    if let Some((_, seeds)) = pda {
+        let seeds: Vec<String> = crate::top_level(seeds, ',')
+            .map(|seed| seed_fixture(seed.trim(), instruction, &context.seeds))
+            .collect();
+        fields.push(format!("\"pda\": {{ \"seeds\": [{}] }}", seeds.join(", ")));
+    }
+
+    format!("    {{\n      {}\n    }}", fields.join(",\n      "))
+}
+
+// `PLATFORM_SEED`, `vote.key().as_ref()` or `wallet.as_ref()`, anything else as written
+pub(crate) fn seed_fixture(
+    seed: &str,
+    instruction: &InstructionMeta,
+    seeds: &[(String, String)],
+) -> String {
+    if let Some((_, text)) = seeds.iter().find(|(name, _)| name == seed) {
+        return format!("{{ \"const\": {} }}", string(text));
+    }
+    let base = seed.trim_end_matches(".as_ref()");
+    if let Some(account) = base.strip_suffix(".key()") {
+        if instruction.accounts.iter().any(|a| a.name == account) {
+            return format!("{{ \"account\": {} }}", string(account));
+        }
+    }
+    if instruction.fields.iter().any(|f| f.name == base) {
+        return format!("{{ \"arg\": {} }}", string(base));
+    }
+    format!("{{ \"expr\": {} }}", string(seed))
+}
+
+// The account holding the mint the instruction's token accounts are in
+fn is_mint(name: &str) -> bool {
+    matches!(name, "mint" | "token") || name.ends_with("_mint")
+}
+
+pub(crate) fn address(key: &str) -> &str {
+    KNOWN_ADDRESSES
+        .iter()
+        .find(|(path, _)| *path == key)
+        .map_or(key, |(_, address)| address)
+}
+
+/// `(state, extra bytes)` of every `space_with_headroom!(State, extra)` under `dir`
+///
+/// The extra bytes must be a literal or a `const NAME: usize = <literal>;`
+/// somewhere under `dir`, other expressions are skipped.
+pub(crate) fn extract_headroom(dir: &Path) -> Vec<(String, usize)> {
+    let sources = read_sources(dir);
+    let mut consts = Vec::new();
+    for line in sources.iter().flat_map(|content| content.lines()) {
+        let line = line.trim().trim_start_matches("pub ");
+        let Some((name, rest)) = line.strip_prefix("const ").and_then(|l| l.split_once(':'))
+        else {
+            continue;
+        };
+        if let Some(value) = rest.trim().strip_prefix("usize =").and_then(|v| v.strip_suffix(';')) {
+            if let Some(value) = literal_usize(value) {
+                consts.push((name.trim().to_string(), value));
+            }
+        }
+    }
+
+    let mut headroom = Vec::new();
+    for content in &sources {
+        for (start, _) in content.match_indices("space_with_headroom!(") {
+            let args = crate::delimited(&content[start + "space_with_headroom!(".len()..]);
+            let Some((state, extra)) = args.split_once(',') else {
+                continue;
+            };
+            let extra = extra.trim();
+            let extra = literal_usize(extra).or_else(|| {
+                let name = crate::last_segment(extra);
+                consts.iter().find(|(c, _)| c == name).map(|(_, value)| *value)
+            });
+            let state = crate::last_segment(state).to_string();
+            if let Some(extra) = extra {
+                if !headroom.contains(&(state.clone(), extra)) {
+                    headroom.push((state, extra));
+                }
+            }
+        }
+    }
+    headroom
+}
+
+/// Headroom `state` is allocated with, the largest one found when there are several
+pub(crate) fn headroom(headroom: &[(String, usize)], state: &str) -> Option<usize> {
+    headroom
+        .iter()
+        .filter(|(name, _)| name == state)
+        .map(|(_, extra)| *extra)
+        .max()
+}
+
+// `96`, `1_024` or `96usize`
+fn literal_usize(text: &str) -> Option<usize> {
+    let text = text.trim().trim_end_matches("usize").trim_end_matches('_');
+    text.replace('_', "").parse().ok()
+}
+
+fn read_sources(dir: &Path) -> Vec<String> {
+    let mut sources = Vec::new();
+    for path in sorted_entries(dir) {
+        if path.is_dir() {
+            sources.extend(read_sources(&path));
+        } else if let Ok(content) = fs::read_to_string(&path) {
+            sources.push(content);
+        }
+    }
+    sources
+}
+
+// `pub const PLATFORM_SEED: &[u8; 6] = b"config";` anywhere under `src`
+pub(crate) fn extract_seed_constants(dir: &Path) -> Vec<(String, String)> {
+    let mut seeds = Vec::new();
+    for path in sorted_entries(dir) {
+        if path.is_dir() {
+            seeds.extend(extract_seed_constants(&path));
+            continue;
+        }
+        let Ok(content) = fs::read_to_string(&path) else {
+            continue;
+        };
+        for line in content.lines() {
+            let line = line.trim().trim_start_matches("pub ");
+            let Some((name, rest)) = line.strip_prefix("const ").and_then(|l| l.split_once(':'))
+            else {
+                continue;
+            };
+            if let Some((_, text)) = rest.split_once("= b\"") {
+                if let Some(text) = text.strip_suffix("\";") {
+                    seeds.push((name.trim().to_string(), text.to_string()));
+                }
+            }
+        }
+    }
+    seeds
+}
+
+pub(crate) fn string(text: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if (c as u32) < 0x20 => write!(quoted, "\\u{:04x}", c as u32).unwrap(),
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+fn optional(text: Option<&str>) -> String {
+    text.map_or_else(|| "null".to_string(), string)
+}
+
+fn number<T: ToString>(value: Option<T>) -> String {
+    value.map_or_else(|| "null".to_string(), |v| v.to_string())
+}
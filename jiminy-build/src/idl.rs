@@ -0,0 +1,300 @@
+//! `JIMINY_IDL=1`: run `shank idl` on the program and write its IDL to the IDL directory
+
+use crate::constants::resolve_program_id;
+use crate::fixtures::{self, string};
+use crate::{
+    extract_enum_metadata, extract_event_metadata, extract_state_metadata, InstructionMeta,
+};
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Write `<dir>/<crate name>.json` from the crate at `root`, with `constraints`
+/// added as its `accountConstraints`, the instructions' `expiry` slot fields
+/// as its `instructionExpiry`, their `deprecated` cutoffs as its
+/// `instructionDeprecation`, the state sizes as its `accountSizes`, the
+/// `define_events!` structs as its `events`, the accounts' labels and
+/// explorer hints as its `accountMetadata` and the `load_foreign!` reads as its
+/// `foreignAccounts`
+///
+/// Runs after `src/generated.rs` is written, since that's what shank reads.
+/// Failures are reported as warnings, the normal build goes on. Returns the
+/// path written to, if it was.
+pub(crate) fn run(
+    root: &Path,
+    dir: &Path,
+    constraints: &str,
+    instructions: &[InstructionMeta],
+) -> Option<PathBuf> {
+    if let Err(e) = fs::create_dir_all(dir) {
+        warn(&format!("cannot create {}: {e}", dir.display()));
+        return None;
+    }
+    let status = Command::new("shank")
+        .arg("idl")
+        .arg("--crate-root")
+        .arg(root)
+        .arg("--out-dir")
+        .arg(dir)
+        .status();
+    match status {
+        Ok(status) if status.success() => {
+            let name = env::var("CARGO_PKG_NAME").unwrap();
+            let path = dir.join(format!("{name}.json"));
+            match add_keys(&path, &keys(root, constraints, instructions)) {
+                Ok(()) => {
+                    println!("cargo:warning=IDL written to {}", dir.display());
+                    return Some(path);
+                }
+                Err(e) => warn(&format!(
+                    "cannot add constraints, expiry, deprecation, sizes, events, account \
+                     metadata and foreign accounts to {}: {e}",
+                    path.display()
+                )),
+            }
+        }
+        Ok(status) => warn(&format!("shank idl failed ({status})")),
+        Err(e) => warn(&format!("cannot run shank: {e}")),
+    }
+    None
+}
+
+/// The keys [`run`] adds, as a JSON object of their own
+pub(crate) fn render(root: &Path, constraints: &str, instructions: &[InstructionMeta]) -> String {
+    let entries: Vec<String> = keys(root, constraints, instructions)
+        .iter()
+        .map(|(key, value)| entry(key, value))
+        .collect();
+    format!("{{{}\n}}\n", entries.join(","))
+}
+
+fn keys(
+    root: &Path,
+    constraints: &str,
+    instructions: &[InstructionMeta],
+) -> [(&'static str, String); 7] {
+    [
+        ("accountConstraints", constraints.to_string()),
+        ("instructionExpiry", expiry(instructions)),
+        ("instructionDeprecation", deprecation(instructions)),
+        ("accountSizes", account_sizes(root)),
+        ("events", events(root)),
+        ("accountMetadata", account_metadata(instructions)),
+        ("foreignAccounts", foreign_accounts(root, instructions)),
+    ]
+}
+
+// `\n  "key": value`, the value indented to sit inside the IDL's object
+fn entry(key: &str, value: &str) -> String {
+    let value = value.trim_end().replace('\n', "\n  ");
+    format!("\n  \"{key}\": {value}")
+}
+
+// `[{ "instruction": "UpdatePlatform", "accounts": [{ "name": "platform", "label":
+// "Platform Config", "explorerHint": "pda" }] }]` for every account, the label
+// falling back to the desc and the hint to `null` when the account has none
+fn account_metadata(instructions: &[InstructionMeta]) -> String {
+    let rows: Vec<String> = instructions
+        .iter()
+        .map(|instruction| {
+            let accounts: Vec<String> = instruction
+                .accounts
+                .iter()
+                .map(|account| {
+                    let label = account.label.as_deref().unwrap_or(&account.desc);
+                    let hint = account
+                        .explorer_hint
+                        .as_deref()
+                        .map_or_else(|| "null".to_string(), string);
+                    format!(
+                        "      {{ \"name\": {}, \"label\": {}, \"explorerHint\": {hint} }}",
+                        string(&account.name),
+                        string(label)
+                    )
+                })
+                .collect();
+            format!(
+                "  {{\n    \"instruction\": {},\n    \"accounts\": [\n{}\n    ]\n  }}",
+                string(&instruction.name),
+                accounts.join(",\n")
+            )
+        })
+        .collect();
+    format!("[\n{}\n]", rows.join(",\n"))
+}
+
+// `[{ "instruction": "PassGate", "account": "counter", "type": "Counter", "owner":
+// "counter::ID", "programId": "Cntr..." }]`, the other programs' accounts each
+// instruction reads, `programId` being `null` when the owner can't be resolved
+fn foreign_accounts(root: &Path, instructions: &[InstructionMeta]) -> String {
+    let rows: Vec<String> = instructions
+        .iter()
+        .flat_map(|instruction| {
+            instruction.state_uses.foreign.iter().map(|(account, state, owner)| {
+                let program_id = resolve_program_id(root, owner)
+                    .map_or_else(|| "null".to_string(), |id| string(&id));
+                format!(
+                    "  {{ \"instruction\": {}, \"account\": {}, \"type\": {}, \"owner\": {}, \"programId\": {program_id} }}",
+                    string(&instruction.name),
+                    string(account),
+                    string(state),
+                    string(owner)
+                )
+            })
+        })
+        .collect();
+    if rows.is_empty() {
+        return "[]".to_string();
+    }
+    format!("[\n{}\n]", rows.join(",\n"))
+}
+
+// `[{ "instruction": "RedeemFeeVoucher", "slotField": "expires_at_slot" }]`, the
+// data field clients set to the last slot the instruction may land in
+fn expiry(instructions: &[InstructionMeta]) -> String {
+    let rows: Vec<String> = instructions
+        .iter()
+        .filter_map(|instruction| {
+            let field = instruction.expiry.as_ref()?;
+            Some(format!(
+                "  {{ \"instruction\": {}, \"slotField\": {} }}",
+                string(&instruction.name),
+                string(field)
+            ))
+        })
+        .collect();
+    if rows.is_empty() {
+        return "[]".to_string();
+    }
+    format!("[\n{}\n]", rows.join(",\n"))
+}
+
+// `[{ "instruction": "InitializePosition", "afterSlot": 350000000 }]`, the last slot
+// each deprecated instruction runs in, a string when it isn't a literal
+fn deprecation(instructions: &[InstructionMeta]) -> String {
+    let rows: Vec<String> = instructions
+        .iter()
+        .filter_map(|instruction| {
+            let slot = instruction.deprecated_after_slot.as_ref()?;
+            let slot = match slot.replace('_', "").parse::<u64>() {
+                Ok(slot) => slot.to_string(),
+                Err(_) => string(slot),
+            };
+            Some(format!(
+                "  {{ \"instruction\": {}, \"afterSlot\": {slot} }}",
+                string(&instruction.name)
+            ))
+        })
+        .collect();
+    if rows.is_empty() {
+        return "[]".to_string();
+    }
+    format!("[\n{}\n]", rows.join(",\n"))
+}
+
+// `[{ "account": "Counter", "size": 57, "allocated": 153 }]`, `allocated` adding the
+// `space_with_headroom!` headroom the program creates the account with
+fn account_sizes(root: &Path) -> String {
+    let states = extract_state_metadata(root);
+    let enums = extract_enum_metadata(root);
+    let headroom = fixtures::extract_headroom(&root.join("src"));
+    let rows: Vec<String> = states
+        .iter()
+        .filter_map(|state| {
+            let size = fixtures::state_size(&states, &enums, state)?;
+            let extra = fixtures::headroom(&headroom, &state.name).unwrap_or(0);
+            Some(format!(
+                "  {{ \"account\": {}, \"size\": {size}, \"allocated\": {} }}",
+                string(&state.name),
+                size + extra
+            ))
+        })
+        .collect();
+    if rows.is_empty() {
+        return "[]".to_string();
+    }
+    format!("[\n{}\n]", rows.join(",\n"))
+}
+
+// `[{ "name": "VoteCreated", "fields": [{ "name": "vote", "type": "publicKey" }] }]`,
+// in the order the program defines them
+fn events(root: &Path) -> String {
+    let rows: Vec<String> = extract_event_metadata(root)
+        .iter()
+        .map(|event| {
+            let fields: Vec<String> = event
+                .fields
+                .iter()
+                .map(|field| {
+                    format!(
+                        "{{ \"name\": {}, \"type\": {} }}",
+                        string(&field.name),
+                        idl_type(&field.field_type)
+                    )
+                })
+                .collect();
+            format!(
+                "  {{ \"name\": {}, \"fields\": [{}] }}",
+                string(&event.name),
+                fields.join(", ")
+            )
+        })
+        .collect();
+    if rows.is_empty() {
+        return "[]".to_string();
+    }
+    format!("[\n{}\n]", rows.join(",\n"))
+}
+
+// A field type the way the generated shank structs show state fields
+fn idl_type(field_type: &str) -> String {
+    let ty = field_type.trim();
+    if let Some((item, len)) = ty
+        .strip_prefix('[')
+        .and_then(|t| t.strip_suffix(']'))
+        .and_then(|t| t.rsplit_once(';'))
+    {
+        return format!("{{ \"array\": [{}, {}] }}", idl_type(item), len.trim());
+    }
+    if crate::fixed_str_capacity(ty).is_some() {
+        return string("string");
+    }
+    if let Some(inner) = crate::pod_option_inner(ty) {
+        return format!("{{ \"option\": {} }}", idl_type(inner));
+    }
+    if let Some(int) = crate::le_int(ty) {
+        return string(int);
+    }
+    match ty.rsplit("::").next().unwrap_or(ty) {
+        "PubkeyBytes" | "Pubkey" => string("publicKey"),
+        "PodBool" | "bool" => string("bool"),
+        // A kind byte then the little-endian value
+        "Deadline" => idl_type("[u8; 9]"),
+        int @ ("u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" | "u128" | "i128") => {
+            string(int)
+        }
+        other => format!("{{ \"defined\": {} }}", string(other)),
+    }
+}
+
+// Shank's IDL is one JSON object, so the extra keys go in last
+fn add_keys(path: &Path, keys: &[(&str, String)]) -> io::Result<()> {
+    let idl = fs::read_to_string(path)?;
+    let mut body = idl
+        .trim_end()
+        .strip_suffix('}')
+        .ok_or_else(|| io::Error::other("not a JSON object"))?
+        .trim_end()
+        .to_string();
+    for (key, value) in keys {
+        body.push(',');
+        body.push_str(&entry(key, value));
+    }
+    fs::write(path, format!("{body}\n}}\n"))
+}
+
+fn warn(message: &str) {
+    println!("cargo:warning=IDL skipped: {message}");
+}
@@ -0,0 +1,590 @@
+//! Compare a previously published shank IDL with a new one
+//!
+//! Instructions are matched by name, then their discriminator, accounts and
+//! args compared in order; state accounts by name, with each field's offset
+//! and size; errors by name, with their code. Whatever an already deployed
+//! client would send or decode differently is breaking, the rest additive.
+//! State fields are laid out back to back, like jiminy's all-`Pod`, align-1
+//! state, so a field's offset is the sum of the sizes before it.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// One difference between two IDLs
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IdlChange {
+    /// Whether clients built against the old IDL stop working
+    pub breaking: bool,
+    pub message: String,
+}
+
+impl fmt::Display for IdlChange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.breaking {
+            "breaking"
+        } else {
+            "additive"
+        };
+        write!(f, "{kind}: {}", self.message)
+    }
+}
+
+/// Every difference between two IDLs, in the order instructions, accounts, errors
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct IdlDiff {
+    pub changes: Vec<IdlChange>,
+}
+
+impl IdlDiff {
+    /// Whether any change is breaking
+    pub fn is_breaking(&self) -> bool {
+        self.changes.iter().any(|change| change.breaking)
+    }
+
+    pub fn breaking(&self) -> impl Iterator<Item = &IdlChange> {
+        self.changes.iter().filter(|change| change.breaking)
+    }
+
+    fn push(&mut self, breaking: bool, message: String) {
+        self.changes.push(IdlChange { breaking, message });
+    }
+}
+
+/// Compare two IDLs given as JSON text, failing if either isn't a JSON object
+pub(crate) fn diff(old: &str, new: &str) -> Result<IdlDiff, String> {
+    let old = Json::parse(old).map_err(|e| format!("old IDL: {e}"))?;
+    let new = Json::parse(new).map_err(|e| format!("new IDL: {e}"))?;
+    let mut diff = IdlDiff::default();
+    diff_instructions(&mut diff, &old, &new);
+    diff_accounts(&mut diff, &old, &new);
+    diff_errors(&mut diff, &old, &new);
+    Ok(diff)
+}
+
+/// Diff the IDL at `previous` against the one just written to `current`
+///
+/// Each change is printed as a cargo warning; with `strict` a breaking change
+/// fails the build. An unreadable IDL only warns.
+pub(crate) fn check(previous: &Path, current: &Path, strict: bool) {
+    let read = |path: &Path| {
+        fs::read_to_string(path).map_err(|e| format!("cannot read {}: {e}", path.display()))
+    };
+    let diff = match read(previous).and_then(|old| diff(&old, &read(current)?)) {
+        Ok(diff) => diff,
+        Err(e) => return println!("cargo:warning=IDL diff skipped: {e}"),
+    };
+    for change in &diff.changes {
+        println!("cargo:warning=IDL {change}");
+    }
+    if strict && diff.is_breaking() {
+        let breaking: Vec<String> = diff.breaking().map(|c| c.message.clone()).collect();
+        panic!(
+            "breaking IDL changes against {}:\n  {}",
+            previous.display(),
+            breaking.join("\n  ")
+        );
+    }
+}
+
+fn diff_instructions(diff: &mut IdlDiff, old: &Json, new: &Json) {
+    let (old, new) = (old.list("instructions"), new.list("instructions"));
+    for before in old {
+        let name = before.str("name");
+        let Some(after) = new.iter().find(|i| i.str("name") == name) else {
+            diff.push(true, format!("instruction {name} removed"));
+            continue;
+        };
+        let discriminator = |i: &Json| i.get("discriminant").map(|d| d.str("value").to_string());
+        if let (Some(was), Some(is)) = (discriminator(before), discriminator(after)) {
+            if was != is {
+                diff.push(
+                    true,
+                    format!("instruction {name}: discriminator {was} is now {is}"),
+                );
+            }
+        }
+        diff_instruction_accounts(diff, name, before.list("accounts"), after.list("accounts"));
+        diff_args(diff, name, before.list("args"), after.list("args"));
+    }
+    for after in new {
+        let name = after.str("name");
+        if !old.iter().any(|i| i.str("name") == name) {
+            diff.push(false, format!("instruction {name} added"));
+        }
+    }
+}
+
+// Accounts are passed by position, so any move, insertion or removal is breaking
+fn diff_instruction_accounts(diff: &mut IdlDiff, instruction: &str, old: &[Json], new: &[Json]) {
+    let names = |accounts: &[Json]| -> Vec<String> {
+        accounts.iter().map(|a| a.str("name").to_string()).collect()
+    };
+    let (old_names, new_names) = (names(old), names(new));
+    for name in &old_names {
+        if !new_names.contains(name) {
+            diff.push(
+                true,
+                format!("instruction {instruction}: account {name} removed"),
+            );
+        }
+    }
+    for (index, name) in new_names.iter().enumerate() {
+        if !old_names.contains(name) {
+            diff.push(
+                true,
+                format!("instruction {instruction}: account {name} added at {index}"),
+            );
+        }
+    }
+    let kept_old: Vec<&String> = old_names.iter().filter(|n| new_names.contains(n)).collect();
+    let kept_new: Vec<&String> = new_names.iter().filter(|n| old_names.contains(n)).collect();
+    if kept_old != kept_new {
+        diff.push(
+            true,
+            format!("instruction {instruction}: accounts reordered"),
+        );
+    }
+
+    for before in old {
+        let name = before.str("name");
+        let Some(after) = new.iter().find(|a| a.str("name") == name) else {
+            continue;
+        };
+        for (flag, what) in [("isSigner", "a signer"), ("isMut", "writable")] {
+            match (before.bool(flag), after.bool(flag)) {
+                (false, true) => diff.push(
+                    true,
+                    format!("instruction {instruction}: account {name} must now be {what}"),
+                ),
+                (true, false) => diff.push(
+                    false,
+                    format!(
+                        "instruction {instruction}: account {name} no longer needs to be {what}"
+                    ),
+                ),
+                _ => {}
+            }
+        }
+    }
+}
+
+// Args are encoded back to back, so only a rename at the same position keeps the bytes
+fn diff_args(diff: &mut IdlDiff, instruction: &str, old: &[Json], new: &[Json]) {
+    for (index, (before, after)) in old.iter().zip(new).enumerate() {
+        let (was, is) = (before.str("name"), after.str("name"));
+        let (was_type, is_type) = (type_name(before.get("type")), type_name(after.get("type")));
+        if was_type != is_type {
+            let change = format!("{was}: {was_type} is now {is}: {is_type}");
+            diff.push(
+                true,
+                format!("instruction {instruction}: arg {index} {change}"),
+            );
+        } else if was != is {
+            diff.push(
+                false,
+                format!("instruction {instruction}: arg {index} {was} renamed to {is}"),
+            );
+        }
+    }
+    for before in old.iter().skip(new.len()) {
+        diff.push(
+            true,
+            format!(
+                "instruction {instruction}: arg {} removed",
+                before.str("name")
+            ),
+        );
+    }
+    for after in new.iter().skip(old.len()) {
+        diff.push(
+            true,
+            format!("instruction {instruction}: arg {} added", after.str("name")),
+        );
+    }
+}
+
+fn diff_accounts(diff: &mut IdlDiff, old: &Json, new: &Json) {
+    let (old_types, new_types) = (old.list("types"), new.list("types"));
+    let (old, new) = (old.list("accounts"), new.list("accounts"));
+    for before in old {
+        let name = before.str("name");
+        let Some(after) = new.iter().find(|a| a.str("name") == name) else {
+            diff.push(true, format!("account {name} removed"));
+            continue;
+        };
+        let was = layout(before, old_types);
+        let is = layout(after, new_types);
+        for field in &was {
+            match is.iter().find(|f| f.name == field.name) {
+                None => diff.push(
+                    true,
+                    format!("account {name}: field {} removed", field.name),
+                ),
+                Some(now) if now.offset != field.offset || now.size != field.size => diff.push(
+                    true,
+                    format!(
+                        "account {name}: field {} changed from {} to {}",
+                        field.name,
+                        field.describe(),
+                        now.describe()
+                    ),
+                ),
+                Some(now) if now.type_name != field.type_name => diff.push(
+                    true,
+                    format!(
+                        "account {name}: field {} is now {}, was {}",
+                        field.name, now.type_name, field.type_name
+                    ),
+                ),
+                Some(_) => {}
+            }
+        }
+        // Offsets are unknown past a variable-size field, so only a field after every
+        // old one leaves the old bytes where clients read them
+        let end = was.iter().filter_map(|f| Some(f.offset? + f.size?)).max();
+        for field in is.iter().filter(|f| !was.iter().any(|w| w.name == f.name)) {
+            let appended =
+                matches!((field.offset, end), (Some(offset), Some(end)) if offset >= end);
+            diff.push(
+                !appended,
+                format!(
+                    "account {name}: field {} added at {}",
+                    field.name,
+                    field.describe()
+                ),
+            );
+        }
+    }
+    for after in new {
+        let name = after.str("name");
+        if !old.iter().any(|a| a.str("name") == name) {
+            diff.push(false, format!("account {name} added"));
+        }
+    }
+}
+
+fn diff_errors(diff: &mut IdlDiff, old: &Json, new: &Json) {
+    let (old, new) = (old.list("errors"), new.list("errors"));
+    for before in old {
+        let name = before.str("name");
+        match new.iter().find(|e| e.str("name") == name) {
+            None => diff.push(
+                true,
+                format!("error {name} ({}) removed", before.str("code")),
+            ),
+            Some(after) if after.str("code") != before.str("code") => diff.push(
+                true,
+                format!(
+                    "error {name} renumbered from {} to {}",
+                    before.str("code"),
+                    after.str("code")
+                ),
+            ),
+            Some(after) if after.str("msg") != before.str("msg") => {
+                diff.push(false, format!("error {name}: message changed"))
+            }
+            Some(_) => {}
+        }
+    }
+    for after in new {
+        let name = after.str("name");
+        if !old.iter().any(|e| e.str("name") == name) {
+            diff.push(false, format!("error {name} ({}) added", after.str("code")));
+        }
+    }
+}
+
+struct Field {
+    name: String,
+    type_name: String,
+    /// `None` after a field whose size depends on its value
+    offset: Option<usize>,
+    size: Option<usize>,
+}
+
+impl Field {
+    fn describe(&self) -> String {
+        match (self.offset, self.size) {
+            (Some(offset), Some(1)) => format!("offset {offset} (1 byte)"),
+            (Some(offset), Some(size)) => format!("offset {offset} ({size} bytes)"),
+            (Some(offset), None) => format!("offset {offset}"),
+            _ => "an unknown offset".to_string(),
+        }
+    }
+}
+
+// The fields of a `{ "kind": "struct" }` account with their offsets and sizes
+fn layout(account: &Json, types: &[Json]) -> Vec<Field> {
+    let fields = account.get("type").map_or(&[][..], |t| t.list("fields"));
+    let mut offset = Some(0);
+    fields
+        .iter()
+        .map(|field| {
+            let size = size_of(field.get("type"), types);
+            let laid_out = Field {
+                name: field.str("name").to_string(),
+                type_name: type_name(field.get("type")),
+                offset,
+                size,
+            };
+            offset = offset.zip(size).map(|(offset, size)| offset + size);
+            laid_out
+        })
+        .collect()
+}
+
+// Bytes of a fixed-size type, `None` for strings, vecs and unknown types. Options are
+// jiminy's `PodOption`, a tag byte then the value whether or not it is set
+fn size_of(ty: Option<&Json>, types: &[Json]) -> Option<usize> {
+    match ty? {
+        Json::Str(name) => match name.as_str() {
+            "u8" | "i8" | "bool" => Some(1),
+            "u16" | "i16" => Some(2),
+            "u32" | "i32" | "f32" => Some(4),
+            "u64" | "i64" | "f64" => Some(8),
+            "u128" | "i128" => Some(16),
+            "publicKey" => Some(32),
+            _ => None,
+        },
+        ty @ Json::Object(_) => {
+            if let Some(Json::Array(array)) = ty.get("array") {
+                let len: usize = array.get(1)?.as_str()?.parse().ok()?;
+                return size_of(array.first(), types).map(|size| size * len);
+            }
+            if let Some(inner) = ty.get("option") {
+                return size_of(Some(inner), types).map(|size| 1 + size);
+            }
+            let name = ty.get("defined")?.as_str()?;
+            let defined = types.iter().find(|t| t.str("name") == name)?.get("type")?;
+            match defined.str("kind") {
+                "struct" => defined
+                    .list("fields")
+                    .iter()
+                    .map(|field| size_of(field.get("type"), types))
+                    .sum(),
+                // A fieldless enum is its one-byte variant index
+                "enum"
+                    if defined
+                        .list("variants")
+                        .iter()
+                        .all(|v| v.get("fields").is_none()) =>
+                {
+                    Some(1)
+                }
+                _ => None,
+            }
+        }
+        _ => None,
+    }
+}
+
+// `u64`, `[u8; 32]`, `Option<u64>`, `Deadline`, as Rust would write it
+fn type_name(ty: Option<&Json>) -> String {
+    let Some(ty) = ty else {
+        return "?".to_string();
+    };
+    if let Some(name) = ty.as_str() {
+        return name.to_string();
+    }
+    if let Some(Json::Array(array)) = ty.get("array") {
+        let len = array.get(1).and_then(Json::as_str).unwrap_or("?");
+        return format!("[{}; {len}]", type_name(array.first()));
+    }
+    for (key, wrapper) in [("option", "Option"), ("vec", "Vec"), ("coption", "COption")] {
+        if let Some(inner) = ty.get(key) {
+            return format!("{wrapper}<{}>", type_name(Some(inner)));
+        }
+    }
+    ty.get("defined")
+        .and_then(Json::as_str)
+        .map_or_else(|| "?".to_string(), str::to_string)
+}
+
+/// Just enough JSON for shank's IDL; numbers are kept as their text
+#[derive(Debug)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    Str(String),
+    Array(Vec<Json>),
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    fn parse(text: &str) -> Result<Json, String> {
+        let mut parser = Parser {
+            bytes: text.as_bytes(),
+            at: 0,
+        };
+        let value = parser.value()?;
+        parser.skip_whitespace();
+        if parser.at != parser.bytes.len() {
+            return Err(format!("unexpected text at byte {}", parser.at));
+        }
+        if !matches!(value, Json::Object(_)) {
+            return Err("not a JSON object".to_string());
+        }
+        Ok(value)
+    }
+
+    fn get(&self, key: &str) -> Option<&Json> {
+        match self {
+            Json::Object(entries) => entries.iter().find(|(k, _)| k == key).map(|(_, v)| v),
+            _ => None,
+        }
+    }
+
+    fn as_str(&self) -> Option<&str> {
+        match self {
+            Json::Str(text) | Json::Number(text) => Some(text),
+            _ => None,
+        }
+    }
+
+    // The string or number at `key`, empty if missing
+    fn str(&self, key: &str) -> &str {
+        self.get(key).and_then(Json::as_str).unwrap_or_default()
+    }
+
+    fn bool(&self, key: &str) -> bool {
+        matches!(self.get(key), Some(Json::Bool(true)))
+    }
+
+    // The array at `key`, empty if missing
+    fn list(&self, key: &str) -> &[Json] {
+        match self.get(key) {
+            Some(Json::Array(items)) => items,
+            _ => &[],
+        }
+    }
+}
+
+struct Parser<'a> {
+    bytes: &'a [u8],
+    at: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self.bytes.get(self.at).is_some_and(u8::is_ascii_whitespace) {
+            self.at += 1;
+        }
+    }
+
+    fn eat(&mut self, byte: u8) -> bool {
+        self.skip_whitespace();
+        let found = self.bytes.get(self.at) == Some(&byte);
+        if found {
+            self.at += 1;
+        }
+        found
+    }
+
+    fn expect(&mut self, byte: u8) -> Result<(), String> {
+        if self.eat(byte) {
+            Ok(())
+        } else {
+            Err(format!("expected `{}` at byte {}", byte as char, self.at))
+        }
+    }
+
+    fn value(&mut self) -> Result<Json, String> {
+        self.skip_whitespace();
+        match self.bytes.get(self.at) {
+            Some(b'{') => {
+                self.at += 1;
+                let mut entries = Vec::new();
+                if !self.eat(b'}') {
+                    loop {
+                        self.skip_whitespace();
+                        let key = self.string()?;
+                        self.expect(b':')?;
+                        entries.push((key, self.value()?));
+                        if self.eat(b'}') {
+                            break;
+                        }
+                        self.expect(b',')?;
+                    }
+                }
+                Ok(Json::Object(entries))
+            }
+            Some(b'[') => {
+                self.at += 1;
+                let mut items = Vec::new();
+                if !self.eat(b']') {
+                    loop {
+                        items.push(self.value()?);
+                        if self.eat(b']') {
+                            break;
+                        }
+                        self.expect(b',')?;
+                    }
+                }
+                Ok(Json::Array(items))
+            }
+            Some(b'"') => self.string().map(Json::Str),
+            Some(_) => {
+                let start = self.at;
+                while self
+                    .bytes
+                    .get(self.at)
+                    .is_some_and(|b| b.is_ascii_alphanumeric() || b"+-.".contains(b))
+                {
+                    self.at += 1;
+                }
+                match &self.bytes[start..self.at] {
+                    b"null" => Ok(Json::Null),
+                    b"true" => Ok(Json::Bool(true)),
+                    b"false" => Ok(Json::Bool(false)),
+                    b"" => Err(format!("unexpected character at byte {start}")),
+                    number => Ok(Json::Number(String::from_utf8_lossy(number).into_owned())),
+                }
+            }
+            None => Err("unexpected end of input".to_string()),
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        if self.bytes.get(self.at) != Some(&b'"') {
+            return Err(format!("expected a string at byte {}", self.at));
+        }
+        self.at += 1;
+        let mut text = Vec::new();
+        loop {
+            match self.bytes.get(self.at) {
+                Some(b'"') => {
+                    self.at += 1;
+                    return String::from_utf8(text).map_err(|e| e.to_string());
+                }
+                Some(b'\\') => {
+                    let escaped = self.bytes.get(self.at + 1).copied();
+                    self.at += 2;
+                    match escaped {
+                        Some(b'n') => text.push(b'\n'),
+                        Some(b't') => text.push(b'\t'),
+                        Some(b'r') => text.push(b'\r'),
+                        Some(b'u') => {
+                            let hex = self.bytes.get(self.at..self.at + 4).unwrap_or_default();
+                            let code = std::str::from_utf8(hex)
+                                .ok()
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .and_then(char::from_u32)
+                                .unwrap_or(char::REPLACEMENT_CHARACTER);
+                            text.extend_from_slice(code.to_string().as_bytes());
+                            self.at += 4;
+                        }
+                        Some(other) => text.push(other),
+                        None => return Err("unterminated string".to_string()),
+                    }
+                }
+                Some(&byte) => {
+                    text.push(byte);
+                    self.at += 1;
+                }
+                None => return Err("unterminated string".to_string()),
+            }
+        }
+    }
+}
@@ -0,0 +1,3216 @@
+//! Build-time code generation for jiminy programs.
+//!
+//! Each program crate calls [`Config::generate`] from its `build.rs`:
+//!
+//! ```no_run
+//! // build.rs
+//! fn main() -> std::io::Result<()> {
+//!     jiminy_build::Config::from_manifest_dir().generate()
+//! }
+//! ```
+//!
+//! Everything is read from and written to the calling crate, so programs in
+//! one workspace can't see each other's instructions or state.
+//!
+//! [`generate_program`] returns the same code for any crate directory without
+//! writing it, so generator changes can be checked against a snapshot;
+//! [`generate_permissions`], [`generate_discriminator_report`],
+//! [`generate_fixtures`], [`generate_constants`] and [`generate_size_report`]
+//! do the same for the permission matrix, the discriminator report, the
+//! account fixtures, the client constants and the size report of a built `.so`.
+
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Component, Path, PathBuf};
+
+use discriminator_ranges::DiscriminatorRange;
+
+pub use idl_diff::{IdlChange, IdlDiff};
+
+mod byte_order;
+mod constants;
+mod constraints;
+mod discriminator_ranges;
+mod fixtures;
+mod idl;
+mod idl_diff;
+mod permissions;
+mod size_report;
+
+/// The program crate to generate code for
+#[derive(Debug, Clone)]
+pub struct Config {
+    root: PathBuf,
+    /// The enclosing workspace, whose `jiminy.toml` the crate's settings extend
+    workspace_root: Option<PathBuf>,
+    idl_dir: Option<PathBuf>,
+    /// The published IDL the new one is diffed against, see [`Config::previous_idl`]
+    previous_idl: Option<PathBuf>,
+    strict_idl_diff: bool,
+}
+
+impl Config {
+    /// The crate whose build script is running, from `CARGO_MANIFEST_DIR`
+    pub fn from_manifest_dir() -> Self {
+        Self::new(env::var("CARGO_MANIFEST_DIR").unwrap())
+    }
+
+    /// The program crate at `root`
+    ///
+    /// The workspace is the closest parent directory whose `Cargo.toml` has a
+    /// `[workspace]` table, and IDLs go to its `idls/` directory.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        let root = root.into();
+        let workspace_root = find_workspace_root(&root);
+        let idl_dir = workspace_root.as_ref().map(|workspace| workspace.join("idls"));
+        Config {
+            root,
+            workspace_root,
+            idl_dir,
+            previous_idl: None,
+            strict_idl_diff: false,
+        }
+    }
+
+    /// Write the IDL to `dir` instead of the workspace's `idls/`
+    pub fn idl_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.idl_dir = Some(dir.into());
+        self
+    }
+
+    /// Diff the IDL written with `JIMINY_IDL=1` against the published one at `path`,
+    /// relative to the crate, and warn about each change, see [`diff_idls`]
+    pub fn previous_idl(mut self, path: impl Into<PathBuf>) -> Self {
+        self.previous_idl = Some(self.root.join(path.into()));
+        self
+    }
+
+    /// Fail the build on a breaking change against [`Config::previous_idl`]
+    pub fn strict_idl_diff(mut self, strict: bool) -> Self {
+        self.strict_idl_diff = strict;
+        self
+    }
+
+    /// Parse `src/instructions`, `src/error.rs` and `src/state` and write the
+    /// shank enums and dispatch to `src/generated.rs`
+    ///
+    /// Settings are read from an optional `jiminy.toml` next to the program's
+    /// `Cargo.toml`, on top of an optional one at the workspace root. Tables
+    /// are merged key by key, the program's values winning, and paths stay
+    /// relative to the program:
+    ///
+    /// ```toml
+    /// [errors]
+    /// # Files with `define_errors!` besides src/error.rs, inside the crate
+    /// paths = ["src/admin_error.rs"]
+    /// # Enum whose `InvalidDiscriminator` unknown instructions return, needed
+    /// # when more than one enum declares that variant
+    /// invalid_discriminator = "UserError"
+    /// # Added to every declared code, so programs sharing a client don't
+    /// # return the same codes
+    /// base = 1000
+    ///
+    /// [lint]
+    /// # Check account order (signers, writable, read-only, then programs and
+    /// # sysvars), duplicate descs and `*_program` accounts without an `address` pin
+    /// accounts = true
+    /// # Fail the build instead of printing warnings
+    /// strict = false
+    /// # `*_program` accounts that may be any program
+    /// allow_unpinned = ["adapter_program"]
+    /// # Warn when an account created with `space: Type::LEN` is loaded as
+    /// # another state type in the same instruction, silenced per instruction
+    /// # with a `// jiminy: allow(create_space)` comment
+    /// create_space = true
+    /// # Warn at `from_be_bytes`/`to_be_bytes` in instruction files, state being
+    /// # little-endian, silenced per line with `// jiminy: allow(byte_order)` on it
+    /// # or on the line before
+    /// byte_order = true
+    /// # With `byte_order`, also warn at bare `from_le_bytes`/`to_le_bytes` where
+    /// # a `jiminy::LeU64` field would do
+    /// raw_le_bytes = false
+    /// # Warn when a `transfers:` recipient has no address, PDA or token owner
+    /// # check, silenced per instruction with `// jiminy: allow(recipients)`
+    /// recipients = true
+    /// # Warn when an instruction file never names a declared account, silenced
+    /// # per instruction with `// jiminy: allow(unused_accounts)`
+    /// unused_accounts = true
+    ///
+    /// [layout]
+    /// # Generate a test per state struct comparing its size, field offsets and
+    /// # bytes with `layouts/<Name>.v<version>.golden`, written on the first run;
+    /// # the tests need `jiminy`'s `testing` feature in dev builds
+    /// golden = true
+    /// # Bumped when a struct's layout changes on purpose, structs start at 1
+    /// versions = { Counter = 2 }
+    ///
+    /// # Discriminators reserved for a group of instructions, listing them
+    /// # makes the range theirs alone; once a range is declared, every
+    /// # discriminator must be in one
+    /// [discriminators.user]
+    /// range = [0, 31]
+    /// [discriminators.admin]
+    /// range = [32, 63]
+    /// instructions = ["UpdatePlatform", "ClaimFees"]
+    /// ```
+    ///
+    /// Every build also writes the permission matrix to `OUT_DIR/permissions.json`
+    /// and `OUT_DIR/permissions.md`, see [`generate_permissions`], and the used and
+    /// free discriminators to `OUT_DIR/discriminators.json` and
+    /// `OUT_DIR/discriminators.md`, see [`generate_discriminator_report`].
+    ///
+    /// With `JIMINY_SIZE_REPORT=1` in the environment it also builds the program
+    /// with `cargo build-sbf` and writes a per-instruction breakdown of the
+    /// symbol sizes to `OUT_DIR/jiminy-size-report.txt`. With `JIMINY_FIXTURES=1`
+    /// it writes a JSON account set per instruction to `fixtures/`. With
+    /// `JIMINY_IDL=1` it runs `shank idl` and writes `<crate name>.json` to the
+    /// IDL directory, with each account's constraints added as `accountConstraints`
+    /// (see [`generate_account_constraints`]), the `expiry` slot fields as
+    /// `instructionExpiry` and the `deprecated` cutoffs as `instructionDeprecation`,
+    /// and with `JIMINY_CONSTANTS=1` it
+    /// writes the program id, discriminators, error codes and seeds there as
+    /// `<crate name>.constants.json` and `<crate name>.constants.ts`.
+    ///
+    /// Fails when a file can't be written, and panics, failing the build, when
+    /// the config or the error enums are invalid.
+    pub fn generate(&self) -> io::Result<()> {
+        println!(
+            "cargo:rerun-if-changed={}",
+            self.root.join("src/instructions").display()
+        );
+        // The account sizes and layout tests come from the state structs
+        let state_dir = self.root.join("src/state");
+        if state_dir.exists() {
+            println!("cargo:rerun-if-changed={}", state_dir.display());
+        }
+
+        let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+        let dest_path = out_dir.join("generated_program.rs");
+
+        let (generated_code, instructions, ranges) = generate_with_metadata(self);
+
+        // Write to output file
+        fs::write(&dest_path, &generated_code)?;
+
+        // Also write to src/generated.rs for shank IDL generation
+        fs::write(self.root.join("src/generated.rs"), &generated_code)?;
+
+        println!(
+            "cargo:rustc-env=GENERATED_PROGRAM_PATH={}",
+            dest_path.display()
+        );
+
+        permissions::run(&out_dir, &instructions)?;
+        discriminator_ranges::run(&out_dir, &ranges, &instructions)?;
+
+        println!("cargo:rerun-if-env-changed=JIMINY_SIZE_REPORT");
+        if env::var("JIMINY_SIZE_REPORT").is_ok_and(|v| v == "1") {
+            size_report::run(&self.root, &instructions);
+        }
+
+        println!("cargo:rerun-if-env-changed=JIMINY_FIXTURES");
+        if env::var("JIMINY_FIXTURES").is_ok_and(|v| v == "1") {
+            fixtures::run(&self.root, &instructions)?;
+        }
+
+        println!("cargo:rerun-if-env-changed=JIMINY_IDL");
+        if env::var("JIMINY_IDL").is_ok_and(|v| v == "1") {
+            let dir = self.idl_dir.clone().unwrap_or_else(|| self.root.join("idls"));
+            let constraints = constraints::render(&self.root, &instructions);
+            let written = idl::run(&self.root, &dir, &constraints, &instructions);
+            if let (Some(previous), Some(written)) = (&self.previous_idl, written) {
+                println!("cargo:rerun-if-changed={}", previous.display());
+                idl_diff::check(previous, &written, self.strict_idl_diff);
+            }
+        }
+
+        println!("cargo:rerun-if-env-changed=JIMINY_CONSTANTS");
+        if env::var("JIMINY_CONSTANTS").is_ok_and(|v| v == "1") {
+            let dir = self.idl_dir.clone().unwrap_or_else(|| self.root.join("idls"));
+            constants::run(self, &dir, &instructions)?;
+        }
+        Ok(())
+    }
+}
+
+/// [`Config::generate`] for the crate whose build script is running
+///
+/// Panics when a file can't be written.
+pub fn generate() {
+    Config::from_manifest_dir().generate().unwrap();
+}
+
+/// Generate the code for the program crate at `root` without writing it anywhere
+///
+/// This is what [`Config::generate`] writes to `src/generated.rs`, handy for
+/// comparing against a committed snapshot when changing the generator.
+pub fn generate_program(root: &Path) -> String {
+    generate_with_metadata(&Config::new(root)).0
+}
+
+/// The permission matrix of the program crate at `root` as `(json, markdown)`
+///
+/// Per instruction: the signers and the transfers, creations and PDAs they
+/// control, the accounts created, closed and mutated, and the declared key
+/// constraints. [`Config::generate`] writes the same to `OUT_DIR` on every build.
+pub fn generate_permissions(root: &Path) -> (String, String) {
+    permissions::render(&generate_with_metadata(&Config::new(root)).1)
+}
+
+/// The discriminator report of the program crate at `root` as `(json, markdown)`
+///
+/// Per `[discriminators]` range in `jiminy.toml`, and for the view range, the
+/// values in use with their instructions and the free ones, then the values
+/// outside every range. [`Config::generate`] writes the same to `OUT_DIR` on every build.
+pub fn generate_discriminator_report(root: &Path) -> (String, String) {
+    let (_, instructions, ranges) = generate_with_metadata(&Config::new(root));
+    discriminator_ranges::render(&ranges, &instructions)
+}
+
+/// The client constants of the program crate at `root` as `(json, typescript)`
+///
+/// The program id from `declare_id!`, the instruction discriminators, the
+/// error codes and messages and the seed byte strings, what
+/// [`Config::generate`] writes with `JIMINY_CONSTANTS=1`.
+pub fn generate_constants(root: &Path) -> (String, String) {
+    let config = Config::new(root);
+    constants::render(&config, &generate_with_metadata(&config).1)
+}
+
+/// The account fixtures of the program crate at `root` as `(file name, json)`
+///
+/// One `<instruction>.json` per instruction that isn't a view, what
+/// [`Config::generate`] writes to `fixtures/` with `JIMINY_FIXTURES=1`.
+pub fn generate_fixtures(root: &Path) -> Vec<(String, String)> {
+    fixtures::render(root, &generate_with_metadata(&Config::new(root)).1)
+}
+
+/// The size report of the program crate at `root` for its unstripped `.so`
+///
+/// `elf` is the file's bytes; `None` when it isn't a 64-bit little-endian ELF
+/// with a symbol table. [`Config::generate`] writes the same to `OUT_DIR` with
+/// `JIMINY_SIZE_REPORT=1`, after building the `.so` itself.
+pub fn generate_size_report(root: &Path, elf: &[u8]) -> Option<String> {
+    size_report::render(&generate_with_metadata(&Config::new(root)).1, elf)
+}
+
+/// What each instruction of the crate at `root` requires of its accounts, as JSON
+///
+/// Per account: `signer`, the owner with `initialized` or `uninitialized`, a
+/// pinned address, `same_as`/`key_in` keys, the token owner and the PDA seeds
+/// with where the bump comes from. [`Config::generate`] adds it to the IDL with
+/// `JIMINY_IDL=1` and writes it to `fixtures/constraints.json` with `JIMINY_FIXTURES=1`.
+pub fn generate_account_constraints(root: &Path) -> String {
+    constraints::render(root, &generate_with_metadata(&Config::new(root)).1)
+}
+
+/// Compare a previously published shank IDL with a new one, both as JSON text
+///
+/// Instructions that were removed or changed their discriminator, accounts that
+/// were added, removed, moved or must now sign or be writable, and args whose
+/// type or position changed are breaking, as are state fields that were
+/// removed or changed offset, size or type, and errors that were removed or
+/// renumbered. Added instructions, state accounts, errors and trailing state
+/// fields, renames that keep the bytes and relaxed account flags are additive.
+/// Fails if either text isn't a JSON object. The `jiminy-idl-diff` binary runs
+/// the same on two files.
+pub fn diff_idls(old: &str, new: &str) -> Result<IdlDiff, String> {
+    idl_diff::diff(old, new)
+}
+
+/// The `jiminy.toml` lint messages for the program crate at `root`, strict or not
+///
+/// What [`Config::generate`] prints as warnings, or fails the build with under
+/// `strict`, one message per finding.
+pub fn generate_lints(root: &Path) -> Vec<String> {
+    let config = Config::new(root);
+    let settings = Settings::load(root, config.workspace_root.as_deref());
+    lints(root, &settings.lint, &extract_instruction_metadata(root))
+}
+
+/// The keys [`Config::generate`] adds to shank's IDL with `JIMINY_IDL=1`, as a JSON object
+///
+/// `accountConstraints`, `instructionExpiry`, `instructionDeprecation`,
+/// `accountSizes`, `events`, `accountMetadata` and `foreignAccounts`, for the
+/// program crate at `root`. Shank writes the rest of the IDL.
+pub fn generate_idl_extensions(root: &Path) -> String {
+    let (_, instructions, _) = generate_with_metadata(&Config::new(root));
+    let constraints = constraints::render(root, &instructions);
+    idl::render(root, &constraints, &instructions)
+}
+
+/// The program id in the `declare_id!("...")` of the crate at `root`'s `src/lib.rs`
+pub fn program_id(root: &Path) -> Option<String> {
+    constants::program_id(root)
+}
+
+// The closest parent of `root` whose `Cargo.toml` declares a workspace
+fn find_workspace_root(root: &Path) -> Option<PathBuf> {
+    let root = root.canonicalize().ok()?;
+    root.ancestors().skip(1).find_map(|dir| {
+        let manifest = fs::read_to_string(dir.join("Cargo.toml")).ok()?;
+        let table: toml::Table = manifest.parse().ok()?;
+        table.contains_key("workspace").then(|| dir.to_path_buf())
+    })
+}
+
+fn generate_with_metadata(
+    config: &Config,
+) -> (String, Vec<InstructionMeta>, Vec<DiscriminatorRange>) {
+    let root = config.root.as_path();
+    let settings = Settings::load(root, config.workspace_root.as_deref());
+
+    // Parse instruction files and extract metadata
+    let instructions = extract_instruction_metadata(root);
+    check_discriminators(&instructions);
+    discriminator_ranges::check(&settings.discriminator_ranges, &instructions);
+
+    report_lints(&lints(root, &settings.lint, &instructions), settings.lint.strict);
+
+    // Parse error definitions from error.rs and the configured paths
+    let errors = extract_error_metadata(root, &settings);
+    let error_owner = resolve_error_owner(&errors, &settings);
+
+    // Parse state definitions from state files
+    let state_structs = extract_state_metadata(root);
+    let enums = extract_enum_metadata(root);
+
+    // Add a read-only view instruction per state struct
+    let mut instructions = instructions;
+    instructions.extend(view_instructions(&state_structs, &instructions));
+
+    // Generate the program enum and dispatch
+    let mut code = generate_program_code(
+        &instructions,
+        &errors,
+        error_owner,
+        settings.error_base,
+        &state_structs,
+        &enums,
+        &fixtures::extract_headroom(&root.join("src")),
+    );
+    if settings.layout.golden {
+        push_layout_tests(&mut code, &state_structs, &enums, &settings.layout);
+    }
+    (code, instructions, settings.discriminator_ranges)
+}
+
+/// Settings from the optional `jiminy.toml` files of the program and its workspace
+#[derive(Debug, Default)]
+struct Settings {
+    /// Extra files holding `define_errors!`, besides `src/error.rs`
+    error_paths: Vec<String>,
+    /// Error enum whose `InvalidDiscriminator` the dispatch returns
+    invalid_discriminator: Option<String>,
+    /// Added to every declared error code
+    error_base: u32,
+    lint: LintConfig,
+    /// The `[discriminators]` ranges, sorted
+    discriminator_ranges: Vec<DiscriminatorRange>,
+    layout: LayoutConfig,
+}
+
+/// The `[layout]` table
+#[derive(Debug, Default)]
+struct LayoutConfig {
+    /// Generate a golden layout test per state struct
+    golden: bool,
+    /// `(state, version)` of the structs past version 1
+    versions: Vec<(String, u32)>,
+}
+
+/// The `[lint]` table, every check is off by default
+#[derive(Debug, Default)]
+struct LintConfig {
+    accounts: bool,
+    strict: bool,
+    /// `*_program` accounts allowed without an `address` pin
+    allow_unpinned: Vec<String>,
+    create_space: bool,
+    byte_order: bool,
+    /// Report little-endian conversions too, with `byte_order`
+    raw_le_bytes: bool,
+    recipients: bool,
+    unused_accounts: bool,
+}
+
+impl Settings {
+    fn load(root: &Path, workspace_root: Option<&Path>) -> Self {
+        let mut table = toml::Table::new();
+        let program = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        let shared = workspace_root.filter(|workspace| *workspace != program);
+        for dir in shared.into_iter().chain([root]) {
+            let path = dir.join("jiminy.toml");
+            println!("cargo:rerun-if-changed={}", path.display());
+            let Ok(content) = fs::read_to_string(&path) else {
+                continue;
+            };
+            let file: toml::Table = content.parse().unwrap_or_else(|e| {
+                panic!("{} is not valid TOML: {e}", path.display())
+            });
+            merge_tables(&mut table, file);
+        }
+
+        let mut settings = Self::default();
+        if let Some(errors) = table.get("errors") {
+            let errors = errors
+                .as_table()
+                .unwrap_or_else(|| panic!("jiminy.toml: `errors` must be a table"));
+            if let Some(paths) = errors.get("paths") {
+                settings.error_paths = string_list(paths, "errors.paths");
+                // The workspace's paths are the program's too, neither may leave the crate
+                for path in &settings.error_paths {
+                    let escapes = Path::new(path)
+                        .components()
+                        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+                    if escapes {
+                        panic!("jiminy.toml: error path `{path}` must be inside the program crate");
+                    }
+                }
+            }
+            if let Some(owner) = errors.get("invalid_discriminator") {
+                settings.invalid_discriminator = Some(
+                    owner
+                        .as_str()
+                        .unwrap_or_else(|| panic!("jiminy.toml: `errors.invalid_discriminator` must be a string"))
+                        .to_string(),
+                );
+            }
+            if let Some(base) = errors.get("base") {
+                settings.error_base = base
+                    .as_integer()
+                    .and_then(|base| u32::try_from(base).ok())
+                    .unwrap_or_else(|| panic!("jiminy.toml: `errors.base` must be a u32"));
+            }
+        }
+        if let Some(ranges) = table.get("discriminators") {
+            settings.discriminator_ranges = discriminator_ranges::parse(ranges);
+        }
+        if let Some(lint) = table.get("lint") {
+            let lint = lint
+                .as_table()
+                .unwrap_or_else(|| panic!("jiminy.toml: `lint` must be a table"));
+            for (key, value) in lint {
+                match key.as_str() {
+                    "accounts" => settings.lint.accounts = bool_value(value, "lint.accounts"),
+                    "strict" => settings.lint.strict = bool_value(value, "lint.strict"),
+                    "allow_unpinned" => {
+                        settings.lint.allow_unpinned = string_list(value, "lint.allow_unpinned")
+                    }
+                    "create_space" => {
+                        settings.lint.create_space = bool_value(value, "lint.create_space")
+                    }
+                    "byte_order" => {
+                        settings.lint.byte_order = bool_value(value, "lint.byte_order")
+                    }
+                    "raw_le_bytes" => {
+                        settings.lint.raw_le_bytes = bool_value(value, "lint.raw_le_bytes")
+                    }
+                    "recipients" => {
+                        settings.lint.recipients = bool_value(value, "lint.recipients")
+                    }
+                    "unused_accounts" => {
+                        settings.lint.unused_accounts = bool_value(value, "lint.unused_accounts")
+                    }
+                    _ => panic!("jiminy.toml: unknown setting `lint.{key}`"),
+                }
+            }
+        }
+        if let Some(layout) = table.get("layout") {
+            let layout = layout
+                .as_table()
+                .unwrap_or_else(|| panic!("jiminy.toml: `layout` must be a table"));
+            for (key, value) in layout {
+                match key.as_str() {
+                    "golden" => settings.layout.golden = bool_value(value, "layout.golden"),
+                    "versions" => {
+                        let versions = value.as_table().unwrap_or_else(|| {
+                            panic!("jiminy.toml: `layout.versions` must be a table")
+                        });
+                        for (state, version) in versions {
+                            let version = version
+                                .as_integer()
+                                .and_then(|version| u32::try_from(version).ok())
+                                .filter(|version| *version > 0)
+                                .unwrap_or_else(|| {
+                                    panic!("jiminy.toml: `layout.versions.{state}` must be a positive u32")
+                                });
+                            settings.layout.versions.push((state.clone(), version));
+                        }
+                    }
+                    _ => panic!("jiminy.toml: unknown setting `layout.{key}`"),
+                }
+            }
+        }
+        settings
+    }
+}
+
+// Copy `from` into `into`, merging nested tables and replacing everything else
+fn merge_tables(into: &mut toml::Table, from: toml::Table) {
+    for (key, value) in from {
+        match (into.get_mut(&key), value) {
+            (Some(toml::Value::Table(into)), toml::Value::Table(from)) => merge_tables(into, from),
+            (_, value) => {
+                into.insert(key, value);
+            }
+        }
+    }
+}
+
+fn string_list(value: &toml::Value, key: &str) -> Vec<String> {
+    value
+        .as_array()
+        .and_then(|items| {
+            items
+                .iter()
+                .map(|item| item.as_str().map(str::to_string))
+                .collect()
+        })
+        .unwrap_or_else(|| panic!("jiminy.toml: `{key}` must be a list of strings"))
+}
+
+fn bool_value(value: &toml::Value, key: &str) -> bool {
+    value
+        .as_bool()
+        .unwrap_or_else(|| panic!("jiminy.toml: `{key}` must be true or false"))
+}
+
+#[derive(Debug)]
+struct InstructionMeta {
+    name: String,
+    /// The `desc:` line, or the doc comment on an attribute-form module
+    desc: Option<String>,
+    discriminator: u8,
+    /// Deprecated discriminators that dispatch to this instruction too
+    aliases: Vec<u8>,
+    accounts: Vec<AccountMeta>,
+    fields: Vec<FieldMeta>,
+    rest: Option<String>,
+    /// Name and desc of `remaining_accounts`, if declared
+    remaining_accounts: Option<(String, String)>,
+    /// False with `strict_data: false`, trailing bytes are ignored instead of rejected
+    strict_data: bool,
+    /// The `data_parser` function; `fields` are then its `data_schema`, borsh encoded
+    data_parser: Option<String>,
+    transfers: Vec<TransferMeta>,
+    invariants: Vec<InvariantMeta>,
+    returns: Option<String>,
+    /// Data field of `expiry: slot_field(...)`, the last slot the instruction may run in
+    expiry: Option<String>,
+    /// Slot of `deprecated(after_slot: ...)` as written, the last one the instruction runs in
+    deprecated_after_slot: Option<String>,
+    /// Path of the state struct a generated view instruction reads
+    view_of: Option<String>,
+    /// How the instruction body creates and loads state accounts
+    state_uses: StateUses,
+}
+
+/// How accounts are used, found in the instruction file's text
+#[derive(Debug, Default)]
+struct StateUses {
+    /// Created with `space: Type::LEN`
+    created: Vec<(String, String)>,
+    /// Read with `load!`, `load_mut!`, `load_prefix!`, `load_prefix_mut!`, `reload!`,
+    /// `with_state!` or `read_state!`
+    loaded: Vec<(String, String)>,
+    /// Lints named in `// jiminy: allow(...)` comments
+    allowed: Vec<String>,
+    /// `(account, seeds)` from PDA checks and creation, the seeds as written between the brackets
+    pdas: Vec<(String, String)>,
+    /// `(account, bump)` of PDAs with a known bump, the bump as written, `None` for the
+    /// canonical bump `assert_pda_cached!` searches for
+    bumps: Vec<(String, Option<String>)>,
+    /// `(token account, owner)` from `check_token_owner` and `vault_deposit_tokens!`
+    token_owners: Vec<(String, String)>,
+    /// `(account, payer)` of every account creation, including `create_ata!`
+    payers: Vec<(String, String)>,
+    /// `(account, recipient)` from `close_account!`
+    closed: Vec<(String, String)>,
+    /// Declared accounts the file's code never names, see `unused_accounts`
+    unused_accounts: Vec<String>,
+    /// `(account, state, owner)` from `load_foreign!`, the owner as written
+    foreign: Vec<(String, String, String)>,
+}
+
+#[derive(Debug)]
+struct TransferMeta {
+    from: String,
+    to: String,
+    amount: String,
+    authority: String,
+    signed: bool,
+}
+
+/// `account.balance: direction(amount)` from the `invariants:` section
+#[derive(Debug)]
+struct InvariantMeta {
+    account: String,
+    balance: String,
+    direction: String,
+    amount: String,
+}
+
+#[derive(Debug)]
+struct AccountMeta {
+    name: String,
+    index: usize,
+    desc: String,
+    /// `signer`, `program`, `address`, ... as written in the accounts section
+    account_type: String,
+    attrs: Vec<String>,
+    /// `KEY` of `address(KEY)`, `authority` of `same_as(authority)`, `Platform::vault of platform`
+    /// of `key_in(Platform::vault of platform)`
+    type_arg: Option<String>,
+    /// `label: "..."` after the desc
+    label: Option<String>,
+    /// `explorer_hint: kind` after the desc, implied for the shorthands
+    explorer_hint: Option<String>,
+}
+
+#[derive(Debug)]
+struct FieldMeta {
+    name: String,
+    field_type: String,
+    /// Type in the data struct, differs from `field_type` for `side: u8 => bool`
+    wire_type: String,
+    /// `///` lines on the field
+    docs: Vec<String>,
+}
+
+fn extract_instruction_metadata(root: &Path) -> Vec<InstructionMeta> {
+    let mut instructions = Vec::new();
+
+    // Find all instruction files
+    let instruction_dir = root.join("src/instructions");
+    if instruction_dir.exists() {
+        for path in sorted_entries(&instruction_dir) {
+            if path.extension().and_then(|s| s.to_str()) == Some("rs")
+                && path.file_name().and_then(|s| s.to_str()) != Some("mod.rs")
+            {
+                if let Some(instruction) = parse_instruction_file(&path) {
+                    instructions.push(instruction);
+                }
+            }
+        }
+    }
+
+    instructions.sort_by_key(|i| i.discriminator);
+    instructions
+}
+
+fn parse_instruction_file(path: &Path) -> Option<InstructionMeta> {
+    let content = fs::read_to_string(path).ok()?;
+    let mut instruction = parse_instruction_content(&content)?;
+    instruction.state_uses = parse_state_uses(&content);
+    instruction.state_uses.unused_accounts = unused_accounts(&content, &instruction.accounts);
+    Some(instruction)
+}
+
+fn parse_instruction_content(content: &str) -> Option<InstructionMeta> {
+    // Look for either define_instruction_with_metadata! or define_instruction! macro,
+    // falling back to the #[jiminy::instruction] attribute form
+    let Some(start) = content
+        .find("define_instruction_with_metadata!(")
+        .or_else(|| content.find("define_instruction!("))
+    else {
+        return parse_instruction_attribute(content);
+    };
+    let mut paren_count = 0;
+    let mut in_macro = false;
+    let mut macro_content = String::new();
+
+    for (_i, ch) in content[start..].char_indices() {
+        if ch == '(' {
+            paren_count += 1;
+            in_macro = true;
+        } else if ch == ')' {
+            paren_count -= 1;
+        }
+
+        if in_macro {
+            macro_content.push(ch);
+        }
+
+        if paren_count == 0 && in_macro {
+            break;
+        }
+    }
+
+    parse_macro_content(&macro_content)
+}
+
+fn parse_instruction_attribute(content: &str) -> Option<InstructionMeta> {
+    // Parse `#[jiminy::instruction(discriminant = 0)] pub mod initialize_platform { ... }`
+    let attr_start = content
+        .find("#[jiminy::instruction(")
+        .or_else(|| content.find("#[instruction("))?;
+    let attr = &content[attr_start..];
+    let attr_end = attr.find(")]")?;
+    let discriminator = attr[..attr_end]
+        .split("discriminant")
+        .nth(1)?
+        .trim_start()
+        .strip_prefix('=')?
+        .trim()
+        .split(|c: char| !c.is_ascii_digit())
+        .next()?
+        .to_string();
+    // `deprecated_aliases = [10, 11]` becomes the macro's `deprecated_aliases: [10, 11],` line
+    let aliases = attr[..attr_end]
+        .split_once("deprecated_aliases")
+        .and_then(|(_, list)| list.trim_start().strip_prefix('='))
+        .map(|list| format!("deprecated_aliases: {},\n", list.trim().trim_end_matches(',')))
+        .unwrap_or_default();
+
+    let module = &attr[attr_end..];
+    let module_name = module[module.find("mod ")? + 4..]
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()?;
+    let name: String = module_name
+        .split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect();
+
+    // `///` lines above or below the attribute describe the instruction
+    let mut docs: Vec<&str> = content[..attr_start]
+        .lines()
+        .rev()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty())
+        .map_while(|line| line.strip_prefix("///"))
+        .collect();
+    docs.reverse();
+    docs.extend(
+        module[..module.find("mod ")?]
+            .lines()
+            .filter_map(|line| line.trim().strip_prefix("///")),
+    );
+
+    // Reuse the macro parser on the sections, skipping any `use` items before them
+    let sections = &module[module.find("accounts!")?..];
+    let mut instruction = parse_macro_content(&format!(
+        "discriminant: {discriminator},\n{name},\n{aliases}{sections}"
+    ))?;
+    if !docs.is_empty() {
+        let docs: Vec<&str> = docs.iter().map(|doc| doc.trim()).collect();
+        instruction.desc = Some(docs.join(" "));
+    }
+    Some(instruction)
+}
+
+fn parse_macro_content(content: &str) -> Option<InstructionMeta> {
+    let lines: Vec<&str> = content.lines().collect();
+
+    let mut name = String::new();
+    let mut discriminator = 0u8;
+    let mut desc = None;
+    let mut aliases = Vec::new();
+    let mut accounts = Vec::new();
+    let mut fields = Vec::new();
+    let mut rest = None;
+    let mut remaining_accounts = None;
+    let mut strict_data = true;
+    let mut data_parser = None;
+    let mut transfers = Vec::new();
+    let mut invariants = Vec::new();
+    let mut returns = None;
+    let mut expiry = None;
+    let mut deprecated_after_slot = None;
+
+    let mut in_accounts = false;
+    let mut in_data = false;
+    let mut in_prepare = false;
+    let mut in_transfers = false;
+    let mut in_invariants = false;
+    let mut account_index = 0;
+    let mut field_docs = Vec::new();
+
+    for line in lines {
+        let line = line.trim();
+
+        // `prepare` holds arbitrary statements, skip them until the next section
+        if in_prepare
+            && !line.starts_with("transfers:")
+            && !line.starts_with("invariants:")
+            && !line.starts_with("returns:")
+            && !line.starts_with("process:")
+        {
+            continue;
+        }
+
+        // Extract discriminant
+        if line.starts_with("discriminant:") {
+            if let Some(num) = line.split(':').nth(1) {
+                discriminator = num.trim().trim_end_matches(',').parse().unwrap_or(0);
+            }
+            continue;
+        }
+
+        // `desc: "...",` right after the name
+        if !in_accounts && !in_data && line.starts_with("desc:") {
+            desc = Some(unquote(line.trim_start_matches("desc:")));
+            continue;
+        }
+
+        // `deprecated_aliases: [10, 11],`
+        if line.starts_with("deprecated_aliases:") {
+            aliases = parse_aliases(line.trim_start_matches("deprecated_aliases:"));
+            continue;
+        }
+
+        // `deprecated(after_slot: 350_000_000),`
+        if line.starts_with("deprecated(") {
+            deprecated_after_slot = line
+                .split_once("after_slot:")
+                .and_then(|(_, slot)| slot.trim_end_matches(',').trim().strip_suffix(')'))
+                .map(|slot| slot.trim().to_string());
+            continue;
+        }
+
+        // Extract instruction name (first identifier after discriminant)
+        if name.is_empty()
+            && !line.is_empty()
+            && !line.starts_with("define_instruction")
+            && !line.starts_with("discriminant:")
+            && line.ends_with(',')
+        {
+            name = line.trim_end_matches(',').to_string();
+            continue;
+        }
+
+        // Track sections, written `accounts: {` in the macro and `accounts! {` in the attribute form
+        if line.starts_with("accounts:") || line.starts_with("accounts!") {
+            in_accounts = true;
+            in_data = false;
+            continue;
+        } else if line.starts_with("remaining_accounts") {
+            // `remaining_accounts: positions, desc: "...",` before the data
+            in_accounts = false;
+            remaining_accounts = line
+                .trim_start_matches("remaining_accounts:")
+                .trim_start_matches("remaining_accounts!(")
+                .split_once(',')
+                .map(|(name, desc)| {
+                    let desc = desc.trim().trim_start_matches("desc:").trim_end_matches(';');
+                    let desc = desc.trim_end_matches(',').trim_end_matches(')');
+                    (name.trim().to_string(), unquote(desc))
+                });
+            continue;
+        } else if line.starts_with("data:") || line.starts_with("data!") {
+            in_accounts = false;
+            in_data = true;
+            continue;
+        } else if line.starts_with("data_parser:") {
+            // `data_parser: parse_args => args: Args, max_len: 80,`
+            in_accounts = false;
+            data_parser = line
+                .trim_start_matches("data_parser:")
+                .split("=>")
+                .next()
+                .map(|parser| parser.trim().to_string());
+            continue;
+        } else if line.starts_with("data_schema:") {
+            in_data = true;
+            continue;
+        } else if line.starts_with("rest:") || line.starts_with("rest!") {
+            // `rest: label, max_len: 64,` names the trailing bytes after the fixed data
+            in_data = false;
+            rest = line
+                .trim_start_matches("rest:")
+                .trim_start_matches("rest!(")
+                .split(',')
+                .next()
+                .map(|name| name.trim().to_string());
+            continue;
+        } else if (line.starts_with("context:") || line.starts_with("context!"))
+            && !line.contains("desc:")
+        {
+            // `context: ctx,` only names the sysvar cache, nothing to record
+            in_accounts = false;
+            in_data = false;
+            continue;
+        } else if line.starts_with("strict_data:") {
+            in_data = false;
+            strict_data = !line.contains("false");
+            continue;
+        } else if line.starts_with("expiry:") && !line.contains("desc:") {
+            // `expiry: slot_field(expires_at_slot),`
+            in_accounts = false;
+            in_data = false;
+            expiry = line
+                .split_once("slot_field(")
+                .and_then(|(_, field)| field.split_once(')'))
+                .map(|(field, _)| field.trim().to_string());
+            continue;
+        } else if line.starts_with("prepare:") {
+            in_accounts = false;
+            in_data = false;
+            in_prepare = true;
+            continue;
+        } else if line.starts_with("transfers:") {
+            in_accounts = false;
+            in_data = false;
+            in_prepare = false;
+            in_transfers = true;
+            continue;
+        } else if line.starts_with("invariants:") {
+            in_accounts = false;
+            in_data = false;
+            in_prepare = false;
+            in_transfers = false;
+            in_invariants = true;
+            continue;
+        } else if line.starts_with("returns:") {
+            // `returns: u64,` is the type of the return data
+            in_data = false;
+            in_prepare = false;
+            in_transfers = false;
+            in_invariants = false;
+            returns = Some(line.trim_start_matches("returns:").trim().trim_end_matches(',').to_string());
+            continue;
+        } else if line.starts_with("process:") || line.starts_with("process!") {
+            break;
+        }
+
+        // Parse transfer lines, one entry per line
+        if in_transfers && line.contains("=>") {
+            if let Some(transfer) = parse_transfer_line(line) {
+                transfers.push(transfer);
+            }
+        }
+
+        // Parse invariant lines, one entry per line
+        if in_invariants {
+            if let Some(invariant) = parse_invariant_line(line) {
+                invariants.push(invariant);
+            }
+        }
+
+        // Parse account lines with new format
+        if in_accounts && (line.starts_with("label:") || line.starts_with("explorer_hint:")) {
+            // `label:` or `explorer_hint:` wrapped onto its own line
+            if let Some(account) = accounts.last_mut() {
+                parse_account_extras(line, account);
+            }
+        } else if in_accounts && line.contains("desc:") {
+            if let Some(account) = parse_new_account_line(line, account_index) {
+                accounts.push(account);
+                account_index += 1;
+            }
+        } else if in_accounts && !line.starts_with("//") {
+            // Shorthands, several may share a line: `system_program, token_program,`
+            for item in line.split(',') {
+                if let Some(account) = parse_shorthand_account(item, account_index) {
+                    accounts.push(account);
+                    account_index += 1;
+                }
+            }
+        }
+
+        // Parse data fields, `///` lines document the field below them
+        if in_data {
+            if let Some(doc) = line.strip_prefix("///") {
+                field_docs.push(doc.trim().to_string());
+                continue;
+            }
+            if line.starts_with("//") {
+                continue;
+            }
+        }
+        if in_data && line.contains(':') && !line.starts_with("data:") && !line.starts_with('}') {
+            if let Some(mut field) = parse_field_line(line) {
+                field.docs = std::mem::take(&mut field_docs);
+                fields.push(field);
+            }
+        }
+    }
+
+    if !name.is_empty() {
+        Some(InstructionMeta {
+            name,
+            desc,
+            discriminator,
+            aliases,
+            accounts,
+            fields,
+            rest,
+            remaining_accounts,
+            strict_data,
+            data_parser,
+            transfers,
+            invariants,
+            returns,
+            expiry,
+            deprecated_after_slot,
+            view_of: None,
+            state_uses: StateUses::default(),
+        })
+    } else {
+        None
+    }
+}
+
+// `"text",` -> `text`
+fn unquote(value: &str) -> String {
+    value
+        .trim()
+        .trim_end_matches(',')
+        .trim()
+        .trim_matches('"')
+        .to_string()
+}
+
+fn parse_aliases(list: &str) -> Vec<u8> {
+    list.trim()
+        .trim_end_matches(',')
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(str::trim)
+        .filter(|alias| !alias.is_empty())
+        .map(|alias| {
+            alias
+                .parse()
+                .unwrap_or_else(|_| panic!("deprecated alias `{alias}` is not a u8"))
+        })
+        .collect()
+}
+
+fn parse_transfer_line(line: &str) -> Option<TransferMeta> {
+    // Parse lines like: from => to: amount, authority: authority, seeds: [...];
+    let (from, rest) = line.split_once("=>")?;
+    let (to, rest) = rest.split_once(':')?;
+    let (amount, rest) = rest.split_once(", authority:")?;
+    let rest = rest.trim().trim_end_matches(';');
+    let (authority, signed) = match rest.split_once(',') {
+        Some((authority, seeds)) => (authority, seeds.contains("seeds:")),
+        None => (rest, false),
+    };
+
+    Some(TransferMeta {
+        from: from.trim().to_string(),
+        to: to.trim().to_string(),
+        amount: amount.trim().to_string(),
+        authority: authority.trim().to_string(),
+        signed,
+    })
+}
+
+fn parse_invariant_line(line: &str) -> Option<InvariantMeta> {
+    // Parse lines like: vault_token_account.tokens: increases_by(fee_amount),
+    let (target, rest) = line.split_once(':')?;
+    let (account, balance) = target.split_once('.')?;
+    let (direction, amount) = rest.split_once('(')?;
+    let amount = amount.trim().trim_end_matches(',').strip_suffix(')')?;
+
+    Some(InvariantMeta {
+        account: account.trim().to_string(),
+        balance: balance.trim().to_string(),
+        direction: direction.trim().to_string(),
+        amount: amount.trim().to_string(),
+    })
+}
+
+fn parse_new_account_line(line: &str, index: usize) -> Option<AccountMeta> {
+    // Parse lines like: authority: signer => writable, desc: "Authority of the vault",
+    // The type may hold a path, as in `system_program: address(pinocchio_system::ID)`
+    let (name, rest) = line.split_once(':')?;
+    let (account_def, desc_part) = rest.split_once("desc:")?;
+    let name = name.trim().to_string();
+    let account_def = account_def.trim().trim_end_matches(',');
+    // The desc is quoted and may be followed by `label:` and `explorer_hint:`
+    let desc_part = desc_part.trim();
+    let (desc_part, extras) = desc_part
+        .strip_prefix('"')
+        .and_then(|quoted| quoted.split_once('"'))
+        .unwrap_or((desc_part.trim_end_matches(','), ""));
+    let account_type = account_def
+        .split(|c: char| !c.is_alphanumeric() && c != '_')
+        .next()
+        .unwrap_or_default()
+        .to_string();
+
+    // Parse account type and validation from account_def
+    let mut attrs = Vec::new();
+    if account_def.contains("signer") {
+        attrs.push("signer".to_string());
+    }
+    if account_def.contains("writable") || account_def.contains("=> writable") {
+        attrs.push("writable".to_string());
+    }
+    // Uninitialized accounts are always writable since they're being created
+    if account_def.contains("uninitialized") {
+        attrs.push("writable".to_string());
+    }
+
+    let type_arg = account_def
+        .strip_prefix(account_type.as_str())
+        .and_then(|rest| rest.trim_start().strip_prefix('('))
+        .and_then(|rest| rest.split_once(')'))
+        .map(|(arg, _)| arg.trim().to_string());
+
+    let mut account = AccountMeta {
+        name,
+        index,
+        desc: desc_part.to_string(),
+        account_type,
+        attrs,
+        type_arg,
+        label: None,
+        explorer_hint: None,
+    };
+    parse_account_extras(extras, &mut account);
+    Some(account)
+}
+
+// `, label: "Platform Config", explorer_hint: pda,` after an account's desc
+fn parse_account_extras(extras: &str, account: &mut AccountMeta) {
+    let mut rest = extras.trim().trim_start_matches(',').trim();
+    if let Some(label) = rest.strip_prefix("label:") {
+        let label = label.trim_start();
+        let Some((label, after)) = label.strip_prefix('"').and_then(|l| l.split_once('"')) else {
+            return;
+        };
+        account.label = Some(label.to_string());
+        rest = after.trim().trim_start_matches(',').trim();
+    }
+    if let Some(hint) = rest.strip_prefix("explorer_hint:") {
+        let hint = hint.trim().trim_end_matches(',').trim();
+        if !hint.is_empty() {
+            account.explorer_hint = Some(hint.to_string());
+        }
+    }
+}
+
+/// Account shorthands: name, pinned address, desc and explorer hint, as
+/// `define_instruction_with_metadata!` expands them
+const ACCOUNT_SHORTHANDS: [(&str, &str, &str, &str); 4] = [
+    ("system_program", "pinocchio_system::ID", "System program", "program"),
+    ("token_program", "pinocchio_token::ID", "Token program", "program"),
+    (
+        "ata_program",
+        "pinocchio_associated_token_account::ID",
+        "Associated Token program",
+        "program",
+    ),
+    ("rent_sysvar", "pinocchio::sysvars::rent::RENT_ID", "Rent sysvar", "sysvar"),
+];
+
+// `system_program` or `rent: rent_sysvar`, None for anything else
+fn parse_shorthand_account(item: &str, index: usize) -> Option<AccountMeta> {
+    let (name, shorthand) = match item.split_once(':') {
+        Some((name, shorthand)) => (name.trim(), shorthand.trim()),
+        None => (item.trim(), item.trim()),
+    };
+    let is_ident = |s: &str| !s.is_empty() && s.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if !is_ident(name) {
+        return None;
+    }
+    let (_, address, desc, hint) = ACCOUNT_SHORTHANDS
+        .iter()
+        .find(|(known, ..)| *known == shorthand)?;
+    Some(AccountMeta {
+        name: name.to_string(),
+        index,
+        desc: desc.to_string(),
+        account_type: "address".to_string(),
+        attrs: Vec::new(),
+        type_arg: Some(address.to_string()),
+        label: None,
+        explorer_hint: Some(hint.to_string()),
+    })
+}
+
+/// Audit conventions for the account lists, one message per violation
+fn lint_accounts(instructions: &[InstructionMeta], lint: &LintConfig) -> Vec<String> {
+    let mut messages = Vec::new();
+    for instruction in instructions {
+        let mut previous: Option<(&AccountMeta, u8)> = None;
+        for (i, account) in instruction.accounts.iter().enumerate() {
+            let (rank, kind) = account_rank(account);
+            if let Some((prev, prev_rank)) = previous {
+                if rank < prev_rank {
+                    messages.push(format!(
+                        "{}: {kind} account `{}` comes after {} account `{}`, order signers, writable, read-only, then programs and sysvars",
+                        instruction.name,
+                        account.name,
+                        account_rank(prev).1,
+                        prev.name
+                    ));
+                }
+            }
+            if previous.is_none_or(|(_, prev_rank)| rank >= prev_rank) {
+                previous = Some((account, rank));
+            }
+
+            if let Some(first) = instruction.accounts[..i]
+                .iter()
+                .find(|other| other.desc == account.desc)
+            {
+                messages.push(format!(
+                    "{}: `{}` and `{}` share the desc \"{}\"",
+                    instruction.name, first.name, account.name, account.desc
+                ));
+            }
+
+            if account.name.ends_with("_program")
+                && account.account_type != "address"
+                && !lint.allow_unpinned.contains(&account.name)
+            {
+                messages.push(format!(
+                    "{}: `{}` accepts any key, pin it with `address(...)` or list it in `lint.allow_unpinned`",
+                    instruction.name, account.name
+                ));
+            }
+        }
+    }
+    messages
+}
+
+/// Flag accounts created with one state type's `LEN` and loaded as another
+///
+/// A text heuristic: it pairs `to:`/`space:` in `create_pda!`, `create_pda_cached!`,
+/// `create_pda_sponsored!` and `CreateAccount` with the first two arguments of
+/// the loaders, so spaces computed any other way are not checked.
+fn lint_create_space(instructions: &[InstructionMeta]) -> Vec<String> {
+    let mut messages = Vec::new();
+    for instruction in instructions {
+        let uses = &instruction.state_uses;
+        if uses.allowed.iter().any(|lint| lint == "create_space") {
+            continue;
+        }
+        for (account, created) in &uses.created {
+            for (_, loaded) in uses.loaded.iter().filter(|(a, t)| a == account && t != created) {
+                messages.push(format!(
+                    "{}: `{account}` is created with `{created}::LEN` but loaded as `{loaded}`",
+                    instruction.name
+                ));
+            }
+        }
+    }
+    messages
+}
+
+/// Flag `transfers:` recipients any key could be substituted for
+///
+/// A recipient counts as checked when it's declared `address`, `same_as` or
+/// `key_in`, or the instruction's text checks it with a PDA macro or
+/// `check_token_owner`, deposits to it with `vault_deposit_tokens!` or creates
+/// it. Checks inside helper functions aren't seen.
+fn lint_recipients(instructions: &[InstructionMeta]) -> Vec<String> {
+    let mut messages = Vec::new();
+    for instruction in instructions {
+        let uses = &instruction.state_uses;
+        if uses.allowed.iter().any(|lint| lint == "recipients") {
+            continue;
+        }
+        for transfer in &instruction.transfers {
+            let to = transfer.to.as_str();
+            let pinned = instruction.accounts.iter().any(|account| {
+                account.name == to
+                    && matches!(account.account_type.as_str(), "address" | "same_as" | "key_in")
+            });
+            let checked = uses.pdas.iter().any(|(account, _)| account == to)
+                || uses.token_owners.iter().any(|(account, _)| account == to)
+                || uses.payers.iter().any(|(account, _)| account == to);
+            if !pinned && !checked {
+                messages.push(format!(
+                    "{}: `{to}` receives `{}` but nothing checks its key or owner",
+                    instruction.name, transfer.amount
+                ));
+            }
+        }
+    }
+    messages
+}
+
+/// Flag declared accounts the instruction's file never names
+///
+/// Like `create_space` it reads the file's text, so an account only used by a
+/// helper in another file counts as unused.
+fn lint_unused_accounts(instructions: &[InstructionMeta]) -> Vec<String> {
+    let mut messages = Vec::new();
+    for instruction in instructions {
+        let uses = &instruction.state_uses;
+        let allowed = uses.allowed.iter().any(|lint| lint == "unused_accounts");
+        if allowed || uses.unused_accounts.is_empty() {
+            continue;
+        }
+        messages.push(format!(
+            "{}: {} declared but never used",
+            instruction.name,
+            uses.unused_accounts
+                .iter()
+                .map(|account| format!("`{account}`"))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ));
+    }
+    messages
+}
+
+// Macros and paths whose CPIs need a program account in the transaction without naming
+// it, by the account's name or its `address(...)`
+const IMPLICIT_PROGRAM_USES: [(&str, &str, &[&str]); 3] = [
+    (
+        "system_program",
+        "pinocchio_system::ID",
+        &[
+            "create_pda!",
+            "create_pda_cached!",
+            "create_pda_sponsored!",
+            "create_ata!",
+            "transfer_sol!",
+            "ensure_vault!",
+            "vault_deposit_sol!",
+            "vault_withdraw_sol!",
+            "pinocchio_system",
+        ],
+    ),
+    (
+        "token_program",
+        "pinocchio_token::ID",
+        &[
+            "transfers:",
+            "transfer_tokens!",
+            "vault_deposit_tokens!",
+            "vault_withdraw_tokens!",
+            "create_ata!",
+            "pinocchio_token",
+        ],
+    ),
+    (
+        "ata_program",
+        "pinocchio_associated_token_account::ID",
+        &["create_ata!"],
+    ),
+];
+
+// Declared accounts named nowhere in `content` outside their declaration, comments and
+// strings skipped; program accounts also count as used by the CPIs that need them
+fn unused_accounts(content: &str, accounts: &[AccountMeta]) -> Vec<String> {
+    let (mut in_block_comment, mut in_string) = (false, false);
+    let code: String = content
+        .lines()
+        .map(|line| byte_order::split_line(line, &mut in_block_comment, &mut in_string).0 + "\n")
+        .collect();
+    let code = without_account_declarations(&code);
+    accounts
+        .iter()
+        .filter(|account| {
+            let address = account.type_arg.as_deref().map(str::trim);
+            let mut implicit = IMPLICIT_PROGRAM_USES
+                .iter()
+                .filter(|(name, id, _)| account.name == *name || address == Some(*id))
+                .flat_map(|(_, _, uses)| uses.iter());
+            !byte_order::contains_word(&code, &account.name)
+                && !implicit.any(|pattern| code.contains(pattern))
+        })
+        .map(|account| account.name.clone())
+        .collect()
+}
+
+// `code` without the `accounts: { ... }` or `accounts! { ... }` block
+fn without_account_declarations(code: &str) -> String {
+    for (start, _) in code.match_indices("accounts") {
+        let before = code[..start].chars().next_back();
+        if before.is_some_and(|c| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+        let after = code[start + "accounts".len()..].trim_start();
+        let Some(block) = after
+            .strip_prefix(':')
+            .or_else(|| after.strip_prefix('!'))
+            .and_then(|rest| rest.trim_start().strip_prefix('{'))
+        else {
+            continue;
+        };
+        let end = code.len() - block.len() + delimited(block).len();
+        return format!("{}{}", &code[..start], &code[end..]);
+    }
+    code.to_string()
+}
+
+fn parse_state_uses(content: &str) -> StateUses {
+    let mut uses = StateUses::default();
+    let mut code = String::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if let Some(comment) = line.strip_prefix("//") {
+            if let Some(list) = comment
+                .trim()
+                .strip_prefix("jiminy: allow(")
+                .and_then(|rest| rest.strip_suffix(')'))
+            {
+                uses.allowed.extend(list.split(',').map(|lint| lint.trim().to_string()));
+            }
+            continue;
+        }
+        code.push_str(line);
+        code.push('\n');
+    }
+
+    for pattern in [
+        "create_pda!(",
+        "create_pda_cached!(",
+        "create_pda_sponsored!(",
+        "CreateAccount {",
+    ] {
+        for (start, _) in code.match_indices(pattern) {
+            let args = delimited(&code[start + pattern.len()..]);
+            if let (Some(to), Some(space)) = (named_arg(args, "to"), named_arg(args, "space")) {
+                if let Some(state) = space_type(space) {
+                    uses.created.push((to.to_string(), state.to_string()));
+                }
+            }
+        }
+    }
+    for (pattern, payer, account) in [
+        ("create_pda!(", "payer", "to"),
+        ("create_pda!(", "from", "to"),
+        ("create_pda_cached!(", "payer", "to"),
+        ("create_pda_cached!(", "from", "to"),
+        ("create_pda_sponsored!(", "sponsor", "to"),
+        ("CreateAccount {", "from", "to"),
+        ("create_ata!(", "payer", "ata"),
+    ] {
+        for (start, _) in code.match_indices(pattern) {
+            let args = delimited(&code[start + pattern.len()..]);
+            let (account, payer) = (named_arg(args, account), named_arg(args, payer));
+            if let (Some(account), Some(payer)) = (account, payer) {
+                uses.payers.push((account.to_string(), payer.to_string()));
+            }
+        }
+    }
+    for (start, _) in code.match_indices("load_foreign!(") {
+        let args = delimited(&code[start + "load_foreign!(".len()..]);
+        let mut positional = top_level(args, ',');
+        if let (Some(account), Some(state), Some(owner)) =
+            (positional.next(), positional.next(), named_arg(args, "owner"))
+        {
+            uses.foreign.push((
+                account.trim().to_string(),
+                last_segment(state).to_string(),
+                owner.to_string(),
+            ));
+        }
+    }
+    for (start, _) in code.match_indices("close_account!(") {
+        let mut args = top_level(delimited(&code[start + "close_account!(".len()..]), ',');
+        if let (Some(account), Some(recipient)) = (args.next(), args.next()) {
+            uses.closed.push((account.trim().to_string(), recipient.trim().to_string()));
+        }
+    }
+    for pattern in [
+        "load!(",
+        "load_mut!(",
+        "load_prefix!(",
+        "load_prefix_mut!(",
+        "with_state!(",
+        "read_state!(",
+    ] {
+        for (start, _) in code.match_indices(pattern) {
+            let mut args = delimited(&code[start + pattern.len()..]).split(',');
+            if let (Some(account), Some(state)) = (args.next(), args.next()) {
+                uses.loaded.push((account.trim().to_string(), last_segment(state).to_string()));
+            }
+        }
+    }
+
+    // PDAs, keyed by the first argument, the second after a `PdaCache`, or `to:`
+    for (pattern, position) in [
+        ("assert_pda!(", 0),
+        ("ensure_vault!(", 0),
+        ("vault_deposit_sol!(", 0),
+        ("vault_deposit_tokens!(", 0),
+        ("vault_withdraw_sol!(", 0),
+        ("vault_withdraw_tokens!(", 0),
+        ("assert_pda_cached!(", 1),
+    ] {
+        for (start, _) in code.match_indices(pattern) {
+            let args = delimited(&code[start + pattern.len()..]);
+            let account = top_level(args, ',').nth(position);
+            if let (Some(account), Some(seeds)) = (account, named_arg(args, "seeds")) {
+                push_pda(&mut uses, account, seeds);
+                match named_arg(args, "bump") {
+                    Some(bump) => push_bump(&mut uses, account, Some(bump)),
+                    None if pattern == "assert_pda_cached!(" => push_bump(&mut uses, account, None),
+                    None => {}
+                }
+            }
+        }
+    }
+    for pattern in ["create_pda!(", "create_pda_cached!(", "create_pda_sponsored!("] {
+        for (start, _) in code.match_indices(pattern) {
+            let args = delimited(&code[start + pattern.len()..]);
+            if let (Some(to), Some(seeds)) = (named_arg(args, "to"), named_arg(args, "seeds")) {
+                push_pda(&mut uses, to, seeds);
+                if let Some(bump) = named_arg(args, "bump") {
+                    push_bump(&mut uses, to, Some(bump));
+                }
+            }
+        }
+    }
+    for (start, _) in code.match_indices("validate_pdas!(") {
+        let args = delimited(&code[start + "validate_pdas!(".len()..]);
+        for entry in top_level(args, ';') {
+            if let Some((account, rest)) = entry.split_once("=>") {
+                if let Some(seeds) = named_arg(rest, "seeds") {
+                    push_pda(&mut uses, account, seeds);
+                    if let Some(bump) = named_arg(rest, "bump") {
+                        push_bump(&mut uses, account, Some(bump));
+                    }
+                }
+            }
+        }
+    }
+
+    for (start, _) in code.match_indices("check_token_owner(") {
+        let mut args = top_level(delimited(&code[start + "check_token_owner(".len()..]), ',');
+        if let (Some(account), Some(owner)) = (args.next(), args.next()) {
+            let owner = owner.trim().trim_end_matches(".key()");
+            uses.token_owners.push((account.trim().to_string(), owner.to_string()));
+        }
+    }
+    for (start, _) in code.match_indices("vault_deposit_tokens!(") {
+        let args = delimited(&code[start + "vault_deposit_tokens!(".len()..]);
+        if let (Some(vault), Some(to)) = (top_level(args, ',').next(), named_arg(args, "to")) {
+            uses.token_owners.push((to.to_string(), vault.trim().to_string()));
+        }
+    }
+    uses
+}
+
+// The first seeds found for an account win
+fn push_pda(uses: &mut StateUses, account: &str, seeds: &str) {
+    let account = account.trim();
+    if uses.pdas.iter().all(|(known, _)| known != account) {
+        let seeds = seeds.trim().trim_start_matches('[').trim_end_matches(']');
+        uses.pdas.push((account.to_string(), seeds.trim().to_string()));
+    }
+}
+
+// The first bump found for an account wins, checks come before creation
+fn push_bump(uses: &mut StateUses, account: &str, bump: Option<&str>) {
+    let account = account.trim();
+    if uses.bumps.iter().all(|(known, _)| known != account) {
+        uses.bumps.push((account.to_string(), bump.map(|b| b.trim().to_string())));
+    }
+}
+
+// Pieces of `text` split on `separator` outside of any brackets
+fn top_level(text: &str, separator: char) -> impl Iterator<Item = &str> {
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut pieces = Vec::new();
+    for (i, c) in text.char_indices().chain([(text.len(), separator)]) {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            c if c == separator && depth == 0 => {
+                pieces.push(&text[start..i]);
+                start = (i + c.len_utf8()).min(text.len());
+            }
+            _ => {}
+        }
+    }
+    pieces.into_iter().filter(|piece| !piece.trim().is_empty())
+}
+
+// Text up to the bracket closing the one just before `text`
+fn delimited(text: &str) -> &str {
+    let mut depth = 0usize;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' if depth == 0 => return &text[..i],
+            ')' | ']' | '}' => depth -= 1,
+            _ => {}
+        }
+    }
+    text
+}
+
+// `to: position,` -> `position`, only at the top level of the arguments
+fn named_arg<'a>(args: &'a str, name: &str) -> Option<&'a str> {
+    let mut depth = 0usize;
+    let mut start = 0;
+    for (i, c) in args.char_indices().chain([(args.len(), ',')]) {
+        match c {
+            '(' | '[' | '{' => depth += 1,
+            ')' | ']' | '}' => depth = depth.saturating_sub(1),
+            ',' if depth == 0 => {
+                let (key, value) = args[start..i].split_once(':').unzip();
+                if key.map(str::trim) == Some(name) {
+                    return value.map(str::trim);
+                }
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+// `Position::LEN as u64`, `Position::LEN + EXTRA` or `space_with_headroom!(Position, EXTRA)`
+// -> `Position`
+fn space_type(space: &str) -> Option<&str> {
+    if let Some((_, args)) = space.split_once("space_with_headroom!(") {
+        return Some(last_segment(args.split(',').next()?));
+    }
+    let space = space.split(" as ").next()?.split('+').next()?.trim();
+    Some(last_segment(space.strip_suffix("::LEN")?))
+}
+
+// `state::Position` -> `Position`
+fn last_segment(path: &str) -> &str {
+    path.trim().rsplit("::").next().unwrap_or_default().trim()
+}
+
+// Position in the conventional order, lower comes first
+fn account_rank(account: &AccountMeta) -> (u8, &'static str) {
+    let name = account.name.as_str();
+    if account.attrs.iter().any(|a| a == "signer") {
+        (0, "signer")
+    } else if name == "program"
+        || name.ends_with("_program")
+        || name.ends_with("_sysvar")
+        || matches!(name, "rent" | "clock")
+    {
+        (3, "program/sysvar")
+    } else if account.attrs.iter().any(|a| a == "writable") {
+        (1, "writable")
+    } else {
+        (2, "read-only")
+    }
+}
+
+// The messages of every lint `jiminy.toml` turns on, in the order of `[lint]`'s keys
+fn lints(root: &Path, lint: &LintConfig, instructions: &[InstructionMeta]) -> Vec<String> {
+    let mut messages = Vec::new();
+    if lint.accounts {
+        messages.extend(lint_accounts(instructions, lint));
+    }
+    if lint.create_space {
+        messages.extend(lint_create_space(instructions));
+    }
+    if lint.byte_order {
+        messages.extend(byte_order::lint(root, lint));
+    }
+    if lint.recipients {
+        messages.extend(lint_recipients(instructions));
+    }
+    if lint.unused_accounts {
+        messages.extend(lint_unused_accounts(instructions));
+    }
+    messages
+}
+
+fn report_lints(messages: &[String], strict: bool) {
+    if strict && !messages.is_empty() {
+        panic!("jiminy lint failed:\n{}", messages.join("\n"));
+    }
+    for message in messages {
+        println!("cargo:warning={message}");
+    }
+}
+
+fn parse_field_line(line: &str) -> Option<FieldMeta> {
+    let parts: Vec<&str> = line.split(':').collect();
+    if parts.len() < 2 {
+        return None;
+    }
+
+    let name = parts[0].trim().to_string();
+    let field_type = parts[1].trim().trim_end_matches(',');
+    // `side: u8 => bool` keeps the wire type in the data struct but shows `bool` in the IDL
+    let (wire_type, field_type) = match field_type.split_once("=>") {
+        Some((wire_type, idl_type)) => (wire_type.trim().to_string(), idl_type.trim().to_string()),
+        None => (field_type.to_string(), field_type.to_string()),
+    };
+
+    Some(FieldMeta {
+        name,
+        field_type,
+        wire_type,
+        docs: Vec::new(),
+    })
+}
+
+#[derive(Debug)]
+struct ErrorMeta {
+    name: String,
+    variants: Vec<ErrorVariant>,
+}
+
+#[derive(Debug)]
+struct ErrorVariant {
+    name: String,
+    code: u32,
+    docs: Vec<String>,
+}
+
+/// A `define_enum!` type, usable as an instruction data field
+#[derive(Debug)]
+struct EnumMeta {
+    name: String,
+    docs: Vec<String>,
+    /// `(variant, value)` in declaration order
+    variants: Vec<(String, String)>,
+}
+
+#[derive(Debug)]
+struct StateMeta {
+    name: String,
+    /// Module path of the file defining it, e.g. `crate::state`
+    module: String,
+    fields: Vec<StateFieldMeta>,
+}
+
+#[derive(Debug)]
+struct StateFieldMeta {
+    name: String,
+    field_type: String,
+}
+
+fn extract_error_metadata(root: &Path, settings: &Settings) -> Vec<ErrorMeta> {
+    let mut errors = Vec::new();
+
+    // src/error.rs is optional, configured paths must exist
+    let default_path = "src/error.rs".to_string();
+    let paths = std::iter::once((&default_path, false))
+        .chain(settings.error_paths.iter().map(|path| (path, true)));
+
+    for (path, required) in paths {
+        println!("cargo:rerun-if-changed={}", root.join(path).display());
+        let content = match fs::read_to_string(root.join(path)) {
+            Ok(content) => content,
+            Err(e) if required => panic!("jiminy.toml: cannot read error path `{path}`: {e}"),
+            Err(_) => continue,
+        };
+
+        // Look for every define_errors! macro call, skipping mentions in comments
+        for (start, _) in content.match_indices("define_errors!") {
+            let line_start = content[..start].rfind('\n').map_or(0, |i| i + 1);
+            if content[line_start..start].trim_start().starts_with("//") {
+                continue;
+            }
+            if let Some(mut error_meta) = parse_error_macro(&content[start..]) {
+                for variant in &mut error_meta.variants {
+                    variant.code = variant.code.checked_add(settings.error_base).unwrap_or_else(|| {
+                        panic!("error code {} + `errors.base` overflows a u32", variant.code)
+                    });
+                }
+                errors.push(error_meta);
+            }
+        }
+    }
+
+    check_error_codes(&errors);
+    errors
+}
+
+// `jiminy::error::RESERVED_CODES`
+const JIMINY_ERROR_CODES: std::ops::RangeInclusive<u32> = 100..=199;
+
+// Every enum converts to `ProgramError::Custom(code)`, so codes must be unique across enums
+// and stay out of the range `jiminy::error::JiminyError` returns from the same program
+fn check_error_codes(errors: &[ErrorMeta]) {
+    let mut seen: Vec<(u32, String)> = Vec::new();
+    for (i, error) in errors.iter().enumerate() {
+        if errors[..i].iter().any(|other| other.name == error.name) {
+            panic!("error enum `{}` is defined more than once", error.name);
+        }
+        for variant in &error.variants {
+            let path = format!("{}::{}", error.name, variant.name);
+            if JIMINY_ERROR_CODES.contains(&variant.code) {
+                panic!(
+                    "error code {} of `{path}` is reserved for `JiminyError` ({}..={})",
+                    variant.code,
+                    JIMINY_ERROR_CODES.start(),
+                    JIMINY_ERROR_CODES.end()
+                );
+            }
+            if let Some((_, other)) = seen.iter().find(|(code, _)| *code == variant.code) {
+                panic!(
+                    "error code {} is used by both `{other}` and `{path}`",
+                    variant.code
+                );
+            }
+            seen.push((variant.code, path));
+        }
+    }
+}
+
+// The enum whose `InvalidDiscriminator` the dispatch returns for unknown instructions
+fn resolve_error_owner<'a>(errors: &'a [ErrorMeta], settings: &Settings) -> Option<&'a str> {
+    let declares = |error: &ErrorMeta| {
+        error
+            .variants
+            .iter()
+            .any(|variant| variant.name == "InvalidDiscriminator")
+    };
+
+    if let Some(owner) = &settings.invalid_discriminator {
+        let error = errors
+            .iter()
+            .find(|error| &error.name == owner)
+            .unwrap_or_else(|| panic!("jiminy.toml: error enum `{owner}` not found"));
+        if !declares(error) {
+            panic!("jiminy.toml: error enum `{owner}` has no `InvalidDiscriminator` variant");
+        }
+        return Some(&error.name);
+    }
+
+    let owners: Vec<&ErrorMeta> = errors.iter().filter(|error| declares(error)).collect();
+    match owners.as_slice() {
+        [] if errors.is_empty() => None,
+        [] => panic!(
+            "no error enum declares `InvalidDiscriminator`, add it to one of {}",
+            errors.iter().map(|e| e.name.as_str()).collect::<Vec<_>>().join(", ")
+        ),
+        [owner] => Some(&owner.name),
+        _ => panic!(
+            "`InvalidDiscriminator` is declared by {}, pick one with `errors.invalid_discriminator` in jiminy.toml",
+            owners.iter().map(|e| e.name.as_str()).collect::<Vec<_>>().join(", ")
+        ),
+    }
+}
+
+fn parse_error_macro(content: &str) -> Option<ErrorMeta> {
+    // Find the macro content between braces
+    let start = content.find('{')?;
+    let mut brace_count = 0;
+    let mut in_macro = false;
+    let mut macro_content = String::new();
+
+    for ch in content[start..].chars() {
+        if ch == '{' {
+            brace_count += 1;
+            in_macro = true;
+        } else if ch == '}' {
+            brace_count -= 1;
+        }
+
+        if in_macro {
+            macro_content.push(ch);
+        }
+
+        if brace_count == 0 && in_macro {
+            break;
+        }
+    }
+
+    // Parse the macro content
+    let lines: Vec<&str> = macro_content.lines().collect();
+    let mut error_name = String::new();
+    let mut variants = Vec::new();
+    let mut docs = Vec::new();
+
+    for line in lines {
+        let line = line.trim();
+
+        // Doc comments belong to the next variant, plain comments are dropped
+        if let Some(doc) = line.strip_prefix("///") {
+            docs.push(doc.trim().to_string());
+            continue;
+        } else if line.starts_with("//") {
+            continue;
+        }
+
+        // First non-empty line after { should be the error type name
+        if error_name.is_empty() && !line.is_empty() && !line.starts_with('{') {
+            error_name = line.trim_end_matches(',').to_string();
+            continue;
+        }
+
+        // Parse error variants: "ErrorName = code,"
+        if line.contains('=') && !line.starts_with('{') && !line.starts_with('}') {
+            if let Some((name, code)) = line.split_once('=') {
+                let name = name.trim().to_string();
+                if let Ok(code) = code.trim().trim_end_matches(',').parse::<u32>() {
+                    variants.push(ErrorVariant {
+                        name,
+                        code,
+                        docs: std::mem::take(&mut docs),
+                    });
+                }
+            }
+        }
+    }
+
+    if !error_name.is_empty() && !variants.is_empty() {
+        Some(ErrorMeta {
+            name: error_name,
+            variants,
+        })
+    } else {
+        None
+    }
+}
+
+fn extract_state_metadata(root: &Path) -> Vec<StateMeta> {
+    let mut state_structs = Vec::new();
+    let src_dir = root.join("src");
+
+    // Find all state files
+    let state_dir = src_dir.join("state");
+    if state_dir.exists() {
+        for path in sorted_entries(&state_dir) {
+            if path.extension().and_then(|s| s.to_str()) == Some("rs") {
+                if let Some(structs) = parse_state_file(&path, &src_dir) {
+                    state_structs.extend(structs);
+                }
+            }
+        }
+    }
+
+    // Also check for state definitions in other source files
+    if src_dir.exists() {
+        for path in sorted_entries(&src_dir) {
+            if path.extension().and_then(|s| s.to_str()) == Some("rs") {
+                let filename = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+                if filename != "lib.rs" && filename != "generated.rs" && filename != "error.rs" {
+                    if let Some(structs) = parse_state_file(&path, &src_dir) {
+                        state_structs.extend(structs);
+                    }
+                }
+            }
+        }
+    }
+
+    state_structs
+}
+
+// Same files as the state structs: `src/state` and the other top-level modules
+fn state_sources(root: &Path) -> Vec<String> {
+    let src_dir = root.join("src");
+    let state_dir = src_dir.join("state");
+    let mut paths = Vec::new();
+    if state_dir.exists() {
+        paths.extend(sorted_entries(&state_dir));
+    }
+    if src_dir.exists() {
+        paths.extend(sorted_entries(&src_dir).into_iter().filter(|path| {
+            let filename = path.file_name().and_then(|s| s.to_str()).unwrap_or("");
+            !matches!(filename, "lib.rs" | "generated.rs" | "error.rs")
+        }));
+    }
+    paths
+        .iter()
+        .filter(|path| path.extension().and_then(|s| s.to_str()) == Some("rs"))
+        .filter_map(|path| fs::read_to_string(path).ok())
+        .collect()
+}
+
+fn extract_enum_metadata(root: &Path) -> Vec<EnumMeta> {
+    let mut enums = Vec::new();
+    for content in state_sources(root) {
+        for (start, _) in content.match_indices("define_enum!") {
+            if let Some(meta) = parse_define_enum_macro(&content[start..]) {
+                enums.push(meta);
+            }
+        }
+    }
+    enums
+}
+
+/// `define_events!` structs, laid out like states, for the IDL's `events`
+fn extract_event_metadata(root: &Path) -> Vec<StateMeta> {
+    let mut events = Vec::new();
+    for content in state_sources(root) {
+        for (start, _) in content.match_indices("define_events!") {
+            events.extend(parse_define_state_macro(&content[start..]).unwrap_or_default());
+        }
+    }
+    events
+}
+
+fn parse_define_enum_macro(content: &str) -> Option<EnumMeta> {
+    let body = &content[content.find('{')? + 1..];
+    let body = delimited(body);
+    let mut docs = Vec::new();
+    let mut name = None;
+    let mut variants = Vec::new();
+    for line in body.lines().map(str::trim) {
+        if name.is_none() {
+            if let Some(doc) = line.strip_prefix("///") {
+                docs.push(doc.trim().to_string());
+            } else if let Some((_, rest)) = line.split_once("enum ") {
+                name = Some(rest.trim_end_matches('{').trim().to_string());
+            }
+            continue;
+        }
+        if let Some((variant, value)) = line.trim_end_matches(',').split_once('=') {
+            variants.push((variant.trim().to_string(), value.trim().to_string()));
+        }
+    }
+    Some(EnumMeta {
+        name: name?,
+        docs,
+        variants,
+    })
+}
+
+// `read_dir` order is platform dependent, sort so view discriminators and the output are stable
+fn sorted_entries(dir: &Path) -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    paths.sort();
+    paths
+}
+
+fn parse_state_file(path: &Path, src_dir: &Path) -> Option<Vec<StateMeta>> {
+    let content = fs::read_to_string(path).ok()?;
+    let mut state_structs = Vec::new();
+    let module = module_path(path, src_dir);
+
+    // Look for define_state! macro calls
+    let mut start_pos = 0;
+    while let Some(start) = content[start_pos..].find("define_state!") {
+        let actual_start = start_pos + start;
+        if let Some(state_meta) = parse_define_state_macro(&content[actual_start..]) {
+            state_structs.extend(state_meta.into_iter().map(|state| StateMeta {
+                module: module.clone(),
+                ..state
+            }));
+        }
+        start_pos = actual_start + 1;
+    }
+
+    // `define_marker!(Name, seeds: [..])` is a state holding just its bump
+    for (start, _) in content.match_indices("define_marker!(") {
+        let rest = &content[start + "define_marker!(".len()..];
+        let name = rest.split(',').next().unwrap_or_default().trim();
+        if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            continue;
+        }
+        state_structs.push(StateMeta {
+            name: name.to_string(),
+            module: module.clone(),
+            fields: vec![StateFieldMeta {
+                name: "bump".to_string(),
+                field_type: "u8".to_string(),
+            }],
+        });
+    }
+
+    if state_structs.is_empty() {
+        None
+    } else {
+        Some(state_structs)
+    }
+}
+
+// `src/state/mod.rs` and `src/state.rs` are `crate::state`, `src/state/vote.rs` is `crate::state::vote`
+fn module_path(path: &Path, src_dir: &Path) -> String {
+    let relative = path.strip_prefix(src_dir).unwrap_or(path).with_extension("");
+    let mut module = String::from("crate");
+    for part in relative.iter().filter_map(|part| part.to_str()) {
+        if part != "mod" {
+            module.push_str("::");
+            module.push_str(part);
+        }
+    }
+    module
+}
+
+// The canonical discriminator followed by the deprecated aliases
+fn discriminators(instruction: &InstructionMeta) -> impl Iterator<Item = u8> + '_ {
+    std::iter::once(instruction.discriminator).chain(instruction.aliases.iter().copied())
+}
+
+/// Panic when two instructions, or an instruction and another's alias, share a discriminator
+fn check_discriminators(instructions: &[InstructionMeta]) {
+    let mut owners: [Option<&str>; 256] = [None; 256];
+    for instruction in instructions {
+        for discriminator in discriminators(instruction) {
+            match owners[discriminator as usize] {
+                Some(owner) if owner == instruction.name => panic!(
+                    "`{owner}` lists discriminator {discriminator} more than once"
+                ),
+                Some(owner) => panic!(
+                    "discriminator {discriminator} is used by both `{owner}` and `{}`",
+                    instruction.name
+                ),
+                None => owners[discriminator as usize] = Some(&instruction.name),
+            }
+        }
+    }
+}
+
+// First discriminator of the range reserved for generated view instructions
+const VIEW_DISCRIMINATOR_BASE: u8 = 200;
+
+/// One read-only `View<State>` instruction per state struct, built with the `views` feature
+fn view_instructions(
+    state_structs: &[StateMeta],
+    instructions: &[InstructionMeta],
+) -> Vec<InstructionMeta> {
+    if let Some((taken, discriminator)) = instructions.iter().find_map(|i| {
+        discriminators(i)
+            .find(|&d| d >= VIEW_DISCRIMINATOR_BASE)
+            .map(|d| (i, d))
+    }) {
+        panic!(
+            "`{}` uses discriminator {discriminator}, {VIEW_DISCRIMINATOR_BASE}..=255 is reserved for view instructions",
+            taken.name
+        );
+    }
+
+    state_structs
+        .iter()
+        .enumerate()
+        .map(|(i, state)| {
+            let discriminator = u8::try_from(i)
+                .ok()
+                .and_then(|i| VIEW_DISCRIMINATOR_BASE.checked_add(i))
+                .unwrap_or_else(|| panic!("too many state structs for the view discriminator range"));
+            InstructionMeta {
+                name: format!("View{}", state.name),
+                desc: None,
+                discriminator,
+                aliases: Vec::new(),
+                accounts: vec![AccountMeta {
+                    name: snake_case(&state.name),
+                    index: 0,
+                    desc: format!("{} account to read", state.name),
+                    account_type: "program".to_string(),
+                    attrs: Vec::new(),
+                    type_arg: None,
+                    label: None,
+                    explorer_hint: None,
+                }],
+                fields: Vec::new(),
+                rest: None,
+                remaining_accounts: None,
+                strict_data: true,
+                data_parser: None,
+                transfers: Vec::new(),
+                invariants: Vec::new(),
+                returns: Some(state.name.clone()),
+                expiry: None,
+                deprecated_after_slot: None,
+                view_of: Some(format!("{}::{}", state.module, state.name)),
+                state_uses: StateUses::default(),
+            }
+        })
+        .collect()
+}
+
+// View instructions live in their own generated module, e.g. `view_vote`
+fn push_view_module(code: &mut String, instruction: &InstructionMeta, state_path: &str) {
+    let account = &instruction.accounts[0].name;
+    code.push_str(&format!(
+        "// Read-only view of `{state_path}`, returns the account data as return data\n"
+    ));
+    code.push_str("#[cfg(feature = \"views\")]\n");
+    code.push_str(&format!("pub mod {} {{\n", snake_case(&instruction.name)));
+    code.push_str("    jiminy::define_instruction_with_metadata!(\n");
+    code.push_str(&format!("        discriminant: {},\n", instruction.discriminator));
+    code.push_str(&format!("        {},\n", instruction.name));
+    code.push_str("        accounts: {\n");
+    code.push_str(&format!(
+        "            {account}: program, desc: \"{}\",\n",
+        instruction.accounts[0].desc
+    ));
+    code.push_str("        },\n");
+    code.push_str("        data: {},\n");
+    code.push_str(&format!("        returns: {state_path},\n"));
+    code.push_str("        process: {\n");
+    code.push_str("            // Checked shared borrow, the account is never written\n");
+    code.push_str(&format!("            let data = {account}.try_borrow_data()?;\n"));
+    code.push_str(&format!(
+        "            bytemuck::try_from_bytes::<{state_path}>(&data)\n"
+    ));
+    code.push_str("                .copied()\n");
+    code.push_str("                .map_err(|_| ProgramError::InvalidAccountData)\n");
+    code.push_str("        }\n");
+    code.push_str("    );\n");
+    code.push_str("}\n\n");
+}
+
+// Path of the instruction type the dispatch calls
+fn instruction_path(instruction: &InstructionMeta) -> String {
+    match instruction.view_of {
+        Some(_) => format!("{}::{}Instruction", snake_case(&instruction.name), instruction.name),
+        None => format!("crate::instructions::{}Instruction", instruction.name),
+    }
+}
+
+// View instructions are only compiled with the `views` feature
+fn cfg_prefix(instruction: &InstructionMeta, indent: &str) -> String {
+    match instruction.view_of {
+        Some(_) => format!("{indent}#[cfg(feature = \"views\")]\n"),
+        None => String::new(),
+    }
+}
+
+fn parse_define_state_macro(content: &str) -> Option<Vec<StateMeta>> {
+    // Find the macro content between braces
+    let start = content.find('{')?;
+    let mut brace_count = 0;
+    let mut in_macro = false;
+    let mut macro_content = String::new();
+
+    for ch in content[start..].chars() {
+        if ch == '{' {
+            brace_count += 1;
+            in_macro = true;
+        } else if ch == '}' {
+            brace_count -= 1;
+        }
+
+        if in_macro {
+            macro_content.push(ch);
+        }
+
+        if brace_count == 0 && in_macro {
+            break;
+        }
+    }
+
+    // Parse the macro content for struct definitions
+    let mut structs = Vec::new();
+    let lines: Vec<&str> = macro_content.lines().collect();
+
+    let mut current_struct: Option<StateMeta> = None;
+    let mut in_struct = false;
+
+    for line in lines {
+        let line = line.trim();
+
+        // Look for struct definition: "pub struct StructName {"
+        if line.starts_with("pub struct") && line.contains('{') {
+            if let Some(struct_name) = extract_struct_name(line) {
+                current_struct = Some(StateMeta {
+                    name: struct_name,
+                    module: String::new(),
+                    fields: Vec::new(),
+                });
+                in_struct = true;
+            }
+            continue;
+        }
+
+        // End of struct
+        if line == "}" && in_struct {
+            if let Some(state_struct) = current_struct.take() {
+                structs.push(state_struct);
+            }
+            in_struct = false;
+            continue;
+        }
+
+        // Parse field lines: "pub field_name: field_type,"
+        if in_struct && line.starts_with("pub ") && line.contains(':') {
+            if let Some(field) = parse_state_field_line(line) {
+                if let Some(ref mut state_struct) = current_struct {
+                    state_struct.fields.push(field);
+                }
+            }
+        }
+    }
+
+    if structs.is_empty() {
+        None
+    } else {
+        Some(structs)
+    }
+}
+
+fn extract_struct_name(line: &str) -> Option<String> {
+    // Parse "pub struct StructName {"
+    let parts: Vec<&str> = line.split_whitespace().collect();
+    if parts.len() >= 3 && parts[0] == "pub" && parts[1] == "struct" {
+        let name = parts[2].trim_end_matches('{').trim();
+        Some(name.to_string())
+    } else {
+        None
+    }
+}
+
+fn parse_state_field_line(line: &str) -> Option<StateFieldMeta> {
+    // Parse "pub field_name: field_type,"
+    if let Some(colon_pos) = line.find(':') {
+        let field_part = &line[..colon_pos];
+        let type_part = &line[colon_pos + 1..];
+
+        let field_name = field_part.trim().strip_prefix("pub ")?.trim();
+        let field_type = type_part.trim().trim_end_matches(',');
+
+        Some(StateFieldMeta {
+            name: field_name.to_string(),
+            field_type: field_type.to_string(),
+        })
+    } else {
+        None
+    }
+}
+
+// `SIZE`, `space()` and rent helpers on a client account type, checked against the state's `LEN`
+fn push_account_size(code: &mut String, state: &StateMeta, size: Option<usize>) {
+    let name = &state.name;
+    let state_len = format!("{}::{name}::LEN", state.module);
+    code.push_str(&format!("impl {name} {{\n"));
+    code.push_str("    /// Data bytes of the account, what `create_pda!` allocates\n");
+    match size {
+        Some(size) => code.push_str(&format!("    pub const SIZE: usize = {size};\n")),
+        None => code.push_str(&format!("    pub const SIZE: usize = {state_len};\n")),
+    }
+    code.push_str("    /// Rent-exempt minimum at the default rent\n");
+    code.push_str("    pub const RENT_EXEMPT_LAMPORTS_AT_DEFAULT_RATE: u64 =\n");
+    code.push_str(
+        "        Self::rent_exempt_lamports(jiminy::rent::DEFAULT_LAMPORTS_PER_BYTE_YEAR);\n\n",
+    );
+    code.push_str("    /// Bytes to allocate when creating the account\n");
+    code.push_str("    pub const fn space() -> usize {\n");
+    code.push_str("        Self::SIZE\n");
+    code.push_str("    }\n\n");
+    code.push_str("    /// Rent-exempt minimum at `lamports_per_byte_year`\n");
+    code.push_str("    pub const fn rent_exempt_lamports(lamports_per_byte_year: u64) -> u64 {\n");
+    code.push_str("        jiminy::rent::exempt_lamports(Self::space(), lamports_per_byte_year)\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+    // The client size comes from the field types, the program's from the real layout
+    if size.is_some() {
+        code.push_str("const _: () = assert!(\n");
+        code.push_str(&format!("    {name}::space() == {state_len},\n"));
+        code.push_str(&format!("    \"{name} size differs from the program\"\n"));
+        code.push_str(");\n\n");
+    }
+}
+
+// A test per state struct pinning its size, field offsets and bytes, see
+// `jiminy::testing::check_layout`
+fn push_layout_tests(
+    code: &mut String,
+    state_structs: &[StateMeta],
+    enums: &[EnumMeta],
+    layout: &LayoutConfig,
+) {
+    for (state, _) in &layout.versions {
+        if !state_structs.iter().any(|s| s.name == *state) {
+            panic!("jiminy.toml: `layout.versions.{state}` names no state struct");
+        }
+    }
+    code.push_str("\n// Layout regression tests, `[layout] golden` in jiminy.toml\n");
+    code.push_str("#[cfg(test)]\n");
+    code.push_str("mod layout_tests {\n");
+    for (index, state) in state_structs.iter().enumerate() {
+        let path = format!("{}::{}", state.module, state.name);
+        let version = layout
+            .versions
+            .iter()
+            .find(|(name, _)| *name == state.name)
+            .map_or(1, |(_, version)| *version);
+        if index > 0 {
+            code.push('\n');
+        }
+        code.push_str("    #[test]\n");
+        code.push_str(&format!("    fn {}_layout() {{\n", snake_case(&state.name)));
+        // Sizes the build script can't work out are left to the golden file
+        if let Some(offsets) = fixtures::field_offsets(state_structs, enums, state) {
+            let size = fixtures::state_size(state_structs, enums, state).unwrap_or_default();
+            code.push_str(&format!(
+                "        assert_eq!(core::mem::size_of::<{path}>(), {size});\n"
+            ));
+            for (field, offset) in state.fields.iter().zip(offsets) {
+                code.push_str(&format!(
+                    "        assert_eq!(core::mem::offset_of!({path}, {}), {offset});\n",
+                    field.name
+                ));
+            }
+        }
+        code.push_str(&format!("        jiminy::testing::check_layout::<{path}>(\n"));
+        code.push_str("            concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/layouts\"),\n");
+        code.push_str(&format!("            {version},\n"));
+        code.push_str("        );\n");
+        code.push_str("    }\n");
+    }
+    code.push_str("}\n");
+}
+
+fn generate_program_code(
+    instructions: &[InstructionMeta],
+    errors: &[ErrorMeta],
+    error_owner: Option<&str>,
+    error_base: u32,
+    state_structs: &[StateMeta],
+    enums: &[EnumMeta],
+    headroom: &[(String, usize)],
+) -> String {
+    let mut code = String::new();
+
+    code.push_str("use shank::ShankInstruction;\n");
+    if !errors.is_empty() {
+        code.push_str("use shank::ShankType;\n");
+        code.push_str("use pinocchio::program_error::ProgramError;\n");
+    }
+    code.push('\n');
+
+    // Since shank looks for declare_id! patterns in all source files,
+    // and we already have pinocchio_pubkey::declare_id! in lib.rs,
+    // we don't need to add another one here. Shank should parse the existing one.
+    // If it can't, we can override with the -p flag when running shank idl.\n");
+
+    // Generate error enums first
+    if !errors.is_empty() {
+        push_error_decoding(&mut code, error_base);
+    }
+    for error in errors {
+        code.push_str(&format!("// Generated error enum: {}\n", error.name));
+        code.push_str("#[derive(Clone, Debug, PartialEq, ShankType)]\n");
+        // New variants must not break clients that match on the enum
+        code.push_str("#[non_exhaustive]\n");
+        code.push_str(&format!("pub enum {} {{\n", error.name));
+
+        for variant in &error.variants {
+            for doc in &variant.docs {
+                code.push_str(&format!("    /// {doc}\n"));
+            }
+            code.push_str(&format!("    {} = {},\n", variant.name, variant.code));
+        }
+
+        code.push_str("}\n\n");
+
+        // Display shows the variant's doc comment, or its name when it has none
+        code.push_str(&format!("impl core::fmt::Display for {} {{\n", error.name));
+        code.push_str("    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {\n");
+        code.push_str("        f.write_str(match self {\n");
+        for variant in &error.variants {
+            let message = if variant.docs.is_empty() {
+                variant.name.clone()
+            } else {
+                variant.docs.join(" ")
+            };
+            code.push_str(&format!(
+                "            Self::{} => {:?},\n",
+                variant.name, message
+            ));
+        }
+        code.push_str("        })\n");
+        code.push_str("    }\n");
+        code.push_str("}\n\n");
+
+        // Generate From implementation
+        code.push_str(&format!("impl From<{}> for ProgramError {{\n", error.name));
+        code.push_str(&format!("    fn from(e: {}) -> Self {{\n", error.name));
+        code.push_str("        Self::Custom(e as u32)\n");
+        code.push_str("    }\n");
+        code.push_str("}\n\n");
+
+        // What `error_owner` hands the dispatch for unknown discriminators
+        if error.variants.iter().any(|variant| variant.name == "InvalidDiscriminator") {
+            code.push_str(&format!(
+                "impl jiminy::dispatch::HasInvalidDiscriminator for {} {{\n",
+                error.name
+            ));
+            code.push_str("    const INVALID_DISCRIMINATOR: Self = Self::InvalidDiscriminator;\n");
+            code.push_str("}\n\n");
+        }
+
+        // The way back from a `Custom` code, for `decode_error`
+        code.push_str(&format!("impl TryFrom<u32> for {} {{\n", error.name));
+        code.push_str("    type Error = u32;\n\n");
+        code.push_str("    fn try_from(code: u32) -> Result<Self, u32> {\n");
+        code.push_str("        match code {\n");
+        for variant in &error.variants {
+            code.push_str(&format!(
+                "            {} => Ok(Self::{}),\n",
+                variant.code, variant.name
+            ));
+        }
+        code.push_str("            _ => Err(code),\n");
+        code.push_str("        }\n");
+        code.push_str("    }\n");
+        code.push_str("}\n\n");
+    }
+
+    // Only generate if we have instructions
+    if instructions.is_empty() {
+        code.push_str("// No instructions found - using fallback\n");
+        code.push_str("pub enum ProgramInstructions {}\n\n");
+        code.push_str("pub fn process_instruction(_program_id: &pinocchio::pubkey::Pubkey, _accounts: &[pinocchio::account_info::AccountInfo], _instruction_data: &[u8]) -> pinocchio::ProgramResult {\n");
+        code.push_str("    Err(pinocchio::program_error::ProgramError::InvalidInstructionData)\n");
+        code.push_str("}\n");
+        return code;
+    }
+
+    let schema_types = || {
+        instructions
+            .iter()
+            .filter(|i| i.data_parser.is_some())
+            .flat_map(|i| &i.fields)
+            .map(|f| f.field_type.as_str())
+    };
+    if instructions.iter().any(|i| i.rest.is_some()) || schema_types().any(|t| t == "Vec<u8>") {
+        code.push_str("extern crate alloc;\n");
+        code.push_str("use alloc::vec::Vec;\n");
+        if schema_types().any(|t| t == "String") {
+            code.push_str("use alloc::string::String;\n");
+        }
+        code.push('\n');
+    } else if schema_types().any(|t| t == "String") {
+        code.push_str("extern crate alloc;\n");
+        code.push_str("use alloc::string::String;\n\n");
+    }
+
+    // Generate shank enum
+    code.push_str("#[repr(u8)]\n");
+    code.push_str("#[derive(Clone, Debug, PartialEq, ShankInstruction)]\n");
+    code.push_str("pub enum ProgramInstructions {\n");
+
+    for instruction in instructions {
+        // Shank copies doc comments into the IDL `docs` arrays
+        if let Some(desc) = &instruction.desc {
+            code.push_str(&format!("    /// {desc}\n"));
+        }
+
+        // Document token transfers so they show up in the IDL docs
+        for transfer in &instruction.transfers {
+            code.push_str(&format!(
+                "    /// Transfers `{}` from `{}` to `{}`, authority `{}`{}\n",
+                transfer.amount,
+                transfer.from,
+                transfer.to,
+                transfer.authority,
+                if transfer.signed { " (PDA signed)" } else { "" }
+            ));
+        }
+
+        for invariant in &instruction.invariants {
+            let direction = match invariant.direction.as_str() {
+                "increases_by" => "increase",
+                "decreases_by" => "decrease",
+                other => other,
+            };
+            code.push_str(&format!(
+                "    /// Expects `{}` {} to {direction} by `{}`\n",
+                invariant.account, invariant.balance, invariant.amount
+            ));
+        }
+
+        if instruction.view_of.is_some() {
+            code.push_str(&format!(
+                "    /// Read-only view, only built with the `views` feature (discriminator {})\n",
+                instruction.discriminator
+            ));
+        }
+
+        // Only the canonical discriminator goes in the IDL, aliases are noted in the docs
+        if !instruction.aliases.is_empty() {
+            code.push_str(&format!(
+                "    /// Also accepts the deprecated discriminators {}\n",
+                join_discriminators(&instruction.aliases)
+            ));
+        }
+
+        // Nor deprecation, clients read it from `instructionDeprecation` too
+        if let Some(slot) = &instruction.deprecated_after_slot {
+            code.push_str(&format!("    /// Deprecated, stops running after slot {slot}\n"));
+        }
+
+        // Shank has no return types, so document it instead
+        if let Some(returns) = &instruction.returns {
+            code.push_str(&format!("    /// Returns `{returns}` as return data\n"));
+        }
+
+        // Nor remaining accounts
+        if let Some((name, desc)) = &instruction.remaining_accounts {
+            code.push_str(&format!("    /// Remaining accounts `{name}`: {desc}\n"));
+        }
+
+        // Add account attributes
+        for account in &instruction.accounts {
+            code.push_str(&format!("    #[account({}", account.index));
+            for attr in &account.attrs {
+                code.push_str(&format!(", {attr}"));
+            }
+            code.push_str(&format!(
+                ", name = \"{}\", desc = \"{}\")]\n",
+                account.name, account.desc
+            ));
+        }
+
+        // Add variant
+        code.push_str(&format!("    {} {{\n", instruction.name));
+        for field in &instruction.fields {
+            for doc in &field.docs {
+                code.push_str(&format!("        /// {doc}\n"));
+            }
+            code.push_str(&format!("        {}: {},\n", field.name, field.field_type));
+        }
+        // Trailing bytes show up as `bytes` in the IDL
+        if let Some(rest) = &instruction.rest {
+            code.push_str(&format!("        {rest}: Vec<u8>,\n"));
+        }
+        // Views sit in a reserved range rather than right after the other instructions
+        if instruction.view_of.is_some() {
+            code.push_str(&format!("    }} = {},\n\n", instruction.discriminator));
+        } else {
+            code.push_str("    },\n\n");
+        }
+    }
+    code.push_str("}\n\n");
+
+    push_codec(&mut code, instructions, enums);
+
+    // `define_enum!` types show up in the IDL types through these copies
+    for meta in enums {
+        for doc in &meta.docs {
+            code.push_str(&format!("/// {doc}\n"));
+        }
+        code.push_str("#[repr(u8)]\n");
+        code.push_str("#[derive(Clone, Copy, Debug, PartialEq, Eq, shank::ShankType)]\n");
+        code.push_str(&format!("pub enum {} {{\n", meta.name));
+        for (variant, value) in &meta.variants {
+            code.push_str(&format!("    {variant} = {value},\n"));
+        }
+        code.push_str("}\n\n");
+    }
+
+    // Generate ShankAccount definitions for state structs
+    code.push_str("// ShankAccount definitions for state structs\n");
+    code.push_str("// These are generated for IDL compatibility\n");
+
+    for state_struct in state_structs {
+        let size = fixtures::state_size(state_structs, enums, state_struct);
+        // Shank has no account sizes, so document it instead
+        if let Some(size) = size {
+            code.push_str(&format!("/// Account size: {size} bytes\n"));
+            if let Some(extra) = fixtures::headroom(headroom, &state_struct.name) {
+                code.push_str(&format!(
+                    "/// Allocated with {extra} bytes of headroom, {} bytes\n",
+                    size + extra
+                ));
+            }
+        }
+        code.push_str("#[repr(C)]\n");
+        code.push_str("#[derive(Clone, shank::ShankAccount)]\n");
+        code.push_str(&format!("pub struct {} {{\n", state_struct.name));
+
+        for field in &state_struct.fields {
+            // `FixedStr<N>` is stored as `[u8; N]` but shown as a string in the IDL
+            if let Some(capacity) = fixed_str_capacity(&field.field_type) {
+                code.push_str(&format!("    /// String, at most {capacity} bytes\n"));
+                code.push_str("    #[idl_type(\"string\")]\n");
+                code.push_str(&format!("    pub {}: [u8; {capacity}],\n", field.name));
+                continue;
+            }
+            // `PubkeyBytes` is a transparent `[u8; 32]`, shown as a public key
+            if field.field_type.rsplit("::").next() == Some("PubkeyBytes") {
+                code.push_str(&format!("    pub {}: pinocchio::pubkey::Pubkey,\n", field.name));
+                continue;
+            }
+            // `PodBool` is one byte, 0 or 1, like a borsh `bool`
+            if field.field_type.rsplit("::").next() == Some("PodBool") {
+                code.push_str(&format!("    pub {}: bool,\n", field.name));
+                continue;
+            }
+            // `PodOption<T>` is a tag byte then `T`, kept at full size when empty
+            if let Some(inner) = pod_option_inner(&field.field_type) {
+                let inner = match inner {
+                    "[u8; 32]" | "[u8;32]" | "PubkeyBytes" => "pinocchio::pubkey::Pubkey",
+                    other => le_int(other).unwrap_or(other),
+                };
+                code.push_str("    /// Tag byte, then the value, zeroed when empty\n");
+                code.push_str(&format!("    pub {}: Option<{inner}>,\n", field.name));
+                continue;
+            }
+            // `LeU16`/`LeU32`/`LeU64` have the layout of the integers they hold
+            if let Some(int) = le_int(&field.field_type) {
+                code.push_str(&format!("    pub {}: {int},\n", field.name));
+                continue;
+            }
+            // `jiminy::time::Deadline` is a kind byte and an 8-byte value
+            if field.field_type.rsplit("::").next() == Some("Deadline") {
+                code.push_str("    /// Deadline kind (0 unix timestamp, 1 slot, 2 epoch), then the little-endian value\n");
+                code.push_str(&format!("    pub {}: [u8; 9],\n", field.name));
+                continue;
+            }
+            code.push_str(&format!("    pub {}: {},\n", field.name, field.field_type));
+        }
+
+        code.push_str("}\n\n");
+        push_account_size(&mut code, state_struct, size);
+    }
+
+    // Use the owning error type if available, otherwise use a generic error
+    let invalid_discriminator = match error_owner {
+        Some(owner) => format!(
+            "Err(<{owner} as jiminy::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR.into())"
+        ),
+        None => "Err(pinocchio::program_error::ProgramError::InvalidInstructionData)".to_string(),
+    };
+
+    // Generate one thin handler per instruction so the dispatch table can point at it
+    code.push_str("pub type InstructionHandler = fn(\n");
+    code.push_str("    &[pinocchio::account_info::AccountInfo],\n");
+    code.push_str("    &[u8],\n");
+    code.push_str(") -> pinocchio::ProgramResult;\n\n");
+
+    for instruction in instructions {
+        if let Some(state_path) = &instruction.view_of {
+            push_view_module(&mut code, instruction, state_path);
+        }
+    }
+
+    for instruction in instructions {
+        code.push_str(&cfg_prefix(instruction, ""));
+        code.push_str(&format!("fn {}(\n", handler_name(&instruction.name)));
+        code.push_str("    accounts: &[pinocchio::account_info::AccountInfo],\n");
+        code.push_str("    data: &[u8],\n");
+        code.push_str(") -> pinocchio::ProgramResult {\n");
+        code.push_str(&format!(
+            "    {}::try_from((accounts, data))?.process()\n",
+            instruction_path(instruction)
+        ));
+        code.push_str("}\n\n");
+
+        // Deprecated aliases report themselves, then run the same handler
+        for alias in &instruction.aliases {
+            code.push_str(&format!(
+                "// Deprecated alias of discriminator {}\n",
+                instruction.discriminator
+            ));
+            code.push_str(&format!("fn {}_{alias}(\n", handler_name(&instruction.name)));
+            code.push_str("    accounts: &[pinocchio::account_info::AccountInfo],\n");
+            code.push_str("    data: &[u8],\n");
+            code.push_str(") -> pinocchio::ProgramResult {\n");
+            code.push_str(&format!(
+                "    jiminy::dispatch::deprecated_discriminator({alias}, {});\n",
+                instruction.discriminator
+            ));
+            code.push_str(&format!("    {}(accounts, data)\n", handler_name(&instruction.name)));
+            code.push_str("}\n\n");
+        }
+    }
+
+    push_instruction_index(&mut code, instructions);
+
+    // Generate the discriminator-indexed dispatch table
+    code.push_str("pub const DISPATCH: [Option<InstructionHandler>; 256] = {\n");
+    code.push_str("    let mut table: [Option<InstructionHandler>; 256] = [None; 256];\n");
+    for instruction in instructions {
+        let entry = format!(
+            "table[{}] = Some({});",
+            instruction.discriminator,
+            handler_name(&instruction.name)
+        );
+        // cfg can't sit on an assignment, so gated entries get a block
+        match instruction.view_of {
+            Some(_) => code.push_str(&format!("{}    {{ {entry} }}\n", cfg_prefix(instruction, "    "))),
+            None => code.push_str(&format!("    {entry}\n")),
+        }
+        for alias in &instruction.aliases {
+            code.push_str(&format!(
+                "    table[{alias}] = Some({}_{alias});\n",
+                handler_name(&instruction.name)
+            ));
+        }
+    }
+    code.push_str("    table\n");
+    code.push_str("};\n\n");
+
+    // Generate dispatch function
+    code.push_str("#[cfg(not(feature = \"match-dispatch\"))]\n");
+    push_process_instruction_header(&mut code);
+    code.push_str("    match instruction_data.split_first() {\n");
+    code.push_str(
+        "        Some((discriminator, data)) => match DISPATCH[*discriminator as usize] {\n",
+    );
+    code.push_str("            Some(handler) => handler(accounts, data),\n");
+    code.push_str(&format!("            None => {invalid_discriminator},\n"));
+    code.push_str("        },\n");
+    code.push_str(&format!("        None => {invalid_discriminator},\n"));
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+
+    // Chained match dispatch kept for comparison against the table
+    code.push_str("#[cfg(feature = \"match-dispatch\")]\n");
+    push_process_instruction_header(&mut code);
+    code.push_str("    match instruction_data.first() {\n");
+
+    for instruction in instructions {
+        code.push_str(&cfg_prefix(instruction, "        "));
+        code.push_str(&format!(
+            "        Some({}) => {{\n",
+            instruction.discriminator
+        ));
+        code.push_str(&format!(
+            "            {}::try_from((accounts, &instruction_data[1..]))?.process()\n",
+            instruction_path(instruction)
+        ));
+        code.push_str("        }\n");
+        for alias in &instruction.aliases {
+            code.push_str(&format!("        Some({alias}) => {{\n"));
+            code.push_str(&format!(
+                "            jiminy::dispatch::deprecated_discriminator({alias}, {});\n",
+                instruction.discriminator
+            ));
+            code.push_str(&format!(
+                "            {}::try_from((accounts, &instruction_data[1..]))?.process()\n",
+                instruction_path(instruction)
+            ));
+            code.push_str("        }\n");
+        }
+    }
+
+    code.push_str(&format!("        _ => {invalid_discriminator},\n"));
+    code.push_str("    }\n");
+    code.push_str("}\n");
+
+    code
+}
+
+/// `ProgramInstructions::pack`/`unpack`, encoding the fields through each
+/// instruction's data struct so they match the on-chain parsing
+fn push_codec(code: &mut String, instructions: &[InstructionMeta], enums: &[EnumMeta]) {
+    const ERROR: &str = "pinocchio::program_error::ProgramError";
+
+    code.push_str("/// Longest instruction data `ProgramInstructions::pack` writes, discriminator included\n");
+    code.push_str("pub const MAX_INSTRUCTION_DATA_LEN: usize = jiminy::dispatch::max_len(&[\n");
+    for instruction in instructions {
+        let mut len = String::from("1");
+        if instruction.data_parser.is_some() {
+            len.push_str(&format!(
+                " + jiminy::dispatch::rest_max_len({}::describe().data_parser)",
+                instruction_path(instruction)
+            ));
+        } else if !instruction.fields.is_empty() {
+            len.push_str(&format!(" + {}::LEN", data_path(instruction)));
+        }
+        if instruction.rest.is_some() {
+            len.push_str(&format!(
+                " + jiminy::dispatch::rest_max_len({}::describe().rest)",
+                instruction_path(instruction)
+            ));
+        }
+        code.push_str(&format!("    {len},\n"));
+    }
+    code.push_str("]);\n\n");
+
+    code.push_str("impl ProgramInstructions {\n");
+    code.push_str("    /// Instruction data for this instruction: the discriminator, the data struct's bytes (the borsh encoded schema for a `data_parser`), then any trailing bytes\n");
+    code.push_str("    ///\n");
+    code.push_str("    /// Panics if the trailing bytes are longer than the instruction's `max_len`.\n");
+    code.push_str("    pub fn pack(&self) -> ([u8; MAX_INSTRUCTION_DATA_LEN], usize) {\n");
+    code.push_str("        let mut buf = [0u8; MAX_INSTRUCTION_DATA_LEN];\n");
+    code.push_str("        let len = match self {\n");
+    for instruction in instructions {
+        let bindings: Vec<&str> = instruction
+            .fields
+            .iter()
+            .map(|field| field.name.as_str())
+            .chain(instruction.rest.as_deref())
+            .collect();
+        let pattern = if bindings.is_empty() {
+            String::from("{}")
+        } else {
+            format!("{{ {} }}", bindings.join(", "))
+        };
+        code.push_str(&format!("            Self::{} {pattern} => {{\n", instruction.name));
+        code.push_str(&format!("                buf[0] = {};\n", instruction.discriminator));
+        let mut end = String::from("1");
+        if instruction.data_parser.is_some() {
+            // Schema fields are borsh encoded in order, for the instruction's `data_parser`
+            code.push_str("                let mut writer = jiminy::borsh::Writer::new(&mut buf[1..]);\n");
+            for field in &instruction.fields {
+                let value = match field.field_type.as_str() {
+                    "String" => format!("{}.as_str()", field.name),
+                    "Vec<u8>" => format!("{}.as_slice()", field.name),
+                    _ => field.name.clone(),
+                };
+                code.push_str(&format!("                writer.put({value});\n"));
+            }
+            end = String::from("1 + writer.len()");
+        } else if !instruction.fields.is_empty() {
+            let data_path = data_path(instruction);
+            code.push_str(&format!("                let data = {data_path} {{\n"));
+            for field in &instruction.fields {
+                let value = if enums.iter().any(|e| e.name == field.field_type) {
+                    format!("*{} as u8", field.name)
+                } else if field.wire_type == field.field_type {
+                    format!("*{}", field.name)
+                } else if field.field_type == "bool" {
+                    format!("{}::from(*{})", field.wire_type, field.name)
+                } else {
+                    format!("(*{}).into()", field.name)
+                };
+                code.push_str(&format!("                    {}: {value},\n", field.name));
+            }
+            code.push_str("                };\n");
+            code.push_str(&format!("                let end = 1 + {data_path}::LEN;\n"));
+            code.push_str("                buf[1..end].copy_from_slice(bytemuck::bytes_of(&data));\n");
+            end = String::from("end");
+        }
+        match &instruction.rest {
+            Some(rest) => {
+                code.push_str(&format!(
+                    "                buf[{end}..{end} + {rest}.len()].copy_from_slice({rest});\n"
+                ));
+                code.push_str(&format!("                {end} + {rest}.len()\n"));
+            }
+            None => code.push_str(&format!("                {end}\n")),
+        }
+        code.push_str("            }\n");
+    }
+    code.push_str("        };\n");
+    code.push_str("        (buf, len)\n");
+    code.push_str("    }\n\n");
+
+    code.push_str("    /// Parse instruction data the way the program does, deprecated discriminators included\n");
+    code.push_str(&format!("    pub fn unpack(data: &[u8]) -> Result<Self, {ERROR}> {{\n"));
+    code.push_str(&format!(
+        "        let (&discriminator, data) = data.split_first().ok_or({ERROR}::InvalidInstructionData)?;\n"
+    ));
+    code.push_str("        Ok(match discriminator {\n");
+    for instruction in instructions {
+        code.push_str(&format!(
+            "            {} => {{\n",
+            join_patterns(instruction)
+        ));
+        let mut tail = "data";
+        if instruction.data_parser.is_some() {
+            code.push_str("                let mut reader = jiminy::borsh::Reader::new(data);\n");
+            for field in &instruction.fields {
+                let value = match field.field_type.as_str() {
+                    "String" => String::from("String::from(reader.read::<&str>()?)"),
+                    "Vec<u8>" => String::from("reader.read::<&[u8]>()?.to_vec()"),
+                    _ => String::from("reader.read()?"),
+                };
+                code.push_str(&format!("                let {} = {value};\n", field.name));
+            }
+            code.push_str("                reader.finish()?;\n");
+            code.push_str(&format!("                Self::{} {{\n", instruction.name));
+            for field in &instruction.fields {
+                code.push_str(&format!("                    {},\n", field.name));
+            }
+            code.push_str("                }\n");
+            code.push_str("            }\n");
+            continue;
+        } else if !instruction.fields.is_empty() {
+            let data_path = data_path(instruction);
+            code.push_str(&format!(
+                "                let fixed = data.get(..{data_path}::LEN).ok_or({ERROR}::InvalidInstructionData)?;\n"
+            ));
+            code.push_str(&format!(
+                "                let parsed: {data_path} = bytemuck::pod_read_unaligned(fixed);\n"
+            ));
+            code.push_str(&format!("                let tail = &data[{data_path}::LEN..];\n"));
+            tail = "tail";
+        }
+        match &instruction.rest {
+            Some(rest) => {
+                code.push_str(&format!(
+                    "                let max_len = jiminy::dispatch::rest_max_len({}::describe().rest);\n",
+                    instruction_path(instruction)
+                ));
+                if instruction.strict_data {
+                    code.push_str(&format!("                if {tail}.len() > max_len {{\n"));
+                    code.push_str("                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());\n");
+                    code.push_str("                }\n");
+                    code.push_str(&format!("                let {rest} = {tail}.to_vec();\n"));
+                } else {
+                    code.push_str(&format!(
+                        "                let {rest} = {tail}[..{tail}.len().min(max_len)].to_vec();\n"
+                    ));
+                }
+            }
+            None if instruction.strict_data => {
+                code.push_str(&format!("                if !{tail}.is_empty() {{\n"));
+                code.push_str("                    return Err(jiminy::error::JiminyError::TrailingInstructionData.into());\n");
+                code.push_str("                }\n");
+            }
+            None => code.push_str(&format!("                let _ = {tail};\n")),
+        }
+        code.push_str(&format!("                Self::{} {{\n", instruction.name));
+        for field in &instruction.fields {
+            let value = if let Some(meta) = enums.iter().find(|e| e.name == field.field_type) {
+                let arms: String = meta
+                    .variants
+                    .iter()
+                    .map(|(variant, value)| format!("{value} => {}::{variant}, ", meta.name))
+                    .collect();
+                format!(
+                    "match parsed.{} {{ {arms}_ => return Err(jiminy::error::JiminyError::InvalidEnumValue.into()) }}",
+                    field.name
+                )
+            } else if field.wire_type == field.field_type {
+                format!("parsed.{}", field.name)
+            } else if field.field_type == "bool" {
+                format!(
+                    "match parsed.{} {{ 0 => false, 1 => true, _ => return Err({ERROR}::InvalidInstructionData) }}",
+                    field.name
+                )
+            } else {
+                format!(
+                    "parsed.{}.try_into().map_err(|_| {ERROR}::InvalidInstructionData)?",
+                    field.name
+                )
+            };
+            code.push_str(&format!("                    {}: {value},\n", field.name));
+        }
+        if let Some(rest) = &instruction.rest {
+            code.push_str(&format!("                    {rest},\n"));
+        }
+        code.push_str("                }\n");
+        code.push_str("            }\n");
+    }
+    code.push_str(&format!("            _ => return Err({ERROR}::InvalidInstructionData),\n"));
+    code.push_str("        })\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+}
+
+// `3 | 13` matches the canonical discriminator and its deprecated aliases
+fn join_patterns(instruction: &InstructionMeta) -> String {
+    discriminators(instruction)
+        .map(|d| d.to_string())
+        .collect::<Vec<_>>()
+        .join(" | ")
+}
+
+// Path of the instruction's data struct, next to its instruction type
+fn data_path(instruction: &InstructionMeta) -> String {
+    let path = instruction_path(instruction);
+    format!("{}Data", path.strip_suffix("Instruction").unwrap_or(&path))
+}
+
+fn join_discriminators(discriminators: &[u8]) -> String {
+    discriminators
+        .iter()
+        .map(u8::to_string)
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+fn fixed_str_capacity(field_type: &str) -> Option<&str> {
+    // Parse "FixedStr<64>" (or a path ending in it) into "64"
+    let generics = field_type.rsplit("::").next()?.strip_prefix("FixedStr<")?;
+    Some(generics.strip_suffix('>')?.trim())
+}
+
+// "PodOption<[u8; 32]>" (or a path ending in it) -> "[u8; 32]"
+fn pod_option_inner(field_type: &str) -> Option<&str> {
+    let start = field_type.find("PodOption<")? + "PodOption<".len();
+    Some(field_type[start..].strip_suffix('>')?.trim())
+}
+
+// `LeU64` (or a path ending in it) -> `u64`
+fn le_int(field_type: &str) -> Option<&'static str> {
+    match field_type.rsplit("::").next()?.trim() {
+        "LeU16" => Some("u16"),
+        "LeU32" => Some("u32"),
+        "LeU64" => Some("u64"),
+        _ => None,
+    }
+}
+
+fn push_process_instruction_header(code: &mut String) {
+    code.push_str("pub fn process_instruction(\n");
+    code.push_str("    program_id: &pinocchio::pubkey::Pubkey,\n");
+    code.push_str("    accounts: &[pinocchio::account_info::AccountInfo],\n");
+    code.push_str("    instruction_data: &[u8],\n");
+    code.push_str(") -> pinocchio::ProgramResult {\n");
+    code.push_str("    if !jiminy::perf::keys_eq(program_id, &crate::ID) {\n");
+    code.push_str(
+        "        return Err(pinocchio::program_error::ProgramError::IncorrectProgramId);\n",
+    );
+    code.push_str("    }\n\n");
+    // Host tests count what they exercised, see `jiminy::testing::coverage_report`
+    code.push_str("    #[cfg(feature = \"host-stubs\")]\n");
+    code.push_str("    if let Some(discriminator) = instruction_data.first() {\n");
+    code.push_str("        jiminy::testing::record_coverage(*discriminator);\n");
+    code.push_str("    }\n\n");
+}
+
+/// `ERROR_BASE` and `decode_error()`, for clients telling programs' errors apart
+fn push_error_decoding(code: &mut String, error_base: u32) {
+    code.push_str("/// Added to every declared error code, `errors.base` in jiminy.toml\n");
+    code.push_str(&format!("pub const ERROR_BASE: u32 = {error_base};\n\n"));
+    code.push_str("/// The `E` a `Custom(code)` from `program_id` stands for\n");
+    code.push_str("///\n");
+    code.push_str("/// `None` if another program returned it or `E` has no such code.\n");
+    code.push_str("pub fn decode_error<E: TryFrom<u32>>(\n");
+    code.push_str("    program_id: &pinocchio::pubkey::Pubkey,\n");
+    code.push_str("    code: u32,\n");
+    code.push_str(") -> Option<E> {\n");
+    code.push_str("    if program_id != &crate::ID {\n");
+    code.push_str("        return None;\n");
+    code.push_str("    }\n");
+    code.push_str("    E::try_from(code).ok()\n");
+    code.push_str("}\n\n");
+}
+
+/// `INSTRUCTION_COUNT`, `INSTRUCTIONS` and `instruction_name()`, views aren't counted
+fn push_instruction_index(code: &mut String, instructions: &[InstructionMeta]) {
+    let listed: Vec<&InstructionMeta> = instructions
+        .iter()
+        .filter(|i| i.view_of.is_none())
+        .collect();
+    code.push_str("/// Instructions of the program, read-only views not counted\n");
+    code.push_str(&format!(
+        "pub const INSTRUCTION_COUNT: usize = {};\n\n",
+        listed.len()
+    ));
+    code.push_str("/// `(discriminator, name)` of each instruction, by discriminator\n");
+    code.push_str("pub const INSTRUCTIONS: [(u8, &str); INSTRUCTION_COUNT] = [\n");
+    for instruction in &listed {
+        code.push_str(&format!(
+            "    ({}, \"{}\"),\n",
+            instruction.discriminator, instruction.name
+        ));
+    }
+    code.push_str("];\n\n");
+
+    code.push_str("/// Instruction name of a discriminator, deprecated aliases included\n");
+    code.push_str("pub const fn instruction_name(discriminator: u8) -> Option<&'static str> {\n");
+    code.push_str("    match discriminator {\n");
+    for instruction in instructions {
+        let mut pattern = instruction.discriminator.to_string();
+        for alias in &instruction.aliases {
+            pattern.push_str(&format!(" | {alias}"));
+        }
+        code.push_str(&cfg_prefix(instruction, "        "));
+        code.push_str(&format!(
+            "        {pattern} => Some(\"{}\"),\n",
+            instruction.name
+        ));
+    }
+    code.push_str("        _ => None,\n");
+    code.push_str("    }\n");
+    code.push_str("}\n\n");
+}
+
+/// Name of the generated dispatch handler for an instruction, e.g. `handle_initialize_platform`
+fn handler_name(instruction_name: &str) -> String {
+    format!("handle_{}", snake_case(instruction_name))
+}
+
+/// `ViewVote` becomes `view_vote`
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    for ch in name.chars() {
+        if ch.is_ascii_uppercase() {
+            if !snake.is_empty() {
+                snake.push('_');
+            }
+            snake.push(ch.to_ascii_lowercase());
+        } else {
+            snake.push(ch);
+        }
+    }
+    snake
+}
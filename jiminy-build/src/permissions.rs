@@ -0,0 +1,375 @@
+//! The permission matrix: who controls what, per instruction, for audit docs
+//!
+//! Written to `OUT_DIR/permissions.json` and `OUT_DIR/permissions.md` on every
+//! build from the metadata the dispatch is generated from, so it can't drift
+//! from the program. Only what the sources declare is listed: signer and
+//! writable flags, `same_as`/`key_in` constraints, PDA seeds, account creation
+//! and `close_account!`, and the `transfers:` section. Checks written by hand
+//! in the instruction body don't show up.
+
+use crate::fixtures::string;
+use crate::InstructionMeta;
+use std::fmt::Write as _;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Write both files to `out_dir`, view instructions are skipped
+pub(crate) fn run(out_dir: &Path, instructions: &[InstructionMeta]) -> io::Result<()> {
+    let (json, markdown) = render(instructions);
+    fs::write(out_dir.join("permissions.json"), json)?;
+    fs::write(out_dir.join("permissions.md"), markdown)
+}
+
+/// The matrix as `(json, markdown)`
+pub(crate) fn render(instructions: &[InstructionMeta]) -> (String, String) {
+    let rows: Vec<Row> = instructions
+        .iter()
+        .filter(|i| i.view_of.is_none())
+        .map(Row::new)
+        .collect();
+    (json(&rows), markdown(&rows))
+}
+
+/// What one signer authorizes in an instruction
+struct Signer<'a> {
+    name: &'a str,
+    /// Transfers it's the authority of, as `from => to: amount`
+    authorizes: Vec<String>,
+    /// Accounts whose creation it pays for
+    pays_for: Vec<&'a str>,
+    /// PDAs whose seeds include its key
+    seeds_of: Vec<&'a str>,
+    /// What its key has to equal, from `same_as` or `key_in`
+    must_match: Option<&'a str>,
+}
+
+struct Row<'a> {
+    instruction: &'a InstructionMeta,
+    signers: Vec<Signer<'a>>,
+    /// `(account, payer)`
+    created: Vec<(&'a str, Option<&'a str>)>,
+    /// `(account, recipient)`
+    closed: Vec<(&'a str, &'a str)>,
+    /// Writable accounts that are neither created nor closed
+    mutated: Vec<&'a str>,
+    /// `(account, seeds)`
+    pdas: Vec<(&'a str, &'a str)>,
+    /// `(account, what its key has to equal)`
+    key_constraints: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> Row<'a> {
+    fn new(instruction: &'a InstructionMeta) -> Self {
+        let uses = &instruction.state_uses;
+        // The body may call helpers with their own parameter names, keep real accounts only
+        let is_account = |name: &str| instruction.accounts.iter().any(|a| a.name == name);
+
+        let mut created: Vec<(&str, Option<&str>)> = Vec::new();
+        for account in &instruction.accounts {
+            if account.account_type == "uninitialized" {
+                created.push((&account.name, None));
+            }
+        }
+        let creations = uses
+            .created
+            .iter()
+            .map(|(account, _)| (account.as_str(), None));
+        let paid = uses
+            .payers
+            .iter()
+            .map(|(account, payer)| (account.as_str(), Some(payer.as_str())));
+        for (account, payer) in creations.chain(paid) {
+            if !is_account(account) || payer.is_some_and(|payer| !is_account(payer)) {
+                continue;
+            }
+            match created.iter_mut().find(|(known, _)| *known == account) {
+                Some(entry) => entry.1 = entry.1.or(payer),
+                None => created.push((account, payer)),
+            }
+        }
+
+        let closed: Vec<(&str, &str)> = uses
+            .closed
+            .iter()
+            .filter(|(account, _)| is_account(account))
+            .map(|(account, recipient)| (account.as_str(), recipient.as_str()))
+            .collect();
+
+        let mutated = instruction
+            .accounts
+            .iter()
+            .filter(|a| a.attrs.iter().any(|attr| attr == "writable"))
+            .map(|a| a.name.as_str())
+            .filter(|name| created.iter().all(|(c, _)| c != name))
+            .filter(|name| closed.iter().all(|(c, _)| c != name))
+            .collect();
+
+        let pdas: Vec<(&str, &str)> = uses
+            .pdas
+            .iter()
+            .filter(|(account, _)| is_account(account))
+            .map(|(account, seeds)| (account.as_str(), seeds.as_str()))
+            .collect();
+
+        let key_constraints: Vec<(&str, &str)> = instruction
+            .accounts
+            .iter()
+            .filter(|a| a.account_type == "same_as" || a.account_type == "key_in")
+            .filter_map(|a| Some((a.name.as_str(), a.type_arg.as_deref()?)))
+            .collect();
+
+        let signers = instruction
+            .accounts
+            .iter()
+            .filter(|a| a.attrs.iter().any(|attr| attr == "signer"))
+            .map(|signer| {
+                let name = signer.name.as_str();
+                let key = format!("{name}.key()");
+                Signer {
+                    name,
+                    authorizes: instruction
+                        .transfers
+                        .iter()
+                        .filter(|t| t.authority == name)
+                        .map(|t| format!("{} => {}: {}", t.from, t.to, t.amount))
+                        .collect(),
+                    pays_for: created
+                        .iter()
+                        .filter(|(_, payer)| *payer == Some(name))
+                        .map(|(account, _)| *account)
+                        .collect(),
+                    seeds_of: pdas
+                        .iter()
+                        .filter(|(_, seeds)| seeds.contains(&key))
+                        .map(|(account, _)| *account)
+                        .collect(),
+                    must_match: key_constraints
+                        .iter()
+                        .find(|(account, _)| *account == name)
+                        .map(|(_, constraint)| *constraint),
+                }
+            })
+            .collect();
+
+        Row {
+            instruction,
+            signers,
+            created,
+            closed,
+            mutated,
+            pdas,
+            key_constraints,
+        }
+    }
+}
+
+fn json(rows: &[Row]) -> String {
+    let mut json = String::from("{\n  \"instructions\": [");
+    for (i, row) in rows.iter().enumerate() {
+        let instruction = row.instruction;
+        json.push_str(if i == 0 { "\n" } else { ",\n" });
+        json.push_str("    {\n");
+        writeln!(
+            json,
+            "      \"instruction\": {},",
+            string(&instruction.name)
+        )
+        .unwrap();
+        writeln!(
+            json,
+            "      \"discriminator\": {},",
+            instruction.discriminator
+        )
+        .unwrap();
+
+        let signers: Vec<String> = row
+            .signers
+            .iter()
+            .map(|signer| {
+                format!(
+                    "{{ \"name\": {}, \"authorizes\": {}, \"pays_for\": {}, \"seeds_of\": {}, \"must_match\": {} }}",
+                    string(signer.name),
+                    list(signer.authorizes.iter().map(String::as_str)),
+                    list(signer.pays_for.iter().copied()),
+                    list(signer.seeds_of.iter().copied()),
+                    signer.must_match.map_or_else(|| "null".to_string(), string),
+                )
+            })
+            .collect();
+        push_array(&mut json, "signers", &signers);
+
+        let created: Vec<String> = row
+            .created
+            .iter()
+            .map(|(account, payer)| {
+                let payer = payer.map_or_else(|| "null".to_string(), string);
+                format!("{{ \"account\": {}, \"payer\": {payer} }}", string(account))
+            })
+            .collect();
+        push_array(&mut json, "created", &created);
+
+        let closed: Vec<String> = row
+            .closed
+            .iter()
+            .map(|(account, to)| {
+                format!(
+                    "{{ \"account\": {}, \"to\": {} }}",
+                    string(account),
+                    string(to)
+                )
+            })
+            .collect();
+        push_array(&mut json, "closed", &closed);
+
+        writeln!(
+            json,
+            "      \"mutated\": {},",
+            list(row.mutated.iter().copied())
+        )
+        .unwrap();
+
+        let pdas: Vec<String> = row
+            .pdas
+            .iter()
+            .map(|(account, seeds)| {
+                format!(
+                    "{{ \"account\": {}, \"seeds\": {} }}",
+                    string(account),
+                    string(seeds)
+                )
+            })
+            .collect();
+        push_array(&mut json, "pdas", &pdas);
+
+        let constraints: Vec<String> = row
+            .key_constraints
+            .iter()
+            .map(|(account, key)| {
+                format!(
+                    "{{ \"account\": {}, \"must_match\": {} }}",
+                    string(account),
+                    string(key)
+                )
+            })
+            .collect();
+        push_array(&mut json, "key_constraints", &constraints);
+
+        let transfers: Vec<String> = instruction
+            .transfers
+            .iter()
+            .map(|t| {
+                format!(
+                    "{{ \"from\": {}, \"to\": {}, \"amount\": {}, \"authority\": {}, \"pda_signed\": {} }}",
+                    string(&t.from),
+                    string(&t.to),
+                    string(&t.amount),
+                    string(&t.authority),
+                    t.signed,
+                )
+            })
+            .collect();
+        json.push_str("      \"transfers\": [");
+        push_items(&mut json, &transfers);
+        json.push_str("]\n    }");
+    }
+    json.push_str("\n  ]\n}\n");
+    json
+}
+
+fn push_array(json: &mut String, key: &str, items: &[String]) {
+    write!(json, "      \"{key}\": [").unwrap();
+    push_items(json, items);
+    json.push_str("],\n");
+}
+
+fn push_items(json: &mut String, items: &[String]) {
+    for (i, item) in items.iter().enumerate() {
+        json.push_str(if i == 0 { "\n" } else { ",\n" });
+        write!(json, "        {item}").unwrap();
+    }
+    if !items.is_empty() {
+        json.push_str("\n      ");
+    }
+}
+
+fn list<'a>(items: impl Iterator<Item = &'a str>) -> String {
+    let items: Vec<String> = items.map(string).collect();
+    format!("[{}]", items.join(", "))
+}
+
+fn markdown(rows: &[Row]) -> String {
+    let mut md = String::from("# Permissions\n\n");
+    md.push_str("Generated by jiminy-build from the instruction sources, don't edit.\n\n");
+    md.push_str(
+        "| Instruction | Signers | Created | Closed | Mutated | Constraints | Transfers |\n",
+    );
+    md.push_str("|---|---|---|---|---|---|---|\n");
+    for row in rows {
+        let instruction = row.instruction;
+        let signers = row.signers.iter().map(|signer| {
+            let mut controls = Vec::new();
+            controls.extend(
+                signer
+                    .authorizes
+                    .iter()
+                    .map(|t| format!("authorizes `{t}`")),
+            );
+            controls.extend(signer.pays_for.iter().map(|a| format!("pays for `{a}`")));
+            controls.extend(signer.seeds_of.iter().map(|a| format!("seeds `{a}`")));
+            controls.extend(signer.must_match.map(|key| format!("must be `{key}`")));
+            if controls.is_empty() {
+                format!("`{}`", signer.name)
+            } else {
+                format!("`{}`: {}", signer.name, controls.join(", "))
+            }
+        });
+        let created = row.created.iter().map(|(account, payer)| match payer {
+            Some(payer) => format!("`{account}` (paid by `{payer}`)"),
+            None => format!("`{account}`"),
+        });
+        let closed = row
+            .closed
+            .iter()
+            .map(|(account, to)| format!("`{account}` to `{to}`"));
+        let mutated = row.mutated.iter().map(|account| format!("`{account}`"));
+        let pdas = row
+            .pdas
+            .iter()
+            .map(|(account, seeds)| format!("`{account}` = PDA[`{seeds}`]"));
+        let keys = row
+            .key_constraints
+            .iter()
+            .map(|(account, key)| format!("`{account}` = `{key}`"));
+        let transfers = instruction.transfers.iter().map(|t| {
+            format!(
+                "`{} => {}: {}` by `{}`",
+                t.from, t.to, t.amount, t.authority
+            )
+        });
+        writeln!(
+            md,
+            "| {} ({}) | {} | {} | {} | {} | {} | {} |",
+            instruction.name,
+            instruction.discriminator,
+            cell(signers),
+            cell(created),
+            cell(closed),
+            cell(mutated),
+            cell(pdas.chain(keys)),
+            cell(transfers),
+        )
+        .unwrap();
+    }
+    md
+}
+
+// Items of one table cell, one per line
+fn cell(items: impl Iterator<Item = String>) -> String {
+    let items: Vec<String> = items.map(|item| item.replace('|', "\\|")).collect();
+    if items.is_empty() {
+        "-".to_string()
+    } else {
+        items.join("<br>")
+    }
+}
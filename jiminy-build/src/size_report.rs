@@ -0,0 +1,204 @@
+//! `JIMINY_SIZE_REPORT=1`: build the program for SBF and attribute its symbol sizes to instructions
+
+use crate::{snake_case, InstructionMeta};
+use std::cmp::Reverse;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+// Symbols listed per instruction in the report
+const TOP_SYMBOLS: usize = 5;
+
+struct Symbol {
+    name: String,
+    size: u64,
+}
+
+/// Build the crate with `cargo build-sbf` and write the report to `OUT_DIR/jiminy-size-report.txt`
+///
+/// Runs in its own target dir under `OUT_DIR`, so the outer build's lock isn't
+/// held against it. Failures are reported as warnings, the normal build goes on.
+pub(crate) fn run(manifest_dir: &Path, instructions: &[InstructionMeta]) {
+    let out_dir = PathBuf::from(env::var("OUT_DIR").unwrap());
+    let lib_name = env::var("CARGO_PKG_NAME").unwrap().replace('-', "_");
+    let target_dir = out_dir.join("jiminy-size");
+
+    let status = Command::new("cargo")
+        .arg("build-sbf")
+        .arg("--manifest-path")
+        .arg(manifest_dir.join("Cargo.toml"))
+        .arg("--sbf-out-dir")
+        .arg(target_dir.join("deploy"))
+        .env("CARGO_TARGET_DIR", &target_dir)
+        // The nested build runs this build script again
+        .env_remove("JIMINY_SIZE_REPORT")
+        .status();
+    match status {
+        Ok(status) if status.success() => {}
+        Ok(status) => return warn(&format!("cargo build-sbf failed ({status})")),
+        Err(e) => return warn(&format!("cannot run cargo build-sbf: {e}")),
+    }
+
+    // The copy in `deploy` is stripped, the one the linker wrote still has `.symtab`
+    let Some(so_path) = find_unstripped(&target_dir, &format!("{lib_name}.so")) else {
+        return warn(&format!("no {lib_name}.so under {}", target_dir.display()));
+    };
+    let elf = match fs::read(&so_path) {
+        Ok(elf) => elf,
+        Err(e) => return warn(&format!("cannot read {}: {e}", so_path.display())),
+    };
+    let Some(report) = render(instructions, &elf) else {
+        return warn(&format!("{} is not a 64-bit little-endian ELF", so_path.display()));
+    };
+
+    let report_path = out_dir.join("jiminy-size-report.txt");
+    fs::write(&report_path, &report).unwrap();
+    println!("cargo:warning=size report written to {}", report_path.display());
+}
+
+fn warn(message: &str) {
+    println!("cargo:warning=size report skipped: {message}");
+}
+
+fn find_unstripped(target_dir: &Path, file_name: &str) -> Option<PathBuf> {
+    fs::read_dir(target_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.path().join("release").join(file_name))
+        .find(|path| path.exists())
+}
+
+// Instruction code lives in the instruction's module and its generated `handle_` fn.
+// Both legacy and v0 mangling spell identifiers as `<len><ident>`
+fn needles(instruction: &InstructionMeta) -> [String; 3] {
+    let module = snake_case(&instruction.name);
+    let handler = format!("handle_{module}");
+    let name = format!("{}Instruction", instruction.name);
+    [
+        format!("{}{module}", module.len()),
+        format!("{}{handler}", handler.len()),
+        format!("{}{name}", name.len()),
+    ]
+}
+
+// `9increment` must not match inside `29increment_num_running_threads`
+fn mentions(symbol: &str, needle: &str) -> bool {
+    symbol
+        .match_indices(needle)
+        .any(|(i, _)| !symbol[..i].ends_with(|c: char| c.is_ascii_digit()))
+}
+
+/// The report for the unstripped `.so` in `elf`, `None` when it isn't a 64-bit
+/// little-endian ELF with a symbol table
+pub(crate) fn render(instructions: &[InstructionMeta], elf: &[u8]) -> Option<String> {
+    Some(format_report(instructions, &read_symbols(elf)?, elf.len()))
+}
+
+fn format_report(instructions: &[InstructionMeta], symbols: &[Symbol], file_size: usize) -> String {
+    let mut groups: Vec<(&str, Vec<&Symbol>)> = instructions
+        .iter()
+        .map(|instruction| (instruction.name.as_str(), Vec::new()))
+        .collect();
+    let mut shared = Vec::new();
+    let needles: Vec<[String; 3]> = instructions.iter().map(needles).collect();
+
+    for symbol in symbols {
+        match needles
+            .iter()
+            .position(|n| n.iter().any(|needle| mentions(&symbol.name, needle)))
+        {
+            Some(i) => groups[i].1.push(symbol),
+            None => shared.push(symbol),
+        }
+    }
+    groups.push(("(shared)", shared));
+
+    let mut report = String::new();
+    writeln!(report, "{:<28} {:>10} {:>8}", "instruction", "bytes", "symbols").unwrap();
+    for (name, group) in &groups {
+        let bytes: u64 = group.iter().map(|s| s.size).sum();
+        writeln!(report, "{name:<28} {bytes:>10} {:>8}", group.len()).unwrap();
+    }
+    let total: u64 = symbols.iter().map(|s| s.size).sum();
+    writeln!(report, "{:<28} {total:>10} {:>8}", "total", symbols.len()).unwrap();
+    writeln!(report, "unstripped .so: {file_size} bytes").unwrap();
+
+    for (name, group) in &mut groups {
+        if group.is_empty() {
+            continue;
+        }
+        group.sort_by_key(|symbol| Reverse(symbol.size));
+        writeln!(report, "\n{name}:").unwrap();
+        for symbol in group.iter().take(TOP_SYMBOLS) {
+            writeln!(report, "  {:>8}  {}", symbol.size, symbol.name).unwrap();
+        }
+    }
+    report
+}
+
+// Sized function and object symbols from `.symtab`, falling back to `.dynsym`
+fn read_symbols(elf: &[u8]) -> Option<Vec<Symbol>> {
+    const SHT_SYMTAB: u32 = 2;
+    const SHT_STRTAB: u32 = 3;
+    const SHT_DYNSYM: u32 = 11;
+    const STT_OBJECT: u8 = 1;
+    const STT_FUNC: u8 = 2;
+
+    // ELFCLASS64, ELFDATA2LSB
+    if elf.get(..6)? != b"\x7fELF\x02\x01" {
+        return None;
+    }
+    let section_offset = read_u64(elf, 0x28)? as usize;
+    let section_size = read_u16(elf, 0x3a)? as usize;
+    let section_count = read_u16(elf, 0x3c)? as usize;
+    let section = |i: usize| {
+        let base = section_offset + i * section_size;
+        Some((
+            read_u32(elf, base + 4)?,
+            read_u64(elf, base + 0x18)? as usize,
+            read_u64(elf, base + 0x20)? as usize,
+            read_u32(elf, base + 0x28)? as usize,
+        ))
+    };
+
+    // Kept at their index, which is how `sh_link` names the string table
+    let sections: Vec<_> = (0..section_count).map(section).collect();
+    let (_, offset, size, link) = [SHT_SYMTAB, SHT_DYNSYM]
+        .iter()
+        .find_map(|&kind| sections.iter().flatten().find(|s| s.0 == kind))
+        .copied()?;
+    let (strings_kind, strings_offset, _, _) = (*sections.get(link)?)?;
+    if strings_kind != SHT_STRTAB {
+        return None;
+    }
+
+    let mut symbols = Vec::new();
+    for base in (offset..offset + size).step_by(24) {
+        let kind = elf.get(base + 4)? & 0xf;
+        let sym_size = read_u64(elf, base + 16)?;
+        if sym_size == 0 || (kind != STT_FUNC && kind != STT_OBJECT) {
+            continue;
+        }
+        let name_start = strings_offset + read_u32(elf, base)? as usize;
+        let name_len = elf.get(name_start..)?.iter().position(|&b| b == 0)?;
+        symbols.push(Symbol {
+            name: String::from_utf8_lossy(&elf[name_start..name_start + name_len]).into_owned(),
+            size: sym_size,
+        });
+    }
+    Some(symbols)
+}
+
+fn read_u16(bytes: &[u8], at: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(bytes.get(at..at + 2)?.try_into().ok()?))
+}
+
+fn read_u32(bytes: &[u8], at: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(bytes.get(at..at + 4)?.try_into().ok()?))
+}
+
+fn read_u64(bytes: &[u8], at: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(bytes.get(at..at + 8)?.try_into().ok()?))
+}
@@ -0,0 +1,152 @@
+//! `[discriminators]` ranges against small programs written to a temporary
+//! directory: a misplaced instruction fails the build, and the report lists what
+//! each range uses and leaves free
+
+use std::env;
+use std::fs;
+use std::panic;
+use std::path::PathBuf;
+use std::process;
+
+const RANGES: &str = "[discriminators.user]\n\
+                      range = [0, 31]\n\
+                      \n\
+                      [discriminators.admin]\n\
+                      range = [32, 63]\n\
+                      instructions = [\"Admin\"]\n";
+
+struct Program {
+    root: PathBuf,
+}
+
+impl Program {
+    /// An empty program crate with `config` as its `jiminy.toml`
+    fn new(name: &str, config: &str) -> Self {
+        let root = env::temp_dir().join(format!("jiminy-discriminators-{name}-{}", process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src/instructions")).unwrap();
+        fs::write(root.join("jiminy.toml"), config).unwrap();
+        Self { root }
+    }
+
+    /// Add `src/instructions/<name>.rs`, an instruction without accounts or data
+    /// at `discriminator`, with the macro's extra lines in `extra`
+    fn instruction(self, name: &str, discriminator: u8, extra: &str) -> Self {
+        let source = format!(
+            "use jiminy::define_instruction_with_metadata;\n\
+             \n\
+             define_instruction_with_metadata!(\n    \
+                 discriminant: {discriminator},\n    \
+                 {name},\n{extra}    \
+                 accounts: {{}},\n    \
+                 data: {{}},\n    \
+                 process: {{\n        Ok(())\n    }}\n\
+             );\n"
+        );
+        let file = self
+            .root
+            .join("src/instructions")
+            .join(format!("{}.rs", name.to_lowercase()));
+        fs::write(file, source).unwrap();
+        self
+    }
+
+    fn report(&self) -> (String, String) {
+        jiminy_build::generate_discriminator_report(&self.root)
+    }
+
+    /// The message the build panics with
+    fn panic_message(&self) -> String {
+        let payload = panic::catch_unwind(|| self.report()).expect_err("the build passed");
+        payload
+            .downcast_ref::<String>()
+            .cloned()
+            .or_else(|| payload.downcast_ref::<&str>().map(|s| s.to_string()))
+            .unwrap()
+    }
+}
+
+impl Drop for Program {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+#[test]
+fn admin_instruction_in_the_user_range_fails() {
+    let program = Program::new("admin-at-5", RANGES).instruction("Admin", 5, "");
+    assert_eq!(
+        program.panic_message(),
+        "`Admin` uses discriminator 5, outside 32..=63 which `discriminators.admin` reserves for it"
+    );
+}
+
+#[test]
+fn unlisted_instruction_in_a_reserved_range_fails() {
+    let program = Program::new("user-at-40", RANGES)
+        .instruction("Admin", 32, "")
+        .instruction("Deposit", 40, "");
+    assert_eq!(
+        program.panic_message(),
+        "`Deposit` uses discriminator 40, inside 32..=63 which `discriminators.admin` reserves \
+         for other instructions; list it there or renumber it"
+    );
+}
+
+#[test]
+fn instruction_outside_every_range_fails() {
+    let program = Program::new("deposit-at-64", RANGES)
+        .instruction("Admin", 32, "")
+        .instruction("Deposit", 64, "");
+    assert_eq!(
+        program.panic_message(),
+        "`Deposit` uses discriminator 64, outside every range in jiminy.toml's `discriminators`"
+    );
+}
+
+#[test]
+fn report_lists_used_and_free_values() {
+    let program = Program::new("report", RANGES)
+        .instruction("Deposit", 0, "")
+        .instruction("Withdraw", 1, "")
+        .instruction("Admin", 32, "    deprecated_aliases: [2],\n");
+    let (_, markdown) = program.report();
+    assert_eq!(
+        markdown,
+        "# Discriminators\n\
+         \n\
+         Generated by jiminy-build from the instruction sources and jiminy.toml, don't edit.\n\
+         \n\
+         | Range | Values | Used | Free |\n\
+         |---|---|---|---|\n\
+         | user | 0..=31 | 3 | 29 |\n\
+         | admin | 32..=63 | 1 | 31 |\n\
+         | views | 200..=255 | 0 | 56 |\n\
+         \n\
+         Outside every range: 64..=199\n\
+         \n\
+         ## user (0..=31)\n\
+         \n\
+         | Discriminator | Instruction |\n\
+         |---|---|\n\
+         | 0 | Deposit |\n\
+         | 1 | Withdraw |\n\
+         | 2 | Admin (deprecated alias) |\n\
+         \n\
+         Free: 3..=31\n\
+         \n\
+         ## admin (32..=63)\n\
+         \n\
+         Reserved for Admin.\n\
+         \n\
+         | Discriminator | Instruction |\n\
+         |---|---|\n\
+         | 32 | Admin |\n\
+         \n\
+         Free: 33..=63\n\
+         \n\
+         ## views (200..=255)\n\
+         \n\
+         Free: 200..=255\n"
+    );
+}
@@ -0,0 +1,64 @@
+//! The account fixtures rendered from the vote example's sources match the ones
+//! committed in `examples/vote/fixtures`, and carry every key clients read
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn vote() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples/vote")
+}
+
+fn initialize_position() -> String {
+    jiminy_build::generate_fixtures(&vote())
+        .into_iter()
+        .find(|(file, _)| file == "initialize_position.json")
+        .expect("no InitializePosition fixture")
+        .1
+}
+
+// The account objects of a fixture, `    {` to `    }` at the top of `accounts`
+fn accounts(fixture: &str) -> Vec<&str> {
+    let start = fixture.find("  \"accounts\": [\n").unwrap();
+    let end = start + fixture[start..].find("\n  ],\n").unwrap();
+    fixture[start..end]
+        .split("\n    {\n")
+        .skip(1)
+        .collect()
+}
+
+#[test]
+fn initialize_position_matches_the_committed_fixture() {
+    let committed = fs::read_to_string(vote().join("fixtures/initialize_position.json")).unwrap();
+    assert_eq!(
+        initialize_position(),
+        committed,
+        "rerun the vote build with JIMINY_FIXTURES=1 if the change is intended"
+    );
+}
+
+#[test]
+fn initialize_position_has_the_required_keys() {
+    let fixture = initialize_position();
+    for key in ["instruction", "discriminator", "accounts", "data", "rest"] {
+        assert!(fixture.contains(&format!("\n  \"{key}\": ")), "no `{key}`");
+    }
+    assert!(fixture.contains("\"instruction\": \"InitializePosition\""));
+    assert!(fixture.contains("\"discriminator\": 3,"));
+
+    let accounts = accounts(&fixture);
+    // authority, vote, four token accounts, position, platform, treasury, vault,
+    // token, vote_vault and whitelist_entry
+    assert_eq!(accounts.len(), 13);
+    for account in accounts {
+        for key in ["name", "signer", "writable", "owner", "lamports", "data_len"] {
+            assert!(
+                account.contains(&format!("      \"{key}\": ")),
+                "no `{key}` in {account}"
+            );
+        }
+        // The PDAs list their seeds
+        if account.contains("\"pda\"") {
+            assert!(account.contains("\"pda\": { \"seeds\": ["), "{account}");
+        }
+    }
+}
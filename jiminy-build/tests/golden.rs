@@ -0,0 +1,75 @@
+//! Golden tests for the generator: each directory in `fixtures/golden` is a small
+//! program crate, and its `expected/` holds the `generated.rs`, the IDL keys and
+//! the lint messages the build script produces for it.
+//!
+//! After an intended change to the output, rerun with `JIMINY_BLESS=1` to rewrite
+//! the expected files and review their diff.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn cases() -> Vec<PathBuf> {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/golden");
+    let mut cases: Vec<PathBuf> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.join("src").is_dir())
+        .collect();
+    cases.sort();
+    assert!(!cases.is_empty(), "no cases in {}", dir.display());
+    cases
+}
+
+// Compare `actual` with `<case>/expected/<file>`, or write it there with `JIMINY_BLESS=1`
+fn check(case: &Path, file: &str, actual: &str) {
+    let path = case.join("expected").join(file);
+    if env::var("JIMINY_BLESS").is_ok_and(|v| v == "1") {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, actual).unwrap();
+        return;
+    }
+    let expected = fs::read_to_string(&path).unwrap_or_else(|e| {
+        panic!("cannot read {}: {e}, run with JIMINY_BLESS=1 to create it", path.display())
+    });
+    if expected != actual {
+        let line = expected
+            .lines()
+            .zip(actual.lines())
+            .position(|(e, a)| e != a)
+            .unwrap_or_else(|| expected.lines().count().min(actual.lines().count()));
+        panic!(
+            "{} differs from the generator's output at line {}:\n  expected: {:?}\n    actual: {:?}\n\
+             rerun with JIMINY_BLESS=1 if the change is intended",
+            path.display(),
+            line + 1,
+            expected.lines().nth(line).unwrap_or("<end of file>"),
+            actual.lines().nth(line).unwrap_or("<end of file>"),
+        );
+    }
+}
+
+#[test]
+fn generated_code_matches() {
+    for case in cases() {
+        check(&case, "generated.rs", &jiminy_build::generate_program(&case));
+    }
+}
+
+#[test]
+fn idl_keys_match() {
+    for case in cases() {
+        check(&case, "idl.json", &jiminy_build::generate_idl_extensions(&case));
+    }
+}
+
+#[test]
+fn lints_match() {
+    for case in cases() {
+        let lints: String = jiminy_build::generate_lints(&case)
+            .iter()
+            .map(|message| format!("{message}\n"))
+            .collect();
+        check(&case, "lints.txt", &lints);
+    }
+}
@@ -0,0 +1,70 @@
+//! Each `<case>.json` in `fixtures/idl-diff` diffed against `base.json`, or against
+//! `<case>.base.json` when the case has its own old side, must print `<case>.txt`
+//!
+//! After an intended change to the output, rerun with `JIMINY_BLESS=1` to rewrite
+//! the expected files and review their diff.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn cases(dir: &Path) -> Vec<PathBuf> {
+    let mut cases: Vec<PathBuf> = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| {
+            let name = path.file_name().unwrap().to_str().unwrap();
+            name.ends_with(".json") && name != "base.json" && !name.ends_with(".base.json")
+        })
+        .collect();
+    cases.sort();
+    assert!(!cases.is_empty(), "no cases in {}", dir.display());
+    cases
+}
+
+fn read(path: &Path) -> String {
+    fs::read_to_string(path).unwrap_or_else(|e| panic!("cannot read {}: {e}", path.display()))
+}
+
+#[test]
+fn fixtures_match() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/idl-diff");
+    let bless = env::var("JIMINY_BLESS").is_ok_and(|v| v == "1");
+    let mut failures = Vec::new();
+    for case in cases(&dir) {
+        let own_base = case.with_extension("base.json");
+        let old = match own_base.exists() {
+            true => read(&own_base),
+            false => read(&dir.join("base.json")),
+        };
+        let diff = jiminy_build::diff_idls(&old, &read(&case))
+            .unwrap_or_else(|e| panic!("{}: {e}", case.display()));
+        let actual: String = diff.changes.iter().map(|change| format!("{change}\n")).collect();
+
+        let expected_path = case.with_extension("txt");
+        if bless {
+            fs::write(&expected_path, &actual).unwrap();
+            continue;
+        }
+        let expected = fs::read_to_string(&expected_path).unwrap_or_default();
+        if expected != actual {
+            failures.push(format!(
+                "{}:\n--- expected\n{expected}--- actual\n{actual}",
+                expected_path.display()
+            ));
+        }
+    }
+    assert!(
+        failures.is_empty(),
+        "{}\nrerun with JIMINY_BLESS=1 if the change is intended",
+        failures.join("\n")
+    );
+}
+
+#[test]
+fn every_case_has_expected_output() {
+    let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/idl-diff");
+    for case in cases(&dir) {
+        assert!(case.with_extension("txt").exists(), "{} has no .txt", case.display());
+    }
+}
@@ -0,0 +1,251 @@
+//! Each `[lint]` check on its own, against a one-instruction program written to a
+//! temporary directory; `golden.rs` covers them all together
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process;
+
+struct Program {
+    root: PathBuf,
+}
+
+impl Program {
+    /// An empty program crate with `lints` under `[lint]` in its `jiminy.toml`
+    fn new(name: &str, lints: &str) -> Self {
+        let root = env::temp_dir().join(format!("jiminy-lints-{name}-{}", process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("src/instructions")).unwrap();
+        fs::write(root.join("jiminy.toml"), format!("[lint]\n{lints}\n")).unwrap();
+        Self { root }
+    }
+
+    /// Add `src/instructions/<file>` with `source`
+    fn file(self, file: &str, source: &str) -> Self {
+        fs::write(self.root.join("src/instructions").join(file), source).unwrap();
+        self
+    }
+
+    /// Add `src/instructions/test.rs`, a `Test` instruction with `accounts` and `process`
+    fn instruction(self, accounts: &str, process: &str) -> Self {
+        let source = format!(
+            "use jiminy::define_instruction_with_metadata;\n\
+             \n\
+             define_instruction_with_metadata!(\n    \
+                 discriminant: 0,\n    \
+                 Test,\n    \
+                 accounts: {{\n{accounts}\n    }},\n    \
+                 data: {{}},\n    \
+                 process: {{\n{process}\n        Ok(())\n    }}\n\
+             );\n"
+        );
+        self.file("test.rs", &source)
+    }
+
+    fn lints(&self) -> Vec<String> {
+        jiminy_build::generate_lints(&self.root)
+    }
+}
+
+impl Drop for Program {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.root);
+    }
+}
+
+#[test]
+fn accounts_out_of_order_are_reported() {
+    let program = Program::new("order", "accounts = true").instruction(
+        "config: any, desc: \"Config\",\n\
+         payer: signer => writable, desc: \"Payer\",\n\
+         vault: any => writable, desc: \"Vault\",",
+        "let _ = (config, payer, vault);",
+    );
+    assert_eq!(
+        program.lints(),
+        [
+            "Test: signer account `payer` comes after read-only account `config`, order \
+             signers, writable, read-only, then programs and sysvars",
+            "Test: writable account `vault` comes after read-only account `config`, order \
+             signers, writable, read-only, then programs and sysvars",
+        ]
+    );
+}
+
+#[test]
+fn accounts_in_order_pass() {
+    let program = Program::new("ordered", "accounts = true").instruction(
+        "payer: signer => writable, desc: \"Payer\",\n\
+         vault: any => writable, desc: \"Vault\",\n\
+         config: any, desc: \"Config\",\n\
+         system_program,",
+        "let _ = (payer, vault, config);",
+    );
+    assert!(program.lints().is_empty(), "{:?}", program.lints());
+}
+
+#[test]
+fn unpinned_programs_are_reported_unless_allowed() {
+    let accounts = "payer: signer => writable, desc: \"Payer\",\n\
+                    token_program: any, desc: \"Token program\",";
+    let program = Program::new("unpinned", "accounts = true").instruction(accounts, "");
+    assert_eq!(
+        program.lints(),
+        ["Test: `token_program` accepts any key, pin it with `address(...)` or list it in \
+          `lint.allow_unpinned`"]
+    );
+
+    let allowed = "accounts = true\nallow_unpinned = [\"token_program\"]";
+    let program = Program::new("unpinned-allowed", allowed).instruction(accounts, "");
+    assert!(program.lints().is_empty(), "{:?}", program.lints());
+}
+
+#[test]
+fn accounts_sharing_a_desc_are_reported() {
+    let program = Program::new("desc", "accounts = true").instruction(
+        "payer: signer => writable, desc: \"Account\",\n\
+         config: any, desc: \"Account\",",
+        "",
+    );
+    assert_eq!(
+        program.lints(),
+        ["Test: `payer` and `config` share the desc \"Account\""]
+    );
+}
+
+const CREATE_LARGE: &str = "payer: signer => writable, desc: \"Payer\",\n\
+                            large: uninitialized, desc: \"Created account\",\n\
+                            system_program,";
+
+// `create_pda!` of `large` with `space`, then `load` of it
+fn create_and_load(space: &str, load: &str) -> String {
+    format!(
+        "create_pda!(from: payer, to: large, space: {space}, seeds: [b\"large\"], bump: 255);\n\
+         {load}"
+    )
+}
+
+#[test]
+fn created_space_of_another_state_is_reported() {
+    let process = create_and_load("Small::LEN", "load_mut!(large, crate::state::Large);");
+    let program = Program::new("space", "create_space = true").instruction(CREATE_LARGE, &process);
+    assert_eq!(
+        program.lints(),
+        ["Test: `large` is created with `Small::LEN` but loaded as `Large`"]
+    );
+}
+
+#[test]
+fn created_space_of_the_loaded_state_passes() {
+    for load in ["load_mut!(large, Large);", "read_state!(large, Large, |l| l.owner);"] {
+        let process = create_and_load("Large::LEN", load);
+        let program = Program::new("same-space", "create_space = true")
+            .instruction(CREATE_LARGE, &process);
+        assert!(program.lints().is_empty(), "{load}: {:?}", program.lints());
+    }
+}
+
+#[test]
+fn created_space_mismatch_can_be_allowed() {
+    let process = format!(
+        "// jiminy: allow(create_space)\n{}",
+        create_and_load("Small::LEN", "load_prefix_mut!(large, Large);")
+    );
+    let program = Program::new("space-allowed", "create_space = true")
+        .instruction(CREATE_LARGE, &process);
+    assert!(program.lints().is_empty(), "{:?}", program.lints());
+}
+
+const BIG_ENDIAN_FIX: &str = "state is little-endian, use a `jiminy::LeU64` field's \
+                              `get()`/`set()`, or add `// jiminy: allow(byte_order)` if \
+                              big-endian is intended";
+
+#[test]
+fn big_endian_conversions_are_reported_by_line() {
+    let program = Program::new("be", "byte_order = true").file(
+        "pay.rs",
+        "fn amount(data: [u8; 8]) -> u64 {\n\
+         \x20   let amount = u64::from_be_bytes(data);\n\
+         \x20   // from_be_bytes in a comment is skipped\n\
+         \x20   let _ = \"to_be_bytes in a string too\";\n\
+         \x20   u64::from_le_bytes(amount.to_be_bytes())\n\
+         }\n",
+    );
+    assert_eq!(
+        program.lints(),
+        [
+            format!("src/instructions/pay.rs:2: `from_be_bytes`, {BIG_ENDIAN_FIX}"),
+            format!("src/instructions/pay.rs:5: `to_be_bytes`, {BIG_ENDIAN_FIX}"),
+        ]
+    );
+}
+
+#[test]
+fn allowed_big_endian_conversions_pass() {
+    let program = Program::new("be-allowed", "byte_order = true").file(
+        "pay.rs",
+        "fn amount(data: [u8; 8]) -> [u8; 8] {\n\
+         \x20   // jiminy: allow(byte_order)\n\
+         \x20   let amount = u64::from_be_bytes(data);\n\
+         \x20   amount.to_be_bytes() // jiminy: allow(byte_order)\n\
+         }\n",
+    );
+    assert!(program.lints().is_empty(), "{:?}", program.lints());
+}
+
+#[test]
+fn little_endian_conversions_are_reported_with_raw_le_bytes() {
+    let source = "fn count(data: [u8; 8]) -> u64 {\n\
+                  \x20   u64::from_le_bytes(data)\n\
+                  }\n";
+    let program = Program::new("le", "byte_order = true").file("count.rs", source);
+    assert!(program.lints().is_empty(), "{:?}", program.lints());
+
+    let raw = "byte_order = true\nraw_le_bytes = true";
+    let program = Program::new("le-raw", raw).file("count.rs", source);
+    assert_eq!(
+        program.lints(),
+        ["src/instructions/count.rs:2: `from_le_bytes`, use a `jiminy::LeU64` field's \
+          `get()`/`set()` instead"]
+    );
+}
+
+#[test]
+fn accounts_named_nowhere_are_reported() {
+    let program = Program::new("unused", "unused_accounts = true").instruction(
+        "payer: signer => writable, desc: \"Payer\",\n\
+         config: any, desc: \"Config\",\n\
+         oracle: any, desc: \"Oracle\",",
+        "// config is only named in this comment\n\
+         let _ = payer.key();\n\
+         let _ = \"oracle\";",
+    );
+    assert_eq!(
+        program.lints(),
+        ["Test: `config`, `oracle` declared but never used"]
+    );
+}
+
+#[test]
+fn programs_used_by_their_cpis_count_as_used() {
+    let program = Program::new("implicit", "unused_accounts = true").instruction(
+        &format!("{CREATE_LARGE}\ntoken_program,"),
+        &create_and_load("Large::LEN", "load_mut!(large, Large);"),
+    );
+    // `create_pda!` needs the system program, nothing here the token program
+    assert_eq!(
+        program.lints(),
+        ["Test: `token_program` declared but never used"]
+    );
+}
+
+#[test]
+fn unused_accounts_can_be_allowed() {
+    let program = Program::new("unused-allowed", "unused_accounts = true").instruction(
+        "payer: signer => writable, desc: \"Payer\",\n\
+         config: any, desc: \"Config\",",
+        "// jiminy: allow(unused_accounts)\n\
+         let _ = payer.key();",
+    );
+    assert!(program.lints().is_empty(), "{:?}", program.lints());
+}
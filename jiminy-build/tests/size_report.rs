@@ -0,0 +1,117 @@
+//! The size report reads the symbols of `fixtures/size-report/vote.o`, stand-ins
+//! for the vote example's, and attributes them to its instructions as in
+//! `vote.txt`; see `vote.c` for how the object is built
+//!
+//! After an intended change to the output, rerun with `JIMINY_BLESS=1` to rewrite
+//! `vote.txt` and review its diff.
+
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn fixtures() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/size-report")
+}
+
+fn vote() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../examples/vote")
+}
+
+fn elf() -> Vec<u8> {
+    fs::read(fixtures().join("vote.o")).unwrap()
+}
+
+fn read_u16(elf: &[u8], at: usize) -> usize {
+    u16::from_le_bytes(elf[at..at + 2].try_into().unwrap()) as usize
+}
+
+fn read_u64(elf: &[u8], at: usize) -> usize {
+    u64::from_le_bytes(elf[at..at + 8].try_into().unwrap()) as usize
+}
+
+// Where section header `i` starts, and the section's type
+fn section_header(elf: &[u8], i: usize) -> (usize, u32) {
+    let base = read_u64(elf, 0x28) + i * read_u16(elf, 0x3a);
+    (base, u32::from_le_bytes(elf[base + 4..base + 8].try_into().unwrap()))
+}
+
+fn symtab_header(elf: &[u8]) -> usize {
+    (0..read_u16(elf, 0x3c))
+        .map(|i| section_header(elf, i))
+        .find(|&(_, kind)| kind == 2)
+        .unwrap()
+        .0
+}
+
+#[test]
+fn report_matches_the_committed_one() {
+    let report = jiminy_build::generate_size_report(&vote(), &elf()).unwrap();
+    let expected = fixtures().join("vote.txt");
+    if env::var("JIMINY_BLESS").is_ok_and(|v| v == "1") {
+        fs::write(&expected, &report).unwrap();
+    }
+    assert_eq!(report, fs::read_to_string(expected).unwrap());
+}
+
+#[test]
+fn symbols_are_attributed_to_their_instructions() {
+    let report = jiminy_build::generate_size_report(&vote(), &elf()).unwrap();
+    let section = |name: &str| {
+        let start = report.find(&format!("\n{name}:\n")).unwrap();
+        report[start + 1..].split("\n\n").next().unwrap().to_string()
+    };
+    // Its module, its handler and its instruction type
+    let redeem = section("RedeemWinnings");
+    assert_eq!(redeem.lines().count(), 4, "{redeem}");
+    assert!(redeem.contains("15redeem_winnings7process"));
+    assert!(redeem.contains("22handle_redeem_winnings"));
+    assert!(redeem.contains("25RedeemWinningsInstruction8METADATA"));
+
+    assert!(section("InitializeVoteWithPayer").contains("26initialize_vote_with_payer"));
+    assert!(!report.contains("\nInitializeVote:\n"));
+
+    let shared = section("(shared)");
+    assert!(shared.contains("entrypoint"));
+    assert!(shared.contains("_ZN4core3fmt5write"));
+}
+
+#[test]
+fn anything_but_a_little_endian_elf64_is_rejected() {
+    let mut elf = elf();
+    assert!(jiminy_build::generate_size_report(&vote(), b"not an elf").is_none());
+    // ELFCLASS32
+    elf[4] = 1;
+    assert!(jiminy_build::generate_size_report(&vote(), &elf).is_none());
+    // ELFDATA2MSB
+    elf[4] = 2;
+    elf[5] = 2;
+    assert!(jiminy_build::generate_size_report(&vote(), &elf).is_none());
+}
+
+#[test]
+fn symbol_names_come_only_from_the_linked_string_table() {
+    // Point `.symtab`'s `sh_link` at `.text`, the names must not be read from it
+    let mut elf = elf();
+    let text = (0..read_u16(&elf, 0x3c))
+        .find(|&i| section_header(&elf, i).1 == 1)
+        .unwrap() as u32;
+    let link = symtab_header(&elf) + 0x28;
+    elf[link..link + 4].copy_from_slice(&text.to_le_bytes());
+    assert!(jiminy_build::generate_size_report(&vote(), &elf).is_none());
+
+    // A link past the last section header
+    elf[link..link + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(jiminy_build::generate_size_report(&vote(), &elf).is_none());
+}
+
+#[test]
+fn truncated_section_headers_are_not_skipped() {
+    // Cut the file inside the header of the section holding `.symtab`'s names,
+    // which `sh_link` counts from the start of the table
+    let elf = elf();
+    let symtab = symtab_header(&elf);
+    let link = u32::from_le_bytes(elf[symtab + 0x28..symtab + 0x2c].try_into().unwrap());
+    let (strtab, _) = section_header(&elf, link as usize);
+    assert!(strtab > symtab);
+    assert!(jiminy_build::generate_size_report(&vote(), &elf[..strtab + 8]).is_none());
+}
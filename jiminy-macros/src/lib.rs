@@ -0,0 +1,890 @@
+//! Procedural macros for jiminy programs.
+//!
+//! Use them through the re-exports in the `jiminy` crate.
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::{
+    parse::{Parse, ParseStream},
+    parse_macro_input,
+    punctuated::Punctuated,
+    Attribute, Expr, ExprLit, Ident, Item, ItemMod, Lit, LitInt, LitStr, Meta, MetaNameValue, Token,
+    Type,
+};
+
+/// Account types understood by `validate_account!`
+const ACCOUNT_TYPES: &[&str] = &[
+    "signer",
+    "program",
+    "uninitialized",
+    "token",
+    "not_token",
+    "address",
+    "same_as",
+    "key_in",
+    "any",
+];
+
+/// Attribute form of `define_instruction_with_metadata!`
+///
+/// ```ignore
+/// #[jiminy::instruction(discriminant = 0)]
+/// pub mod initialize_platform {
+///     use crate::state::Platform;
+///
+///     accounts! {
+///         authority: signer => writable, desc: "Authority of the vault",
+///         platform: uninitialized, desc: "Platform pda key",
+///     }
+///
+///     data! {
+///         fee: [u8; 2],
+///     }
+///
+///     process! {
+///         Ok(())
+///     }
+/// }
+/// ```
+///
+/// The module name gives the instruction name (`InitializePlatform`), and its
+/// items are emitted in place of the module, next to the generated
+/// `InitializePlatform`, `InitializePlatformData` and
+/// `InitializePlatformInstruction` types. An optional
+/// `rest!(label, max_len: 64);` section works like `rest:` in the macro form,
+/// `remaining_accounts!(name, desc: "...");` like `remaining_accounts:` and
+/// `context!(ctx);` like `context: ctx,`. Accounts take the same shorthands,
+/// e.g. `system_program,`, and the same `label:` and `explorer_hint:` after a desc.
+/// Doc comments on the module and on data fields work like `desc:` and field
+/// doc comments in the macro form.
+///
+/// The `prepare`, `transfers`, `invariants`, `returns`, `expiry`, `deprecated`,
+/// `data_parser` and `strict_data` sections of the macro form aren't supported
+/// and fail to compile, as a `transfers! { ... }` section or a `deprecated`
+/// argument; declare such instructions with `define_instruction_with_metadata!`.
+#[proc_macro_attribute]
+pub fn instruction(args: TokenStream, item: TokenStream) -> TokenStream {
+    let args = parse_macro_input!(args as InstructionArgs);
+    let module = parse_macro_input!(item as ItemMod);
+
+    match expand_instruction(args, module) {
+        Ok(tokens) => tokens.into(),
+        Err(error) => error.to_compile_error().into(),
+    }
+}
+
+/// Sections of `define_instruction_with_metadata!` the attribute form has no counterpart for
+const UNSUPPORTED_SECTIONS: &[&str] = &[
+    "prepare",
+    "transfers",
+    "invariants",
+    "returns",
+    "expiry",
+    "deprecated",
+    "data_parser",
+    "strict_data",
+];
+
+fn unsupported_section(section: &Ident) -> syn::Error {
+    syn::Error::new(
+        section.span(),
+        format!(
+            "`{section}` isn't supported by `#[jiminy::instruction]`, \
+             declare the instruction with `define_instruction_with_metadata!` instead"
+        ),
+    )
+}
+
+struct InstructionArgs {
+    discriminant: LitInt,
+    aliases: Vec<LitInt>,
+}
+
+impl Parse for InstructionArgs {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key: Ident = input.parse()?;
+        if UNSUPPORTED_SECTIONS.iter().any(|section| key == section) {
+            return Err(unsupported_section(&key));
+        }
+        if key != "discriminant" {
+            return Err(syn::Error::new(
+                key.span(),
+                "expected `discriminant = <u8>`",
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        let discriminant: LitInt = input.parse()?;
+        discriminant.base10_parse::<u8>()?;
+
+        // Optional `, deprecated_aliases = [10, 11]`
+        let mut aliases = Vec::new();
+        if input.parse::<Option<Token![,]>>()?.is_some() && !input.is_empty() {
+            let key: Ident = input.parse()?;
+            if UNSUPPORTED_SECTIONS.iter().any(|section| key == section) {
+                return Err(unsupported_section(&key));
+            }
+            if key != "deprecated_aliases" {
+                return Err(syn::Error::new(
+                    key.span(),
+                    "expected `deprecated_aliases = [<u8>, ..]`",
+                ));
+            }
+            input.parse::<Token![=]>()?;
+            let content;
+            syn::bracketed!(content in input);
+            for alias in Punctuated::<LitInt, Token![,]>::parse_terminated(&content)? {
+                alias.base10_parse::<u8>()?;
+                aliases.push(alias);
+            }
+            input.parse::<Option<Token![,]>>()?;
+        }
+        if !input.is_empty() {
+            return Err(input.error("unexpected tokens after the discriminant"));
+        }
+        Ok(Self {
+            discriminant,
+            aliases,
+        })
+    }
+}
+
+struct AccountDef {
+    name: Ident,
+    account_type: Ident,
+    /// Argument of `address(KEY)`, `same_as(account)` or `key_in(State::field of account)`
+    type_arg: Option<TypeArg>,
+    validation: Option<Ident>,
+    desc: LitStr,
+    /// `label: "..."` after the desc
+    label: Option<LitStr>,
+    /// `explorer_hint: kind` after the desc (or label)
+    explorer_hint: Option<Ident>,
+}
+
+enum TypeArg {
+    Address(Expr),
+    SameAs(Ident),
+    KeyIn {
+        state: Ident,
+        field: Ident,
+        of: Ident,
+    },
+}
+
+impl TypeArg {
+    // The account a `same_as` or `key_in` account refers to
+    fn refers_to(&self) -> Option<&Ident> {
+        match self {
+            Self::Address(_) => None,
+            Self::SameAs(other) => Some(other),
+            Self::KeyIn { of, .. } => Some(of),
+        }
+    }
+}
+
+impl quote::ToTokens for TypeArg {
+    fn to_tokens(&self, tokens: &mut TokenStream2) {
+        tokens.extend(match self {
+            Self::Address(address) => quote!((#address)),
+            Self::SameAs(other) => quote!((#other)),
+            Self::KeyIn { state, field, of } => quote!((#state::#field of #of)),
+        });
+    }
+}
+
+// `(KEY)`, `(account)` or `(State::field of account)` after the account type
+fn parse_type_arg(input: ParseStream, account_type: &Ident) -> syn::Result<Option<TypeArg>> {
+    let expected = match account_type.to_string().as_str() {
+        "address" => "expected the pinned key, e.g. `address(pinocchio_system::ID)`",
+        "same_as" => "expected the account to match, e.g. `same_as(authority)`",
+        "key_in" => "expected the state field holding the key, e.g. `key_in(Platform::vault of platform)`",
+        _ => return Ok(None),
+    };
+    if !input.peek(syn::token::Paren) {
+        return Err(syn::Error::new(account_type.span(), expected));
+    }
+    let content;
+    syn::parenthesized!(content in input);
+    let type_arg = match account_type.to_string().as_str() {
+        "address" => TypeArg::Address(content.parse()?),
+        "same_as" => TypeArg::SameAs(content.parse()?),
+        _ => {
+            let state: Ident = content.parse()?;
+            content.parse::<Token![::]>()?;
+            let field: Ident = content.parse()?;
+            let of_key: Ident = content.parse()?;
+            if of_key != "of" {
+                return Err(syn::Error::new(of_key.span(), expected));
+            }
+            TypeArg::KeyIn {
+                state,
+                field,
+                of: content.parse()?,
+            }
+        }
+    };
+    if !content.is_empty() {
+        return Err(content.error(expected));
+    }
+    Ok(Some(type_arg))
+}
+
+/// Account shorthands: name, pinned address, desc and explorer hint
+const SHORTHANDS: &[(&str, &str, &str, &str)] = &[
+    ("system_program", "pinocchio_system::ID", "System program", "program"),
+    ("token_program", "pinocchio_token::ID", "Token program", "program"),
+    (
+        "ata_program",
+        "pinocchio_associated_token_account::ID",
+        "Associated Token program",
+        "program",
+    ),
+    ("rent_sysvar", "pinocchio::sysvars::rent::RENT_ID", "Rent sysvar", "sysvar"),
+];
+
+/// Kinds an `explorer_hint:` can name, as in `jiminy::metadata::EXPLORER_HINTS`
+const EXPLORER_HINTS: &[&str] = &["pda", "wallet", "token_account", "mint", "program", "sysvar"];
+
+// `name: address(KEY), desc: "..."` for a shorthand account type
+fn shorthand_account(name: Ident, shorthand: &Ident) -> syn::Result<AccountDef> {
+    let Some((_, address, desc, hint)) = SHORTHANDS.iter().find(|(s, ..)| shorthand == s) else {
+        let known: Vec<&str> = SHORTHANDS.iter().map(|(s, ..)| *s).collect();
+        return Err(syn::Error::new(
+            shorthand.span(),
+            format!(
+                "`{shorthand}` is not an account shorthand ({}), \
+                 write `{name}: type, desc: \"...\"`",
+                known.join(", ")
+            ),
+        ));
+    };
+    Ok(AccountDef {
+        account_type: Ident::new("address", shorthand.span()),
+        type_arg: Some(TypeArg::Address(syn::parse_str(address)?)),
+        validation: None,
+        desc: LitStr::new(desc, shorthand.span()),
+        label: None,
+        explorer_hint: Some(Ident::new(hint, shorthand.span())),
+        name,
+    })
+}
+
+// `, key: value` after an account's desc, where an account named `key` would
+// instead be followed by its type and desc
+fn peek_extra(input: ParseStream, key: &str, value: impl Fn(ParseStream) -> bool) -> bool {
+    let fork = input.fork();
+    fork.parse::<Token![,]>().is_ok()
+        && fork.parse::<Ident>().is_ok_and(|k| k == key)
+        && fork.parse::<Token![:]>().is_ok()
+        && value(&fork)
+}
+
+struct Accounts(Vec<AccountDef>);
+
+impl Parse for Accounts {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut accounts: Vec<AccountDef> = Vec::new();
+        while !input.is_empty() {
+            // name: type [=> writable], desc: "..."
+            let name: Ident = input.parse()?;
+            if let Some(previous) = accounts.iter().find(|a| a.name == name) {
+                let mut error =
+                    syn::Error::new(name.span(), format!("account `{name}` is declared twice"));
+                error.combine(syn::Error::new(previous.name.span(), "first declared here"));
+                return Err(error);
+            }
+
+            // `system_program` alone or `name: system_program`, without a desc
+            let ends = |input: ParseStream| {
+                let fork = input.fork();
+                fork.is_empty()
+                    || (fork.parse::<Token![,]>().is_ok()
+                        && !fork.parse::<Ident>().is_ok_and(|key| key == "desc"))
+            };
+            let account_type: Ident = if ends(input) {
+                name.clone()
+            } else {
+                input.parse::<Token![:]>()?;
+                input.parse()?
+            };
+            if ends(input) {
+                accounts.push(shorthand_account(name, &account_type)?);
+                if input.is_empty() {
+                    break;
+                }
+                input.parse::<Token![,]>()?;
+                continue;
+            }
+
+            if !ACCOUNT_TYPES.iter().any(|t| account_type == t) {
+                return Err(syn::Error::new(
+                    account_type.span(),
+                    format!(
+                        "unknown account type `{account_type}`, expected one of: {}",
+                        ACCOUNT_TYPES.join(", ")
+                    ),
+                ));
+            }
+
+            let type_arg = parse_type_arg(input, &account_type)?;
+            // Checks run in declaration order, so only earlier accounts can be named
+            if let Some(other) = type_arg.as_ref().and_then(TypeArg::refers_to) {
+                if !accounts.iter().any(|a| a.name == *other) {
+                    return Err(syn::Error::new(
+                        other.span(),
+                        format!("`{name}` refers to `{other}`, which must be declared before it"),
+                    ));
+                }
+            }
+
+            let validation = if input.peek(Token![=>]) {
+                input.parse::<Token![=>]>()?;
+                let validation: Ident = input.parse()?;
+                if validation != "writable" {
+                    return Err(syn::Error::new(
+                        validation.span(),
+                        format!("unknown validation `{validation}`, expected `writable`"),
+                    ));
+                }
+                Some(validation)
+            } else {
+                None
+            };
+
+            if !input.peek(Token![,]) {
+                return Err(
+                    input.error(format!("expected `, desc: \"...\"` after account `{name}`"))
+                );
+            }
+            input.parse::<Token![,]>()?;
+            let desc_key: Ident = input.parse()?;
+            if desc_key != "desc" {
+                return Err(syn::Error::new(
+                    desc_key.span(),
+                    format!("expected `desc: \"...\"` for account `{name}`"),
+                ));
+            }
+            input.parse::<Token![:]>()?;
+            let desc: LitStr = input.parse()?;
+
+            let label = if peek_extra(input, "label", |fork| fork.peek(LitStr)) {
+                input.parse::<Token![,]>()?;
+                input.parse::<Ident>()?;
+                input.parse::<Token![:]>()?;
+                Some(input.parse::<LitStr>()?)
+            } else {
+                None
+            };
+            let is_hint = |fork: ParseStream| {
+                if fork.parse::<Ident>().is_err()
+                    || fork.peek(Token![=>])
+                    || fork.peek(syn::token::Paren)
+                {
+                    return false;
+                }
+                // `explorer_hint: program, desc: "..."` declares an account named `explorer_hint`
+                let account = fork.parse::<Token![,]>().is_ok()
+                    && fork.parse::<Ident>().is_ok_and(|key| key == "desc");
+                !account
+            };
+            let explorer_hint = if peek_extra(input, "explorer_hint", is_hint) {
+                input.parse::<Token![,]>()?;
+                input.parse::<Ident>()?;
+                input.parse::<Token![:]>()?;
+                let hint: Ident = input.parse()?;
+                if !EXPLORER_HINTS.iter().any(|h| hint == h) {
+                    return Err(syn::Error::new(
+                        hint.span(),
+                        format!(
+                            "unknown explorer_hint `{hint}`, expected one of: {}",
+                            EXPLORER_HINTS.join(", ")
+                        ),
+                    ));
+                }
+                Some(hint)
+            } else {
+                None
+            };
+
+            accounts.push(AccountDef {
+                name,
+                account_type,
+                type_arg,
+                validation,
+                desc,
+                label,
+                explorer_hint,
+            });
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(Self(accounts))
+    }
+}
+
+struct FieldDef {
+    name: Ident,
+    field_type: Type,
+    /// `///` lines on the field
+    docs: Vec<LitStr>,
+}
+
+struct Data(Vec<FieldDef>);
+
+impl Parse for Data {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut fields: Vec<FieldDef> = Vec::new();
+        while !input.is_empty() {
+            // [/// doc] name: type [=> idl_type]
+            let docs = doc_strings(&input.call(Attribute::parse_outer)?)?;
+            let name: Ident = input.parse()?;
+            if fields.iter().any(|f| f.name == name) {
+                return Err(syn::Error::new(
+                    name.span(),
+                    format!("data field `{name}` is declared twice"),
+                ));
+            }
+            input.parse::<Token![:]>()?;
+            let field_type: Type = input.parse()?;
+            if input.peek(Token![=>]) {
+                // Only the build script reads the IDL type
+                input.parse::<Token![=>]>()?;
+                input.parse::<Type>()?;
+            }
+            fields.push(FieldDef {
+                name,
+                field_type,
+                docs,
+            });
+
+            if input.is_empty() {
+                break;
+            }
+            input.parse::<Token![,]>()?;
+        }
+        Ok(Self(fields))
+    }
+}
+
+struct RemainingAccounts {
+    name: Ident,
+    desc: LitStr,
+}
+
+impl Parse for RemainingAccounts {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // name, desc: "..."
+        let name: Ident = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let key: Ident = input.parse()?;
+        if key != "desc" {
+            return Err(syn::Error::new(key.span(), "expected `desc: \"...\"`"));
+        }
+        input.parse::<Token![:]>()?;
+        let desc: LitStr = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self { name, desc })
+    }
+}
+
+struct Rest {
+    name: Ident,
+    max_len: Expr,
+}
+
+impl Parse for Rest {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        // name, max_len: expr
+        let name: Ident = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let key: Ident = input.parse()?;
+        if key != "max_len" {
+            return Err(syn::Error::new(key.span(), "expected `max_len: <usize>`"));
+        }
+        input.parse::<Token![:]>()?;
+        let max_len: Expr = input.parse()?;
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self { name, max_len })
+    }
+}
+
+// Values of `#[doc = "..."]` attributes, anything else is an error
+fn doc_strings(attrs: &[Attribute]) -> syn::Result<Vec<LitStr>> {
+    attrs
+        .iter()
+        .map(|attr| match &attr.meta {
+            Meta::NameValue(MetaNameValue {
+                path,
+                value: Expr::Lit(ExprLit { lit: Lit::Str(doc), .. }),
+                ..
+            }) if path.is_ident("doc") => Ok(doc.clone()),
+            _ => Err(syn::Error::new_spanned(attr, "only doc comments are allowed here")),
+        })
+        .collect()
+}
+
+fn expand_instruction(args: InstructionArgs, module: ItemMod) -> syn::Result<TokenStream2> {
+    // `///` lines on the module are the instruction's description
+    let module_docs: Vec<String> = doc_strings(&module.attrs)?
+        .iter()
+        .map(|doc| doc.value().trim().to_string())
+        .collect();
+    let desc = if module_docs.is_empty() {
+        quote!(None)
+    } else {
+        let desc = module_docs.join(" ");
+        quote!(Some(#desc))
+    };
+    let Some((_, items)) = module.content else {
+        return Err(syn::Error::new(
+            module.ident.span(),
+            "#[instruction] needs an inline module: `mod name { ... }`",
+        ));
+    };
+
+    let mut accounts = None;
+    let mut data = None;
+    let mut rest = None;
+    let mut remaining = None;
+    let mut context = None;
+    let mut process = None;
+    let mut other_items = Vec::new();
+
+    for item in items {
+        let Item::Macro(item_macro) = &item else {
+            other_items.push(item);
+            continue;
+        };
+        let Some(section) = item_macro.mac.path.get_ident() else {
+            other_items.push(item);
+            continue;
+        };
+        let tokens = item_macro.mac.tokens.clone();
+        let span = section.span();
+        if section == "accounts" {
+            set_section(&mut accounts, syn::parse2::<Accounts>(tokens)?, span)?;
+        } else if section == "data" {
+            set_section(&mut data, syn::parse2::<Data>(tokens)?, span)?;
+        } else if section == "rest" {
+            set_section(&mut rest, syn::parse2::<Rest>(tokens)?, span)?;
+        } else if section == "remaining_accounts" {
+            set_section(&mut remaining, syn::parse2::<RemainingAccounts>(tokens)?, span)?;
+        } else if section == "context" {
+            set_section(&mut context, syn::parse2::<Ident>(tokens)?, span)?;
+        } else if section == "process" {
+            set_section(&mut process, tokens, span)?;
+        } else if UNSUPPORTED_SECTIONS.iter().any(|unsupported| section == unsupported) {
+            return Err(unsupported_section(section));
+        } else {
+            other_items.push(item);
+        }
+    }
+
+    let missing = |section: &str| {
+        syn::Error::new(
+            module.ident.span(),
+            format!("missing `{section}! {{ ... }}` section"),
+        )
+    };
+    let accounts = accounts.ok_or_else(|| missing("accounts"))?.0;
+    let data = data.map(|d| d.0).unwrap_or_default();
+    let process = process.ok_or_else(|| missing("process"))?;
+
+    let name = Ident::new(&camel_case(&module.ident.to_string()), module.ident.span());
+    let data_name = format_ident!("{}Data", name);
+    let instruction_name = format_ident!("{}Instruction", name);
+    let metadata_name = format_ident!("{}_METADATA", name);
+    let discriminant = &args.discriminant;
+    let aliases = &args.aliases;
+
+    let account_names: Vec<&Ident> = accounts.iter().map(|a| &a.name).collect();
+    let validations = accounts.iter().map(|a| {
+        let (account, account_type) = (&a.name, &a.account_type);
+        let type_arg = &a.type_arg;
+        let context = account.to_string();
+        match &a.validation {
+            Some(validation) => quote!(
+                ::jiminy::validate_account!(
+                    #account, context: #context, #account_type #type_arg => #validation
+                );
+            ),
+            None => quote!(
+                ::jiminy::validate_account!(#account, context: #context, #account_type #type_arg);
+            ),
+        }
+    });
+    let key_in_checks = accounts
+        .iter()
+        .filter(|a| matches!(a.type_arg, Some(TypeArg::KeyIn { .. })))
+        .map(|a| {
+            let (account, type_arg) = (&a.name, &a.type_arg);
+            quote!(::jiminy::define_instruction_with_metadata!(@key_in #account, key_in #type_arg);)
+        });
+    let account_metadata = accounts.iter().enumerate().map(|(index, a)| {
+        let account = a.name.to_string();
+        let account_type = a.account_type.to_string();
+        let desc = &a.desc;
+        quote!((#account, #account_type, #index, #desc))
+    });
+    let account_descriptors = accounts.iter().enumerate().map(|(index, a)| {
+        let account = a.name.to_string();
+        let account_type = a.account_type.to_string();
+        let validation = a.validation.iter().map(|v| v.to_string());
+        let signer = a.account_type == "signer";
+        let writable = a.validation.is_some() || a.account_type == "uninitialized";
+        let desc = &a.desc;
+        let label = match &a.label {
+            Some(label) => quote!(Some(#label)),
+            None => quote!(None),
+        };
+        let explorer_hint = match &a.explorer_hint {
+            Some(hint) => {
+                let hint = hint.to_string();
+                quote!(Some(#hint))
+            }
+            None => quote!(None),
+        };
+        quote! {
+            ::jiminy::metadata::AccountDescriptor {
+                name: #account,
+                index: #index,
+                account_type: #account_type,
+                validation: &[#(#validation),*],
+                signer: #signer,
+                writable: #writable,
+                desc: #desc,
+                label: #label,
+                explorer_hint: #explorer_hint,
+            }
+        }
+    });
+    let shank_attrs = accounts.iter().map(|a| {
+        let account = a.name.to_string();
+        let signer = a.account_type == "signer";
+        let writable = a.validation.is_some() || a.account_type == "uninitialized";
+        let attrs = [signer.then_some("signer"), writable.then_some("writable")]
+            .into_iter()
+            .flatten();
+        quote!((#account, &[#(#attrs),*]))
+    });
+
+    let field_names: Vec<&Ident> = data.iter().map(|f| &f.name).collect();
+    let field_types: Vec<&Type> = data.iter().map(|f| &f.field_type).collect();
+    let field_type_names: Vec<String> =
+        field_types.iter().map(|t| quote!(#t).to_string()).collect();
+    let field_name_strs: Vec<String> = field_names.iter().map(|f| f.to_string()).collect();
+    let field_docs: Vec<&Vec<LitStr>> = data.iter().map(|f| &f.docs).collect();
+
+    let (split_data, rest_binding, rest_metadata) = match &rest {
+        Some(Rest { name, max_len }) => {
+            let rest_name = name.to_string();
+            (
+                quote!(
+                    ::jiminy::define_instruction_with_metadata!(@split_data data, #data_name, strict: [], #max_len)
+                ),
+                quote!(let #name: &[u8] = rest;),
+                quote!(Some((#rest_name, #max_len))),
+            )
+        }
+        None => (
+            quote!(::jiminy::define_instruction_with_metadata!(@split_data data, #data_name, strict: [])),
+            quote!(),
+            quote!(None),
+        ),
+    };
+
+    let (remaining_binding, remaining_metadata) = match &remaining {
+        Some(RemainingAccounts { name, desc }) => {
+            let remaining_name = name.to_string();
+            (
+                quote! {
+                    #[allow(unused_imports)]
+                    use ::jiminy::account_iter::TypedAccounts as _;
+                    let #name: &[AccountInfo] = remaining_accounts;
+                },
+                quote!(Some((#remaining_name, #desc))),
+            )
+        }
+        None => (quote!(), quote!(None)),
+    };
+
+    let context_binding = context.map(|ctx| quote! {
+        let #ctx = ::jiminy::context::JiminyContext::new();
+    });
+
+    Ok(quote! {
+        #(#other_items)*
+
+        use bytemuck::{Pod, Zeroable};
+        use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+        #[repr(C)]
+        pub struct #name<'info> {
+            #(pub #account_names: &'info AccountInfo,)*
+        }
+
+        #[repr(C)]
+        #[derive(Clone, Copy, Pod, Zeroable)]
+        pub struct #data_name {
+            #(#(#[doc = #field_docs])* pub #field_names: <#field_types as ::jiminy::DataField>::Wire,)*
+        }
+
+        impl #data_name {
+            pub const LEN: usize = core::mem::size_of::<Self>();
+        }
+
+        #[repr(C)]
+        pub struct #instruction_name<'info> {
+            pub accounts: #name<'info>,
+            pub data: #data_name,
+            pub rest: &'info [u8],
+            /// Accounts past the declared ones
+            pub remaining_accounts: &'info [AccountInfo],
+        }
+
+        impl<'info> TryFrom<&'info [AccountInfo]> for #name<'info> {
+            type Error = ProgramError;
+
+            fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+                let [#(#account_names,)* ..] = accounts else {
+                    return Err(ProgramError::NotEnoughAccountKeys);
+                };
+
+                #(#validations)*
+
+                Ok(Self {
+                    #(#account_names,)*
+                })
+            }
+        }
+
+        impl<'info> TryFrom<(&'info [AccountInfo], &'info [u8])> for #instruction_name<'info> {
+            type Error = ProgramError;
+
+            fn try_from((accounts, data): (&'info [AccountInfo], &'info [u8])) -> Result<Self, Self::Error> {
+                let remaining_accounts =
+                    accounts.get(#metadata_name::ACCOUNTS.len()..).unwrap_or_default();
+                let accounts = #name::try_from(accounts)?;
+                let (data, rest, ()) = Self::parse_data(data)?;
+
+                Ok(Self {
+                    accounts,
+                    data,
+                    rest,
+                    remaining_accounts,
+                })
+            }
+        }
+
+        impl<'info> #instruction_name<'info> {
+            /// Parse the instruction data after the discriminator the way `try_from`
+            /// does, without the accounts; the args are always `()` here, there is
+            /// no `data_parser` in the attribute form
+            pub fn parse_data(
+                data: &'info [u8],
+            ) -> Result<(#data_name, &'info [u8], ()), ProgramError> {
+                let (data, rest) = #split_data;
+                Ok((*data, rest, ()))
+            }
+
+            pub fn process(self) -> ProgramResult {
+                #[allow(unused_variables)]
+                let Self { accounts, data, rest, remaining_accounts } = self;
+                #[allow(unused_variables)]
+                let #name { #(#account_names,)* } = accounts;
+                #[allow(unused_variables)]
+                let #data_name { #(#field_names,)* } = data;
+                #(let #field_names = <#field_types as ::jiminy::DataField>::decode(#field_names)?;)*
+                #rest_binding
+                #remaining_binding
+                #context_binding
+
+                #(#key_in_checks)*
+
+                #process
+            }
+        }
+
+        #[doc(hidden)]
+        #[allow(non_snake_case)]
+        pub mod #metadata_name {
+            #[allow(unused_imports)]
+            use super::*;
+
+            pub const DISCRIMINATOR: u8 = #discriminant;
+            pub const ALIASES: &[u8] = &[#(#aliases),*];
+            pub const NAME: &str = stringify!(#name);
+            pub const DESC: Option<&str> = #desc;
+
+            pub const ACCOUNTS: &[(&str, &str, usize, &str)] = &[#(#account_metadata,)*];
+
+            pub const SHANK_ATTRS: &[(&str, &[&str])] = &[#(#shank_attrs,)*];
+
+            pub const FIELDS: &[(&str, &str)] = &[#((#field_name_strs, #field_type_names),)*];
+
+            pub const REST: Option<(&str, usize)> = #rest_metadata;
+
+            pub const REMAINING_ACCOUNTS: Option<(&str, &str)> = #remaining_metadata;
+
+            // The attribute form rejects `transfers`, `returns`, `expiry` and `deprecated`
+            pub const TRANSFERS: &[::jiminy::metadata::TransferDescriptor] = &[];
+            pub const RETURNS: Option<&str> = None;
+
+            pub const fn describe() -> ::jiminy::metadata::InstructionDescriptor {
+                ::jiminy::metadata::InstructionDescriptor {
+                    name: NAME,
+                    desc: DESC,
+                    discriminator: DISCRIMINATOR,
+                    aliases: ALIASES,
+                    accounts: &[#(#account_descriptors,)*],
+                    fields: &[#(
+                        ::jiminy::metadata::FieldDescriptor {
+                            name: #field_name_strs,
+                            field_type: #field_type_names,
+                            docs: &[#(#field_docs),*],
+                        },
+                    )*],
+                    rest: REST,
+                    data_parser: None,
+                    transfers: TRANSFERS,
+                    returns: RETURNS,
+                    remaining_accounts: REMAINING_ACCOUNTS,
+                    expiry: None,
+                    deprecated_after_slot: None,
+                }
+            }
+        }
+
+        impl<'info> #instruction_name<'info> {
+            /// Accounts, data fields and discriminator of this instruction
+            pub const fn describe() -> ::jiminy::metadata::InstructionDescriptor {
+                #metadata_name::describe()
+            }
+        }
+    })
+}
+
+fn set_section<T>(slot: &mut Option<T>, value: T, span: Span) -> syn::Result<()> {
+    if slot.is_some() {
+        return Err(syn::Error::new(span, "section is declared twice"));
+    }
+    *slot = Some(value);
+    Ok(())
+}
+
+fn camel_case(snake: &str) -> String {
+    snake
+        .split('_')
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
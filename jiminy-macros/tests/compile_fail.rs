@@ -0,0 +1,8 @@
+//! The attribute form's errors, with the expected compiler output in `tests/ui`
+//!
+//! `TRYBUILD=overwrite cargo test -p jiminy-macros` rewrites the `.stderr` files.
+
+#[test]
+fn compile_fail() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
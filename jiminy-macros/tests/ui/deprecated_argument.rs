@@ -0,0 +1,13 @@
+// The macro form's `deprecated(after_slot: ...)` written as an argument
+#[jiminy_macros::instruction(discriminant = 0, deprecated(after_slot = 350_000_000))]
+mod initialize_position {
+    accounts! {
+        authority: signer, desc: "Position owner",
+    }
+
+    process! {
+        Ok(())
+    }
+}
+
+fn main() {}
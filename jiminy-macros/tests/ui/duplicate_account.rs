@@ -0,0 +1,15 @@
+// The same account name on two account lines
+#[jiminy_macros::instruction(discriminant = 0)]
+mod increment {
+    accounts! {
+        authority: signer, desc: "Counter owner",
+        counter: program => writable, desc: "Counter to increment",
+        authority: signer, desc: "Counter owner again",
+    }
+
+    process! {
+        Ok(())
+    }
+}
+
+fn main() {}
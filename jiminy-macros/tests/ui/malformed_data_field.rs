@@ -0,0 +1,18 @@
+// A data field without the `:` before its type
+#[jiminy_macros::instruction(discriminant = 0)]
+mod increment {
+    accounts! {
+        counter: program => writable, desc: "Counter to increment",
+    }
+
+    data! {
+        /// How much to add
+        amount [u8; 8],
+    }
+
+    process! {
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+// `program` misspelled on an account line
+#[jiminy_macros::instruction(discriminant = 0)]
+mod increment {
+    accounts! {
+        authority: signer, desc: "Counter owner",
+        counter: progam => writable, desc: "Counter to increment",
+    }
+
+    process! {
+        Ok(())
+    }
+}
+
+fn main() {}
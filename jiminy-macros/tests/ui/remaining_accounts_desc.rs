@@ -0,0 +1,15 @@
+// `remaining_accounts!` with its description under another key
+#[jiminy_macros::instruction(discriminant = 0)]
+mod close_positions {
+    accounts! {
+        authority: signer, desc: "Owner of the positions",
+    }
+
+    remaining_accounts!(positions, description: "Positions to close");
+
+    process! {
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,15 @@
+// The macro form's `returns:` written as a section
+#[jiminy_macros::instruction(discriminant = 0)]
+mod get_count {
+    accounts! {
+        counter: program, desc: "Counter to read",
+    }
+
+    returns!(u64);
+
+    process! {
+        Ok(0)
+    }
+}
+
+fn main() {}
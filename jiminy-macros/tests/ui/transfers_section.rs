@@ -0,0 +1,19 @@
+// The macro form's `transfers:` written as a section
+#[jiminy_macros::instruction(discriminant = 0)]
+mod pay {
+    accounts! {
+        authority: signer, desc: "Authority of the source",
+        source: token => writable, desc: "Token account paying",
+        destination: token => writable, desc: "Token account paid",
+    }
+
+    transfers! {
+        source => destination: 10, authority: authority;
+    }
+
+    process! {
+        Ok(())
+    }
+}
+
+fn main() {}
@@ -0,0 +1,4805 @@
+//! Pinocchio program macros.
+//!
+//! # Feature flags
+//!
+//! - `safe-borrows`: `load!`, `load_mut!` and `with_state!` go through
+//!   `try_borrow_data()`/`try_borrow_mut_data()` instead of the unchecked
+//!   unsafe borrows, and [`perf::load_checked`] replaces
+//!   [`perf::load_unchecked`]. Borrow conflicts surface as
+//!   `ProgramError::AccountBorrowFailed` instead of aliasing. The extra cost
+//!   is one borrow-state check when the state is loaded and one release when
+//!   the guard drops; measure it for your toolchain with
+//!   `examples/counter/benches/compute_units.rs`, which reports the compute
+//!   units of `Increment` and the delta to its previous run, once built
+//!   without and once with `--features safe-borrows`. No measured delta is
+//!   recorded here yet: it depends on the `cargo build-sbf` platform tools
+//!   version, and none has been benched and pinned for this crate. Quote the
+//!   `cargo build-sbf --version` output next to any figure you record.
+//! - `debug-panics`: `jiminy_entrypoint!` installs a panic handler that logs
+//!   where the panic happened, e.g. `panic at instructions/redeem_winnings.rs:87`,
+//!   and jiminy's panicking helpers are `#[track_caller]` so the location is
+//!   the handler code rather than this file. Without the feature the handler
+//!   and the extra attributes are not compiled at all. With it, every panic
+//!   site keeps its file name and line in the binary, so the program grows.
+//!   The vote example's sizes with and without the feature haven't been
+//!   measured for this crate yet; build it with `cargo build-sbf --manifest-path
+//!   examples/vote/Cargo.toml`, again with `--features debug-panics`, and
+//!   compare the two `vote.so` files under the `cargo build-sbf --version`
+//!   you deploy with before shipping a debug build.
+//! - `fast-id-check`: [`perf::keys_eq`], used by the generated dispatch to
+//!   check the program id, compares the keys as four `u64`s instead of
+//!   byte by byte.
+//! - `deprecation-logs`: deprecated discriminator aliases log
+//!   `Discriminator 10 is deprecated, send 0` before dispatching, see
+//!   [`dispatch::deprecated_discriminator`]. Without it aliases dispatch silently.
+//!   It also has `deprecated(after_slot: ...)` instructions warn on every call
+//!   instead of only in the last [`dispatch::DEPRECATION_WARNING_SLOTS`].
+//! - `sysvar-logs`: a failed `Clock` or `Rent` read logs where it happened,
+//!   e.g. `create_pda: Rent sysvar unavailable`, before failing with
+//!   `JiminyError::SysvarUnavailable`, see [`syscalls::rent_in`]. Without it
+//!   the error code alone tells a runtime failure from bad accounts.
+//! - `context-logs`: [`fail!`] logs its context line before returning the
+//!   error, so `assert_pda!`/`validate_pdas!` contexts and the instruction
+//!   accounts name the account that failed, e.g. `vault failed validation`.
+//!   Without it the contexts compile away.
+//! - `unchecked-writable`: drops the [`writable`] checks of `close_account!`,
+//!   `create_pda!` and the vault macros. A read-only account then fails in
+//!   the runtime, without saying which, instead of with `AccountNotWritable`.
+//! - `testing`: host-only helpers for tests, see [`testing`]. Pulls in `std`,
+//!   so only enable it from `[dev-dependencies]`.
+//! - `host-stubs`: [`syscalls`], which every macro reads the clock and rent,
+//!   runs CPIs, sets return data and logs events through, goes to the configurable stand-ins
+//!   in [`testing::stubs`], so programs build and run their handlers in plain
+//!   `cargo test` on the host. Implies `testing`; never enable it for a deploy.
+
+#![no_std]
+// The macros refer to the calling program's `crate::ID` on purpose
+#![allow(clippy::crate_in_macro_def)]
+
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError};
+
+/// Generates complete instruction handler with minimal boilerplate
+/// Also generates metadata for automatic shank enum generation via build script
+///
+/// `system_program`, `token_program`, `ata_program` and `rent_sysvar` can be
+/// listed alone, or as the type of a differently named account, without a
+/// desc; they stand for the account pinned to that address with a standard desc.
+///
+/// An account's desc may be followed by `label: "..."`, a short name for
+/// transaction previews, then `explorer_hint: kind`, one of
+/// [`metadata::EXPLORER_HINTS`]; both end up in its `AccountDescriptor` and
+/// the IDL's `accountMetadata`.
+#[macro_export]
+macro_rules! define_instruction_with_metadata {
+    // Expand the account shorthands first
+    (
+        discriminant: $disc:literal,
+        $name:ident,
+        $(desc: $instruction_desc:literal,)?
+        $(deprecated_aliases: [$($alias:literal),* $(,)?],)?
+        $(deprecated(after_slot: $deprecated_slot:expr),)?
+        accounts: { $($accounts:tt)* },
+        $($rest:tt)*
+    ) => {
+        $crate::define_instruction_with_metadata!(
+            @accounts [
+                discriminant: $disc,
+                $name,
+                $(desc: $instruction_desc,)?
+                $(deprecated_aliases: [$($alias),*],)?
+                $(deprecated(after_slot: $deprecated_slot),)?
+            ]
+            [] [$($accounts)*] $($rest)*
+        );
+    };
+
+    (
+        @expanded
+        discriminant: $disc:literal,
+        $name:ident,
+        // Optional one-line description, shown in the IDL docs
+        $(desc: $instruction_desc:literal,)?
+        // Optional old discriminators that still dispatch here, listed in the metadata only
+        $(deprecated_aliases: [$($alias:literal),* $(,)?],)?
+        // Optional last slot the instruction runs in, it logs a warning until then
+        $(deprecated(after_slot: $deprecated_slot:expr),)?
+        // Accounts with their validation rules and descriptions
+        accounts: {
+            $(
+                $account:ident: $account_type:tt $(($($type_arg:tt)*))? $(=> $validation:tt)*, desc: $desc:literal,
+                    label: [$($label:literal)?], explorer_hint: [$($explorer_hint:ident)?]
+            ),* $(,)?
+        },
+        // Optional name the accounts past the declared ones are bound to, e.g. for batches
+        $(remaining_accounts: $remaining:ident, desc: $remaining_desc:literal,)?
+        // Instruction data fields, optionally annotated with the type the IDL should show;
+        // `///` comments on a field end up in the IDL docs
+        data: {
+            $(
+                $(#[doc = $field_doc:literal])*
+                $field:ident: $field_type:ty $(=> $idl_type:ty)?
+            ),* $(,)?
+        },
+        // Or a function parsing the whole data into `$args`, for layouts that aren't `Pod`,
+        // with the fields the IDL should show; written in place of `data`
+        $(
+            data_parser: $parser:path => $args:ident: $args_type:ty, max_len: $parser_max:expr,
+            data_schema: {
+                $(
+                    $(#[doc = $schema_doc:literal])*
+                    $schema_field:ident: $schema_type:ty
+                ),* $(,)?
+            },
+        )?
+        // Optional trailing bytes after the fixed data, at most `max_len` long
+        $(rest: $rest:ident, max_len: $rest_max:expr,)?
+        // `strict_data: false` ignores bytes past the declared data instead of failing
+        $(strict_data: $strict_data:tt,)?
+        // Optional `[u8; 8]` data field holding the last slot the instruction may run in,
+        // e.g. `expiry: slot_field(expires_at_slot),`
+        $(expiry: slot_field($expiry_field:ident),)?
+        // Optional name of a `JiminyContext` caching the clock and rent, e.g. `context: ctx,`
+        $(context: $ctx:ident,)?
+        // Optional statements run before the transfers, their bindings stay in scope for the rest
+        $(prepare: { $($prepare:tt)* },)?
+        // Optional token transfers, run in order after `prepare` and before the process body;
+        // zero amounts are skipped
+        $(transfers: {
+            $(
+                $from:ident => $to:ident: $amount:expr, authority: $transfer_authority:ident
+                    $(, seeds: [$($transfer_seed:expr),* $(,)?])?
+            );* $(;)?
+        },)?
+        // Optional balance changes checked with `runtime-invariants`, read before the transfers
+        // and checked after the body, e.g. `vault_token_account.tokens: increases_by(fee)`
+        $(invariants: {
+            $(
+                $inv_account:ident . $inv_balance:ident : $inv_direction:ident ( $inv_amount:expr )
+            ),* $(,)?
+        },)?
+        // Optional Pod type the process body returns, handed to CPI callers as return data
+        $(returns: $ret:ty,)?
+        // Process function body
+        process: $process_body:block
+    ) => {
+        use bytemuck::{Pod, Zeroable};
+        use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+        #[repr(C)]
+        pub struct $name<'info> {
+            $(pub $account: &'info AccountInfo,)*
+        }
+
+        $crate::paste! {
+            #[repr(C)]
+            #[derive(Clone, Copy, Pod, Zeroable)]
+            pub struct [<$name Data>] {
+                $($(#[doc = $field_doc])* pub $field: <$field_type as $crate::DataField>::Wire,)*
+            }
+
+            impl [<$name Data>] {
+                pub const LEN: usize = core::mem::size_of::<Self>();
+            }
+
+            #[repr(C)]
+            pub struct [<$name Instruction>]<'info> {
+                pub accounts: $name<'info>,
+                pub data: [<$name Data>],
+                pub rest: &'info [u8],
+                /// Accounts past the declared ones
+                pub remaining_accounts: &'info [AccountInfo],
+                /// What the `data_parser` returned, `()` without one
+                pub args: $crate::define_instruction_with_metadata!(@args_type $($args_type)?),
+            }
+        }
+
+        impl<'info> TryFrom<&'info [AccountInfo]> for $name<'info> {
+            type Error = ProgramError;
+
+            fn try_from(accounts: &'info [AccountInfo]) -> Result<Self, Self::Error> {
+                // Destructure accounts array
+                let [$($account,)* ..] = accounts else {
+                    return Err(ProgramError::NotEnoughAccountKeys);
+                };
+
+                // Apply validations
+                $(
+                    $crate::validate_account!(
+                        $account,
+                        context: stringify!($account),
+                        $account_type $(($($type_arg)*))? $(=> $validation)*
+                    );
+                )*
+
+                Ok(Self {
+                    $($account,)*
+                })
+            }
+        }
+
+        $crate::paste! {
+            impl<'info> TryFrom<(&'info [AccountInfo], &'info [u8])> for [<$name Instruction>]<'info> {
+                type Error = ProgramError;
+
+                fn try_from((accounts, data): (&'info [AccountInfo], &'info [u8])) -> Result<Self, Self::Error> {
+                    // A sunset instruction fails before its accounts are looked at
+                    $($crate::dispatch::check_deprecated(stringify!($name), $deprecated_slot)?;)?
+                    let remaining_accounts =
+                        accounts.get([<$name _METADATA>]::ACCOUNTS.len()..).unwrap_or_default();
+                    let accounts = $name::try_from(accounts)?;
+                    let (data, rest, args) = Self::parse_data(data)?;
+
+                    Ok(Self {
+                        accounts,
+                        data,
+                        rest,
+                        remaining_accounts,
+                        args,
+                    })
+                }
+            }
+
+            impl<'info> [<$name Instruction>]<'info> {
+                /// Parse the instruction data after the discriminator the way `try_from`
+                /// does, without the accounts
+                #[allow(clippy::type_complexity)]
+                pub fn parse_data(
+                    data: &'info [u8],
+                ) -> Result<
+                    (
+                        [<$name Data>],
+                        &'info [u8],
+                        $crate::define_instruction_with_metadata!(@args_type $($args_type)?),
+                    ),
+                    ProgramError,
+                > {
+                    let (data, rest, args) = $crate::define_instruction_with_metadata!(
+                        @parse_data [$($parser, $parser_max)?] data, [<$name Data>],
+                        strict: [$($strict_data)?] $(, $rest_max)?
+                    );
+                    Ok((*data, rest, args))
+                }
+
+                pub fn process(self) -> ProgramResult {
+                    // Destructure for easier access in process body
+                    #[allow(unused_variables)]
+                    let Self { accounts, data, rest, remaining_accounts, args: parsed_args } = self;
+                    #[allow(unused_variables)]
+                    let $name { $($account,)* } = accounts;
+                    #[allow(unused_variables)]
+                    let [<$name Data>] { $($field,)* } = data;
+                    $(let $args: $args_type = parsed_args;)?
+                    $(let $field = <$field_type as $crate::DataField>::decode($field)?;)*
+                    $(let $rest: &[u8] = rest;)?
+                    $(
+                        #[allow(unused_imports)]
+                        use $crate::account_iter::TypedAccounts as _;
+                        let $remaining: &[AccountInfo] = remaining_accounts;
+                    )?
+                    $(let $ctx = $crate::context::JiminyContext::new();)?
+
+                    // Stale instructions fail before anything else runs
+                    $crate::define_instruction_with_metadata!(
+                        @expiry [$($expiry_field)?] [$($ctx)?]
+                    );
+
+                    // `key_in` accounts, after every account passed its own checks
+                    $(
+                        $crate::define_instruction_with_metadata!(
+                            @key_in $account, $account_type $(($($type_arg)*))?
+                        );
+                    )*
+
+                    $($($prepare)*)?
+
+                    // Balances and amounts the invariants start from, read with `runtime-invariants`
+                    $(let invariants_before = [$(
+                        if $crate::invariants::ENABLED {
+                            ($crate::invariants::$inv_balance($inv_account)?, $inv_amount)
+                        } else {
+                            (0, 0)
+                        }
+                    ),*];)?
+
+                    // A transfer of zero, e.g. a waived fee, makes no CPI, see `transfer_tokens!`
+                    $($(
+                        $crate::transfer_tokens!(
+                            $from, $to, $transfer_authority, $amount $(, seeds: [$($transfer_seed),*])?
+                        );
+                    )*)?
+
+                    $crate::define_instruction_with_metadata!(
+                        @run [$(invariants_before, $($inv_account . $inv_balance : $inv_direction),*)?]
+                        $process_body $(, $ret)?
+                    )
+                }
+            }
+
+            $(
+                impl<'info> [<$name Instruction>]<'info> {
+                    /// Decodes the return data this instruction sets, e.g. from a simulation
+                    pub fn decode_return_data(data: &[u8]) -> Option<$ret> {
+                        bytemuck::try_pod_read_unaligned(data).ok()
+                    }
+                }
+
+                const _: () = assert!(
+                    core::mem::size_of::<$ret>() <= pinocchio::cpi::MAX_RETURN_DATA,
+                    "return type is larger than the maximum return data"
+                );
+            )?
+
+            // Export metadata for build script parsing with auto-generated shank attributes
+            #[doc(hidden)]
+            #[allow(non_snake_case)]
+            pub mod [<$name _METADATA>] {
+                // Lets `max_len` name constants from the instruction's module
+                #[allow(unused_imports)]
+                use super::*;
+
+                pub const DISCRIMINATOR: u8 = $disc;
+                pub const ALIASES: &[u8] = &[$($($alias),*)?];
+                pub const NAME: &str = stringify!($name);
+                pub const DESC: Option<&str> = $crate::define_instruction_with_metadata!(@desc $($instruction_desc)?);
+
+                // Declaration order gives each account its index
+                #[allow(non_camel_case_types, dead_code)]
+                enum AccountIndex {
+                    $($account,)*
+                }
+
+                // `same_as` and `key_in` may only name accounts declared before them
+                $(
+                    $crate::define_instruction_with_metadata!(
+                        @declared_before $account, $account_type $(($($type_arg)*))?
+                    );
+                )*
+
+                // Account metadata with indices in declaration order
+                pub const ACCOUNTS: &[(&str, &str, usize, &str)] = &[
+                    $(
+                        (stringify!($account), stringify!($account_type), AccountIndex::$account as usize, $desc),
+                    )*
+                ];
+
+                // Auto-generated shank attributes
+                pub const SHANK_ATTRS: &[(&str, &[&str])] = &[
+                    $(
+                        (stringify!($account), $crate::define_instruction_with_metadata!(@shank_attrs $account_type $(=> $validation)*)),
+                    )*
+                ];
+
+                pub const FIELDS: &[(&str, &str)] = &[
+                    $(
+                        (stringify!($field), stringify!($field_type)),
+                    )*
+                    $($(
+                        (stringify!($schema_field), stringify!($schema_type)),
+                    )*)?
+                ];
+
+                // Trailing bytes field name and max length, if declared
+                pub const REST: Option<(&str, usize)> =
+                    $crate::define_instruction_with_metadata!(@rest $($rest, $rest_max)?);
+
+                // Data parser name and max data length, if declared
+                pub const DATA_PARSER: Option<(&str, usize)> =
+                    $crate::define_instruction_with_metadata!(@data_parser $($parser, $parser_max)?);
+
+                // Type of the return data, if declared
+                pub const RETURNS: Option<&str> = $crate::define_instruction_with_metadata!(@returns $($ret)?);
+
+                // Data field holding the expiry slot, if declared
+                pub const EXPIRY: Option<&str> =
+                    $crate::define_instruction_with_metadata!(@expiry_field $($expiry_field)?);
+
+                // Last slot the instruction runs in, if declared deprecated
+                pub const DEPRECATED_AFTER_SLOT: Option<u64> =
+                    $crate::define_instruction_with_metadata!(@deprecated $($deprecated_slot)?);
+
+                // Name and description of the remaining accounts, if declared
+                pub const REMAINING_ACCOUNTS: Option<(&str, &str)> = $crate::define_instruction_with_metadata!(
+                    @remaining $($remaining, $remaining_desc)?
+                );
+
+                // Token transfers declared in the `transfers` section, in execution order
+                pub const TRANSFERS: &[$crate::metadata::TransferDescriptor] = &[
+                    $($(
+                        $crate::metadata::TransferDescriptor {
+                            from: stringify!($from),
+                            to: stringify!($to),
+                            amount: stringify!($amount),
+                            authority: stringify!($transfer_authority),
+                            signed: $crate::define_instruction_with_metadata!(@signed $($($transfer_seed),*)?),
+                        },
+                    )*)?
+                ];
+
+                pub const fn describe() -> $crate::metadata::InstructionDescriptor {
+                    $crate::metadata::InstructionDescriptor {
+                        name: NAME,
+                        desc: DESC,
+                        discriminator: DISCRIMINATOR,
+                        aliases: ALIASES,
+                        accounts: &[
+                            $(
+                                $crate::metadata::AccountDescriptor {
+                                    name: stringify!($account),
+                                    index: AccountIndex::$account as usize,
+                                    account_type: stringify!($account_type),
+                                    validation: &[$(stringify!($validation)),*],
+                                    signer: $crate::define_instruction_with_metadata!(@signer $account_type $(=> $validation)*),
+                                    writable: $crate::define_instruction_with_metadata!(@writable $account_type $(=> $validation)*),
+                                    desc: $desc,
+                                    label: $crate::define_instruction_with_metadata!(@desc $($label)?),
+                                    explorer_hint: $crate::define_instruction_with_metadata!(
+                                        @explorer_hint $($explorer_hint)?
+                                    ),
+                                },
+                            )*
+                        ],
+                        fields: &[
+                            $(
+                                $crate::metadata::FieldDescriptor {
+                                    name: stringify!($field),
+                                    field_type: stringify!($field_type),
+                                    docs: &[$($field_doc),*],
+                                },
+                            )*
+                            $($(
+                                $crate::metadata::FieldDescriptor {
+                                    name: stringify!($schema_field),
+                                    field_type: stringify!($schema_type),
+                                    docs: &[$($schema_doc),*],
+                                },
+                            )*)?
+                        ],
+                        rest: REST,
+                        data_parser: DATA_PARSER,
+                        transfers: TRANSFERS,
+                        returns: RETURNS,
+                        remaining_accounts: REMAINING_ACCOUNTS,
+                        expiry: EXPIRY,
+                        deprecated_after_slot: DEPRECATED_AFTER_SLOT,
+                    }
+                }
+            }
+
+            impl<'info> [<$name Instruction>]<'info> {
+                /// Accounts, data fields and discriminator of this instruction
+                pub const fn describe() -> $crate::metadata::InstructionDescriptor {
+                    [<$name _METADATA>]::describe()
+                }
+            }
+        }
+    };
+
+    // Helper moving the accounts over one at a time, shorthands written out in full;
+    // a `data_parser` instruction gets an empty `data` block
+    (
+        @accounts [$($head:tt)*] [$($done:tt)*] []
+        $(remaining_accounts: $remaining:ident, desc: $remaining_desc:literal,)?
+        data_parser: $($rest:tt)*
+    ) => {
+        $crate::define_instruction_with_metadata!(
+            @expanded $($head)* accounts: { $($done)* },
+            $(remaining_accounts: $remaining, desc: $remaining_desc,)?
+            data: {}, data_parser: $($rest)*
+        );
+    };
+    (@accounts [$($head:tt)*] [$($done:tt)*] [] $($rest:tt)*) => {
+        $crate::define_instruction_with_metadata!(
+            @expanded $($head)* accounts: { $($done)* }, $($rest)*
+        );
+    };
+    (
+        @accounts $head:tt $done:tt
+        [
+            $account:ident: $account_type:ident $(($($type_arg:tt)*))? $(=> $validation:tt)*,
+            desc: $desc:literal $(, $($more:tt)*)?
+        ]
+        $($rest:tt)*
+    ) => {
+        $crate::define_instruction_with_metadata!(
+            @account_extras $head $done
+            [$account: $account_type $(($($type_arg)*))? $(=> $validation)*, desc: $desc]
+            [] [] [$($($more)*)?] $($rest)*
+        );
+    };
+    // `label: "..."` and then `explorer_hint: kind` after a desc, both optional
+    (
+        @account_extras $head:tt $done:tt $account:tt [] []
+        [label: $label:literal $(, $($more:tt)*)?] $($rest:tt)*
+    ) => {
+        $crate::define_instruction_with_metadata!(
+            @account_extras $head $done $account [$label] [] [$($($more)*)?] $($rest)*
+        );
+    };
+    (
+        @account_extras $head:tt $done:tt $account:tt $label:tt []
+        [explorer_hint: $explorer_hint:ident $(, $($more:tt)*)?] $($rest:tt)*
+    ) => {
+        $crate::define_instruction_with_metadata!(
+            @account_extras $head $done $account $label [$explorer_hint] [$($($more)*)?] $($rest)*
+        );
+    };
+    (
+        @account_extras $head:tt [$($done:tt)*] [$($account:tt)*] [$($label:tt)?]
+        [$($explorer_hint:tt)?] $more:tt $($rest:tt)*
+    ) => {
+        $crate::define_instruction_with_metadata!(
+            @accounts $head
+            [$($done)* $($account)*, label: [$($label)?], explorer_hint: [$($explorer_hint)?],]
+            $more $($rest)*
+        );
+    };
+    (
+        @accounts $head:tt [$($done:tt)*]
+        [$account:ident: system_program $(, $($more:tt)*)?] $($rest:tt)*
+    ) => {
+        $crate::define_instruction_with_metadata!(
+            @accounts $head
+            [
+                $($done)* $account: address(pinocchio_system::ID), desc: "System program",
+                label: [], explorer_hint: [program],
+            ]
+            [$($($more)*)?] $($rest)*
+        );
+    };
+    (
+        @accounts $head:tt [$($done:tt)*]
+        [$account:ident: token_program $(, $($more:tt)*)?] $($rest:tt)*
+    ) => {
+        $crate::define_instruction_with_metadata!(
+            @accounts $head
+            [
+                $($done)* $account: address(pinocchio_token::ID), desc: "Token program",
+                label: [], explorer_hint: [program],
+            ]
+            [$($($more)*)?] $($rest)*
+        );
+    };
+    (
+        @accounts $head:tt [$($done:tt)*]
+        [$account:ident: ata_program $(, $($more:tt)*)?] $($rest:tt)*
+    ) => {
+        $crate::define_instruction_with_metadata!(
+            @accounts $head
+            [
+                $($done)* $account: address(pinocchio_associated_token_account::ID),
+                desc: "Associated Token program", label: [], explorer_hint: [program],
+            ]
+            [$($($more)*)?] $($rest)*
+        );
+    };
+    (
+        @accounts $head:tt [$($done:tt)*]
+        [$account:ident: rent_sysvar $(, $($more:tt)*)?] $($rest:tt)*
+    ) => {
+        $crate::define_instruction_with_metadata!(
+            @accounts $head
+            [
+                $($done)* $account: address(pinocchio::sysvars::rent::RENT_ID), desc: "Rent sysvar",
+                label: [], explorer_hint: [sysvar],
+            ]
+            [$($($more)*)?] $($rest)*
+        );
+    };
+    (
+        @accounts $head:tt $done:tt
+        [$account:ident: $other:ident $(, $($more:tt)*)?] $($rest:tt)*
+    ) => {
+        compile_error!(concat!(
+            "`", stringify!($other), "` is not an account shorthand (system_program, ",
+            "token_program, ata_program, rent_sysvar), write `", stringify!($account),
+            ": type, desc: \"...\"`"
+        ));
+    };
+    // A bare shorthand names the account after itself
+    (@accounts $head:tt $done:tt [$account:ident $(, $($more:tt)*)?] $($rest:tt)*) => {
+        $crate::define_instruction_with_metadata!(
+            @accounts $head $done [$account: $account $(, $($more)*)?] $($rest)*
+        );
+    };
+
+    // Helper splitting the data into the fixed struct, the trailing bytes and the parsed
+    // args; a `data_parser` gets all of it, longer data than its `max_len` fails
+    (@parse_data [] $data:ident, $data_type:ty, strict: $strict:tt $(, $rest_max:expr)?) => {{
+        let (data, rest) = $crate::define_instruction_with_metadata!(
+            @split_data $data, $data_type, strict: $strict $(, $rest_max)?
+        );
+        (data, rest, ())
+    }};
+    (
+        @parse_data [$parser:path, $max_len:expr] $data:ident, $data_type:ty,
+        strict: $strict:tt $(, $rest_max:expr)?
+    ) => {{
+        let max_len: usize = $max_len;
+        if $data.len() > max_len {
+            return Err($crate::error::trailing_instruction_data($data.len() - max_len));
+        }
+        (bytemuck::from_bytes::<$data_type>(&[]), &[][..], $parser($data)?)
+    }};
+
+    // Helper to parse the fixed data exactly, failing on any trailing bytes
+    (@split_data $data:ident, $data_type:ty, strict: [$(true)?]) => {{
+        if $data.len() > <$data_type>::LEN {
+            return Err($crate::error::trailing_instruction_data($data.len() - <$data_type>::LEN));
+        }
+        (
+            bytemuck::try_from_bytes::<$data_type>($data)
+                .map_err(|_| ProgramError::InvalidInstructionData)?,
+            &[][..],
+        )
+    }};
+
+    // Helper to parse the fixed data from the prefix, failing past `max_len` trailing bytes
+    (@split_data $data:ident, $data_type:ty, strict: [$(true)?], $rest_max:expr) => {{
+        if $data.len() < <$data_type>::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (fixed, rest) = $data.split_at(<$data_type>::LEN);
+        if rest.len() > $rest_max {
+            return Err($crate::error::trailing_instruction_data(rest.len() - $rest_max));
+        }
+        (
+            bytemuck::try_from_bytes::<$data_type>(fixed)
+                .map_err(|_| ProgramError::InvalidInstructionData)?,
+            rest,
+        )
+    }};
+
+    // Helper to parse the fixed data from the prefix and ignore the rest (`strict_data: false`)
+    (@split_data $data:ident, $data_type:ty, strict: [false]) => {{
+        let fixed = $data.get(..<$data_type>::LEN).ok_or(ProgramError::InvalidInstructionData)?;
+        (
+            bytemuck::try_from_bytes::<$data_type>(fixed)
+                .map_err(|_| ProgramError::InvalidInstructionData)?,
+            &[][..],
+        )
+    }};
+
+    // Helper to parse the fixed data from the prefix and return up to `max_len` trailing bytes, ignoring the others
+    (@split_data $data:ident, $data_type:ty, strict: [false], $rest_max:expr) => {{
+        if $data.len() < <$data_type>::LEN {
+            return Err(ProgramError::InvalidInstructionData);
+        }
+        let (fixed, rest) = $data.split_at(<$data_type>::LEN);
+        (
+            bytemuck::try_from_bytes::<$data_type>(fixed)
+                .map_err(|_| ProgramError::InvalidInstructionData)?,
+            &rest[..rest.len().min($rest_max)],
+        )
+    }};
+
+    // Helper to run the process body, then check the invariants if there are any
+    (@run [] $process_body:block $(, $ret:ty)?) => {
+        $crate::define_instruction_with_metadata!(@process $process_body $(, $ret)?)
+    };
+    (
+        @run [$before:ident, $($account:ident . $balance:ident : $direction:ident),+]
+        $process_body:block $(, $ret:ty)?
+    ) => {{
+        // A closure so an early `return Ok(())` in the body still gets checked
+        let result = (|| -> ProgramResult {
+            $crate::define_instruction_with_metadata!(@process $process_body $(, $ret)?)
+        })();
+        if $crate::invariants::ENABLED && result.is_ok() {
+            let after = [$(
+                (
+                    stringify!($account),
+                    $crate::invariants::$balance($account)?,
+                    $crate::invariants::$direction as fn(u64, u64, u64) -> bool,
+                )
+            ),+];
+            for ((account, after, holds), (before, amount)) in after.into_iter().zip($before) {
+                $crate::invariants::check(account, before, after, holds, amount)?;
+            }
+        }
+        result
+    }};
+
+    // Helper to run the process body, setting its value as return data when `returns` is declared
+    (@process $process_body:block) => { $process_body };
+    (@process $process_body:block, $ret:ty) => {{
+        let value: $ret = (|| -> Result<$ret, ProgramError> { $process_body })()?;
+        $crate::syscalls::set_return_data(bytemuck::bytes_of(&value));
+        Ok(())
+    }};
+
+    // Helper to check a `key_in` account against the key in the state it names
+    (@key_in $account:ident, key_in($state:ident :: $field:ident of $of:ident)) => {
+        if $crate::read_state!($of, $state, |state| state.$field) != *$account.key() {
+            return Err($crate::error::account_key_mismatch(
+                stringify!($account),
+                concat!(stringify!($state), "::", stringify!($field), " of ", stringify!($of)),
+            ));
+        }
+    };
+    (@key_in $account:ident, key_in($($key_in:tt)*)) => {
+        compile_error!(concat!(
+            "expected `", stringify!($account), ": key_in(State::field of account)`"
+        ));
+    };
+    (@key_in $account:ident, $($account_type:tt)*) => {};
+
+    // Helper to reject `same_as` and `key_in` references to the account itself or a later one
+    (@declared_before $account:ident, same_as($other:ident)) => {
+        $crate::define_instruction_with_metadata!(@account_order $other < $account);
+    };
+    (@declared_before $account:ident, key_in($state:ident :: $field:ident of $of:ident)) => {
+        $crate::define_instruction_with_metadata!(@account_order $of < $account);
+    };
+    (@declared_before $account:ident, $($account_type:tt)*) => {};
+    (@account_order $other:ident < $account:ident) => {
+        const _: () = assert!(
+            (AccountIndex::$other as usize) < (AccountIndex::$account as usize),
+            concat!(
+                "`", stringify!($account), "` refers to `", stringify!($other),
+                "`, which must be declared before it"
+            )
+        );
+    };
+
+    // Helper for the return type metadata
+    (@desc) => { None };
+    (@desc $desc:literal) => { Some($desc) };
+    (@explorer_hint) => { None };
+    (@explorer_hint pda) => { Some("pda") };
+    (@explorer_hint wallet) => { Some("wallet") };
+    (@explorer_hint token_account) => { Some("token_account") };
+    (@explorer_hint mint) => { Some("mint") };
+    (@explorer_hint program) => { Some("program") };
+    (@explorer_hint sysvar) => { Some("sysvar") };
+    (@explorer_hint $other:ident) => {
+        compile_error!(concat!(
+            "unknown explorer_hint `", stringify!($other),
+            "`, expected pda, wallet, token_account, mint, program or sysvar"
+        ))
+    };
+    (@returns) => { None };
+    (@returns $ret:ty) => { Some(stringify!($ret)) };
+    (@expiry_field) => { None };
+    (@expiry_field $field:ident) => { Some(stringify!($field)) };
+
+    (@deprecated) => { None };
+    (@deprecated $slot:expr) => { Some($slot) };
+
+    // Expiry check, through the context's cached clock if there is one
+    (@expiry [] [$($ctx:ident)?]) => {};
+    (@expiry [$field:ident] []) => {
+        $crate::time::check_expiry($field, &$crate::syscalls::clock_in("expiry")?)?;
+    };
+    (@expiry [$field:ident] [$ctx:ident]) => {
+        $crate::time::check_expiry($field, $ctx.clock()?)?;
+    };
+
+    // Helper for the remaining accounts metadata
+    (@remaining) => { None };
+    (@remaining $remaining:ident, $remaining_desc:literal) => {
+        Some((stringify!($remaining), $remaining_desc))
+    };
+
+    // Helper for the trailing bytes metadata
+    (@rest) => { None };
+    (@rest $rest:ident, $rest_max:expr) => { Some((stringify!($rest), $rest_max)) };
+    (@data_parser) => { None };
+    (@data_parser $parser:path, $max_len:expr) => { Some((stringify!($parser), $max_len)) };
+
+    // Helper for the type of the parsed data, `()` without a `data_parser`
+    (@args_type) => { () };
+    (@args_type $args_type:ty) => { $args_type };
+
+    // Helper for whether a transfer is signed with PDA seeds
+    (@signed) => { false };
+    (@signed $($seed:expr),+) => { true };
+
+    // Helpers for the descriptor's signer and writable flags
+    (@signer signer $(=> $validation:tt)*) => { true };
+    (@signer $account_type:tt $(=> $validation:tt)*) => { false };
+    (@writable uninitialized $(=> $validation:tt)*) => { true }; // uninitialized accounts are always writable
+    (@writable $account_type:tt => writable) => { true };
+    (@writable $account_type:tt $(=> $validation:tt)*) => { false };
+
+    // Helper to generate shank attributes from account type and validation
+    (@shank_attrs signer => writable) => { &["signer", "writable"] };
+    (@shank_attrs signer) => { &["signer"] };
+    (@shank_attrs uninitialized => writable) => { &["writable"] };
+    (@shank_attrs uninitialized) => { &["writable"] }; // uninitialized accounts are always writable
+    (@shank_attrs $account_type:tt => writable) => { &["writable"] };
+    (@shank_attrs $account_type:tt) => { &[] };
+}
+
+/// Validates accounts based on type and additional rules
+///
+/// `context: "label", rule` logs `label failed validation` before the error
+/// with the `context-logs` feature, see [`fail!`].
+#[macro_export]
+macro_rules! validate_account {
+    // Any rule, naming the account in the log when it fails
+    ($account:expr, context: $context:expr, $($rule:tt)+) => {{
+        let check = || -> pinocchio::ProgramResult {
+            $crate::validate_account!($account, $($rule)+);
+            Ok(())
+        };
+        if let Err(error) = check() {
+            $crate::fail!(error, "{} failed validation", $context);
+        }
+    }};
+
+    // Signer validation
+    ($account:expr, signer) => {{
+        if !$account.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+    }};
+
+    // Signer + writable
+    ($account:expr, signer => writable) => {{
+        if !$account.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        if !$account.is_writable() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }};
+
+    // Program account (owned by program + initialized)
+    ($account:expr, program) => {{
+        if !$account.is_owned_by(&crate::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        // `close_account!` earlier in the transaction, with or without lamports sent back
+        if $crate::layout::is_closed($account) {
+            return Err($crate::error::JiminyError::AccountClosed.into());
+        }
+        if $account.lamports() == 0 {
+            return Err(ProgramError::UninitializedAccount);
+        }
+    }};
+
+    // Program account + writable
+    ($account:expr, program => writable) => {{
+        $crate::validate_account!($account, program);
+        if !$account.is_writable() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }};
+
+    // Uninitialized system account, lamports sent to it ahead of time are allowed
+    // so nobody can block the address with a transfer; `create_pda!` handles them
+    ($account:expr, uninitialized) => {{
+        if !$account.is_owned_by(&pinocchio_system::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if $account.data_len() != 0 {
+            return Err(ProgramError::AccountAlreadyInitialized);
+        }
+    }};
+
+    // Uninitialized system account + writable
+    ($account:expr, uninitialized => writable) => {{
+        $crate::validate_account!($account, uninitialized);
+        if !$account.is_writable() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }};
+
+    // Token account
+    ($account:expr, token) => {{
+        if !$account.is_owned_by(&pinocchio_token::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        if $account.lamports() == 0 {
+            return Err(ProgramError::UninitializedAccount);
+        }
+    }};
+
+    // Token account + writable
+    ($account:expr, token => writable) => {{
+        $crate::validate_account!($account, token);
+        if !$account.is_writable() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }};
+
+    // Token account (but NOT owned by token program - for ATAs)
+    ($account:expr, not_token) => {{
+        if $account.is_owned_by(&pinocchio_token::ID) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+    }};
+
+    // Token account (but NOT owned by token program - for ATAs) + writable
+    ($account:expr, not_token => writable) => {{
+        $crate::validate_account!($account, not_token);
+        if !$account.is_writable() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }};
+
+    // Any account + writable
+    ($account:expr, any => writable) => {{
+        if !$account.is_writable() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }};
+
+    // Account pinned to a known address, e.g. `address(pinocchio_system::ID)`
+    ($account:expr, address($address:expr)) => {{
+        if $account.key() != &$address {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+    }};
+
+    // Pinned address + writable
+    ($account:expr, address($address:expr) => writable) => {{
+        $crate::validate_account!($account, address($address));
+        if !$account.is_writable() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }};
+
+    // Same key as an account declared before it, e.g. `payer: same_as(authority)`
+    ($account:expr, same_as($other:ident)) => {{
+        if !$crate::perf::keys_eq($account.key(), $other.key()) {
+            return Err($crate::error::account_key_mismatch(
+                stringify!($account),
+                stringify!($other),
+            ));
+        }
+    }};
+
+    // Same key as another account + writable
+    ($account:expr, same_as($other:ident) => writable) => {{
+        $crate::validate_account!($account, same_as($other));
+        if !$account.is_writable() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }};
+
+    // Key stored in another account's state, checked by the instruction before `prepare`
+    ($account:expr, key_in($($key_in:tt)*)) => {{}};
+
+    // Key stored in another account's state + writable
+    ($account:expr, key_in($($key_in:tt)*) => writable) => {{
+        if !$account.is_writable() {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }};
+
+    // Any account type
+    ($account:expr, any) => {{
+        // No validation needed for any type
+    }};
+
+    // Custom validation
+    ($account:expr, any, custom($validation:expr)) => {{
+        if !$validation($account) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+    }};
+}
+
+/// Take the next account from an [`account_iter::AccountIter`] and run `validate_account!` on it
+///
+/// ```ignore
+/// let extra_vault = next_validated!(rest, token => writable);
+/// ```
+#[macro_export]
+macro_rules! next_validated {
+    ($iter:expr, $($rule:tt)+) => {{
+        let account = $iter.next_account()?;
+        $crate::validate_account!(account, $($rule)+);
+        account
+    }};
+}
+
+/// Fast PDA validation without recomputing
+/// Evaluates to the derived address
+///
+/// A trailing `context: "label"` logs `label is not the expected PDA` before
+/// the error with the `context-logs` feature. The seeds are checked like in
+/// `create_pda!`.
+#[macro_export]
+macro_rules! assert_pda {
+    (
+        $account:expr, seeds: [$($seed:expr),*], bump: $bump:expr, error: $error:expr
+        $(, context: $context:expr)? $(,)?
+    ) => {{
+        $crate::check_seed_count!(bump: $($seed),*);
+        let expected = $crate::derive_pda!(
+            seeds: [$($crate::pda::seed($seed, stringify!($seed))?),*],
+            bump: $bump
+        );
+        if $account.key() != &expected {
+            $crate::fail!($error $(, "{} is not the expected PDA", $context)?);
+        }
+        expected
+    }};
+}
+
+/// Return `error` from the enclosing function, logging a context line first
+///
+/// The context is formatted like `pinocchio_log::log!` and only logged with
+/// the `context-logs` feature, otherwise it compiles to the bare `return`.
+///
+/// ```ignore
+/// fail!(PTokenProgramError::InvalidSide, "side {} of {} outcomes", side, outcomes);
+/// ```
+#[macro_export]
+macro_rules! fail {
+    ($error:expr $(,)?) => {
+        return Err($error.into())
+    };
+    ($error:expr, $($context:tt)+) => {{
+        if $crate::error::LOG_CONTEXT {
+            pinocchio_log::log!($($context)+);
+        }
+        return Err($error.into());
+    }};
+}
+
+/// Log a `Pod` event as `Program data:`, named after its struct
+///
+/// The data fields are the struct name and the event's bytes, see [`event::emit`].
+///
+/// ```ignore
+/// emit_event!(CountSnapshot { owner, count, slot: clock.slot.to_le_bytes() });
+/// ```
+#[macro_export]
+macro_rules! emit_event {
+    ($name:ident { $($fields:tt)* }) => {
+        $crate::event::emit(stringify!($name), &$name { $($fields)* })
+    };
+}
+
+/// Define the `Pod` event structs a program logs with `emit_event!`
+///
+/// Fields are laid out like `define_state!`, so only unpadded types fit. Each
+/// struct gets a `NAME` const, the name it's logged under, and the build script
+/// lists the structs in the IDL's `events`.
+///
+/// ```ignore
+/// define_events! {
+///     /// Logged by SnapshotCount
+///     pub struct CountSnapshot {
+///         pub owner: PubkeyBytes,
+///         pub count: [u8; 8],
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_events {
+    (
+        $(
+            $(#[$meta:meta])*
+            pub struct $name:ident {
+                $(pub $field:ident: $field_type:ty),* $(,)?
+            }
+        )*
+    ) => {
+        $(
+            $(#[$meta])*
+            #[repr(C)]
+            #[derive(Clone, Copy, Debug, PartialEq, Eq, bytemuck::Pod, bytemuck::Zeroable)]
+            pub struct $name {
+                $(pub $field: $field_type,)*
+            }
+
+            impl $name {
+                /// The name `emit_event!` logs the event under
+                pub const NAME: &'static str = stringify!($name);
+            }
+        )*
+    };
+}
+
+/// Fail to compile if there are more seeds than Solana's 16
+///
+/// With `bump:` the macro adds the bump itself, so it takes one seed less.
+#[doc(hidden)]
+#[macro_export]
+macro_rules! check_seed_count {
+    (bump: $($seed:expr),*) => {
+        const _: () = assert!(
+            <[&str]>::len(&[$(stringify!($seed)),*]) < $crate::pda::MAX_SEEDS,
+            "too many PDA seeds, Solana allows 16 including the bump"
+        );
+    };
+    ($($seed:expr),*) => {
+        const _: () = assert!(
+            <[&str]>::len(&[$(stringify!($seed)),*]) <= $crate::pda::MAX_SEEDS,
+            "too many PDA seeds, Solana allows 16 including the bump"
+        );
+    };
+}
+
+/// Derive a PDA from its seeds and known bump without checking any account
+#[macro_export]
+macro_rules! derive_pda {
+    (seeds: [$($seed:expr),*], bump: $bump:expr) => {
+        $crate::derive_pda!(seeds: [$($seed),*], bump: $bump, program: &crate::ID)
+    };
+    (seeds: [$($seed:expr),*], bump: $bump:expr, program: $program_id:expr) => {
+        pinocchio_pubkey::derive_address(&[$($seed),*], Some($bump), $program_id)
+    };
+}
+
+/// Fail unless the account data is exactly the size of a state type
+///
+/// A closed account fails with `JiminyError::AccountClosed`, shorter data with
+/// `JiminyError::AccountDataTooShort` and longer data with
+/// `JiminyError::AccountDataTooLong`, logging both lengths.
+#[macro_export]
+macro_rules! assert_len {
+    ($account:expr, $type:ty) => {
+        $crate::layout::check_account_len(&$account, ::core::mem::size_of::<$type>())?
+    };
+}
+
+/// Load account data with zero-copy
+///
+/// The data must be exactly `size_of::<T>()` bytes, see `assert_len!`.
+#[cfg(not(feature = "safe-borrows"))]
+#[macro_export]
+macro_rules! load_mut {
+    ($account:expr, $type:ty) => {{
+        $crate::assert_len!($account, $type);
+        let data = unsafe { $account.borrow_mut_data_unchecked() };
+        bytemuck::try_from_bytes_mut::<$type>(data).map_err(|_| ProgramError::InvalidAccountData)?
+    }};
+}
+
+/// Load account data with zero-copy through a checked mutable borrow
+///
+/// The borrow guard lives until the end of the enclosing block, so the
+/// account cannot be borrowed again (or passed writable to a CPI) while the
+/// returned reference is in scope.
+#[cfg(feature = "safe-borrows")]
+#[macro_export]
+macro_rules! load_mut {
+    ($account:expr, $type:ty) => {{
+        $crate::assert_len!($account, $type);
+        &mut *pinocchio::account_info::RefMut::map(
+            {
+                let mut data = $account.try_borrow_mut_data()?;
+                bytemuck::try_from_bytes_mut::<$type>(&mut data)
+                    .map_err(|_| ProgramError::InvalidAccountData)?;
+                data
+            },
+            bytemuck::from_bytes_mut::<$type>,
+        )
+    }};
+}
+
+/// Load account data immutably
+#[cfg(not(feature = "safe-borrows"))]
+#[macro_export]
+macro_rules! load {
+    ($account:expr, $type:ty) => {{
+        $crate::assert_len!($account, $type);
+        unsafe {
+            let data = $account.borrow_data_unchecked();
+            bytemuck::try_from_bytes::<$type>(&data)
+                .map_err(|_| ProgramError::InvalidAccountData)?
+        }
+    }};
+}
+
+/// Load account data immutably through a checked borrow
+#[cfg(feature = "safe-borrows")]
+#[macro_export]
+macro_rules! load {
+    ($account:expr, $type:ty) => {{
+        $crate::assert_len!($account, $type);
+        &*pinocchio::account_info::Ref::map(
+            {
+                let data = $account.try_borrow_data()?;
+                bytemuck::try_from_bytes::<$type>(&data)
+                    .map_err(|_| ProgramError::InvalidAccountData)?;
+                data
+            },
+            bytemuck::from_bytes::<$type>,
+        )
+    }};
+}
+
+/// Load the first `size_of::<T>()` bytes of account data, ignoring any tail
+///
+/// For header + tail layouts; only data shorter than `T` fails, with
+/// `JiminyError::AccountDataTooShort`, or `JiminyError::AccountClosed` for a
+/// closed account.
+#[cfg(not(feature = "safe-borrows"))]
+#[macro_export]
+macro_rules! load_prefix {
+    ($account:expr, $type:ty) => {{
+        const LEN: usize = ::core::mem::size_of::<$type>();
+        $crate::layout::check_account_min_len(&$account, LEN)?;
+        unsafe {
+            let data = $account.borrow_data_unchecked();
+            bytemuck::try_from_bytes::<$type>(&data[..LEN])
+                .map_err(|_| ProgramError::InvalidAccountData)?
+        }
+    }};
+}
+
+/// Load the first `size_of::<T>()` bytes of account data through a checked borrow
+#[cfg(feature = "safe-borrows")]
+#[macro_export]
+macro_rules! load_prefix {
+    ($account:expr, $type:ty) => {{
+        const LEN: usize = ::core::mem::size_of::<$type>();
+        $crate::layout::check_account_min_len(&$account, LEN)?;
+        &*pinocchio::account_info::Ref::map(
+            {
+                let data = $account.try_borrow_data()?;
+                bytemuck::try_from_bytes::<$type>(&data[..LEN])
+                    .map_err(|_| ProgramError::InvalidAccountData)?;
+                data
+            },
+            |data| bytemuck::from_bytes::<$type>(&data[..LEN]),
+        )
+    }};
+}
+
+/// Load the first `size_of::<T>()` bytes of account data mutably, ignoring any tail
+#[cfg(not(feature = "safe-borrows"))]
+#[macro_export]
+macro_rules! load_prefix_mut {
+    ($account:expr, $type:ty) => {{
+        const LEN: usize = ::core::mem::size_of::<$type>();
+        $crate::layout::check_account_min_len(&$account, LEN)?;
+        let data = unsafe { $account.borrow_mut_data_unchecked() };
+        bytemuck::try_from_bytes_mut::<$type>(&mut data[..LEN])
+            .map_err(|_| ProgramError::InvalidAccountData)?
+    }};
+}
+
+/// Load the first `size_of::<T>()` bytes of account data through a checked mutable borrow
+#[cfg(feature = "safe-borrows")]
+#[macro_export]
+macro_rules! load_prefix_mut {
+    ($account:expr, $type:ty) => {{
+        const LEN: usize = ::core::mem::size_of::<$type>();
+        $crate::layout::check_account_min_len(&$account, LEN)?;
+        &mut *pinocchio::account_info::RefMut::map(
+            {
+                let mut data = $account.try_borrow_mut_data()?;
+                bytemuck::try_from_bytes_mut::<$type>(&mut data[..LEN])
+                    .map_err(|_| ProgramError::InvalidAccountData)?;
+                data
+            },
+            |data| bytemuck::from_bytes_mut::<$type>(&mut data[..LEN]),
+        )
+    }};
+}
+
+/// Bytes to allocate for a state type plus `extra` bytes of headroom for later fields
+///
+/// A const expression, for `create_pda!`'s `space:` while rent is cheap. The
+/// account is then longer than the type, so load it with `load_prefix!` or
+/// `load_prefix_mut!`; `load!` fails with `JiminyError::AccountDataTooLong`.
+///
+/// ```ignore
+/// pub const COUNTER_SPACE: usize = space_with_headroom!(Counter, 96);
+/// ```
+#[macro_export]
+macro_rules! space_with_headroom {
+    ($type:ty, $extra:expr) => {
+        ::core::mem::size_of::<$type>() + $extra
+    };
+}
+
+/// Create PDA with automatic bump calculation
+///
+/// More than 15 seeds, or a `b"..."` or `[u8; N]` seed over 32 bytes, fail to
+/// compile; a longer slice seed fails with `MaxSeedLengthExceeded` and logs
+/// which one, see [`pda::seed`].
+///
+/// A trailing `created_at_slot: State.field` writes the current slot into that
+/// `LeU64` field of the new account and makes the macro evaluate to the slot,
+/// for `assert_min_age!`. A later `init` overwrites every field, so pass the
+/// slot on to it.
+#[macro_export]
+macro_rules! create_pda {
+    (@created_at_slot $to:expr) => {
+        ()
+    };
+    (@created_at_slot $to:expr, $state:ident . $field:ident) => {{
+        let slot = $crate::syscalls::clock_in("created_at_slot")?.slot;
+        $crate::load_mut!($to, $state).$field.set(slot);
+        slot
+    }};
+    // `from:` is the older spelling of `payer:`
+    (
+        from: $from:expr,
+        to: $to:expr,
+        space: $space:expr,
+        seeds: [$($seed:expr),*],
+        bump: $bump:expr
+        $(, created_at_slot: $state:ident . $field:ident)?
+    ) => {
+        $crate::create_pda!(
+            payer: $from,
+            to: $to,
+            space: $space,
+            seeds: [$($seed),*],
+            bump: $bump
+            $(, created_at_slot: $state.$field)?
+        )
+    };
+    (
+        payer: $from:expr,
+        to: $to:expr,
+        space: $space:expr,
+        seeds: [$($seed:expr),*],
+        bump: $bump:expr
+        $(, created_at_slot: $state:ident . $field:ident)?
+    ) => {{
+        use pinocchio::instruction::{Seed, Signer};
+
+        $crate::check_seed_count!(bump: $($seed),*);
+        let bump_seed = [$bump];
+        let seeds = [
+            $(Seed::from($crate::pda::seed($seed, stringify!($seed))?),)* Seed::from(&bump_seed)
+        ];
+        let space: usize = $space;
+        let rent = $crate::syscalls::rent_in("create_pda")?.minimum_balance(space);
+        let lamports = $to.lamports();
+        $crate::payer::check($from, rent.saturating_sub(lamports))?;
+        $crate::writable::check($to, "target")?;
+
+        if lamports == 0 {
+            $crate::syscalls::cpi_applying(
+                "system::create_account",
+                || {
+                    pinocchio_system::instructions::CreateAccount {
+                        from: $from,
+                        to: $to,
+                        space: space as u64,
+                        lamports: rent,
+                        owner: &crate::ID,
+                    }
+                    .invoke_signed(&[Signer::from(&seeds)])
+                },
+                || {
+                    $crate::syscalls::system::create_account(
+                        $from, $to, rent, space as u64, &crate::ID,
+                    )
+                },
+            )?;
+        } else {
+            // Anyone can send lamports to the address first, which makes CreateAccount
+            // fail, so top it up to rent exemption and allocate and assign it instead
+            if lamports < rent {
+                $crate::syscalls::cpi_applying(
+                    "system::transfer",
+                    || {
+                        pinocchio_system::instructions::Transfer {
+                            from: $from,
+                            to: $to,
+                            lamports: rent - lamports,
+                        }
+                        .invoke()
+                    },
+                    || $crate::syscalls::system::transfer($from, $to, rent - lamports),
+                )?;
+            }
+            $crate::syscalls::cpi_applying(
+                "system::allocate",
+                || {
+                    pinocchio_system::instructions::Allocate {
+                        account: $to,
+                        space: space as u64,
+                    }
+                    .invoke_signed(&[Signer::from(&seeds)])
+                },
+                || $crate::syscalls::system::allocate($to, space as u64),
+            )?;
+            $crate::syscalls::cpi_applying(
+                "system::assign",
+                || {
+                    pinocchio_system::instructions::Assign {
+                        account: $to,
+                        owner: &crate::ID,
+                    }
+                    .invoke_signed(&[Signer::from(&seeds)])
+                },
+                || $crate::syscalls::system::assign($to, &crate::ID),
+            )?;
+        }
+        $crate::create_pda!(@created_at_slot $to $(, $state.$field)?)
+    }};
+}
+
+/// Create the associated token account of `wallet` for `mint`, rent paid by `payer`
+///
+/// The payer is checked like in `create_pda!` before the CPI, so a short payer
+/// fails with `JiminyError::InsufficientPayerFunds` instead of inside the ATA program.
+/// `idempotent: true` sends `CreateIdempotent`, which succeeds when the account
+/// already exists; the payer check then only asks for the rent it is missing.
+#[macro_export]
+macro_rules! create_ata {
+    (@idempotent) => {
+        false
+    };
+    (@idempotent $idempotent:expr) => {
+        $idempotent
+    };
+
+    (
+        payer: $payer:expr,
+        ata: $ata:expr,
+        wallet: $wallet:expr,
+        mint: $mint:expr,
+        system_program: $system_program:expr,
+        token_program: $token_program:expr
+        $(, idempotent: $idempotent:expr)? $(,)?
+    ) => {{
+        use pinocchio::instruction::AccountMeta;
+        let rent = $crate::syscalls::rent_in("create_ata")?
+            .minimum_balance($crate::payer::TOKEN_ACCOUNT_LEN);
+        $crate::payer::check($payer, rent.saturating_sub($ata.lamports()))?;
+        $crate::writable::check($ata, "target")?;
+        let idempotent: bool = $crate::create_ata!(@idempotent $($idempotent)?);
+        let mut metas = $crate::collections::StackVec::<AccountMeta, 6>::new();
+        metas.push(AccountMeta::writable_signer($payer.key()))?;
+        metas.push(AccountMeta::writable($ata.key()))?;
+        metas.push(AccountMeta::readonly($wallet.key()))?;
+        metas.push(AccountMeta::readonly($mint.key()))?;
+        metas.push(AccountMeta::readonly($system_program.key()))?;
+        metas.push(AccountMeta::readonly($token_program.key()))?;
+        $crate::invoke_program!(
+            if idempotent {
+                "associated_token_account::create_idempotent"
+            } else {
+                "associated_token_account::create"
+            },
+            program: &pinocchio_associated_token_account::ID,
+            metas: metas,
+            accounts: [$payer, $ata, $wallet, $mint, $system_program, $token_program],
+            data: [idempotent as u8]
+        )?;
+    }};
+}
+
+/// Invoke `program` with `metas`, e.g. a `StackVec` built from optional accounts
+///
+/// `metas` and `accounts` are anything that borrows as a slice of
+/// `AccountMeta`s and of `&AccountInfo`s: arrays, slices or `StackVec`s.
+/// `name` is what the host stubs record. With `seeds: [...]`, ending with the
+/// bump, the program's PDA signs the call.
+///
+/// ```ignore
+/// let mut metas = StackVec::<AccountMeta, 3>::new();
+/// metas.push(AccountMeta::writable(vault.key()))?;
+/// if let Some(referrer) = referrer {
+///     metas.push(AccountMeta::writable(referrer.key()))?;
+/// }
+/// invoke_program!("rewards::accrue", program: &REWARDS_ID, metas: metas,
+///     accounts: infos, data: amount.to_le_bytes())?;
+/// ```
+#[macro_export]
+macro_rules! invoke_program {
+    (
+        @invoke $name:expr, $program:expr, $metas:expr, $accounts:expr, $data:expr,
+        $signers:expr
+    ) => {{
+        let metas: &[pinocchio::instruction::AccountMeta] = &$metas;
+        let accounts: &[&pinocchio::account_info::AccountInfo] = &$accounts;
+        let data: &[u8] = &$data;
+        $crate::syscalls::cpi($name, || {
+            pinocchio::cpi::slice_invoke_signed(
+                &pinocchio::instruction::Instruction {
+                    program_id: $program,
+                    accounts: metas,
+                    data,
+                },
+                accounts,
+                $signers,
+            )
+        })
+    }};
+
+    (
+        $name:expr,
+        program: $program:expr,
+        metas: $metas:expr,
+        accounts: $accounts:expr,
+        data: $data:expr $(,)?
+    ) => {
+        $crate::invoke_program!(@invoke $name, $program, $metas, $accounts, $data, &[])
+    };
+
+    (
+        $name:expr,
+        program: $program:expr,
+        metas: $metas:expr,
+        accounts: $accounts:expr,
+        data: $data:expr,
+        seeds: [$($seed:expr),*] $(,)?
+    ) => {{
+        use pinocchio::instruction::{Seed, Signer};
+        // The caller's seeds end with the bump
+        $crate::check_seed_count!($($seed),*);
+        let seeds = [$(Seed::from($crate::pda::seed($seed, stringify!($seed))?),)*];
+        let signer = Signer::from(&seeds);
+        $crate::invoke_program!(@invoke $name, $program, $metas, $accounts, $data, &[signer])
+    }};
+}
+
+/// Create a PDA with its rent paid by a program-owned `sponsor` instead of a signer
+///
+/// The sponsor can't be the `from` of a system CreateAccount since it carries
+/// data, so its lamports move directly with `move_lamports!` and the target is
+/// allocated and assigned. Fails with `JiminyError::VaultBelowRentFloor` if the
+/// sponsor would drop below its own rent-exempt minimum. Takes the same
+/// `created_at_slot:` option as `create_pda!`.
+#[macro_export]
+macro_rules! create_pda_sponsored {
+    (
+        sponsor: $sponsor:expr,
+        to: $to:expr,
+        space: $space:expr,
+        seeds: [$($seed:expr),*],
+        bump: $bump:expr
+        $(, created_at_slot: $state:ident . $field:ident)? $(,)?
+    ) => {{
+        use pinocchio::instruction::{Seed, Signer};
+
+        $crate::check_seed_count!(bump: $($seed),*);
+        let bump_seed = [$bump];
+        let seeds = [
+            $(Seed::from($crate::pda::seed($seed, stringify!($seed))?),)* Seed::from(&bump_seed)
+        ];
+        let space: usize = $space;
+        $crate::writable::check($sponsor, "sponsor")?;
+        $crate::writable::check($to, "target")?;
+        let rent = $crate::syscalls::rent_in("create_pda_sponsored")?;
+        let missing = rent.minimum_balance(space).saturating_sub($to.lamports());
+        if missing > 0 {
+            let sponsor_floor = rent.minimum_balance($sponsor.data_len());
+            $crate::vault::check_rent_floor($sponsor.lamports(), missing, sponsor_floor)?;
+            $crate::move_lamports!($sponsor, $to, missing);
+        }
+        $crate::syscalls::cpi_applying(
+            "system::allocate",
+            || {
+                pinocchio_system::instructions::Allocate {
+                    account: $to,
+                    space: space as u64,
+                }
+                .invoke_signed(&[Signer::from(&seeds)])
+            },
+            || $crate::syscalls::system::allocate($to, space as u64),
+        )?;
+        $crate::syscalls::cpi_applying(
+            "system::assign",
+            || {
+                pinocchio_system::instructions::Assign {
+                    account: $to,
+                    owner: &crate::ID,
+                }
+                .invoke_signed(&[Signer::from(&seeds)])
+            },
+            || $crate::syscalls::system::assign($to, &crate::ID),
+        )?;
+        $crate::create_pda!(@created_at_slot $to $(, $state.$field)?)
+    }};
+}
+
+/// Transfer tokens with optional PDA signing
+///
+/// A zero amount moves nothing and makes no CPI, since some token programs reject it.
+#[macro_export]
+macro_rules! transfer_tokens {
+    ($from:expr, $to:expr, $authority:expr, $amount:expr) => {{
+        let amount: u64 = $amount;
+        if amount > 0 {
+            $crate::syscalls::cpi_applying(
+                "token::transfer",
+                || {
+                    pinocchio_token::instructions::Transfer {
+                        from: $from,
+                        to: $to,
+                        authority: $authority,
+                        amount,
+                    }
+                    .invoke()
+                },
+                || $crate::syscalls::token::transfer($from, $to, amount),
+            )?;
+        }
+    }};
+
+    ($from:expr, $to:expr, $authority:expr, $amount:expr, seeds: [$($seed:expr),*]) => {{
+        use pinocchio::instruction::{Seed, Signer};
+        // The caller's seeds end with the bump
+        $crate::check_seed_count!($($seed),*);
+        let amount: u64 = $amount;
+        if amount > 0 {
+            let seeds = [$(Seed::from($crate::pda::seed($seed, stringify!($seed))?),)*];
+            let signer = Signer::from(&seeds);
+
+            $crate::syscalls::cpi_applying(
+                "token::transfer",
+                || {
+                    pinocchio_token::instructions::Transfer {
+                        from: $from,
+                        to: $to,
+                        authority: $authority,
+                        amount,
+                    }
+                    .invoke_signed(&[signer])
+                },
+                || $crate::syscalls::token::transfer($from, $to, amount),
+            )?;
+        }
+    }};
+}
+
+/// Transfer SOL
+///
+/// A zero amount moves nothing and makes no CPI.
+#[macro_export]
+macro_rules! transfer_sol {
+    ($from:expr, $to:expr, $amount:expr) => {{
+        let lamports: u64 = $amount;
+        if lamports > 0 {
+            $crate::syscalls::cpi_applying(
+                "system::transfer",
+                || {
+                    pinocchio_system::instructions::Transfer {
+                        from: $from,
+                        to: $to,
+                        lamports,
+                    }
+                    .invoke()
+                },
+                || $crate::syscalls::system::transfer($from, $to, lamports),
+            )?;
+        }
+    }};
+}
+
+/// Assert a vault PDA, the check every `vault_*` macro starts with; see [`vault`]
+#[macro_export]
+macro_rules! ensure_vault {
+    ($vault:expr, seeds: [$($seed:expr),*], bump: $bump:expr, error: $error:expr) => {{
+        $crate::assert_pda!($vault, seeds: [$($seed),*], bump: $bump, error: $error);
+    }};
+}
+
+/// Send SOL into a vault PDA, a zero amount only checks the vault
+#[macro_export]
+macro_rules! vault_deposit_sol {
+    (
+        $vault:expr, seeds: [$($seed:expr),*], bump: $bump:expr, error: $error:expr,
+        from: $from:expr, amount: $amount:expr $(,)?
+    ) => {{
+        $crate::ensure_vault!($vault, seeds: [$($seed),*], bump: $bump, error: $error);
+        $crate::writable::check($from, "payer")?;
+        $crate::writable::check($vault, "vault")?;
+        let amount: u64 = $amount;
+        if amount > 0 {
+            $crate::transfer_sol!($from, $vault, amount);
+        }
+    }};
+}
+
+/// Send SOL out of a vault PDA, failing if it would drop below its rent-exempt minimum
+#[macro_export]
+macro_rules! vault_withdraw_sol {
+    (
+        $vault:expr, seeds: [$($seed:expr),*], bump: $bump:expr, error: $error:expr,
+        to: $to:expr, amount: $amount:expr $(,)?
+    ) => {{
+        use pinocchio::instruction::{Seed, Signer};
+
+        let bump: u8 = $bump;
+        $crate::ensure_vault!($vault, seeds: [$($seed),*], bump: bump, error: $error);
+        $crate::writable::check($vault, "vault")?;
+        $crate::writable::check($to, "receiver")?;
+        let amount: u64 = $amount;
+        if amount > 0 {
+            let rent_floor =
+                $crate::syscalls::rent_in("vault_withdraw_sol")?.minimum_balance($vault.data_len());
+            $crate::vault::check_rent_floor($vault.lamports(), amount, rent_floor)?;
+
+            // The system program only moves lamports out of accounts it owns
+            if $vault.is_owned_by(&crate::ID) {
+                $crate::move_lamports!($vault, $to, amount);
+            } else {
+                let bump_seed = [bump];
+                let seeds = [
+                    $(Seed::from($crate::pda::seed($seed, stringify!($seed))?),)*
+                    Seed::from(&bump_seed),
+                ];
+                let signer = Signer::from(&seeds);
+                $crate::syscalls::cpi_applying(
+                    "system::transfer",
+                    || {
+                        pinocchio_system::instructions::Transfer {
+                            from: $vault,
+                            to: $to,
+                            lamports: amount,
+                        }
+                        .invoke_signed(&[signer])
+                    },
+                    || $crate::syscalls::system::transfer($vault, $to, amount),
+                )?;
+            }
+        }
+    }};
+}
+
+/// Send tokens into a token account owned by a vault PDA, a zero amount only checks the accounts
+#[macro_export]
+macro_rules! vault_deposit_tokens {
+    (
+        $vault:expr, seeds: [$($seed:expr),*], bump: $bump:expr, error: $error:expr,
+        from: $from:expr, to: $to:expr, authority: $authority:expr, amount: $amount:expr $(,)?
+    ) => {{
+        $crate::ensure_vault!($vault, seeds: [$($seed),*], bump: $bump, error: $error);
+        $crate::vault::check_token_owner($to, $vault.key())?;
+        $crate::writable::check($from, "payer")?;
+        $crate::writable::check($to, "vault")?;
+        let amount: u64 = $amount;
+        if amount > 0 {
+            $crate::transfer_tokens!($from, $to, $authority, amount);
+        }
+    }};
+}
+
+/// Send tokens out of a vault PDA's token account, signed by the vault
+#[macro_export]
+macro_rules! vault_withdraw_tokens {
+    (
+        $vault:expr, seeds: [$($seed:expr),*], bump: $bump:expr, error: $error:expr,
+        from: $from:expr, to: $to:expr, amount: $amount:expr $(,)?
+    ) => {{
+        use pinocchio::instruction::{Seed, Signer};
+
+        let bump: u8 = $bump;
+        $crate::ensure_vault!($vault, seeds: [$($seed),*], bump: bump, error: $error);
+        $crate::writable::check($from, "vault")?;
+        $crate::writable::check($to, "receiver")?;
+        let amount: u64 = $amount;
+        if amount > 0 {
+            let bump_seed = [bump];
+            let seeds = [
+                $(Seed::from($crate::pda::seed($seed, stringify!($seed))?),)*
+                Seed::from(&bump_seed),
+            ];
+            let signer = Signer::from(&seeds);
+            $crate::syscalls::cpi_applying(
+                "token::transfer",
+                || {
+                    pinocchio_token::instructions::Transfer {
+                        from: $from,
+                        to: $to,
+                        authority: $vault,
+                        amount,
+                    }
+                    .invoke_signed(&[signer])
+                },
+                || $crate::syscalls::token::transfer($from, $to, amount),
+            )?;
+        }
+    }};
+}
+
+/// Add lamports to an account, failing with `JiminyError::LamportOverflow`
+#[macro_export]
+macro_rules! credit_lamports {
+    ($account:expr, $amount:expr) => {
+        $crate::lamports::credit($account, $amount)?
+    };
+}
+
+/// Take lamports from a program-owned account, failing with `JiminyError::InsufficientLamports`
+#[macro_export]
+macro_rules! debit_lamports {
+    ($account:expr, $amount:expr) => {
+        $crate::lamports::debit($account, $amount)?
+    };
+}
+
+/// Move lamports between accounts without a CPI, both are unchanged if either side fails
+#[macro_export]
+macro_rules! move_lamports {
+    ($from:expr, $to:expr, $amount:expr) => {
+        $crate::lamports::transfer($from, $to, $amount)?
+    };
+}
+
+/// Close account efficiently
+///
+/// Both accounts must be writable, a read-only one fails with
+/// `JiminyError::AccountNotWritable` before anything moves.
+///
+/// The account keeps its owner and is left holding only the eight bytes of
+/// `layout::CLOSED_MARKER`, so a later instruction of the same transaction
+/// that loads it fails with `JiminyError::AccountClosed`; the runtime removes
+/// it once the transaction ends with no lamports in it.
+#[macro_export]
+macro_rules! close_account {
+    ($account:expr, $receiver:expr) => {{
+        $crate::writable::check($account, "target")?;
+        $crate::writable::check($receiver, "receiver")?;
+
+        // Transfer lamports
+        let lamports = $account.lamports();
+        $crate::move_lamports!($account, $receiver, lamports);
+
+        // Resize to the marker alone
+        $account.resize($crate::layout::CLOSED_MARKER.len())?;
+        $account
+            .try_borrow_mut_data()?
+            .copy_from_slice(&$crate::layout::CLOSED_MARKER);
+    }};
+}
+
+/// Optimized byte array conversions
+#[macro_export]
+macro_rules! to_le_bytes {
+    ($arr:expr) => {
+        u64::from_le_bytes($arr)
+    };
+}
+
+#[macro_export]
+macro_rules! to_be_bytes {
+    ($arr:expr) => {
+        u64::from_be_bytes($arr)
+    };
+}
+
+/// Copy values out of account state without keeping a reference to the data
+///
+/// The closure's result must be `Copy` and can't borrow from the state, so
+/// nothing can dangle after a later CPI resizes the account.
+///
+/// ```ignore
+/// let count = read_state!(counter, Counter, |c| u64::from_le_bytes(c.count));
+/// ```
+///
+/// With `prefix` the state is read through `load_prefix!`, for accounts
+/// allocated with `space_with_headroom!`.
+#[macro_export]
+macro_rules! read_state {
+    ($account:expr, $type:ty, |$state:ident| $body:expr) => {
+        $crate::layout::read_with($crate::load!($account, $type), |$state: &$type| $body)
+    };
+    ($account:expr, $type:ty, prefix, |$state:ident| $body:expr) => {
+        $crate::layout::read_with($crate::load_prefix!($account, $type), |$state: &$type| $body)
+    };
+}
+
+/// Load another program's account read-only, once that program owns it
+///
+/// For state types shared with the owning program, e.g. through its crate built
+/// with `no-entrypoint`. Fails with `InvalidAccountOwner` unless `owner` owns
+/// the account, then loads it like `load!`, or like `load_prefix!` with
+/// `prefix` for accounts allocated with headroom. The build script lists each
+/// use in the IDL's `foreignAccounts`.
+///
+/// ```ignore
+/// let counter_state = load_foreign!(counter, Counter, owner: counter::ID, prefix);
+/// ```
+#[macro_export]
+macro_rules! load_foreign {
+    ($account:expr, $type:ty, owner: $owner:expr) => {{
+        if !$account.is_owned_by(&$owner) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        $crate::load!($account, $type)
+    }};
+    ($account:expr, $type:ty, owner: $owner:expr, prefix) => {{
+        if !$account.is_owned_by(&$owner) {
+            return Err(ProgramError::InvalidAccountOwner);
+        }
+        $crate::load_prefix!($account, $type)
+    }};
+}
+
+/// Fast state loading pattern
+///
+/// The state borrow ends with the block, so the account can be used again
+/// right after it.
+#[macro_export]
+macro_rules! with_state {
+    ($account:expr, $type:ty, |$state:ident| $body:block) => {{
+        // Borrowed rather than cloned, `$account` may be any expression
+        let account = &$account;
+        let $state = $crate::load_mut!(account, $type);
+        $body
+    }};
+    ($account:expr, $type:ty, reload_after_cpi, |$state:ident| $body:block) => {{
+        let account = &$account;
+        let $state = $crate::reload!(account, $type);
+        $body
+    }};
+}
+
+/// Re-borrow account data after a CPI that may have changed it
+///
+/// References from an earlier `load!`/`load_mut!` still point at the same
+/// bytes, but reloading makes the read explicit and, with `safe-borrows`,
+/// fails if the earlier borrow is still alive.
+#[macro_export]
+macro_rules! reload {
+    ($account:expr, $type:ty) => {
+        $crate::load_mut!($account, $type)
+    };
+}
+
+/// Require `signer` to be this program's upgrade authority
+///
+/// `program` must be this program's account and `programdata` its programdata
+/// account. Fails with `ProgramError::Immutable` if the program is frozen.
+#[macro_export]
+macro_rules! assert_upgrade_authority {
+    ($program:expr, $programdata:expr, $signer:expr) => {
+        $crate::upgrade::check_upgrade_authority($program, $programdata, $signer, &crate::ID)?
+    };
+}
+
+/// Run a block with a reentrancy flag set in a state account
+///
+/// Fails with `error` if the flag is already set, i.e. the program was
+/// re-entered through a CPI made inside the block, logging the flag's name with
+/// the `context-logs` feature. The flag is cleared when
+/// the block returns; an early `?` return leaves it set, which is harmless
+/// because the failing transaction is rolled back.
+#[macro_export]
+macro_rules! reentrancy_guard {
+    ($account:expr, $type:ty, $flag:ident, error: $error:expr, $body:block) => {{
+        $crate::reentrancy_guard!(enter $account, $type, $flag, error: $error);
+        let result = $body;
+        $crate::reentrancy_guard!(exit $account, $type, $flag);
+        result
+    }};
+    (enter $account:expr, $type:ty, $flag:ident, error: $error:expr) => {{
+        let state = $crate::load_mut!($account, $type);
+        if state.$flag != 0 {
+            $crate::fail!($error, "Reentered while {} is set", stringify!($flag));
+        }
+        state.$flag = 1;
+    }};
+    (exit $account:expr, $type:ty, $flag:ident) => {{
+        let state = $crate::load_mut!($account, $type);
+        state.$flag = 0;
+    }};
+}
+
+/// Assert an account is the canonical PDA for the seeds, deriving through a `PdaCache`
+/// Evaluates to the canonical bump
+///
+/// Takes the same optional `context: "label"` as `assert_pda!`, and checks
+/// the seeds the same way.
+#[macro_export]
+macro_rules! assert_pda_cached {
+    (
+        $cache:expr, $account:expr, seeds: [$($seed:expr),*], error: $error:expr
+        $(, context: $context:expr)? $(,)?
+    ) => {{
+        $crate::check_seed_count!(bump: $($seed),*);
+        let (expected, bump) = $cache.find(
+            &[$($crate::pda::seed($seed, stringify!($seed))?),*],
+            &crate::ID,
+        );
+        if $account.key() != &expected {
+            $crate::fail!($error $(, "{} is not the expected PDA", $context)?);
+        }
+        bump
+    }};
+}
+
+/// Create PDA taking the bump from a `PdaCache`, searching for it only if not cached yet
+///
+/// Takes the same `created_at_slot:` option as `create_pda!`. The seeds are
+/// checked before the cache sees them.
+#[macro_export]
+macro_rules! create_pda_cached {
+    (
+        $cache:expr,
+        from: $from:expr,
+        to: $to:expr,
+        space: $space:expr,
+        seeds: [$($seed:expr),*]
+        $(, created_at_slot: $state:ident . $field:ident)?
+    ) => {
+        $crate::create_pda_cached!(
+            $cache,
+            payer: $from,
+            to: $to,
+            space: $space,
+            seeds: [$($seed),*]
+            $(, created_at_slot: $state.$field)?
+        )
+    };
+    (
+        $cache:expr,
+        payer: $from:expr,
+        to: $to:expr,
+        space: $space:expr,
+        seeds: [$($seed:expr),*]
+        $(, created_at_slot: $state:ident . $field:ident)?
+    ) => {{
+        $crate::check_seed_count!(bump: $($seed),*);
+        let seeds = [$($crate::pda::seed($seed, stringify!($seed))?),*];
+        let bump = match $cache.bump_hint(&seeds) {
+            Some(bump) => bump,
+            None => $cache.find(&seeds, &crate::ID).1,
+        };
+        $crate::create_pda!(
+            payer: $from,
+            to: $to,
+            space: $space,
+            seeds: [$($seed),*],
+            bump: bump
+            $(, created_at_slot: $state.$field)?
+        )
+    }};
+}
+
+/// Batch PDA validation
+#[macro_export]
+macro_rules! validate_pdas {
+    (
+        $(
+            $account:expr => seeds: [$($seed:expr),*], bump: $bump:expr, error: $error:expr
+                $(, context: $context:expr)?
+        );* $(;)?
+    ) => {
+        $(
+            $crate::assert_pda!(
+                $account, seeds: [$($seed),*], bump: $bump, error: $error $(, context: $context)?
+            );
+        )*
+    };
+}
+
+/// Define state structs with automatic load methods and ShankAccount for IDL
+#[macro_export]
+macro_rules! define_state {
+    (
+        $(
+            pub struct $name:ident {
+                $(pub $field:ident: $field_type:ty),* $(,)?
+            }
+        )*
+    ) => {
+
+
+        $(
+            #[repr(C)]
+            #[derive(Clone, Copy, bytemuck::Pod, bytemuck::Zeroable)]
+            pub struct $name {
+                $(pub $field: $field_type,)*
+            }
+
+            impl $name {
+                pub const LEN: usize = ::core::mem::size_of::<Self>();
+            }
+
+            $crate::paste! {
+                /// Every field of the state, so `init` can't leave one zeroed by accident
+                pub struct [<$name Init>] {
+                    $(pub $field: $field_type,)*
+                }
+
+                impl $name {
+                    /// Set every field of a freshly created account
+                    #[inline]
+                    pub fn init(&mut self, args: [<$name Init>]) {
+                        let [<$name Init>] { $($field,)* } = args;
+                        $(self.$field = $field;)*
+                    }
+                }
+            }
+
+            impl $crate::metadata::StateLayout for $name {
+                const NAME: &'static str = stringify!($name);
+                const FIELDS: &'static [$crate::metadata::StateFieldDescriptor] = &[
+                    $(
+                        $crate::metadata::StateFieldDescriptor {
+                            name: stringify!($field),
+                            field_type: stringify!($field_type),
+                            offset: ::core::mem::offset_of!($name, $field),
+                            size: ::core::mem::size_of::<$field_type>(),
+                        },
+                    )*
+                ];
+            }
+        )*
+    };
+}
+
+/// Define a `repr(u8)` enum usable as an instruction data field
+///
+/// The data struct stores the byte and the process body gets the enum;
+/// bytes that match no variant fail with `JiminyError::InvalidEnumValue`
+/// before the body runs. The build script copies the enum into the IDL.
+///
+/// ```ignore
+/// define_enum! {
+///     /// Side of a vote a position is on
+///     pub enum Side {
+///         False = 0,
+///         True = 1,
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! define_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident = $value:literal),* $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        #[repr(u8)]
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        $vis enum $name {
+            $($(#[$variant_meta])* $variant = $value,)*
+        }
+
+        impl $name {
+            /// Returns `None` for a byte that matches no variant
+            pub const fn from_u8(value: u8) -> Option<Self> {
+                match value {
+                    $($value => Some(Self::$variant),)*
+                    _ => None,
+                }
+            }
+
+            pub const fn to_u8(self) -> u8 {
+                self as u8
+            }
+        }
+
+        impl TryFrom<u8> for $name {
+            type Error = pinocchio::program_error::ProgramError;
+
+            fn try_from(value: u8) -> Result<Self, Self::Error> {
+                Self::from_u8(value).ok_or($crate::error::JiminyError::InvalidEnumValue.into())
+            }
+        }
+
+        impl $crate::DataField for $name {
+            type Wire = u8;
+
+            #[inline(always)]
+            fn decode(wire: u8) -> Result<Self, pinocchio::program_error::ProgramError> {
+                Self::try_from(wire)
+            }
+
+            #[inline(always)]
+            fn encode(self) -> u8 {
+                self as u8
+            }
+        }
+    };
+}
+
+/// Define a marker account, a PDA whose only data is its bump and whose existence is the state
+///
+/// The seeds are a prefix followed by the accounts whose keys complete them.
+/// Besides the one-byte state struct this generates `find_address`,
+/// `check_exists` and `check_absent` on it, and three macros named after it,
+/// imported from the module that defines the marker along with the struct:
+/// `create_<name>!` creates the marker after checking it's absent,
+/// `assert_<name>_exists!` fails with `JiminyError::MarkerMissing` and
+/// `assert_<name>_absent!` with `JiminyError::MarkerExists`. A key other than
+/// the marker's address fails with `JiminyError::MarkerKeyMismatch`.
+///
+/// ```ignore
+/// define_marker!(ClaimReceipt, seeds: [RECEIPT_SEED, vote, authority]);
+///
+/// assert_claim_receipt_absent!(receipt, vote: vote, authority: authority);
+/// create_claim_receipt!(receipt, vote: vote, authority: authority, payer: authority);
+/// assert_claim_receipt_exists!(receipt, vote: vote, authority: authority);
+/// ```
+#[macro_export]
+macro_rules! define_marker {
+    ($name:ident, seeds: [$prefix:expr $(, $key:ident)* $(,)?]) => {
+        $crate::define_marker!(@impl ($) $name, $prefix, [$($key),*]);
+    };
+    (@impl ($d:tt) $name:ident, $prefix:expr, [$($key:ident),*]) => {
+        $crate::define_state! {
+            pub struct $name {
+                pub bump: u8,
+            }
+        }
+
+        impl $name {
+            /// First seed, before the keys
+            pub const SEED_PREFIX: &'static [u8] = $prefix;
+
+            /// The marker's address and canonical bump for its seed keys
+            pub fn find_address(
+                $($key: &pinocchio::pubkey::Pubkey),*
+            ) -> (pinocchio::pubkey::Pubkey, u8) {
+                $crate::syscalls::find_program_address(
+                    &[Self::SEED_PREFIX $(, $key.as_ref())*],
+                    &crate::ID,
+                )
+            }
+
+            /// Fail unless `account` is the created marker for the seed keys
+            pub fn check_exists(
+                account: &pinocchio::account_info::AccountInfo,
+                $($key: &pinocchio::pubkey::Pubkey),*
+            ) -> pinocchio::ProgramResult {
+                if !account.is_owned_by(&crate::ID) || account.data_len() != Self::LEN {
+                    return Err($crate::error::JiminyError::MarkerMissing.into());
+                }
+                let bump = account.try_borrow_data()?[0];
+                let address =
+                    $crate::derive_pda!(seeds: [Self::SEED_PREFIX $(, $key.as_ref())*], bump: bump);
+                if account.key() != &address {
+                    return Err($crate::error::JiminyError::MarkerKeyMismatch.into());
+                }
+                Ok(())
+            }
+
+            /// Fail unless `account` is the marker's address and not created yet,
+            /// returns the marker's bump
+            pub fn check_absent(
+                account: &pinocchio::account_info::AccountInfo,
+                $($key: &pinocchio::pubkey::Pubkey),*
+            ) -> Result<u8, pinocchio::program_error::ProgramError> {
+                let (address, bump) = Self::find_address($($key),*);
+                if account.key() != &address {
+                    return Err($crate::error::JiminyError::MarkerKeyMismatch.into());
+                }
+                if account.is_owned_by(&crate::ID) {
+                    return Err($crate::error::JiminyError::MarkerExists.into());
+                }
+                Ok(bump)
+            }
+        }
+
+        $crate::paste! {
+            #[allow(unused_macros)]
+            macro_rules! [<create_ $name:snake>] {
+                ($d account:expr, $($key: $d $key:expr,)* payer: $d payer:expr) => {{
+                    let bump = $name::check_absent($d account $(, $d $key.key())*)?;
+                    $crate::create_pda!(
+                        payer: $d payer,
+                        to: $d account,
+                        space: $name::LEN,
+                        seeds: [$name::SEED_PREFIX $(, $d $key.key().as_ref())*],
+                        bump: bump
+                    );
+                    $d account.try_borrow_mut_data()?[0] = bump;
+                }};
+            }
+
+            #[allow(unused_macros)]
+            macro_rules! [<assert_ $name:snake _exists>] {
+                ($d account:expr $(, $key: $d $key:expr)* $d(,)?) => {
+                    $name::check_exists($d account $(, $d $key.key())*)?
+                };
+            }
+
+            #[allow(unused_macros)]
+            macro_rules! [<assert_ $name:snake _absent>] {
+                ($d account:expr $(, $key: $d $key:expr)* $d(,)?) => {
+                    $name::check_absent($d account $(, $d $key.key())*)?
+                };
+            }
+
+            #[allow(unused_imports)]
+            pub(crate) use {
+                [<create_ $name:snake>], [<assert_ $name:snake _exists>],
+                [<assert_ $name:snake _absent>],
+            };
+        }
+    };
+}
+
+/// How instruction data fields are stored in the data struct
+pub mod field {
+    use pinocchio::program_error::ProgramError;
+
+    /// A type usable as an instruction data field
+    ///
+    /// Pod types are stored as themselves; `define_enum!` types are stored as
+    /// their byte and checked when the instruction runs.
+    pub trait DataField: Sized {
+        /// What the data struct holds
+        type Wire: bytemuck::Pod;
+
+        fn decode(wire: Self::Wire) -> Result<Self, ProgramError>;
+
+        fn encode(self) -> Self::Wire;
+    }
+
+    impl<T: bytemuck::Pod> DataField for T {
+        type Wire = T;
+
+        #[inline(always)]
+        fn decode(wire: T) -> Result<T, ProgramError> {
+            Ok(wire)
+        }
+
+        #[inline(always)]
+        fn encode(self) -> T {
+            self
+        }
+    }
+}
+
+/// Performance utilities
+pub mod perf {
+    use super::*;
+    use bytemuck::Pod;
+    use pinocchio::{account_info::RefMut, pubkey::Pubkey};
+
+    /// Load account data as mutable reference (no_std compatible)
+    /// Documentation
+    ///
+    /// # Safety
+    ///
+    /// Ensure the account data is initialized and matches the expected type
+    #[inline(always)]
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn load_unchecked<T: Pod>(account: &AccountInfo) -> Result<&mut T, ProgramError> {
+        let data = account.borrow_mut_data_unchecked();
+        bytemuck::try_from_bytes_mut::<T>(data).map_err(|_| ProgramError::InvalidAccountData)
+    }
+
+    /// Checked counterpart of [`load_unchecked`] used by the `safe-borrows` feature
+    ///
+    /// Fails with `AccountBorrowFailed` if the account data is already borrowed
+    /// and releases the borrow when the returned guard is dropped.
+    #[inline(always)]
+    pub fn load_checked<T: Pod>(account: &AccountInfo) -> Result<RefMut<'_, T>, ProgramError> {
+        let mut data = account.try_borrow_mut_data()?;
+        bytemuck::try_from_bytes_mut::<T>(&mut data)
+            .map_err(|_| ProgramError::InvalidAccountData)?;
+        Ok(RefMut::map(data, bytemuck::from_bytes_mut::<T>))
+    }
+
+    /// Fast memcpy for account data (no_std compatible)
+    /// Documentation
+    ///
+    /// # Safety
+    ///
+    /// Ensure the source and destination slices are of the same length
+    #[inline(always)]
+    #[cfg_attr(feature = "debug-panics", track_caller)]
+    pub unsafe fn fast_copy(src: &[u8], dst: &mut [u8]) {
+        if src.len() != dst.len() {
+            panic!("Length mismatch in fast_copy");
+        }
+        core::ptr::copy_nonoverlapping(src.as_ptr(), dst.as_mut_ptr(), src.len());
+    }
+
+    /// Compares two pubkeys, as four `u64` reads with the `fast-id-check` feature
+    #[inline(always)]
+    pub fn keys_eq(a: &Pubkey, b: &Pubkey) -> bool {
+        #[cfg(feature = "fast-id-check")]
+        {
+            let (a, b) = (a.as_ptr() as *const u64, b.as_ptr() as *const u64);
+            // SAFETY: both keys are 32 bytes, read as four possibly unaligned u64s
+            unsafe {
+                a.read_unaligned() == b.read_unaligned()
+                    && a.add(1).read_unaligned() == b.add(1).read_unaligned()
+                    && a.add(2).read_unaligned() == b.add(2).read_unaligned()
+                    && a.add(3).read_unaligned() == b.add(3).read_unaligned()
+            }
+        }
+        #[cfg(not(feature = "fast-id-check"))]
+        {
+            a == b
+        }
+    }
+}
+
+/// Per-instruction cache of derived program addresses
+pub mod pda {
+    use pinocchio::{
+        program_error::ProgramError,
+        pubkey::Pubkey,
+    };
+    use pinocchio_pubkey::derive_address;
+
+    pub use pinocchio::pubkey::{MAX_SEEDS, MAX_SEED_LEN};
+
+    /// A PDA seed, `[u8]` or `[u8; N]` up to 32 bytes
+    ///
+    /// Only arrays that fit implement it, so a longer one in [`seed`] is a type
+    /// error, reported by `cargo check` too; slices are checked when the
+    /// instruction runs.
+    #[diagnostic::on_unimplemented(
+        message = "PDA seed longer than 32 bytes, or not bytes",
+        label = "`{Self}` is not a PDA seed",
+        note = "seeds are `[u8]` or `[u8; N]` with N at most 32"
+    )]
+    pub trait SeedBytes {
+        fn seed_bytes(&self) -> &[u8];
+    }
+
+    macro_rules! seed_arrays {
+        ($($len:literal)*) => {$(
+            impl SeedBytes for [u8; $len] {
+                #[inline(always)]
+                fn seed_bytes(&self) -> &[u8] {
+                    self
+                }
+            }
+        )*};
+    }
+
+    // Every length up to `MAX_SEED_LEN`
+    seed_arrays!(0 1 2 3 4 5 6 7 8 9 10 11 12 13 14 15 16);
+    seed_arrays!(17 18 19 20 21 22 23 24 25 26 27 28 29 30 31 32);
+    const _: () = assert!(MAX_SEED_LEN == 32);
+
+    impl SeedBytes for [u8] {
+        #[inline(always)]
+        fn seed_bytes(&self) -> &[u8] {
+            self
+        }
+    }
+
+    /// `seed` as bytes, failing with `MaxSeedLengthExceeded` if it's over 32 bytes
+    ///
+    /// An array that long doesn't compile. A slice that long logs `name`, the
+    /// seed's source text, and its length first.
+    #[inline(always)]
+    pub fn seed<'a, T: SeedBytes + ?Sized>(
+        seed: &'a T,
+        name: &'static str,
+    ) -> Result<&'a [u8], ProgramError> {
+        let bytes = seed.seed_bytes();
+        if bytes.len() > MAX_SEED_LEN {
+            return Err(seed_too_long(name, bytes.len()));
+        }
+        Ok(bytes)
+    }
+
+    #[cold]
+    fn seed_too_long(name: &'static str, len: usize) -> ProgramError {
+        pinocchio_log::log!("PDA seed {} is {} bytes, the limit is 32", name, len);
+        ProgramError::MaxSeedLengthExceeded
+    }
+
+    #[derive(Clone, Copy)]
+    struct CachedPda {
+        seeds_hash: u64,
+        address: Pubkey,
+        bump: u8,
+    }
+
+    /// Stack-allocated cache of up to `N` canonical PDAs, keyed by a hash of their seeds
+    ///
+    /// `find` runs the bump search (and its curve checks) once per set of seeds;
+    /// later lookups for the same seeds are confirmed by re-deriving the address
+    /// with the cached bump, so a hash collision can never hand back the wrong
+    /// address. Once full, new entries are derived but not cached.
+    pub struct PdaCache<const N: usize> {
+        entries: [CachedPda; N],
+        len: usize,
+    }
+
+    impl<const N: usize> Default for PdaCache<N> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<const N: usize> PdaCache<N> {
+        pub const fn new() -> Self {
+            Self {
+                entries: [CachedPda {
+                    seeds_hash: 0,
+                    address: [0; 32],
+                    bump: 0,
+                }; N],
+                len: 0,
+            }
+        }
+
+        /// Canonical address and bump for `seeds`, searching for the bump only on a miss
+        #[inline]
+        #[cfg_attr(feature = "debug-panics", track_caller)]
+        pub fn find<const S: usize>(
+            &mut self,
+            seeds: &[&[u8]; S],
+            program_id: &Pubkey,
+        ) -> (Pubkey, u8) {
+            let seeds_hash = seed_hash(seeds);
+            for entry in &self.entries[..self.len] {
+                if entry.seeds_hash == seeds_hash
+                    && derive_address(seeds, Some(entry.bump), program_id) == entry.address
+                {
+                    return (entry.address, entry.bump);
+                }
+            }
+
+            let (address, bump) = crate::syscalls::find_program_address(seeds, program_id);
+            if self.len < N {
+                self.entries[self.len] = CachedPda {
+                    seeds_hash,
+                    address,
+                    bump,
+                };
+                self.len += 1;
+            }
+            (address, bump)
+        }
+
+        /// Cached bump for `seeds` without re-deriving the address
+        ///
+        /// Only use this for signing, where the runtime derives the address from
+        /// the signer seeds again and rejects a wrong bump.
+        #[inline]
+        pub fn bump_hint(&self, seeds: &[&[u8]]) -> Option<u8> {
+            let seeds_hash = seed_hash(seeds);
+            self.entries[..self.len]
+                .iter()
+                .find(|entry| entry.seeds_hash == seeds_hash)
+                .map(|entry| entry.bump)
+        }
+    }
+
+    /// FNV-1a over each seed's length and bytes
+    #[inline]
+    fn seed_hash(seeds: &[&[u8]]) -> u64 {
+        const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+        const PRIME: u64 = 0x0000_0100_0000_01b3;
+
+        let mut hash = OFFSET_BASIS;
+        for seed in seeds {
+            hash = (hash ^ seed.len() as u64).wrapping_mul(PRIME);
+            for byte in seed.iter() {
+                hash = (hash ^ *byte as u64).wrapping_mul(PRIME);
+            }
+        }
+        hash
+    }
+}
+
+/// Errors raised by jiminy itself rather than by the program
+pub mod error {
+    use pinocchio::program_error::ProgramError;
+
+    /// Whether the `context-logs` feature is on, see [`fail!`](crate::fail)
+    pub const LOG_CONTEXT: bool = cfg!(feature = "context-logs");
+
+    /// Codes set aside for `JiminyError`, the build script rejects program errors in it
+    pub const RESERVED_CODES: core::ops::RangeInclusive<u32> = 100..=199;
+
+    /// Framework error codes, kept below the 6000+ range programs use with `define_errors!`
+    #[repr(u32)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum JiminyError {
+        /// Instruction data is longer than the declared fields and trailing bytes
+        TrailingInstructionData = 100,
+        /// A vault withdrawal would leave it below its rent-exempt minimum
+        VaultBelowRentFloor = 101,
+        /// A vault deposit targets a token account the vault doesn't own
+        VaultTokenAccountMismatch = 102,
+        /// Crediting an account would overflow its lamports
+        LamportOverflow = 103,
+        /// Debiting an account would take more lamports than it holds
+        InsufficientLamports = 104,
+        /// Account data is shorter than the state type
+        AccountDataTooShort = 105,
+        /// Account data is longer than the state type
+        AccountDataTooLong = 106,
+        /// An instruction data byte doesn't match any variant of its `define_enum!` type
+        InvalidEnumValue = 107,
+        /// A balance didn't change the way the instruction's `invariants` declare
+        InvariantViolated = 108,
+        /// A `same_as` or `key_in` account doesn't have the key it must match
+        AccountKeyMismatch = 109,
+        /// The payer of an account creation has fewer lamports than the rent it owes
+        InsufficientPayerFunds = 110,
+        /// A `define_marker!` account that must exist hasn't been created
+        MarkerMissing = 111,
+        /// A `define_marker!` account that must be absent was already created
+        MarkerExists = 112,
+        /// An account isn't at the address of the `define_marker!` account it stands for
+        MarkerKeyMismatch = 113,
+        /// The runtime failed to provide a sysvar, not a problem with the accounts
+        SysvarUnavailable = 114,
+        /// An account a macro writes to was passed read-only
+        AccountNotWritable = 115,
+        /// An account is younger than the slots `assert_min_age!` requires
+        AccountTooYoung = 116,
+        /// The slot in an instruction's `expiry: slot_field(...)` has passed
+        InstructionExpired = 117,
+        /// A `PodBool` or `PodOption` tag byte is neither 0 nor 1
+        InvalidPodTag = 118,
+        /// A `StackVec` push past its capacity
+        CapacityExceeded = 119,
+        /// An account to load holds exactly `layout::CLOSED_MARKER`, left by `close_account!`
+        AccountClosed = 120,
+        /// The slot in an instruction's `deprecated(after_slot: ...)` has passed
+        InstructionDeprecated = 121,
+    }
+
+    impl From<JiminyError> for ProgramError {
+        fn from(e: JiminyError) -> Self {
+            ProgramError::Custom(e as u32)
+        }
+    }
+
+    /// The way back from a `Custom` code, for a program's `decode_error`
+    impl TryFrom<u32> for JiminyError {
+        type Error = u32;
+
+        fn try_from(code: u32) -> Result<Self, u32> {
+            match code {
+                100 => Ok(Self::TrailingInstructionData),
+                101 => Ok(Self::VaultBelowRentFloor),
+                102 => Ok(Self::VaultTokenAccountMismatch),
+                103 => Ok(Self::LamportOverflow),
+                104 => Ok(Self::InsufficientLamports),
+                105 => Ok(Self::AccountDataTooShort),
+                106 => Ok(Self::AccountDataTooLong),
+                107 => Ok(Self::InvalidEnumValue),
+                108 => Ok(Self::InvariantViolated),
+                109 => Ok(Self::AccountKeyMismatch),
+                110 => Ok(Self::InsufficientPayerFunds),
+                111 => Ok(Self::MarkerMissing),
+                112 => Ok(Self::MarkerExists),
+                113 => Ok(Self::MarkerKeyMismatch),
+                114 => Ok(Self::SysvarUnavailable),
+                115 => Ok(Self::AccountNotWritable),
+                116 => Ok(Self::AccountTooYoung),
+                117 => Ok(Self::InstructionExpired),
+                118 => Ok(Self::InvalidPodTag),
+                119 => Ok(Self::CapacityExceeded),
+                120 => Ok(Self::AccountClosed),
+                121 => Ok(Self::InstructionDeprecated),
+                _ => Err(code),
+            }
+        }
+    }
+
+    /// Logs the number of excess bytes and returns `TrailingInstructionData`
+    #[cold]
+    pub fn trailing_instruction_data(excess: usize) -> ProgramError {
+        pinocchio_log::log!("Trailing instruction data: {} bytes", excess);
+        JiminyError::TrailingInstructionData.into()
+    }
+
+    /// Logs the expiry and current slots, returns `InstructionExpired`
+    #[cold]
+    pub fn instruction_expired(expires_at_slot: u64, slot: u64) -> ProgramError {
+        pinocchio_log::log!("Instruction expired at slot {}, now {}", expires_at_slot, slot);
+        JiminyError::InstructionExpired.into()
+    }
+
+    /// Logs the instruction and the slot it stopped after, returns `InstructionDeprecated`
+    #[cold]
+    pub fn instruction_deprecated(name: &str, after_slot: u64) -> ProgramError {
+        pinocchio_log::log!("{} was disabled after slot {}", name, after_slot);
+        JiminyError::InstructionDeprecated.into()
+    }
+
+    /// Logs the account and what its key must match, returns `AccountKeyMismatch`
+    #[cold]
+    pub fn account_key_mismatch(account: &str, expected: &str) -> ProgramError {
+        pinocchio_log::log!("Account {} doesn't match {}", account, expected);
+        JiminyError::AccountKeyMismatch.into()
+    }
+
+    /// Logs the role of the read-only account, returns `AccountNotWritable`
+    #[cold]
+    pub fn account_not_writable(role: &str) -> ProgramError {
+        pinocchio_log::log!("The {} account is not writable", role);
+        #[cfg(feature = "host-stubs")]
+        crate::testing::stubs::record_log(format_args!("The {} account is not writable", role));
+        JiminyError::AccountNotWritable.into()
+    }
+}
+
+/// Helpers called from the generated dispatch
+pub mod dispatch {
+    /// The error an unknown instruction discriminator fails with
+    ///
+    /// `define_errors!` and the build script implement it for enums with an
+    /// `InvalidDiscriminator` variant; implement it by hand to return another one.
+    pub trait HasInvalidDiscriminator: Sized {
+        const INVALID_DISCRIMINATOR: Self;
+    }
+
+    /// Called before a deprecated `alias` dispatches to the instruction at `canonical`
+    ///
+    /// Logs the two discriminators with the `deprecation-logs` feature, does nothing
+    /// otherwise. With `host-stubs` the call is recorded either way, see
+    /// `testing::stubs::deprecated_discriminators`.
+    #[inline(always)]
+    pub fn deprecated_discriminator(alias: u8, canonical: u8) {
+        #[cfg(feature = "host-stubs")]
+        crate::testing::stubs::record_deprecated_discriminator(alias, canonical);
+        #[cfg(feature = "deprecation-logs")]
+        pinocchio_log::log!("Discriminator {} is deprecated, send {}", alias, canonical);
+        #[cfg(not(feature = "deprecation-logs"))]
+        let _ = (alias, canonical);
+    }
+
+    /// Slots before the cutoff in which [`check_deprecated`] logs its warning, an epoch
+    pub const DEPRECATION_WARNING_SLOTS: u64 = 432_000;
+
+    /// Whether [`check_deprecated`] logs its warning at `slot`: in the last
+    /// [`DEPRECATION_WARNING_SLOTS`] up to and including `after_slot`, or always
+    /// before it with the `deprecation-logs` feature
+    pub const fn deprecation_warning(slot: u64, after_slot: u64) -> bool {
+        slot <= after_slot
+            && (cfg!(feature = "deprecation-logs")
+                || after_slot - slot < DEPRECATION_WARNING_SLOTS)
+    }
+
+    /// Run before an instruction declared `deprecated(after_slot: ...)` parses anything
+    ///
+    /// Fails with `JiminyError::InstructionDeprecated` once the slot is past
+    /// `after_slot`, and logs that `name` is deprecated while
+    /// [`deprecation_warning`] says so.
+    #[inline(always)]
+    pub fn check_deprecated(name: &str, after_slot: u64) -> pinocchio::ProgramResult {
+        let slot = crate::syscalls::clock_in("deprecated")?.slot;
+        if slot > after_slot {
+            return Err(crate::error::instruction_deprecated(name, after_slot));
+        }
+        if deprecation_warning(slot, after_slot) {
+            pinocchio_log::log!("{} is deprecated and stops after slot {}", name, after_slot);
+        }
+        Ok(())
+    }
+
+    /// Largest of `lens`, sizes the buffer `ProgramInstructions::pack` writes to
+    pub const fn max_len(lens: &[usize]) -> usize {
+        let mut max = 0;
+        let mut i = 0;
+        while i < lens.len() {
+            if lens[i] > max {
+                max = lens[i];
+            }
+            i += 1;
+        }
+        max
+    }
+
+    /// Most trailing bytes an instruction accepts, from its descriptor's `rest`
+    pub const fn rest_max_len(rest: Option<(&str, usize)>) -> usize {
+        match rest {
+            Some((_, max_len)) => max_len,
+            None => 0,
+        }
+    }
+}
+
+/// Overflow-free fixed-point math for fees and price ratios
+pub mod math {
+    /// `value * numerator / denominator` rounded down, through a `u128` intermediate
+    ///
+    /// `None` if `denominator` is zero or the result doesn't fit a `u64`.
+    #[inline(always)]
+    pub const fn mul_div(value: u64, numerator: u64, denominator: u64) -> Option<u64> {
+        if denominator == 0 {
+            return None;
+        }
+        let result = value as u128 * numerator as u128 / denominator as u128;
+        if result > u64::MAX as u128 {
+            return None;
+        }
+        Some(result as u64)
+    }
+
+    /// [`mul_div`] rounded up, for amounts that must not be undercharged
+    #[inline(always)]
+    pub const fn mul_div_ceil(value: u64, numerator: u64, denominator: u64) -> Option<u64> {
+        if denominator == 0 {
+            return None;
+        }
+        let result = (value as u128 * numerator as u128).div_ceil(denominator as u128);
+        if result > u64::MAX as u128 {
+            return None;
+        }
+        Some(result as u64)
+    }
+}
+
+/// Deadlines measured in unix time, slots or epochs
+///
+/// A deadline has *passed* once the clock is strictly after it, and is
+/// *reached* from the deadline itself on. At exactly the deadline it is
+/// reached but not passed, so a check on each side of it both succeed there.
+pub mod time {
+    use bytemuck::{Pod, Zeroable};
+    use pinocchio::{program_error::ProgramError, sysvars::clock::Clock};
+
+    /// What the value of a [`Deadline`] counts
+    #[repr(u8)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub enum DeadlineKind {
+        /// `Clock::unix_timestamp`, which validators can skew slightly
+        Unix = 0,
+        /// `Clock::slot`
+        Slot = 1,
+        /// `Clock::epoch`
+        Epoch = 2,
+    }
+
+    impl DeadlineKind {
+        pub const fn from_u8(kind: u8) -> Option<Self> {
+            match kind {
+                0 => Some(Self::Unix),
+                1 => Some(Self::Slot),
+                2 => Some(Self::Epoch),
+                _ => None,
+            }
+        }
+    }
+
+    /// A kind byte followed by the little-endian deadline, 9 bytes with no padding
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Pod, Zeroable)]
+    pub struct Deadline {
+        kind: u8,
+        value: [u8; 8],
+    }
+
+    impl Deadline {
+        pub const fn unix(timestamp: i64) -> Self {
+            Self {
+                kind: DeadlineKind::Unix as u8,
+                value: timestamp.to_le_bytes(),
+            }
+        }
+
+        pub const fn slot(slot: u64) -> Self {
+            Self {
+                kind: DeadlineKind::Slot as u8,
+                value: slot.to_le_bytes(),
+            }
+        }
+
+        pub const fn epoch(epoch: u64) -> Self {
+            Self {
+                kind: DeadlineKind::Epoch as u8,
+                value: epoch.to_le_bytes(),
+            }
+        }
+
+        /// `duration` seconds, slots or epochs after `clock`, saturating
+        pub const fn from_now(kind: DeadlineKind, clock: &Clock, duration: u64) -> Self {
+            match kind {
+                DeadlineKind::Unix => {
+                    Self::unix(clock.unix_timestamp.saturating_add_unsigned(duration))
+                }
+                DeadlineKind::Slot => Self::slot(clock.slot.saturating_add(duration)),
+                DeadlineKind::Epoch => Self::epoch(clock.epoch.saturating_add(duration)),
+            }
+        }
+
+        /// `duration` seconds, slots or epochs after `clock`, `None` on overflow
+        pub const fn checked_from_now(
+            kind: DeadlineKind,
+            clock: &Clock,
+            duration: u64,
+        ) -> Option<Self> {
+            match kind {
+                DeadlineKind::Unix => match clock.unix_timestamp.checked_add_unsigned(duration) {
+                    Some(timestamp) => Some(Self::unix(timestamp)),
+                    None => None,
+                },
+                DeadlineKind::Slot => match clock.slot.checked_add(duration) {
+                    Some(slot) => Some(Self::slot(slot)),
+                    None => None,
+                },
+                DeadlineKind::Epoch => match clock.epoch.checked_add(duration) {
+                    Some(epoch) => Some(Self::epoch(epoch)),
+                    None => None,
+                },
+            }
+        }
+
+        /// `None` for a kind byte this version doesn't know
+        pub const fn kind(&self) -> Option<DeadlineKind> {
+            DeadlineKind::from_u8(self.kind)
+        }
+
+        /// The timestamp, slot or epoch, depending on the kind
+        pub const fn value(&self) -> u64 {
+            u64::from_le_bytes(self.value)
+        }
+
+        /// The same kind of deadline, `duration` units later, saturating
+        pub const fn extended_by(&self, duration: u64) -> Self {
+            match self.kind() {
+                Some(DeadlineKind::Unix) => {
+                    Self::unix(i64::from_le_bytes(self.value).saturating_add_unsigned(duration))
+                }
+                Some(DeadlineKind::Slot) => Self::slot(self.value().saturating_add(duration)),
+                Some(DeadlineKind::Epoch) => Self::epoch(self.value().saturating_add(duration)),
+                None => *self,
+            }
+        }
+
+        /// Whether `clock` is strictly after the deadline; unknown kinds count as passed
+        pub const fn is_expired(&self, clock: &Clock) -> bool {
+            match self.kind() {
+                Some(DeadlineKind::Unix) => clock.unix_timestamp > i64::from_le_bytes(self.value),
+                Some(DeadlineKind::Slot) => clock.slot > self.value(),
+                Some(DeadlineKind::Epoch) => clock.epoch > self.value(),
+                None => true,
+            }
+        }
+
+        /// Whether `clock` is at or after the deadline; unknown kinds count as reached
+        pub const fn is_reached(&self, clock: &Clock) -> bool {
+            match self.kind() {
+                Some(DeadlineKind::Unix) => clock.unix_timestamp >= i64::from_le_bytes(self.value),
+                Some(DeadlineKind::Slot) => clock.slot >= self.value(),
+                Some(DeadlineKind::Epoch) => clock.epoch >= self.value(),
+                None => true,
+            }
+        }
+    }
+
+    /// Fail with `JiminyError::InstructionExpired` once `clock` is past the little-endian slot
+    ///
+    /// What `expiry: slot_field(...)` runs before the instruction body; the
+    /// instruction still goes through at the expiry slot itself.
+    #[inline(always)]
+    pub fn check_expiry(expires_at_slot: [u8; 8], clock: &Clock) -> Result<(), ProgramError> {
+        let expires_at_slot = u64::from_le_bytes(expires_at_slot);
+        if clock.slot > expires_at_slot {
+            return Err(crate::error::instruction_expired(expires_at_slot, clock.slot));
+        }
+        Ok(())
+    }
+
+    /// Whether at least `min_slots` slots passed between `created_at_slot` and `current_slot`
+    ///
+    /// A creation slot in the future counts as zero slots old.
+    pub const fn min_age_reached(created_at_slot: u64, current_slot: u64, min_slots: u64) -> bool {
+        current_slot.saturating_sub(created_at_slot) >= min_slots
+    }
+}
+
+/// Whether the clock's unix timestamp is strictly after `deadline` (an `i64`)
+#[macro_export]
+macro_rules! deadline_passed_unix {
+    ($deadline:expr) => {
+        $crate::syscalls::clock_in("deadline_passed_unix")?.unix_timestamp
+            > $deadline
+    };
+}
+
+/// Whether the clock's slot is strictly after `deadline` (a `u64`)
+#[macro_export]
+macro_rules! deadline_passed_slot {
+    ($deadline:expr) => {
+        $crate::syscalls::clock_in("deadline_passed_slot")?.slot > $deadline
+    };
+}
+
+/// Whether the clock's epoch is strictly after `deadline` (a `u64`)
+#[macro_export]
+macro_rules! deadline_passed_epoch {
+    ($deadline:expr) => {
+        $crate::syscalls::clock_in("deadline_passed_epoch")?.epoch > $deadline
+    };
+}
+
+/// Fail unless at least `min_slots` slots passed since `created_at_slot`
+///
+/// `created_at_slot` is a `u64` or a `LeU64` field, usually the one written by
+/// the `created_at_slot:` option of `create_pda!`. Reads the clock unless given
+/// one and fails with `JiminyError::AccountTooYoung` unless given an error; a
+/// `min_slots` of 1 rejects an account created in the current slot.
+///
+/// ```ignore
+/// assert_min_age!(position.created_at_slot, 1, clock: clock);
+/// ```
+#[macro_export]
+macro_rules! assert_min_age {
+    ($created_at_slot:expr, $min_slots:expr $(,)?) => {
+        $crate::assert_min_age!(
+            $created_at_slot,
+            $min_slots,
+            clock: &$crate::syscalls::clock_in("assert_min_age")?,
+            error: $crate::error::JiminyError::AccountTooYoung
+        )
+    };
+    ($created_at_slot:expr, $min_slots:expr, clock: $clock:expr $(,)?) => {
+        $crate::assert_min_age!(
+            $created_at_slot,
+            $min_slots,
+            clock: $clock,
+            error: $crate::error::JiminyError::AccountTooYoung
+        )
+    };
+    ($created_at_slot:expr, $min_slots:expr, error: $error:expr $(,)?) => {
+        $crate::assert_min_age!(
+            $created_at_slot,
+            $min_slots,
+            clock: &$crate::syscalls::clock_in("assert_min_age")?,
+            error: $error
+        )
+    };
+    ($created_at_slot:expr, $min_slots:expr, clock: $clock:expr, error: $error:expr $(,)?) => {{
+        let created_at_slot: u64 = ($created_at_slot).into();
+        let min_slots: u64 = $min_slots;
+        if !$crate::time::min_age_reached(created_at_slot, $clock.slot, min_slots) {
+            $crate::fail!(
+                $error,
+                "Account created at slot {} is younger than {} slots",
+                created_at_slot,
+                min_slots
+            );
+        }
+    }};
+}
+
+/// Account data length checks behind `assert_len!` and the loaders
+///
+/// A wrong length fails with `JiminyError::AccountDataTooShort` or
+/// `AccountDataTooLong`, logging both lengths, rather than one size mismatch
+/// error: the two codes predate `AccountClosed` and clients already decode them.
+/// Only data equal to `CLOSED_MARKER` fails with `AccountClosed`. A one-byte
+/// `0xff` would also be a live `define_marker!` state with bump 255, and empty
+/// data is what a never initialized account holds too, so neither can be told
+/// apart from a closed account; an empty account is too short.
+pub mod layout {
+    use crate::error::JiminyError;
+    use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+    /// The data `close_account!` leaves in a closed account
+    ///
+    /// Longer than the one byte of a `define_marker!` state, so a live marker
+    /// with bump 255 is never taken for a closed account, and a state of
+    /// exactly this length would have to spell it out.
+    pub const CLOSED_MARKER: [u8; 8] = *b"\xffclosed\xff";
+
+    /// Whether the account holds exactly `CLOSED_MARKER`
+    ///
+    /// An empty account isn't closed: it may never have held state, and the
+    /// length checks report it as too short.
+    #[inline(always)]
+    pub fn is_closed(account: &AccountInfo) -> bool {
+        account.data_len() == CLOSED_MARKER.len()
+            && account
+                .try_borrow_data()
+                .is_ok_and(|data| *data == CLOSED_MARKER)
+    }
+
+    /// Fail unless the account data is exactly `expected` bytes, see `assert_len!`
+    #[inline(always)]
+    pub fn check_account_len(account: &AccountInfo, expected: usize) -> ProgramResult {
+        let len = account.data_len();
+        if len != expected {
+            return Err(account_len_error(account, len, expected));
+        }
+        Ok(())
+    }
+
+    /// Fail unless the account data is at least `min` bytes, see `load_prefix!`
+    #[inline(always)]
+    pub fn check_account_min_len(account: &AccountInfo, min: usize) -> ProgramResult {
+        let len = account.data_len();
+        if len < min {
+            return Err(account_len_error(account, len, min));
+        }
+        Ok(())
+    }
+
+    // `AccountClosed` for a closed account, else the size mismatch with both lengths logged
+    #[cold]
+    fn account_len_error(account: &AccountInfo, len: usize, expected: usize) -> ProgramError {
+        if is_closed(account) {
+            return JiminyError::AccountClosed.into();
+        }
+        pinocchio_log::log!("Account data is {} bytes, expected {}", len, expected);
+        if len < expected {
+            JiminyError::AccountDataTooShort.into()
+        } else {
+            JiminyError::AccountDataTooLong.into()
+        }
+    }
+
+    /// Fail unless `len` is exactly `expected`
+    #[inline(always)]
+    pub fn check_len(len: usize, expected: usize) -> ProgramResult {
+        if len < expected {
+            return Err(JiminyError::AccountDataTooShort.into());
+        }
+        if len > expected {
+            return Err(JiminyError::AccountDataTooLong.into());
+        }
+        Ok(())
+    }
+
+    /// Fail with `AccountDataTooShort` if `len` is below `min`
+    #[inline(always)]
+    pub fn check_min_len(len: usize, min: usize) -> ProgramResult {
+        if len < min {
+            return Err(JiminyError::AccountDataTooShort.into());
+        }
+        Ok(())
+    }
+
+    /// Run `read` on `state`, the result can't borrow from it, see `read_state!`
+    #[inline(always)]
+    pub fn read_with<T, R: Copy>(state: &T, read: impl FnOnce(&T) -> R) -> R {
+        read(state)
+    }
+}
+
+/// Balance checks behind the instruction `invariants:` section
+///
+/// Each entry reads a balance (`lamports` or `tokens`) before the transfers and
+/// after the body, and fails with `JiminyError::InvariantViolated` unless it
+/// changed as declared (`increases_by` or `decreases_by`). Without the
+/// `runtime-invariants` feature nothing is read or checked.
+pub mod invariants {
+    use crate::error::JiminyError;
+    use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+    // SPL token account layout: mint, owner, then the little-endian amount
+    const TOKEN_AMOUNT_OFFSET: usize = 64;
+
+    /// Whether the `runtime-invariants` feature is on
+    pub const ENABLED: bool = cfg!(feature = "runtime-invariants");
+
+    pub fn lamports(account: &AccountInfo) -> Result<u64, ProgramError> {
+        Ok(account.lamports())
+    }
+
+    /// Amount held by an SPL token account
+    pub fn tokens(account: &AccountInfo) -> Result<u64, ProgramError> {
+        let data = account.try_borrow_data()?;
+        data.get(TOKEN_AMOUNT_OFFSET..TOKEN_AMOUNT_OFFSET + 8)
+            .map(|amount| u64::from_le_bytes(amount.try_into().unwrap()))
+            .ok_or(ProgramError::InvalidAccountData)
+    }
+
+    pub const fn increases_by(before: u64, after: u64, amount: u64) -> bool {
+        matches!(before.checked_add(amount), Some(expected) if expected == after)
+    }
+
+    pub const fn decreases_by(before: u64, after: u64, amount: u64) -> bool {
+        matches!(before.checked_sub(amount), Some(expected) if expected == after)
+    }
+
+    /// Logs the balances and fails with `InvariantViolated` unless `holds`
+    pub fn check(
+        account: &str,
+        before: u64,
+        after: u64,
+        holds: fn(u64, u64, u64) -> bool,
+        amount: u64,
+    ) -> ProgramResult {
+        if holds(before, after, amount) {
+            return Ok(());
+        }
+        pinocchio_log::log!("Invariant failed on {}: {} -> {}, by {}", account, before, after, amount);
+        Err(JiminyError::InvariantViolated.into())
+    }
+}
+
+/// The syscall-backed pieces the macros use: sysvar reads, CPIs and return data
+///
+/// With `host-stubs` they read and record through [`testing::stubs`] instead,
+/// so handlers run in plain host tests without the SBF toolchain.
+pub mod syscalls {
+    use pinocchio::{
+        account_info::AccountInfo,
+        instruction::Instruction,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        sysvars::{clock::Clock, rent::Rent},
+        ProgramResult,
+    };
+
+    /// The `Clock` sysvar, failing with `JiminyError::SysvarUnavailable`
+    #[inline(always)]
+    pub fn clock() -> Result<Clock, ProgramError> {
+        clock_in("clock")
+    }
+
+    /// [`clock`] read by `site`, which the `sysvar-logs` feature logs on failure
+    #[inline(always)]
+    pub fn clock_in(site: &'static str) -> Result<Clock, ProgramError> {
+        #[cfg(not(feature = "host-stubs"))]
+        let clock = <Clock as pinocchio::sysvars::Sysvar>::get();
+        #[cfg(feature = "host-stubs")]
+        let clock = crate::testing::stubs::read_clock();
+        clock.map_err(|_| sysvar_unavailable(site, "Clock"))
+    }
+
+    /// The `Rent` sysvar, failing with `JiminyError::SysvarUnavailable`
+    #[inline(always)]
+    pub fn rent() -> Result<Rent, ProgramError> {
+        rent_in("rent")
+    }
+
+    /// [`rent`] read by `site`, which the `sysvar-logs` feature logs on failure
+    #[inline(always)]
+    pub fn rent_in(site: &'static str) -> Result<Rent, ProgramError> {
+        #[cfg(not(feature = "host-stubs"))]
+        let rent = <Rent as pinocchio::sysvars::Sysvar>::get();
+        #[cfg(feature = "host-stubs")]
+        let rent = crate::testing::stubs::read_rent();
+        rent.map_err(|_| sysvar_unavailable(site, "Rent"))
+    }
+
+    // Logs e.g. `create_pda: Rent sysvar unavailable` with `sysvar-logs`
+    #[cold]
+    fn sysvar_unavailable(site: &'static str, sysvar: &'static str) -> ProgramError {
+        #[cfg(feature = "sysvar-logs")]
+        pinocchio_log::log!("{}: {} sysvar unavailable", site, sysvar);
+        #[cfg(not(feature = "sysvar-logs"))]
+        let _ = (site, sysvar);
+        crate::error::JiminyError::SysvarUnavailable.into()
+    }
+
+    /// Run the CPI in `call`, `name` (e.g. `"system::transfer"`) is what the stubs record
+    #[inline(always)]
+    pub fn cpi(name: &'static str, call: impl FnOnce() -> ProgramResult) -> ProgramResult {
+        #[cfg(not(feature = "host-stubs"))]
+        {
+            let _ = name;
+            call()
+        }
+        #[cfg(feature = "host-stubs")]
+        {
+            let _ = call;
+            crate::testing::stubs::record_cpi(name)
+        }
+    }
+
+    /// Invoke `instruction`, a hand-written CPI into a program the stubs can stand in for
+    ///
+    /// With `host-stubs` it's recorded as `name` like [`cpi`], then the program
+    /// added for the instruction's program id with `testing::stubs::add_program`
+    /// runs on the same accounts, so a test program can call back in. CPIs to
+    /// any other program do nothing.
+    #[inline(always)]
+    pub fn invoke<const N: usize>(
+        name: &'static str,
+        instruction: &Instruction,
+        accounts: &[&AccountInfo; N],
+    ) -> ProgramResult {
+        #[cfg(not(feature = "host-stubs"))]
+        {
+            let _ = name;
+            pinocchio::cpi::invoke(instruction, accounts)
+        }
+        #[cfg(feature = "host-stubs")]
+        {
+            crate::testing::stubs::record_cpi(name)?;
+            crate::testing::stubs::invoke(instruction, accounts)
+        }
+    }
+
+    /// [`cpi`] with `effect`, what the call does to the accounts, which the stubs
+    /// apply in its place so the handler can go on to use what it created
+    #[inline(always)]
+    pub fn cpi_applying(
+        name: &'static str,
+        call: impl FnOnce() -> ProgramResult,
+        effect: impl FnOnce() -> ProgramResult,
+    ) -> ProgramResult {
+        #[cfg(not(feature = "host-stubs"))]
+        {
+            let _ = (name, effect);
+            call()
+        }
+        #[cfg(feature = "host-stubs")]
+        {
+            let _ = call;
+            crate::testing::stubs::record_cpi(name)?;
+            effect()
+        }
+    }
+
+    /// What the system program's instructions do to their accounts, the effects
+    /// the macros pass to [`cpi_applying`]
+    ///
+    /// Failures use the system program's own codes: 0 for an account already in
+    /// use, 1 for a payer left short.
+    pub mod system {
+        use pinocchio::{
+            account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+        };
+
+        const ACCOUNT_ALREADY_IN_USE: ProgramError = ProgramError::Custom(0);
+        const RESULT_WITH_NEGATIVE_LAMPORTS: ProgramError = ProgramError::Custom(1);
+
+        pub fn create_account(
+            from: &AccountInfo,
+            to: &AccountInfo,
+            lamports: u64,
+            space: u64,
+            owner: &Pubkey,
+        ) -> ProgramResult {
+            if to.lamports() != 0 {
+                return Err(ACCOUNT_ALREADY_IN_USE);
+            }
+            transfer(from, to, lamports)?;
+            allocate(to, space)?;
+            assign(to, owner)
+        }
+
+        pub fn transfer(from: &AccountInfo, to: &AccountInfo, lamports: u64) -> ProgramResult {
+            let debited = from
+                .lamports()
+                .checked_sub(lamports)
+                .ok_or(RESULT_WITH_NEGATIVE_LAMPORTS)?;
+            let credited = to
+                .lamports()
+                .checked_add(lamports)
+                .ok_or(ProgramError::ArithmeticOverflow)?;
+            *from.try_borrow_mut_lamports()? = debited;
+            *to.try_borrow_mut_lamports()? = credited;
+            Ok(())
+        }
+
+        pub fn allocate(account: &AccountInfo, space: u64) -> ProgramResult {
+            if account.data_len() != 0 {
+                return Err(ACCOUNT_ALREADY_IN_USE);
+            }
+            account.resize(space as usize)
+        }
+
+        pub fn assign(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
+            // SAFETY: nothing borrows the account's owner across the call
+            unsafe { account.assign(owner) };
+            Ok(())
+        }
+    }
+
+    /// What the token program's `Transfer` does to its accounts, the effect
+    /// `transfer_tokens!` and `vault_withdraw_tokens!` pass to [`cpi_applying`]
+    ///
+    /// Only the amounts of the SPL token account layouts move; the authority,
+    /// the mints and the account states aren't checked. Failures use the token
+    /// program's own codes: 1 for a source left short, 14 for an overflow.
+    pub mod token {
+        use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+        const INSUFFICIENT_FUNDS: ProgramError = ProgramError::Custom(1);
+        const OVERFLOW: ProgramError = ProgramError::Custom(14);
+        // SPL token account layout: mint, owner, then the little-endian amount
+        const AMOUNT_OFFSET: usize = 64;
+
+        pub fn transfer(from: &AccountInfo, to: &AccountInfo, amount: u64) -> ProgramResult {
+            let debited = crate::invariants::tokens(from)?
+                .checked_sub(amount)
+                .ok_or(INSUFFICIENT_FUNDS)?;
+            // The token program lets an account send to itself, changing nothing
+            if from.key() == to.key() {
+                return Ok(());
+            }
+            let credited = crate::invariants::tokens(to)?
+                .checked_add(amount)
+                .ok_or(OVERFLOW)?;
+            set_amount(from, debited)?;
+            set_amount(to, credited)
+        }
+
+        fn set_amount(account: &AccountInfo, amount: u64) -> ProgramResult {
+            let mut data = account.try_borrow_mut_data()?;
+            data[AMOUNT_OFFSET..AMOUNT_OFFSET + 8].copy_from_slice(&amount.to_le_bytes());
+            Ok(())
+        }
+    }
+
+    /// The canonical address and bump for `seeds`, searching bumps from 255 down
+    #[inline(always)]
+    pub fn find_program_address<const N: usize>(
+        seeds: &[&[u8]; N],
+        program_id: &Pubkey,
+    ) -> (Pubkey, u8) {
+        #[cfg(not(feature = "host-stubs"))]
+        let found = pinocchio::pubkey::find_program_address(seeds, program_id);
+        #[cfg(feature = "host-stubs")]
+        let found = crate::testing::stubs::find_program_address(seeds, program_id);
+        found
+    }
+
+    /// Set the instruction's return data
+    #[inline(always)]
+    pub fn set_return_data(data: &[u8]) {
+        #[cfg(not(feature = "host-stubs"))]
+        pinocchio::cpi::set_return_data(data);
+        #[cfg(feature = "host-stubs")]
+        crate::testing::stubs::record_return_data(data);
+    }
+
+    /// Log `fields` as one `Program data:` line, each field base64 encoded
+    #[inline(always)]
+    pub fn log_data(fields: &[&[u8]]) {
+        #[cfg(not(feature = "host-stubs"))]
+        pinocchio::log::sol_log_data(fields);
+        #[cfg(feature = "host-stubs")]
+        crate::testing::stubs::record_log_data(fields);
+    }
+}
+
+/// Events logged with `sol_log_data`, for indexers and tests to read back
+///
+/// An event is a `Pod` struct logged as two fields, its name and its bytes,
+/// so readers tell events apart by the first field. Host tests decode them
+/// with [`testing::events`](crate::testing::events).
+pub mod event {
+    /// Log `event` under `name`, see `emit_event!`
+    #[inline(always)]
+    pub fn emit<T: bytemuck::Pod>(name: &'static str, event: &T) {
+        crate::syscalls::log_data(&[name.as_bytes(), bytemuck::bytes_of(event)]);
+    }
+}
+
+/// Per-instruction cache of the sysvars, declared with `context: ctx,`
+///
+/// The first `ctx.clock()?` reads the sysvar through [`syscalls`] and every
+/// later call returns the same value, so the checks in `prepare` and the
+/// process body see one clock and pay for one syscall.
+pub mod context {
+    use core::cell::OnceCell;
+    use pinocchio::{
+        program_error::ProgramError,
+        sysvars::{clock::Clock, rent::Rent},
+    };
+
+    /// Lazily read `Clock` and `Rent`, alive for one instruction
+    #[derive(Default)]
+    pub struct JiminyContext {
+        clock: OnceCell<Clock>,
+        rent: OnceCell<Rent>,
+    }
+
+    impl JiminyContext {
+        pub const fn new() -> Self {
+            Self {
+                clock: OnceCell::new(),
+                rent: OnceCell::new(),
+            }
+        }
+
+        /// The `Clock` sysvar, read on the first call
+        #[inline(always)]
+        pub fn clock(&self) -> Result<&Clock, ProgramError> {
+            if let Some(clock) = self.clock.get() {
+                return Ok(clock);
+            }
+            let clock = crate::syscalls::clock_in("JiminyContext::clock")?;
+            Ok(self.clock.get_or_init(|| clock))
+        }
+
+        /// The `Rent` sysvar, read on the first call
+        #[inline(always)]
+        pub fn rent(&self) -> Result<&Rent, ProgramError> {
+            if let Some(rent) = self.rent.get() {
+                return Ok(rent);
+            }
+            let rent = crate::syscalls::rent_in("JiminyContext::rent")?;
+            Ok(self.rent.get_or_init(|| rent))
+        }
+    }
+}
+
+/// Checks on the account paying for an account creation
+///
+/// The payer can be any signer, not only the instruction's authority, so a
+/// custodial service can fund accounts its users own.
+pub mod payer {
+    use crate::error::JiminyError;
+    use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+    /// Size of an SPL token account, what `create_ata!` pays rent for
+    pub const TOKEN_ACCOUNT_LEN: usize = 165;
+
+    /// Fail unless `payer` signed, is writable and holds at least `lamports`
+    ///
+    /// A shortfall logs both amounts and fails with `InsufficientPayerFunds`.
+    pub fn check(payer: &AccountInfo, lamports: u64) -> ProgramResult {
+        if !payer.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        crate::writable::check(payer, "payer")?;
+        if payer.lamports() < lamports {
+            pinocchio_log::log!("Payer has {} lamports, needs {}", payer.lamports(), lamports);
+            return Err(JiminyError::InsufficientPayerFunds.into());
+        }
+        Ok(())
+    }
+}
+
+/// The writable checks of the macros that change an account's lamports or data
+///
+/// A read-only account would otherwise only fail when the runtime sees it was
+/// modified, with no hint of which one. The `unchecked-writable` feature
+/// compiles the checks out to save their compute units.
+pub mod writable {
+    use pinocchio::{account_info::AccountInfo, ProgramResult};
+
+    /// Fail with `AccountNotWritable`, logging `role`, unless `account` is writable
+    #[inline(always)]
+    pub fn check(account: &AccountInfo, role: &str) -> ProgramResult {
+        #[cfg(not(feature = "unchecked-writable"))]
+        if !account.is_writable() {
+            return Err(crate::error::account_not_writable(role));
+        }
+        #[cfg(feature = "unchecked-writable")]
+        let _ = (account, role);
+        Ok(())
+    }
+}
+
+/// Checked lamport arithmetic on accounts
+///
+/// Each step borrows the lamports only for the update itself, so passing the
+/// same account on both sides of [`transfer`] doesn't hit a borrow error.
+pub mod lamports {
+    use crate::error::JiminyError;
+    use pinocchio::{account_info::AccountInfo, ProgramResult};
+
+    /// Add `amount` to `account`, failing with `LamportOverflow`
+    pub fn credit(account: &AccountInfo, amount: u64) -> ProgramResult {
+        let mut lamports = account.try_borrow_mut_lamports()?;
+        *lamports = lamports
+            .checked_add(amount)
+            .ok_or(JiminyError::LamportOverflow)?;
+        Ok(())
+    }
+
+    /// Take `amount` from `account`, failing with `InsufficientLamports`
+    pub fn debit(account: &AccountInfo, amount: u64) -> ProgramResult {
+        let mut lamports = account.try_borrow_mut_lamports()?;
+        *lamports = lamports
+            .checked_sub(amount)
+            .ok_or(JiminyError::InsufficientLamports)?;
+        Ok(())
+    }
+
+    /// Move `amount` from `from` to `to`, leaving both untouched on failure
+    pub fn transfer(from: &AccountInfo, to: &AccountInfo, amount: u64) -> ProgramResult {
+        debit(from, amount)?;
+        if let Err(e) = credit(to, amount) {
+            // Can't overflow, the lamports were just taken out
+            *from.try_borrow_mut_lamports()? += amount;
+            return Err(e);
+        }
+        Ok(())
+    }
+}
+
+/// Rent-exemption minimums computed off-chain, for clients budgeting account creation
+///
+/// Mirrors `Rent::minimum_balance` with the rate as a parameter, since it can
+/// change; the exemption threshold is the runtime's fixed two years.
+pub mod rent {
+    /// Bytes charged for on top of the account data
+    pub const ACCOUNT_STORAGE_OVERHEAD: u64 = 128;
+    /// Lamports per byte-year of the default rent
+    pub const DEFAULT_LAMPORTS_PER_BYTE_YEAR: u64 = 3480;
+    /// Years of rent an exempt account holds
+    pub const EXEMPTION_THRESHOLD_YEARS: u64 = 2;
+
+    /// Minimum balance of an account with `space` data bytes at `lamports_per_byte_year`
+    pub const fn exempt_lamports(space: usize, lamports_per_byte_year: u64) -> u64 {
+        (ACCOUNT_STORAGE_OVERHEAD + space as u64)
+            * lamports_per_byte_year
+            * EXEMPTION_THRESHOLD_YEARS
+    }
+}
+
+/// PDA vaults that hold SOL and own token accounts
+///
+/// `ensure_vault!`, `vault_deposit_sol!`, `vault_withdraw_sol!`,
+/// `vault_deposit_tokens!` and `vault_withdraw_tokens!` all take the vault
+/// as `vault, seeds: [...], bump: b, error: e`, assert it is that PDA
+/// (failing with `e`), then build the signer and transfer. Zero amounts
+/// skip the transfer.
+pub mod vault {
+    use crate::error::JiminyError;
+    use pinocchio::{account_info::AccountInfo, pubkey::Pubkey, ProgramResult};
+
+    // SPL token account layout: 32-byte mint, then the 32-byte owner
+    const TOKEN_OWNER_OFFSET: usize = 32;
+
+    /// Lamports a vault holding `lamports` can send while keeping `rent_floor`
+    pub const fn withdrawable(lamports: u64, rent_floor: u64) -> u64 {
+        lamports.saturating_sub(rent_floor)
+    }
+
+    /// Fail with `VaultBelowRentFloor` if sending `amount` leaves less than `rent_floor`
+    pub fn check_rent_floor(lamports: u64, amount: u64, rent_floor: u64) -> ProgramResult {
+        if amount > withdrawable(lamports, rent_floor) {
+            return Err(JiminyError::VaultBelowRentFloor.into());
+        }
+        Ok(())
+    }
+
+    /// Fail with `VaultTokenAccountMismatch` unless `vault` is the owner of `token_account`
+    pub fn check_token_owner(token_account: &AccountInfo, vault: &Pubkey) -> ProgramResult {
+        let data = token_account.try_borrow_data()?;
+        match data.get(TOKEN_OWNER_OFFSET..TOKEN_OWNER_OFFSET + 32) {
+            Some(owner) if owner == vault.as_ref() => Ok(()),
+            _ => Err(JiminyError::VaultTokenAccountMismatch.into()),
+        }
+    }
+}
+
+/// Checks against the program's upgrade authority (BPF upgradeable loader)
+pub mod upgrade {
+    use pinocchio::{
+        account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, ProgramResult,
+    };
+
+    pub const BPF_LOADER_UPGRADEABLE_ID: Pubkey =
+        pinocchio_pubkey::pubkey!("BPFLoaderUpgradeab1e11111111111111111111111");
+
+    // UpgradeableLoaderState::ProgramData: u32 tag, u64 slot, Option<Pubkey>
+    const PROGRAM_DATA_TAG: u32 = 3;
+    const AUTHORITY_OPTION_OFFSET: usize = 12;
+    const PROGRAM_DATA_HEADER_LEN: usize = AUTHORITY_OPTION_OFFSET + 1 + 32;
+
+    /// Upgrade authority stored in a programdata account, `None` once the program is frozen
+    pub fn programdata_authority(
+        programdata: &AccountInfo,
+    ) -> Result<Option<Pubkey>, ProgramError> {
+        if !programdata.is_owned_by(&BPF_LOADER_UPGRADEABLE_ID) {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        let data = programdata.try_borrow_data()?;
+        if data.len() < PROGRAM_DATA_HEADER_LEN
+            || u32::from_le_bytes([data[0], data[1], data[2], data[3]]) != PROGRAM_DATA_TAG
+        {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        match data[AUTHORITY_OPTION_OFFSET] {
+            0 => Ok(None),
+            1 => {
+                let mut authority = [0u8; 32];
+                authority
+                    .copy_from_slice(&data[AUTHORITY_OPTION_OFFSET + 1..PROGRAM_DATA_HEADER_LEN]);
+                Ok(Some(authority))
+            }
+            _ => Err(ProgramError::InvalidAccountData),
+        }
+    }
+
+    /// Fail unless `authority` signed and is the upgrade authority of `program_id`
+    ///
+    /// A frozen program has no upgrade authority and fails with `ProgramError::Immutable`.
+    pub fn check_upgrade_authority(
+        program: &AccountInfo,
+        programdata: &AccountInfo,
+        authority: &AccountInfo,
+        program_id: &Pubkey,
+    ) -> ProgramResult {
+        if program.key() != program_id {
+            return Err(ProgramError::IncorrectProgramId);
+        }
+        // Through the syscalls so host tests can build the programdata key
+        let (programdata_address, _) = crate::syscalls::find_program_address(
+            &[program_id.as_ref()],
+            &BPF_LOADER_UPGRADEABLE_ID,
+        );
+        if programdata.key() != &programdata_address {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        if !authority.is_signer() {
+            return Err(ProgramError::MissingRequiredSignature);
+        }
+        match programdata_authority(programdata)? {
+            Some(upgrade_authority) if &upgrade_authority == authority.key() => Ok(()),
+            Some(_) => Err(ProgramError::IncorrectAuthority),
+            None => Err(ProgramError::Immutable),
+        }
+    }
+}
+
+/// Descriptors of the instructions defined with jiminy, for test harnesses and doc tooling
+pub mod metadata {
+    /// An instruction as declared, from `XInstruction::describe()`
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct InstructionDescriptor {
+        pub name: &'static str,
+        /// The `desc:` line, if given
+        pub desc: Option<&'static str>,
+        pub discriminator: u8,
+        /// Deprecated discriminators that still dispatch here
+        pub aliases: &'static [u8],
+        pub accounts: &'static [AccountDescriptor],
+        pub fields: &'static [FieldDescriptor],
+        /// Name and max length of the trailing bytes, if declared
+        pub rest: Option<(&'static str, usize)>,
+        /// Name of the `data_parser` and the longest data it takes, if declared
+        pub data_parser: Option<(&'static str, usize)>,
+        /// Token transfers from the `transfers` section, in execution order
+        pub transfers: &'static [TransferDescriptor],
+        /// Type of the return data, if declared with `returns`
+        pub returns: Option<&'static str>,
+        /// Name and description of the accounts past the declared ones, if they're used
+        pub remaining_accounts: Option<(&'static str, &'static str)>,
+        /// Data field holding the last slot the instruction may run in, if declared with `expiry`
+        pub expiry: Option<&'static str>,
+        /// Last slot the instruction runs in, if declared with `deprecated(after_slot: ...)`
+        pub deprecated_after_slot: Option<u64>,
+    }
+
+    /// The kinds an account's `explorer_hint:` can name
+    pub const EXPLORER_HINTS: &[&str] =
+        &["pda", "wallet", "token_account", "mint", "program", "sysvar"];
+
+    /// One account of an instruction, in the order it must be passed
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct AccountDescriptor {
+        pub name: &'static str,
+        pub index: usize,
+        /// `signer`, `program`, `token`, `not_token`, `uninitialized`, `address` or `any`
+        pub account_type: &'static str,
+        /// Modifiers after `=>`, e.g. `["writable"]`
+        pub validation: &'static [&'static str],
+        pub signer: bool,
+        pub writable: bool,
+        pub desc: &'static str,
+        /// `label: "..."`, a short name for transaction previews
+        pub label: Option<&'static str>,
+        /// `explorer_hint: kind`, what sort of account explorers should show it as
+        pub explorer_hint: Option<&'static str>,
+    }
+
+    /// One fixed instruction data field
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct FieldDescriptor {
+        pub name: &'static str,
+        pub field_type: &'static str,
+        /// `///` lines on the field, as written (each keeps its leading space)
+        pub docs: &'static [&'static str],
+    }
+
+    /// One declared token transfer, `from => to: amount, authority: authority`
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct TransferDescriptor {
+        pub from: &'static str,
+        pub to: &'static str,
+        /// The amount expression as written
+        pub amount: &'static str,
+        pub authority: &'static str,
+        /// Whether the transfer is signed with PDA seeds
+        pub signed: bool,
+    }
+
+    /// Byte layout of a `define_state!` struct
+    pub trait StateLayout {
+        const NAME: &'static str;
+        const FIELDS: &'static [StateFieldDescriptor];
+    }
+
+    /// One field of a state struct and where it sits in the account data
+    #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+    pub struct StateFieldDescriptor {
+        pub name: &'static str,
+        pub field_type: &'static str,
+        pub offset: usize,
+        pub size: usize,
+    }
+
+    impl InstructionDescriptor {
+        /// Account with the given name
+        pub fn account(&self, name: &str) -> Option<&AccountDescriptor> {
+            self.accounts.iter().find(|account| account.name == name)
+        }
+    }
+}
+
+/// Fixed-capacity strings for state fields
+pub mod fixed_str {
+    use pinocchio::program_error::ProgramError;
+
+    /// Up to `N` bytes of text, zero-padded, that can live inside a Pod state struct
+    ///
+    /// The text ends at the first zero byte, so an embedded NUL truncates it.
+    #[repr(transparent)]
+    #[derive(Clone, Copy, PartialEq, Eq)]
+    pub struct FixedStr<const N: usize>([u8; N]);
+
+    // SAFETY: a transparent wrapper over `[u8; N]`, every bit pattern is valid
+    unsafe impl<const N: usize> bytemuck::Zeroable for FixedStr<N> {}
+    unsafe impl<const N: usize> bytemuck::Pod for FixedStr<N> {}
+
+    impl<const N: usize> FixedStr<N> {
+        pub const CAPACITY: usize = N;
+
+        pub const fn new() -> Self {
+            Self([0; N])
+        }
+
+        /// `s` zero-padded to `N` bytes, `InvalidArgument` if it's longer
+        #[inline]
+        pub fn from_bytes(s: &[u8]) -> Result<Self, ProgramError> {
+            let mut fixed = Self::new();
+            fixed.set(s)?;
+            Ok(fixed)
+        }
+
+        /// Replace the contents with `s`, zeroing the unused tail
+        #[inline]
+        pub fn set(&mut self, s: &[u8]) -> Result<(), ProgramError> {
+            if s.len() > N {
+                return Err(ProgramError::InvalidArgument);
+            }
+            self.0[..s.len()].copy_from_slice(s);
+            self.0[s.len()..].fill(0);
+            Ok(())
+        }
+
+        /// Bytes up to the first zero byte
+        #[inline]
+        pub fn as_bytes(&self) -> &[u8] {
+            let len = self.0.iter().position(|&b| b == 0).unwrap_or(N);
+            &self.0[..len]
+        }
+
+        /// Text up to the first zero byte, cut at the first invalid UTF-8 sequence
+        #[inline]
+        pub fn as_str_lossy(&self) -> &str {
+            let bytes = self.as_bytes();
+            match core::str::from_utf8(bytes) {
+                Ok(s) => s,
+                // SAFETY: `valid_up_to` marks the end of the valid UTF-8 prefix
+                Err(e) => unsafe { core::str::from_utf8_unchecked(&bytes[..e.valid_up_to()]) },
+            }
+        }
+
+        #[inline]
+        pub fn is_empty(&self) -> bool {
+            self.as_bytes().is_empty()
+        }
+    }
+
+    impl<const N: usize> Default for FixedStr<N> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+}
+
+/// Typed public keys for state fields
+pub mod pubkey_bytes {
+    use crate::perf::keys_eq;
+    use pinocchio::pubkey::Pubkey;
+
+    /// A public key inside a Pod state struct
+    ///
+    /// Unlike a bare `[u8; 32]` field it can't be assigned a raw byte array
+    /// without an explicit conversion. The build script shows it as
+    /// `publicKey` in the IDL.
+    #[repr(transparent)]
+    #[derive(Clone, Copy, Debug, Default, Eq)]
+    pub struct PubkeyBytes(Pubkey);
+
+    // SAFETY: a transparent wrapper over `[u8; 32]`, every bit pattern is valid
+    unsafe impl bytemuck::Zeroable for PubkeyBytes {}
+    unsafe impl bytemuck::Pod for PubkeyBytes {}
+
+    impl PubkeyBytes {
+        pub const fn new(key: Pubkey) -> Self {
+            Self(key)
+        }
+
+        #[inline(always)]
+        pub const fn as_key(&self) -> &Pubkey {
+            &self.0
+        }
+    }
+
+    impl From<&Pubkey> for PubkeyBytes {
+        #[inline(always)]
+        fn from(key: &Pubkey) -> Self {
+            Self(*key)
+        }
+    }
+
+    impl From<Pubkey> for PubkeyBytes {
+        #[inline(always)]
+        fn from(key: Pubkey) -> Self {
+            Self(key)
+        }
+    }
+
+    impl PartialEq for PubkeyBytes {
+        #[inline(always)]
+        fn eq(&self, other: &Self) -> bool {
+            keys_eq(&self.0, &other.0)
+        }
+    }
+
+    impl PartialEq<Pubkey> for PubkeyBytes {
+        #[inline(always)]
+        fn eq(&self, other: &Pubkey) -> bool {
+            keys_eq(&self.0, other)
+        }
+    }
+
+    impl AsRef<[u8]> for PubkeyBytes {
+        fn as_ref(&self) -> &[u8] {
+            &self.0
+        }
+    }
+}
+
+/// Booleans and optional values for state fields, with explicit encodings
+///
+/// Both are one tag byte that must be 0 or 1, followed for `PodOption` by the
+/// value's bytes, zeroed when empty. Any other tag fails the read with
+/// `JiminyError::InvalidPodTag` rather than being taken as true or present. The
+/// build script shows them as `bool` and `option<...>` in the IDL.
+pub mod pod {
+    use crate::error::JiminyError;
+    use crate::le::{LeU16, LeU32, LeU64};
+    use crate::pubkey_bytes::PubkeyBytes;
+    use pinocchio::program_error::ProgramError;
+
+    const NONE: u8 = 0;
+    const SOME: u8 = 1;
+
+    /// A `bool` inside a Pod state struct, stored as 0 or 1
+    #[repr(transparent)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct PodBool(u8);
+
+    // SAFETY: a transparent wrapper over `u8`, every bit pattern is valid to hold
+    unsafe impl bytemuck::Zeroable for PodBool {}
+    unsafe impl bytemuck::Pod for PodBool {}
+
+    impl PodBool {
+        pub const fn new(value: bool) -> Self {
+            Self(value as u8)
+        }
+
+        /// The value, `InvalidPodTag` if the byte is neither 0 nor 1
+        #[inline(always)]
+        pub fn get(&self) -> Result<bool, ProgramError> {
+            match self.0 {
+                NONE => Ok(false),
+                SOME => Ok(true),
+                _ => Err(JiminyError::InvalidPodTag.into()),
+            }
+        }
+
+        #[inline(always)]
+        pub fn set(&mut self, value: bool) {
+            self.0 = value as u8;
+        }
+    }
+
+    impl From<bool> for PodBool {
+        #[inline(always)]
+        fn from(value: bool) -> Self {
+            Self::new(value)
+        }
+    }
+
+    /// Types a `PodOption` can hold, Pod with an alignment of 1 so the tag adds no padding
+    ///
+    /// # Safety
+    ///
+    /// The type must be `Pod` and `align_of::<Self>() == 1`.
+    pub unsafe trait PodValue: bytemuck::Pod {}
+
+    // SAFETY: byte arrays and the transparent byte-array wrappers are all 1-aligned
+    unsafe impl<const N: usize> PodValue for [u8; N] where [u8; N]: bytemuck::Pod {}
+    unsafe impl PodValue for PubkeyBytes {}
+    unsafe impl PodValue for LeU16 {}
+    unsafe impl PodValue for LeU32 {}
+    unsafe impl PodValue for LeU64 {}
+
+    /// An `Option<T>` inside a Pod state struct, a tag byte then `T`'s bytes
+    ///
+    /// The value takes its full size even when empty, so the struct keeps a
+    /// fixed layout; `clear` zeroes it.
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct PodOption<T: PodValue> {
+        tag: u8,
+        value: T,
+    }
+
+    // SAFETY: `repr(C)` over a `u8` and a 1-aligned Pod `T`, so there is no padding
+    unsafe impl<T: PodValue> bytemuck::Zeroable for PodOption<T> {}
+    unsafe impl<T: PodValue> bytemuck::Pod for PodOption<T> {}
+
+    const _: () = assert!(core::mem::size_of::<PodBool>() == 1);
+    const _: () = assert!(core::mem::size_of::<PodOption<[u8; 32]>>() == 33);
+    const _: () = assert!(core::mem::align_of::<PodOption<[u8; 32]>>() == 1);
+    const _: () = assert!(core::mem::offset_of!(PodOption<[u8; 32]>, value) == 1);
+
+    impl<T: PodValue> PodOption<T> {
+        pub fn none() -> Self {
+            bytemuck::Zeroable::zeroed()
+        }
+
+        pub const fn some(value: T) -> Self {
+            Self { tag: SOME, value }
+        }
+
+        /// Whether a value is set, `InvalidPodTag` if the tag is neither 0 nor 1
+        #[inline(always)]
+        pub fn is_some(&self) -> Result<bool, ProgramError> {
+            match self.tag {
+                NONE => Ok(false),
+                SOME => Ok(true),
+                _ => Err(JiminyError::InvalidPodTag.into()),
+            }
+        }
+
+        /// The value if one is set, `InvalidPodTag` if the tag is neither 0 nor 1
+        #[inline(always)]
+        pub fn get(&self) -> Result<Option<&T>, ProgramError> {
+            Ok(self.is_some()?.then_some(&self.value))
+        }
+
+        #[inline(always)]
+        pub fn set(&mut self, value: T) {
+            *self = Self::some(value);
+        }
+
+        /// Unset the value, zeroing its bytes
+        #[inline(always)]
+        pub fn clear(&mut self) {
+            *self = Self::none();
+        }
+    }
+
+    impl<T: PodValue> From<Option<T>> for PodOption<T> {
+        #[inline(always)]
+        fn from(value: Option<T>) -> Self {
+            value.map_or_else(Self::none, Self::some)
+        }
+    }
+}
+
+/// Little-endian integers for state fields
+///
+/// Each is a transparent byte array, so it is `Pod` at any offset, and is read
+/// and written through `get()`/`set()` instead of `from_le_bytes`/`to_le_bytes`
+/// at every use. The build script shows them as plain integers in the IDL.
+pub mod le {
+    use pinocchio::program_error::ProgramError;
+
+    macro_rules! le_int {
+        ($name:ident, $int:ty, $len:literal) => {
+            #[doc = concat!("A little-endian `", stringify!($int), "` inside a Pod state struct")]
+            #[repr(transparent)]
+            #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+            pub struct $name([u8; $len]);
+
+            // SAFETY: a transparent wrapper over a byte array, every bit pattern is valid
+            unsafe impl bytemuck::Zeroable for $name {}
+            unsafe impl bytemuck::Pod for $name {}
+
+            impl $name {
+                pub const fn new(value: $int) -> Self {
+                    Self(value.to_le_bytes())
+                }
+
+                #[inline(always)]
+                pub const fn get(&self) -> $int {
+                    <$int>::from_le_bytes(self.0)
+                }
+
+                #[inline(always)]
+                pub fn set(&mut self, value: $int) {
+                    self.0 = value.to_le_bytes();
+                }
+
+                /// `self += amount`, failing with `ArithmeticOverflow` instead of wrapping
+                #[inline(always)]
+                pub fn checked_add_assign(&mut self, amount: $int) -> Result<(), ProgramError> {
+                    let total = self
+                        .get()
+                        .checked_add(amount)
+                        .ok_or(ProgramError::ArithmeticOverflow)?;
+                    self.set(total);
+                    Ok(())
+                }
+            }
+
+            impl From<$int> for $name {
+                #[inline(always)]
+                fn from(value: $int) -> Self {
+                    Self::new(value)
+                }
+            }
+
+            impl From<$name> for $int {
+                #[inline(always)]
+                fn from(value: $name) -> Self {
+                    value.get()
+                }
+            }
+        };
+    }
+
+    le_int!(LeU16, u16, 2);
+    le_int!(LeU32, u32, 4);
+    le_int!(LeU64, u64, 8);
+}
+
+/// Fixed-capacity collections for `no_std` programs
+pub mod collections {
+    use crate::error::JiminyError;
+    use core::mem::MaybeUninit;
+    use core::ops::{Deref, DerefMut};
+    use pinocchio::program_error::ProgramError;
+
+    /// Up to `N` values on the stack, for lists whose length is only known at runtime
+    ///
+    /// E.g. the account metas of a CPI with optional accounts, sized for the
+    /// most accounts it can take rather than built as a `Vec`. Derefs to the
+    /// slice of the values pushed so far; pushing past `N` is an error, never a panic.
+    pub struct StackVec<T, const N: usize> {
+        buf: MaybeUninit<[T; N]>,
+        len: usize,
+    }
+
+    impl<T, const N: usize> StackVec<T, N> {
+        pub const fn new() -> Self {
+            Self {
+                buf: MaybeUninit::uninit(),
+                len: 0,
+            }
+        }
+
+        #[inline(always)]
+        pub const fn len(&self) -> usize {
+            self.len
+        }
+
+        #[inline(always)]
+        pub const fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        #[inline(always)]
+        pub const fn capacity(&self) -> usize {
+            N
+        }
+
+        #[inline(always)]
+        pub const fn is_full(&self) -> bool {
+            self.len == N
+        }
+
+        /// Append `value`, failing with `CapacityExceeded` once `N` values are in
+        #[inline]
+        pub fn push(&mut self, value: T) -> Result<(), ProgramError> {
+            self.try_push(value)
+                .map_err(|_| JiminyError::CapacityExceeded.into())
+        }
+
+        /// Append `value`, handing it back if the vec is full
+        #[inline]
+        pub fn try_push(&mut self, value: T) -> Result<(), T> {
+            if self.len == N {
+                return Err(value);
+            }
+            // SAFETY: `len < N`, so the slot is inside the buffer and not yet initialized
+            unsafe { self.as_mut_ptr().add(self.len).write(value) };
+            self.len += 1;
+            Ok(())
+        }
+
+        #[inline]
+        pub fn pop(&mut self) -> Option<T> {
+            if self.len == 0 {
+                return None;
+            }
+            self.len -= 1;
+            // SAFETY: the slot at the old last index was initialized and is now past `len`
+            Some(unsafe { self.as_mut_ptr().add(self.len).read() })
+        }
+
+        pub fn clear(&mut self) {
+            let values: *mut [T] = self.as_mut_slice();
+            self.len = 0;
+            // SAFETY: the values were initialized and are no longer reachable
+            unsafe { core::ptr::drop_in_place(values) };
+        }
+
+        #[inline(always)]
+        pub fn as_slice(&self) -> &[T] {
+            // SAFETY: the first `len` values are initialized
+            unsafe { core::slice::from_raw_parts(self.buf.as_ptr() as *const T, self.len) }
+        }
+
+        #[inline(always)]
+        pub fn as_mut_slice(&mut self) -> &mut [T] {
+            // SAFETY: the first `len` values are initialized
+            unsafe { core::slice::from_raw_parts_mut(self.as_mut_ptr(), self.len) }
+        }
+
+        #[inline(always)]
+        fn as_mut_ptr(&mut self) -> *mut T {
+            self.buf.as_mut_ptr() as *mut T
+        }
+    }
+
+    impl<T, const N: usize> Default for StackVec<T, N> {
+        fn default() -> Self {
+            Self::new()
+        }
+    }
+
+    impl<T, const N: usize> Drop for StackVec<T, N> {
+        fn drop(&mut self) {
+            self.clear();
+        }
+    }
+
+    impl<T, const N: usize> Deref for StackVec<T, N> {
+        type Target = [T];
+
+        #[inline(always)]
+        fn deref(&self) -> &[T] {
+            self.as_slice()
+        }
+    }
+
+    impl<T, const N: usize> DerefMut for StackVec<T, N> {
+        #[inline(always)]
+        fn deref_mut(&mut self) -> &mut [T] {
+            self.as_mut_slice()
+        }
+    }
+
+    impl<T: core::fmt::Debug, const N: usize> core::fmt::Debug for StackVec<T, N> {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            f.debug_list().entries(self.as_slice()).finish()
+        }
+    }
+}
+
+/// Program entrypoint with pinocchio's default allocator and panic handler
+#[cfg(not(feature = "debug-panics"))]
+#[macro_export]
+macro_rules! jiminy_entrypoint {
+    ($process_instruction:ident) => {
+        pinocchio::entrypoint!($process_instruction);
+    };
+}
+
+/// Program entrypoint that logs panic locations (`debug-panics` feature)
+#[cfg(feature = "debug-panics")]
+#[macro_export]
+macro_rules! jiminy_entrypoint {
+    ($process_instruction:ident) => {
+        pinocchio::program_entrypoint!($process_instruction);
+        pinocchio::default_allocator!();
+        $crate::debug_panic_handler!();
+    };
+}
+
+/// no_std panic handler logging the file and line of the panic
+#[cfg(feature = "debug-panics")]
+#[macro_export]
+macro_rules! debug_panic_handler {
+    () => {
+        #[cfg(target_os = "solana")]
+        #[panic_handler]
+        fn panic_handler(info: &core::panic::PanicInfo<'_>) -> ! {
+            match info.location() {
+                Some(location) => {
+                    pinocchio_log::log!("panic at {}:{}", location.file(), location.line())
+                }
+                None => pinocchio_log::log!("panic at unknown location"),
+            }
+            unsafe { pinocchio::syscalls::abort() }
+        }
+    };
+}
+
+/// Borsh encoding for `data_parser` instructions, without the borsh crate
+///
+/// Integers are little-endian, `bool` is one byte, `[u8; N]` is its bytes,
+/// `Option<T>` a `0`/`1` tag before the value, and byte slices and strings
+/// (`Vec<u8>` and `String` in borsh) a `u32` length before the bytes. The
+/// generated `ProgramInstructions::pack`/`unpack` use the same encoding for
+/// the `data_schema` fields.
+///
+/// ```ignore
+/// let mut reader = Reader::new(data);
+/// let label: &str = reader.read()?;
+/// let max_position: Option<u64> = reader.read()?;
+/// reader.finish()?;
+/// ```
+pub mod borsh {
+    use pinocchio::{program_error::ProgramError, ProgramResult};
+
+    /// Reads values off the front of the data, failing with
+    /// `InvalidInstructionData` when it runs out
+    pub struct Reader<'a> {
+        data: &'a [u8],
+    }
+
+    impl<'a> Reader<'a> {
+        pub const fn new(data: &'a [u8]) -> Self {
+            Self { data }
+        }
+
+        pub fn read<T: Decode<'a>>(&mut self) -> Result<T, ProgramError> {
+            T::decode(self)
+        }
+
+        /// The next `len` bytes
+        pub fn take(&mut self, len: usize) -> Result<&'a [u8], ProgramError> {
+            if self.data.len() < len {
+                return Err(ProgramError::InvalidInstructionData);
+            }
+            let (taken, data) = self.data.split_at(len);
+            self.data = data;
+            Ok(taken)
+        }
+
+        /// Fail with `TrailingInstructionData` unless every byte was read
+        pub fn finish(self) -> ProgramResult {
+            if !self.data.is_empty() {
+                return Err(crate::error::trailing_instruction_data(self.data.len()));
+            }
+            Ok(())
+        }
+    }
+
+    /// A value [`Reader::read`] can decode
+    pub trait Decode<'a>: Sized {
+        fn decode(reader: &mut Reader<'a>) -> Result<Self, ProgramError>;
+    }
+
+    /// Writes values into a buffer, panicking if it's too short
+    pub struct Writer<'a> {
+        buf: &'a mut [u8],
+        len: usize,
+    }
+
+    impl<'a> Writer<'a> {
+        pub fn new(buf: &'a mut [u8]) -> Self {
+            Self { buf, len: 0 }
+        }
+
+        pub fn put<T: Encode + ?Sized>(&mut self, value: &T) {
+            value.encode(self);
+        }
+
+        pub fn put_bytes(&mut self, bytes: &[u8]) {
+            self.buf[self.len..self.len + bytes.len()].copy_from_slice(bytes);
+            self.len += bytes.len();
+        }
+
+        /// Bytes written so far
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+    }
+
+    /// A value [`Writer::put`] can encode
+    pub trait Encode {
+        fn encode(&self, writer: &mut Writer);
+    }
+
+    macro_rules! int {
+        ($($int:ty),*) => {$(
+            impl<'a> Decode<'a> for $int {
+                fn decode(reader: &mut Reader<'a>) -> Result<Self, ProgramError> {
+                    let bytes = reader.take(core::mem::size_of::<$int>())?;
+                    Ok(<$int>::from_le_bytes(bytes.try_into().unwrap()))
+                }
+            }
+
+            impl Encode for $int {
+                fn encode(&self, writer: &mut Writer) {
+                    writer.put_bytes(&self.to_le_bytes());
+                }
+            }
+        )*};
+    }
+
+    int!(u8, u16, u32, u64, u128, i8, i16, i32, i64, i128);
+
+    impl<'a> Decode<'a> for bool {
+        fn decode(reader: &mut Reader<'a>) -> Result<Self, ProgramError> {
+            match reader.read::<u8>()? {
+                0 => Ok(false),
+                1 => Ok(true),
+                _ => Err(ProgramError::InvalidInstructionData),
+            }
+        }
+    }
+
+    impl Encode for bool {
+        fn encode(&self, writer: &mut Writer) {
+            writer.put(&(*self as u8));
+        }
+    }
+
+    impl<'a, const N: usize> Decode<'a> for [u8; N] {
+        fn decode(reader: &mut Reader<'a>) -> Result<Self, ProgramError> {
+            Ok(reader.take(N)?.try_into().unwrap())
+        }
+    }
+
+    impl<const N: usize> Encode for [u8; N] {
+        fn encode(&self, writer: &mut Writer) {
+            writer.put_bytes(self);
+        }
+    }
+
+    impl<'a, T: Decode<'a>> Decode<'a> for Option<T> {
+        fn decode(reader: &mut Reader<'a>) -> Result<Self, ProgramError> {
+            match reader.read::<bool>()? {
+                false => Ok(None),
+                true => Ok(Some(reader.read()?)),
+            }
+        }
+    }
+
+    impl<T: Encode> Encode for Option<T> {
+        fn encode(&self, writer: &mut Writer) {
+            writer.put(&self.is_some());
+            if let Some(value) = self {
+                writer.put(value);
+            }
+        }
+    }
+
+    impl<'a> Decode<'a> for &'a [u8] {
+        fn decode(reader: &mut Reader<'a>) -> Result<Self, ProgramError> {
+            let len = reader.read::<u32>()?;
+            reader.take(len as usize)
+        }
+    }
+
+    impl Encode for [u8] {
+        fn encode(&self, writer: &mut Writer) {
+            writer.put(&(self.len() as u32));
+            writer.put_bytes(self);
+        }
+    }
+
+    impl<'a> Decode<'a> for &'a str {
+        fn decode(reader: &mut Reader<'a>) -> Result<Self, ProgramError> {
+            core::str::from_utf8(reader.read()?).map_err(|_| ProgramError::InvalidInstructionData)
+        }
+    }
+
+    impl Encode for str {
+        fn encode(&self, writer: &mut Writer) {
+            writer.put(self.as_bytes());
+        }
+    }
+}
+
+/// Typed consumption of an instruction's remaining accounts
+///
+/// Running out fails with `NotEnoughAccountKeys` and logs which account is
+/// missing, counted from the first remaining account.
+///
+/// ```ignore
+/// let mut rest = remaining.typed();
+/// let extra_vault = next_validated!(rest, token => writable);
+/// for pair in rest.chunks_of(2)? {
+///     let (position, owner_token_account) = pair.typed().next_pair()?;
+/// }
+/// ```
+pub mod account_iter {
+    use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+    /// Hands out accounts in order, counting how many it has handed out
+    #[derive(Clone)]
+    pub struct AccountIter<'a> {
+        accounts: &'a [AccountInfo],
+        consumed: usize,
+    }
+
+    impl<'a> AccountIter<'a> {
+        pub const fn new(accounts: &'a [AccountInfo]) -> Self {
+            Self {
+                accounts,
+                consumed: 0,
+            }
+        }
+
+        /// Accounts not handed out yet
+        pub const fn len(&self) -> usize {
+            self.accounts.len()
+        }
+
+        pub const fn is_empty(&self) -> bool {
+            self.accounts.is_empty()
+        }
+
+        /// The accounts not handed out yet, as a slice
+        pub const fn as_slice(&self) -> &'a [AccountInfo] {
+            self.accounts
+        }
+
+        /// The next account, `NotEnoughAccountKeys` if none are left
+        pub fn next_account(&mut self) -> Result<&'a AccountInfo, ProgramError> {
+            let index = self.consumed;
+            self.next().ok_or_else(|| missing_account(index))
+        }
+
+        /// The next two accounts
+        pub fn next_pair(&mut self) -> Result<(&'a AccountInfo, &'a AccountInfo), ProgramError> {
+            Ok((self.next_account()?, self.next_account()?))
+        }
+
+        /// Fail unless exactly `len` accounts are left
+        ///
+        /// Too few is `NotEnoughAccountKeys`, too many `InvalidArgument`.
+        pub fn expect_len(&self, len: usize) -> ProgramResult {
+            if self.accounts.len() == len {
+                return Ok(());
+            }
+            pinocchio_log::log!("Expected {} remaining accounts, got {}", len, self.accounts.len());
+            if self.accounts.len() < len {
+                Err(ProgramError::NotEnoughAccountKeys)
+            } else {
+                Err(ProgramError::InvalidArgument)
+            }
+        }
+
+        /// Every account left, in groups of `size`
+        ///
+        /// Fails with `NotEnoughAccountKeys` when the last group would be short.
+        pub fn chunks_of(
+            &mut self,
+            size: usize,
+        ) -> Result<core::slice::ChunksExact<'a, AccountInfo>, ProgramError> {
+            if size == 0 {
+                return Err(ProgramError::InvalidArgument);
+            }
+            let short = self.accounts.len() % size;
+            if short != 0 {
+                return Err(missing_account(self.consumed + self.accounts.len() + size - short - 1));
+            }
+            let chunks = self.accounts.chunks_exact(size);
+            self.consumed += self.accounts.len();
+            self.accounts = &[];
+            Ok(chunks)
+        }
+    }
+
+    impl<'a> Iterator for AccountIter<'a> {
+        type Item = &'a AccountInfo;
+
+        fn next(&mut self) -> Option<Self::Item> {
+            let (first, rest) = self.accounts.split_first()?;
+            self.accounts = rest;
+            self.consumed += 1;
+            Some(first)
+        }
+
+        fn size_hint(&self) -> (usize, Option<usize>) {
+            (self.accounts.len(), Some(self.accounts.len()))
+        }
+    }
+
+    impl ExactSizeIterator for AccountIter<'_> {}
+
+    /// `.typed()` on an account slice, imported by instructions that declare `remaining_accounts`
+    pub trait TypedAccounts {
+        fn typed(&self) -> AccountIter<'_>;
+    }
+
+    impl TypedAccounts for [AccountInfo] {
+        fn typed(&self) -> AccountIter<'_> {
+            AccountIter::new(self)
+        }
+    }
+
+    #[cold]
+    fn missing_account(index: usize) -> ProgramError {
+        pinocchio_log::log!("Remaining account {} is missing", index);
+        ProgramError::NotEnoughAccountKeys
+    }
+}
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+/// Re-export common items
+pub use field::DataField;
+pub use account_iter::AccountIter;
+pub use collections::StackVec;
+pub use context::JiminyContext;
+pub use fixed_str::FixedStr;
+pub use le::{LeU16, LeU32, LeU64};
+pub use pod::{PodBool, PodOption};
+pub use pubkey_bytes::PubkeyBytes;
+pub use time::Deadline;
+pub use jiminy_macros::instruction;
+pub use paste::paste;
+
+/// Macro that generates program errors with ShankType for IDL generation
+///
+/// Usage:
+/// ```ignore
+/// define_errors! {
+///     ProgramError,
+///     InvalidDiscriminator = 6001,
+///     PlatformKeyIncorrect = 6002,
+///     VaultKeyIncorrect = 6003,
+/// }
+/// ```
+///
+/// This will generate:
+/// - A `#[non_exhaustive]` enum with #[derive(Clone, Debug, PartialEq, ShankType)]
+/// - A `Display` impl showing each variant's doc comment, or its name
+/// - impl From<ProgramError> for ProgramError conversion
+/// - [`dispatch::HasInvalidDiscriminator`] if the enum has an `InvalidDiscriminator` variant
+///
+/// Nothing is imported into the calling module, so several enums can share one.
+#[macro_export]
+macro_rules! define_errors {
+    (
+        $error_name:ident,
+        $(
+            $(#[doc = $doc:literal])*
+            $variant:ident = $code:literal
+        ),* $(,)?
+    ) => {
+        #[derive(Clone, Debug, PartialEq, shank::ShankType)]
+        #[non_exhaustive]
+        pub enum $error_name {
+            $(
+                $(#[doc = $doc])*
+                $variant = $code,
+            )*
+        }
+
+        impl core::fmt::Display for $error_name {
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                match self {
+                    $(
+                        Self::$variant => $crate::define_errors!(@message f, $variant $(, $doc)*),
+                    )*
+                }
+            }
+        }
+
+        impl From<$error_name> for pinocchio::program_error::ProgramError {
+            fn from(e: $error_name) -> Self {
+                Self::Custom(e as u32)
+            }
+        }
+
+        $crate::define_errors!(@invalid_discriminator $error_name, $($variant)*);
+    };
+
+    // Implement `HasInvalidDiscriminator` when one of the variants is `InvalidDiscriminator`
+    (@invalid_discriminator $error_name:ident, InvalidDiscriminator $($rest:ident)*) => {
+        impl $crate::dispatch::HasInvalidDiscriminator for $error_name {
+            const INVALID_DISCRIMINATOR: Self = Self::InvalidDiscriminator;
+        }
+    };
+    (@invalid_discriminator $error_name:ident, $variant:ident $($rest:ident)*) => {
+        $crate::define_errors!(@invalid_discriminator $error_name, $($rest)*);
+    };
+    (@invalid_discriminator $error_name:ident,) => {};
+
+    // Helper for the Display message, the variant name when undocumented
+    (@message $f:ident, $variant:ident) => { $f.write_str(stringify!($variant)) };
+    (@message $f:ident, $variant:ident $(, $doc:literal)+) => {
+        // `/// text` lines arrive as " text", so the joined lines are already space separated
+        $f.write_str(concat!($($doc),+).trim())
+    };
+}
+
+/// Simple program definition that generates dispatch and references external shank enum
+///
+/// `0 | 10 => InitializePlatform` also dispatches the deprecated discriminator 10,
+/// calling [`dispatch::deprecated_discriminator`] first. Unknown discriminators fail with
+/// `error_type`'s [`dispatch::HasInvalidDiscriminator::INVALID_DISCRIMINATOR`].
+#[macro_export]
+macro_rules! jiminy_program {
+    (
+        error_type: $error_type:ty,
+        $(
+            $disc:literal $(| $alias:literal)* => $instruction:ident
+        ),* $(,)?
+    ) => {
+        pub fn process_instruction(
+            program_id: &Pubkey,
+            accounts: &[AccountInfo],
+            instruction_data: &[u8],
+        ) -> ProgramResult {
+            // Validate program ID
+            if !$crate::perf::keys_eq(program_id, &crate::ID) {
+                return Err(ProgramError::IncorrectProgramId);
+            }
+
+            // Dispatch to instruction handlers
+            match instruction_data.first() {
+                $(
+                    Some($disc) => {
+                        $crate::paste! {
+                            [<$instruction Instruction>]::try_from((accounts, &instruction_data[1..]))?.process()
+                        }
+                    }
+                    $(
+                        Some($alias) => {
+                            $crate::dispatch::deprecated_discriminator($alias, $disc);
+                            $crate::paste! {
+                                [<$instruction Instruction>]::try_from((accounts, &instruction_data[1..]))?.process()
+                            }
+                        }
+                    )*
+                )*
+                _ => Err(
+                    <$error_type as $crate::dispatch::HasInvalidDiscriminator>::INVALID_DISCRIMINATOR
+                        .into(),
+                ),
+            }
+        }
+    };
+}
+
+/// Macro to define shank instruction enum variants
+#[macro_export]
+macro_rules! shank_instruction {
+    (
+        $name:ident {
+            $(
+                #[account($idx:literal, $($account_attr:tt)*)]
+            )*
+            data: {
+                $(
+                    $field:ident: $field_type:ty
+                ),* $(,)?
+            }
+        }
+    ) => {
+        $(
+            #[account($idx, $($account_attr)*)]
+        )*
+        $name {
+            $(
+                $field: $field_type,
+            )*
+        }
+    };
+}
+
+/// Generate complete shank enum from instruction list  
+#[macro_export]
+macro_rules! define_program_instructions {
+    (
+        $(
+            $variant:tt
+        ),* $(,)?
+    ) => {
+        use shank::ShankInstruction;
+
+        /// Program instructions for IDL generation
+        #[repr(u8)]
+        #[derive(Clone, Debug, PartialEq, ShankInstruction)]
+        pub enum ProgramInstructions {
+            $(
+                $variant,
+            )*
+        }
+    };
+}
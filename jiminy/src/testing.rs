@@ -0,0 +1,1401 @@
+//! Host-side helpers for program tests
+//!
+//! Snapshot the accounts before and after executing an instruction (with
+//! mollusk, litesvm or anything else that hands back account data) and
+//! [`Differ::diff`] them to see what changed:
+//!
+//! ```ignore
+//! let differ = Differ::new().state::<Counter>(counter_key, "counter").label(owner_key, "owner");
+//! let report = differ.diff(&before, &after);
+//! report.assert_only(&["counter.count"]);
+//! ```
+//!
+//! [`execute`] turns the backend's logs and inner instructions into the CPI
+//! tree, see [`Trace`]. [`coverage_report`] lists the instructions no test
+//! has run yet, and [`events`] decodes the events in the logs. [`Harness`]
+//! moves a backend's clock for deadline tests, and [`process`] runs a program's
+//! `process_instruction` on the host without a backend at all.
+
+extern crate std;
+
+use crate::metadata::{StateFieldDescriptor, StateLayout};
+use core::fmt;
+use core::sync::atomic::{AtomicU64, Ordering};
+use pinocchio::{account_info::AccountInfo, pubkey::Pubkey, sysvars::clock::Clock, ProgramResult};
+use std::{format, string::String, vec, vec::Vec};
+
+/// An account as it looked at one point of a test
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountSnapshot {
+    pub key: Pubkey,
+    pub lamports: u64,
+    pub owner: Pubkey,
+    pub data: Vec<u8>,
+}
+
+impl AccountSnapshot {
+    pub fn new(key: Pubkey, lamports: u64, owner: Pubkey, data: &[u8]) -> Self {
+        Self {
+            key,
+            lamports,
+            owner,
+            data: data.to_vec(),
+        }
+    }
+}
+
+/// SPL token account data to seed a test with, e.g. an account with an approval:
+///
+/// ```ignore
+/// let data = TokenAccount::new(mint, owner, 1_000).approve(delegate, 500).to_bytes();
+/// ```
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct TokenAccount {
+    pub mint: Pubkey,
+    pub owner: Pubkey,
+    pub amount: u64,
+    /// Approved delegate and the amount it may still move
+    pub delegate: Option<(Pubkey, u64)>,
+}
+
+impl TokenAccount {
+    pub const LEN: usize = crate::payer::TOKEN_ACCOUNT_LEN;
+
+    pub fn new(mint: Pubkey, owner: Pubkey, amount: u64) -> Self {
+        Self {
+            mint,
+            owner,
+            amount,
+            delegate: None,
+        }
+    }
+
+    /// Approve `delegate` for `amount`, like an SPL `Approve` signed by the owner
+    pub fn approve(mut self, delegate: Pubkey, amount: u64) -> Self {
+        self.delegate = Some((delegate, amount));
+        self
+    }
+
+    /// The account data in the SPL layout, initialized and not native
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut data = vec![0u8; Self::LEN];
+        data[..32].copy_from_slice(&self.mint);
+        data[32..64].copy_from_slice(&self.owner);
+        data[64..72].copy_from_slice(&self.amount.to_le_bytes());
+        if let Some((delegate, amount)) = self.delegate {
+            data[72..76].copy_from_slice(&1u32.to_le_bytes());
+            data[76..108].copy_from_slice(&delegate);
+            data[121..129].copy_from_slice(&amount.to_le_bytes());
+        }
+        // AccountState::Initialized
+        data[108] = 1;
+        data
+    }
+}
+
+/// A state field whose bytes changed
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldChange {
+    pub field: StateFieldDescriptor,
+    pub before: Vec<u8>,
+    pub after: Vec<u8>,
+}
+
+/// Everything that changed on one account
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AccountDiff {
+    pub key: Pubkey,
+    /// Registered label, or the base58 key
+    pub label: String,
+    /// Name of the registered state struct, if any
+    pub state: Option<&'static str>,
+    pub lamports: (u64, u64),
+    pub owner: (Pubkey, Pubkey),
+    pub data_len: (usize, usize),
+    /// Changed fields when a state is registered and both sides fit its layout
+    pub fields: Vec<FieldChange>,
+    /// Whether any data byte changed, decoded or not
+    pub data_changed: bool,
+}
+
+impl AccountDiff {
+    pub fn lamport_delta(&self) -> i128 {
+        self.lamports.1 as i128 - self.lamports.0 as i128
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.lamports.0 == self.lamports.1
+            && self.owner.0 == self.owner.1
+            && self.data_len.0 == self.data_len.1
+            && !self.data_changed
+    }
+
+    /// Paths of what changed, e.g. `counter.lamports` or `counter.count`
+    ///
+    /// Data changes are reported per field when they can be decoded, and as
+    /// `label.data` otherwise.
+    pub fn changes(&self) -> Vec<String> {
+        let mut changes = Vec::new();
+        if self.lamports.0 != self.lamports.1 {
+            changes.push(format!("{}.lamports", self.label));
+        }
+        if self.owner.0 != self.owner.1 {
+            changes.push(format!("{}.owner", self.label));
+        }
+        if self.data_len.0 != self.data_len.1 {
+            changes.push(format!("{}.data_len", self.label));
+        }
+        if self.fields.is_empty() {
+            if self.data_changed {
+                changes.push(format!("{}.data", self.label));
+            }
+        } else {
+            for change in &self.fields {
+                changes.push(format!("{}.{}", self.label, change.field.name));
+            }
+        }
+        changes
+    }
+}
+
+/// Changes across all accounts of an execution, printable for assertion messages
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct StateDiff {
+    /// Accounts that changed, in snapshot order
+    pub accounts: Vec<AccountDiff>,
+}
+
+impl StateDiff {
+    pub fn is_empty(&self) -> bool {
+        self.accounts.is_empty()
+    }
+
+    pub fn account(&self, label: &str) -> Option<&AccountDiff> {
+        self.accounts.iter().find(|account| account.label == label)
+    }
+
+    /// Paths of everything that changed, see [`AccountDiff::changes`]
+    pub fn changes(&self) -> Vec<String> {
+        self.accounts.iter().flat_map(AccountDiff::changes).collect()
+    }
+
+    /// Panics with the full report unless exactly `expected` changed
+    #[track_caller]
+    pub fn assert_only(&self, expected: &[&str]) {
+        let mut changes = self.changes();
+        changes.sort();
+        let mut expected: Vec<&str> = expected.to_vec();
+        expected.sort();
+        if changes.iter().map(String::as_str).ne(expected.iter().copied()) {
+            panic!("expected only {expected:?} to change, got {changes:?}\n{self}");
+        }
+    }
+
+    /// Panics with the full report if anything changed
+    #[track_caller]
+    pub fn assert_unchanged(&self) {
+        self.assert_only(&[]);
+    }
+}
+
+impl fmt::Display for StateDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.accounts.is_empty() {
+            return writeln!(f, "no account changed");
+        }
+        for account in &self.accounts {
+            match account.state {
+                Some(state) => writeln!(f, "{} ({state}):", account.label)?,
+                None => writeln!(f, "{}:", account.label)?,
+            }
+            if account.lamports.0 != account.lamports.1 {
+                writeln!(
+                    f,
+                    "  lamports: {} -> {} ({:+})",
+                    account.lamports.0,
+                    account.lamports.1,
+                    account.lamport_delta()
+                )?;
+            }
+            if account.owner.0 != account.owner.1 {
+                writeln!(
+                    f,
+                    "  owner: {} -> {}",
+                    bs58(&account.owner.0),
+                    bs58(&account.owner.1)
+                )?;
+            }
+            if account.data_len.0 != account.data_len.1 {
+                writeln!(f, "  data_len: {} -> {}", account.data_len.0, account.data_len.1)?;
+            }
+            for change in &account.fields {
+                writeln!(
+                    f,
+                    "  {}: {} -> {}",
+                    change.field.name,
+                    format_value(&change.before),
+                    format_value(&change.after)
+                )?;
+            }
+            if account.fields.is_empty() && account.data_changed {
+                writeln!(f, "  data changed")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Tracked {
+    key: Pubkey,
+    label: &'static str,
+    state: Option<(&'static str, &'static [StateFieldDescriptor])>,
+}
+
+/// Compares account snapshots, decoding registered accounts field by field
+#[derive(Default)]
+pub struct Differ {
+    tracked: Vec<Tracked>,
+}
+
+impl Differ {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Names an account in the report
+    pub fn label(mut self, key: Pubkey, label: &'static str) -> Self {
+        self.tracked.push(Tracked {
+            key,
+            label,
+            state: None,
+        });
+        self
+    }
+
+    /// Names an account and decodes its data with the layout of `T`
+    pub fn state<T: StateLayout>(mut self, key: Pubkey, label: &'static str) -> Self {
+        self.tracked.push(Tracked {
+            key,
+            label,
+            state: Some((T::NAME, T::FIELDS)),
+        });
+        self
+    }
+
+    /// Compares `before` and `after` by key; accounts missing on one side count as empty
+    pub fn diff(&self, before: &[AccountSnapshot], after: &[AccountSnapshot]) -> StateDiff {
+        let mut keys: Vec<Pubkey> = Vec::new();
+        for snapshot in before.iter().chain(after) {
+            if !keys.contains(&snapshot.key) {
+                keys.push(snapshot.key);
+            }
+        }
+
+        let accounts = keys
+            .into_iter()
+            .map(|key| {
+                let old = before.iter().find(|s| s.key == key);
+                let new = after.iter().find(|s| s.key == key);
+                self.diff_account(key, old, new)
+            })
+            .filter(|diff| !diff.is_empty())
+            .collect();
+
+        StateDiff { accounts }
+    }
+
+    fn diff_account(
+        &self,
+        key: Pubkey,
+        before: Option<&AccountSnapshot>,
+        after: Option<&AccountSnapshot>,
+    ) -> AccountDiff {
+        let tracked = self.tracked.iter().rev().find(|t| t.key == key);
+        let empty: &[u8] = &[];
+        let old_data = before.map_or(empty, |s| &s.data[..]);
+        let new_data = after.map_or(empty, |s| &s.data[..]);
+
+        let mut fields = Vec::new();
+        if let Some((_, layout)) = tracked.and_then(|t| t.state) {
+            for field in layout {
+                let range = field.offset..field.offset + field.size;
+                if let (Some(old), Some(new)) =
+                    (old_data.get(range.clone()), new_data.get(range))
+                {
+                    if old != new {
+                        fields.push(FieldChange {
+                            field: *field,
+                            before: old.to_vec(),
+                            after: new.to_vec(),
+                        });
+                    }
+                }
+            }
+        }
+
+        AccountDiff {
+            key,
+            label: tracked.map_or_else(|| bs58(&key), |t| String::from(t.label)),
+            state: tracked.and_then(|t| t.state).map(|(name, _)| name),
+            lamports: (
+                before.map_or(0, |s| s.lamports),
+                after.map_or(0, |s| s.lamports),
+            ),
+            owner: (
+                before.map_or([0; 32], |s| s.owner),
+                after.map_or([0; 32], |s| s.owner),
+            ),
+            data_len: (old_data.len(), new_data.len()),
+            fields,
+            data_changed: old_data != new_data,
+        }
+    }
+}
+
+/// Compares snapshots without any registered labels or states
+pub fn diff(before: &[AccountSnapshot], after: &[AccountSnapshot]) -> StateDiff {
+    Differ::new().diff(before, after)
+}
+
+/// One instruction a backend ran inside the top-level one, in execution order
+///
+/// What mollusk and litesvm report as inner instructions; `stack_height` is 2
+/// for a CPI made by the program itself, 3 for one made by that callee, and so on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InnerInstruction {
+    pub stack_height: u8,
+    pub program_id: Pubkey,
+    pub accounts: Vec<Pubkey>,
+    pub data: Vec<u8>,
+}
+
+/// What a simulation backend reports for one executed instruction
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Execution {
+    /// The top-level error, formatted
+    pub result: Result<(), String>,
+    /// The program logs, `Program <id> invoke [n]` lines included
+    pub logs: Vec<String>,
+    pub inner_instructions: Vec<InnerInstruction>,
+}
+
+/// One node of the call tree: the top-level instruction or a CPI
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TraceNode {
+    pub program_id: Pubkey,
+    pub accounts: Vec<Pubkey>,
+    pub data: Vec<u8>,
+    /// The error it failed with, `None` when it succeeded
+    pub error: Option<String>,
+    /// CPIs it made, in order
+    pub children: Vec<TraceNode>,
+}
+
+impl TraceNode {
+    fn new(program_id: Pubkey) -> Self {
+        Self {
+            program_id,
+            accounts: Vec::new(),
+            data: Vec::new(),
+            error: None,
+            children: Vec::new(),
+        }
+    }
+
+    /// The first data byte, the instruction for jiminy and SPL token programs
+    pub fn discriminator(&self) -> Option<u8> {
+        self.data.first().copied()
+    }
+
+    fn walk<'a>(&'a self, nodes: &mut Vec<&'a TraceNode>) {
+        for child in &self.children {
+            nodes.push(child);
+            child.walk(nodes);
+        }
+    }
+
+    fn render(&self, f: &mut fmt::Formatter<'_>, depth: usize) -> fmt::Result {
+        write!(f, "{:indent$}{}", "", bs58(&self.program_id), indent = depth * 2)?;
+        if let Some(discriminator) = self.discriminator() {
+            write!(f, " [{discriminator}]")?;
+        }
+        write!(f, ", {} accounts", self.accounts.len())?;
+        match &self.error {
+            Some(error) => writeln!(f, ": failed, {error}")?,
+            None => writeln!(f, ": ok")?,
+        }
+        for child in &self.children {
+            child.render(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+/// The CPI tree of an executed instruction, printed indented one level per call depth
+///
+/// Built from the backend's logs, which give the tree and each call's result,
+/// and its inner instructions, which give the accounts and data. Without logs
+/// the tree comes from the stack heights alone and only the top-level result
+/// is known.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Trace {
+    pub root: TraceNode,
+}
+
+impl Trace {
+    /// The trace of `data` sent to `program_id` with `accounts`, as `execution` reports it
+    pub fn new(
+        program_id: Pubkey,
+        accounts: &[Pubkey],
+        data: &[u8],
+        execution: &Execution,
+    ) -> Self {
+        let mut root = TraceNode::new(program_id);
+        root.accounts = accounts.to_vec();
+        root.data = data.to_vec();
+
+        if !tree_from_logs(&mut root, &execution.logs) {
+            for inner in &execution.inner_instructions {
+                let depth = usize::from(inner.stack_height.saturating_sub(1)).max(1);
+                let mut node = TraceNode::new(inner.program_id);
+                node.accounts = inner.accounts.clone();
+                node.data = inner.data.clone();
+                last_at_depth(&mut root, depth - 1).children.push(node);
+            }
+        } else {
+            // Both list the CPIs in the order they were made
+            let mut paths = Vec::new();
+            cpi_paths(&root, &mut Vec::new(), &mut paths);
+            for (path, inner) in paths.iter().zip(&execution.inner_instructions) {
+                let node = node_at(&mut root, path);
+                if node.program_id == inner.program_id {
+                    node.accounts = inner.accounts.clone();
+                    node.data = inner.data.clone();
+                }
+            }
+        }
+        if let Err(error) = &execution.result {
+            root.error.get_or_insert_with(|| error.clone());
+        }
+        Self { root }
+    }
+
+    pub fn is_ok(&self) -> bool {
+        self.root.error.is_none()
+    }
+
+    /// Every CPI, depth first in the order they were made
+    pub fn cpis(&self) -> Vec<&TraceNode> {
+        let mut nodes = Vec::new();
+        self.root.walk(&mut nodes);
+        nodes
+    }
+
+    /// CPIs to `program_id` with `discriminator` as their first data byte
+    pub fn cpis_to(&self, program_id: &Pubkey, discriminator: u8) -> Vec<&TraceNode> {
+        self.cpis()
+            .into_iter()
+            .filter(|node| node.program_id == *program_id)
+            .filter(|node| node.discriminator() == Some(discriminator))
+            .collect()
+    }
+
+    /// The deepest failed call, where the error came from
+    pub fn failure(&self) -> Option<&TraceNode> {
+        let mut node = &self.root;
+        node.error.as_ref()?;
+        while let Some(child) = node.children.iter().find(|child| child.error.is_some()) {
+            node = child;
+        }
+        Some(node)
+    }
+
+    /// Panics with the rendered tree if the instruction failed
+    #[track_caller]
+    pub fn assert_ok(&self) {
+        if !self.is_ok() {
+            panic!("instruction failed\n{self}");
+        }
+    }
+}
+
+impl fmt::Display for Trace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.root.render(f, 0)
+    }
+}
+
+/// Run one instruction through a backend and trace it
+///
+/// `run` executes the instruction and converts the backend's result into an
+/// [`Execution`]. A failure prints the tree to stderr, which the test harness
+/// shows when the test fails:
+///
+/// ```ignore
+/// let trace = execute(program_id, &keys, &data, || {
+///     let result = mollusk.process_instruction(&instruction, &accounts);
+///     Execution { result: .., logs: .., inner_instructions: .. }
+/// });
+/// trace.assert_ok();
+/// assert_eq!(trace.cpis_to(&TOKEN_PROGRAM_ID, 3).len(), 2); // two `Transfer`s
+/// ```
+pub fn execute(
+    program_id: Pubkey,
+    accounts: &[Pubkey],
+    data: &[u8],
+    run: impl FnOnce() -> Execution,
+) -> Trace {
+    if let Some(&discriminator) = data.first() {
+        record_coverage(discriminator);
+    }
+    let trace = Trace::new(program_id, accounts, data, &run());
+    if !trace.is_ok() {
+        std::eprint!("{trace}");
+    }
+    trace
+}
+
+/// Where a [`Harness`] writes its clock, one impl per simulation backend
+///
+/// ```ignore
+/// impl ClockSysvar for MolluskBackend {
+///     fn set_clock(&mut self, clock: &Clock) {
+///         self.mollusk.sysvars.clock.slot = clock.slot;
+///         self.mollusk.sysvars.clock.unix_timestamp = clock.unix_timestamp;
+///     }
+/// }
+/// ```
+pub trait ClockSysvar {
+    fn set_clock(&mut self, clock: &Clock);
+}
+
+/// Milliseconds a slot takes, what [`Harness::advance`] converts seconds with
+pub const MS_PER_SLOT: u64 = 400;
+
+/// A backend with a clock tests move by hand, for deadline and age checks
+///
+/// Every change is written to the backend right away. [`Harness::execute`]
+/// moves the slot on by one after each instruction like a chain would, unless
+/// the clock is frozen, so a frozen multi-instruction test sees the same
+/// `Clock` in every instruction:
+///
+/// ```ignore
+/// let mut harness = Harness::new(backend);
+/// harness.warp_to_timestamp(deadline - 1);
+/// harness.freeze_clock();
+/// harness.execute(program_id, &keys, &data, |backend| backend.run(&instruction)).assert_ok();
+/// harness.advance(1); // exactly at the deadline
+/// ```
+pub struct Harness<B> {
+    backend: B,
+    clock: Clock,
+    frozen: bool,
+}
+
+impl<B: ClockSysvar> Harness<B> {
+    /// A harness at slot 0 and timestamp 0, written to `backend`
+    pub fn new(backend: B) -> Self {
+        Self::with_clock(
+            backend,
+            Clock {
+                slot: 0,
+                epoch_start_timestamp: 0,
+                epoch: 0,
+                leader_schedule_epoch: 0,
+                unix_timestamp: 0,
+            },
+        )
+    }
+
+    /// A harness starting at `clock`, written to `backend`
+    pub fn with_clock(mut backend: B, clock: Clock) -> Self {
+        backend.set_clock(&clock);
+        Self {
+            backend,
+            clock,
+            frozen: false,
+        }
+    }
+
+    pub fn backend(&self) -> &B {
+        &self.backend
+    }
+
+    pub fn backend_mut(&mut self) -> &mut B {
+        &mut self.backend
+    }
+
+    /// The clock the next instruction reads
+    pub fn clock(&self) -> Clock {
+        self.clock
+    }
+
+    /// Move `unix_timestamp` to `timestamp`, the slot stays
+    pub fn warp_to_timestamp(&mut self, timestamp: i64) {
+        self.clock.unix_timestamp = timestamp;
+        self.backend.set_clock(&self.clock);
+    }
+
+    /// Move the slot to `slot`, the timestamp stays
+    pub fn warp_to_slot(&mut self, slot: u64) {
+        self.clock.slot = slot;
+        self.backend.set_clock(&self.clock);
+    }
+
+    /// Move the timestamp `seconds` forward and the slot by as many slots as that takes
+    pub fn advance(&mut self, seconds: u64) {
+        self.clock.unix_timestamp = self.clock.unix_timestamp.saturating_add_unsigned(seconds);
+        self.clock.slot = self.clock.slot.saturating_add(seconds * 1000 / MS_PER_SLOT);
+        self.backend.set_clock(&self.clock);
+    }
+
+    /// Stop [`Harness::execute`] from moving the slot, warps still apply
+    pub fn freeze_clock(&mut self) {
+        self.frozen = true;
+    }
+
+    pub fn unfreeze_clock(&mut self) {
+        self.frozen = false;
+    }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    /// [`execute`] with the backend, then the next slot unless the clock is frozen
+    pub fn execute(
+        &mut self,
+        program_id: Pubkey,
+        accounts: &[Pubkey],
+        data: &[u8],
+        run: impl FnOnce(&mut B) -> Execution,
+    ) -> Trace {
+        let trace = execute(program_id, accounts, data, || run(&mut self.backend));
+        if !self.frozen {
+            self.warp_to_slot(self.clock.slot.saturating_add(1));
+        }
+        trace
+    }
+}
+
+/// An account handed to a program run on the host with [`process`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HostAccount {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub is_signer: bool,
+    pub is_writable: bool,
+    pub executable: bool,
+}
+
+impl HostAccount {
+    /// A read-only account that doesn't sign
+    pub fn new(key: Pubkey, owner: Pubkey, lamports: u64, data: &[u8]) -> Self {
+        Self {
+            key,
+            owner,
+            lamports,
+            data: data.to_vec(),
+            is_signer: false,
+            is_writable: false,
+            executable: false,
+        }
+    }
+
+    pub fn signer(mut self) -> Self {
+        self.is_signer = true;
+        self
+    }
+
+    pub fn writable(mut self) -> Self {
+        self.is_writable = true;
+        self
+    }
+
+    /// The account as a [`Differ`] compares it
+    pub fn snapshot(&self) -> AccountSnapshot {
+        AccountSnapshot::new(self.key, self.lamports, self.owner, &self.data)
+    }
+}
+
+// What the loader puts before an account's data: the borrow state (or duplicate
+// marker), the signer, writable and executable flags, 4 bytes of padding, the key,
+// the owner, the lamports and the data length
+const ACCOUNT_HEADER_LEN: usize = 88;
+// How far the loader lets the program grow an account's data
+const MAX_PERMITTED_DATA_INCREASE: usize = 10_240;
+// The borrow state of an account nothing has borrowed
+const NON_DUP_MARKER: u8 = u8::MAX;
+
+/// Accounts laid out the way the loader serializes them, for the `AccountInfo`s of a host run
+///
+/// Each account gets its header, its data and the room it may grow by, so the
+/// `AccountInfo`s read, borrow, resize and close it like they would on chain. A
+/// key listed again shares its first entry, as the loader's duplicate marker does.
+pub struct HostInput {
+    // Kept alive, and in place, for `infos` to point into
+    _entries: Vec<Vec<u64>>,
+    infos: Vec<AccountInfo>,
+}
+
+impl HostInput {
+    pub fn new(accounts: &[HostAccount]) -> Self {
+        let mut entries = Vec::new();
+        let mut infos: Vec<AccountInfo> = Vec::with_capacity(accounts.len());
+        for (index, account) in accounts.iter().enumerate() {
+            if let Some(first) = accounts[..index].iter().position(|a| a.key == account.key) {
+                infos.push(infos[first].clone());
+                continue;
+            }
+            let len = ACCOUNT_HEADER_LEN + account.data.len() + MAX_PERMITTED_DATA_INCREASE;
+            // Whole words, so the header is aligned like the loader's
+            let mut entry = vec![0u64; len.div_ceil(8)];
+            let bytes: &mut [u8] = bytemuck::cast_slice_mut(&mut entry);
+            bytes[0] = NON_DUP_MARKER;
+            bytes[1] = account.is_signer.into();
+            bytes[2] = account.is_writable.into();
+            bytes[3] = account.executable.into();
+            bytes[8..40].copy_from_slice(&account.key);
+            bytes[40..72].copy_from_slice(&account.owner);
+            bytes[72..80].copy_from_slice(&account.lamports.to_le_bytes());
+            bytes[80..88].copy_from_slice(&(account.data.len() as u64).to_le_bytes());
+            bytes[ACCOUNT_HEADER_LEN..ACCOUNT_HEADER_LEN + account.data.len()]
+                .copy_from_slice(&account.data);
+            // SAFETY: an `AccountInfo` is the pointer to its header the entrypoint hands out,
+            // and the entry stays where it is for as long as `self` lives
+            let info = unsafe {
+                core::mem::transmute::<*mut u8, AccountInfo>(entry.as_mut_ptr().cast())
+            };
+            infos.push(info);
+            entries.push(entry);
+        }
+        Self {
+            _entries: entries,
+            infos,
+        }
+    }
+
+    /// The accounts as the program's `process_instruction` takes them
+    pub fn accounts(&self) -> &[AccountInfo] {
+        &self.infos
+    }
+
+    /// Copy the lamports, owner and data the program left into `accounts`, in the same order
+    pub fn write_back(&self, accounts: &mut [HostAccount]) {
+        for (account, info) in accounts.iter_mut().zip(&self.infos) {
+            account.lamports = info.lamports();
+            // SAFETY: the program has returned, nothing borrows the entry anymore
+            unsafe {
+                account.owner = *info.owner();
+                account.data = info.borrow_data_unchecked().to_vec();
+            }
+        }
+    }
+}
+
+/// Run a program's `process_instruction` on the host, then copy back what it changed
+///
+/// With `host-stubs` the sysvar reads, CPIs, return data and events go through
+/// `stubs`. A stubbed system program CPI still moves lamports and creates,
+/// allocates and assigns accounts, and a token transfer moves token amounts.
+/// Programs added with `stubs::add_program` run when invoked; any other CPI
+/// changes nothing, so start from accounts already in the state it would leave:
+///
+/// ```ignore
+/// let mut accounts = [HostAccount::new(owner, SYSTEM, 1, &[]).signer(), counter];
+/// process(counter::process_instruction, &counter::ID, &mut accounts, &[1])?;
+/// ```
+///
+/// Wrapped in [`execute`] it counts towards [`coverage_report`] and yields a [`Trace`]:
+/// `execute(id, &keys, &data, || process(..).into())`.
+pub fn process(
+    process_instruction: fn(&Pubkey, &[AccountInfo], &[u8]) -> ProgramResult,
+    program_id: &Pubkey,
+    accounts: &mut [HostAccount],
+    data: &[u8],
+) -> ProgramResult {
+    let input = HostInput::new(accounts);
+    let result = process_instruction(program_id, input.accounts(), data);
+    input.write_back(accounts);
+    result
+}
+
+// A host run reports no logs and no inner instructions, only its result
+impl From<ProgramResult> for Execution {
+    fn from(result: ProgramResult) -> Self {
+        Self {
+            result: result.map_err(|error| format!("{error:?}")),
+            logs: Vec::new(),
+            inner_instructions: Vec::new(),
+        }
+    }
+}
+
+// Discriminators dispatched so far, one bit each, shared by every test thread
+static COVERED: [AtomicU64; 4] = [const { AtomicU64::new(0) }; 4];
+
+/// Note that an instruction with this discriminator was run
+///
+/// The generated `process_instruction` calls it under `host-stubs` and
+/// [`execute`] does for every instruction it runs, so only tests driving a
+/// backend some other way need it.
+pub fn record_coverage(discriminator: u8) {
+    let (word, bit) = (discriminator as usize / 64, discriminator % 64);
+    COVERED[word].fetch_or(1 << bit, Ordering::Relaxed);
+}
+
+/// Forget the recorded discriminators
+pub fn reset_coverage() {
+    for word in &COVERED {
+        word.store(0, Ordering::Relaxed);
+    }
+}
+
+/// Which of a program's instructions the tests have run so far
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CoverageReport {
+    pub tested: Vec<(u8, &'static str)>,
+    pub untested: Vec<(u8, &'static str)>,
+}
+
+impl CoverageReport {
+    pub fn is_complete(&self) -> bool {
+        self.untested.is_empty()
+    }
+
+    /// Panic with the report if an instruction was never run
+    pub fn assert_complete(&self) {
+        assert!(self.is_complete(), "{self}");
+    }
+}
+
+impl fmt::Display for CoverageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.tested.len() + self.untested.len();
+        writeln!(f, "{}/{total} instructions exercised", self.tested.len())?;
+        for (discriminator, name) in &self.untested {
+            writeln!(f, "  untested: {name} ({discriminator})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Split the program's generated `INSTRUCTIONS` into run and never run
+///
+/// Deprecated aliases count for nothing, only the canonical discriminators
+/// are listed. Tests in one binary share the record and run in no set order,
+/// so run the instructions and the report in one test, alone in its binary.
+///
+/// ```ignore
+/// reset_coverage();
+/// // ... run every instruction
+/// let report = jiminy::testing::coverage_report(&vote::INSTRUCTIONS);
+/// std::println!("{report}");
+/// report.assert_complete();
+/// ```
+pub fn coverage_report(instructions: &[(u8, &'static str)]) -> CoverageReport {
+    let (tested, untested) = instructions.iter().partition(|&&(discriminator, _)| {
+        let (word, bit) = (discriminator as usize / 64, discriminator % 64);
+        COVERED[word].load(Ordering::Relaxed) & (1 << bit) != 0
+    });
+    CoverageReport { tested, untested }
+}
+
+/// The `name` events in `logs`, decoded from their `Program data:` lines
+///
+/// ```ignore
+/// let snapshots = events::<CountSnapshot>(&execution.logs, "CountSnapshot");
+/// assert_eq!(snapshots[0].count, 3u64.to_le_bytes());
+/// ```
+pub fn events<T: bytemuck::Pod>(logs: &[String], name: &str) -> Vec<T> {
+    logs.iter()
+        .filter_map(|line| line.strip_prefix("Program data: "))
+        .filter_map(|data| data.split(' ').map(from_base64).collect::<Option<Vec<_>>>())
+        .filter_map(|fields| decode_event(&fields, name))
+        .collect()
+}
+
+// `fields` as logged by `event::emit`: the name, then the event's bytes
+fn decode_event<T: bytemuck::Pod>(fields: &[Vec<u8>], name: &str) -> Option<T> {
+    match fields {
+        [event_name, bytes] if event_name == name.as_bytes() => {
+            bytemuck::try_pod_read_unaligned(bytes).ok()
+        }
+        _ => None,
+    }
+}
+
+/// Compare `T`'s layout with `<dir>/<NAME>.v<version>.golden`, writing the file when it's missing
+///
+/// The file holds the size, each field's offset and size, and the bytes of a
+/// value whose fields are filled with their index plus one, after a round trip
+/// through `bytemuck`. Any difference panics; a layout changed on purpose gets a
+/// new version, and so a new file, instead. The generated `layout_tests` call it
+/// for every state struct when `[layout] golden` is set in jiminy.toml.
+pub fn check_layout<T: StateLayout + bytemuck::Pod>(dir: &str, version: u32) {
+    let mut bytes = vec![0u8; core::mem::size_of::<T>()];
+    for (index, field) in T::FIELDS.iter().enumerate() {
+        bytes[field.offset..field.offset + field.size].fill(index as u8 + 1);
+    }
+    let value: T = bytemuck::pod_read_unaligned(&bytes);
+    assert!(
+        bytemuck::bytes_of(&value) == bytes,
+        "{} bytes changed in a bytemuck round trip",
+        T::NAME
+    );
+
+    let layout = render_layout::<T>(version, &bytes);
+    let path = format!("{dir}/{}.v{version}.golden", T::NAME);
+    match std::fs::read_to_string(&path) {
+        Ok(golden) => assert!(
+            golden == layout,
+            "{} no longer matches {path}, bump its version in jiminy.toml's \
+             `[layout.versions]` if the change is intended:\n{layout}",
+            T::NAME
+        ),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+            std::fs::create_dir_all(dir).unwrap();
+            std::fs::write(&path, layout).unwrap();
+        }
+        Err(e) => panic!("cannot read {path}: {e}"),
+    }
+}
+
+// The golden file of `T`: name and version, size, fields, then the bytes in hex
+fn render_layout<T: StateLayout>(version: u32, bytes: &[u8]) -> String {
+    let mut layout = format!("{} v{version}\nsize {}\n", T::NAME, bytes.len());
+    for field in T::FIELDS {
+        layout += &format!(
+            "{}: {} at {}, {} bytes\n",
+            field.name, field.field_type, field.offset, field.size
+        );
+    }
+    for line in bytes.chunks(32) {
+        let hex: Vec<String> = line.iter().map(|byte| format!("{byte:02x}")).collect();
+        layout += &hex.concat();
+        layout.push('\n');
+    }
+    layout
+}
+
+// Build the tree under `root` from `Program <id> invoke [n]`, `success` and
+// `failed: <error>` lines, false when the logs have no invoke line
+fn tree_from_logs(root: &mut TraceNode, logs: &[String]) -> bool {
+    // Child indices from the root down to the call that is running
+    let mut path: Vec<usize> = Vec::new();
+    let mut seen_root = false;
+    for line in logs {
+        let Some((id, event)) = line
+            .strip_prefix("Program ")
+            .and_then(|rest| rest.split_once(' '))
+        else {
+            continue;
+        };
+        // Skips `Program log:`, `Program data:` and the like
+        let Some(program_id) = from_bs58(id) else {
+            continue;
+        };
+        if let Some(depth) = event
+            .strip_prefix("invoke [")
+            .and_then(|d| d.strip_suffix(']'))
+            .and_then(|d| d.parse::<usize>().ok())
+        {
+            if depth <= 1 {
+                seen_root = true;
+                path.clear();
+                continue;
+            }
+            path.truncate(depth - 2);
+            let parent = node_at(root, &path);
+            parent.children.push(TraceNode::new(program_id));
+            path.push(parent.children.len() - 1);
+        } else if event == "success" {
+            path.pop();
+        } else if let Some(error) = event.strip_prefix("failed: ") {
+            node_at(root, &path).error = Some(error.into());
+            path.pop();
+        }
+    }
+    // Calls still open when the logs end never got to return
+    for len in 1..=path.len() {
+        let node = node_at(root, &path[..len]);
+        node.error.get_or_insert_with(|| "did not finish".into());
+    }
+    seen_root
+}
+
+fn node_at<'a>(mut node: &'a mut TraceNode, path: &[usize]) -> &'a mut TraceNode {
+    for &i in path {
+        node = &mut node.children[i];
+    }
+    node
+}
+
+// Paths of every node below `node`, depth first
+fn cpi_paths(node: &TraceNode, path: &mut Vec<usize>, paths: &mut Vec<Vec<usize>>) {
+    for (i, child) in node.children.iter().enumerate() {
+        path.push(i);
+        paths.push(path.clone());
+        cpi_paths(child, path, paths);
+        path.pop();
+    }
+}
+
+// The latest call `depth` levels down, or the deepest one on the way
+fn last_at_depth(root: &mut TraceNode, depth: usize) -> &mut TraceNode {
+    let mut node = root;
+    for _ in 0..depth {
+        let Some(last) = node.children.len().checked_sub(1) else {
+            break;
+        };
+        node = &mut node.children[last];
+    }
+    node
+}
+
+// Small fields are shown as little-endian integers, keys in base58 and the rest in hex
+fn format_value(bytes: &[u8]) -> String {
+    match bytes.len() {
+        1..=8 => {
+            let mut le = [0u8; 8];
+            le[..bytes.len()].copy_from_slice(bytes);
+            format!("{}", u64::from_le_bytes(le))
+        }
+        32 => bs58(bytes),
+        _ => bytes.iter().map(|b| format!("{b:02x}")).collect(),
+    }
+}
+
+fn bs58(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+    let mut digits: Vec<u8> = Vec::new();
+    for &byte in bytes {
+        let mut carry = byte as u32;
+        for digit in digits.iter_mut() {
+            carry += (*digit as u32) << 8;
+            *digit = (carry % 58) as u8;
+            carry /= 58;
+        }
+        while carry > 0 {
+            digits.push((carry % 58) as u8);
+            carry /= 58;
+        }
+    }
+
+    let zeros = bytes.iter().take_while(|&&b| b == 0).count();
+    core::iter::repeat_n('1', zeros)
+        .chain(digits.iter().rev().map(|&d| ALPHABET[d as usize] as char))
+        .collect()
+}
+
+fn from_base64(text: &str) -> Option<Vec<u8>> {
+    let text = text.trim_end_matches('=');
+    let mut bytes = Vec::with_capacity(text.len() * 3 / 4);
+    let (mut buffer, mut bits) = (0u32, 0);
+    for c in text.bytes() {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            _ => return None,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push((buffer >> bits) as u8);
+        }
+    }
+    Some(bytes)
+}
+
+fn from_bs58(text: &str) -> Option<Pubkey> {
+    const ALPHABET: &[u8; 58] = b"123456789ABCDEFGHJKLMNPQRSTUVWXYZabcdefghijkmnopqrstuvwxyz";
+
+    // Little-endian base-256 digits
+    let mut bytes: Vec<u8> = Vec::new();
+    for c in text.bytes() {
+        let mut carry = ALPHABET.iter().position(|&a| a == c)? as u32;
+        for byte in bytes.iter_mut() {
+            carry += (*byte as u32) * 58;
+            *byte = carry as u8;
+            carry >>= 8;
+        }
+        while carry > 0 {
+            bytes.push(carry as u8);
+            carry >>= 8;
+        }
+    }
+    let zeros = text.bytes().take_while(|&c| c == b'1').count();
+    bytes.extend(core::iter::repeat_n(0, zeros));
+    bytes.reverse();
+    bytes.try_into().ok()
+}
+
+/// Stand-ins for the syscalls behind [`crate::syscalls`] (`host-stubs` feature)
+///
+/// State is per thread, so tests running in parallel don't see each other's
+/// clock or CPIs. Call [`reset`] at the start of a test that depends on them.
+///
+/// ```ignore
+/// stubs::reset();
+/// stubs::set_clock(Clock { slot: 100, ..stubs::clock() });
+/// process_instruction(&program_id, &accounts, &data)?;
+/// assert_eq!(stubs::cpi_calls(), ["system::create_account"]);
+/// ```
+#[cfg(feature = "host-stubs")]
+pub mod stubs {
+    use core::cell::RefCell;
+    use pinocchio::{
+        account_info::AccountInfo,
+        instruction::Instruction,
+        program_error::ProgramError,
+        pubkey::Pubkey,
+        sysvars::{
+            clock::Clock,
+            rent::{Rent, DEFAULT_EXEMPTION_THRESHOLD, DEFAULT_LAMPORTS_PER_BYTE_YEAR},
+        },
+        ProgramResult,
+    };
+    use super::std::{string::String, thread_local, vec::Vec};
+
+    struct Stubs {
+        clock: Clock,
+        rent: Rent,
+        clock_error: Option<ProgramError>,
+        rent_error: Option<ProgramError>,
+        cpi_error: Option<ProgramError>,
+        cpi_calls: Vec<&'static str>,
+        return_data: Vec<u8>,
+        logged_data: Vec<Vec<Vec<u8>>>,
+        deprecated_discriminators: Vec<(u8, u8)>,
+        logs: Vec<String>,
+        programs: Vec<(Pubkey, ProcessInstruction)>,
+    }
+
+    type ProcessInstruction = fn(&Pubkey, &[AccountInfo], &[u8]) -> ProgramResult;
+
+    impl Stubs {
+        const DEFAULT: Self = Self {
+            clock: Clock {
+                slot: 0,
+                epoch_start_timestamp: 0,
+                epoch: 0,
+                leader_schedule_epoch: 0,
+                unix_timestamp: 0,
+            },
+            rent: Rent {
+                lamports_per_byte_year: DEFAULT_LAMPORTS_PER_BYTE_YEAR,
+                exemption_threshold: DEFAULT_EXEMPTION_THRESHOLD,
+                // Mainnet's burn percent
+                burn_percent: 50,
+            },
+            clock_error: None,
+            rent_error: None,
+            cpi_error: None,
+            cpi_calls: Vec::new(),
+            return_data: Vec::new(),
+            logged_data: Vec::new(),
+            deprecated_discriminators: Vec::new(),
+            logs: Vec::new(),
+            programs: Vec::new(),
+        };
+    }
+
+    thread_local! {
+        static STUBS: RefCell<Stubs> = const { RefCell::new(Stubs::DEFAULT) };
+    }
+
+    /// Back to a zeroed clock, mainnet rent, working sysvars and CPIs, and nothing recorded
+    pub fn reset() {
+        STUBS.with_borrow_mut(|stubs| *stubs = Stubs::DEFAULT);
+    }
+
+    /// The clock `syscalls::clock` returns
+    pub fn clock() -> Clock {
+        STUBS.with_borrow(|stubs| stubs.clock)
+    }
+
+    pub fn set_clock(clock: Clock) {
+        STUBS.with_borrow_mut(|stubs| stubs.clock = clock);
+    }
+
+    /// The stubbed clock as a [`super::Harness`] backend
+    ///
+    /// ```ignore
+    /// let mut harness = Harness::new(StubClock);
+    /// harness.warp_to_timestamp(deadline);
+    /// process_instruction(&program_id, &accounts, &data)?;
+    /// ```
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct StubClock;
+
+    impl super::ClockSysvar for StubClock {
+        fn set_clock(&mut self, clock: &Clock) {
+            set_clock(*clock);
+        }
+    }
+
+    /// The rent `syscalls::rent` returns
+    pub fn rent() -> Rent {
+        STUBS.with_borrow(|stubs| stubs.rent)
+    }
+
+    pub fn set_rent(rent: Rent) {
+        STUBS.with_borrow_mut(|stubs| stubs.rent = rent);
+    }
+
+    /// Make every following read of the clock fail with `error`, `None` to read it again
+    ///
+    /// `syscalls` turns the failure into `JiminyError::SysvarUnavailable` like
+    /// it does on chain.
+    pub fn fail_clock(error: Option<ProgramError>) {
+        STUBS.with_borrow_mut(|stubs| stubs.clock_error = error);
+    }
+
+    /// Make every following read of the rent fail with `error`, see [`fail_clock`]
+    pub fn fail_rent(error: Option<ProgramError>) {
+        STUBS.with_borrow_mut(|stubs| stubs.rent_error = error);
+    }
+
+    /// Make every following CPI fail with `error`, `None` to let them succeed again
+    pub fn fail_cpis(error: Option<ProgramError>) {
+        STUBS.with_borrow_mut(|stubs| stubs.cpi_error = error);
+    }
+
+    /// Run `process_instruction` for the CPIs to `program_id` made with `syscalls::invoke`
+    ///
+    /// E.g. a test adapter that calls back into the program under test. It gets
+    /// the caller's accounts in the order of the instruction's metas.
+    pub fn add_program(program_id: Pubkey, process_instruction: ProcessInstruction) {
+        STUBS.with_borrow_mut(|stubs| stubs.programs.push((program_id, process_instruction)));
+    }
+
+    /// Names of the CPIs made since the last [`reset`], in order
+    pub fn cpi_calls() -> Vec<&'static str> {
+        STUBS.with_borrow(|stubs| stubs.cpi_calls.clone())
+    }
+
+    /// The last return data set since the last [`reset`]
+    pub fn return_data() -> Vec<u8> {
+        STUBS.with_borrow(|stubs| stubs.return_data.clone())
+    }
+
+    /// The fields of every `sol_log_data` call since the last [`reset`], in order
+    pub fn logged_data() -> Vec<Vec<Vec<u8>>> {
+        STUBS.with_borrow(|stubs| stubs.logged_data.clone())
+    }
+
+    /// `(alias, canonical)` of each deprecated alias dispatched since the last
+    /// [`reset`], what the `deprecation-logs` feature logs as
+    /// `Discriminator 1 is deprecated, send 32`
+    pub fn deprecated_discriminators() -> Vec<(u8, u8)> {
+        STUBS.with_borrow(|stubs| stubs.deprecated_discriminators.clone())
+    }
+
+    /// What jiminy's error helpers logged since the last [`reset`], in order, e.g.
+    /// `The receiver account is not writable`; the program's own `log!` calls
+    /// aren't captured
+    pub fn logs() -> Vec<String> {
+        STUBS.with_borrow(|stubs| stubs.logs.clone())
+    }
+
+    /// The `name` events logged since the last [`reset`], see [`super::events`]
+    pub fn events<T: bytemuck::Pod>(name: &str) -> Vec<T> {
+        STUBS.with_borrow(|stubs| {
+            stubs
+                .logged_data
+                .iter()
+                .filter_map(|fields| super::decode_event(fields, name))
+                .collect()
+        })
+    }
+
+    pub(crate) fn read_clock() -> Result<Clock, ProgramError> {
+        STUBS.with_borrow(|stubs| match &stubs.clock_error {
+            Some(error) => Err(error.clone()),
+            None => Ok(stubs.clock),
+        })
+    }
+
+    pub(crate) fn read_rent() -> Result<Rent, ProgramError> {
+        STUBS.with_borrow(|stubs| match &stubs.rent_error {
+            Some(error) => Err(error.clone()),
+            None => Ok(stubs.rent),
+        })
+    }
+
+    pub(crate) fn record_log(message: core::fmt::Arguments) {
+        STUBS.with_borrow_mut(|stubs| stubs.logs.push(super::std::fmt::format(message)));
+    }
+
+    pub(crate) fn record_deprecated_discriminator(alias: u8, canonical: u8) {
+        STUBS.with_borrow_mut(|stubs| stubs.deprecated_discriminators.push((alias, canonical)));
+    }
+
+    pub(crate) fn record_cpi(name: &'static str) -> ProgramResult {
+        STUBS.with_borrow_mut(|stubs| {
+            stubs.cpi_calls.push(name);
+            match &stubs.cpi_error {
+                Some(error) => Err(error.clone()),
+                None => Ok(()),
+            }
+        })
+    }
+
+    pub(crate) fn invoke(instruction: &Instruction, accounts: &[&AccountInfo]) -> ProgramResult {
+        let program = STUBS.with_borrow(|stubs| {
+            stubs
+                .programs
+                .iter()
+                .find(|(id, _)| id == instruction.program_id)
+                .map(|&(_, process_instruction)| process_instruction)
+        });
+        let Some(process_instruction) = program else {
+            return Ok(());
+        };
+        // Passed like the runtime does, one account per meta
+        let infos = instruction
+            .accounts
+            .iter()
+            .map(|meta| {
+                accounts
+                    .iter()
+                    .find(|account| account.key() == meta.pubkey)
+                    .map(|&account| account.clone())
+                    .ok_or(ProgramError::NotEnoughAccountKeys)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        process_instruction(instruction.program_id, &infos, instruction.data)
+    }
+
+    /// The bump the stubbed PDA search settles on
+    pub const FOUND_BUMP: u8 = 255;
+
+    /// What `syscalls::find_program_address` returns: the address with [`FOUND_BUMP`]
+    ///
+    /// The host doesn't check whether an address is on the curve, so the search
+    /// stops at the first bump it tries. Build the keys of PDAs a handler searches
+    /// for with this, the ones with a stored bump with `derive_address`.
+    pub fn find_program_address<const N: usize>(
+        seeds: &[&[u8]; N],
+        program_id: &Pubkey,
+    ) -> (Pubkey, u8) {
+        let address = pinocchio_pubkey::derive_address(seeds, Some(FOUND_BUMP), program_id);
+        (address, FOUND_BUMP)
+    }
+
+    pub(crate) fn record_return_data(data: &[u8]) {
+        STUBS.with_borrow_mut(|stubs| stubs.return_data = data.to_vec());
+    }
+
+    pub(crate) fn record_log_data(fields: &[&[u8]]) {
+        let fields = fields.iter().map(|field| field.to_vec()).collect();
+        STUBS.with_borrow_mut(|stubs| stubs.logged_data.push(fields));
+    }
+}
@@ -0,0 +1,167 @@
+//! `load!`/`load_mut!` on the unchecked borrows and, with `--features safe-borrows`,
+//! on the checked ones, and what a reference they return does across a resize
+
+use bytemuck::{Pod, Zeroable};
+use jiminy::testing::{HostAccount, HostInput};
+use jiminy::{load, load_mut, read_state};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+const PROGRAM: [u8; 32] = [7; 32];
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct Tally {
+    count: [u8; 8],
+}
+
+fn tally(key: u8, count: u64) -> HostAccount {
+    HostAccount::new([key; 32], PROGRAM, 1, &count.to_le_bytes()).writable()
+}
+
+// Whether a mutable borrow of the data would succeed right now
+fn unborrowed(account: &AccountInfo) -> bool {
+    account.try_borrow_mut_data().is_ok()
+}
+
+fn bump(account: &AccountInfo) -> ProgramResult {
+    let tally = load_mut!(account, Tally);
+    tally.count = (u64::from_le_bytes(tally.count) + 1).to_le_bytes();
+    Ok(())
+}
+
+fn read(account: &AccountInfo) -> Result<u64, ProgramError> {
+    Ok(u64::from_le_bytes(load!(account, Tally).count))
+}
+
+// Moves the count of `from` onto `to`, holding both states at once
+fn merge(from: &AccountInfo, to: &AccountInfo) -> ProgramResult {
+    let from = load_mut!(from, Tally);
+    let to = load_mut!(to, Tally);
+    let total = u64::from_le_bytes(from.count) + u64::from_le_bytes(to.count);
+    to.count = total.to_le_bytes();
+    from.count = [0; 8];
+    Ok(())
+}
+
+// Shrinks `account` to nothing while a `load!` reference to its state is alive
+fn count_across_resize(account: &AccountInfo) -> Result<u64, ProgramError> {
+    let tally = load!(account, Tally);
+    account.resize(0)?;
+    Ok(u64::from_le_bytes(tally.count))
+}
+
+// The same with the count copied out first
+fn count_then_resize(account: &AccountInfo) -> Result<u64, ProgramError> {
+    let count = read_state!(account, Tally, |tally| u64::from_le_bytes(tally.count));
+    account.resize(0)?;
+    Ok(count)
+}
+
+#[test]
+fn load_mut_writes_to_the_account() {
+    let mut accounts = [tally(1, 41)];
+    let input = HostInput::new(&accounts);
+    bump(&input.accounts()[0]).unwrap();
+    assert_eq!(read(&input.accounts()[0]), Ok(42));
+    input.write_back(&mut accounts);
+    assert_eq!(accounts[0].data, 42u64.to_le_bytes());
+}
+
+#[test]
+fn loads_release_their_borrows() {
+    let input = HostInput::new(&[tally(1, 0)]);
+    let account = &input.accounts()[0];
+    for _ in 0..3 {
+        bump(account).unwrap();
+        read(account).unwrap();
+    }
+    assert!(unborrowed(account));
+    assert_eq!(read(account), Ok(3));
+}
+
+#[test]
+fn two_accounts_load_mutably_together() {
+    let input = HostInput::new(&[tally(1, 2), tally(2, 3)]);
+    let [from, to] = input.accounts() else { unreachable!() };
+    merge(from, to).unwrap();
+    assert_eq!((read(from), read(to)), (Ok(0), Ok(5)));
+}
+
+#[test]
+fn wrong_size_fails_before_borrowing() {
+    let input = HostInput::new(&[HostAccount::new([1; 32], PROGRAM, 1, &[0; 7])]);
+    let account = &input.accounts()[0];
+    assert!(read(account).is_err());
+    assert!(unborrowed(account));
+}
+
+#[cfg(feature = "safe-borrows")]
+#[test]
+fn same_account_twice_fails_to_borrow() {
+    // The loader passes a repeated account as the same `AccountInfo`
+    let input = HostInput::new(&[tally(1, 2), tally(1, 2)]);
+    let [from, to] = input.accounts() else { unreachable!() };
+    assert_eq!(merge(from, to), Err(ProgramError::AccountBorrowFailed));
+    // The first guard was released on the way out, the data is untouched
+    assert!(unborrowed(from));
+    assert_eq!(read(to), Ok(2));
+}
+
+#[cfg(feature = "safe-borrows")]
+#[test]
+fn load_while_mutably_loaded_fails() {
+    let input = HostInput::new(&[tally(1, 0)]);
+    let account = &input.accounts()[0];
+    let held = jiminy::perf::load_checked::<Tally>(account).unwrap();
+    assert_eq!(read(account), Err(ProgramError::AccountBorrowFailed));
+    assert_eq!(bump(account), Err(ProgramError::AccountBorrowFailed));
+    drop(held);
+    assert_eq!(bump(account), Ok(()));
+}
+
+#[cfg(not(feature = "safe-borrows"))]
+#[test]
+fn unchecked_loads_leave_the_borrow_state_alone() {
+    let input = HostInput::new(&[tally(1, 0)]);
+    let account = &input.accounts()[0];
+    // Nothing records the unchecked borrow, a checked one still succeeds
+    let tally = unsafe { jiminy::perf::load_unchecked::<Tally>(account) }.unwrap();
+    tally.count = 9u64.to_le_bytes();
+    assert!(unborrowed(account));
+    assert_eq!(account.try_borrow_data().unwrap()[..], 9u64.to_le_bytes());
+}
+
+#[cfg(not(feature = "safe-borrows"))]
+#[test]
+fn unchecked_load_outlives_a_resize() {
+    let input = HostInput::new(&[tally(1, 5)]);
+    let account = &input.accounts()[0];
+    // Nothing stops the resize, and the reference then reads bytes past the
+    // end of the data
+    assert_eq!(count_across_resize(account), Ok(5));
+    assert_eq!(account.data_len(), 0);
+}
+
+#[cfg(feature = "safe-borrows")]
+#[test]
+fn resize_under_a_checked_load_fails() {
+    let input = HostInput::new(&[tally(1, 5)]);
+    let account = &input.accounts()[0];
+    assert_eq!(
+        count_across_resize(account),
+        Err(ProgramError::AccountBorrowFailed)
+    );
+    assert_eq!(account.data_len(), 8);
+    assert_eq!(read(account), Ok(5));
+}
+
+#[test]
+fn read_state_copies_out_before_the_resize() {
+    let input = HostInput::new(&[tally(1, 5)]);
+    let account = &input.accounts()[0];
+    // The same on either borrow, see `tests/ui/read_state_returns_a_reference.rs`
+    // for the reference it won't hand out
+    assert_eq!(count_then_resize(account), Ok(5));
+    assert_eq!(account.data_len(), 0);
+    assert!(unborrowed(account));
+}
@@ -0,0 +1,9 @@
+//! Seed mistakes the declarative macros catch at compile time, and references
+//! `read_state!` won't hand out, with the expected compiler output in `tests/ui`
+//!
+//! `TRYBUILD=overwrite cargo test -p jiminy --test compile_fail` rewrites the `.stderr` files.
+
+#[test]
+fn compile_fail() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,60 @@
+//! `dispatch::check_deprecated` on either side of the cutoff, and where it warns
+
+use jiminy::dispatch::{check_deprecated, deprecation_warning, DEPRECATION_WARNING_SLOTS};
+use jiminy::error::JiminyError;
+use jiminy::testing::stubs;
+use pinocchio::{program_error::ProgramError, sysvars::clock::Clock};
+
+const CUTOFF: u64 = 350_000_000;
+
+fn at_slot(slot: u64) -> Result<(), ProgramError> {
+    stubs::reset();
+    stubs::set_clock(Clock { slot, ..stubs::clock() });
+    check_deprecated("InitializePosition", CUTOFF)
+}
+
+#[test]
+fn runs_up_to_and_including_the_cutoff() {
+    assert_eq!(at_slot(0), Ok(()));
+    assert_eq!(at_slot(CUTOFF - 1), Ok(()));
+    assert_eq!(at_slot(CUTOFF), Ok(()));
+}
+
+#[test]
+fn fails_after_the_cutoff() {
+    let deprecated = Err(JiminyError::InstructionDeprecated.into());
+    assert_eq!(at_slot(CUTOFF + 1), deprecated);
+    assert_eq!(at_slot(u64::MAX), deprecated);
+}
+
+#[test]
+fn fails_when_the_clock_is_unavailable() {
+    stubs::reset();
+    stubs::fail_clock(Some(ProgramError::UnsupportedSysvar));
+    assert_eq!(
+        check_deprecated("InitializePosition", CUTOFF),
+        Err(JiminyError::SysvarUnavailable.into())
+    );
+}
+
+#[test]
+fn warns_in_the_last_epoch() {
+    assert!(deprecation_warning(CUTOFF, CUTOFF));
+    assert!(deprecation_warning(CUTOFF - DEPRECATION_WARNING_SLOTS + 1, CUTOFF));
+    assert!(deprecation_warning(0, DEPRECATION_WARNING_SLOTS - 1));
+    assert!(!deprecation_warning(CUTOFF + 1, CUTOFF));
+}
+
+#[cfg(not(feature = "deprecation-logs"))]
+#[test]
+fn quiet_before_the_last_epoch() {
+    assert!(!deprecation_warning(CUTOFF - DEPRECATION_WARNING_SLOTS, CUTOFF));
+    assert!(!deprecation_warning(0, CUTOFF));
+}
+
+#[cfg(feature = "deprecation-logs")]
+#[test]
+fn warns_on_every_call_with_deprecation_logs() {
+    assert!(deprecation_warning(CUTOFF - DEPRECATION_WARNING_SLOTS, CUTOFF));
+    assert!(deprecation_warning(0, CUTOFF));
+}
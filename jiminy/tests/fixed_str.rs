@@ -0,0 +1,58 @@
+//! `FixedStr<N>` holds up to `N` bytes and reads back up to the first zero byte
+
+use jiminy::FixedStr;
+use pinocchio::program_error::ProgramError;
+
+#[test]
+fn text_of_exactly_the_capacity_fits() {
+    let label = FixedStr::<4>::from_bytes(b"abcd").unwrap();
+    assert_eq!(label.as_bytes(), b"abcd");
+    assert_eq!(label.as_str_lossy(), "abcd");
+    assert_eq!(bytemuck::bytes_of(&label), b"abcd");
+}
+
+#[test]
+fn text_over_the_capacity_is_rejected() {
+    assert_eq!(
+        FixedStr::<4>::from_bytes(b"abcde").err(),
+        Some(ProgramError::InvalidArgument)
+    );
+
+    // A failed set leaves the old text
+    let mut label = FixedStr::<4>::from_bytes(b"ab").unwrap();
+    assert_eq!(label.set(b"abcde"), Err(ProgramError::InvalidArgument));
+    assert_eq!(label.as_bytes(), b"ab");
+}
+
+#[test]
+fn shorter_text_zeroes_the_old_tail() {
+    let mut label = FixedStr::<8>::from_bytes(b"abcdefgh").unwrap();
+    label.set(b"xy").unwrap();
+    assert_eq!(bytemuck::bytes_of(&label), b"xy\0\0\0\0\0\0");
+    label.set(b"").unwrap();
+    assert!(label.is_empty());
+}
+
+#[test]
+fn embedded_nul_ends_the_text() {
+    let label = FixedStr::<8>::from_bytes(b"ab\0cd").unwrap();
+    assert_eq!(label.as_bytes(), b"ab");
+    assert_eq!(label.as_str_lossy(), "ab");
+    // The bytes after it are still stored
+    assert_eq!(&bytemuck::bytes_of(&label)[3..5], b"cd");
+
+    assert!(FixedStr::<8>::from_bytes(b"\0ab").unwrap().is_empty());
+}
+
+#[test]
+fn invalid_utf8_cuts_the_lossy_text() {
+    let label = FixedStr::<8>::from_bytes(b"hi\xffthere").unwrap();
+    assert_eq!(label.as_bytes(), b"hi\xffthere");
+    assert_eq!(label.as_str_lossy(), "hi");
+
+    // A multi-byte character cut short by the capacity
+    let label = FixedStr::<4>::from_bytes("ab\u{e9}".as_bytes()).unwrap();
+    assert_eq!(label.as_str_lossy(), "ab\u{e9}");
+    let truncated: FixedStr<3> = bytemuck::cast(*b"ab\xc3");
+    assert_eq!(truncated.as_str_lossy(), "ab");
+}
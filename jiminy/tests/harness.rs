@@ -0,0 +1,94 @@
+//! `testing::Harness` moves the clock a backend hands to the program
+
+use jiminy::testing::{process, stubs, ClockSysvar, Harness, MS_PER_SLOT};
+use jiminy::{syscalls, Deadline};
+use pinocchio::{
+    account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey, sysvars::clock::Clock,
+    ProgramResult,
+};
+
+const PROGRAM: Pubkey = [7; 32];
+const DEADLINE: i64 = 1_000;
+
+// Every clock the harness wrote
+#[derive(Default)]
+struct Recorder {
+    clocks: Vec<Clock>,
+}
+
+impl ClockSysvar for Recorder {
+    fn set_clock(&mut self, clock: &Clock) {
+        self.clocks.push(*clock);
+    }
+}
+
+// Fails once `DEADLINE` has passed, like an instruction on an ended vote
+fn before_deadline(_: &Pubkey, _: &[AccountInfo], _: &[u8]) -> ProgramResult {
+    if Deadline::unix(DEADLINE).is_expired(&syscalls::clock()?) {
+        return Err(ProgramError::Custom(1));
+    }
+    Ok(())
+}
+
+fn run(harness: &mut Harness<stubs::StubClock>) -> Result<(), String> {
+    let trace = harness.execute(PROGRAM, &[], &[0], |_| {
+        process(before_deadline, &PROGRAM, &mut [], &[0]).into()
+    });
+    trace.root.error.map_or(Ok(()), Err)
+}
+
+#[test]
+fn every_change_is_written_to_the_backend() {
+    let mut harness = Harness::new(Recorder::default());
+    harness.warp_to_timestamp(50);
+    harness.warp_to_slot(9);
+    harness.advance(2);
+
+    let clocks = &harness.backend().clocks;
+    assert_eq!(clocks.len(), 4);
+    assert_eq!((clocks[0].slot, clocks[0].unix_timestamp), (0, 0));
+    assert_eq!((clocks[1].slot, clocks[1].unix_timestamp), (0, 50));
+    assert_eq!((clocks[2].slot, clocks[2].unix_timestamp), (9, 50));
+    let slots = 2 * 1000 / MS_PER_SLOT;
+    assert_eq!((clocks[3].slot, clocks[3].unix_timestamp), (9 + slots, 52));
+    assert_eq!(harness.clock().slot, clocks[3].slot);
+}
+
+#[test]
+fn each_instruction_moves_the_slot_unless_frozen() {
+    stubs::reset();
+    let mut harness = Harness::new(stubs::StubClock);
+    harness.warp_to_slot(10);
+    run(&mut harness).unwrap();
+    run(&mut harness).unwrap();
+    assert_eq!(harness.clock().slot, 12);
+    assert_eq!(stubs::clock().slot, 12);
+
+    harness.freeze_clock();
+    assert!(harness.is_frozen());
+    run(&mut harness).unwrap();
+    assert_eq!(stubs::clock().slot, 12);
+    // Warps still apply to a frozen clock
+    harness.warp_to_slot(20);
+    assert_eq!(stubs::clock().slot, 20);
+
+    harness.unfreeze_clock();
+    run(&mut harness).unwrap();
+    assert_eq!(stubs::clock().slot, 21);
+}
+
+#[test]
+fn deadline_holds_until_the_second_after_it() {
+    stubs::reset();
+    let mut harness = Harness::with_clock(stubs::StubClock, stubs::clock());
+    harness.warp_to_timestamp(DEADLINE - 1);
+    harness.freeze_clock();
+    run(&mut harness).unwrap();
+
+    // Exactly at the deadline the instruction still runs
+    harness.advance(1);
+    run(&mut harness).unwrap();
+
+    harness.advance(1);
+    assert_eq!(run(&mut harness), Err(format!("{:?}", ProgramError::Custom(1))));
+}
@@ -0,0 +1,129 @@
+//! With `runtime-invariants`, an instruction whose transfers don't move what its
+//! `invariants:` section declares fails with `InvariantViolated`
+
+use jiminy::error::JiminyError;
+use jiminy::testing::{stubs, HostAccount, HostInput};
+use pinocchio::ProgramResult;
+
+// Takes a 1% fee, rounded down
+mod pay_fee {
+    use jiminy::define_instruction_with_metadata;
+
+    define_instruction_with_metadata!(
+        discriminant: 0,
+        PayFee,
+        accounts: {
+            payer: signer, desc: "Owner of the paying token account",
+            payer_tokens: token => writable, desc: "Token account paying the fee",
+            fee_vault: token => writable, desc: "Token account receiving the fee",
+        },
+        data: {
+            amount: [u8; 8],
+        },
+        prepare: {
+            let fee = u64::from_le_bytes(amount) / 100;
+        },
+        transfers: {
+            payer_tokens => fee_vault: fee, authority: payer;
+        },
+        invariants: {
+            payer_tokens.tokens: decreases_by(fee),
+            fee_vault.tokens: increases_by(fee),
+        },
+        process: {
+            Ok(())
+        }
+    );
+}
+
+// Declares the same fee but transfers it rounded up
+mod pay_broken_fee {
+    use jiminy::define_instruction_with_metadata;
+
+    define_instruction_with_metadata!(
+        discriminant: 0,
+        PayBrokenFee,
+        accounts: {
+            payer: signer, desc: "Owner of the paying token account",
+            payer_tokens: token => writable, desc: "Token account paying the fee",
+            fee_vault: token => writable, desc: "Token account receiving the fee",
+        },
+        data: {
+            amount: [u8; 8],
+        },
+        prepare: {
+            let fee = u64::from_le_bytes(amount) / 100;
+            let charged = u64::from_le_bytes(amount).div_ceil(100);
+        },
+        transfers: {
+            payer_tokens => fee_vault: charged, authority: payer;
+        },
+        invariants: {
+            payer_tokens.tokens: decreases_by(fee),
+            fee_vault.tokens: increases_by(fee),
+        },
+        process: {
+            Ok(())
+        }
+    );
+}
+
+// The payer, its token account holding 1_000 and an empty fee vault
+fn fee_accounts() -> [HostAccount; 3] {
+    [
+        HostAccount::new([1; 32], pinocchio_system::ID, 1, &[]).signer(),
+        token_account(2, 1_000),
+        token_account(3, 0),
+    ]
+}
+
+fn token_account(key: u8, amount: u64) -> HostAccount {
+    let mut data = [0; 165];
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    HostAccount::new([key; 32], pinocchio_token::ID, 1, &data).writable()
+}
+
+fn amount(account: &HostAccount) -> u64 {
+    u64::from_le_bytes(account.data[64..72].try_into().unwrap())
+}
+
+#[test]
+fn fee_moved_as_declared_passes() {
+    stubs::reset();
+    let mut accounts = fee_accounts();
+    let input = HostInput::new(&accounts);
+    let data = 150u64.to_le_bytes();
+    pay_fee::PayFeeInstruction::try_from((input.accounts(), &data[..]))
+        .and_then(|instruction| instruction.process())
+        .unwrap();
+
+    input.write_back(&mut accounts);
+    assert_eq!((amount(&accounts[1]), amount(&accounts[2])), (999, 1));
+}
+
+#[test]
+fn fee_moved_otherwise_fails_with_invariant_violated() {
+    stubs::reset();
+    let mut accounts = fee_accounts();
+    let input = HostInput::new(&accounts);
+    let data = 150u64.to_le_bytes();
+    let result: ProgramResult =
+        pay_broken_fee::PayBrokenFeeInstruction::try_from((input.accounts(), &data[..]))
+            .and_then(|instruction| instruction.process());
+    assert_eq!(result, Err(JiminyError::InvariantViolated.into()));
+
+    // The transfer ran, the check after it caught the extra token
+    input.write_back(&mut accounts);
+    assert_eq!((amount(&accounts[1]), amount(&accounts[2])), (998, 2));
+
+    // A fee that rounds the same either way passes
+    stubs::reset();
+    let mut accounts = fee_accounts();
+    let input = HostInput::new(&accounts);
+    let data = 200u64.to_le_bytes();
+    pay_broken_fee::PayBrokenFeeInstruction::try_from((input.accounts(), &data[..]))
+        .and_then(|instruction| instruction.process())
+        .unwrap();
+    input.write_back(&mut accounts);
+    assert_eq!(amount(&accounts[2]), 2);
+}
@@ -0,0 +1,104 @@
+//! `perf::keys_eq` agrees with `==`; the dev-dependency enables `fast-id-check`,
+//! so this covers the u64 path
+
+use jiminy::perf::keys_eq;
+use pinocchio::pubkey::Pubkey;
+
+/// xorshift64, seeded so a failure reproduces
+struct Keys(u64);
+
+impl Keys {
+    fn next_u64(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+
+    fn key(&mut self) -> Pubkey {
+        let mut key = [0; 32];
+        for lane in key.chunks_exact_mut(8) {
+            lane.copy_from_slice(&self.next_u64().to_le_bytes());
+        }
+        key
+    }
+}
+
+fn check(a: &Pubkey, b: &Pubkey) {
+    assert_eq!(keys_eq(a, b), a == b, "{a:?} vs {b:?}");
+    assert_eq!(keys_eq(b, a), a == b, "{b:?} vs {a:?}");
+}
+
+#[test]
+fn random_keys() {
+    let mut keys = Keys(0x9e37_79b9_7f4a_7c15);
+    for _ in 0..10_000 {
+        let (a, b) = (keys.key(), keys.key());
+        check(&a, &b);
+        let copy = a;
+        check(&a, &copy);
+        assert!(keys_eq(&a, &copy));
+    }
+}
+
+#[test]
+fn equal_keys() {
+    check(&[0; 32], &[0; 32]);
+    check(&[u8::MAX; 32], &[u8::MAX; 32]);
+    assert!(keys_eq(&[0; 32], &[0; 32]));
+    assert!(keys_eq(&[u8::MAX; 32], &[u8::MAX; 32]));
+}
+
+#[test]
+fn one_byte_apart_at_every_position() {
+    let mut keys = Keys(0x2545_f491_4f6c_dd1d);
+    for _ in 0..256 {
+        let a = keys.key();
+        // Every byte of every 8-byte lane, with a random nonzero difference
+        for i in 0..32 {
+            let mut b = a;
+            b[i] ^= (keys.next_u64() as u8).max(1);
+            check(&a, &b);
+            assert!(!keys_eq(&a, &b), "byte {i} differs");
+        }
+    }
+}
+
+#[test]
+fn one_lane_apart() {
+    let mut keys = Keys(0xd1b5_4a32_d192_ed03);
+    for _ in 0..256 {
+        let a = keys.key();
+        let other = keys.key();
+        // The other key's lane swapped in, with the rest equal
+        for lane in 0..4 {
+            let mut b = a;
+            b[lane * 8..lane * 8 + 8].copy_from_slice(&other[lane * 8..lane * 8 + 8]);
+            check(&a, &b);
+        }
+    }
+}
+
+#[test]
+fn unaligned_keys() {
+    // Keys inside account data or instruction data sit at any offset
+    let mut keys = Keys(0x6a09_e667_f3bc_c908);
+    let mut buf = [0u8; 76];
+    for _ in 0..256 {
+        let key = keys.key();
+        let (at_a, at_b) = (keys.next_u64() as usize % 8, 40 + keys.next_u64() as usize % 4);
+        buf[at_a..at_a + 32].copy_from_slice(&key);
+        buf[at_b..at_b + 32].copy_from_slice(&key);
+        let a: &Pubkey = buf[at_a..at_a + 32].try_into().unwrap();
+        let b: &Pubkey = buf[at_b..at_b + 32].try_into().unwrap();
+        check(a, b);
+        assert!(keys_eq(a, b));
+
+        let flipped = at_b + keys.next_u64() as usize % 32;
+        buf[flipped] ^= 0x80;
+        let a: &Pubkey = buf[at_a..at_a + 32].try_into().unwrap();
+        let b: &Pubkey = buf[at_b..at_b + 32].try_into().unwrap();
+        check(a, b);
+        assert!(!keys_eq(a, b));
+    }
+}
@@ -0,0 +1,74 @@
+//! `lamports` and `close_account!` at the edges of `u64`, where a wrap would mint or
+//! burn lamports
+
+use jiminy::close_account;
+use jiminy::error::JiminyError;
+use jiminy::layout::CLOSED_MARKER;
+use jiminy::lamports::{credit, debit, transfer};
+use jiminy::testing::{HostAccount, HostInput};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+const PROGRAM: [u8; 32] = [7; 32];
+
+fn account(key: u8, lamports: u64) -> HostAccount {
+    HostAccount::new([key; 32], PROGRAM, lamports, &[1; 16]).writable()
+}
+
+fn close(accounts: &[AccountInfo]) -> ProgramResult {
+    close_account!(&accounts[0], &accounts[1]);
+    Ok(())
+}
+
+#[test]
+fn credit_up_to_the_max() {
+    let input = HostInput::new(&[account(1, u64::MAX - 1)]);
+    let account = &input.accounts()[0];
+    credit(account, 1).unwrap();
+    assert_eq!(account.lamports(), u64::MAX);
+    assert_eq!(credit(account, 1), Err(JiminyError::LamportOverflow.into()));
+    assert_eq!(account.lamports(), u64::MAX);
+}
+
+#[test]
+fn debit_down_from_the_max() {
+    let input = HostInput::new(&[account(1, u64::MAX)]);
+    let account = &input.accounts()[0];
+    debit(account, u64::MAX).unwrap();
+    assert_eq!(account.lamports(), 0);
+    assert_eq!(debit(account, 1), Err(JiminyError::InsufficientLamports.into()));
+    assert_eq!(account.lamports(), 0);
+}
+
+#[test]
+fn transfer_that_would_overflow_moves_nothing() {
+    let input = HostInput::new(&[account(1, 10), account(2, u64::MAX - 5)]);
+    let [from, to] = input.accounts() else { unreachable!() };
+    assert_eq!(transfer(from, to, 10), Err(JiminyError::LamportOverflow.into()));
+    assert_eq!((from.lamports(), to.lamports()), (10, u64::MAX - 5));
+
+    transfer(from, to, 5).unwrap();
+    assert_eq!((from.lamports(), to.lamports()), (5, u64::MAX));
+}
+
+#[test]
+fn close_into_a_near_max_account_fails_untouched() {
+    let mut accounts = [account(1, 10), account(2, u64::MAX - 9)];
+    let before = accounts.clone();
+    let input = HostInput::new(&accounts);
+    assert_eq!(
+        close(input.accounts()),
+        Err(ProgramError::from(JiminyError::LamportOverflow))
+    );
+    input.write_back(&mut accounts);
+    assert_eq!(accounts, before);
+}
+
+#[test]
+fn close_into_exactly_the_max() {
+    let mut accounts = [account(1, 10), account(2, u64::MAX - 10)];
+    let input = HostInput::new(&accounts);
+    close(input.accounts()).unwrap();
+    input.write_back(&mut accounts);
+    assert_eq!((accounts[0].lamports, accounts[1].lamports), (0, u64::MAX));
+    assert_eq!(accounts[0].data, CLOSED_MARKER);
+}
@@ -0,0 +1,57 @@
+//! `PodBool` and `PodOption` read only the tags 0 and 1, anything else is corrupt
+
+use jiminy::error::JiminyError;
+use jiminy::{LeU64, PodBool, PodOption};
+use pinocchio::program_error::ProgramError;
+
+fn invalid_tag() -> ProgramError {
+    JiminyError::InvalidPodTag.into()
+}
+
+#[test]
+fn bool_reads_zero_and_one() {
+    assert_eq!(bytemuck::cast::<u8, PodBool>(0).get(), Ok(false));
+    assert_eq!(bytemuck::cast::<u8, PodBool>(1).get(), Ok(true));
+    assert_eq!(bytemuck::bytes_of(&PodBool::new(true)), [1]);
+}
+
+#[test]
+fn bool_rejects_other_bytes() {
+    for byte in [2u8, 0xff] {
+        let value: PodBool = bytemuck::cast(byte);
+        assert_eq!(value.get(), Err(invalid_tag()), "byte {byte}");
+    }
+}
+
+#[test]
+fn option_reads_its_tag() {
+    let some = PodOption::some(LeU64::new(7));
+    assert_eq!(some.is_some(), Ok(true));
+    assert_eq!(some.get(), Ok(Some(&LeU64::new(7))));
+    assert_eq!(bytemuck::bytes_of(&some), [1, 7, 0, 0, 0, 0, 0, 0, 0]);
+
+    let none = PodOption::<LeU64>::none();
+    assert_eq!(none.is_some(), Ok(false));
+    assert_eq!(none.get(), Ok(None));
+}
+
+#[test]
+fn option_rejects_other_tags() {
+    for tag in [2u8, 0xff] {
+        // A tag that isn't 0 or 1 fails with or without a value behind it
+        for value in [[0; 8], 7u64.to_le_bytes()] {
+            let mut bytes = [tag; 9];
+            bytes[1..].copy_from_slice(&value);
+            let option: PodOption<LeU64> = bytemuck::pod_read_unaligned(&bytes);
+            assert_eq!(option.is_some(), Err(invalid_tag()), "tag {tag}");
+            assert_eq!(option.get(), Err(invalid_tag()), "tag {tag}");
+        }
+    }
+}
+
+#[test]
+fn clear_zeroes_the_value() {
+    let mut option = PodOption::some(LeU64::new(u64::MAX));
+    option.clear();
+    assert_eq!(bytemuck::bytes_of(&option), [0; 9]);
+}
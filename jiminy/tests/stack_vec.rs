@@ -0,0 +1,84 @@
+//! `StackVec` fails instead of overflowing, and drops each value it holds exactly once
+
+use jiminy::collections::StackVec;
+use jiminy::error::JiminyError;
+use pinocchio::program_error::ProgramError;
+use std::rc::Rc;
+
+// `N` clones of `value` pushed, so its strong count tells how many are alive
+fn filled<const N: usize>(value: &Rc<()>) -> StackVec<Rc<()>, N> {
+    let mut values = StackVec::new();
+    for _ in 0..N {
+        values.push(Rc::clone(value)).unwrap();
+    }
+    values
+}
+
+#[test]
+fn push_past_the_capacity_fails() {
+    let mut values = StackVec::<u8, 2>::new();
+    values.push(1).unwrap();
+    values.push(2).unwrap();
+    assert!(values.is_full());
+    assert_eq!(
+        values.push(3),
+        Err(ProgramError::from(JiminyError::CapacityExceeded))
+    );
+    assert_eq!(values.try_push(3), Err(3));
+    assert_eq!(*values, [1, 2]);
+}
+
+#[test]
+fn rejected_values_are_handed_back_not_dropped() {
+    let value = Rc::new(());
+    let mut values = filled::<2>(&value);
+    let extra = Rc::clone(&value);
+    assert_eq!(Rc::strong_count(&value), 4);
+    let extra = values.try_push(extra).unwrap_err();
+    assert_eq!(Rc::strong_count(&value), 4);
+    drop(extra);
+    // `push` drops the value it couldn't take
+    assert!(values.push(Rc::clone(&value)).is_err());
+    assert_eq!(Rc::strong_count(&value), 3);
+}
+
+#[test]
+fn pop_moves_the_last_value_out() {
+    let value = Rc::new(());
+    let mut values = filled::<3>(&value);
+    let last = values.pop().unwrap();
+    assert_eq!((values.len(), Rc::strong_count(&value)), (2, 4));
+    drop(last);
+    assert_eq!(Rc::strong_count(&value), 3);
+
+    values.pop();
+    values.pop();
+    assert_eq!(values.pop(), None);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
+
+#[test]
+fn clear_drops_every_value() {
+    let value = Rc::new(());
+    let mut values = filled::<3>(&value);
+    values.clear();
+    assert!(values.is_empty());
+    assert_eq!(Rc::strong_count(&value), 1);
+
+    // Reusable after clearing
+    values.push(Rc::clone(&value)).unwrap();
+    assert_eq!(Rc::strong_count(&value), 2);
+}
+
+#[test]
+fn drop_drops_only_the_values_pushed() {
+    let value = Rc::new(());
+    let mut values = StackVec::<Rc<()>, 4>::new();
+    values.push(Rc::clone(&value)).unwrap();
+    values.push(Rc::clone(&value)).unwrap();
+    drop(values);
+    assert_eq!(Rc::strong_count(&value), 1);
+
+    drop(filled::<4>(&value));
+    assert_eq!(Rc::strong_count(&value), 1);
+}
@@ -0,0 +1,134 @@
+//! `testing::Trace` rebuilds the CPI tree from a backend's logs and inner instructions
+
+use jiminy::testing::{coverage_report, execute, Execution, InnerInstruction, Trace};
+use pinocchio::pubkey::Pubkey;
+
+const PROGRAM: Pubkey = [7; 32];
+const PROGRAM_BS58: &str = "US517G5965aydkZ46HS38QLi7UQiSojurfbQfKCELFx";
+const SYSTEM_BS58: &str = "11111111111111111111111111111111";
+const TOKEN_BS58: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+
+fn logs(lines: &[&str]) -> Vec<String> {
+    lines.iter().map(|line| line.to_string()).collect()
+}
+
+fn inner(stack_height: u8, program_id: Pubkey, data: &[u8]) -> InnerInstruction {
+    InnerInstruction {
+        stack_height,
+        program_id,
+        accounts: vec![[stack_height; 32]],
+        data: data.to_vec(),
+    }
+}
+
+// Two token transfers, then a system call whose own token CPI fails
+fn failed_execution() -> Execution {
+    Execution {
+        result: Err("custom program error: 0x1".into()),
+        logs: logs(&[
+            &format!("Program {PROGRAM_BS58} invoke [1]"),
+            "Program log: Instruction: Redeem",
+            &format!("Program {TOKEN_BS58} invoke [2]"),
+            &format!("Program {TOKEN_BS58} success"),
+            &format!("Program {TOKEN_BS58} invoke [2]"),
+            &format!("Program {TOKEN_BS58} success"),
+            &format!("Program {SYSTEM_BS58} invoke [2]"),
+            &format!("Program {TOKEN_BS58} invoke [3]"),
+            &format!("Program {TOKEN_BS58} failed: custom program error: 0x1"),
+            &format!("Program {SYSTEM_BS58} failed: custom program error: 0x1"),
+            &format!("Program {PROGRAM_BS58} failed: custom program error: 0x1"),
+        ]),
+        inner_instructions: vec![
+            inner(2, pinocchio_token::ID, &[3, 1]),
+            inner(2, pinocchio_token::ID, &[3, 2]),
+            inner(2, pinocchio_system::ID, &[0]),
+            inner(3, pinocchio_token::ID, &[7]),
+        ],
+    }
+}
+
+#[test]
+fn logs_give_the_tree_and_inner_instructions_the_data() {
+    let trace = Trace::new(PROGRAM, &[[1; 32]], &[5], &failed_execution());
+    assert_eq!(trace.root.data, [5]);
+    assert_eq!(trace.root.children.len(), 3);
+    let system = &trace.root.children[2];
+    assert_eq!(system.program_id, pinocchio_system::ID);
+    assert_eq!(system.children.len(), 1);
+    assert_eq!(system.children[0].data, [7]);
+    assert_eq!(system.children[0].accounts, [[3; 32]]);
+
+    let transfers = trace.cpis_to(&pinocchio_token::ID, 3);
+    let data: Vec<&[u8]> = transfers.iter().map(|node| node.data.as_slice()).collect();
+    assert_eq!(data, [[3, 1], [3, 2]]);
+    assert_eq!(trace.cpis().len(), 4);
+}
+
+#[test]
+fn failure_is_the_deepest_failed_call() {
+    let trace = Trace::new(PROGRAM, &[], &[5], &failed_execution());
+    assert!(!trace.is_ok());
+    let failure = trace.failure().unwrap();
+    assert_eq!(failure.program_id, pinocchio_token::ID);
+    assert_eq!(failure.data, [7]);
+    assert_eq!(failure.error.as_deref(), Some("custom program error: 0x1"));
+    assert!(trace.root.children[0].error.is_none());
+}
+
+#[test]
+fn tree_renders_one_level_per_call_depth() {
+    let trace = Trace::new(PROGRAM, &[[1; 32]], &[5], &failed_execution());
+    let rendered = trace.to_string();
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines.len(), 5);
+    assert_eq!(
+        lines[0],
+        format!("{PROGRAM_BS58} [5], 1 accounts: failed, custom program error: 0x1")
+    );
+    assert_eq!(lines[1], format!("  {TOKEN_BS58} [3], 1 accounts: ok"));
+    assert_eq!(
+        lines[4],
+        format!("    {TOKEN_BS58} [7], 1 accounts: failed, custom program error: 0x1")
+    );
+}
+
+#[test]
+fn without_logs_the_stack_heights_give_the_tree() {
+    let mut execution = failed_execution();
+    execution.logs.clear();
+    let trace = Trace::new(PROGRAM, &[], &[5], &execution);
+    assert_eq!(trace.root.children.len(), 3);
+    assert_eq!(trace.root.children[2].children[0].data, [7]);
+    // Only the top-level result is known
+    assert_eq!(trace.failure().unwrap().program_id, PROGRAM);
+    assert!(trace.cpis().iter().all(|node| node.error.is_none()));
+}
+
+#[test]
+fn calls_cut_off_by_the_end_of_the_logs_did_not_finish() {
+    let execution = Execution {
+        result: Err("exceeded CUs meter".into()),
+        logs: logs(&[
+            &format!("Program {PROGRAM_BS58} invoke [1]"),
+            &format!("Program {TOKEN_BS58} invoke [2]"),
+        ]),
+        inner_instructions: vec![inner(2, pinocchio_token::ID, &[3])],
+    };
+    let trace = Trace::new(PROGRAM, &[], &[5], &execution);
+    assert_eq!(
+        trace.failure().unwrap().error.as_deref(),
+        Some("did not finish")
+    );
+    // The top-level call has the backend's result
+    assert_eq!(trace.root.error.as_deref(), Some("exceeded CUs meter"));
+}
+
+#[test]
+fn execute_traces_the_run_and_records_coverage() {
+    let trace = execute(PROGRAM, &[], &[42], || Ok(()).into());
+    trace.assert_ok();
+    assert!(trace.cpis().is_empty());
+
+    let report = coverage_report(&[(42, "Traced")]);
+    report.assert_complete();
+}
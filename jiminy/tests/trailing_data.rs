@@ -0,0 +1,87 @@
+//! Bytes past the declared data fail with `TrailingInstructionData`, unless the
+//! instruction declares `strict_data: false`
+
+use jiminy::error::JiminyError;
+use jiminy::testing::{stubs, HostAccount, HostInput};
+use pinocchio::program_error::ProgramError;
+
+mod strict {
+    use jiminy::define_instruction_with_metadata;
+
+    define_instruction_with_metadata!(
+        discriminant: 0,
+        SetValue,
+        accounts: {
+            owner: signer, desc: "Owner setting the value",
+        },
+        data: {
+            value: [u8; 8],
+        },
+        process: {
+            jiminy::syscalls::set_return_data(&value);
+            Ok(())
+        }
+    );
+}
+
+mod lenient {
+    use jiminy::define_instruction_with_metadata;
+
+    define_instruction_with_metadata!(
+        discriminant: 0,
+        SetValue,
+        accounts: {
+            owner: signer, desc: "Owner setting the value",
+        },
+        data: {
+            value: [u8; 8],
+        },
+        strict_data: false,
+        process: {
+            jiminy::syscalls::set_return_data(&value);
+            Ok(())
+        }
+    );
+}
+
+fn owner() -> HostAccount {
+    HostAccount::new([1; 32], pinocchio_system::ID, 1, &[]).signer()
+}
+
+// 7 in the declared field, then 3 stray bytes
+fn data() -> Vec<u8> {
+    [&7u64.to_le_bytes()[..], &[0, 0, 0]].concat()
+}
+
+#[test]
+fn strict_instruction_rejects_trailing_bytes() {
+    stubs::reset();
+    let input = HostInput::new(&[owner()]);
+    let data = data();
+    let result = strict::SetValueInstruction::try_from((input.accounts(), &data[..]))
+        .and_then(|instruction| instruction.process());
+    assert_eq!(result, Err(JiminyError::TrailingInstructionData.into()));
+    assert!(stubs::return_data().is_empty());
+
+    // Exactly the declared bytes run
+    strict::SetValueInstruction::try_from((input.accounts(), &data[..8]))
+        .and_then(|instruction| instruction.process())
+        .unwrap();
+    assert_eq!(stubs::return_data(), 7u64.to_le_bytes());
+}
+
+#[test]
+fn lenient_instruction_ignores_trailing_bytes() {
+    stubs::reset();
+    let input = HostInput::new(&[owner()]);
+    let data = data();
+    lenient::SetValueInstruction::try_from((input.accounts(), &data[..]))
+        .and_then(|instruction| instruction.process())
+        .unwrap();
+    assert_eq!(stubs::return_data(), 7u64.to_le_bytes());
+
+    // Short data still fails
+    let result = lenient::SetValueInstruction::try_from((input.accounts(), &data[..7]))
+        .and_then(|instruction| instruction.process());
+    assert_eq!(result, Err(ProgramError::InvalidInstructionData));
+}
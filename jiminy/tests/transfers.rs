@@ -0,0 +1,104 @@
+//! `transfer_tokens!` and `transfer_sol!` skip the CPI for a zero amount, and
+//! the stubbed CPIs move what the real ones would
+
+use jiminy::testing::{stubs, HostAccount, HostInput};
+use jiminy::{transfer_sol, transfer_tokens};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+fn wallet(key: u8, lamports: u64) -> HostAccount {
+    HostAccount::new([key; 32], pinocchio_system::ID, lamports, &[])
+        .signer()
+        .writable()
+}
+
+// An SPL token account holding `amount`: mint, owner, amount, then the rest
+fn token_account(key: u8, amount: u64) -> HostAccount {
+    let mut data = [0; 165];
+    data[64..72].copy_from_slice(&amount.to_le_bytes());
+    HostAccount::new([key; 32], pinocchio_token::ID, 1, &data).writable()
+}
+
+fn amount(account: &HostAccount) -> u64 {
+    u64::from_le_bytes(account.data[64..72].try_into().unwrap())
+}
+
+fn send_tokens(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    transfer_tokens!(&accounts[0], &accounts[1], &accounts[0], amount);
+    Ok(())
+}
+
+fn send_signed_tokens(accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let bump = [255];
+    transfer_tokens!(&accounts[0], &accounts[1], &accounts[0], amount,
+        seeds: [b"vault", &bump]);
+    Ok(())
+}
+
+fn send_sol(accounts: &[AccountInfo], lamports: u64) -> ProgramResult {
+    transfer_sol!(&accounts[0], &accounts[1], lamports);
+    Ok(())
+}
+
+#[test]
+fn zero_amounts_make_no_cpi() {
+    stubs::reset();
+    let mut accounts = [wallet(1, 100), wallet(2, 0)];
+    let input = HostInput::new(&accounts);
+    send_sol(input.accounts(), 0).unwrap();
+    input.write_back(&mut accounts);
+    assert_eq!((accounts[0].lamports, accounts[1].lamports), (100, 0));
+
+    let mut accounts = [token_account(1, 100), token_account(2, 0)];
+    let input = HostInput::new(&accounts);
+    send_tokens(input.accounts(), 0).unwrap();
+    send_signed_tokens(input.accounts(), 0).unwrap();
+    input.write_back(&mut accounts);
+    assert_eq!((amount(&accounts[0]), amount(&accounts[1])), (100, 0));
+    assert!(stubs::cpi_calls().is_empty());
+}
+
+#[test]
+fn other_amounts_make_the_cpi() {
+    stubs::reset();
+    let mut tokens = [token_account(1, 100), token_account(2, 0)];
+    let input = HostInput::new(&tokens);
+    send_tokens(input.accounts(), 1).unwrap();
+    send_signed_tokens(input.accounts(), 2).unwrap();
+    // The stubbed token transfers move the amounts
+    input.write_back(&mut tokens);
+    assert_eq!((amount(&tokens[0]), amount(&tokens[1])), (97, 3));
+
+    let mut accounts = [wallet(1, 100), wallet(2, 0)];
+    let input = HostInput::new(&accounts);
+    send_sol(input.accounts(), 40).unwrap();
+    assert_eq!(
+        stubs::cpi_calls(),
+        ["token::transfer", "token::transfer", "system::transfer"]
+    );
+
+    // The stubbed system transfer moves the lamports
+    input.write_back(&mut accounts);
+    assert_eq!((accounts[0].lamports, accounts[1].lamports), (60, 40));
+}
+
+#[test]
+fn token_transfer_past_the_balance_fails_like_the_token_program() {
+    stubs::reset();
+    let mut accounts = [token_account(1, 5), token_account(2, u64::MAX)];
+    let input = HostInput::new(&accounts);
+    assert_eq!(send_tokens(input.accounts(), 6), Err(ProgramError::Custom(1)));
+    assert_eq!(send_tokens(input.accounts(), 1), Err(ProgramError::Custom(14)));
+    input.write_back(&mut accounts);
+    assert_eq!((amount(&accounts[0]), amount(&accounts[1])), (5, u64::MAX));
+}
+
+#[test]
+fn token_transfer_from_an_account_without_token_data_fails() {
+    stubs::reset();
+    let accounts = [wallet(1, 100), token_account(2, 0)];
+    let input = HostInput::new(&accounts);
+    assert_eq!(
+        send_tokens(input.accounts(), 1),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
@@ -0,0 +1,15 @@
+// 16 seeds plus the bump the cache searches for is one more than Solana allows
+use jiminy::{assert_pda_cached, pda::PdaCache};
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+pinocchio_pubkey::declare_id!("Cntrt7BXEtNAnSo9ecGs9n9KkHGDF73Shr3xqFvsvQTJ");
+
+pub fn check(account: &AccountInfo) -> ProgramResult {
+    let mut pdas = PdaCache::<1>::new();
+    let s: &[u8] = b"s";
+    assert_pda_cached!(pdas, account, seeds: [s, s, s, s, s, s, s, s, s, s, s, s, s, s, s, s],
+        error: ProgramError::InvalidSeeds);
+    Ok(())
+}
+
+fn main() {}
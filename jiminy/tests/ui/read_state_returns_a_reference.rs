@@ -0,0 +1,16 @@
+// `read_state!` copies values out, a reference into the state doesn't compile
+use bytemuck::{Pod, Zeroable};
+use jiminy::read_state;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError};
+
+#[repr(C)]
+#[derive(Clone, Copy, Pod, Zeroable)]
+struct Tally {
+    count: [u8; 8],
+}
+
+fn count(account: &AccountInfo) -> Result<&[u8; 8], ProgramError> {
+    Ok(read_state!(account, Tally, |tally| &tally.count))
+}
+
+fn main() {}
@@ -0,0 +1,14 @@
+// 16 seeds plus the bump is one more than Solana signs with
+use jiminy::vault_withdraw_sol;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+pinocchio_pubkey::declare_id!("Cntrt7BXEtNAnSo9ecGs9n9KkHGDF73Shr3xqFvsvQTJ");
+
+pub fn withdraw(vault: &AccountInfo, to: &AccountInfo) -> ProgramResult {
+    let s: &[u8] = b"s";
+    vault_withdraw_sol!(vault, seeds: [s, s, s, s, s, s, s, s, s, s, s, s, s, s, s, s, s],
+        bump: 255, error: ProgramError::InvalidSeeds, to: to, amount: 1);
+    Ok(())
+}
+
+fn main() {}
@@ -0,0 +1,13 @@
+// A 33-byte seed literal, one over the limit
+use jiminy::vault_withdraw_tokens;
+use pinocchio::{account_info::AccountInfo, program_error::ProgramError, ProgramResult};
+
+pinocchio_pubkey::declare_id!("Cntrt7BXEtNAnSo9ecGs9n9KkHGDF73Shr3xqFvsvQTJ");
+
+pub fn withdraw(vault: &AccountInfo, from: &AccountInfo, to: &AccountInfo) -> ProgramResult {
+    vault_withdraw_tokens!(vault, seeds: [b"a-vault-seed-that-is-33-byte-long"],
+        bump: 255, error: ProgramError::InvalidSeeds, from: from, to: to, amount: 1);
+    Ok(())
+}
+
+fn main() {}
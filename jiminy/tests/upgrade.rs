@@ -0,0 +1,99 @@
+//! `check_upgrade_authority` against handcrafted programdata accounts
+
+use jiminy::testing::{stubs, HostAccount, HostInput};
+use jiminy::upgrade::{check_upgrade_authority, programdata_authority, BPF_LOADER_UPGRADEABLE_ID};
+use pinocchio::{program_error::ProgramError, pubkey::Pubkey, ProgramResult};
+
+const PROGRAM: Pubkey = [7; 32];
+const AUTHORITY: Pubkey = [8; 32];
+
+// `UpgradeableLoaderState::ProgramData`: u32 tag, u64 slot, `Option<Pubkey>`,
+// then the program's bytes
+fn programdata(tag: u32, authority: Option<Pubkey>) -> Vec<u8> {
+    let mut data = tag.to_le_bytes().to_vec();
+    data.extend_from_slice(&42u64.to_le_bytes());
+    match authority {
+        Some(authority) => {
+            data.push(1);
+            data.extend_from_slice(&authority);
+        }
+        None => data.extend_from_slice(&[0; 33]),
+    }
+    data.extend_from_slice(&[0xaa; 16]);
+    data
+}
+
+fn programdata_key() -> Pubkey {
+    stubs::find_program_address(&[PROGRAM.as_ref()], &BPF_LOADER_UPGRADEABLE_ID).0
+}
+
+// The program, its programdata holding `data` under `key`, and `signer`
+fn check(key: Pubkey, data: Vec<u8>, signer: Pubkey) -> ProgramResult {
+    let input = HostInput::new(&[
+        HostAccount::new(PROGRAM, BPF_LOADER_UPGRADEABLE_ID, 1, &[]),
+        HostAccount::new(key, BPF_LOADER_UPGRADEABLE_ID, 1, &data),
+        HostAccount::new(signer, pinocchio_system::ID, 1, &[]).signer(),
+    ]);
+    let [program, programdata, authority] = input.accounts() else {
+        unreachable!()
+    };
+    check_upgrade_authority(program, programdata, authority, &PROGRAM)
+}
+
+#[test]
+fn matching_authority_passes() {
+    check(programdata_key(), programdata(3, Some(AUTHORITY)), AUTHORITY).unwrap();
+}
+
+#[test]
+fn other_signer_fails_with_incorrect_authority() {
+    assert_eq!(
+        check(programdata_key(), programdata(3, Some(AUTHORITY)), [9; 32]),
+        Err(ProgramError::IncorrectAuthority)
+    );
+}
+
+#[test]
+fn frozen_program_fails_with_immutable() {
+    assert_eq!(
+        check(programdata_key(), programdata(3, None), AUTHORITY),
+        Err(ProgramError::Immutable)
+    );
+}
+
+#[test]
+fn programdata_of_another_key_is_rejected() {
+    assert_eq!(
+        check([9; 32], programdata(3, Some(AUTHORITY)), AUTHORITY),
+        Err(ProgramError::InvalidAccountData)
+    );
+}
+
+#[test]
+fn other_loader_states_are_rejected() {
+    // 2 is `UpgradeableLoaderState::Program`, 1 a buffer
+    for tag in [1, 2] {
+        assert_eq!(
+            check(programdata_key(), programdata(tag, Some(AUTHORITY)), AUTHORITY),
+            Err(ProgramError::InvalidAccountData),
+            "tag {tag}"
+        );
+    }
+}
+
+#[test]
+fn programdata_authority_reads_the_option() {
+    // Distinct keys, a repeated one would be passed as the same account
+    let input = HostInput::new(&[
+        HostAccount::new([1; 32], BPF_LOADER_UPGRADEABLE_ID, 1, &programdata(3, Some(AUTHORITY))),
+        HostAccount::new([2; 32], BPF_LOADER_UPGRADEABLE_ID, 1, &programdata(3, None)),
+        HostAccount::new([3; 32], pinocchio_system::ID, 1, &programdata(3, None)),
+    ]);
+    let accounts = input.accounts();
+    assert_eq!(programdata_authority(&accounts[0]), Ok(Some(AUTHORITY)));
+    assert_eq!(programdata_authority(&accounts[1]), Ok(None));
+    assert_eq!(
+        programdata_authority(&accounts[2]),
+        Err(ProgramError::IncorrectProgramId)
+    );
+}
@@ -0,0 +1,135 @@
+//! `close_account!` and `create_pda!` refuse a read-only account in a role they
+//! write to, naming the role in the log, unless `unchecked-writable` compiles the
+//! checks out. Run both sides with and without `--features unchecked-writable`.
+
+use jiminy::testing::{stubs, HostAccount, HostInput};
+use jiminy::{close_account, create_pda};
+use pinocchio::{account_info::AccountInfo, pubkey::Pubkey, ProgramResult};
+
+// What `create_pda!` assigns new accounts to
+const ID: Pubkey = [7; 32];
+
+fn program_account(key: u8, writable: bool) -> HostAccount {
+    let account = HostAccount::new([key; 32], ID, 10, &[1; 16]);
+    if writable {
+        account.writable()
+    } else {
+        account
+    }
+}
+
+fn payer(writable: bool) -> HostAccount {
+    let payer = HostAccount::new([1; 32], pinocchio_system::ID, 1_000_000_000, &[]).signer();
+    if writable {
+        payer.writable()
+    } else {
+        payer
+    }
+}
+
+fn new_account(writable: bool) -> HostAccount {
+    let account = HostAccount::new([2; 32], pinocchio_system::ID, 0, &[]);
+    if writable {
+        account.writable()
+    } else {
+        account
+    }
+}
+
+fn close(accounts: &[AccountInfo]) -> ProgramResult {
+    close_account!(&accounts[0], &accounts[1]);
+    Ok(())
+}
+
+fn create(accounts: &[AccountInfo]) -> ProgramResult {
+    create_pda!(payer: &accounts[0], to: &accounts[1], space: 8, seeds: [b"seed"], bump: 255);
+    Ok(())
+}
+
+// Run `f` on `accounts`, writing back what it left
+fn run(f: fn(&[AccountInfo]) -> ProgramResult, accounts: &mut [HostAccount]) -> ProgramResult {
+    stubs::reset();
+    let input = HostInput::new(accounts);
+    let result = f(input.accounts());
+    input.write_back(accounts);
+    result
+}
+
+#[cfg(not(feature = "unchecked-writable"))]
+mod checked {
+    use super::*;
+    use jiminy::error::JiminyError;
+
+    // `f` fails with `AccountNotWritable` naming `role`, changing nothing
+    fn assert_not_writable(
+        f: fn(&[AccountInfo]) -> ProgramResult,
+        accounts: &mut [HostAccount],
+        role: &str,
+    ) {
+        let before = accounts.to_vec();
+        assert_eq!(run(f, accounts), Err(JiminyError::AccountNotWritable.into()));
+        assert_eq!(stubs::logs(), [format!("The {role} account is not writable")]);
+        assert!(stubs::cpi_calls().is_empty());
+        assert_eq!(accounts, &before[..]);
+    }
+
+    #[test]
+    fn close_into_a_read_only_receiver_fails() {
+        let mut accounts = [program_account(1, true), program_account(2, false)];
+        assert_not_writable(close, &mut accounts, "receiver");
+    }
+
+    #[test]
+    fn closing_a_read_only_account_fails() {
+        let mut accounts = [program_account(1, false), program_account(2, true)];
+        assert_not_writable(close, &mut accounts, "target");
+    }
+
+    #[test]
+    fn create_with_a_read_only_payer_fails() {
+        let mut accounts = [payer(false), new_account(true)];
+        assert_not_writable(create, &mut accounts, "payer");
+    }
+
+    #[test]
+    fn create_into_a_read_only_target_fails() {
+        let mut accounts = [payer(true), new_account(false)];
+        assert_not_writable(create, &mut accounts, "target");
+    }
+
+    #[test]
+    fn writable_accounts_pass() {
+        let mut accounts = [program_account(1, true), program_account(2, true)];
+        run(close, &mut accounts).unwrap();
+        assert_eq!((accounts[0].lamports, accounts[1].lamports), (0, 20));
+
+        let mut accounts = [payer(true), new_account(true)];
+        run(create, &mut accounts).unwrap();
+        assert_eq!(accounts[1].owner, ID);
+        assert!(stubs::logs().is_empty());
+    }
+}
+
+// With the checks compiled out the macros go ahead, the runtime would reject the
+// transaction afterwards
+#[cfg(feature = "unchecked-writable")]
+mod unchecked {
+    use super::*;
+
+    #[test]
+    fn close_into_a_read_only_receiver_goes_ahead() {
+        let mut accounts = [program_account(1, false), program_account(2, false)];
+        run(close, &mut accounts).unwrap();
+        assert_eq!((accounts[0].lamports, accounts[1].lamports), (0, 20));
+        assert!(stubs::logs().is_empty());
+    }
+
+    #[test]
+    fn create_with_read_only_accounts_goes_ahead() {
+        let mut accounts = [payer(false), new_account(false)];
+        run(create, &mut accounts).unwrap();
+        assert_eq!(stubs::cpi_calls(), ["system::create_account"]);
+        assert_eq!(accounts[1].owner, ID);
+        assert!(stubs::logs().is_empty());
+    }
+}